@@ -5,28 +5,49 @@
 
 // -------------------------------------------------------------------------------------------------
 
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::os::unix::io;
 use nix::{self, Errno};
-use nix::fcntl::{open, OFlag};
+use nix::fcntl::{open, OFlag, O_CLOEXEC, O_RDWR};
 use nix::sys::stat::{Mode, stat};
 
 use dharma;
-use qualia::{Context, Illusion, Ipc};
+use qualia::{Action, Command, Context, Illusion, Ipc, perceptron, Perceptron};
 
+use device_tracker::DeviceTracker;
 use evdev;
 use udev;
 use output_collector::OutputCollector;
+use sensor_proxy::SensorProxy;
+use media_keys::MediaKeys;
+use session::SessionMonitor;
 use input_gateway::InputGateway;
 use drivers::InputDriver;
 
 // -------------------------------------------------------------------------------------------------
 
+// `DRM_IOCTL_SET_MASTER` and `DRM_IOCTL_DROP_MASTER`.
+ioctl!(none drm_set_master with b'd', 0x1e);
+ioctl!(none drm_drop_master with b'd', 0x1f);
+
+// -------------------------------------------------------------------------------------------------
+
 /// Device Manager manages searching input and output devices and monitoring them.
 pub struct DeviceManager<'a> {
     udev: udev::Udev<'a>,
     ipc: Ipc,
     output_collector: OutputCollector,
+    dispatcher: dharma::Dispatcher,
+    tracker: DeviceTracker,
+    media_keys: MediaKeys,
+    context: Context,
+
+    /// Device nodes and numbers of input devices handled by drivers with given IDs.
+    drivers: HashMap<dharma::EventHandlerId, (PathBuf, u64)>,
+
+    /// File descriptors and numbers of opened DRM devices.
+    outputs: Vec<(io::RawFd, u64)>,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -39,51 +60,105 @@ impl<'a> DeviceManager<'a> {
             ipc: Ipc::new(),
             output_collector: OutputCollector::new(context.get_dispatcher().clone(),
                                                    context.get_signaler().clone()),
+            dispatcher: context.get_dispatcher().clone(),
+            tracker: DeviceTracker::new(context.get_coordinator().clone()),
+            media_keys: MediaKeys::new(context.get_config().clone()),
+            context: context,
+            drivers: HashMap::new(),
+            outputs: Vec::new(),
         };
 
         // Initialize IPC
         mine.initialize_ipc();
 
         // Initialize input devices
-        mine.initialize_input_devices(&mut context);
+        mine.initialize_input_devices();
 
         // Initialize output devices
         mine.initialize_output_devices();
 
         // Initialize device monitor
-        mine.initialize_device_monitor(&mut context);
+        mine.initialize_device_monitor();
+
+        // Initialize accelerometer
+        mine.initialize_sensor_proxy();
 
         mine
     }
 
-    /// Try to open device. If we have insufficient permissions ask `logind` to do it for us.
-    fn open_restricted(&self,
-                       path: &Path,
-                       oflag: OFlag,
-                       mode: Mode)
-                       -> Result<io::RawFd, Illusion> {
-        match open(path, oflag, mode) {
-            Ok(fd) => Ok(fd),
-            Err(nix::Error::Sys(errno)) => {
-                if (errno == Errno::EPERM) || (errno == Errno::EACCES) {
-                    match stat(path) {
-                        Ok(st) => self.ipc.take_device(st.st_rdev as u64),
-                        _ => Err(Illusion::General(format!("Could not stat file '{:?}'", path))),
-                    }
-                } else {
-                    Err(Illusion::InvalidArgument(errno.desc().to_owned()))
-                }
+    /// Removes driver of unplugged input device and gives the device back to `logind`.
+    pub fn on_device_lost(&mut self, id: dharma::EventHandlerId) {
+        if self.dispatcher.delete_source(id).is_none() {
+            log_warn2!("Device Manager: no driver with ID {} to remove", id);
+        }
+        if let Some((_, rdev)) = self.drivers.remove(&id) {
+            if let Err(err) = self.ipc.release_device(rdev) {
+                log_warn2!("Device Manager: failed to release device: {}", err);
+            }
+        }
+    }
+
+    /// Handles change of session state.
+    ///
+    /// When session becomes inactive (e.g. user switched to other virtual terminal) input devices
+    /// are closed and DRM master is dropped. When it becomes active again DRM master is set and
+    /// input devices are searched for again. Masters of devices taken from `logind` are managed by
+    /// `logind` itself. Other modules are informed with `VtReleased` and `VtAcquired` signals.
+    pub fn on_session_state_changed(&mut self, active: bool) {
+        if active {
+            self.set_drm_master(true);
+            self.initialize_input_devices();
+            self.context.get_signaler().emit(perceptron::VT_ACQUIRED, Perceptron::VtAcquired);
+        } else {
+            let ids: Vec<dharma::EventHandlerId> = self.drivers.keys().cloned().collect();
+            for id in ids {
+                self.on_device_lost(id);
+            }
+            self.set_drm_master(false);
+            self.context.get_signaler().emit(perceptron::VT_RELEASED, Perceptron::VtReleased);
+        }
+    }
+
+    /// Handles command. Only switching virtual terminal and media keys are handled by Device
+    /// Manager.
+    pub fn on_command(&mut self, command: Command) {
+        if command.action == Action::Terminal {
+            log_info1!("Device Manager: switching to virtual terminal {}", command.magnitude);
+            if let Err(err) = self.ipc.switch_to(command.magnitude as u32) {
+                log_warn1!("Device Manager: failed to switch virtual terminal: {}", err);
             }
-            Err(nix::Error::InvalidPath) => {
-                Err(Illusion::InvalidArgument(format!("Path '{:?}' does not exist!", path)))
+        } else if command.action == Action::Media {
+            self.media_keys.perform(&command.string);
+        }
+    }
+
+    /// Sets or drops DRM master for all output devices not taken from `logind`.
+    fn set_drm_master(&self, master: bool) {
+        for &(fd, rdev) in self.outputs.iter() {
+            if !self.ipc.is_device_taken(rdev) {
+                let result = unsafe {
+                    if master { drm_set_master(fd) } else { drm_drop_master(fd) }
+                };
+                if let Err(err) = result {
+                    log_warn1!("Device Manager: failed to change DRM master: {:?}", err);
+                }
             }
         }
     }
 
-    /// Initialize connection to `logind`.
+    /// Initialize connection to `logind` and start handling signals of the session.
     fn initialize_ipc(&mut self) {
         match self.ipc.initialize() {
-            Ok(_) => (),
+            Ok(_) => {
+                match SessionMonitor::new(self.ipc.clone(), self.context.get_signaler().clone()) {
+                    Ok(monitor) => {
+                        self.context.add_event_handler(Box::new(monitor), dharma::event_kind::READ);
+                    }
+                    Err(err) => {
+                        log_warn1!("Device Manager: failed to monitor session: {}", err);
+                    }
+                }
+            }
             Err(err) => {
                 log_warn1!("Failed to initialize IPC ({:?}). \
                            This may cause problems with access to devices.",
@@ -93,54 +168,126 @@ impl<'a> DeviceManager<'a> {
     }
 
     /// Iterate over input devices to find usable ones and initialize event handlers for them.
-    fn initialize_input_devices(&mut self, context: &mut Context) {
-        self.udev.iterate_event_devices(|devnode, devkind, _| {
-            let config = context.get_config().get_input_config();
-            let gateway = InputGateway::new(config,
-                                            context.get_input_manager().clone(),
-                                            context.get_signaler().clone());
-            let r = evdev::Evdev::initialize_device(devnode,
-                                                    devkind,
-                                                    config,
-                                                    gateway,
-                                                    |path, oflag, mode| {
-                                                        self.open_restricted(path, oflag, mode)
-                                                    });
-            match r {
-                Ok(driver) => {
-                    context.add_event_handler(driver, dharma::event_kind::READ);
+    /// Devices which already have drivers are skipped.
+    fn initialize_input_devices(&mut self) {
+        let mut tracker = self.tracker.clone();
+        let input_manager = self.context.get_input_manager().clone();
+        let signaler = self.context.get_signaler().clone();
+        let mut new_drivers = Vec::new();
+        {
+            let context = &self.context;
+            let ipc = &self.ipc;
+            let drivers = &self.drivers;
+            self.udev.iterate_event_devices(|devnode, devkind, device| {
+                if drivers.values().any(|&(ref path, _)| path == devnode) {
+                    return;
                 }
-                Err(err) => {
-                    log_error!("Could not initialize input devices: {}", err);
+
+                let device_id = udev::get_device_id(device);
+                let config = context.get_config().get_input_config_for_device(&device_id);
+                let gateway = InputGateway::new(context.get_config(),
+                                                config,
+                                                input_manager.clone(),
+                                                signaler.clone(),
+                                                &device_id);
+                let seat = gateway.get_seat();
+                let r = evdev::Evdev::initialize_device(devnode,
+                                                        devkind,
+                                                        config,
+                                                        gateway,
+                                                        tracker.clone(),
+                                                        |path, oflag, mode| {
+                                                            open_restricted(ipc, path, oflag, mode)
+                                                        });
+                match r {
+                    Ok(driver) => {
+                        tracker.add(seat, devkind);
+                        let rdev = stat(devnode).map(|st| st.st_rdev as u64).unwrap_or(0);
+                        new_drivers.push((driver, devnode.to_owned(), rdev));
+                    }
+                    Err(err) => {
+                        log_error!("Could not initialize input devices: {}", err);
+                    }
                 }
-            }
-        });
+            });
+        }
+
+        for (driver, devnode, rdev) in new_drivers {
+            let id = self.dispatcher.add_source(driver, dharma::event_kind::READ);
+            self.drivers.insert(id, (devnode, rdev));
+        }
     }
 
     /// Find and initialize outputs.
     fn initialize_output_devices(&mut self) {
         let oc = &mut self.output_collector;
+        let ipc = &self.ipc;
+        let outputs = &mut self.outputs;
         self.udev.iterate_drm_devices(|devnode, _| {
             // FIXME: Can not do:
             // self.output_collector.scan_device(devnode);
             // Is it compiler bug?
-            if let Err(err) = oc.scan_device(devnode) {
-                log_error!("{}", err);
+            let result = open_restricted(ipc, devnode, O_RDWR, Mode::empty()).and_then(|fd| {
+                let rdev = stat(devnode).map(|st| st.st_rdev as u64).unwrap_or(0);
+                outputs.push((fd, rdev));
+                oc.scan_device(devnode, fd)
+            });
+            if let Err(err) = result {
+                log_error!("Could not open output device {:?}: {}", devnode, err);
             }
         });
     }
 
     /// Initialize device monitoring.
-    fn initialize_device_monitor(&mut self, context: &mut Context) {
+    fn initialize_device_monitor(&mut self) {
         match self.udev.start_device_monitor() {
             Ok(device_monitor) => {
-                context.add_event_handler(Box::new(device_monitor), dharma::event_kind::READ);
+                self.context.add_event_handler(Box::new(device_monitor), dharma::event_kind::READ);
             }
             Err(err) => {
                 log_warn1!("Device Manager: {}", err);
             }
         }
     }
+
+    /// Initialize reading orientation of the device from `iio-sensor-proxy`.
+    fn initialize_sensor_proxy(&mut self) {
+        match SensorProxy::new(self.context.get_signaler().clone()) {
+            Ok(sensor_proxy) => {
+                self.context.add_event_handler(Box::new(sensor_proxy), dharma::event_kind::READ);
+            }
+            Err(err) => {
+                log_info1!("Device Manager: accelerometer not available: {}", err);
+            }
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Try to open device. If we have insufficient permissions ask `logind` to do it for us. Device is
+/// not inherited by restarted compositor.
+fn open_restricted(ipc: &Ipc,
+                   path: &Path,
+                   oflag: OFlag,
+                   mode: Mode)
+                   -> Result<io::RawFd, Illusion> {
+    match open(path, oflag | O_CLOEXEC, mode) {
+        Ok(fd) => Ok(fd),
+        Err(nix::Error::Sys(errno)) => {
+            if (errno == Errno::EPERM) || (errno == Errno::EACCES) {
+                match stat(path) {
+                    Ok(st) => ipc.take_device(st.st_rdev as u64),
+                    _ => Err(Illusion::General(format!("Could not stat file '{:?}'", path))),
+                }
+            } else {
+                Err(Illusion::InvalidArgument(errno.desc().to_owned()))
+            }
+        }
+        Err(nix::Error::InvalidPath) => {
+            Err(Illusion::InvalidArgument(format!("Path '{:?}' does not exist!", path)))
+        }
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
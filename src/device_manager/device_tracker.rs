@@ -0,0 +1,97 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module keeps track of input devices available in seats.
+//!
+//! Capabilities of seat are derived from devices currently present in it: keyboards give
+//! keyboard capability to seat they are assigned to, while mice, touchpads and touchscreens give
+//! pointer or touch capability to the default seat as pointer is shared by all seats.
+
+// -------------------------------------------------------------------------------------------------
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use qualia::{Coordinator, DeviceKind, SeatId};
+use qualia::seat_capability::{self, SeatCapabilities};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Number of present devices of given kind for every seat.
+type DeviceCountMap = HashMap<(SeatId, DeviceKind), usize>;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Counts input devices and updates capabilities of seats in `Coordinator` when devices appear or
+/// disappear. Clones refer to the same state.
+#[derive(Clone)]
+pub struct DeviceTracker {
+    counts: Arc<Mutex<DeviceCountMap>>,
+    coordinator: Coordinator,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl DeviceTracker {
+    /// `DeviceTracker` constructor.
+    pub fn new(coordinator: Coordinator) -> Self {
+        DeviceTracker {
+            counts: Arc::new(Mutex::new(DeviceCountMap::new())),
+            coordinator: coordinator,
+        }
+    }
+
+    /// Registers new device of given kind in given seat.
+    pub fn add(&mut self, seat: SeatId, kind: DeviceKind) {
+        let seat = Self::seat_for_kind(seat, kind);
+        let mut counts = self.counts.lock().unwrap();
+        *counts.entry((seat, kind)).or_insert(0) += 1;
+        Self::update(&mut self.coordinator, &counts, seat);
+    }
+
+    /// Unregisters device of given kind from given seat.
+    pub fn remove(&mut self, seat: SeatId, kind: DeviceKind) {
+        let seat = Self::seat_for_kind(seat, kind);
+        let mut counts = self.counts.lock().unwrap();
+        if let Some(count) = counts.get_mut(&(seat, kind)) {
+            *count = count.saturating_sub(1);
+        }
+        Self::update(&mut self.coordinator, &counts, seat);
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Private methods.
+impl DeviceTracker {
+    /// Returns seat whose capabilities are affected by device of given kind assigned to `seat`.
+    fn seat_for_kind(seat: SeatId, kind: DeviceKind) -> SeatId {
+        match kind {
+            DeviceKind::Keyboard => seat,
+            _ => SeatId::default_seat(),
+        }
+    }
+
+    /// Returns capability given to seat by device of given kind.
+    fn capability_for_kind(kind: DeviceKind) -> SeatCapabilities {
+        match kind {
+            DeviceKind::Keyboard => seat_capability::KEYBOARD,
+            DeviceKind::Mouse | DeviceKind::Touchpad => seat_capability::POINTER,
+            DeviceKind::Touchscreen => seat_capability::TOUCH,
+            DeviceKind::Switch | DeviceKind::Unknown => seat_capability::NONE,
+        }
+    }
+
+    /// Recomputes capabilities of given seat and passes them to `Coordinator`.
+    fn update(coordinator: &mut Coordinator, counts: &DeviceCountMap, seat: SeatId) {
+        let mut capabilities = seat_capability::NONE;
+        for (&(device_seat, kind), &count) in counts.iter() {
+            if (device_seat == seat) && (count > 0) {
+                capabilities.insert(Self::capability_for_kind(kind));
+            }
+        }
+        coordinator.set_seat_capabilities(seat, capabilities);
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -12,6 +12,7 @@ use nix::sys::stat::Mode;
 
 use qualia::{DeviceKind, Illusion, InputConfig};
 
+use device_tracker::DeviceTracker;
 use input_gateway::InputGateway;
 
 // -------------------------------------------------------------------------------------------------
@@ -23,6 +24,7 @@ pub trait InputDriver {
                             device_kind: DeviceKind,
                             config: InputConfig,
                             gateway: InputGateway,
+                            tracker: DeviceTracker,
                             open_restricted: F)
                             -> Result<Box<Self>, Illusion>
         where F: Fn(&Path, OFlag, Mode) -> Result<io::RawFd, Illusion>;
@@ -2,26 +2,135 @@
 // the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
 
 //! Driver for evdev input devices.
+//!
+//! Besides translating events the driver implements touchpad features: tap-to-click, two-finger
+//! and edge scrolling and recognition of gestures.
 
 // -------------------------------------------------------------------------------------------------
 
 use std::mem;
 use std::os::unix::io;
 use std::path::Path;
+use std::time::{Duration, Instant};
 use uinput_sys::{self, input_event};
 
 use nix::fcntl::{self, OFlag};
 use nix::sys::stat::Mode;
-use nix::unistd::read;
+use nix::unistd::{close, read};
 
-use qualia::{DeviceKind, Illusion, InputConfig};
-use dharma::{EventHandler, EventKind, event_kind};
+use qualia::{DeviceKind, Illusion, InputConfig, ScrollMethod, Switch};
+use dharma::{EventHandler, EventHandlerId, EventKind, event_kind};
 
+use device_tracker::DeviceTracker;
 use drivers;
+use gesture_recognizer::GestureRecognizer;
 use input_gateway::InputGateway;
 
 // -------------------------------------------------------------------------------------------------
 
+/// Information about absolute axis as returned by `EVIOCGABS` ioctl.
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+struct AbsInfo {
+    value: i32,
+    minimum: i32,
+    maximum: i32,
+    fuzz: i32,
+    flat: i32,
+    resolution: i32,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl AbsInfo {
+    /// Translates value of the axis to range from 0 to 1.
+    fn normalize(&self, value: i32) -> f32 {
+        if self.maximum > self.minimum {
+            (value - self.minimum) as f32 / (self.maximum - self.minimum) as f32
+        } else {
+            0.0
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+ioctl!(read eviocgabs_x with b'E', 0x40 + uinput_sys::ABS_X; AbsInfo);
+ioctl!(read eviocgabs_y with b'E', 0x40 + uinput_sys::ABS_Y; AbsInfo);
+ioctl!(read eviocgabs_mt_slot with b'E', 0x40 + uinput_sys::ABS_MT_SLOT; AbsInfo);
+ioctl!(read eviocgabs_mt_x with b'E', 0x40 + uinput_sys::ABS_MT_POSITION_X; AbsInfo);
+ioctl!(read eviocgabs_mt_y with b'E', 0x40 + uinput_sys::ABS_MT_POSITION_Y; AbsInfo);
+
+// -------------------------------------------------------------------------------------------------
+
+/// Maximal number of touch points tracked on multi-touch touchscreen.
+const MAX_TOUCH_SLOTS: usize = 10;
+
+/// Maximal duration of tap in milliseconds.
+const TAP_TIMEOUT: u64 = 180;
+
+/// Fraction of touchpad width fingers may move during tap.
+const TAP_MOTION_THRESHOLD: f32 = 0.03;
+
+/// Fraction of touchpad width or height along right and bottom edge used for edge scrolling.
+const EDGE_SCROLL_SIZE: f32 = 0.08;
+
+// -------------------------------------------------------------------------------------------------
+
+/// State of one slot of multi-touch touchscreen. Changes are accumulated until sync.
+#[derive(Clone, Copy, Default)]
+struct TouchSlot {
+    /// Tells if the slot is tracking a touch point.
+    active: bool,
+
+    /// Last absolute position of the touch point.
+    position: (i32, i32),
+
+    /// Tells if touch point appeared since last sync.
+    down: bool,
+
+    /// Tells if touch point disappeared since last sync.
+    up: bool,
+
+    /// Tells if touch point moved since last sync.
+    moved: bool,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Touch of touchpad which may turn out to be a tap.
+#[derive(Clone, Copy)]
+struct Tap {
+    /// Time when touchpad was touched.
+    start: Instant,
+
+    /// Maximal number of fingers touching touchpad during the tap.
+    max_fingers: u32,
+
+    /// Number of fingers touching touchpad since last change of their number.
+    fingers: u32,
+
+    /// Center of fingers when their number last changed.
+    origin: Option<(f32, f32)>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// State of tap-to-click detection.
+#[derive(Clone, Copy)]
+enum TapState {
+    /// Touchpad is not touched.
+    Idle,
+
+    /// Touchpad is touched and the touch may still be a tap.
+    Touching(Tap),
+
+    /// Touchpad is touched but the touch is not a tap.
+    Invalid,
+}
+
+// -------------------------------------------------------------------------------------------------
+
 /// Structure representing evdev input device driver.
 pub struct Evdev {
     fd: io::RawFd,
@@ -29,6 +138,47 @@ pub struct Evdev {
     config: InputConfig,
     gateway: InputGateway,
     pressure: i32,
+
+    /// Register of present devices and ID assigned to this driver by `Dispatcher`.
+    tracker: DeviceTracker,
+    id: EventHandlerId,
+
+    /// Tells if the device was unplugged.
+    lost: bool,
+
+    /// Ranges of absolute axes of touchscreen.
+    abs_x: AbsInfo,
+    abs_y: AbsInfo,
+
+    /// Last absolute position of touchscreen and flag telling if it changed since last sync.
+    abs_position: (i32, i32),
+    abs_changed: bool,
+
+    /// Change of touch state waiting for sync.
+    touch: Option<i32>,
+
+    /// Ranges of absolute axes of multi-touch touchscreen.
+    abs_mt_x: AbsInfo,
+    abs_mt_y: AbsInfo,
+
+    /// Slots of multi-touch touchscreen or touchpad. Empty if device does not support
+    /// multi-touch, in which case touch is reported as pointer events.
+    slots: Vec<TouchSlot>,
+
+    /// Index of slot currently updated by events.
+    slot: usize,
+
+    /// Number of fingers touching touchpad.
+    fingers: u32,
+
+    /// Recognizer of touchpad gestures. `None` if device is not multi-touch touchpad.
+    gestures: Option<GestureRecognizer>,
+
+    /// State of tap-to-click detection.
+    tap: TapState,
+
+    /// Last position of fingers used for scrolling. `None` if touchpad is not scrolling.
+    scroll_position: Option<(f32, f32)>,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -38,13 +188,14 @@ impl drivers::InputDriver for Evdev {
                             device_kind: DeviceKind,
                             config: InputConfig,
                             gateway: InputGateway,
+                            tracker: DeviceTracker,
                             open_restricted: F)
                             -> Result<Box<Self>, Illusion>
         where F: Fn(&Path, OFlag, Mode) -> Result<io::RawFd, Illusion>
     {
         let r = open_restricted(devnode, fcntl::O_RDONLY, Mode::empty());
         match r {
-            Ok(fd) => Ok(Box::new(Evdev::new(fd, device_kind, config, gateway))),
+            Ok(fd) => Ok(Box::new(Evdev::new(fd, device_kind, config, gateway, tracker))),
             Err(err) => Err(err),
         }
     }
@@ -59,11 +210,30 @@ impl EventHandler for Evdev {
 
     fn process_event(&mut self, event_kind: EventKind) {
         if event_kind.intersects(event_kind::HANGUP) {
-            // TODO: Implement handling hangup of event device.
+            self.handle_hangup();
         } else if event_kind.intersects(event_kind::READ) {
             self.read_events();
         }
     }
+
+    fn set_id(&mut self, id: EventHandlerId) {
+        self.id = id;
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Device removed without being unplugged (e.g. when switching virtual terminal) is removed from
+/// seat capabilities here.
+impl Drop for Evdev {
+    fn drop(&mut self) {
+        if !self.lost {
+            self.tracker.remove(self.gateway.get_seat(), self.device_kind);
+        }
+        if let Err(err) = close(self.fd) {
+            log_warn2!("Failed to close input device: {:?}", err);
+        }
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -73,19 +243,82 @@ impl Evdev {
     fn new(fd: io::RawFd,
            device_kind: DeviceKind,
            config: InputConfig,
-           gateway: InputGateway)
+           gateway: InputGateway,
+           tracker: DeviceTracker)
            -> Self {
+        let mut abs_x = AbsInfo::default();
+        let mut abs_y = AbsInfo::default();
+        let mut abs_mt_slot = AbsInfo::default();
+        let mut abs_mt_x = AbsInfo::default();
+        let mut abs_mt_y = AbsInfo::default();
+        let mut slots = Vec::new();
+        let mut gestures = None;
+        if device_kind == DeviceKind::Touchscreen {
+            let result = unsafe { eviocgabs_x(fd, &mut abs_x).and(eviocgabs_y(fd, &mut abs_y)) };
+            if let Err(err) = result {
+                log_warn1!("Failed to read ranges of touchscreen axes: {:?}", err);
+            }
+        }
+
+        if (device_kind == DeviceKind::Touchscreen) || (device_kind == DeviceKind::Touchpad) {
+            let result = unsafe {
+                eviocgabs_mt_slot(fd, &mut abs_mt_slot)
+                    .and(eviocgabs_mt_x(fd, &mut abs_mt_x))
+                    .and(eviocgabs_mt_y(fd, &mut abs_mt_y))
+            };
+            if result.is_ok() && (abs_mt_x.maximum > abs_mt_x.minimum) {
+                let num_slots = (abs_mt_slot.maximum.max(0) as usize + 1).min(MAX_TOUCH_SLOTS);
+                slots = vec![TouchSlot::default(); num_slots];
+                if device_kind == DeviceKind::Touchpad {
+                    gestures = Some(GestureRecognizer::new(abs_mt_x.maximum - abs_mt_x.minimum));
+                }
+            }
+        }
+
         Evdev {
             fd: fd,
             device_kind: device_kind,
             config: config,
             gateway: gateway,
             pressure: 0,
+            tracker: tracker,
+            id: 0,
+            lost: false,
+            abs_x: abs_x,
+            abs_y: abs_y,
+            abs_position: (abs_x.value, abs_y.value),
+            abs_changed: false,
+            touch: None,
+            abs_mt_x: abs_mt_x,
+            abs_mt_y: abs_mt_y,
+            slots: slots,
+            slot: 0,
+            fingers: 0,
+            gestures: gestures,
+            tap: TapState::Idle,
+            scroll_position: None,
+        }
+    }
+
+    /// Handles unplugging of the device. Device is removed from seat capabilities and request for
+    /// removing this driver from `Dispatcher` is sent. `Dispatcher` is busy calling this driver so
+    /// it can not be done here. Further hangups until then are ignored.
+    fn handle_hangup(&mut self) {
+        if !self.lost {
+            self.lost = true;
+            log_info1!("Input device {} was unplugged", self.fd);
+            self.tracker.remove(self.gateway.get_seat(), self.device_kind);
+            self.gateway.emit_device_lost(self.id);
         }
     }
 
     /// Reads events.
     fn read_events(&mut self) {
+        // Apply configuration reloaded since last event
+        if self.gateway.refresh_config() {
+            self.config = self.gateway.get_config();
+        }
+
         let mut ev: input_event = unsafe { mem::uninitialized() };
         let data = unsafe { mem::transmute::<&mut input_event, &mut [u8; 3 * 8]>(&mut ev) };
         match read(self.fd, &mut data[..]) {
@@ -94,6 +327,8 @@ impl Evdev {
                     DeviceKind::Keyboard => self.process_keyboard_event(&ev),
                     DeviceKind::Mouse => self.process_mouse_event(&ev),
                     DeviceKind::Touchpad => self.process_touchpad_event(&ev),
+                    DeviceKind::Touchscreen => self.process_touchscreen_event(&ev),
+                    DeviceKind::Switch => self.process_switch_event(&ev),
                     DeviceKind::Unknown => panic!("Received event from device of unknown type"),
                 }
             }
@@ -144,13 +379,32 @@ impl Evdev {
     /// Helper method for processing touchpad events.
     fn process_touchpad_event(&mut self, ev: &input_event) {
         if ev.kind == uinput_sys::EV_SYN as _ {
-            // Ignore sync
+            let points: Vec<(i32, i32)> = self.slots
+                .iter()
+                .filter(|slot| slot.active)
+                .map(|slot| slot.position)
+                .collect();
+            if let Some(ref mut gestures) = self.gestures {
+                gestures.update(self.fingers, &points, &mut self.gateway);
+            }
+            self.update_tap(&points);
+            self.update_scroll(&points);
         } else if ev.kind == uinput_sys::EV_KEY as _ {
             if (ev.code == uinput_sys::BTN_LEFT as _) || (ev.code == uinput_sys::BTN_MIDDLE as _) ||
                (ev.code == uinput_sys::BTN_RIGHT as _) {
+                // Touch pressing physical button is not a tap
+                if let TapState::Touching(_) = self.tap {
+                    self.tap = TapState::Invalid;
+                }
                 self.gateway.emit_button(ev.code, ev.value);
-            } else if (ev.code == uinput_sys::BTN_TOOL_FINGER as _) ||
-                      (ev.code == uinput_sys::BTN_TOUCH as _) {
+            } else if ev.code == uinput_sys::BTN_TOUCH as _ {
+                self.gateway.emit_position_reset();
+            } else if let Some(count) = Self::get_finger_count(ev.code) {
+                if ev.value != 0 {
+                    self.fingers = count;
+                } else if self.fingers == count {
+                    self.fingers = 0;
+                }
                 self.gateway.emit_position_reset();
             } else {
                 log_nyimp!("Unhandled touchpad key event (code: {}, value: {})", ev.code, ev.value);
@@ -160,12 +414,14 @@ impl Evdev {
                        ev.code,
                        ev.value);
         } else if ev.kind == uinput_sys::EV_ABS as _ {
+            self.update_slot(ev);
             if ev.code == uinput_sys::ABS_PRESSURE as _ {
                 log_info4!("Touchpad pressure: {:?}", ev.value);
                 self.pressure = ev.value;
             } else if ev.code == uinput_sys::ABS_MT_TRACKING_ID as _ {
                 self.gateway.emit_position_reset();
-            } else if self.pressure > self.config.touchpad_pressure_threshold {
+            } else if (self.fingers < 2) && self.scroll_position.is_none() &&
+                      (self.pressure > self.config.touchpad_pressure_threshold) {
                 if (ev.code == uinput_sys::ABS_MT_POSITION_X as _) ||
                    (ev.code == uinput_sys::ABS_X as _) {
                     self.gateway.emit_position(Some(ev.value as isize), None);
@@ -181,6 +437,245 @@ impl Evdev {
                        ev.value);
         }
     }
+
+    /// Helper method for detecting taps on touchpad. Short touch without significant motion clicks
+    /// left, right or middle button when made with one, two or three fingers.
+    fn update_tap(&mut self, points: &[(i32, i32)]) {
+        if !self.config.tap_to_click {
+            self.tap = TapState::Idle;
+            return;
+        }
+
+        let center = Self::get_center(points);
+        let width = (self.abs_mt_x.maximum - self.abs_mt_x.minimum) as f32;
+        let state = self.tap;
+        self.tap = match state {
+            TapState::Idle if self.fingers > 0 => {
+                TapState::Touching(Tap {
+                    start: Instant::now(),
+                    max_fingers: self.fingers,
+                    fingers: self.fingers,
+                    origin: center,
+                })
+            }
+            TapState::Touching(tap) if self.fingers == 0 => {
+                if tap.start.elapsed() <= Duration::from_millis(TAP_TIMEOUT) {
+                    self.click(tap.max_fingers);
+                }
+                TapState::Idle
+            }
+            TapState::Touching(mut tap) => {
+                let threshold = TAP_MOTION_THRESHOLD * width;
+                let moved = match (tap.origin, center) {
+                    (Some(origin), Some(center)) if tap.fingers == self.fingers => {
+                        let (dx, dy) = (center.0 - origin.0, center.1 - origin.1);
+                        (dx * dx + dy * dy).sqrt() > threshold
+                    }
+                    _ => false,
+                };
+
+                if moved || (tap.start.elapsed() > Duration::from_millis(TAP_TIMEOUT)) {
+                    TapState::Invalid
+                } else {
+                    if tap.fingers != self.fingers {
+                        tap.fingers = self.fingers;
+                        tap.origin = center;
+                    }
+                    tap.max_fingers = tap.max_fingers.max(self.fingers);
+                    TapState::Touching(tap)
+                }
+            }
+            TapState::Invalid if self.fingers == 0 => TapState::Idle,
+            state => state,
+        };
+    }
+
+    /// Helper method for emitting click of button corresponding to number of fingers of tap.
+    fn click(&mut self, fingers: u32) {
+        let code = match fingers {
+            1 => uinput_sys::BTN_LEFT,
+            2 => uinput_sys::BTN_RIGHT,
+            _ => uinput_sys::BTN_MIDDLE,
+        };
+        self.gateway.emit_button(code as _, 1);
+        self.gateway.emit_button(code as _, 0);
+    }
+
+    /// Helper method for scrolling with touchpad according to configured scroll method.
+    fn update_scroll(&mut self, points: &[(i32, i32)]) {
+        let gesture = self.gestures.as_ref().map_or(false, |gestures| gestures.is_active());
+        let position = match self.config.scroll_method {
+            ScrollMethod::TwoFinger if (self.fingers == 2) && (points.len() == 2) && !gesture => {
+                Self::get_center(points)
+            }
+            ScrollMethod::Edge if (self.fingers == 1) && (points.len() == 1) => {
+                self.get_edge_position(points[0])
+            }
+            _ => None,
+        };
+
+        if let (Some(last), Some(current)) = (self.scroll_position, position) {
+            let (dx, dy) = (current.0 - last.0, current.1 - last.1);
+            if (dx != 0.0) || (dy != 0.0) {
+                self.gateway.emit_scroll(dx, dy);
+            }
+        }
+        self.scroll_position = position;
+    }
+
+    /// Returns position of finger relevant for edge scrolling: vertical along right edge and
+    /// horizontal along bottom edge. Returns `None` if finger is not on the edge.
+    fn get_edge_position(&self, point: (i32, i32)) -> Option<(f32, f32)> {
+        if self.abs_mt_x.normalize(point.0) > (1.0 - EDGE_SCROLL_SIZE) {
+            Some((0.0, point.1 as f32))
+        } else if self.abs_mt_y.normalize(point.1) > (1.0 - EDGE_SCROLL_SIZE) {
+            Some((point.0 as f32, 0.0))
+        } else {
+            None
+        }
+    }
+
+    /// Returns center of given points or `None` if there are no points.
+    fn get_center(points: &[(i32, i32)]) -> Option<(f32, f32)> {
+        if points.is_empty() {
+            None
+        } else {
+            let n = points.len() as f32;
+            let x = points.iter().map(|p| p.0 as f32).sum::<f32>() / n;
+            let y = points.iter().map(|p| p.1 as f32).sum::<f32>() / n;
+            Some((x, y))
+        }
+    }
+
+    /// Helper method for processing touchscreen and tablet events. Position and touch state are
+    /// accumulated until sync so pointer is moved before touch is reported.
+    fn process_touchscreen_event(&mut self, ev: &input_event) {
+        if !self.slots.is_empty() {
+            self.process_multitouch_event(ev);
+        } else if ev.kind == uinput_sys::EV_SYN as _ {
+            if self.abs_changed {
+                self.abs_changed = false;
+                let x = self.abs_x.normalize(self.abs_position.0);
+                let y = self.abs_y.normalize(self.abs_position.1);
+                self.gateway.emit_absolute_position(x, y);
+            }
+            if let Some(value) = self.touch.take() {
+                self.gateway.emit_button(uinput_sys::BTN_LEFT as _, value);
+            }
+        } else if ev.kind == uinput_sys::EV_KEY as _ {
+            if ev.code == uinput_sys::BTN_TOUCH as _ {
+                self.touch = Some(ev.value);
+            } else {
+                log_nyimp!("Unhandled touchscreen key event (code: {}, value: {})",
+                           ev.code,
+                           ev.value);
+            }
+        } else if ev.kind == uinput_sys::EV_ABS as _ {
+            if ev.code == uinput_sys::ABS_X as _ {
+                self.abs_position.0 = ev.value;
+                self.abs_changed = true;
+            } else if ev.code == uinput_sys::ABS_Y as _ {
+                self.abs_position.1 = ev.value;
+                self.abs_changed = true;
+            }
+        } else {
+            log_nyimp!("Unhandled touchscreen event (type: {}, code: {}, value: {})",
+                       ev.kind,
+                       ev.code,
+                       ev.value);
+        }
+    }
+
+    /// Helper method for processing multi-touch touchscreen events. Changes of slots are
+    /// accumulated until sync and then reported as touch events followed by touch frame.
+    fn process_multitouch_event(&mut self, ev: &input_event) {
+        if ev.kind == uinput_sys::EV_SYN as _ {
+            let mut changed = false;
+            for (id, slot) in self.slots.iter_mut().enumerate() {
+                let x = self.abs_mt_x.normalize(slot.position.0);
+                let y = self.abs_mt_y.normalize(slot.position.1);
+                if slot.up && slot.active {
+                    // Touch point was replaced by new one in the same slot
+                    self.gateway.emit_touch_up(id as i32);
+                    self.gateway.emit_touch_down(id as i32, x, y);
+                } else {
+                    if slot.down {
+                        self.gateway.emit_touch_down(id as i32, x, y);
+                    } else if slot.moved && slot.active {
+                        self.gateway.emit_touch_motion(id as i32, x, y);
+                    }
+                    if slot.up {
+                        self.gateway.emit_touch_up(id as i32);
+                    }
+                }
+
+                changed = changed || slot.down || slot.up || (slot.moved && slot.active);
+                slot.down = false;
+                slot.up = false;
+                slot.moved = false;
+            }
+            if changed {
+                self.gateway.emit_touch_frame();
+            }
+        } else if ev.kind == uinput_sys::EV_ABS as _ {
+            self.update_slot(ev);
+        }
+    }
+
+    /// Helper method for updating state of multi-touch slots.
+    fn update_slot(&mut self, ev: &input_event) {
+        if ev.code == uinput_sys::ABS_MT_SLOT as _ {
+            self.slot = ev.value.max(0) as usize;
+        } else if let Some(slot) = self.slots.get_mut(self.slot) {
+            if ev.code == uinput_sys::ABS_MT_TRACKING_ID as _ {
+                if ev.value < 0 {
+                    slot.up = slot.active;
+                    slot.active = false;
+                } else {
+                    slot.down = true;
+                    slot.active = true;
+                }
+            } else if ev.code == uinput_sys::ABS_MT_POSITION_X as _ {
+                slot.position.0 = ev.value;
+                slot.moved = true;
+            } else if ev.code == uinput_sys::ABS_MT_POSITION_Y as _ {
+                slot.position.1 = ev.value;
+                slot.moved = true;
+            }
+        }
+    }
+
+    /// Returns number of fingers reported by given tool key code.
+    fn get_finger_count(code: u16) -> Option<u32> {
+        if code == uinput_sys::BTN_TOOL_FINGER as _ {
+            Some(1)
+        } else if code == uinput_sys::BTN_TOOL_DOUBLETAP as _ {
+            Some(2)
+        } else if code == uinput_sys::BTN_TOOL_TRIPLETAP as _ {
+            Some(3)
+        } else if code == uinput_sys::BTN_TOOL_QUADTAP as _ {
+            Some(4)
+        } else {
+            None
+        }
+    }
+
+    /// Helper method for processing switch events.
+    fn process_switch_event(&mut self, ev: &input_event) {
+        if ev.kind == uinput_sys::EV_SW as _ {
+            let switch = if ev.code == uinput_sys::SW_LID as _ {
+                Switch::Lid
+            } else if ev.code == uinput_sys::SW_TABLET_MODE as _ {
+                Switch::TabletMode
+            } else if ev.code == uinput_sys::SW_DOCK as _ {
+                Switch::Dock
+            } else {
+                log_nyimp!("Unhandled switch event (code: {}, value: {})", ev.code, ev.value);
+                return;
+            };
+            self.gateway.emit_switch(switch, ev.value != 0);
+        }
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -0,0 +1,198 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains recognizer of multi-finger touchpad gestures.
+//!
+//! Gesture is recognized from positions of fingers tracked in multi-touch slots. Two fingers can
+//! only pinch (two-finger motion is left for scrolling), three or more fingers can swipe or pinch.
+//! Gesture ends when fingers are lifted or their number changes.
+
+// -------------------------------------------------------------------------------------------------
+
+use qualia::GestureKind;
+
+use input_gateway::InputGateway;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Fraction of touchpad width the fingers have to travel together to start swipe.
+const SWIPE_THRESHOLD: f32 = 0.05;
+
+/// Fraction of touchpad width the fingers have to spread or pinch to start pinch.
+const PINCH_THRESHOLD: f32 = 0.04;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Positions of fingers summarized for recognition.
+#[derive(Clone, Copy, Debug)]
+struct Fingers {
+    /// Number of fingers on touchpad.
+    count: u32,
+
+    /// Center of fingers.
+    center: (f32, f32),
+
+    /// Average distance of fingers from their center.
+    spread: f32,
+
+    /// Angle of line between first two fingers in degrees.
+    angle: f32,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Fingers {
+    /// Summarizes given positions of `count` fingers. Returns `None` if there are less than two
+    /// positions.
+    fn new(count: u32, points: &[(i32, i32)]) -> Option<Self> {
+        if points.len() < 2 {
+            return None;
+        }
+
+        let n = points.len() as f32;
+        let x = points.iter().map(|p| p.0 as f32).sum::<f32>() / n;
+        let y = points.iter().map(|p| p.1 as f32).sum::<f32>() / n;
+        let spread = points.iter()
+            .map(|p| ((p.0 as f32 - x).powi(2) + (p.1 as f32 - y).powi(2)).sqrt())
+            .sum::<f32>() / n;
+        let angle = ((points[1].1 - points[0].1) as f32)
+            .atan2((points[1].0 - points[0].0) as f32)
+            .to_degrees();
+
+        Some(Fingers {
+            count: count,
+            center: (x, y),
+            spread: spread,
+            angle: angle,
+        })
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// State of recognition.
+#[derive(Clone, Copy, Debug)]
+enum State {
+    /// Less than two fingers on touchpad.
+    Idle,
+
+    /// Fingers are on touchpad but did not move enough to recognize gesture.
+    Pending(Fingers),
+
+    /// Gesture of given kind is ongoing. Keeps fingers from start of gesture and from last update.
+    Active(GestureKind, Fingers, Fingers),
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Recognizes swipe and pinch gestures and emits them via `InputGateway`.
+pub struct GestureRecognizer {
+    /// Distance in device units needed to start swipe.
+    swipe_threshold: f32,
+
+    /// Change of spread in device units needed to start pinch.
+    pinch_threshold: f32,
+
+    /// State of recognition.
+    state: State,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl GestureRecognizer {
+    /// Constructs new `GestureRecognizer` for touchpad of given width in device units.
+    pub fn new(width: i32) -> Self {
+        GestureRecognizer {
+            swipe_threshold: SWIPE_THRESHOLD * width as f32,
+            pinch_threshold: PINCH_THRESHOLD * width as f32,
+            state: State::Idle,
+        }
+    }
+
+    /// Tells if gesture is ongoing.
+    pub fn is_active(&self) -> bool {
+        if let State::Active(..) = self.state {
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Updates recognition with number of fingers touching touchpad and positions of tracked
+    /// fingers. Should be called on every sync.
+    pub fn update(&mut self, count: u32, points: &[(i32, i32)], gateway: &mut InputGateway) {
+        let fingers = if count >= 2 { Fingers::new(count, points) } else { None };
+        let fingers = match fingers {
+            Some(fingers) => fingers,
+            None => {
+                if let State::Active(kind, _, _) = self.state {
+                    gateway.emit_gesture_end(kind, false);
+                }
+                self.state = State::Idle;
+                return;
+            }
+        };
+
+        self.state = match self.state {
+            State::Idle => State::Pending(fingers),
+            State::Pending(start) => {
+                if start.count != fingers.count {
+                    State::Pending(fingers)
+                } else if let Some(kind) = self.recognize(&start, &fingers) {
+                    gateway.emit_gesture_begin(kind, fingers.count);
+                    State::Active(kind, fingers, fingers)
+                } else {
+                    State::Pending(start)
+                }
+            }
+            State::Active(kind, start, last) => {
+                if start.count != fingers.count {
+                    gateway.emit_gesture_end(kind, false);
+                    State::Pending(fingers)
+                } else {
+                    let dx = fingers.center.0 - last.center.0;
+                    let dy = fingers.center.1 - last.center.1;
+                    let (scale, rotation) = match kind {
+                        GestureKind::Pinch if start.spread > 0.0 => {
+                            (fingers.spread / start.spread, Self::wrap(fingers.angle - last.angle))
+                        }
+                        _ => (1.0, 0.0),
+                    };
+                    gateway.emit_gesture_update(kind, dx, dy, scale, rotation);
+                    State::Active(kind, start, fingers)
+                }
+            }
+        };
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Private methods.
+impl GestureRecognizer {
+    /// Checks if fingers moved from starting positions enough to recognize a gesture.
+    fn recognize(&self, start: &Fingers, current: &Fingers) -> Option<GestureKind> {
+        let dx = current.center.0 - start.center.0;
+        let dy = current.center.1 - start.center.1;
+        if (current.spread - start.spread).abs() > self.pinch_threshold {
+            Some(GestureKind::Pinch)
+        } else if (current.count >= 3) && ((dx * dx + dy * dy).sqrt() > self.swipe_threshold) {
+            Some(GestureKind::Swipe)
+        } else {
+            None
+        }
+    }
+
+    /// Wraps given angle in degrees to range from -180 to 180.
+    fn wrap(angle: f32) -> f32 {
+        if angle > 180.0 {
+            angle - 360.0
+        } else if angle < -180.0 {
+            angle + 360.0
+        } else {
+            angle
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
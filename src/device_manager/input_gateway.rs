@@ -7,17 +7,32 @@
 
 use uinput_sys;
 
-use qualia::{perceptron, Perceptron, InputConfig};
-use qualia::{Axis, Button, Key, OptionalPosition, Slide, Vector};
-use qualia::{modifier, InputManager, KeyCatchResult, KeyCode, KeyValue, KeyState};
-use dharma::Signaler;
+use qualia::{perceptron, Perceptron, AccelProfile, Config, InputConfig, InputDeviceId, TouchConfig};
+use qualia::{AbsolutePosition, Axis, Button, Key, OptionalPosition, Slide, Switch, Vector};
+use qualia::{GestureKind, GestureMotion, Milliseconds, SeatId, TouchPoint};
+use qualia::{modifier, InputManager, KeyCatchResult, KeyCode, KeyRemapper, KeyValue, KeyState};
+use dharma::{EventHandlerId, Signaler};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Speed of mouse in device units per event above which adaptive profile accelerates motion.
+const ACCEL_THRESHOLD: f32 = 4.0;
+
+/// Maximal factor by which adaptive profile accelerates motion.
+const ACCEL_MAX_FACTOR: f32 = 2.5;
 
 // -------------------------------------------------------------------------------------------------
 
 pub struct InputGateway {
     modifiers: modifier::ModifierType,
+    global_config: Config,
+    generation: usize,
+    device_id: InputDeviceId,
+    seat: SeatId,
     config: InputConfig,
+    touch_config: TouchConfig,
     input_manager: InputManager,
+    key_remapper: KeyRemapper,
     signaler: Signaler<Perceptron>,
     modifier_keys: Vec<(KeyCode, modifier::ModifierType)>,
 }
@@ -25,14 +40,24 @@ pub struct InputGateway {
 // -------------------------------------------------------------------------------------------------
 
 impl InputGateway {
-    /// `InputGateway` constructor.
-    pub fn new(config: InputConfig,
+    /// `InputGateway` constructor. Key remapping and seat for given device are chosen once when
+    /// gateway is created. Input configuration and touch mapping are chosen again when global
+    /// configuration is reloaded (see `refresh_config`).
+    pub fn new(config: &Config,
+               input_config: InputConfig,
                input_manager: InputManager,
-               signaler: Signaler<Perceptron>)
+               signaler: Signaler<Perceptron>,
+               device_id: &InputDeviceId)
                -> Self {
         InputGateway {
             modifiers: modifier::NONE,
-            config: config,
+            key_remapper: KeyRemapper::new(&config.get_keyboard_configs(), &device_id.name),
+            global_config: config.clone(),
+            generation: config.get_generation(),
+            device_id: device_id.clone(),
+            seat: config.get_seat_for_device(device_id),
+            config: input_config,
+            touch_config: config.get_touch_config_for_device(device_id),
             input_manager: input_manager,
             signaler: signaler,
             modifier_keys: vec![(uinput_sys::KEY_LEFTCTRL as KeyCode, modifier::LCTL),
@@ -50,6 +75,37 @@ impl InputGateway {
 // -------------------------------------------------------------------------------------------------
 
 impl InputGateway {
+    /// Returns seat the device is assigned to.
+    pub fn get_seat(&self) -> SeatId {
+        self.seat
+    }
+
+    /// Informs rest of the application that device was unplugged and its event handler with given
+    /// ID should be removed.
+    pub fn emit_device_lost(&mut self, id: EventHandlerId) {
+        self.signaler.emit(perceptron::INPUT_DEVICE_LOST, Perceptron::InputDeviceLost(id));
+    }
+
+    /// Returns current input configuration of the device.
+    pub fn get_config(&self) -> InputConfig {
+        self.config
+    }
+
+    /// Chooses input configuration and touch mapping of the device again if global configuration
+    /// was reloaded since last check. Returns `true` if configuration was updated.
+    pub fn refresh_config(&mut self) -> bool {
+        let generation = self.global_config.get_generation();
+        if generation != self.generation {
+            self.generation = generation;
+            self.config = self.global_config.get_input_config_for_device(&self.device_id);
+            self.touch_config = self.global_config.get_touch_config_for_device(&self.device_id);
+            log_info2!("Applied new input configuration for '{}'", self.device_id.name);
+            true
+        } else {
+            false
+        }
+    }
+
     /// Emit keyboards event.
     pub fn emit_key(&mut self, code: u16, value: i32) {
         // Ignore repeats
@@ -57,23 +113,17 @@ impl InputGateway {
             return;
         }
 
-        // Update modifiers
-        if self.update_modifiers(code, value) != KeyCatchResult::Passed {
-            return;
-        }
-
-        // Try to execute key binding
-        if self.input_manager.catch_key(code, value, self.modifiers) == KeyCatchResult::Passed {
-            // If no binding found inform the rest of the world
-            let key = Key::new_now(code, value);
-            self.signaler.emit(perceptron::INPUT_KEYBOARD, Perceptron::InputKeyboard(key));
+        // Translate keys before anything else sees them
+        for (code, value) in self.key_remapper.remap(code, value) {
+            self.handle_key(code, value);
         }
     }
 
-    /// Scale displacements and emit pointer motion event.
+    /// Scale and accelerate displacements and emit pointer motion event.
     pub fn emit_motion(&mut self, x: isize, y: isize) {
         // Scale event values
-        let vector = Vector::new(x, y).scaled(self.config.mouse_scale);
+        let factor = self.config.mouse_scale * self.get_accel_factor(x, y);
+        let vector = Vector::new(x, y).scaled(factor);
 
         // Signal event
         self.signaler.emit(perceptron::INPUT_POINTER_MOTION, Perceptron::InputPointerMotion(vector))
@@ -82,16 +132,87 @@ impl InputGateway {
     /// Scale position and emit pointer position event.
     pub fn emit_position(&mut self, x: Option<isize>, y: Option<isize>) {
         // Scale event values. Skip scaling invalid values
-        let pos = OptionalPosition::new(x, y).scaled(self.config.touchpad_scale);
+        let scale = self.config.touchpad_scale;
+        let pos = OptionalPosition::new(x, y).scaled(scale);
 
         // Signal event
         self.signaler.emit(perceptron::INPUT_POINTER_POSITION,
                            Perceptron::InputPointerPosition(pos))
     }
 
+    /// Calibrate normalized absolute position and emit absolute position event.
+    pub fn emit_absolute_position(&mut self, x: f32, y: f32) {
+        let pos = self.calibrate_touch(x, y);
+
+        // Signal event
+        self.signaler.emit(perceptron::INPUT_POINTER_ABSOLUTE_POSITION,
+                           Perceptron::InputPointerAbsolutePosition(pos))
+    }
+
+    /// Calibrate normalized position and emit touch down event for touch point with given ID.
+    pub fn emit_touch_down(&mut self, id: i32, x: f32, y: f32) {
+        let touch = TouchPoint::new_now(id, self.calibrate_touch(x, y));
+
+        // Signal event
+        self.signaler.emit(perceptron::INPUT_TOUCH_DOWN, Perceptron::InputTouchDown(touch))
+    }
+
+    /// Calibrate normalized position and emit touch motion event for touch point with given ID.
+    pub fn emit_touch_motion(&mut self, id: i32, x: f32, y: f32) {
+        let touch = TouchPoint::new_now(id, self.calibrate_touch(x, y));
+
+        // Signal event
+        self.signaler.emit(perceptron::INPUT_TOUCH_MOTION, Perceptron::InputTouchMotion(touch))
+    }
+
+    /// Emit touch up event for touch point with given ID.
+    pub fn emit_touch_up(&mut self, id: i32) {
+        // Signal event
+        self.signaler.emit(perceptron::INPUT_TOUCH_UP,
+                           Perceptron::InputTouchUp(id, Milliseconds::now()))
+    }
+
+    /// Emit event closing set of touch events which happened at the same time.
+    pub fn emit_touch_frame(&mut self) {
+        // Signal event
+        self.signaler.emit(perceptron::INPUT_TOUCH_FRAME, Perceptron::InputTouchFrame)
+    }
+
+    /// Emit event of beginning of touchpad gesture of given kind performed with given number of
+    /// fingers.
+    pub fn emit_gesture_begin(&mut self, kind: GestureKind, fingers: u32) {
+        // Signal event
+        self.signaler.emit(perceptron::INPUT_GESTURE_BEGIN,
+                           Perceptron::InputGestureBegin(kind, fingers, Milliseconds::now()))
+    }
+
+    /// Scale displacements and emit touchpad gesture update event.
+    pub fn emit_gesture_update(&mut self,
+                               kind: GestureKind,
+                               dx: f32,
+                               dy: f32,
+                               scale: f32,
+                               rotation: f32) {
+        // Scale event values
+        let factor = self.config.touchpad_scale;
+        let delta = Slide::new(factor * dx, factor * dy);
+        let motion = GestureMotion::new_now(delta, scale, rotation);
+
+        // Signal event
+        self.signaler.emit(perceptron::INPUT_GESTURE_UPDATE,
+                           Perceptron::InputGestureUpdate(kind, motion))
+    }
+
+    /// Emit event of end of touchpad gesture.
+    pub fn emit_gesture_end(&mut self, kind: GestureKind, cancelled: bool) {
+        // Signal event
+        self.signaler.emit(perceptron::INPUT_GESTURE_END,
+                           Perceptron::InputGestureEnd(kind, cancelled, Milliseconds::now()))
+    }
+
     /// Emit button event.
     pub fn emit_button(&mut self, code: u16, value: i32) {
-        let btn = Button::new_now(code, value);
+        let btn = Button::new_now(code, value).with_seat(self.seat);
 
         // Signal event
         self.signaler.emit(perceptron::INPUT_POINTER_BUTTON, Perceptron::InputPointerButton(btn))
@@ -99,6 +220,11 @@ impl InputGateway {
 
     /// Emit exist event.
     pub fn emit_axis(&mut self, horizontal: isize, vertical: isize) {
+        let (horizontal, vertical) = if self.config.natural_scrolling {
+            (-horizontal, -vertical)
+        } else {
+            (horizontal, vertical)
+        };
         let axis = Axis::new_now(Vector::new(horizontal, vertical),
                                  Slide::new(10.0 * horizontal as f32, 10.0 * vertical as f32));
 
@@ -106,17 +232,72 @@ impl InputGateway {
         self.signaler.emit(perceptron::INPUT_POINTER_AXIS, Perceptron::InputPointerAxis(axis))
     }
 
+    /// Scale displacements of fingers and emit continuous axis event.
+    pub fn emit_scroll(&mut self, dx: f32, dy: f32) {
+        // Scale event values. Moving fingers down scrolls down unless scrolling is natural
+        let factor = self.config.touchpad_scale;
+        let (dx, dy) = if self.config.natural_scrolling {
+            (-factor * dx, factor * dy)
+        } else {
+            (factor * dx, -factor * dy)
+        };
+        let axis = Axis::new_now(Vector::new(0, 0), Slide::new(dx, dy));
+
+        // Signal event
+        self.signaler.emit(perceptron::INPUT_POINTER_AXIS, Perceptron::InputPointerAxis(axis))
+    }
+
     /// Emit position reset event.
     pub fn emit_position_reset(&mut self) {
         // Signal event
         self.signaler.emit(perceptron::INPUT_POINTER_POSITION_RESET,
                            Perceptron::InputPointerPositionReset)
     }
+
+    /// Emit switch event.
+    pub fn emit_switch(&mut self, switch: Switch, state: bool) {
+        // Signal event
+        self.signaler.emit(perceptron::SWITCH_TOGGLED, Perceptron::SwitchToggled(switch, state))
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
 
 impl InputGateway {
+    /// Helper method for computing factor by which mouse motion is accelerated.
+    fn get_accel_factor(&self, x: isize, y: isize) -> f32 {
+        match self.config.accel_profile {
+            AccelProfile::Flat => 1.0,
+            AccelProfile::Adaptive => {
+                let speed = ((x * x + y * y) as f32).sqrt();
+                let excess = (speed - ACCEL_THRESHOLD).max(0.0);
+                (1.0 + 0.5 * excess / ACCEL_THRESHOLD).min(ACCEL_MAX_FACTOR)
+            }
+        }
+    }
+
+    /// Helper method for calibrating normalized position and mapping it to output.
+    fn calibrate_touch(&self, x: f32, y: f32) -> AbsolutePosition {
+        let (x, y) = self.touch_config.calibrate(x, y);
+        AbsolutePosition::new(self.touch_config.output.clone(), x, y)
+    }
+
+    /// Helper method for handling single key event: updates modifiers, executes key bindings or
+    /// emits key event.
+    fn handle_key(&mut self, code: KeyCode, value: KeyValue) {
+        // Update modifiers
+        if self.update_modifiers(code, value) != KeyCatchResult::Passed {
+            return;
+        }
+
+        // Try to execute key binding
+        if self.input_manager.catch_key(code, value, self.modifiers) == KeyCatchResult::Passed {
+            // If no binding found inform the rest of the world
+            let key = Key::new_now(code, value).with_seat(self.seat);
+            self.signaler.emit(perceptron::INPUT_KEYBOARD, Perceptron::InputKeyboard(key));
+        }
+    }
+
     /// Helper method for updating modifiers.
     fn update_modifiers(&mut self, code: KeyCode, value: KeyValue) -> KeyCatchResult {
         let mut result = KeyCatchResult::Passed;
@@ -5,8 +5,10 @@
 
 extern crate libudev;
 extern crate uinput_sys;
+#[macro_use]
 extern crate nix;
 extern crate drm as libdrm;
+extern crate dbus;
 
 #[macro_use]
 extern crate timber;
@@ -16,10 +18,15 @@ extern crate dharma;
 
 mod input_gateway;
 mod evdev;
+mod gesture_recognizer;
 mod drivers;
 mod pageflip;
 mod output_collector;
 mod device_monitor;
+mod device_tracker;
+mod sensor_proxy;
+mod media_keys;
+mod session;
 
 pub mod udev;
 
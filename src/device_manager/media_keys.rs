@@ -0,0 +1,207 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains functionality related to performing actions of media keys.
+//!
+//! Commands configured for media keys are executed with shell. Playback keys without configured
+//! command may control media player over MPRIS on session bus.
+
+// Quick reminder - this command lists all media players available on session bus:
+//
+// gdbus call --session \
+//            --dest org.freedesktop.DBus \
+//            --object-path /org/freedesktop/DBus \
+//            --method org.freedesktop.DBus.ListNames
+
+// -------------------------------------------------------------------------------------------------
+
+use std::process::{Child, Command};
+
+use dbus::{BusName, BusType, Connection, Interface, Member, Message, MessageItem, Path};
+
+use qualia::{Config, Illusion};
+use qualia::defs::media_key;
+
+// -------------------------------------------------------------------------------------------------
+
+const BUS_DESTINATION: &'static str = "org.freedesktop.DBus";
+const BUS_OBJECT_PATH: &'static str = "/org/freedesktop/DBus";
+const BUS_INTERFACE: &'static str = "org.freedesktop.DBus";
+const MPRIS_PREFIX: &'static str = "org.mpris.MediaPlayer2.";
+const MPRIS_OBJECT_PATH: &'static str = "/org/mpris/MediaPlayer2";
+const PLAYER_INTERFACE: &'static str = "org.mpris.MediaPlayer2.Player";
+const PROPERTIES_INTERFACE: &'static str = "org.freedesktop.DBus.Properties";
+const PLAYBACK_STATUS_PROPERTY: &'static str = "PlaybackStatus";
+
+/// Response timeout in milliseconds.
+const TIMEOUT: i32 = 1000;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Returns name of MPRIS method performing action of given media key or `None` if the key does not
+/// control playback.
+fn get_mpris_method(name: &str) -> Option<&'static str> {
+    match name {
+        media_key::PLAY_PAUSE => Some("PlayPause"),
+        media_key::NEXT => Some("Next"),
+        media_key::PREVIOUS => Some("Previous"),
+        media_key::STOP => Some("Stop"),
+        _ => None,
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Performs actions of media keys.
+pub struct MediaKeys {
+    config: Config,
+
+    /// Connection to session bus. Established when needed for the first time.
+    connection: Option<Connection>,
+
+    /// Executed commands which did not exit yet.
+    children: Vec<Child>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl MediaKeys {
+    /// Constructs new `MediaKeys`. Actions are read from configuration every time key is pressed
+    /// so reloading configuration takes effect at once.
+    pub fn new(config: Config) -> Self {
+        MediaKeys {
+            config: config,
+            connection: None,
+            children: Vec::new(),
+        }
+    }
+
+    /// Performs action of media key with given name.
+    pub fn perform(&mut self, name: &str) {
+        self.reap_children();
+
+        let config = self.config.get_media_keys_config();
+        if let Some(command) = config.commands.get(name) {
+            if !command.is_empty() {
+                self.execute(command);
+            }
+        } else if let Some(method) = get_mpris_method(name) {
+            if config.mpris {
+                if let Err(err) = self.control_player(method) {
+                    log_warn2!("Media keys: failed to control media player: {}", err);
+                }
+            }
+        } else {
+            log_warn2!("Media keys: no action for '{}'", name);
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl MediaKeys {
+    /// Executes given command with shell without waiting for it to exit.
+    fn execute(&mut self, command: &str) {
+        log_info2!("Media keys: executing '{}'", command);
+        match Command::new("sh").arg("-c").arg(command).spawn() {
+            Ok(child) => self.children.push(child),
+            Err(err) => log_warn1!("Media keys: failed to execute '{}': {}", command, err),
+        }
+    }
+
+    /// Collects exit status of commands which already exited.
+    fn reap_children(&mut self) {
+        let children: Vec<Child> = self.children.drain(..).collect();
+        for mut child in children {
+            if let Ok(None) = child.try_wait() {
+                self.children.push(child);
+            }
+        }
+    }
+
+    /// Calls given method of media player. Player which is currently playing is preferred,
+    /// otherwise the first found one is chosen.
+    fn control_player(&mut self, method: &str) -> Result<(), Illusion> {
+        let players = self.find_players()?;
+        let mut chosen = None;
+        for player in players.iter() {
+            if self.is_playing(player) {
+                chosen = Some(player);
+                break;
+            } else if chosen.is_none() {
+                chosen = Some(player);
+            }
+        }
+
+        if let Some(player) = chosen {
+            log_info2!("Media keys: calling '{}' of '{}'", method, player);
+            self.call(player, MPRIS_OBJECT_PATH, PLAYER_INTERFACE, method, &[])?;
+            Ok(())
+        } else {
+            Err(Illusion::General(format!("No media player found")))
+        }
+    }
+
+    /// Returns bus names of all media players implementing MPRIS.
+    fn find_players(&mut self) -> Result<Vec<String>, Illusion> {
+        let reply = self.call(BUS_DESTINATION, BUS_OBJECT_PATH, BUS_INTERFACE, "ListNames", &[])?;
+        let mut players = Vec::new();
+        if let Some(&MessageItem::Array(ref names, _)) = reply.get_items().first() {
+            for name in names.iter() {
+                if let MessageItem::Str(ref name) = *name {
+                    if name.starts_with(MPRIS_PREFIX) {
+                        players.push(name.clone());
+                    }
+                }
+            }
+        }
+        Ok(players)
+    }
+
+    /// Checks if given media player is currently playing.
+    fn is_playing(&mut self, player: &str) -> bool {
+        let items = [PLAYER_INTERFACE.into(), PLAYBACK_STATUS_PROPERTY.into()];
+        match self.call(player, MPRIS_OBJECT_PATH, PROPERTIES_INTERFACE, "Get", &items) {
+            Ok(reply) => {
+                if let Some(&MessageItem::Variant(ref value)) = reply.get_items().first() {
+                    **value == MessageItem::Str("Playing".to_owned())
+                } else {
+                    false
+                }
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Calls method on session bus and waits for reply. Connects to session bus if not connected
+    /// yet.
+    fn call(&mut self,
+            destination: &str,
+            path: &str,
+            interface: &str,
+            method: &str,
+            items: &[MessageItem])
+            -> Result<Message, Illusion> {
+        if self.connection.is_none() {
+            let connection = Connection::get_private(BusType::Session).map_err(|err| {
+                    Illusion::General(format!("Failed to connect to session bus: {:?}", err))
+                })?;
+            self.connection = Some(connection);
+        }
+
+        let destination = BusName::new(destination).map_err(Illusion::General)?;
+        let path = Path::new(path).map_err(Illusion::General)?;
+        let interface = Interface::new(interface).map_err(Illusion::General)?;
+        let member = Member::new(method).map_err(Illusion::General)?;
+        let mut message = Message::method_call(&destination, &path, &interface, &member);
+        message.append_items(items);
+        if let Some(ref connection) = self.connection {
+            connection.send_with_reply_and_block(message, TIMEOUT)
+                .map_err(|err| Illusion::General(format!("Call to '{}' failed: {:?}", method, err)))
+        } else {
+            Err(Illusion::General(format!("No connection to session bus")))
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -9,8 +9,6 @@
 
 use std::path::Path;
 use std::os::unix::io;
-use nix::fcntl;
-use nix::sys::stat;
 use libdrm::drm_mode;
 
 use dharma::{Dispatcher, Signaler, event_kind};
@@ -39,22 +37,15 @@ impl OutputCollector {
     }
 
     /// Scan DRM devices to find outputs. When the output is found emits `OutputFound` signal.
-    pub fn scan_device(&mut self, path: &Path) -> Result<(), Illusion> {
-        // Open device
+    /// Device must be already opened with read and write access.
+    pub fn scan_device(&mut self, path: &Path, fd: io::RawFd) -> Result<(), Illusion> {
         log_info1!("OutputCollector: scan device '{:?}'", path);
-        let fd = match fcntl::open(path, fcntl::O_RDWR, stat::Mode::empty()) {
-            Ok(fd) => fd,
-            Err(err) => {
-                let text = format!("Could open output device {:?}: {}", path, err);
-                return Err(Illusion::General(text));
-            }
-        };
 
         // Scan for connected outputs
         if let Some(resources) = drm_mode::get_resources(fd) {
             for id in resources.get_connectors() {
                 if let Some(connector) = drm_mode::get_connector(fd, id) {
-                    self.process_connector(fd, &connector);
+                    self.process_connector(path, fd, &connector);
                 } else {
                     log_warn1!("Failed to get connector info!");
                 }
@@ -71,12 +62,16 @@ impl OutputCollector {
     }
 
     /// Helper method for `scan_devices`.
-    fn process_connector(&mut self, fd: io::RawFd, connector: &drm_mode::Connector) {
+    fn process_connector(&mut self,
+                         path: &Path,
+                         fd: io::RawFd,
+                         connector: &drm_mode::Connector) {
         log_info1!("{:?}", connector);
 
         if connector.get_connection() == drm_mode::Connection::Connected {
             if let Some(encoder) = drm_mode::get_encoder(fd, connector.get_encoder_id()) {
                 let bundle = DrmBundle {
+                    path: path.to_path_buf(),
                     fd: fd,
                     connector_id: connector.get_connector_id(),
                     crtc_id: encoder.get_crtc_id(),
@@ -9,7 +9,7 @@ use std::os::unix::io;
 use libdrm::drm;
 
 use dharma::{EventHandler, EventKind, Signaler, event_kind};
-use qualia::{perceptron, Perceptron};
+use qualia::{perceptron, Perceptron, PresentationTime};
 
 // -------------------------------------------------------------------------------------------------
 
@@ -37,7 +37,8 @@ impl drm::EventContext for PageFlipContext {
 
     #[allow(unused_variables)]
     fn page_flip_handler(&mut self, fd: io::RawFd, sequence: u32, sec: u32, usec: u32, data: i32) {
-        self.signaler.emit(perceptron::PAGE_FLIP, Perceptron::PageFlip(data));
+        let time = PresentationTime::from_drm(sec, usec, sequence);
+        self.signaler.emit(perceptron::PAGE_FLIP, Perceptron::PageFlip(data, time));
     }
 }
 
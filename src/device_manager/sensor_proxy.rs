@@ -0,0 +1,190 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains `SensorProxy` which reads orientation of the device from accelerometer
+//! via `iio-sensor-proxy` and informs rest of application about its changes.
+
+// Quick reminder - this command prints current orientation:
+//
+// gdbus call --system \
+//            --dest net.hadess.SensorProxy \
+//            --object-path /net/hadess/SensorProxy \
+//            --method org.freedesktop.DBus.Properties.Get \
+//            net.hadess.SensorProxy AccelerometerOrientation
+
+// -------------------------------------------------------------------------------------------------
+
+use std::os::unix::io;
+
+use dbus::{BusName, BusType, Connection, ConnectionItem, Interface, Member, Message};
+use dbus::{MessageItem, Path};
+
+use dharma::{EventHandler, EventKind, Signaler};
+use qualia::{perceptron, Illusion, Perceptron, Transform};
+
+// -------------------------------------------------------------------------------------------------
+
+const SENSOR_PROXY_DESTINATION: &'static str = "net.hadess.SensorProxy";
+const SENSOR_PROXY_OBJECT_PATH: &'static str = "/net/hadess/SensorProxy";
+const SENSOR_PROXY_INTERFACE: &'static str = "net.hadess.SensorProxy";
+const PROPERTIES_INTERFACE: &'static str = "org.freedesktop.DBus.Properties";
+const ORIENTATION_PROPERTY: &'static str = "AccelerometerOrientation";
+
+/// Response timeout in milliseconds.
+const TIMEOUT: i32 = 1000;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Translates orientation reported by `iio-sensor-proxy` to transform of output. Returns `None`
+/// for undefined orientation.
+fn translate_orientation(orientation: &str) -> Option<Transform> {
+    match orientation {
+        "normal" => Some(Transform::Normal),
+        "left-up" => Some(Transform::Rotated90),
+        "bottom-up" => Some(Transform::Rotated180),
+        "right-up" => Some(Transform::Rotated270),
+        _ => None,
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Handler of messages from `iio-sensor-proxy`.
+pub struct SensorProxy {
+    connection: Connection,
+    fd: io::RawFd,
+    orientation: Option<Transform>,
+    signaler: Signaler<Perceptron>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// `SensorProxy` is used only in main dispatchers thread.
+unsafe impl Send for SensorProxy {}
+
+// -------------------------------------------------------------------------------------------------
+
+impl SensorProxy {
+    /// Connects to `iio-sensor-proxy`, claims accelerometer and subscribes for changes of its
+    /// orientation. Current orientation is emitted right away.
+    pub fn new(signaler: Signaler<Perceptron>) -> Result<Self, Illusion> {
+        let connection = Connection::get_private(BusType::System).map_err(|err| {
+                Illusion::General(format!("Failed to connect to system bus: {:?}", err))
+            })?;
+
+        let fd = match connection.watch_fds().first() {
+            Some(watch) => watch.fd(),
+            None => return Err(Illusion::General(format!("No file descriptor for system bus"))),
+        };
+
+        let rule = format!("type='signal',sender='{}',path='{}',interface='{}'",
+                           SENSOR_PROXY_DESTINATION,
+                           SENSOR_PROXY_OBJECT_PATH,
+                           PROPERTIES_INTERFACE);
+        connection.add_match(&rule).map_err(|err| {
+                Illusion::General(format!("Failed to subscribe for orientation: {:?}", err))
+            })?;
+
+        let mut mine = SensorProxy {
+            connection: connection,
+            fd: fd,
+            orientation: None,
+            signaler: signaler,
+        };
+
+        mine.call(SENSOR_PROXY_INTERFACE, "ClaimAccelerometer", &[])?;
+        let reply = mine.call(PROPERTIES_INTERFACE,
+                              "Get",
+                              &[SENSOR_PROXY_INTERFACE.into(), ORIENTATION_PROPERTY.into()])?;
+        if let Some(item) = reply.get_items().first() {
+            mine.update_orientation(item);
+        }
+        Ok(mine)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl SensorProxy {
+    /// Calls method of `iio-sensor-proxy` and waits for reply.
+    fn call(&self,
+            interface: &str,
+            method: &str,
+            items: &[MessageItem])
+            -> Result<Message, Illusion> {
+        let mut message = Message::method_call(&BusName::new(SENSOR_PROXY_DESTINATION).unwrap(),
+                                               &Path::new(SENSOR_PROXY_OBJECT_PATH).unwrap(),
+                                               &Interface::new(interface).unwrap(),
+                                               &Member::new(method).unwrap());
+        message.append_items(items);
+        self.connection
+            .send_with_reply_and_block(message, TIMEOUT)
+            .map_err(|err| Illusion::General(format!("Call to '{}' failed: {:?}", method, err)))
+    }
+
+    /// Handles `PropertiesChanged` signal.
+    fn handle_properties_changed(&mut self, message: &Message) {
+        let items = message.get_items();
+        if let Some(&MessageItem::Array(ref entries, _)) = items.get(1) {
+            for entry in entries.iter() {
+                if let MessageItem::DictEntry(ref key, ref value) = *entry {
+                    if **key == MessageItem::Str(ORIENTATION_PROPERTY.to_owned()) {
+                        self.update_orientation(value);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Emits new orientation if it differs from last known one.
+    fn update_orientation(&mut self, item: &MessageItem) {
+        let orientation = match *item {
+            MessageItem::Variant(ref value) => {
+                match **value {
+                    MessageItem::Str(ref string) => translate_orientation(string),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+
+        if let Some(transform) = orientation {
+            if orientation != self.orientation {
+                log_info2!("Sensor proxy: orientation changed to {:?}", transform);
+                self.orientation = orientation;
+                self.signaler.emit(perceptron::ORIENTATION_CHANGED,
+                                   Perceptron::OrientationChanged(transform));
+            }
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// This code executes in main dispatchers thread.
+impl EventHandler for SensorProxy {
+    fn get_fd(&self) -> io::RawFd {
+        self.fd
+    }
+
+    fn process_event(&mut self, _: EventKind) {
+        let mut messages = Vec::new();
+        for item in self.connection.incoming(0) {
+            match item {
+                ConnectionItem::Signal(message) => messages.push(message),
+                ConnectionItem::Nothing => break,
+                _ => {}
+            }
+        }
+
+        for message in messages.iter() {
+            let (_, _, interface, member) = message.headers();
+            if (interface == Some(PROPERTIES_INTERFACE.to_owned())) &&
+               (member == Some("PropertiesChanged".to_owned())) {
+                self.handle_properties_changed(message);
+            }
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,154 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains `SessionMonitor` which handles signals of `logind` session object.
+//!
+//! When session becomes inactive `logind` pauses devices taken by the compositor and revokes access
+//! to them. Pausing has to be acknowledged with `PauseDeviceComplete`. When session becomes active
+//! again `logind` resumes DRM devices passing new file descriptors. Input devices are not resumed,
+//! they are hung up and have to be taken again, so rest of application is informed about change of
+//! session state.
+
+// Quick reminder - this command prints if the session is active:
+//
+// gdbus call --system \
+//            --dest org.freedesktop.login1 \
+//            --object-path /org/freedesktop/login1/session/self \
+//            --method org.freedesktop.DBus.Properties.Get \
+//            org.freedesktop.login1.Session Active
+
+// -------------------------------------------------------------------------------------------------
+
+use std::os::unix::io;
+
+use dbus::{Message, MessageItem};
+
+use dharma::{EventHandler, EventKind, Signaler};
+use qualia::{perceptron, ipc, Illusion, Ipc, Perceptron};
+
+// -------------------------------------------------------------------------------------------------
+
+const SESSION_INTERFACE: &'static str = "org.freedesktop.login1.Session";
+const PROPERTIES_INTERFACE: &'static str = "org.freedesktop.DBus.Properties";
+const ACTIVE_PROPERTY: &'static str = "Active";
+
+// -------------------------------------------------------------------------------------------------
+
+/// Handler of signals from `logind` session.
+pub struct SessionMonitor {
+    ipc: Ipc,
+    fd: io::RawFd,
+    active: bool,
+    signaler: Signaler<Perceptron>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// `SessionMonitor` is used only in main dispatchers thread.
+unsafe impl Send for SessionMonitor {}
+
+// -------------------------------------------------------------------------------------------------
+
+impl SessionMonitor {
+    /// Constructs new `SessionMonitor`. `ipc` must be already initialized.
+    pub fn new(ipc: Ipc, signaler: Signaler<Perceptron>) -> Result<Self, Illusion> {
+        let fd = match ipc.get_fd() {
+            Some(fd) => fd,
+            None => return Err(Illusion::General(format!("No file descriptor for system bus"))),
+        };
+
+        Ok(SessionMonitor {
+               ipc: ipc,
+               fd: fd,
+               active: true,
+               signaler: signaler,
+           })
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl SessionMonitor {
+    /// Handles `PauseDevice` signal. Devices paused with type `pause` must be acknowledged. Other
+    /// types (`force` and `gone`) inform about already revoked access.
+    fn handle_pause_device(&mut self, message: &Message) {
+        let items = message.get_items();
+        if let (Some(&MessageItem::UInt32(major)),
+                Some(&MessageItem::UInt32(minor)),
+                Some(&MessageItem::Str(ref kind))) = (items.get(0), items.get(1), items.get(2)) {
+            log_info2!("Session: device {}:{} paused ({})", major, minor, kind);
+            if kind == ipc::PAUSE_TYPE_PAUSE {
+                if let Err(err) = self.ipc.pause_device_complete(major, minor) {
+                    log_warn1!("Session: failed to acknowledge pausing device: {}", err);
+                }
+            }
+        }
+    }
+
+    /// Handles `ResumeDevice` signal. Received file descriptor is not used; it is closed when
+    /// message is dropped. Devices are opened again after session becomes active.
+    fn handle_resume_device(&mut self, message: &Message) {
+        let items = message.get_items();
+        if let (Some(&MessageItem::UInt32(major)), Some(&MessageItem::UInt32(minor))) =
+            (items.get(0), items.get(1)) {
+            log_info2!("Session: device {}:{} resumed", major, minor);
+        }
+    }
+
+    /// Handles `PropertiesChanged` signal.
+    fn handle_properties_changed(&mut self, message: &Message) {
+        let items = message.get_items();
+        if let Some(&MessageItem::Array(ref entries, _)) = items.get(1) {
+            for entry in entries.iter() {
+                if let MessageItem::DictEntry(ref key, ref value) = *entry {
+                    if **key == MessageItem::Str(ACTIVE_PROPERTY.to_owned()) {
+                        self.update_active(value);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Emits new state of session if it differs from last known one.
+    fn update_active(&mut self, item: &MessageItem) {
+        if let MessageItem::Variant(ref value) = *item {
+            if let MessageItem::Bool(active) = **value {
+                if active != self.active {
+                    log_info1!("Session: {}", if active { "activated" } else { "deactivated" });
+                    self.active = active;
+                    self.signaler.emit(perceptron::SESSION_STATE_CHANGED,
+                                       Perceptron::SessionStateChanged(active));
+                }
+            }
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// This code executes in main dispatchers thread.
+impl EventHandler for SessionMonitor {
+    fn get_fd(&self) -> io::RawFd {
+        self.fd
+    }
+
+    fn process_event(&mut self, _: EventKind) {
+        for message in self.ipc.receive_signals().iter() {
+            let (_, _, interface, member) = message.headers();
+            match (interface.as_ref().map(|s| s.as_str()), member.as_ref().map(|s| s.as_str())) {
+                (Some(SESSION_INTERFACE), Some("PauseDevice")) => {
+                    self.handle_pause_device(message);
+                }
+                (Some(SESSION_INTERFACE), Some("ResumeDevice")) => {
+                    self.handle_resume_device(message);
+                }
+                (Some(PROPERTIES_INTERFACE), Some("PropertiesChanged")) => {
+                    self.handle_properties_changed(message);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
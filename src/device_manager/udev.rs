@@ -19,6 +19,9 @@ use device_monitor::DeviceMonitor;
 const INPUT_MOUSE: &'static str = "ID_INPUT_MOUSE";
 const INPUT_TOUCHPAD: &'static str = "ID_INPUT_TOUCHPAD";
 const INPUT_KEYBOARD: &'static str = "ID_INPUT_KEYBOARD";
+const INPUT_SWITCH: &'static str = "ID_INPUT_SWITCH";
+const INPUT_TOUCHSCREEN: &'static str = "ID_INPUT_TOUCHSCREEN";
+const INPUT_TABLET: &'static str = "ID_INPUT_TABLET";
 
 // -------------------------------------------------------------------------------------------------
 
@@ -122,6 +125,27 @@ fn is_output_device(devnode: &Path, sysname: &String) -> bool {
 
 // -------------------------------------------------------------------------------------------------
 
+/// Reads name, vendor ID and product ID of input device. They are stored in parent of event
+/// device. Values which are not available are empty or zero.
+pub fn get_device_id(device: &libudev::Device) -> qualia::InputDeviceId {
+    let parent = device.parent();
+    let read = |attribute: &str| {
+        parent.as_ref()
+            .and_then(|parent| parent.attribute_value(attribute))
+            .map(|value| value.to_string_lossy().trim().to_owned())
+            .unwrap_or(String::new())
+    };
+    let read_hex = |attribute: &str| u16::from_str_radix(&read(attribute), 16).unwrap_or(0);
+
+    qualia::InputDeviceId {
+        vendor: read_hex("id/vendor"),
+        product: read_hex("id/product"),
+        name: read("name"),
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
 /// Reads devices properties and determines device kind basing on them.
 pub fn determine_device_kind(device: &libudev::Device) -> qualia::enums::DeviceKind {
     for property in device.properties() {
@@ -131,6 +155,10 @@ pub fn determine_device_kind(device: &libudev::Device) -> qualia::enums::DeviceK
             return qualia::DeviceKind::Touchpad;
         } else if property.name() == INPUT_KEYBOARD {
             return qualia::DeviceKind::Keyboard;
+        } else if (property.name() == INPUT_TOUCHSCREEN) || (property.name() == INPUT_TABLET) {
+            return qualia::DeviceKind::Touchscreen;
+        } else if property.name() == INPUT_SWITCH {
+            return qualia::DeviceKind::Switch;
         }
     }
     qualia::DeviceKind::Unknown
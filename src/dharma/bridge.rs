@@ -63,6 +63,9 @@ pub type SignalId = usize;
 pub enum SpecialCommand {
     /// Request termination on receiver side.
     Terminate,
+
+    /// Request receiver to report it is alive (see `Watchdog`).
+    Ping,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -391,6 +394,11 @@ impl<T> Sender<T>
         }
     }
 
+    /// Returns number of connected `Receiver`s.
+    pub fn count_receivers(&self) -> usize {
+        self.bridges.len()
+    }
+
     /// Helper function to connect to `Receiver` with `Bridge`.
     fn add_bridge(&mut self, bridge: Bridge<T>) {
         self.bridges.push(bridge);
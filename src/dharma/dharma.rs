@@ -28,6 +28,15 @@
 //! allows to register `EventHandler`s (wrapping file descriptors) and invokes them when system
 //! events they are assigned are ready to be processed.
 //!
+//! ### `timer` module
+//!
+//! `Timer`s wrap `timerfd` so `Dispatcher` can invoke handlers after given time or periodically.
+//!
+//! ### `watchdog` module
+//!
+//! `Watchdog` collects reports of threads telling they are alive so ones which got stuck can be
+//! found.
+//!
 //! ### `system` module
 //!
 //! Last module contains helper code for and handling system signals.
@@ -36,6 +45,7 @@
 
 #[macro_use]
 extern crate bitflags;
+extern crate libc;
 extern crate nix;
 
 /// Communication between two endpoints in different threads.
@@ -47,7 +57,7 @@ pub use bridge::{DirectSender, Sender, Receiver, ReceiveResult, SignalId, Specia
 /// Notification sender.
 ///
 pub mod signaler;
-pub use signaler::Signaler;
+pub use signaler::{Signal, Signaler, Tracer};
 
 /// Implementation of main thread loop with notification listening.
 ///
@@ -60,6 +70,16 @@ pub use event_loop::{Module, ModuleConstructor, Service, ServiceConstructor};
 pub mod dispatcher;
 pub use dispatcher::{Dispatcher, EventHandler, EventHandlerId, EventKind, event_kind};
 
+/// Timers waited for in `Dispatcher`.
+///
+pub mod timer;
+pub use timer::{Timer, TimerEventHandler};
+
+/// Detecting stuck threads.
+///
+pub mod watchdog;
+pub use watchdog::{Heartbeat, ThreadReport, Watchdog};
+
 /// System signal handling.
 ///
 pub mod system;
@@ -10,6 +10,9 @@
 //! their constructors to `EventLoopInfo` structure which is context for creation on `EventLoop`.
 //!
 //! If `EventLoop` is not enough or too much, one can make new loop by implementing `Service` trait.
+//!
+//! `EventLoop` can be monitored by `Watchdog`: it reports it is alive when pinged and marks which
+//! signal its modules are processing.
 
 // -------------------------------------------------------------------------------------------------
 
@@ -19,6 +22,7 @@ use std::collections::btree_map::BTreeMap as Map;
 use bridge::{self, ReceiveResult, SpecialCommand};
 use signaler;
 use system;
+use watchdog::{Heartbeat, Watchdog};
 
 // -------------------------------------------------------------------------------------------------
 
@@ -95,6 +99,7 @@ pub struct EventLoopInfo<P, C>
     signaler: signaler::Signaler<P>,
     constructors: Vec<Box<ModuleConstructor<T = P, C = C>>>,
     context: C,
+    heartbeat: Option<Heartbeat>,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -110,9 +115,15 @@ impl<P, C> EventLoopInfo<P, C>
             signaler: signaler,
             constructors: Vec::new(),
             context: context,
+            heartbeat: None,
         }
     }
 
+    /// Registers event loop in `Watchdog` under its name.
+    pub fn set_watchdog(&mut self, mut watchdog: Watchdog) {
+        self.heartbeat = Some(watchdog.register(&self.name));
+    }
+
     /// Add module constructor.
     pub fn add_module(&mut self,
                       constructor: Box<ModuleConstructor<T = P, C = C>>) {
@@ -148,6 +159,7 @@ pub struct EventLoop<P, C>
     receiver: bridge::Receiver<P>,
     subscriptions: Map<bridge::SignalId, Vec<usize>>,
     context: C,
+    heartbeat: Option<Heartbeat>,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -168,6 +180,7 @@ impl<P, C> EventLoop<P, C>
             receiver: bridge::Receiver::new(),
             subscriptions: Map::new(),
             context: info.context,
+            heartbeat: info.heartbeat,
         };
 
         // Consume constructors to return module instances
@@ -219,10 +232,18 @@ impl<P, C> EventLoop<P, C>
                 ReceiveResult::Defined(id, package) => {
                     match self.subscriptions.get_mut(&id) {
                         Some(ref mut subscribers) => {
+                            if let Some(ref heartbeat) = self.heartbeat {
+                                heartbeat.begin(id);
+                            }
+
                             // Inform all subscriber about notification.
                             for i in subscribers.iter() {
                                 self.modules[*i].execute(&package);
                             }
+
+                            if let Some(ref heartbeat) = self.heartbeat {
+                                heartbeat.end();
+                            }
                         }
                         None => {
                             // Received signal we did not subscribe for.
@@ -235,6 +256,11 @@ impl<P, C> EventLoop<P, C>
                 ReceiveResult::Special(command) => {
                     match command {
                         SpecialCommand::Terminate => break,
+                        SpecialCommand::Ping => {
+                            if let Some(ref heartbeat) = self.heartbeat {
+                                heartbeat.beat();
+                            }
+                        }
                     }
                 }
 
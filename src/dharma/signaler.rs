@@ -4,23 +4,60 @@
 //! `Signaler` allows to send events (notifications) to other parts of application.
 //!
 //! Together with `EventLoop` constitutes higher level framework over `Sender` and `Receiver`.
+//!
+//! For debugging `Signaler` can trace emitted signals: when tracing is enabled every `emit` is
+//! reported to tracer function along with number of subscribers and time it took to dispatch the
+//! signal to them. Signals without subscribers are reported too, which helps to find events which
+//! are emitted but never received.
+//!
+//! Signals can be also identified by types implementing `Signal`. Such type ties signal ID with
+//! type of data carried along with it, so subscribing and emitting with `subscribe_signal` and
+//! `emit_signal` can not mix up signal ID with not matching data. Typed and untyped signals are
+//! dispatched the same way and can be used interchangeably.
 
 // -------------------------------------------------------------------------------------------------
 
 use std::clone::Clone;
 use std::collections::btree_map::BTreeMap as Map;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use bridge;
 
 // -------------------------------------------------------------------------------------------------
 
+/// Function receiving traces of emitted signals: signal ID, number of subscribers and dispatch
+/// latency. It is called with `Signaler` locked so it must not use the `Signaler` itself.
+pub type Tracer = Box<Fn(bridge::SignalId, usize, Duration) + Send>;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Type-level description of signal sent as package of type `P`.
+pub trait Signal<P> {
+    /// Type of data carried by the signal.
+    type Payload;
+
+    /// Returns ID of the signal.
+    fn id() -> bridge::SignalId;
+
+    /// Wraps data in package sent by `Signaler`.
+    fn wrap(payload: Self::Payload) -> P;
+
+    /// Extracts data from received package. Returns `None` if package does not belong to this
+    /// signal.
+    fn unwrap(package: P) -> Option<Self::Payload>;
+}
+
+// -------------------------------------------------------------------------------------------------
+
 /// Helper structure constituting shared memory between `Signaler`s from different threads.
 struct InnerSignaler<P>
     where P: Clone + Send
 {
     map: Map<bridge::SignalId, bridge::Sender<P>>,
     registry: Vec<bridge::DirectSender<P>>,
+    tracer: Option<Tracer>,
+    tracing: bool,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -45,6 +82,8 @@ impl<P> Signaler<P>
             inner: Arc::new(Mutex::new(InnerSignaler {
                                            map: Map::new(),
                                            registry: Vec::new(),
+                                           tracer: None,
+                                           tracing: false,
                                        })),
         }
     }
@@ -66,6 +105,13 @@ impl<P> Signaler<P>
         }
     }
 
+    /// Subscribe given `receiver` for signal `S`.
+    pub fn subscribe_signal<S>(&mut self, receiver: &bridge::Receiver<P>)
+        where S: Signal<P>
+    {
+        self.subscribe(S::id(), receiver);
+    }
+
     /// Register `receiver` for control instructions like request to terminate.
     pub fn register(&mut self, receiver: &bridge::Receiver<P>) {
         let mut mine = self.inner.lock().unwrap();
@@ -77,19 +123,61 @@ impl<P> Signaler<P>
     /// Emit signal `id` containing data `package`. All subscribed `Receiver`s will be notified.
     pub fn emit(&mut self, id: bridge::SignalId, package: P) {
         let mut mine = self.inner.lock().unwrap();
+        let start = if mine.tracing { Some(Instant::now()) } else { None };
 
         // Find sender assigned to signal
-        match mine.map.get_mut(&id) {
+        let subscribers = match mine.map.get_mut(&id) {
             Some(sender) => {
                 // Send package to all connected receivers
                 sender.send_defined(id, package);
+                sender.count_receivers()
             }
             None => {
                 // No one to notify
+                0
+            }
+        };
+
+        if let Some(start) = start {
+            if let Some(ref tracer) = mine.tracer {
+                tracer(id, subscribers, start.elapsed());
             }
         }
     }
 
+    /// Emit signal `S` carrying given data. All subscribed `Receiver`s will be notified.
+    pub fn emit_signal<S>(&mut self, payload: S::Payload)
+        where S: Signal<P>
+    {
+        self.emit(S::id(), S::wrap(payload));
+    }
+
+    /// Set function receiving traces of emitted signals. Tracer is used only when tracing is
+    /// enabled.
+    pub fn set_tracer(&mut self, tracer: Tracer) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.tracer = Some(tracer);
+    }
+
+    /// Enable or disable tracing of emitted signals.
+    pub fn set_tracing(&mut self, enabled: bool) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.tracing = enabled;
+    }
+
+    /// Check if tracing of emitted signals is enabled.
+    pub fn is_tracing(&self) -> bool {
+        let mine = self.inner.lock().unwrap();
+        mine.tracing
+    }
+
+    /// Return IDs of signals having at least one subscriber along with number of their
+    /// subscribers, sorted by signal ID.
+    pub fn get_subscriptions(&self) -> Vec<(bridge::SignalId, usize)> {
+        let mine = self.inner.lock().unwrap();
+        mine.map.iter().map(|(id, sender)| (*id, sender.count_receivers())).collect()
+    }
+
     /// Send `Terminate` instruction to registered `Receiver`s indicating `Signaler` (possibly whole
     /// application) is going to shut down.
     pub fn terminate(&mut self) {
@@ -98,6 +186,14 @@ impl<P> Signaler<P>
             sender.send_special(bridge::SpecialCommand::Terminate);
         }
     }
+
+    /// Send `Ping` instruction to registered `Receiver`s asking them to report they are alive.
+    pub fn ping(&mut self) {
+        let mut mine = self.inner.lock().unwrap();
+        for mut sender in mine.registry.iter_mut() {
+            sender.send_special(bridge::SpecialCommand::Ping);
+        }
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
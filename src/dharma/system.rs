@@ -13,35 +13,41 @@ use signaler::Signaler;
 
 // -------------------------------------------------------------------------------------------------
 
-/// Block signals `SIGINT` and `SIGTERM` for current thread.
+/// Block signals `SIGINT`, `SIGTERM` and `SIGHUP` for current thread.
 pub fn block_signals() {
     let mut mask = signal::SigSet::empty();
     mask.add(signal::SIGINT);
     mask.add(signal::SIGTERM);
+    mask.add(signal::SIGHUP);
     mask.thread_block().unwrap();
 }
 
 // -------------------------------------------------------------------------------------------------
 
-/// Unblock signals `SIGINT` and `SIGTERM` for current thread.
+/// Unblock signals `SIGINT`, `SIGTERM` and `SIGHUP` for current thread.
 pub fn unblock_signals() {
     let mut mask = signal::SigSet::empty();
     mask.add(signal::SIGINT);
     mask.add(signal::SIGTERM);
+    mask.add(signal::SIGHUP);
     mask.thread_unblock().unwrap();
 }
 
 // -------------------------------------------------------------------------------------------------
 
 /// Implementation of `dharma::EventHandler` for handling system signals synchronously. For this to
-/// work receiving of signals `SIGINT` and `SIGTERM` must be blocked in all threads in application.
-/// Otherwise non-blocking threads will catch all signals.
+/// work receiving of signals `SIGINT`, `SIGTERM` and `SIGHUP` must be blocked in all threads in
+/// application. Otherwise non-blocking threads will catch all signals.
+///
+/// `SIGINT` and `SIGTERM` stop the application. `SIGHUP` calls handler set with
+/// `set_hangup_handler` or is ignored if no handler was set.
 pub struct SignalEventHandler<P>
     where P: Clone + Send + 'static
 {
     fd: signalfd::SignalFd,
     dispatcher: Dispatcher,
     signaler: Signaler<P>,
+    hangup_handler: Option<Box<FnMut() + Send>>,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -49,18 +55,25 @@ pub struct SignalEventHandler<P>
 impl<P> SignalEventHandler<P>
     where P: Clone + Send + 'static
 {
-    /// `SignalEventHandler` constructor. Creates `SignalEventHandler` ready for handling `SIGINT`
-    /// and `SIGTERM` signals.
+    /// `SignalEventHandler` constructor. Creates `SignalEventHandler` ready for handling `SIGINT`,
+    /// `SIGTERM` and `SIGHUP` signals.
     pub fn new(dispatcher: Dispatcher, signaler: Signaler<P>) -> Self {
         let mut mask = signal::SigSet::empty();
         mask.add(signal::SIGINT);
         mask.add(signal::SIGTERM);
+        mask.add(signal::SIGHUP);
         SignalEventHandler {
             fd: signalfd::SignalFd::new(&mask).unwrap(),
             dispatcher: dispatcher,
             signaler: signaler,
+            hangup_handler: None,
         }
     }
+
+    /// Sets function to be called when `SIGHUP` is received.
+    pub fn set_hangup_handler(&mut self, handler: Box<FnMut() + Send>) {
+        self.hangup_handler = Some(handler);
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -82,6 +95,10 @@ impl<P> EventHandler for SignalEventHandler<P>
                            (ssi.ssi_signo == signal::SIGTERM as u32) {
                             self.dispatcher.stop();
                             self.signaler.terminate();
+                        } else if ssi.ssi_signo == signal::SIGHUP as u32 {
+                            if let Some(ref mut handler) = self.hangup_handler {
+                                handler();
+                            }
                         }
                     }
                     None => {
@@ -8,6 +8,7 @@
 extern crate dharma;
 
 use std::{thread, time};
+use std::sync::{Arc, Mutex};
 
 // -------------------------------------------------------------------------------------------------
 
@@ -152,3 +153,96 @@ fn test_subscribe_one_recever_threaded() {
 }
 
 // -------------------------------------------------------------------------------------------------
+
+/// Subscribe `Receiver`s for signals and check if subscriptions are listed with correct number of
+/// subscribers.
+#[test]
+fn test_list_subscriptions() {
+    let r1: EventReceiver = dharma::Receiver::new();
+    let r2: EventReceiver = dharma::Receiver::new();
+    let mut s: dharma::Signaler<String> = dharma::Signaler::new();
+    assert_eq!(s.get_subscriptions(), vec![]);
+
+    s.subscribe(3, &r1);
+    s.subscribe(1, &r1);
+    s.subscribe(3, &r2);
+    assert_eq!(s.get_subscriptions(), vec![(1, 1), (3, 2)]);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Enable tracing and perform `emit`s. Every `emit`, including ones without subscribers, should be
+/// reported to tracer with number of subscribers. Nothing should be reported when tracing is
+/// disabled.
+#[test]
+fn test_trace_emits() {
+    let r: EventReceiver = dharma::Receiver::new();
+    let mut s = dharma::Signaler::new();
+    let traces = Arc::new(Mutex::new(Vec::new()));
+    let tracer_traces = traces.clone();
+    s.set_tracer(Box::new(move |id, subscribers, _| {
+        tracer_traces.lock().unwrap().push((id, subscribers));
+    }));
+    s.subscribe(0, &r);
+
+    s.emit(0, String::from(H1));
+    assert!(!s.is_tracing());
+    s.set_tracing(true);
+    s.emit(0, String::from(H2));
+    s.emit(1, String::from(H3));
+    s.set_tracing(false);
+    s.emit(0, String::from(T));
+    assert_eq!(*traces.lock().unwrap(), vec![(0, 1), (1, 0)]);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Signal used in tests of typed signals. Carries greeted name.
+struct Greeting;
+
+impl dharma::Signal<String> for Greeting {
+    type Payload = String;
+
+    fn id() -> dharma::SignalId {
+        3
+    }
+
+    fn wrap(payload: String) -> String {
+        format!("hello {}", payload)
+    }
+
+    fn unwrap(package: String) -> Option<String> {
+        if package.starts_with("hello ") {
+            Some(package[6..].to_owned())
+        } else {
+            None
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Subscribe and emit typed signal. `Receiver` should receive wrapped data under ID of the signal
+/// and the data should be possible to extract back.
+#[test]
+fn test_typed_signal() {
+    let d = time::Duration::new(1, 0);
+    let mut r: EventReceiver = dharma::Receiver::new();
+    let mut s = dharma::Signaler::new();
+
+    s.subscribe_signal::<Greeting>(&r);
+    s.emit_signal::<Greeting>(String::from("world"));
+    s.emit(0, String::from(T));
+
+    match r.recv_timeout(d) {
+        dharma::ReceiveResult::Defined(id, package) => {
+            assert_eq!(id, 3);
+            let payload = <Greeting as dharma::Signal<String>>::unwrap(package);
+            assert_eq!(payload, Some(String::from("world")));
+        }
+        _ => panic!("Expected defined result"),
+    }
+    assert!(r.try_recv().is_empty());
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,70 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Unit tests for `dharma::timer` module.
+
+// -------------------------------------------------------------------------------------------------
+
+extern crate dharma;
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use dharma::{event_kind, Dispatcher, Timer, TimerEventHandler};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Maximal time in milliseconds to wait for expiration of timer.
+const WAIT: usize = 500;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Prepares `Dispatcher` with handler of given timer counting expirations.
+fn prepare(timer: &Timer) -> (Dispatcher, Arc<Mutex<u32>>) {
+    let counter = Arc::new(Mutex::new(0));
+    let handler_counter = counter.clone();
+    let handler = Box::new(move || *handler_counter.lock().unwrap() += 1);
+
+    let mut dispatcher = Dispatcher::new();
+    dispatcher.add_source(Box::new(TimerEventHandler::new(timer.clone(), handler)),
+                          event_kind::READ);
+    (dispatcher, counter)
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if armed timer expires once and periodic timer expires repeatedly.
+#[test]
+fn test_timer_expiration() {
+    let timer = Timer::new();
+    let (dispatcher, counter) = prepare(&timer);
+
+    timer.arm(Duration::from_millis(10), None);
+    dispatcher.wait_and_process(Some(WAIT));
+    assert_eq!(*counter.lock().unwrap(), 1);
+
+    dispatcher.wait_and_process(Some(50));
+    assert_eq!(*counter.lock().unwrap(), 1);
+
+    timer.arm(Duration::from_millis(10), Some(Duration::from_millis(10)));
+    dispatcher.wait_and_process(Some(WAIT));
+    dispatcher.wait_and_process(Some(WAIT));
+    dispatcher.wait_and_process(Some(WAIT));
+    assert_eq!(*counter.lock().unwrap(), 4);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if disarmed timer does not expire.
+#[test]
+fn test_timer_disarm() {
+    let timer = Timer::new();
+    let (dispatcher, counter) = prepare(&timer);
+
+    timer.arm(Duration::from_millis(20), Some(Duration::from_millis(20)));
+    timer.disarm();
+    dispatcher.wait_and_process(Some(100));
+    assert_eq!(*counter.lock().unwrap(), 0);
+}
+
+// -------------------------------------------------------------------------------------------------
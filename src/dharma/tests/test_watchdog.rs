@@ -0,0 +1,84 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Unit tests for `dharma::watchdog` module.
+
+// -------------------------------------------------------------------------------------------------
+
+extern crate dharma;
+
+use std::thread;
+use std::time::Duration;
+
+use dharma::{ReceiveResult, SpecialCommand, Watchdog};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Time after which threads are considered stuck in tests.
+const TIMEOUT: u64 = 20;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if only threads which did not beat within timeout are reported as stuck.
+#[test]
+fn test_finding_stuck_threads() {
+    let mut watchdog = Watchdog::new();
+    let first = watchdog.register("first");
+    let second = watchdog.register("second");
+    let timeout = Duration::from_millis(TIMEOUT);
+
+    assert!(watchdog.find_stuck(timeout).is_empty());
+
+    thread::sleep(Duration::from_millis(2 * TIMEOUT));
+    second.beat();
+
+    let stuck = watchdog.find_stuck(timeout);
+    assert_eq!(stuck.len(), 1);
+    assert_eq!(stuck[0].name, "first");
+
+    first.beat();
+    assert!(watchdog.find_stuck(timeout).is_empty());
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if signal being processed is reported and finishing processing counts as beat.
+#[test]
+fn test_reporting_processed_signal() {
+    let mut watchdog = Watchdog::new();
+    let heartbeat = watchdog.register("thread");
+    let timeout = Duration::from_millis(TIMEOUT);
+
+    heartbeat.begin(7);
+    thread::sleep(Duration::from_millis(2 * TIMEOUT));
+
+    let stuck = watchdog.find_stuck(timeout);
+    assert_eq!(stuck.len(), 1);
+    assert_eq!(stuck[0].signal.map(|(id, _)| id), Some(7));
+    assert!(stuck[0].signal.unwrap().1 >= timeout);
+
+    heartbeat.end();
+    let reports = watchdog.get_reports();
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].signal.is_none());
+    assert!(watchdog.find_stuck(timeout).is_empty());
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if `Signaler` sends `Ping` to registered receivers.
+#[test]
+fn test_ping() {
+    let mut r: dharma::Receiver<String> = dharma::Receiver::new();
+    let mut s = dharma::Signaler::new();
+
+    s.register(&r);
+    s.ping();
+
+    match r.recv_timeout(Duration::from_millis(100)) {
+        ReceiveResult::Special(command) => assert_eq!(command, SpecialCommand::Ping),
+        _ => panic!("Expected ping"),
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
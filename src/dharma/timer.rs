@@ -0,0 +1,151 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains timers which can be waited for in `Dispatcher`.
+//!
+//! `Timer` is a handle to `timerfd` which can be armed and disarmed from any thread.
+//! `TimerEventHandler` calls given function when the timer expires.
+
+// -------------------------------------------------------------------------------------------------
+
+use std::os::unix::io::RawFd;
+use std::sync::Arc;
+use std::time::Duration;
+use std::{mem, ptr};
+
+use libc;
+use nix::unistd;
+
+use dispatcher::{EventHandler, EventKind};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Owner of timer file descriptor. Closes it when dropped.
+struct TimerFd {
+    fd: RawFd,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Drop for TimerFd {
+    fn drop(&mut self) {
+        unistd::close(self.fd).ok();
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Handle to monotonic timer. Clones refer to the same timer.
+#[derive(Clone)]
+pub struct Timer {
+    inner: Arc<TimerFd>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Timer {
+    /// `Timer` constructor. Timer is initially disarmed.
+    pub fn new() -> Self {
+        let fd = unsafe {
+            libc::timerfd_create(libc::CLOCK_MONOTONIC, libc::TFD_NONBLOCK | libc::TFD_CLOEXEC)
+        };
+        if fd < 0 {
+            panic!("Failed to create timer!");
+        }
+        Timer { inner: Arc::new(TimerFd { fd: fd }) }
+    }
+
+    /// Arms the timer to expire after `delay` and then every `interval` if given. Rearming timer
+    /// cancels previous setting.
+    pub fn arm(&self, delay: Duration, interval: Option<Duration>) {
+        // Zero value would disarm the timer.
+        let delay = if delay == Duration::new(0, 0) {
+            Duration::new(0, 1)
+        } else {
+            delay
+        };
+        let interval = interval.unwrap_or(Duration::new(0, 0));
+        self.set(delay, interval);
+    }
+
+    /// Disarms the timer. It will not expire until armed again.
+    pub fn disarm(&self) {
+        self.set(Duration::new(0, 0), Duration::new(0, 0));
+    }
+
+    /// Returns file descriptor of the timer.
+    pub fn get_fd(&self) -> RawFd {
+        self.inner.fd
+    }
+
+    /// Reads number of expirations since last read. Returns zero if timer did not expire.
+    pub fn read(&self) -> u64 {
+        let mut buffer = [0u8; 8];
+        match unistd::read(self.inner.fd, &mut buffer) {
+            Ok(8) => unsafe { mem::transmute::<[u8; 8], u64>(buffer) },
+            _ => 0,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Private methods.
+impl Timer {
+    /// Sets expiration time and interval of the timer.
+    fn set(&self, value: Duration, interval: Duration) {
+        let spec = libc::itimerspec {
+            it_interval: Self::to_timespec(interval),
+            it_value: Self::to_timespec(value),
+        };
+        let result = unsafe { libc::timerfd_settime(self.inner.fd, 0, &spec, ptr::null_mut()) };
+        if result < 0 {
+            panic!("Failed to set timer!");
+        }
+    }
+
+    /// Converts `Duration` to `timespec`.
+    fn to_timespec(duration: Duration) -> libc::timespec {
+        libc::timespec {
+            tv_sec: duration.as_secs() as libc::time_t,
+            tv_nsec: duration.subsec_nanos() as libc::c_long,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Implementation of `EventHandler` calling given function when timer expires. If the timer
+/// expired more than once since last processing the function is called only once.
+pub struct TimerEventHandler {
+    timer: Timer,
+    handler: Box<FnMut() + Send>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl TimerEventHandler {
+    /// `TimerEventHandler` constructor.
+    pub fn new(timer: Timer, handler: Box<FnMut() + Send>) -> Self {
+        TimerEventHandler {
+            timer: timer,
+            handler: handler,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl EventHandler for TimerEventHandler {
+    fn get_fd(&self) -> RawFd {
+        self.timer.get_fd()
+    }
+
+    fn process_event(&mut self, _: EventKind) {
+        if self.timer.read() > 0 {
+            (self.handler)();
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
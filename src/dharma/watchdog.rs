@@ -0,0 +1,122 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! `Watchdog` detects threads which stopped responding.
+//!
+//! Every monitored thread registers in `Watchdog` and gets `Heartbeat` with which it reports it is
+//! alive. Threads waiting for events would not report anything while idle, so `Signaler::ping`
+//! sends `Ping` command to all registered receivers and threads are expected to `beat` when they
+//! receive it. Threads also mark which signal they are processing so report of stuck thread tells
+//! where it got stuck.
+
+// -------------------------------------------------------------------------------------------------
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use bridge::SignalId;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Liveness information about single thread.
+struct Record {
+    name: String,
+    last_beat: Instant,
+    signal: Option<(SignalId, Instant)>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// State of monitored thread at time of `Watchdog` check.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThreadReport {
+    /// Name under which thread was registered.
+    pub name: String,
+
+    /// Time since thread last reported it is alive.
+    pub since_beat: Duration,
+
+    /// ID of signal thread is processing and time since it started processing it.
+    pub signal: Option<(SignalId, Duration)>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Handle used by thread to report it is alive. Clones refer to the same thread.
+#[derive(Clone)]
+pub struct Heartbeat {
+    record: Arc<Mutex<Record>>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Heartbeat {
+    /// Reports thread is alive.
+    pub fn beat(&self) {
+        let mut record = self.record.lock().unwrap();
+        record.last_beat = Instant::now();
+    }
+
+    /// Marks thread started processing signal with given ID.
+    pub fn begin(&self, id: SignalId) {
+        let mut record = self.record.lock().unwrap();
+        record.signal = Some((id, Instant::now()));
+    }
+
+    /// Marks thread finished processing signal. Finishing processing is also reported as beat.
+    pub fn end(&self) {
+        let mut record = self.record.lock().unwrap();
+        record.signal = None;
+        record.last_beat = Instant::now();
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Register of monitored threads. Clones refer to the same register.
+#[derive(Clone)]
+pub struct Watchdog {
+    records: Arc<Mutex<Vec<Arc<Mutex<Record>>>>>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Watchdog {
+    /// `Watchdog` constructor.
+    pub fn new() -> Self {
+        Watchdog { records: Arc::new(Mutex::new(Vec::new())) }
+    }
+
+    /// Starts monitoring thread with given name. Thread is considered alive at registration.
+    pub fn register(&mut self, name: &str) -> Heartbeat {
+        let record = Arc::new(Mutex::new(Record {
+                                             name: name.to_owned(),
+                                             last_beat: Instant::now(),
+                                             signal: None,
+                                         }));
+        self.records.lock().unwrap().push(record.clone());
+        Heartbeat { record: record }
+    }
+
+    /// Returns state of all monitored threads in order of registration.
+    pub fn get_reports(&self) -> Vec<ThreadReport> {
+        let records = self.records.lock().unwrap();
+        records.iter()
+            .map(|record| {
+                let record = record.lock().unwrap();
+                ThreadReport {
+                    name: record.name.clone(),
+                    since_beat: record.last_beat.elapsed(),
+                    signal: record.signal.map(|(id, start)| (id, start.elapsed())),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns state of threads which did not report they are alive for longer than `timeout`.
+    pub fn find_stuck(&self, timeout: Duration) -> Vec<ThreadReport> {
+        self.get_reports().into_iter().filter(|report| report.since_beat > timeout).collect()
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,251 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains functionality related to hiding layer surfaces (e.g. docks) off the output
+//! until pointer reaches the edge they are attached to.
+
+// -------------------------------------------------------------------------------------------------
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use dharma::{event_kind, Dispatcher, Signaler, Timer, TimerEventHandler};
+use qualia::{perceptron, Perceptron};
+use qualia::{Area, AutoHideConfig, LayerSurface, Position, SurfaceId, Vector};
+use qualia::positioner::edges;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Interval between animation frames.
+const FRAME_INTERVAL_MS: u64 = 16;
+
+// -------------------------------------------------------------------------------------------------
+
+/// State of single automatically hidden surface.
+struct Hiding {
+    /// Tells if the surface is (being) revealed or concealed.
+    shown: bool,
+
+    /// Time of last change of `shown`.
+    changed: Instant,
+
+    /// Visibility of the surface at time of last change.
+    from: f32,
+
+    /// Change of `shown` waiting for dwell time to pass and time when it should be applied.
+    pending: Option<(bool, Instant)>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Decides which layer surfaces are hidden and how far they are slid off the output.
+///
+/// Timer emits notifications while any surface waits for dwell time to pass or slides so displays
+/// get redrawn.
+pub struct AutoHide {
+    config: AutoHideConfig,
+    surfaces: HashMap<SurfaceId, Hiding>,
+    timer: Timer,
+    timer_armed: bool,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl AutoHide {
+    /// Constructs new `AutoHide`. Animation timer is added to given `Dispatcher`.
+    pub fn new(signaler: Signaler<Perceptron>,
+               mut dispatcher: Dispatcher,
+               config: AutoHideConfig)
+               -> Self {
+        let timer = Timer::new();
+        let mut timer_signaler = signaler;
+        let handler = Box::new(move || {
+            timer_signaler.emit(perceptron::NOTIFY, Perceptron::Notify);
+        });
+        dispatcher.add_source(Box::new(TimerEventHandler::new(timer.clone(), handler)),
+                              event_kind::READ);
+
+        AutoHide {
+            config: config,
+            surfaces: HashMap::new(),
+            timer: timer,
+            timer_armed: false,
+        }
+    }
+
+    /// Returns current configuration.
+    pub fn get_config(&self) -> &AutoHideConfig {
+        &self.config
+    }
+
+    /// Sets new configuration. All surfaces are hidden again.
+    pub fn set_config(&mut self, config: AutoHideConfig) {
+        self.config = config;
+        self.surfaces.clear();
+        self.update_timer();
+    }
+
+    /// Checks if given surface should be hidden automatically: its namespace is configured and it
+    /// is attached to single edge of output.
+    pub fn is_enabled_for(&self, surface: &LayerSurface) -> bool {
+        self.config.namespaces.contains(&surface.namespace) && surface.get_anchored_edge().is_some()
+    }
+
+    /// Updates pointer position for surface attached to given edge of output. `area` is area of
+    /// the surface when fully shown.
+    ///
+    /// Surface is revealed when pointer is held at the edge of output within extent of the surface
+    /// and concealed when pointer leaves both the surface and the edge.
+    pub fn update(&mut self,
+                  sid: SurfaceId,
+                  edge: edges::Edges,
+                  area: Area,
+                  output: Area,
+                  position: Position) {
+        let at_edge = is_at_edge(edge, &area, &output, &position);
+        let now = Instant::now();
+        let reveal_delay = Duration::from_millis(self.config.reveal_delay);
+        let conceal_delay = Duration::from_millis(self.config.conceal_delay);
+
+        {
+            let hiding = self.surfaces.entry(sid).or_insert_with(|| Hiding::new(now));
+            let shown = at_edge || (hiding.shown && area.contains(&position));
+            if shown == hiding.shown {
+                hiding.pending = None;
+            } else if hiding.pending.map(|(pending, _)| pending) != Some(shown) {
+                let delay = if shown { reveal_delay } else { conceal_delay };
+                hiding.pending = Some((shown, now + delay));
+            }
+        }
+        self.update_timer();
+    }
+
+    /// Returns visibility of given surface: zero if it is hidden, one if it is fully shown.
+    pub fn get_visibility(&mut self, sid: SurfaceId) -> f32 {
+        let now = Instant::now();
+        let duration = Duration::from_millis(self.config.animation_time);
+        let visibility = {
+            let hiding = self.surfaces.entry(sid).or_insert_with(|| Hiding::new(now));
+            hiding.apply_pending(now, duration);
+            hiding.get_visibility(now, duration)
+        };
+        self.update_timer();
+        visibility
+    }
+
+    /// Stops tracking given surface.
+    pub fn remove(&mut self, sid: SurfaceId) {
+        self.surfaces.remove(&sid);
+        self.update_timer();
+    }
+
+    /// Arms timer if any surface waits for dwell time or slides, disarms it otherwise.
+    fn update_timer(&mut self) {
+        let now = Instant::now();
+        let duration = Duration::from_millis(self.config.animation_time);
+        let busy = self.surfaces.values().any(|hiding| hiding.is_busy(now, duration));
+        if busy && !self.timer_armed {
+            let interval = Duration::from_millis(FRAME_INTERVAL_MS);
+            self.timer.arm(interval, Some(interval));
+            self.timer_armed = true;
+        } else if !busy && self.timer_armed {
+            self.timer.disarm();
+            self.timer_armed = false;
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Hiding {
+    /// Constructs new `Hiding` for concealed surface.
+    fn new(now: Instant) -> Self {
+        Hiding {
+            shown: false,
+            changed: now,
+            from: 0.0,
+            pending: None,
+        }
+    }
+
+    /// Applies pending change if its dwell time passed.
+    fn apply_pending(&mut self, now: Instant, duration: Duration) {
+        if let Some((shown, at)) = self.pending {
+            if now >= at {
+                self.from = self.get_visibility(now, duration);
+                self.shown = shown;
+                self.changed = now;
+                self.pending = None;
+            }
+        }
+    }
+
+    /// Returns visibility of the surface at given time.
+    fn get_visibility(&self, now: Instant, duration: Duration) -> f32 {
+        let target = if self.shown { 1.0 } else { 0.0 };
+        let progress = get_progress(now.duration_since(self.changed), duration);
+        self.from + (target - self.from) * progress
+    }
+
+    /// Checks if the surface waits for dwell time or slides.
+    fn is_busy(&self, now: Instant, duration: Duration) -> bool {
+        let target = if self.shown { 1.0 } else { 0.0 };
+        let sliding = (self.from != target) &&
+                      (get_progress(now.duration_since(self.changed), duration) < 1.0);
+        self.pending.is_some() || sliding
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Returns vector by which surface attached to given edge should be moved to be shown with given
+/// visibility. Fully hidden surface is placed just outside the output.
+pub fn get_offset(edge: edges::Edges, area: &Area, output: &Area, visibility: f32) -> Vector {
+    let distance = match edge {
+        edges::TOP => area.pos.y + area.size.height as isize - output.pos.y,
+        edges::BOTTOM => output.pos.y + output.size.height as isize - area.pos.y,
+        edges::LEFT => area.pos.x + area.size.width as isize - output.pos.x,
+        edges::RIGHT => output.pos.x + output.size.width as isize - area.pos.x,
+        _ => 0,
+    };
+    let distance = (distance as f32 * (1.0 - visibility)).round() as isize;
+    match edge {
+        edges::TOP => Vector::new(0, -distance),
+        edges::BOTTOM => Vector::new(0, distance),
+        edges::LEFT => Vector::new(-distance, 0),
+        edges::RIGHT => Vector::new(distance, 0),
+        _ => Vector::default(),
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Checks if pointer is placed at given edge of output within extent of surface with given area.
+fn is_at_edge(edge: edges::Edges, area: &Area, output: &Area, position: &Position) -> bool {
+    let within_x = (position.x >= area.pos.x) &&
+                   (position.x < area.pos.x + area.size.width as isize);
+    let within_y = (position.y >= area.pos.y) &&
+                   (position.y < area.pos.y + area.size.height as isize);
+    match edge {
+        edges::TOP => within_x && (position.y <= output.pos.y),
+        edges::BOTTOM => within_x && (position.y >= output.pos.y + output.size.height as isize - 1),
+        edges::LEFT => within_y && (position.x <= output.pos.x),
+        edges::RIGHT => within_y && (position.x >= output.pos.x + output.size.width as isize - 1),
+        _ => false,
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Returns progress of animation of given duration after given time, between zero and one.
+fn get_progress(elapsed: Duration, duration: Duration) -> f32 {
+    let duration = duration.as_secs() as f32 * 1000.0 + duration.subsec_nanos() as f32 / 1.0e6;
+    if duration > 0.0 {
+        let elapsed = elapsed.as_secs() as f32 * 1000.0 + elapsed.subsec_nanos() as f32 / 1.0e6;
+        (elapsed / duration).min(1.0)
+    } else {
+        1.0
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,247 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains simple software drawing of shapes and text used for elements drawn by
+//! compositor itself (e.g. window decorations).
+//!
+//! Text is drawn with built-in 5x7 bitmap font covering printable ASCII characters. Other
+//! characters are drawn as question marks.
+
+// -------------------------------------------------------------------------------------------------
+
+use std;
+
+use qualia::{Area, Buffer, Position, Size};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Color in format used by clients (blue, green, red, alpha).
+pub type Color = [u8; 4];
+
+/// Width of single glyph.
+pub const GLYPH_WIDTH: usize = 5;
+
+/// Height of single glyph.
+pub const GLYPH_HEIGHT: usize = 7;
+
+/// Space between glyphs.
+const GLYPH_SPACING: usize = 1;
+
+/// First character covered by the font.
+const FIRST_CHAR: u8 = 0x20;
+
+/// Glyphs of printable ASCII characters. Every byte is one column with the least significant bit
+/// at the top.
+const FONT: [[u8; GLYPH_WIDTH]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00], // ' '
+    [0x00, 0x00, 0x5F, 0x00, 0x00], // '!'
+    [0x00, 0x07, 0x00, 0x07, 0x00], // '"'
+    [0x14, 0x7F, 0x14, 0x7F, 0x14], // '#'
+    [0x24, 0x2A, 0x7F, 0x2A, 0x12], // '$'
+    [0x23, 0x13, 0x08, 0x64, 0x62], // '%'
+    [0x36, 0x49, 0x55, 0x22, 0x50], // '&'
+    [0x00, 0x05, 0x03, 0x00, 0x00], // '''
+    [0x00, 0x1C, 0x22, 0x41, 0x00], // '('
+    [0x00, 0x41, 0x22, 0x1C, 0x00], // ')'
+    [0x08, 0x2A, 0x1C, 0x2A, 0x08], // '*'
+    [0x08, 0x08, 0x3E, 0x08, 0x08], // '+'
+    [0x00, 0x50, 0x30, 0x00, 0x00], // ','
+    [0x08, 0x08, 0x08, 0x08, 0x08], // '-'
+    [0x00, 0x60, 0x60, 0x00, 0x00], // '.'
+    [0x20, 0x10, 0x08, 0x04, 0x02], // '/'
+    [0x3E, 0x51, 0x49, 0x45, 0x3E], // '0'
+    [0x00, 0x42, 0x7F, 0x40, 0x00], // '1'
+    [0x42, 0x61, 0x51, 0x49, 0x46], // '2'
+    [0x21, 0x41, 0x45, 0x4B, 0x31], // '3'
+    [0x18, 0x14, 0x12, 0x7F, 0x10], // '4'
+    [0x27, 0x45, 0x45, 0x45, 0x39], // '5'
+    [0x3C, 0x4A, 0x49, 0x49, 0x30], // '6'
+    [0x01, 0x71, 0x09, 0x05, 0x03], // '7'
+    [0x36, 0x49, 0x49, 0x49, 0x36], // '8'
+    [0x06, 0x49, 0x49, 0x29, 0x1E], // '9'
+    [0x00, 0x36, 0x36, 0x00, 0x00], // ':'
+    [0x00, 0x56, 0x36, 0x00, 0x00], // ';'
+    [0x08, 0x14, 0x22, 0x41, 0x00], // '<'
+    [0x14, 0x14, 0x14, 0x14, 0x14], // '='
+    [0x00, 0x41, 0x22, 0x14, 0x08], // '>'
+    [0x02, 0x01, 0x51, 0x09, 0x06], // '?'
+    [0x32, 0x49, 0x79, 0x41, 0x3E], // '@'
+    [0x7E, 0x11, 0x11, 0x11, 0x7E], // 'A'
+    [0x7F, 0x49, 0x49, 0x49, 0x36], // 'B'
+    [0x3E, 0x41, 0x41, 0x41, 0x22], // 'C'
+    [0x7F, 0x41, 0x41, 0x22, 0x1C], // 'D'
+    [0x7F, 0x49, 0x49, 0x49, 0x41], // 'E'
+    [0x7F, 0x09, 0x09, 0x09, 0x01], // 'F'
+    [0x3E, 0x41, 0x49, 0x49, 0x7A], // 'G'
+    [0x7F, 0x08, 0x08, 0x08, 0x7F], // 'H'
+    [0x00, 0x41, 0x7F, 0x41, 0x00], // 'I'
+    [0x20, 0x40, 0x41, 0x3F, 0x01], // 'J'
+    [0x7F, 0x08, 0x14, 0x22, 0x41], // 'K'
+    [0x7F, 0x40, 0x40, 0x40, 0x40], // 'L'
+    [0x7F, 0x02, 0x0C, 0x02, 0x7F], // 'M'
+    [0x7F, 0x04, 0x08, 0x10, 0x7F], // 'N'
+    [0x3E, 0x41, 0x41, 0x41, 0x3E], // 'O'
+    [0x7F, 0x09, 0x09, 0x09, 0x06], // 'P'
+    [0x3E, 0x41, 0x51, 0x21, 0x5E], // 'Q'
+    [0x7F, 0x09, 0x19, 0x29, 0x46], // 'R'
+    [0x46, 0x49, 0x49, 0x49, 0x31], // 'S'
+    [0x01, 0x01, 0x7F, 0x01, 0x01], // 'T'
+    [0x3F, 0x40, 0x40, 0x40, 0x3F], // 'U'
+    [0x1F, 0x20, 0x40, 0x20, 0x1F], // 'V'
+    [0x3F, 0x40, 0x38, 0x40, 0x3F], // 'W'
+    [0x63, 0x14, 0x08, 0x14, 0x63], // 'X'
+    [0x07, 0x08, 0x70, 0x08, 0x07], // 'Y'
+    [0x61, 0x51, 0x49, 0x45, 0x43], // 'Z'
+    [0x00, 0x7F, 0x41, 0x41, 0x00], // '['
+    [0x02, 0x04, 0x08, 0x10, 0x20], // '\'
+    [0x00, 0x41, 0x41, 0x7F, 0x00], // ']'
+    [0x04, 0x02, 0x01, 0x02, 0x04], // '^'
+    [0x40, 0x40, 0x40, 0x40, 0x40], // '_'
+    [0x00, 0x01, 0x02, 0x04, 0x00], // '`'
+    [0x20, 0x54, 0x54, 0x54, 0x78], // 'a'
+    [0x7F, 0x48, 0x44, 0x44, 0x38], // 'b'
+    [0x38, 0x44, 0x44, 0x44, 0x20], // 'c'
+    [0x38, 0x44, 0x44, 0x48, 0x7F], // 'd'
+    [0x38, 0x54, 0x54, 0x54, 0x18], // 'e'
+    [0x08, 0x7E, 0x09, 0x01, 0x02], // 'f'
+    [0x0C, 0x52, 0x52, 0x52, 0x3E], // 'g'
+    [0x7F, 0x08, 0x04, 0x04, 0x78], // 'h'
+    [0x00, 0x44, 0x7D, 0x40, 0x00], // 'i'
+    [0x20, 0x40, 0x44, 0x3D, 0x00], // 'j'
+    [0x7F, 0x10, 0x28, 0x44, 0x00], // 'k'
+    [0x00, 0x41, 0x7F, 0x40, 0x00], // 'l'
+    [0x7C, 0x04, 0x18, 0x04, 0x78], // 'm'
+    [0x7C, 0x08, 0x04, 0x04, 0x78], // 'n'
+    [0x38, 0x44, 0x44, 0x44, 0x38], // 'o'
+    [0x7C, 0x14, 0x14, 0x14, 0x08], // 'p'
+    [0x08, 0x14, 0x14, 0x18, 0x7C], // 'q'
+    [0x7C, 0x08, 0x04, 0x04, 0x08], // 'r'
+    [0x48, 0x54, 0x54, 0x54, 0x20], // 's'
+    [0x04, 0x3F, 0x44, 0x40, 0x20], // 't'
+    [0x3C, 0x40, 0x40, 0x20, 0x7C], // 'u'
+    [0x1C, 0x20, 0x40, 0x20, 0x1C], // 'v'
+    [0x3C, 0x40, 0x30, 0x40, 0x3C], // 'w'
+    [0x44, 0x28, 0x10, 0x28, 0x44], // 'x'
+    [0x0C, 0x50, 0x50, 0x50, 0x3C], // 'y'
+    [0x44, 0x64, 0x54, 0x4C, 0x44], // 'z'
+    [0x00, 0x08, 0x36, 0x41, 0x00], // '{'
+    [0x00, 0x00, 0x7F, 0x00, 0x00], // '|'
+    [0x00, 0x41, 0x36, 0x08, 0x00], // '}'
+    [0x08, 0x04, 0x08, 0x10, 0x08], // '~'
+];
+
+// -------------------------------------------------------------------------------------------------
+
+/// Returns glyph for given character.
+fn get_glyph(c: char) -> &'static [u8; GLYPH_WIDTH] {
+    let code = c as u32;
+    if (FIRST_CHAR as u32 <= code) && (code < FIRST_CHAR as u32 + FONT.len() as u32) {
+        &FONT[(code - FIRST_CHAR as u32) as usize]
+    } else {
+        &FONT[('?' as u8 - FIRST_CHAR) as usize]
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Image in memory compositor can draw on. Initially fully transparent.
+pub struct Canvas {
+    size: Size,
+    data: Vec<u8>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Canvas {
+    /// Constructs new transparent `Canvas` of given size.
+    pub fn new(size: Size) -> Self {
+        Canvas {
+            size: size,
+            data: vec![0; 4 * size.width * size.height],
+        }
+    }
+
+    /// Returns width of given text when drawn.
+    pub fn measure_text(text: &str) -> usize {
+        let len = text.chars().count();
+        if len > 0 {
+            len * (GLYPH_WIDTH + GLYPH_SPACING) - GLYPH_SPACING
+        } else {
+            0
+        }
+    }
+
+    /// Sets color of single pixel. Pixels outside of canvas are ignored.
+    pub fn put_pixel(&mut self, x: isize, y: isize, color: Color) {
+        if (0 <= x) && (x < self.size.width as isize) && (0 <= y) &&
+           (y < self.size.height as isize) {
+            let index = 4 * (y as usize * self.size.width + x as usize);
+            self.data[index..index + 4].copy_from_slice(&color);
+        }
+    }
+
+    /// Fills given area with given color. Area is clipped to the canvas.
+    pub fn fill(&mut self, area: Area, color: Color) {
+        let bounds = Area::new(Position::default(), self.size);
+        if let Some(area) = area.intersected(&bounds) {
+            for y in area.pos.y..(area.pos.y + area.size.height as isize) {
+                for x in area.pos.x..(area.pos.x + area.size.width as isize) {
+                    self.put_pixel(x, y, color);
+                }
+            }
+        }
+    }
+
+    /// Draws one pixel wide outline of given area.
+    pub fn draw_outline(&mut self, area: Area, color: Color) {
+        let (width, height) = (area.size.width, area.size.height);
+        let (x, y) = (area.pos.x, area.pos.y);
+        self.fill(Area::create(x, y, width, 1), color);
+        self.fill(Area::create(x, y + height as isize - 1, width, 1), color);
+        self.fill(Area::create(x, y, 1, height), color);
+        self.fill(Area::create(x + width as isize - 1, y, 1, height), color);
+    }
+
+    /// Draws both diagonals of given area.
+    pub fn draw_cross(&mut self, area: Area, color: Color) {
+        let length = std::cmp::min(area.size.width, area.size.height) as isize;
+        let right = area.pos.x + length - 1;
+        for i in 0..length {
+            self.put_pixel(area.pos.x + i, area.pos.y + i, color);
+            self.put_pixel(right - i, area.pos.y + i, color);
+        }
+    }
+
+    /// Draws text with top left corner at given position. Characters not fitting in `max_width`
+    /// are skipped and the text is ended with two dots.
+    pub fn draw_text(&mut self, position: Position, text: &str, color: Color, max_width: usize) {
+        let advance = GLYPH_WIDTH + GLYPH_SPACING;
+        let text: Vec<char> = if Self::measure_text(text) <= max_width {
+            text.chars().collect()
+        } else if max_width >= 2 * advance {
+            let len = (max_width + GLYPH_SPACING) / advance - 2;
+            text.chars().take(len).chain("..".chars()).collect()
+        } else {
+            Vec::new()
+        };
+
+        let mut x = position.x;
+        for c in text.iter() {
+            for (column, bits) in get_glyph(*c).iter().enumerate() {
+                for row in 0..GLYPH_HEIGHT {
+                    if bits & (1 << row) != 0 {
+                        self.put_pixel(x + column as isize, position.y + row as isize, color);
+                    }
+                }
+            }
+            x += advance as isize;
+        }
+    }
+
+    /// Converts the canvas to buffer.
+    pub fn into_buffer(self) -> Buffer {
+        Buffer::new(self.size.width, self.size.height, 4 * self.size.width, self.data)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,72 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains functionality related to counting consecutive clicks to recognize
+//! double-clicks.
+
+// -------------------------------------------------------------------------------------------------
+
+use std::time::{Duration, Instant};
+
+use qualia::{ExhibitorConfig, Position};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Maximal distance in pixels pointer may travel between clicks for them to still count as
+/// consecutive.
+const MAX_DISTANCE: isize = 4;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Counts clicks of the same button made quickly one after another in the same place.
+pub struct ClickCounter {
+    /// Maximal time between consecutive clicks.
+    interval: Duration,
+
+    /// Code of button, global position of pointer and time of last click.
+    last: Option<(u16, Position, Instant)>,
+
+    /// Number of consecutive clicks so far.
+    count: u32,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl ClickCounter {
+    /// Constructs new `ClickCounter`.
+    pub fn new(config: &ExhibitorConfig) -> Self {
+        ClickCounter {
+            interval: Duration::from_millis(config.double_click_interval),
+            last: None,
+            count: 0,
+        }
+    }
+
+    /// Registers press of button with given `code` at given global `position`.
+    ///
+    /// Returns number of consecutive clicks including this one: `1` for single click, `2` for
+    /// double-click and so on.
+    pub fn click(&mut self, code: u16, position: Position) -> u32 {
+        let now = Instant::now();
+        let consecutive = match self.last {
+            Some((last_code, last_position, time)) => {
+                (last_code == code) && (now.duration_since(time) <= self.interval) &&
+                ((position.x - last_position.x).abs() <= MAX_DISTANCE) &&
+                ((position.y - last_position.y).abs() <= MAX_DISTANCE)
+            }
+            None => false,
+        };
+
+        self.count = if consecutive { self.count + 1 } else { 1 };
+        self.last = Some((code, position, now));
+        self.count
+    }
+
+    /// Forgets previous clicks so the next one will be counted as first.
+    pub fn reset(&mut self) {
+        self.last = None;
+        self.count = 0;
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
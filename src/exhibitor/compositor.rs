@@ -7,12 +7,17 @@
 // -------------------------------------------------------------------------------------------------
 
 use std;
+use std::collections::HashMap;
+use std::path::PathBuf;
 
 use timber;
-use qualia::{Action, Area, Command, Coordinator, Direction, SurfaceId, SurfaceInfo};
+use qualia::{Action, Area, Command, Coordinator, Direction, Position, Size, SurfaceId, Vector};
+use qualia::{surface_state, SurfaceInfo};
+use qualia::{ExhibitorConfig, WorkspaceConfig, WorkspaceLayout};
 
-use surface_history::SurfaceHistory;
-use frames::{self, Frame, Geometry, Side};
+use surface_history::{self, Fingerprint, SurfaceHistory};
+use frames::{self, Frame, FrameIndex, Geometry, Side};
+use frames::packing::Packing;
 use frames::searching::Searching;
 use frames::settling::Settling;
 
@@ -20,6 +25,9 @@ use frames::settling::Settling;
 
 const MAX_WORKSPACES: u32 = 1000;
 
+/// Maximal distance in pixels from border between frames at which the border can be grabbed.
+const BORDER_TOLERANCE: isize = 4;
+
 // -------------------------------------------------------------------------------------------------
 
 macro_rules! try_get_surface {
@@ -79,9 +87,19 @@ struct ManageDecision {
 /// Compositor main structure.
 pub struct Compositor {
     history: SurfaceHistory,
+    history_path: Option<PathBuf>,
     coordinator: Coordinator,
     root: Frame,
     selection: Frame,
+    index: FrameIndex,
+    workspace_names: Vec<String>,
+    workspace_definitions: Vec<WorkspaceConfig>,
+    capture_excluded_apps: Vec<String>,
+    float_unresizable: bool,
+    moved: Option<Frame>,
+    moved_floating: bool,
+    restored_geometries: HashMap<SurfaceId, Geometry>,
+    minimized: Vec<(Frame, bool)>,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -89,23 +107,61 @@ pub struct Compositor {
 /// Public methods.
 impl Compositor {
     /// `Compositor` constructor.
-    pub fn new(coordinator: Coordinator) -> Self {
+    pub fn new(coordinator: Coordinator, config: &ExhibitorConfig) -> Self {
         let root = Frame::new_root();
+        let mut history = SurfaceHistory::new();
+        let history_path = config.history_file.as_ref().map(|path| PathBuf::from(path));
+        if let Some(ref path) = history_path {
+            match surface_history::read_fingerprints(path) {
+                Ok(saved) => history.set_saved(saved),
+                Err(err) => log_warn1!("Failed to read surface history from {:?}: {}", path, err),
+            }
+        }
+
         Compositor {
-            history: SurfaceHistory::new(),
+            history: history,
+            history_path: history_path,
             coordinator: coordinator,
             root: root.clone(),
             selection: root,
+            index: FrameIndex::new(),
+            workspace_names: config.workspace_names.clone(),
+            workspace_definitions: config.workspaces.clone(),
+            capture_excluded_apps: config.capture_excluded_apps.clone(),
+            float_unresizable: config.float_unresizable,
+            moved: None,
+            moved_floating: false,
+            restored_geometries: HashMap::new(),
+            minimized: Vec::new(),
         }
     }
 
-    /// Creates new display with default workspace.
+    /// Applies new configuration. Already existing workspaces are not renamed nor reconfigured
+    /// but newly defined ones are created and defined ones are moved to their preferred displays.
+    pub fn set_config(&mut self, config: &ExhibitorConfig) {
+        self.workspace_names = config.workspace_names.clone();
+        self.workspace_definitions = config.workspaces.clone();
+        self.capture_excluded_apps = config.capture_excluded_apps.clone();
+        self.float_unresizable = config.float_unresizable;
+        self.apply_workspace_definitions();
+        self.update_suspension();
+    }
+
+    /// Creates new display. Workspaces defined in configuration preferring this display are
+    /// moved to it. If there are none new default workspace is created.
     pub fn create_display(&mut self, area: Area, name: String) -> Frame {
         let mut display = Frame::new_display(area, name);
-        let mut workspace = self.create_next_workspace()
-            .expect("Could not create workspace. This probably indicates compositor logic error");
         self.root.append(&mut display);
-        workspace.settle(&mut display, &mut self.coordinator);
+        self.apply_workspace_definitions();
+
+        let workspace = if let Some(workspace) = self.find_defined_workspace(&display) {
+            workspace
+        } else {
+            let mut workspace = self.create_next_workspace()
+                .expect("Could not create workspace. This indicates compositor logic error");
+            workspace.settle(&mut display, &mut self.coordinator);
+            workspace
+        };
         self.select(workspace);
         display
     }
@@ -115,12 +171,28 @@ impl Compositor {
         // Execute command
         let mut frame = self.selection.clone();
         let result = match command.action {
-            Action::Configure => self.configure(&mut frame, command.direction),
+            Action::Configure => {
+                match command.direction {
+                    Direction::Workspace => self.rename_workspace(&command.string),
+                    _ => self.configure(&mut frame, command.direction),
+                }
+            }
+            Action::Create => {
+                match command.direction {
+                    Direction::Workspace => self.create_workspace(),
+                    _ => CommandResult::NotHandled,
+                }
+            }
             Action::Focus => {
                 match command.direction {
                     Direction::Workspace => {
-                        self.focus_workspace(&command.string);
-                        CommandResult::Ok
+                        match self.choose_workspace_title(&command) {
+                            Some(title) => {
+                                self.focus_workspace(&title);
+                                CommandResult::Ok
+                            }
+                            None => CommandResult::NotHandled,
+                        }
                     }
                     _ => self.focus(&mut frame, command.direction, command.magnitude),
                 }
@@ -128,8 +200,13 @@ impl Compositor {
             Action::Jump => {
                 match command.direction {
                     Direction::Workspace => {
-                        self.jump_to_workspace(&mut frame, &command.string);
-                        CommandResult::Ok
+                        match self.choose_workspace_title(&command) {
+                            Some(title) => {
+                                self.jump_to_workspace(&mut frame, &title);
+                                CommandResult::Ok
+                            }
+                            None => CommandResult::NotHandled,
+                        }
                     }
                     Direction::End => {
                         self.ramify(frame);
@@ -145,12 +222,56 @@ impl Compositor {
             Action::Dive => {
                 match command.direction {
                     Direction::Workspace => {
-                        self.dive_to_workspace(frame, &command.string);
-                        CommandResult::Ok
+                        match self.choose_workspace_title(&command) {
+                            Some(title) => {
+                                self.dive_to_workspace(frame, &title);
+                                CommandResult::Ok
+                            }
+                            None => CommandResult::NotHandled,
+                        }
                     }
                     _ => self.dive(&mut frame, command.direction, command.magnitude),
                 }
             }
+            Action::Move => {
+                match command.direction {
+                    Direction::Forward | Direction::Backward => {
+                        self.move_workspace(command.direction)
+                    }
+                    Direction::Workspace => {
+                        match self.choose_display(&command) {
+                            Some(display) => {
+                                let workspace = self.find_current_workspace();
+                                self.assign_workspace(&workspace.get_title(),
+                                                      &display.get_title());
+                                CommandResult::Ok
+                            }
+                            None => CommandResult::NotHandled,
+                        }
+                    }
+                    _ => CommandResult::NotHandled,
+                }
+            }
+            Action::Swap => {
+                match command.direction {
+                    Direction::Workspace => {
+                        match self.choose_display(&command) {
+                            Some(display) => self.swap_workspaces(display),
+                            None => CommandResult::NotHandled,
+                        }
+                    }
+                    _ => CommandResult::NotHandled,
+                }
+            }
+            Action::Conceal => self.conceal(&frame),
+            Action::Anchor => self.anchor(&mut frame),
+            Action::Tab => self.switch_tab(&frame, command.direction, command.magnitude),
+            Action::Minimize => {
+                match command.direction {
+                    Direction::Backward => self.restore_minimized(),
+                    _ => self.minimize(frame),
+                }
+            }
             _ => CommandResult::NotHandled,
         };
 
@@ -164,34 +285,287 @@ impl Compositor {
         }
     }
 
+    /// Moves workspace with given title to display with given title. Moved workspace becomes the
+    /// visible one on its new display. If the workspace was the only one on its old display, new
+    /// workspace is created there so no display is left empty. If the workspace does not exist it
+    /// is created on the display.
+    pub fn assign_workspace(&mut self, title: &String, display_title: &String) {
+        let mut display = if let Some(display) = self.find_display(display_title) {
+            display
+        } else {
+            log_warn2!("Compositor: display '{}' not found", display_title);
+            return;
+        };
+
+        let mut workspace = if let Some(workspace) = self.find_workspace(title) {
+            workspace
+        } else {
+            self.create_new_workspace(&mut display, title, false);
+            return;
+        };
+
+        let mut old_display = workspace.get_parent().expect("workspace must have parent");
+        if old_display.equals_exact(&display) {
+            return;
+        }
+
+        log_info2!("Compositor: assign workspace '{}' to display '{}'", title, display_title);
+        workspace.resettle(&mut display, &mut self.coordinator);
+        display.pop_recursively(&mut workspace);
+        if !old_display.has_children() {
+            if let Some(mut new_workspace) = self.create_next_workspace() {
+                new_workspace.settle(&mut old_display, &mut self.coordinator);
+            }
+        }
+
+        self.update_suspension();
+        self.coordinator.notify();
+        self.log_frames();
+    }
+
     /// Handles new surface by settling it in frame tree, adding to history and notifying
     /// coordinator.
     pub fn manage_surface(&mut self, sid: SurfaceId) {
-        if self.root.find_with_sid(sid).is_none() {
+        if self.index.find(sid).is_none() {
             // Get surface
             let surface = try_get_surface!(self, sid);
 
+            // Hide contents of listed applications in screen captures
+            if let Some(ref app_id) = surface.app_id {
+                if self.capture_excluded_apps.contains(app_id) {
+                    self.coordinator.set_surface_capture_excluded(sid, true);
+                }
+            }
+
             // Consult about placement strategy
             let mut decision = self.choose_target(&surface);
 
-            // Settle and optionally select new frame
+            // Settle and optionally select new frame. Windows which can not be resized float.
             let mut frame = Frame::new_leaf(sid, decision.geometry);
-            frame.settle(&mut decision.target, &mut self.coordinator);
-            if decision.selection {
+            if self.float_unresizable && !surface.min_size.is_zero() &&
+               (surface.min_size == surface.max_size) {
+                let size = surface.margins.grow(surface.min_size);
+                self.float(&mut frame, &mut decision.target, size);
+            } else {
+                frame.settle(&mut decision.target, &mut self.coordinator);
+            }
+            self.index.insert(&frame);
+
+            // Surfaces known from before restart take focus only if they were focused the latest
+            let latest = self.add_to_history(sid, &surface);
+            if decision.selection && latest {
                 self.select(frame);
             }
 
             // Finalize
-            self.history.add(sid);
+            self.update_suspension();
             self.coordinator.notify();
             self.log_frames();
         }
     }
 
+    /// Starts interactive move of frame with given surface. The frame is detached from frame tree
+    /// and floats until `finish_moving` is called. Only frames placed directly in workspaces or
+    /// their containers (including floating layers) can be moved.
+    ///
+    /// Returns position of the frame in global coordinates if the move was started.
+    pub fn start_moving(&mut self, sid: SurfaceId) -> Option<Position> {
+        if self.moved.is_some() {
+            return None;
+        }
+
+        let mut frame = match self.index.find(sid) {
+            Some(frame) => frame,
+            None => return None,
+        };
+        if frame.get_mode().is_top() {
+            return None;
+        }
+
+        log_info2!("Compositor: start moving {}", sid);
+        let position = frame.get_position();
+        let mut source = frame.get_parent().expect("moved frame must have parent");
+        let workspace = frame.find_top().expect("moved frame must have top");
+
+        self.index.remove(sid);
+        frame.remove_self(&mut self.coordinator);
+        if !source.get_mode().is_top() {
+            self.index.deramify(&mut source);
+        }
+        self.moved_floating = source.is_floating_layer();
+        self.remove_empty_layer(&mut source);
+        self.moved = Some(frame);
+
+        let most_recent = self.find_most_recent(workspace);
+        self.select(most_recent);
+        self.coordinator.notify();
+        self.log_frames();
+        Some(position)
+    }
+
+    /// Finishes interactive move. Moved frame is settled in visible workspace of display containing
+    /// given pointer position (in global coordinates) and becomes selected. Floating frame stays
+    /// floating and is placed at given origin (in global coordinates).
+    pub fn finish_moving(&mut self, position: Position, origin: Position) {
+        let mut frame = match self.moved.take() {
+            Some(frame) => frame,
+            None => return,
+        };
+
+        let mut workspace = self.find_pointed_workspace(position);
+        log_info2!("Compositor: finish moving {} to '{}'", frame.get_sid(), workspace.get_title());
+        if self.moved_floating {
+            let mut layer = self.find_floating_layer(&mut workspace);
+            layer.append(&mut frame);
+            frame.set_position(origin);
+        } else {
+            frame.settle(&mut workspace, &mut self.coordinator);
+        }
+        self.index.insert(&frame);
+        self.select(frame);
+        self.coordinator.notify();
+        self.log_frames();
+    }
+
+    /// Checks if there is border between tiled frames at given position in global coordinates.
+    pub fn has_border_at(&self, position: Position) -> bool {
+        self.find_pointed_workspace(position).find_border(position, BORDER_TOLERANCE).is_some()
+    }
+
+    /// Moves border between tiled frames found at given position in global coordinates by given
+    /// vector resizing frames on both its sides.
+    ///
+    /// Border is not moved further than size limits of surfaces on its sides allow.
+    pub fn move_border(&mut self, position: Position, vector: Vector) {
+        let workspace = self.find_pointed_workspace(position);
+        if let Some(mut frame) = workspace.find_border(position, BORDER_TOLERANCE) {
+            let geometry = match frame.get_parent().map(|parent| parent.get_geometry()) {
+                Some(geometry) => geometry,
+                None => return,
+            };
+            let offset = match geometry {
+                Geometry::Vertical => vector.y,
+                Geometry::Horizontal => vector.x,
+                _ => return,
+            };
+            let next = match frame.get_next_space() {
+                Some(next) => next,
+                None => return,
+            };
+
+            // Limits only prevent moving further from allowed length, never force moving back
+            let (length, min, max) = self.get_length_limits(&frame, geometry);
+            let (next_length, next_min, next_max) = self.get_length_limits(&next, geometry);
+            let mut lower = min - length;
+            let mut upper = next_length - next_min;
+            if let Some(max) = max {
+                upper = upper.min(max - length);
+            }
+            if let Some(next_max) = next_max {
+                lower = lower.max(next_length - next_max);
+            }
+            let offset = offset.max(lower.min(0)).min(upper.max(0));
+
+            frame.move_border(offset, &mut self.coordinator);
+            self.coordinator.notify();
+        }
+    }
+
+    /// Makes all frames sharing border found at given position in global coordinates equal in
+    /// size.
+    pub fn equalize_border(&mut self, position: Position) {
+        let workspace = self.find_pointed_workspace(position);
+        if let Some(frame) = workspace.find_border(position, BORDER_TOLERANCE) {
+            if let Some(mut parent) = frame.get_parent() {
+                log_info2!("Compositor: equalize frames at {:?}", position);
+                parent.homogenize(&mut self.coordinator);
+                self.coordinator.notify();
+            }
+        }
+    }
+
+    /// Configures not yet managed surface with size and state its frame would get if the surface
+    /// was managed now, so client can draw its first buffer in proper size. Surfaces which will
+    /// float are let to choose the size by themselves.
+    pub fn configure_initially(&mut self, sid: SurfaceId) {
+        let surface = try_get_surface!(self, sid);
+        let (size, state) = if let Some(frame) = self.index.find(sid) {
+            (frame.get_size(), surface.state_flags)
+        } else if self.float_unresizable && !surface.min_size.is_zero() &&
+                  (surface.min_size == surface.max_size) {
+            (Size::default(), surface_state::REGULAR)
+        } else {
+            let decision = self.choose_target(&surface);
+            match decision.target.find_buildable() {
+                Some(ref buildable) if buildable.get_geometry() == Geometry::Stacked => {
+                    (buildable.get_size(), surface_state::MAXIMIZED)
+                }
+                Some(ref buildable) => (buildable.calculate_appended_size(), surface_state::TILED),
+                None => (Size::default(), surface_state::REGULAR),
+            }
+        };
+
+        log_info2!("Compositor: initial size of {} is {:?}", sid, size);
+        self.coordinator.set_surface_initial_size(sid, size, state);
+    }
+
+    /// Prepares interactive resize of frame with given surface. Only floating frames can be resized
+    /// this way. The frame becomes selected.
+    ///
+    /// Returns area of the frame in global coordinates if the resize can be started.
+    pub fn start_resizing(&mut self, sid: SurfaceId) -> Option<Area> {
+        let frame = match self.index.find(sid) {
+            Some(frame) => frame,
+            None => return None,
+        };
+        match frame.get_parent() {
+            Some(ref parent) if parent.is_floating_layer() => {
+                log_info2!("Compositor: start resizing {}", sid);
+                self.pop_surface(sid);
+                Some(frame.get_area())
+            }
+            _ => None,
+        }
+    }
+
+    /// Places floating frame with given surface in given area in global coordinates.
+    pub fn resize_floating(&mut self, sid: SurfaceId, area: Area) {
+        if let Some(mut frame) = self.index.find(sid) {
+            frame.set_position(area.pos);
+            frame.set_size(area.size, &mut self.coordinator);
+            self.coordinator.notify();
+        }
+    }
+
     /// Handles destruction of surface. Removes it from history and frame free.
     pub fn unmanage_surface(&mut self, sid: SurfaceId) {
-        if let Some(ref mut frame) = self.root.find_with_sid(sid) {
+        let moved_destroyed = match self.moved {
+            Some(ref frame) => frame.get_sid() == sid,
+            None => false,
+        };
+        if moved_destroyed {
             self.history.remove(sid);
+            if let Some(frame) = self.moved.take() {
+                frame.destroy();
+            }
+            self.coordinator.notify();
+            return;
+        }
+
+        let minimized_position = self.minimized.iter().position(|&(ref frame, _)| {
+            frame.get_sid() == sid
+        });
+        if let Some(position) = minimized_position {
+            let (frame, _) = self.minimized.remove(position);
+            self.history.remove(sid);
+            frame.destroy();
+            return;
+        }
+
+        self.restored_geometries.remove(&sid);
+        if let Some(ref mut frame) = self.index.remove(sid) {
+            self.history.remove(sid);
+            let mut parent = frame.get_parent().expect("managed frame must have parent");
             if frame.get_sid() == self.selection.get_sid() {
                 // TODO: This should be configurable
                 let new_selection = self.selection.find_buildable().unwrap();
@@ -199,15 +573,40 @@ impl Compositor {
             }
 
             frame.destroy_self(&mut self.coordinator);
+            self.remove_empty_layer(&mut parent);
             self.coordinator.notify();
             self.log_frames();
         }
     }
 
+    /// Focuses next (`Forward`) or previous (`Backward`) workspace in order of their placement on
+    /// display containing given position.
+    pub fn switch_workspace(&mut self, position: Position, direction: Direction) {
+        let mut displays = self.root.time_iter();
+        let display = match displays.find(|frame| frame.get_area().contains(&position)) {
+            Some(display) => display,
+            None => return,
+        };
+
+        let distance = if direction == Direction::Forward { 1 } else { -1 };
+        if let Some(workspace) = self.find_relative_workspace(&display, distance) {
+            self.focus_workspace(&workspace.get_title());
+            self.coordinator.notify();
+            self.log_frames();
+        }
+    }
+
+    /// Focuses workspace with given title.
+    pub fn switch_to_workspace(&mut self, title: &String) {
+        self.focus_workspace(title);
+        self.coordinator.notify();
+        self.log_frames();
+    }
+
     /// Pop given surface in history.
     pub fn pop_surface(&mut self, sid: SurfaceId) {
         if sid.is_valid() {
-            if let Some(mut frame) = self.root.find_with_sid(sid) {
+            if let Some(mut frame) = self.index.find(sid) {
                 // Pop in frame hierarchy.
                 self.root.pop_recursively(&mut frame);
 
@@ -217,8 +616,92 @@ impl Compositor {
 
             // Pop in history.
             self.history.pop(sid);
+            self.save_history();
+        }
+    }
+
+    /// Maximizes frame with given surface by stacking frames in its parent or restores geometry
+    /// the parent had before maximization.
+    pub fn toggle_maximized(&mut self, sid: SurfaceId) {
+        let mut frame = if let Some(frame) = self.index.find(sid) {
+            frame
+        } else {
+            return;
+        };
+        let mut parent = frame.get_parent().expect("managed frame must have parent");
+        if parent.is_floating_layer() {
+            return;
+        }
+
+        let geometry = if let Some(geometry) = self.restored_geometries.remove(&sid) {
+            geometry
+        } else {
+            self.restored_geometries.insert(sid, parent.get_geometry());
+            Geometry::Stacked
+        };
+
+        log_info2!("Compositor: Change geometry of maximized frame to '{:?}'", geometry);
+        parent.change_geometry(geometry, &mut self.coordinator);
+        self.root.pop_recursively(&mut frame);
+        self.select(frame);
+        self.history.pop(sid);
+        self.save_history();
+        self.coordinator.notify();
+        self.log_frames();
+    }
+
+    /// Selects frame with given surface and executes given command on it.
+    pub fn execute_command_on(&mut self, sid: SurfaceId, command: Command) {
+        if self.index.find(sid).is_some() {
+            self.pop_surface(sid);
+            self.execute_command(command);
         }
     }
+
+    /// Returns titles of all workspaces except the one containing given surface.
+    pub fn get_other_workspace_titles(&self, sid: SurfaceId) -> Vec<String> {
+        let current = self.index.find(sid).and_then(|frame| frame.find_top());
+        let mut titles = Vec::new();
+        for display_frame in self.root.time_iter() {
+            for workspace_frame in display_frame.time_iter() {
+                let is_current = match current {
+                    Some(ref current) => current.equals_exact(&workspace_frame),
+                    None => false,
+                };
+                if !is_current {
+                    titles.push(workspace_frame.get_title());
+                }
+            }
+        }
+        titles
+    }
+
+    /// Maximizes or restores frame with given surface if it is not already in requested state.
+    pub fn set_maximized(&mut self, sid: SurfaceId, maximized: bool) {
+        if maximized != self.restored_geometries.contains_key(&sid) {
+            self.toggle_maximized(sid);
+        }
+    }
+
+    /// Reconfigures surface with given ID to size of its frame. Used to restore surface after it
+    /// was made fullscreen.
+    pub fn refresh_surface(&mut self, sid: SurfaceId) {
+        if let Some(mut frame) = self.index.find(sid) {
+            let size = frame.get_size();
+            frame.set_size(size, &mut self.coordinator);
+            self.coordinator.notify();
+        }
+    }
+
+    /// Returns position in global coordinates of given surface. The surface is placed in its frame
+    /// after space reserved for decorations.
+    pub fn get_surface_position(&self, sid: SurfaceId) -> Option<Position> {
+        let offset = match self.coordinator.get_surface(sid) {
+            Some(info) => info.margins.get_offset(),
+            None => return None,
+        };
+        self.index.find(sid).map(|frame| frame.get_position() + offset)
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -241,6 +724,10 @@ impl Compositor {
         }
 
         let mut parent = frame.get_parent().expect("reconfigured frame should have parent");
+        if parent.is_floating_layer() && !frame.has_children() {
+            log_warn2!("Can not change geometry of floating layer");
+            return CommandResult::NotHandled;
+        }
 
         // Choose geometry
         let geometry = match direction {
@@ -328,7 +815,7 @@ impl Compositor {
         if let Some(mut target) = reference.find_adjacent(direction, distance) {
             let mut source = reference.get_parent().expect("jump reference must have parent");
             reference.jump(side, &mut target, &mut self.coordinator);
-            source.deramify();
+            self.index.deramify(&mut source);
         }
         CommandResult::Ok
     }
@@ -367,7 +854,7 @@ impl Compositor {
         if let Some(mut target) = reference.find_adjacent(direction, distance) {
             let mut source = reference.get_parent().expect("dive reference must have parent");
             reference.jump(Side::On, &mut target, &mut self.coordinator);
-            source.deramify();
+            self.index.deramify(&mut source);
         }
         CommandResult::Ok
     }
@@ -392,6 +879,42 @@ impl Compositor {
         self.select(frame);
     }
 
+    /// Makes anchored frame floating or anchors floating frame. Frame made floating is moved to
+    /// floating layer of its workspace and placed in the middle of the workspace with half of its
+    /// size. Anchored frame is settled in its workspace.
+    fn anchor(&mut self, frame: &mut Frame) -> CommandResult {
+        if !frame.get_sid().is_valid() || frame.get_mode().is_top() {
+            log_warn1!("Only frames with surfaces can be anchored. {:?}", frame);
+            return CommandResult::WrongFrame;
+        }
+
+        let mut source = frame.get_parent().expect("anchored frame must have parent");
+        let mut workspace = frame.find_top().expect("anchored frame must have top");
+        frame.remove_self(&mut self.coordinator);
+        if source.is_floating_layer() {
+            log_info2!("Compositor: anchor {}", frame.get_sid());
+            self.remove_empty_layer(&mut source);
+            frame.settle(&mut workspace, &mut self.coordinator);
+        } else {
+            log_info2!("Compositor: float {}", frame.get_sid());
+            if !source.get_mode().is_top() {
+                self.index.deramify(&mut source);
+            }
+
+            let area = workspace.get_area();
+            let size = Size::new(area.size.width / 2, area.size.height / 2);
+            let position = Position::new(area.pos.x + (area.size.width / 4) as isize,
+                                         area.pos.y + (area.size.height / 4) as isize);
+            let mut layer = self.find_floating_layer(&mut workspace);
+            layer.append(frame);
+            frame.set_position(position);
+            frame.set_size(size, &mut self.coordinator);
+        }
+
+        self.select(frame.clone());
+        CommandResult::Ok
+    }
+
     /// Jumps frame one level higher.
     fn exalt(&mut self, frame: &mut Frame) {
         // Choose target
@@ -422,17 +945,45 @@ impl Compositor {
     ///
     /// Returns most recently focused frame, or `reference` frame if nothing found.
     ///
-    /// Searching for new selection is done by iterating through surface history and checking if
-    /// surface with given ID is somewhere in workspace three. Not the most efficient... Any ideas
-    /// for improvement?
+    /// Searching for new selection is done by iterating through surface history and checking in
+    /// frame index if surface with given ID is somewhere in workspace three.
     fn find_most_recent(&self, reference: Frame) -> Frame {
         for sid in self.history.iter() {
-            if let Some(frame) = reference.find_with_sid(sid) {
+            if let Some(frame) = self.index.find_within(sid, &reference) {
                 return frame.clone();
             }
         }
         reference
     }
+
+    /// Returns floating layer of given workspace. If workspace does not have one, new layer is
+    /// created.
+    fn find_floating_layer(&mut self, workspace: &mut Frame) -> Frame {
+        if let Some(layer) = workspace.space_iter().find(|frame| frame.is_floating_layer()) {
+            return layer;
+        }
+
+        let mut layer = Frame::new_container(Geometry::Floating);
+        workspace.append(&mut layer);
+        workspace.relax(&mut self.coordinator);
+        layer
+    }
+
+    /// Removes given frame if it is floating layer without any frames. If the layer was selected,
+    /// most recently used frame in its workspace becomes selected.
+    fn remove_empty_layer(&mut self, layer: &mut Frame) {
+        if !layer.is_floating_layer() || layer.has_children() {
+            return;
+        }
+
+        let workspace = layer.get_parent().expect("floating layer must have parent");
+        let selected = self.selection.equals_exact(layer);
+        layer.destroy_self(&mut self.coordinator);
+        if selected {
+            let most_recent = self.find_most_recent(workspace);
+            self.select(most_recent);
+        }
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -451,6 +1002,309 @@ impl Compositor {
         None
     }
 
+    /// Search for existing display with given title.
+    fn find_display(&self, title: &String) -> Option<Frame> {
+        self.root.time_iter().find(|display_frame| display_frame.get_title() == *title)
+    }
+
+    /// Moves current workspace to next (`Forward`) or previous (`Backward`) display in order of
+    /// their placement.
+    fn move_workspace(&mut self, direction: Direction) -> CommandResult {
+        let workspace = self.find_current_workspace();
+        let display = workspace.get_parent().expect("workspace must have parent");
+        let distance = match direction {
+            Direction::Forward => 1,
+            _ => -1,
+        };
+        if let Some(target) = self.find_relative_display(&display, distance) {
+            self.assign_workspace(&workspace.get_title(), &target.get_title());
+        }
+        CommandResult::Ok
+    }
+
+    /// Swaps current workspace with the visible workspace of given display. Both workspaces are
+    /// repacked to geometry of their new displays and stay visible.
+    fn swap_workspaces(&mut self, mut target: Frame) -> CommandResult {
+        let mut workspace = self.find_current_workspace();
+        let mut display = workspace.get_parent().expect("workspace must have parent");
+        if display.equals_exact(&target) {
+            return CommandResult::Ok;
+        }
+
+        let mut target_workspace = target.get_first_time().expect("display must have workspace");
+        log_info2!("Compositor: swap workspace '{}' on display '{}' with workspace '{}' on \
+                    display '{}'",
+                   workspace.get_title(),
+                   display.get_title(),
+                   target_workspace.get_title(),
+                   target.get_title());
+
+        workspace.resettle(&mut target, &mut self.coordinator);
+        target_workspace.resettle(&mut display, &mut self.coordinator);
+        target.pop_recursively(&mut workspace);
+        display.pop_recursively(&mut target_workspace);
+
+        self.update_suspension();
+        CommandResult::Ok
+    }
+
+    /// Finds display placed `distance` displays after (or before if negative) given one. Displays
+    /// are ordered by their placement and wrap around. Returns `None` if found display is the
+    /// given one.
+    fn find_relative_display(&self, display: &Frame, distance: i32) -> Option<Frame> {
+        let displays: Vec<Frame> = self.root.space_iter().collect();
+        let len = displays.len() as i32;
+        if let Some(index) = displays.iter().position(|frame| frame.equals_exact(display)) {
+            let target = ((index as i32 + distance) % len + len) % len;
+            if target != index as i32 {
+                return Some(displays[target as usize].clone());
+            }
+        }
+        None
+    }
+
+    /// Chooses display the workspace reassignment command refers to. If command contains string
+    /// it is the title of display. Otherwise command magnitude is treated as distance from
+    /// current display.
+    fn choose_display(&self, command: &Command) -> Option<Frame> {
+        if command.string.is_empty() {
+            let display = self.find_current_workspace()
+                .get_parent()
+                .expect("workspace must be contained in display frame");
+            self.find_relative_display(&display, command.magnitude)
+        } else {
+            let display = self.find_display(&command.string);
+            if display.is_none() {
+                log_warn2!("Compositor: display '{}' not found", command.string);
+            }
+            display
+        }
+    }
+
+    /// Finds workspace placed `distance` workspaces after (or before if negative) the visible one
+    /// on given display. Workspaces are ordered by their placement and wrap around. Returns `None`
+    /// if found workspace is the visible one.
+    fn find_relative_workspace(&self, display: &Frame, distance: i32) -> Option<Frame> {
+        let workspaces: Vec<Frame> = display.space_iter().collect();
+        let current = display.get_first_time().expect("display must have workspace");
+        let len = workspaces.len() as i32;
+        if let Some(index) = workspaces.iter().position(|frame| frame.equals_exact(&current)) {
+            let target = ((index as i32 + distance) % len + len) % len;
+            if target != index as i32 {
+                return Some(workspaces[target as usize].clone());
+            }
+        }
+        None
+    }
+
+    /// Chooses title of workspace the workspace command refers to. If command contains string it
+    /// is the title. Otherwise command magnitude is treated as distance from current workspace on
+    /// current display.
+    fn choose_workspace_title(&self, command: &Command) -> Option<String> {
+        if command.string.is_empty() {
+            let display = self.find_current_workspace()
+                .get_parent()
+                .expect("workspace must be contained in display frame");
+            self.find_relative_workspace(&display, command.magnitude)
+                .map(|workspace| workspace.get_title())
+        } else {
+            Some(command.string.clone())
+        }
+    }
+
+    /// Creates new workspace with first free title on current display and focuses it.
+    fn create_workspace(&mut self) -> CommandResult {
+        let mut display = self.find_current_workspace()
+            .get_parent()
+            .expect("workspace must be contained in display frame");
+        if let Some(title) = self.generate_next_workspace_title() {
+            self.create_new_workspace(&mut display, &title, true);
+            CommandResult::Ok
+        } else {
+            CommandResult::NotHandled
+        }
+    }
+
+    /// Changes title of current workspace. Titles must be unique so renaming to title of other
+    /// existing workspace fails.
+    fn rename_workspace(&mut self, title: &String) -> CommandResult {
+        let mut workspace = self.find_current_workspace();
+        if title.is_empty() || self.find_workspace(title).is_some() {
+            log_warn2!("Compositor: can not rename workspace to '{}'", title);
+            return CommandResult::NotHandled;
+        }
+
+        log_info2!("Compositor: rename workspace '{}' to '{}'", workspace.get_title(), title);
+        workspace.set_plumbing_title(title.clone());
+        CommandResult::Ok
+    }
+
+    /// Toggles hiding contents of surface in given frame in screen captures. Surface is still
+    /// drawn normally on displays.
+    fn conceal(&self, frame: &Frame) -> CommandResult {
+        let sid = frame.get_sid();
+        if let Some(surface) = self.coordinator.get_surface(sid) {
+            let excluded = !surface.capture_excluded;
+            log_info2!("Compositor: {} surface {} in captures",
+                       if excluded { "conceal" } else { "reveal" },
+                       sid);
+            self.coordinator.set_surface_capture_excluded(sid, excluded);
+            CommandResult::Ok
+        } else {
+            CommandResult::NotHandled
+        }
+    }
+
+    /// Shows and selects frame placed `magnitude` tabs forward or backward from frame containing
+    /// given `reference` in its closest stacked ancestor.
+    fn switch_tab(&mut self,
+                  reference: &Frame,
+                  direction: Direction,
+                  magnitude: i32)
+                  -> CommandResult {
+        let distance = match direction {
+            Direction::Forward => magnitude as isize,
+            Direction::Backward => -magnitude as isize,
+            _ => return CommandResult::NotHandled,
+        };
+
+        // Find closest stacked ancestor and its child containing reference frame
+        let mut current = reference.clone();
+        let mut stack = None;
+        while let Some(parent) = current.get_parent() {
+            if parent.get_geometry() == Geometry::Stacked {
+                stack = Some(parent);
+                break;
+            }
+            if parent.get_mode().is_top() {
+                break;
+            }
+            current = parent;
+        }
+        let stack = match stack {
+            Some(stack) => stack,
+            None => return CommandResult::WrongFrame,
+        };
+
+        let tabs: Vec<Frame> =
+            stack.space_iter().filter(|frame| !frame.is_floating_layer()).collect();
+        let index = match tabs.iter().position(|frame| frame.equals_exact(&current)) {
+            Some(index) => index as isize,
+            None => return CommandResult::WrongFrame,
+        };
+        let len = tabs.len() as isize;
+        let tab = tabs[(((index + distance) % len + len) % len) as usize].clone();
+
+        log_info2!("Compositor: switch tab to {:?}", tab);
+        let most_recent = self.find_most_recent(tab);
+        self.select(most_recent);
+        CommandResult::Ok
+    }
+
+    /// Removes given frame from frame tree and hides it until it is restored by
+    /// `restore_minimized`. The surface stays in history and is suspended so its client can stop
+    /// rendering.
+    fn minimize(&mut self, mut frame: Frame) -> CommandResult {
+        let sid = frame.get_sid();
+        if !sid.is_valid() || frame.get_mode().is_top() {
+            log_warn1!("Only frames with surfaces can be minimized. {:?}", frame);
+            return CommandResult::WrongFrame;
+        }
+
+        log_info2!("Compositor: minimize {}", sid);
+        let mut source = frame.get_parent().expect("minimized frame must have parent");
+        let workspace = frame.find_top().expect("minimized frame must have top");
+
+        self.index.remove(sid);
+        frame.remove_self(&mut self.coordinator);
+        if !source.get_mode().is_top() {
+            self.index.deramify(&mut source);
+        }
+        let floating = source.is_floating_layer();
+        self.remove_empty_layer(&mut source);
+        self.minimized.push((frame, floating));
+        self.coordinator.set_surface_suspended(sid, true);
+
+        let most_recent = self.find_most_recent(workspace);
+        self.select(most_recent);
+        CommandResult::Ok
+    }
+
+    /// Places most recently minimized frame back in current workspace and selects it. Floating
+    /// frame is restored in its previous position.
+    fn restore_minimized(&mut self) -> CommandResult {
+        let (mut frame, floating) = match self.minimized.pop() {
+            Some(minimized) => minimized,
+            None => return CommandResult::NotHandled,
+        };
+
+        log_info2!("Compositor: restore minimized {}", frame.get_sid());
+        let mut workspace = self.find_current_workspace();
+        if floating {
+            let position = frame.get_position();
+            let mut layer = self.find_floating_layer(&mut workspace);
+            layer.append(&mut frame);
+            frame.set_position(position);
+        } else {
+            frame.settle(&mut workspace, &mut self.coordinator);
+        }
+        self.index.insert(&frame);
+        self.history.pop(frame.get_sid());
+        self.save_history();
+        self.select(frame);
+        CommandResult::Ok
+    }
+
+    /// Places given frame in floating layer of workspace containing `target`. The frame gets given
+    /// size and is centered in the workspace.
+    fn float(&mut self, frame: &mut Frame, target: &mut Frame, size: Size) {
+        let mut workspace = target.find_top().expect("target frame must have top");
+        let area = workspace.get_area();
+        let x = area.pos.x + (area.size.width as isize - size.width as isize) / 2;
+        let y = area.pos.y + (area.size.height as isize - size.height as isize) / 2;
+        let position = Position::new(x, y);
+        let mut layer = self.find_floating_layer(&mut workspace);
+        layer.append(frame);
+        frame.set_position(position);
+        frame.set_size(size, &mut self.coordinator);
+    }
+
+    /// Returns length of given frame along given geometry together with minimal and maximal
+    /// length allowed by size limits of its surface (including decoration).
+    fn get_length_limits(&self,
+                         frame: &Frame,
+                         geometry: Geometry)
+                         -> (isize, isize, Option<isize>) {
+        let size = frame.get_size();
+        let length = match geometry {
+            Geometry::Vertical => size.height,
+            _ => size.width,
+        } as isize;
+
+        let info = match self.coordinator.get_surface(frame.get_sid()) {
+            Some(info) => info,
+            None => return (length, 0, None),
+        };
+        let margins = info.margins.grow(Size::default());
+        let (min, max, margin) = match geometry {
+            Geometry::Vertical => (info.min_size.height, info.max_size.height, margins.height),
+            _ => (info.min_size.width, info.max_size.width, margins.width),
+        };
+        let min = if min > 0 { (min + margin) as isize } else { 0 };
+        let max = if max > 0 { Some((max + margin) as isize) } else { None };
+        (length, min, max)
+    }
+
+    /// Finds workspace visible on display containing given position in global coordinates or
+    /// current workspace if the position is outside all displays.
+    fn find_pointed_workspace(&self, position: Position) -> Frame {
+        let mut displays = self.root.time_iter();
+        match displays.find(|display| display.get_area().contains(&position)) {
+            Some(display) => display.get_first_time().expect("display must have workspace"),
+            None => self.find_current_workspace(),
+        }
+    }
+
     /// Search for existing workspace with given title.
     fn find_current_workspace(&self) -> Frame {
         self.selection.find_top().expect("selection should have `top`")
@@ -479,16 +1333,78 @@ impl Compositor {
         workspace
     }
 
+    /// Creates workspaces defined in configuration which do not exist yet and moves existing ones
+    /// to their preferred displays if these are present. Workspaces without preferred display or
+    /// with preferred display not present are created on the first display.
+    fn apply_workspace_definitions(&mut self) {
+        let first_display = if let Some(display) = self.root.space_iter().next() {
+            display
+        } else {
+            return;
+        };
+
+        for definition in self.workspace_definitions.clone() {
+            let preferred_display = if let Some(ref output) = definition.output {
+                self.find_display(output)
+            } else {
+                None
+            };
+
+            if self.find_workspace(&definition.name).is_some() {
+                if let Some(display) = preferred_display {
+                    self.assign_workspace(&definition.name, &display.get_title());
+                }
+            } else {
+                log_info2!("Compositor: create defined workspace '{}'", definition.name);
+                let geometry = match definition.layout {
+                    WorkspaceLayout::Stacked => Geometry::Stacked,
+                    WorkspaceLayout::Vertical => Geometry::Vertical,
+                    WorkspaceLayout::Horizontal => Geometry::Horizontal,
+                };
+                let mut display = preferred_display.unwrap_or(first_display.clone());
+                let mut workspace = Frame::new_workspace(definition.name.clone());
+                workspace.set_plumbing_geometry(geometry);
+                workspace.settle(&mut display, &mut self.coordinator);
+            }
+        }
+    }
+
+    /// Searches for first workspace defined in configuration which is placed on given display.
+    fn find_defined_workspace(&self, display: &Frame) -> Option<Frame> {
+        for definition in self.workspace_definitions.iter() {
+            if let Some(workspace) = self.find_workspace(&definition.name) {
+                if let Some(parent) = workspace.get_parent() {
+                    if parent.equals_exact(display) {
+                        return Some(workspace);
+                    }
+                }
+            }
+        }
+        None
+    }
+
     /// Creates next workspace.
-    ///
-    /// This method will check if workspaces title "1", "2", "3" and so on up to "1000" exist and
-    /// create next workspace titled will first available name. 1000 frames is probably to much for
-    /// any use. We should not need to create more.
     fn create_next_workspace(&mut self) -> Option<Frame> {
+        self.generate_next_workspace_title().map(Frame::new_workspace)
+    }
+
+    /// Generates title for next workspace.
+    ///
+    /// First unused title from configured workspace names is chosen. If all are used this method
+    /// will check if workspaces title "1", "2", "3" and so on up to "1000" exist and return first
+    /// available name. 1000 frames is probably to much for any use. We should not need to create
+    /// more.
+    fn generate_next_workspace_title(&self) -> Option<String> {
+        for title in self.workspace_names.iter() {
+            if self.find_workspace(title).is_none() {
+                return Some(title.clone());
+            }
+        }
+
         for i in 1..MAX_WORKSPACES {
             let title = i.to_string();
             if self.find_workspace(&title).is_none() {
-                return Some(Frame::new_workspace(title));
+                return Some(title);
             }
         }
         log_error!("Don't you think {} workspaces isn't enough?", MAX_WORKSPACES);
@@ -531,6 +1447,49 @@ impl Compositor {
         if self.selection.get_sid().is_valid() {
             self.coordinator.set_keyboard_focus(self.selection.get_sid());
         }
+        self.update_suspension();
+    }
+
+    /// Suspends surfaces placed on workspaces which are not visible on any display and resumes
+    /// surfaces on visible ones. Visible workspace is the most recently used one in its display.
+    fn update_suspension(&mut self) {
+        for display_frame in self.root.time_iter() {
+            for (i, workspace_frame) in display_frame.time_iter().enumerate() {
+                for sid in workspace_frame.collect_sids() {
+                    self.coordinator.set_surface_suspended(sid, i != 0);
+                }
+            }
+        }
+    }
+
+    /// Adds surface to history, in place it had before restart if it is known from saved history.
+    /// Returns `true` if the surface became the latest in history.
+    fn add_to_history(&mut self, sid: SurfaceId, surface: &SurfaceInfo) -> bool {
+        let latest = match Fingerprint::new(surface.app_id.as_ref(), surface.title.as_ref()) {
+            Some(fingerprint) => self.history.add_restored(sid, &fingerprint),
+            None => {
+                self.history.add(sid);
+                true
+            }
+        };
+        self.save_history();
+        latest
+    }
+
+    /// Saves history to file if it is configured. Saved surfaces which did not appear since start
+    /// are kept after current ones so they are not lost if their clients reconnect later.
+    fn save_history(&self) {
+        if let Some(ref path) = self.history_path {
+            let mut fingerprints: Vec<Fingerprint> = self.history
+                .iter()
+                .filter_map(|sid| self.coordinator.get_surface(sid))
+                .filter_map(|info| Fingerprint::new(info.app_id.as_ref(), info.title.as_ref()))
+                .collect();
+            fingerprints.extend(self.history.get_unclaimed());
+            if let Err(err) = surface_history::write_fingerprints(path, &fingerprints) {
+                log_warn1!("Failed to save surface history to {:?}: {}", path, err);
+            }
+        }
     }
 
     /// Get selected frame.
@@ -540,16 +1499,22 @@ impl Compositor {
 
     /// Decide how to handle new surface.
     fn choose_target(&self, surface: &SurfaceInfo) -> ManageDecision {
+        // New surfaces are anchored even if selection is floating
+        let mut target = self.get_selection().find_buildable().unwrap();
+        if target.is_floating_layer() {
+            target = target.get_parent().expect("floating layer must have parent");
+        }
+
         if surface.parent_sid.is_valid() {
             // FIXME: Choosing surface target should be configurable.
             ManageDecision {
-                target: self.get_selection().find_buildable().unwrap(),
+                target: target,
                 geometry: frames::Geometry::Stacked,
                 selection: true,
             }
         } else {
             ManageDecision {
-                target: self.get_selection().find_buildable().unwrap(),
+                target: target,
                 geometry: frames::Geometry::Vertical,
                 selection: true,
             }
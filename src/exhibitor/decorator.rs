@@ -0,0 +1,496 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains drawing of server-side decorations (title bars and borders) around
+//! windows.
+//!
+//! Decoration is drawn by compositor to surface of its own which is placed below decorated surface
+//! and has size of the whole frame. Decorated surface is moved and shrunk by space reserved for
+//! the decoration, so together they fill the frame.
+//!
+//! Title bar of window placed in stacked frame together with other windows is split into tabs
+//! showing titles of all of them.
+
+// -------------------------------------------------------------------------------------------------
+
+use std::collections::HashMap;
+
+use qualia::{AlphaMode, Area, Coordinator, DecorationMode, ExhibitorConfig, Margins, MemoryPoolId};
+use qualia::PixelFormat;
+use qualia::{MemoryViewId, Position, Size, SurfaceId, SurfaceInfo, Vector};
+use frames::{Frame, Geometry};
+
+use canvas::{Canvas, Color, GLYPH_HEIGHT};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Height of title bar.
+const TITLE_BAR_HEIGHT: usize = 20;
+
+/// Width of border around title bar and window.
+const BORDER_WIDTH: usize = 2;
+
+/// Size of close and maximize buttons.
+const BUTTON_SIZE: usize = 12;
+
+/// Space between title bar elements.
+const SPACING: usize = 6;
+
+/// Color of decoration of window with keyboard focus.
+const ACTIVE_COLOR: Color = [0x80, 0x50, 0x30, 0xFF];
+
+/// Color of decoration of other windows.
+const INACTIVE_COLOR: Color = [0x50, 0x50, 0x50, 0xFF];
+
+/// Color of title and buttons.
+const FOREGROUND_COLOR: Color = [0xF0, 0xF0, 0xF0, 0xFF];
+
+/// Color of tabs of windows hidden in stacked frame.
+const HIDDEN_TAB_COLOR: Color = [0x30, 0x30, 0x30, 0xFF];
+
+// -------------------------------------------------------------------------------------------------
+
+/// Parts of decoration reacting to pointer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DecorationPart {
+    /// Title bar without buttons.
+    TitleBar,
+
+    /// Border around the window.
+    Border,
+
+    /// Button closing the window.
+    CloseButton,
+
+    /// Button maximizing or restoring the window.
+    MaximizeButton,
+
+    /// Tab of other window in the same stacked frame.
+    Tab(SurfaceId),
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// State of decoration of one surface.
+struct Decoration {
+    /// ID of surface decoration is drawn to.
+    sid: SurfaceId,
+
+    /// Size of the frame. Zero if decoration was not drawn yet.
+    size: Size,
+
+    /// Title drawn on title bar.
+    title: String,
+
+    /// Surface IDs and titles of windows sharing stacked frame with decorated one (including
+    /// itself) in order of tabs. Empty if the window is not stacked with others.
+    tabs: Vec<(SurfaceId, String)>,
+
+    /// Tells if decorated surface has keyboard focus.
+    active: bool,
+
+    /// IDs of memory pool and view holding currently drawn image.
+    memory: Option<(MemoryPoolId, MemoryViewId)>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Draws decorations and tells which part of decoration pointer points to.
+pub struct Decorator {
+    /// Tells if new surfaces should be decorated when their clients have no preference.
+    enabled: bool,
+
+    /// Decorations of surfaces (decorated surface ID to decoration).
+    decorations: HashMap<SurfaceId, Decoration>,
+
+    /// ID of surface with keyboard focus.
+    active_sid: SurfaceId,
+
+    coordinator: Coordinator,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Decorator {
+    /// Constructs new `Decorator`.
+    pub fn new(coordinator: Coordinator, config: &ExhibitorConfig) -> Self {
+        Decorator {
+            enabled: config.decorations,
+            decorations: HashMap::new(),
+            active_sid: SurfaceId::invalid(),
+            coordinator: coordinator,
+        }
+    }
+
+    /// Applies new configuration. Surfaces which are already decorated are not affected.
+    pub fn set_config(&mut self, config: &ExhibitorConfig) {
+        self.enabled = config.decorations;
+    }
+
+    /// Decorates given surface if server-side decorations were chosen for it. Surfaces which have
+    /// parents are never decorated.
+    pub fn on_surface_ready(&mut self, sid: SurfaceId) {
+        if let Some(info) = self.coordinator.get_surface(sid) {
+            let mode = self.choose_mode(&info);
+            if (mode == DecorationMode::ServerSide) && !info.parent_sid.is_valid() {
+                self.decorate(sid);
+            }
+            if mode != info.decoration_mode {
+                self.coordinator.set_surface_decoration_mode(sid, mode);
+            }
+        }
+    }
+
+    /// Handles request of client to change decoration mode of given surface. Decoration is added
+    /// or removed accordingly and the chosen mode is always reported back to the client.
+    pub fn on_decoration_mode_requested(&mut self, sid: SurfaceId) {
+        if let Some(info) = self.coordinator.get_surface(sid) {
+            let mode = if info.parent_sid.is_valid() {
+                DecorationMode::ClientSide
+            } else {
+                self.choose_mode(&info)
+            };
+
+            match mode {
+                DecorationMode::ServerSide => self.decorate(sid),
+                DecorationMode::ClientSide => self.undecorate(sid),
+            }
+            self.coordinator.set_surface_decoration_mode(sid, mode);
+        }
+    }
+
+    /// Handles destruction of surface by destroying its decoration.
+    pub fn on_surface_destroyed(&mut self, sid: SurfaceId) {
+        self.forget(sid);
+    }
+
+    /// Redraws decoration of given surface with its new title.
+    pub fn on_title_changed(&mut self, sid: SurfaceId) {
+        let title = self.get_title(sid);
+        let changed = if let Some(decoration) = self.decorations.get_mut(&sid) {
+            let changed = decoration.title != title;
+            decoration.title = title;
+            changed
+        } else {
+            false
+        };
+
+        if changed {
+            self.redraw(sid);
+        }
+    }
+
+    /// Redraws decorations of surfaces which gained or lost keyboard focus.
+    pub fn on_keyboard_focus_changed(&mut self, sid: SurfaceId) {
+        let old_sid = self.active_sid;
+        if old_sid == sid {
+            return;
+        }
+
+        self.active_sid = sid;
+        for &(sid, active) in [(old_sid, false), (sid, true)].iter() {
+            let changed = if let Some(decoration) = self.decorations.get_mut(&sid) {
+                let changed = decoration.active != active;
+                decoration.active = active;
+                changed
+            } else {
+                false
+            };
+
+            if changed {
+                self.redraw(sid);
+            }
+        }
+    }
+
+    /// Adjusts decorations of surfaces in given frame and its subframes to sizes of their frames
+    /// and to tabs of stacked frames.
+    pub fn update(&mut self, frame: &Frame) {
+        let sid = frame.get_sid();
+        let size = frame.get_size();
+        let tabs = if self.decorations.contains_key(&sid) {
+            self.collect_tabs(frame)
+        } else {
+            Vec::new()
+        };
+        let changed = if let Some(decoration) = self.decorations.get_mut(&sid) {
+            let changed = (decoration.size != size) || (decoration.tabs != tabs);
+            decoration.size = size;
+            decoration.tabs = tabs;
+            changed
+        } else {
+            false
+        };
+
+        if changed {
+            self.redraw(sid);
+        }
+
+        for subframe in frame.space_iter() {
+            self.update(&subframe);
+        }
+    }
+
+    /// Returns ID of decorated surface and part of decoration at given position relative to
+    /// decoration surface with given ID, or `None` if the surface is not a decoration.
+    pub fn find_part(&self,
+                     sid: SurfaceId,
+                     position: Position)
+                     -> Option<(SurfaceId, DecorationPart)> {
+        for (&decorated_sid, decoration) in self.decorations.iter() {
+            if decoration.sid == sid {
+                let size = decoration.size;
+                let part = if Self::get_close_button_area(size).contains(&position) {
+                    DecorationPart::CloseButton
+                } else if Self::get_maximize_button_area(size).contains(&position) {
+                    DecorationPart::MaximizeButton
+                } else if Self::get_title_bar_area(size).contains(&position) {
+                    let tab = Self::get_tab_areas(size, decoration.tabs.len())
+                        .into_iter()
+                        .zip(decoration.tabs.iter())
+                        .find(|&(ref area, _)| area.contains(&position))
+                        .map(|(_, &(tab_sid, _))| tab_sid);
+                    match tab {
+                        Some(tab_sid) if tab_sid != decorated_sid => DecorationPart::Tab(tab_sid),
+                        _ => DecorationPart::TitleBar,
+                    }
+                } else {
+                    DecorationPart::Border
+                };
+                return Some((decorated_sid, part));
+            }
+        }
+        None
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Private methods.
+impl Decorator {
+    /// Starts drawing decoration around given surface. Space for the decoration is reserved
+    /// immediately but the decoration is drawn when size of its frame is known.
+    fn decorate(&mut self, sid: SurfaceId) {
+        if self.decorations.contains_key(&sid) {
+            return;
+        }
+
+        let title = self.get_title(sid);
+        let decoration = Decoration {
+            sid: self.coordinator.create_surface(),
+            size: Size::default(),
+            title: title,
+            tabs: Vec::new(),
+            active: sid == self.active_sid,
+            memory: None,
+        };
+        self.decorations.insert(sid, decoration);
+        self.coordinator.set_surface_decoration(sid, SurfaceId::invalid(), Self::get_margins());
+    }
+
+    /// Removes decoration of given surface and releases space reserved for it.
+    fn undecorate(&mut self, sid: SurfaceId) {
+        if self.decorations.contains_key(&sid) {
+            self.coordinator.set_surface_decoration(sid, SurfaceId::invalid(), Margins::default());
+            self.forget(sid);
+        }
+    }
+
+    /// Chooses decoration mode for given surface. Preference of client wins, otherwise the mode
+    /// follows configuration.
+    fn choose_mode(&self, info: &SurfaceInfo) -> DecorationMode {
+        if let Some(mode) = info.requested_decoration_mode {
+            mode
+        } else if self.enabled {
+            DecorationMode::ServerSide
+        } else {
+            DecorationMode::ClientSide
+        }
+    }
+
+    /// Returns space reserved for decoration around decorated surface.
+    fn get_margins() -> Margins {
+        Margins::new(BORDER_WIDTH + TITLE_BAR_HEIGHT, BORDER_WIDTH, BORDER_WIDTH, BORDER_WIDTH)
+    }
+
+    /// Returns area of title bar (including buttons) in frame of given size.
+    fn get_title_bar_area(size: Size) -> Area {
+        let width = size.width.saturating_sub(2 * BORDER_WIDTH);
+        Area::create(BORDER_WIDTH as isize, BORDER_WIDTH as isize, width, TITLE_BAR_HEIGHT)
+    }
+
+    /// Returns area of close button in frame of given size.
+    fn get_close_button_area(size: Size) -> Area {
+        let x = size.width as isize - (BORDER_WIDTH + SPACING + BUTTON_SIZE) as isize;
+        let y = (BORDER_WIDTH + (TITLE_BAR_HEIGHT - BUTTON_SIZE) / 2) as isize;
+        Area::create(x, y, BUTTON_SIZE, BUTTON_SIZE)
+    }
+
+    /// Returns area of maximize button in frame of given size.
+    fn get_maximize_button_area(size: Size) -> Area {
+        let mut area = Self::get_close_button_area(size);
+        area.pos.x -= (SPACING + BUTTON_SIZE) as isize;
+        area
+    }
+
+    /// Returns areas of given number of tabs in frame of given size. Tabs split space of title bar
+    /// left of buttons evenly.
+    fn get_tab_areas(size: Size, count: usize) -> Vec<Area> {
+        if count == 0 {
+            return Vec::new();
+        }
+
+        let title_bar = Self::get_title_bar_area(size);
+        let maximize = Self::get_maximize_button_area(size);
+        let width = (maximize.pos.x - SPACING as isize - title_bar.pos.x).max(0) as usize;
+        (0..count)
+            .map(|i| {
+                let start = width * i / count;
+                let end = width * (i + 1) / count;
+                Area::create(title_bar.pos.x + start as isize,
+                             title_bar.pos.y,
+                             end - start,
+                             title_bar.size.height)
+            })
+            .collect()
+    }
+
+    /// Returns surface IDs and titles of windows in stacked frame containing given frame if there
+    /// are more of them. Container in stacked frame is represented by its most recently used
+    /// window.
+    fn collect_tabs(&self, frame: &Frame) -> Vec<(SurfaceId, String)> {
+        let parent = match frame.get_parent() {
+            Some(ref parent) if parent.get_geometry() == Geometry::Stacked => parent.clone(),
+            _ => return Vec::new(),
+        };
+
+        let mut tabs = Vec::new();
+        for sibling in parent.space_iter().filter(|sibling| !sibling.is_floating_layer()) {
+            let mut leaf = Some(sibling);
+            while let Some(current) = leaf.clone() {
+                if current.get_sid().is_valid() {
+                    break;
+                }
+                leaf = current.get_first_time();
+            }
+            if let Some(leaf) = leaf {
+                tabs.push((leaf.get_sid(), self.get_title(leaf.get_sid())));
+            }
+        }
+
+        if tabs.len() > 1 { tabs } else { Vec::new() }
+    }
+
+    /// Returns title of given surface or empty string if it has no title.
+    fn get_title(&self, sid: SurfaceId) -> String {
+        self.coordinator
+            .get_surface(sid)
+            .and_then(|info| info.title)
+            .unwrap_or(String::new())
+    }
+
+    /// Draws decoration image of given surface.
+    fn draw(sid: SurfaceId, decoration: &Decoration) -> Canvas {
+        let size = decoration.size;
+        let color = if decoration.active { ACTIVE_COLOR } else { INACTIVE_COLOR };
+        let mut canvas = Canvas::new(size);
+
+        // Draw title bar and borders leaving space for the surface transparent
+        let margins = Self::get_margins();
+        let bottom = (size.height - margins.bottom) as isize;
+        let right = (size.width - margins.right) as isize;
+        canvas.fill(Area::create(0, 0, size.width, margins.top), color);
+        canvas.fill(Area::create(0, bottom, size.width, margins.bottom), color);
+        canvas.fill(Area::create(0, 0, margins.left, size.height), color);
+        canvas.fill(Area::create(right, 0, margins.right, size.height), color);
+
+        // Draw buttons
+        let close = Self::get_close_button_area(size);
+        canvas.draw_cross(close, FOREGROUND_COLOR);
+        let maximize = Self::get_maximize_button_area(size);
+        canvas.draw_outline(maximize, FOREGROUND_COLOR);
+        canvas.fill(Area::new(maximize.pos, Size::new(maximize.size.width, 2)), FOREGROUND_COLOR);
+
+        // Draw title or tabs with titles of all stacked windows
+        let padding = (TITLE_BAR_HEIGHT - GLYPH_HEIGHT) / 2;
+        let offset = Vector::new(SPACING as isize, padding as isize);
+        if decoration.tabs.len() > 0 {
+            let areas = Self::get_tab_areas(size, decoration.tabs.len());
+            for (area, &(tab_sid, ref title)) in areas.iter().zip(decoration.tabs.iter()) {
+                if tab_sid != sid {
+                    canvas.fill(*area, HIDDEN_TAB_COLOR);
+                }
+                canvas.draw_outline(*area, color);
+                let max_width = area.size.width.saturating_sub(2 * SPACING);
+                canvas.draw_text(area.pos + offset, title, FOREGROUND_COLOR, max_width);
+            }
+        } else {
+            let title_bar = Self::get_title_bar_area(size);
+            let position = title_bar.pos + offset;
+            let max_width = (maximize.pos.x - SPACING as isize - position.x).max(0) as usize;
+            canvas.draw_text(position, &decoration.title, FOREGROUND_COLOR, max_width);
+        }
+        canvas
+    }
+
+    /// Draws decoration of given surface to new buffer and attaches it to decoration surface.
+    /// Decoration is attached to decorated surface when drawn for the first time.
+    fn redraw(&mut self, sid: SurfaceId) {
+        let decoration = if let Some(decoration) = self.decorations.get_mut(&sid) {
+            decoration
+        } else {
+            return;
+        };
+
+        let size = decoration.size;
+        let margins = Self::get_margins();
+        if (size.width <= margins.left + margins.right) ||
+           (size.height <= margins.top + margins.bottom) {
+            return;
+        }
+
+        let stride = 4 * size.width;
+        let buffer = Self::draw(sid, decoration).into_buffer();
+        let mpid = self.coordinator.create_pool_from_buffer(buffer);
+        let mvid = match self.coordinator.create_memory_view(mpid,
+                                                             0,
+                                                             size.width,
+                                                             size.height,
+                                                             stride,
+                                                             PixelFormat::Argb8888,
+                                                             AlphaMode::Straight) {
+            Some(mvid) => mvid,
+            None => {
+                self.coordinator.destroy_memory_pool(mpid);
+                return;
+            }
+        };
+
+        self.coordinator.attach(mvid, decoration.sid);
+        self.coordinator.damage_surface(decoration.sid, Area::new(Position::default(), size));
+        self.coordinator.set_surface_requested_size(decoration.sid, size);
+        self.coordinator.commit_surface(decoration.sid);
+
+        if let Some((old_mpid, old_mvid)) = decoration.memory.take() {
+            self.coordinator.destroy_memory_view(old_mvid);
+            self.coordinator.destroy_memory_pool(old_mpid);
+        } else {
+            self.coordinator.set_surface_decoration(sid, decoration.sid, margins);
+        }
+        decoration.memory = Some((mpid, mvid));
+        self.coordinator.notify();
+    }
+
+    /// Destroys decoration of given surface.
+    fn forget(&mut self, sid: SurfaceId) {
+        if let Some(decoration) = self.decorations.remove(&sid) {
+            if let Some((mpid, mvid)) = decoration.memory {
+                self.coordinator.destroy_memory_view(mvid);
+                self.coordinator.destroy_memory_pool(mpid);
+            }
+            self.coordinator.destroy_surface(decoration.sid);
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
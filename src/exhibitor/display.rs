@@ -7,15 +7,79 @@
 
 use std::rc::Rc;
 use std::cell::RefCell;
+use std::time::Instant;
 
 use dharma::Signaler;
-use qualia::{Coordinator, Illusion};
-use qualia::{Milliseconds, OutputInfo, SurfaceContext, perceptron, Perceptron};
+use qualia::{Area, Coordinator, Illusion, Position, Size, SurfaceId, Transform, Vector};
+use qualia::{Milliseconds, OutputInfo, OutputMode, SurfaceContext, perceptron, Perceptron};
+use qualia::{png, Buffer, Env, PresentationTime, WallpaperMode};
+use qualia::{Layer, LayerArrangement, LayerSurface};
 
 use frames::{Frame, Displaying};
+use frames::packing::Packing;
+use frames::searching::Searching;
 use output::Output;
 
 use pointer::Pointer;
+use overlap_watcher::OverlapWatcher;
+use decorator::Decorator;
+use menu::Menu;
+use auto_hide::{self, AutoHide};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Describes when display is redrawn. Modes other than `DamageOnly` are meant for debugging.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RedrawMode {
+    /// Redraw on every page flip even if nothing changed.
+    Continuous,
+
+    /// Redraw only when something changed.
+    DamageOnly,
+
+    /// Redraw only on explicit request (one frame per request).
+    SingleStep,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl RedrawMode {
+    /// Returns mode following this one in cycle.
+    pub fn next(self) -> Self {
+        match self {
+            RedrawMode::Continuous => RedrawMode::DamageOnly,
+            RedrawMode::DamageOnly => RedrawMode::SingleStep,
+            RedrawMode::SingleStep => RedrawMode::Continuous,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Describes what was drawn on display in last frame. Used to decide if display is affected by
+/// changes and needs to be redrawn.
+#[derive(Clone, Debug, PartialEq)]
+struct Scene {
+    /// IDs, positions, sizes of space reserved for drawn surfaces and their dimming.
+    surfaces: Vec<(SurfaceId, Position, Option<Size>, f32)>,
+
+    /// Cursor surface ID and position if pointer was on this display.
+    pointer: Option<(SurfaceId, Position)>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Scene {
+    /// Creates new `Scene` from surfaces and pointer contexts.
+    fn new(surfaces: &Vec<SurfaceContext>, pointer: Option<&SurfaceContext>) -> Self {
+        Scene {
+            surfaces: surfaces.iter()
+                .map(|context| (context.id, context.pos, context.bounds, context.dim))
+                .collect(),
+            pointer: pointer.map(|context| (context.id, context.pos)),
+        }
+    }
+}
 
 // -------------------------------------------------------------------------------------------------
 
@@ -24,10 +88,31 @@ pub struct Display {
     coordinator: Coordinator,
     signaler: Signaler<Perceptron>,
     pointer: Rc<RefCell<Pointer>>,
+    overlap_watcher: Rc<RefCell<OverlapWatcher>>,
+    decorator: Rc<RefCell<Decorator>>,
+    menu: Rc<RefCell<Menu>>,
+    auto_hide: Rc<RefCell<AutoHide>>,
     output: Output,
     frame: Frame,
     redraw_needed: bool,
     page_flip_scheduled: bool,
+
+    /// Surfaces drawn in frame which waits for page flip to be presented.
+    drawn_sids: Vec<SurfaceId>,
+
+    redraw_mode: RedrawMode,
+    last_scene: Option<Scene>,
+    fullscreen_sid: Option<SurfaceId>,
+    zoomed_sid: Option<SurfaceId>,
+    overview: bool,
+    dim_unfocused: f32,
+    powered: bool,
+    enabled: bool,
+    active: bool,
+    capture_requested: bool,
+    screenshot_requested: bool,
+    area_screenshots: Vec<(u32, Option<Area>)>,
+    pending_mode: Option<OutputMode>,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -37,6 +122,10 @@ impl Display {
     pub fn new(coordinator: Coordinator,
                signaler: Signaler<Perceptron>,
                pointer: Rc<RefCell<Pointer>>,
+               overlap_watcher: Rc<RefCell<OverlapWatcher>>,
+               decorator: Rc<RefCell<Decorator>>,
+               menu: Rc<RefCell<Menu>>,
+               auto_hide: Rc<RefCell<AutoHide>>,
                output: Output,
                frame: Frame)
                -> Self {
@@ -44,10 +133,28 @@ impl Display {
             coordinator: coordinator,
             signaler: signaler,
             pointer: pointer,
+            overlap_watcher: overlap_watcher,
+            decorator: decorator,
+            menu: menu,
+            auto_hide: auto_hide,
             output: output,
             frame: frame,
             redraw_needed: true,
             page_flip_scheduled: false,
+            drawn_sids: Vec::new(),
+            redraw_mode: RedrawMode::DamageOnly,
+            last_scene: None,
+            fullscreen_sid: None,
+            zoomed_sid: None,
+            overview: false,
+            dim_unfocused: 0.0,
+            powered: true,
+            enabled: true,
+            active: true,
+            capture_requested: false,
+            screenshot_requested: false,
+            area_screenshots: Vec::new(),
+            pending_mode: None,
         };
         d.redraw_all(); // TODO: Remove when notifications are supported in Wayland module.
         d
@@ -63,20 +170,58 @@ impl Display {
         }
     }
 
-    /// Handle page flip: redraw everything.
-    pub fn on_pageflip(&mut self) {
+    /// Handle page flip: inform about presentation of drawn surfaces and redraw everything. Mode
+    /// requested while page flip was scheduled is set now.
+    pub fn on_pageflip(&mut self, time: PresentationTime) {
         self.page_flip_scheduled = false;
-        if self.redraw_needed {
+        if !self.drawn_sids.is_empty() {
+            let sids = self.drawn_sids.drain(..).collect();
+            let perceptron = Perceptron::FramePresented(self.output.get_info().id, sids, time);
+            self.signaler.emit(perceptron::FRAME_PRESENTED, perceptron);
+        }
+        if !self.active {
+            return;
+        }
+        if !self.powered {
+            self.power_off();
+            return;
+        }
+        if self.pending_mode.is_some() {
+            self.apply_mode();
             self.redraw_all();
+            return;
+        }
+        match self.redraw_mode {
+            RedrawMode::Continuous => self.redraw_all(),
+            RedrawMode::DamageOnly => {
+                if self.redraw_needed {
+                    self.redraw_all();
+                }
+            }
+            RedrawMode::SingleStep => {}
         }
     }
 
     /// Handle notification about needed redraw.
     ///
-    /// This will cause display redraw. If page flip is already scheduled, display will be redraw
-    /// again after page flip.
+    /// Notifications are broadcast to all displays so redraw is requested only if the change
+    /// affects this display: surfaces shown on it were damaged, moved, appeared or disappeared, or
+    /// pointer was moved over it.
     pub fn on_notify(&mut self) {
-        if !self.redraw_needed {
+        self.update_decorations();
+        if self.is_affected() {
+            self.request_redraw();
+        }
+    }
+
+    /// Marks display as needing redraw.
+    ///
+    /// This will cause display redraw. If page flip is already scheduled, display will be redraw
+    /// again after page flip. In single-step mode redraw is postponed until explicitly requested.
+    pub fn request_redraw(&mut self) {
+        if self.redraw_mode == RedrawMode::SingleStep {
+            self.redraw_needed = true;
+        } else if !self.redraw_needed {
             if !self.page_flip_scheduled {
                 self.redraw_all();
             } else {
@@ -85,31 +230,679 @@ impl Display {
         }
     }
 
+    /// Adjusts decorations to current sizes of frames in visible workspace.
+    fn update_decorations(&mut self) {
+        if let Some(workspace) = self.frame.get_first_time() {
+            self.decorator.borrow_mut().update(&workspace);
+        }
+    }
+
+    /// Checks if anything drawn on this display changed since last redraw.
+    fn is_affected(&self) -> bool {
+        let last_scene = if let Some(ref last_scene) = self.last_scene {
+            last_scene
+        } else {
+            return true;
+        };
+
+        let mut surfaces = self.prepare_surface_contexts();
+        surfaces.append(&mut self.prepare_moved_contexts());
+        surfaces.extend(self.menu.borrow().prepare_context(&self.output.get_area()));
+        if let Some(context) = self.prepare_drag_icon_context() {
+            surfaces.push(context);
+        }
+        let pointer = self.prepare_pointer_context();
+
+        surfaces.iter().chain(pointer.iter()).any(|context| context.damage.len() > 0) ||
+        (Scene::new(&surfaces, pointer.as_ref()) != *last_scene)
+    }
+
+    /// Returns pointer context if pointer is placed on this display.
+    fn prepare_pointer_context(&self) -> Option<SurfaceContext> {
+        let context = self.prepare_layover_context();
+        if context.pos.is_inside(&self.output.get_area()) {
+            Some(context)
+        } else {
+            None
+        }
+    }
+
+    /// Prepare rendering contexts for surfaces in current workspace. If fullscreen or zoomed
+    /// surface is placed on current workspace only it is drawn, covering whole display, without
+    /// decoration. While session is locked only lock surface is drawn. Layer surfaces are drawn
+    /// below or above the workspace according to their layer; fullscreen and zoomed surfaces cover
+    /// also the top layer.
+    fn prepare_surface_contexts(&self) -> Vec<SurfaceContext> {
+        if self.coordinator.is_session_locked() {
+            return self.prepare_lock_contexts();
+        }
+
+        if self.overview {
+            return self.prepare_overview_contexts();
+        }
+
+        let workspace =
+            self.frame.get_first_time().expect("display must have at least one workspace");
+        let mut result = self.prepare_layer_contexts(&[Layer::Background, Layer::Bottom]);
+        if let Some(contexts) = self.prepare_zoomed_contexts(&workspace) {
+            result.extend(contexts);
+        } else if let Some(contexts) = self.prepare_fullscreen_contexts(&workspace) {
+            result.extend(contexts);
+        } else {
+            result.extend(self.prepare_workspace_contexts(&workspace));
+            result.extend(self.prepare_layer_contexts(&[Layer::Top]));
+        }
+        result.extend(self.prepare_layer_contexts(&[Layer::Overlay]));
+        result
+    }
+
+    /// Prepare rendering contexts for windows in given workspace. All windows except the focused
+    /// one (with its subsurfaces) are dimmed if dimming is enabled.
+    fn prepare_workspace_contexts(&self, workspace: &Frame) -> Vec<SurfaceContext> {
+        let mut result = workspace.to_array(&self.coordinator);
+        if self.dim_unfocused > 0.0 {
+            let focused_sid = self.coordinator.get_keyboard_focused_sid();
+            let focused: Vec<SurfaceId> = self.coordinator
+                .get_renderer_context(focused_sid)
+                .unwrap_or(Vec::new())
+                .iter()
+                .map(|context| context.id)
+                .collect();
+            for context in result.iter_mut().filter(|context| !focused.contains(&context.id)) {
+                context.dim = self.dim_unfocused;
+            }
+        }
+        result
+    }
+
+    /// Returns layer surfaces placed on this display. Automatically hidden surfaces do not reserve
+    /// space.
+    fn get_layer_surfaces(&self) -> Vec<LayerSurface> {
+        let output_id = self.output.get_info().id;
+        let auto_hide = self.auto_hide.borrow();
+        self.coordinator
+            .get_layer_surfaces()
+            .into_iter()
+            .filter(|surface| surface.output_id == Some(output_id))
+            .map(|mut surface| {
+                if auto_hide.is_enabled_for(&surface) && (surface.exclusive_zone > 0) {
+                    surface.exclusive_zone = 0;
+                }
+                surface
+            })
+            .collect()
+    }
+
+    /// Prepare rendering contexts for layer surfaces placed on this display in given layers.
+    /// Automatically hidden surfaces are slid off the display according to their visibility.
+    fn prepare_layer_contexts(&self, layers: &[Layer]) -> Vec<SurfaceContext> {
+        let output_area = self.output.get_area();
+        let mut surfaces = self.get_layer_surfaces();
+        let arrangement = LayerArrangement::new(output_area, &surfaces);
+        surfaces.retain(|surface| layers.contains(&surface.layer));
+        surfaces.sort_by_key(|surface| surface.layer);
+
+        let mut result = Vec::new();
+        for surface in surfaces {
+            let area = match arrangement.get_area(surface.sid) {
+                Some(area) => area,
+                None => continue,
+            };
+            let mut position = area.pos;
+            if self.auto_hide.borrow().is_enabled_for(&surface) {
+                let visibility = self.auto_hide.borrow_mut().get_visibility(surface.sid);
+                if visibility <= 0.0 {
+                    continue;
+                }
+                if let Some(edge) = surface.get_anchored_edge() {
+                    position = position +
+                               auto_hide::get_offset(edge, &area, &output_area, visibility);
+                }
+            }
+            if let Some(contexts) = self.coordinator.get_renderer_context(surface.sid) {
+                result.extend(contexts.iter().map(|context| context.moved(position)));
+            }
+        }
+        result
+    }
+
+    /// Arranges layer surfaces placed on this display. If space left for windows changed,
+    /// workspaces are moved and resized to fit in it. Returns the arrangement.
+    pub fn arrange_layers(&mut self) -> LayerArrangement {
+        let arrangement = LayerArrangement::new(self.output.get_area(), &self.get_layer_surfaces());
+
+        let usable_area = arrangement.usable_area;
+        if self.frame.get_area() != usable_area {
+            self.frame.set_position(usable_area.pos);
+            self.frame.set_size(usable_area.size, &mut self.coordinator);
+            self.request_redraw();
+        }
+        arrangement
+    }
+
+    /// Passes pointer position to automatically hidden layer surfaces placed on this display.
+    pub fn update_auto_hide(&self, position: Position) {
+        let output_area = self.output.get_area();
+        let surfaces = self.get_layer_surfaces();
+        let arrangement = LayerArrangement::new(output_area, &surfaces);
+        let mut auto_hide = self.auto_hide.borrow_mut();
+        for surface in surfaces.iter() {
+            if !auto_hide.is_enabled_for(surface) {
+                continue;
+            }
+            if let (Some(edge), Some(area)) = (surface.get_anchored_edge(),
+                                               arrangement.get_area(surface.sid)) {
+                auto_hide.update(surface.sid, edge, area, output_area, position);
+            }
+        }
+    }
+
+    /// Prepare rendering contexts for fullscreen surface if it is placed on given workspace.
+    fn prepare_fullscreen_contexts(&self, workspace: &Frame) -> Option<Vec<SurfaceContext>> {
+        let sid = match self.fullscreen_sid {
+            Some(sid) if workspace.find_with_sid(sid).is_some() => sid,
+            _ => return None,
+        };
+
+        if let Some(info) = self.coordinator.get_surface(sid) {
+            if let Some(contexts) = self.coordinator.get_renderer_context(sid) {
+                let position = self.output.get_area().pos - info.margins.get_offset();
+                return Some(contexts.iter()
+                                .filter(|context| context.id != info.decoration_sid)
+                                .map(|context| context.moved(position))
+                                .collect());
+            }
+        }
+        None
+    }
+
+    /// Prepare rendering contexts for zoomed surface if it is placed on given workspace. The
+    /// window is scaled keeping its aspect ratio to fill the display and centered on it. Client is
+    /// not told about the scale; pointer positions are translated back to surface coordinates
+    /// basing on scale of the contexts.
+    fn prepare_zoomed_contexts(&self, workspace: &Frame) -> Option<Vec<SurfaceContext>> {
+        let sid = match self.zoomed_sid {
+            Some(sid) if workspace.find_with_sid(sid).is_some() => sid,
+            _ => return None,
+        };
+
+        let info = if let Some(info) = self.coordinator.get_surface(sid) {
+            info
+        } else {
+            return None;
+        };
+        let window = info.requested_size;
+        if (window.width == 0) || (window.height == 0) {
+            return None;
+        }
+
+        let area = self.output.get_area();
+        let scale = (area.size.width as f32 / window.width as f32)
+            .min(area.size.height as f32 / window.height as f32);
+        let size = Size::new((window.width as f32 * scale).round() as usize,
+                             (window.height as f32 * scale).round() as usize);
+        let offset = Vector::new((area.size.width.saturating_sub(size.width) / 2) as isize,
+                                 (area.size.height.saturating_sub(size.height) / 2) as isize);
+        let origin = area.pos + offset - info.margins.get_offset().scaled(scale);
+        self.coordinator.get_renderer_context(sid).map(|contexts| {
+            contexts.iter()
+                .filter(|context| context.id != info.decoration_sid)
+                .map(|context| context.scaled(Position::default(), scale).moved(origin))
+                .collect()
+        })
+    }
+
+    /// Prepare rendering contexts for lock surface covering this display. Display without lock
+    /// surface is left blank.
+    fn prepare_lock_contexts(&self) -> Vec<SurfaceContext> {
+        let output_id = self.output.get_info().id;
+        if let Some(sid) = self.coordinator.get_lock_surface(output_id) {
+            if let Some(contexts) = self.coordinator.get_renderer_context(sid) {
+                let position = self.output.get_area().pos;
+                return contexts.iter().map(|context| context.moved(position)).collect();
+            }
+        }
+        Vec::new()
+    }
+
+    /// Prepare rendering contexts for surfaces of all workspaces scaled down to fit in overview
+    /// grid.
+    fn prepare_overview_contexts(&self) -> Vec<SurfaceContext> {
+        let mut result = Vec::new();
+        for (workspace, cell, scale) in self.get_overview_layout() {
+            let origin = workspace.get_position();
+            for context in workspace.to_array(&self.coordinator) {
+                result.push(context.scaled(origin, scale).moved(cell.pos - origin));
+            }
+        }
+        result
+    }
+
+    /// Arranges workspaces of this display in grid in order of their placement. Returns
+    /// workspaces with areas of their cells and factor by which they are scaled.
+    fn get_overview_layout(&self) -> Vec<(Frame, Area, f32)> {
+        let workspaces: Vec<Frame> = self.frame.space_iter().collect();
+        let columns = (workspaces.len() as f32).sqrt().ceil().max(1.0) as usize;
+        let scale = 1.0 / columns as f32;
+        let area = self.frame.get_area();
+        let size = Size::new(area.size.width / columns, area.size.height / columns);
+        workspaces.into_iter()
+            .enumerate()
+            .map(|(i, workspace)| {
+                let position = Position::new((size.width * (i % columns)) as isize,
+                                             (size.height * (i / columns)) as isize);
+                (workspace, Area::new(area.pos + position, size), scale)
+            })
+            .collect()
+    }
+
+    /// Returns rendering context of given surface alone (without satellites) placed at given
+    /// position. The context carries damage accumulated since the surface was last drawn.
+    fn prepare_single_context(&self, sid: SurfaceId, pos: Position) -> SurfaceContext {
+        let mut context = self.coordinator
+            .get_renderer_context(sid)
+            .and_then(|contexts| contexts.into_iter().find(|context| context.id == sid))
+            .unwrap_or(SurfaceContext::new(sid, pos));
+        context.pos = pos;
+        context
+    }
+
+    /// Enables or disables overview of all workspaces of this display.
+    pub fn set_overview(&mut self, overview: bool) {
+        if self.overview != overview {
+            self.overview = overview;
+            self.request_redraw();
+        }
+    }
+
+    /// Checks if overview of workspaces is shown.
+    pub fn is_overview(&self) -> bool {
+        self.overview
+    }
+
+    /// Returns title of workspace shown in overview at given position in global coordinates.
+    pub fn find_workspace_at(&self, position: Position) -> Option<String> {
+        if self.overview {
+            self.get_overview_layout()
+                .into_iter()
+                .find(|&(_, ref cell, _)| cell.contains(&position))
+                .map(|(workspace, _, _)| workspace.get_title())
+        } else {
+            None
+        }
+    }
+
+    /// Sets redraw mode. Redraws immediately if the new mode allows to.
+    pub fn set_redraw_mode(&mut self, mode: RedrawMode) {
+        self.redraw_mode = mode;
+        if (mode != RedrawMode::SingleStep) && !self.page_flip_scheduled {
+            self.redraw_all();
+        }
+    }
+
+    /// Enables or disables tinting of regions repainted in last frames.
+    pub fn set_damage_visualization(&mut self, enabled: bool) {
+        self.output.set_damage_visualization(enabled);
+        self.request_redraw();
+    }
+
+    /// Sets region (in global coordinates) being interactively selected or ends selection if
+    /// `None`. Display is dimmed outside of the region.
+    pub fn set_selection(&mut self, selection: Option<Area>) {
+        let origin = self.output.get_area().pos;
+        let selection = selection.map(|area| Area::new(area.pos - origin, area.size));
+        self.output.set_selection(selection);
+        self.request_redraw();
+    }
+
+    /// Enables or disables blending colors in linear space.
+    pub fn set_linear_blending(&mut self, enabled: bool) {
+        self.output.set_linear_blending(enabled);
+        self.request_redraw();
+    }
+
+    /// Sets fraction by which colors of unfocused windows are dimmed. Zero disables dimming.
+    pub fn set_dim_unfocused(&mut self, dim: f32) {
+        self.dim_unfocused = dim;
+        self.request_redraw();
+    }
+
+    /// Sets image drawn as background and the way it is fitted to the screen. `None` removes the
+    /// image.
+    pub fn set_wallpaper(&mut self, image: Option<&Buffer>, mode: WallpaperMode) {
+        if let Err(err) = self.output.set_wallpaper(image, mode) {
+            log_error!("Display: failed to set wallpaper: {}", err);
+        }
+        self.request_redraw();
+    }
+
+    /// Moves display to given position in global coordinates together with its contents.
+    pub fn set_position(&mut self, position: Position) {
+        self.output.set_position(position);
+        self.arrange_layers();
+        self.request_redraw();
+    }
+
+    /// Returns ID of fullscreen surface on this display.
+    pub fn get_fullscreen_sid(&self) -> Option<SurfaceId> {
+        self.fullscreen_sid
+    }
+
+    /// Sets or clears fullscreen surface.
+    pub fn set_fullscreen_sid(&mut self, sid: Option<SurfaceId>) {
+        self.fullscreen_sid = sid;
+        self.request_redraw();
+    }
+
+    /// Returns ID of surface zoomed to fill this display.
+    pub fn get_zoomed_sid(&self) -> Option<SurfaceId> {
+        self.zoomed_sid
+    }
+
+    /// Sets or clears surface zoomed to fill this display. Zoomed surface is drawn only while it
+    /// is placed on visible workspace.
+    pub fn set_zoomed_sid(&mut self, sid: Option<SurfaceId>) {
+        self.zoomed_sid = sid;
+        self.request_redraw();
+    }
+
+    /// Checks if given surface is placed on visible workspace of this display.
+    pub fn is_showing(&self, sid: SurfaceId) -> bool {
+        self.frame
+            .get_first_time()
+            .map_or(false, |workspace| workspace.find_with_sid(sid).is_some())
+    }
+
+    /// Sets number of pixels per logical unit of the output. Workspaces are resized to new logical
+    /// size of the display.
+    pub fn set_scale(&mut self, scale: f32) {
+        self.output.set_scale(scale);
+        self.arrange_layers();
+        self.request_redraw();
+    }
+
+    /// Sets rotation and flipping of the output. Workspaces are resized to new logical size of the
+    /// display.
+    pub fn set_transform(&mut self, transform: Transform) {
+        self.output.set_transform(transform);
+        self.arrange_layers();
+        self.request_redraw();
+    }
+
+    /// Sets resolution and refresh rate of the output. Mode is changed at once if no page flip is
+    /// scheduled and the display is active and powered on, otherwise when it becomes possible.
+    /// Workspaces are resized to new size of the display and `DisplayChanged` is emitted.
+    pub fn set_mode(&mut self, mode: OutputMode) {
+        self.pending_mode = Some(mode);
+        if self.active && self.powered && !self.page_flip_scheduled {
+            self.apply_mode();
+            self.redraw_needed = false;
+            self.request_redraw();
+        }
+    }
+
+    /// Sets pending mode of the output if any.
+    fn apply_mode(&mut self) {
+        if let Some(mode) = self.pending_mode.take() {
+            let old_info = self.output.get_info();
+            if let Err(err) = self.output.set_mode(&mode) {
+                log_error!("Display: failed to set mode {}: {}", mode, err);
+                return;
+            }
+
+            let info = self.output.get_info();
+            if (info.pixel_size != old_info.pixel_size) ||
+               (info.refresh_rate != old_info.refresh_rate) {
+                log_info1!("Display: mode of '{}' set to {}", self.get_name(), mode);
+                self.last_scene = None;
+                self.arrange_layers();
+                self.signaler.emit(perceptron::DISPLAY_CHANGED, Perceptron::DisplayChanged(info));
+            }
+        }
+    }
+
+    /// Starts capturing contents of the display with given frame size and rate or stops it if
+    /// `None` is given.
+    pub fn set_screencast(&mut self, settings: Option<(Size, u32)>) {
+        if let Some((size, rate)) = settings {
+            match self.output.start_screencast(size, rate) {
+                Ok(_) => self.request_redraw(),
+                Err(err) => log_error!("Display: failed to start screencast: {}", err),
+            }
+        } else {
+            self.output.stop_screencast();
+        }
+    }
+
+    /// Requests capturing contents of the display on behalf of clients. Captured frame is emitted
+    /// after next redraw.
+    pub fn request_capture(&mut self) {
+        self.capture_requested = true;
+        self.output.request_capture();
+        self.request_redraw();
+    }
+
+    /// Requests saving contents of the display to PNG file in data directory after next redraw.
+    pub fn request_screenshot(&mut self) {
+        self.screenshot_requested = true;
+        self.output.request_capture();
+        self.request_redraw();
+    }
+
+    /// Requests screenshot of given area (in global coordinates) of the display or of the whole
+    /// display if `None`. Screenshot is emitted as `ScreenshotTaken` with given ID after next
+    /// redraw. Fails at once if the display is not drawn.
+    pub fn request_area_screenshot(&mut self, id: u32, area: Option<Area>) {
+        if !self.powered || !self.active {
+            let result = Err(format!("Display '{}' is not active", self.get_name()));
+            let perceptron = Perceptron::ScreenshotTaken(id, result);
+            self.signaler.emit(perceptron::SCREENSHOT_TAKEN, perceptron);
+            return;
+        }
+
+        self.area_screenshots.push((id, area));
+        self.output.request_capture();
+        self.request_redraw();
+    }
+
+    /// Handles frame captured on request.
+    fn handle_captured_frame(&mut self, frame: Buffer) {
+        if self.screenshot_requested {
+            self.screenshot_requested = false;
+            let name = format!("screenshot-{}", self.get_name());
+            let path = Env::get_data_dir_path().join(Env::get_data_file_name(&name, "png"));
+            match png::write(&path, &frame) {
+                Ok(_) => log_info1!("Display: screenshot saved to {:?}", path),
+                Err(err) => log_error!("Display: failed to save screenshot: {}", err),
+            }
+        }
+
+        let origin = self.output.get_area().pos;
+        let scale = self.output.get_info().scale;
+        for (id, area) in self.area_screenshots.drain(..) {
+            let image = match area {
+                Some(area) => frame.crop(&Area::new(area.pos - origin, area.size).scaled(scale)),
+                None => frame.clone(),
+            };
+            let perceptron = Perceptron::ScreenshotTaken(id, Ok(image));
+            self.signaler.emit(perceptron::SCREENSHOT_TAKEN, perceptron);
+        }
+
+        if self.capture_requested {
+            self.capture_requested = false;
+            let perceptron = Perceptron::OutputCaptured(self.output.get_info().id, frame);
+            self.signaler.emit(perceptron::OUTPUT_CAPTURED, perceptron);
+        }
+    }
+
+    /// Powers display on or off. Powering off is postponed until scheduled page flip is done.
+    /// Nothing is drawn while display is off and it is fully redrawn when powered on.
+    pub fn set_power(&mut self, on: bool) {
+        if self.powered == on {
+            return;
+        }
+
+        self.powered = on;
+        if on {
+            if let Err(err) = self.output.set_power(true) {
+                log_error!("Display: failed to power on: {}", err);
+            }
+            self.apply_mode();
+            self.last_scene = None;
+            self.redraw_needed = false;
+            self.request_redraw();
+        } else if !self.page_flip_scheduled {
+            self.power_off();
+        }
+    }
+
+    /// Checks if the display is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Marks the display as enabled or disabled. Disabled display keeps its place in the layout
+    /// but should stay powered off; powering is left to the caller.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Suspends or resumes drawing when virtual terminal was released or acquired. Other DRM master
+    /// might have changed the mode and contents of the output so the mode is restored and the
+    /// display fully redrawn when resumed. Page flips scheduled before releasing may never
+    /// complete so they are forgotten.
+    pub fn set_active(&mut self, active: bool) {
+        if self.active == active {
+            return;
+        }
+
+        self.active = active;
+        self.page_flip_scheduled = false;
+        if active {
+            if let Err(err) = self.output.restore_mode() {
+                log_error!("Display: {}", err);
+            }
+            self.apply_mode();
+            self.last_scene = None;
+            self.redraw_needed = false;
+            self.request_redraw();
+        }
+    }
+
+    /// Powers the output off if it is still on.
+    fn power_off(&mut self) {
+        if self.output.is_powered() {
+            if let Err(err) = self.output.set_power(false) {
+                log_error!("Display: failed to power off: {}", err);
+            }
+        }
+    }
+
+    /// Draws one frame if in single-step mode.
+    pub fn step(&mut self) {
+        if (self.redraw_mode == RedrawMode::SingleStep) && !self.page_flip_scheduled {
+            self.redraw_all();
+        }
+    }
+
     /// Prepare rendering context for layover.
     pub fn prepare_layover_context(&self) -> SurfaceContext {
-        SurfaceContext::new(self.pointer.borrow().get_cursor_sid(),
-                            self.pointer.borrow().get_global_position())
+        let csid = if self.pointer.borrow().is_cursor_hidden() {
+            SurfaceId::invalid()
+        } else {
+            self.pointer.borrow().get_cursor_sid()
+        };
+        self.prepare_single_context(csid, self.pointer.borrow().get_global_position())
+    }
+
+    /// Prepare rendering contexts for surface moved interactively if it overlaps this display. The
+    /// surface is drawn on every display it overlaps.
+    pub fn prepare_moved_contexts(&self) -> Vec<SurfaceContext> {
+        if self.coordinator.is_session_locked() {
+            return Vec::new();
+        }
+
+        let moved = self.pointer.borrow().get_moved();
+        if let Some((sid, position)) = moved {
+            if let Some(info) = self.coordinator.get_surface(sid) {
+                let area = Area::new(position, info.requested_size);
+                if area.is_overlapping(&self.output.get_area()) {
+                    if let Some(contexts) = self.coordinator.get_renderer_context(sid) {
+                        return contexts.iter().map(|context| context.moved(position)).collect();
+                    }
+                }
+            }
+        }
+        Vec::new()
+    }
+
+    /// Prepare rendering context for drag icon if there is one.
+    pub fn prepare_drag_icon_context(&self) -> Option<SurfaceContext> {
+        let pointer = self.pointer.borrow();
+        let disid = pointer.get_drag_icon_sid();
+        if pointer.is_dragging() && disid.is_valid() && !self.coordinator.is_session_locked() {
+            Some(self.prepare_single_context(disid, pointer.get_global_position()))
+        } else {
+            None
+        }
     }
 
-    /// Draw the scene and then schedule page flip.
+    /// Draw the scene and then schedule page flip. Drawing is postponed while display is off or
+    /// virtual terminal is released.
     pub fn redraw_all(&mut self) {
-        let surfaces = self.frame
-            .get_first_time()
-            .expect("display must have at least one workspace")
-            .to_array(&self.coordinator);
+        if !self.powered || !self.active {
+            self.redraw_needed = true;
+            return;
+        }
+
+        self.update_decorations();
+        let mut surfaces = self.prepare_surface_contexts();
+        surfaces.append(&mut self.prepare_moved_contexts());
+        surfaces.extend(self.menu.borrow().prepare_context(&self.output.get_area()));
 
         let pointer = self.prepare_layover_context();
         self.pointer.borrow_mut().update_hover_state(self.output.get_area(), &surfaces);
+        if let Some(context) = self.prepare_drag_icon_context() {
+            surfaces.push(context);
+        }
+        self.overlap_watcher.borrow_mut().update(self.output.get_info().id, &surfaces);
 
-        if let Err(err) = self.output.draw(&surfaces, pointer, &self.coordinator) {
+        let start = Instant::now();
+        if let Err(err) = self.output.draw(&surfaces, pointer.clone(), &self.coordinator) {
             log_error!("Display: {}", err);
         }
 
         if let Err(err) = self.output.swap_buffers() {
             log_error!("Display: {}", err);
         }
+        self.coordinator.record_frame(&self.get_name(), start.elapsed());
+
+        if let Some(frame) = self.output.take_screencast_frame() {
+            let perceptron = Perceptron::ScreencastFrame(self.output.get_info().id, frame);
+            self.signaler.emit(perceptron::SCREENCAST_FRAME, perceptron);
+        }
+
+        if let Some(frame) = self.output.take_captured_frame() {
+            self.handle_captured_frame(frame);
+        }
 
-        // Send frame notifications
+        // Remember what was drawn to be able to tell if next change affects this display
+        let pointer_on_display = if pointer.pos.is_inside(&self.output.get_area()) {
+            Some(&pointer)
+        } else {
+            None
+        };
+        self.last_scene = Some(Scene::new(&surfaces, pointer_on_display));
+
+        // Damage was drawn and is not needed any more
+        for context in surfaces.iter().chain(Some(&pointer)) {
+            if context.damage.len() > 0 {
+                self.coordinator.discard_damage(context.id, &context.damage, context.generation);
+            }
+        }
+
+        // Send frame notifications and remember surfaces to inform about their presentation
+        self.drawn_sids.extend(surfaces.iter().map(|context| context.id));
         for context in surfaces {
             let frame = Perceptron::SurfaceFrame(context.id, Milliseconds::now());
             self.signaler.emit(perceptron::SURFACE_FRAME, frame);
@@ -121,9 +914,38 @@ impl Display {
         }
     }
 
+    /// Finds surface drawn at given position in global coordinates the same way pointer finds
+    /// hovered surface. Returns ID of the surface and the position relative to the surface.
+    pub fn find_surface_at(&self, position: Position) -> Option<(SurfaceId, Position)> {
+        let area = self.output.get_area();
+        if !area.contains(&position) {
+            return None;
+        }
+
+        let display_relative = position - area.pos;
+        let mut surfaces = self.prepare_surface_contexts();
+        surfaces.append(&mut self.prepare_moved_contexts());
+        for context in surfaces.iter().rev() {
+            if let Some(placement) = self.coordinator.get_surface_placement(context) {
+                let window = Area::new(context.pos + placement.window.pos, placement.window.size);
+                if window.contains(&display_relative) {
+                    return Some((context.id, placement.to_surface(display_relative - context.pos)));
+                }
+            }
+        }
+        None
+    }
+
     /// Get information about output (size, position, model name, etc.).
     pub fn get_info(&self) -> OutputInfo {
-        self.output.get_info()
+        let mut info = self.output.get_info();
+        info.enabled = self.enabled;
+        info
+    }
+
+    /// Returns name of the output.
+    pub fn get_name(&self) -> String {
+        self.output.get_name()
     }
 }
 
@@ -0,0 +1,78 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains functionality related to switching workspaces when dragged item is held
+//! at the edge of display.
+
+// -------------------------------------------------------------------------------------------------
+
+use std::time::{Duration, Instant};
+
+use qualia::{Area, Direction, ExhibitorConfig, Position};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Tracks how long pointer stays at left or right edge of display and decides when workspace
+/// should be switched.
+pub struct EdgeSwitcher {
+    /// Time pointer has to be held at the edge before switching.
+    delay: Duration,
+
+    /// Edge pointer is currently held at and time since when it is held there. `Backward` means
+    /// left edge, `Forward` means right edge.
+    edge: Option<(Direction, Instant)>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl EdgeSwitcher {
+    /// Constructs new `EdgeSwitcher`.
+    pub fn new(config: &ExhibitorConfig) -> Self {
+        EdgeSwitcher {
+            delay: Duration::from_millis(config.edge_switch_delay),
+            edge: None,
+        }
+    }
+
+    /// Updates pointer position. `area` is area of display the pointer is placed on.
+    ///
+    /// Returns direction in which workspace should be switched if pointer was held at the edge
+    /// long enough. Holding pointer further at the edge will switch workspaces again after next
+    /// delay.
+    pub fn update(&mut self, position: Position, area: Area) -> Option<Direction> {
+        let edge = if position.x <= area.pos.x {
+            Some(Direction::Backward)
+        } else if position.x >= (area.pos.x + area.size.width as isize - 1) {
+            Some(Direction::Forward)
+        } else {
+            None
+        };
+
+        let now = Instant::now();
+        match (edge, self.edge) {
+            (Some(direction), Some((last_direction, since))) if direction == last_direction => {
+                if now.duration_since(since) >= self.delay {
+                    self.edge = Some((direction, now));
+                    Some(direction)
+                } else {
+                    None
+                }
+            }
+            (Some(direction), _) => {
+                self.edge = Some((direction, now));
+                None
+            }
+            (None, _) => {
+                self.edge = None;
+                None
+            }
+        }
+    }
+
+    /// Stops tracking the pointer.
+    pub fn reset(&mut self) {
+        self.edge = None;
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -12,26 +12,55 @@ extern crate timber;
 extern crate qualia;
 extern crate frames;
 extern crate output;
+extern crate uinput_sys;
 
 mod surface_history;
 mod compositor;
 mod pointer;
 mod display;
+mod overlap_watcher;
+mod edge_switcher;
+mod click_counter;
+mod canvas;
+mod decorator;
+mod menu;
+mod resize_grab;
+mod region_selection;
+mod touch;
+mod gestures;
+mod auto_hide;
 
 // -------------------------------------------------------------------------------------------------
 
 use std::rc::Rc;
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::path::Path;
 
-use dharma::Signaler;
-use qualia::{Coordinator, SurfaceId, Button, Command, OptionalPosition, Vector};
-use qualia::{perceptron, Perceptron};
+use dharma::{Dispatcher, Signaler};
+use qualia::{AbsolutePosition, Action, Area, Coordinator, SurfaceId, Button, Command, Direction};
+use qualia::{OptionalPosition, OutputConfiguration, OutputInfo, OutputMode};
+use qualia::{ExhibitorConfig, Key, Position, ProfileConfig, ScreencastConfig, Size, Vector};
+use qualia::{KeyCode, KeyState, KeyValue};
+use qualia::{Switch, SwitchAction, SwitchConfig, Transform};
+use qualia::{wallpaper, WallpaperConfig, WallpaperMode};
+use qualia::{GestureKind, GestureMotion, IdleConfig, Milliseconds, PresentationTime, TouchPoint};
+use qualia::{perceptron, surface_state, Perceptron, Pixmap, ScreenshotTarget};
 use output::Output;
 
 use compositor::Compositor;
 use pointer::Pointer;
-use display::Display;
+use display::{Display, RedrawMode};
+use overlap_watcher::OverlapWatcher;
+use edge_switcher::EdgeSwitcher;
+use click_counter::ClickCounter;
+use decorator::{Decorator, DecorationPart};
+use menu::{Menu, MenuItem};
+use resize_grab::ResizeGrab;
+use region_selection::{RegionSelection, SelectionPurpose};
+use touch::Touch;
+use gestures::{GestureAction, Gestures};
+use auto_hide::AutoHide;
 
 // -------------------------------------------------------------------------------------------------
 
@@ -40,7 +69,33 @@ pub struct Exhibitor {
     last_output_id: i32,
     compositor: Compositor,
     pointer: Rc<RefCell<Pointer>>,
+    overlap_watcher: Rc<RefCell<OverlapWatcher>>,
+    edge_switcher: EdgeSwitcher,
+    click_counter: ClickCounter,
+    touch: Touch,
+    gestures: Gestures,
+    decorator: Rc<RefCell<Decorator>>,
+    menu: Rc<RefCell<Menu>>,
+    auto_hide: Rc<RefCell<AutoHide>>,
+    resize_grab: Option<ResizeGrab>,
+    selection: Option<RegionSelection>,
+    preserve_aspect_ratio: bool,
     displays: HashMap<i32, Display>,
+    redraw_mode: RedrawMode,
+    visualize_damage: bool,
+    linear_blending: bool,
+    dim_unfocused: f32,
+    wallpapers: Vec<WallpaperConfig>,
+    profiles: Vec<ProfileConfig>,
+    active_profile: Option<String>,
+    screencast: Option<ScreencastConfig>,
+    switches: Vec<SwitchConfig>,
+    auto_rotation: bool,
+    auto_rotation_output: Option<String>,
+    tablet_mode: bool,
+    orientation: Transform,
+    idle: IdleConfig,
+    powered: bool,
     coordinator: Coordinator,
     signaler: Signaler<Perceptron>,
 }
@@ -49,13 +104,48 @@ pub struct Exhibitor {
 
 /// General methods.
 impl Exhibitor {
-    /// `Exhibitor` constructor.
-    pub fn new(signaler: Signaler<Perceptron>, coordinator: Coordinator) -> Self {
+    /// `Exhibitor` constructor. Timers are added to given `Dispatcher`.
+    pub fn new(signaler: Signaler<Perceptron>,
+               mut coordinator: Coordinator,
+               dispatcher: Dispatcher,
+               config: ExhibitorConfig)
+               -> Self {
+        let pointer =
+            Pointer::new(signaler.clone(), coordinator.clone(), dispatcher.clone(), config.cursor);
+        let auto_hide = AutoHide::new(signaler.clone(), dispatcher, config.auto_hide.clone());
+        coordinator.set_size_mismatch_policy(config.size_mismatch_policy);
         Exhibitor {
             last_output_id: 0,
-            compositor: Compositor::new(coordinator.clone()),
-            pointer: Rc::new(RefCell::new(Pointer::new(signaler.clone(), coordinator.clone()))),
+            compositor: Compositor::new(coordinator.clone(), &config),
+            pointer: Rc::new(RefCell::new(pointer)),
+            overlap_watcher: Rc::new(RefCell::new(OverlapWatcher::new(signaler.clone(),
+                                                                      coordinator.clone()))),
+            edge_switcher: EdgeSwitcher::new(&config),
+            click_counter: ClickCounter::new(&config),
+            touch: Touch::new(signaler.clone()),
+            gestures: Gestures::new(signaler.clone(), &config),
+            decorator: Rc::new(RefCell::new(Decorator::new(coordinator.clone(), &config))),
+            menu: Rc::new(RefCell::new(Menu::new(coordinator.clone()))),
+            auto_hide: Rc::new(RefCell::new(auto_hide)),
+            resize_grab: None,
+            selection: None,
+            preserve_aspect_ratio: config.preserve_aspect_ratio,
             displays: HashMap::new(),
+            redraw_mode: RedrawMode::DamageOnly,
+            visualize_damage: config.visualize_damage,
+            linear_blending: config.linear_blending,
+            dim_unfocused: config.dim_unfocused,
+            wallpapers: config.wallpapers.clone(),
+            profiles: config.profiles.clone(),
+            active_profile: None,
+            screencast: config.screencast.clone(),
+            switches: config.switches.clone(),
+            auto_rotation: config.auto_rotation,
+            auto_rotation_output: config.auto_rotation_output.clone(),
+            tablet_mode: false,
+            orientation: Transform::Normal,
+            idle: config.idle,
+            powered: true,
             coordinator: coordinator,
             signaler: signaler,
         }
@@ -77,7 +167,8 @@ impl Exhibitor {
     pub fn on_output_found(&mut self, bundle: qualia::DrmBundle) {
         log_info1!("Exhibitor: found output");
         let id = self.generate_next_output_id();
-        let output = match Output::new(bundle, id) {
+        let mode = self.find_profile_mode(&Output::get_connector_name(&bundle));
+        let mut output = match Output::new(bundle, id, mode) {
             Ok(output) => {
                 log_info2!("Created output: {}", output.get_name());
                 output
@@ -88,36 +179,161 @@ impl Exhibitor {
             }
         };
 
+        // Place new output on the right of already existing ones
+        let right = self.displays
+            .values()
+            .map(|display| {
+                     let area = display.get_info().area;
+                     area.pos.x + area.size.width as isize
+                 })
+            .max()
+            .unwrap_or(0);
+        output.set_position(Position::new(right, 0));
+
         let info = output.get_info();
         if self.displays.len() == 0 {
             self.pointer.borrow_mut().change_display(info.area);
         }
+        self.overlap_watcher.borrow_mut().on_output_changed(info.clone());
 
         log_info1!("Exhibitor: creating display");
         let display_frame = self.compositor.create_display(output.get_area(), output.get_name());
-        let display = Display::new(self.coordinator.clone(),
+        let mut display = Display::new(self.coordinator.clone(),
                                    self.signaler.clone(),
                                    self.pointer.clone(),
+                                   self.overlap_watcher.clone(),
+                                   self.decorator.clone(),
+                                   self.menu.clone(),
+                                   self.auto_hide.clone(),
                                    output,
                                    display_frame);
+        display.set_redraw_mode(self.redraw_mode);
+        display.set_power(self.powered);
+        if self.visualize_damage {
+            display.set_damage_visualization(true);
+        }
+        display.set_linear_blending(self.linear_blending);
+        display.set_dim_unfocused(self.dim_unfocused);
+        Self::apply_wallpaper(&self.wallpapers, &mut display);
         self.displays.insert(id, display);
+        self.apply_profile();
+        self.apply_screencast();
 
+        let info = self.displays.get(&id).expect("display was just inserted").get_info();
         self.signaler.emit(perceptron::DISPLAY_CREATED, Perceptron::DisplayCreated(info));
     }
 
     /// This method is called when pageflip occurred.
-    /// `id` is ID of output that scheduled the pageflip.
-    pub fn on_pageflip(&mut self, id: i32) {
+    /// `id` is ID of output that scheduled the pageflip, `time` is when the frame was presented.
+    pub fn on_pageflip(&mut self, id: i32, time: PresentationTime) {
         // Pass notification to associated display
         if let Some(ref mut display) = self.displays.get_mut(&id) {
-            display.on_pageflip();
+            display.on_pageflip(time);
+        }
+    }
+
+    /// This method is called when mode of display was changed. Pointer follows change of area of
+    /// the display it is on and layer surfaces are arranged to new size of the display.
+    pub fn on_display_changed(&mut self, info: OutputInfo) {
+        {
+            let mut pointer = self.pointer.borrow_mut();
+            let area = pointer.get_display_area();
+            if (area.pos == info.area.pos) && (area != info.area) {
+                pointer.change_display(info.area);
+            }
         }
+        self.overlap_watcher.borrow_mut().on_output_changed(info);
+        self.arrange_layers();
+        self.coordinator.notify();
     }
 
     /// This method is called when a command was requested to be executed by compositor.
     pub fn on_command(&mut self, command: Command) {
         log_info2!("Received command: {:?}", command);
-        self.compositor.execute_command(command);
+        if command.action == Action::Redraw {
+            self.change_redraw(command.direction);
+        } else if command.action == Action::Trace {
+            self.change_tracing(command.direction);
+        } else if command.action == Action::Layout {
+            // Keyboard layouts are handled by Wayland frontend.
+        } else if command.action == Action::Terminal {
+            // Virtual terminals are switched by Device Manager.
+        } else if command.action == Action::Media {
+            // Media keys are handled by Device Manager.
+        } else if command.action == Action::Power {
+            self.change_power(command.direction);
+        } else if command.action == Action::Screenshot {
+            self.take_screenshot();
+        } else if command.action == Action::Rotate {
+            self.change_rotation(command.direction, &command.string);
+        } else if command.action == Action::Mode {
+            self.change_mode(command.direction, &command.string);
+        } else if self.coordinator.is_session_locked() {
+            log_info2!("Ignoring command while session is locked");
+        } else if command.action == Action::Zoom {
+            self.toggle_zoom();
+        } else {
+            self.compositor.execute_command(command);
+        }
+    }
+
+    /// This method is called when client requested capturing contents of output.
+    pub fn on_capture_requested(&mut self, output_id: i32) {
+        if let Some(ref mut display) = self.displays.get_mut(&output_id) {
+            display.request_capture();
+        }
+    }
+
+    /// This method is called when client requested configuration of outputs. The configuration is
+    /// applied only if it is valid and was not requested only to be tested. Result is emitted in
+    /// `OutputsConfigured`.
+    pub fn on_output_configuration_requested(&mut self,
+                                             id: u32,
+                                             configurations: Vec<OutputConfiguration>,
+                                             test: bool) {
+        let result = self.validate_output_configuration(&configurations);
+        if result.is_ok() && !test {
+            for configuration in configurations.iter() {
+                self.configure_display(configuration);
+            }
+            self.arrange_layers();
+            self.coordinator.notify();
+        }
+
+        if let Err(ref err) = result {
+            log_warn2!("Exhibitor: output configuration rejected: {}", err);
+        }
+        self.signaler.emit(perceptron::OUTPUTS_CONFIGURED,
+                           Perceptron::OutputsConfigured(id, result));
+    }
+
+    /// This method is called when screenshot was requested over control socket. Region is cut to
+    /// display containing its top left corner. If the screenshot can not be taken error is
+    /// emitted at once. If the region is to be selected by user, the selection is started.
+    pub fn on_screenshot_requested(&mut self, id: u32, target: ScreenshotTarget) {
+        if target == ScreenshotTarget::Selection {
+            self.start_selection(SelectionPurpose::Screenshot(id));
+            return;
+        }
+
+        let (display_id, area) = match self.find_screenshot_area(&target) {
+            Ok(found) => found,
+            Err(err) => {
+                let perceptron = Perceptron::ScreenshotTaken(id, Err(err));
+                self.signaler.emit(perceptron::SCREENSHOT_TAKEN, perceptron);
+                return;
+            }
+        };
+
+        if let Some(display) = self.displays.get_mut(&display_id) {
+            display.request_area_screenshot(id, area);
+        }
+    }
+
+    /// This method is called when interactive selection of screen region was requested over
+    /// control socket.
+    pub fn on_region_selection_requested(&mut self, id: u32) {
+        self.start_selection(SelectionPurpose::Report(id));
     }
 
     /// This method is called when changing cursor surface was requested.
@@ -127,18 +343,290 @@ impl Exhibitor {
 
     /// This method is called when new surface is ready to be managed.
     pub fn on_surface_ready(&mut self, sid: SurfaceId) {
+        self.decorator.borrow_mut().on_surface_ready(sid);
         self.compositor.manage_surface(sid);
     }
 
     /// This method is called when surface was destroyed.
     pub fn on_surface_destroyed(&mut self, sid: SurfaceId) {
+        for display in self.displays.values_mut() {
+            if display.get_fullscreen_sid() == Some(sid) {
+                display.set_fullscreen_sid(None);
+            }
+            if display.get_zoomed_sid() == Some(sid) {
+                display.set_zoomed_sid(None);
+            }
+        }
+        if self.menu.borrow().get_target_sid() == sid {
+            self.menu.borrow_mut().close();
+        }
+        if self.resize_grab.as_ref().map_or(false, |grab| grab.get_sid() == sid) {
+            self.resize_grab = None;
+            self.pointer.borrow_mut().on_resize_ended();
+        }
         self.compositor.unmanage_surface(sid);
         self.pointer.borrow_mut().on_surface_destroyed(sid);
+        self.overlap_watcher.borrow_mut().on_surface_destroyed(sid);
+        self.touch.on_surface_destroyed(sid);
+        self.gestures.on_surface_destroyed(sid);
+        self.decorator.borrow_mut().on_surface_destroyed(sid);
+    }
+
+    /// This method is called when title of surface changed.
+    pub fn on_surface_title_changed(&mut self, sid: SurfaceId) {
+        self.decorator.borrow_mut().on_title_changed(sid);
+    }
+
+    /// This method is called when client requested decoration mode for surface.
+    pub fn on_decoration_mode_requested(&mut self, sid: SurfaceId) {
+        self.decorator.borrow_mut().on_decoration_mode_requested(sid);
+    }
+
+    /// This method is called when configuration was reloaded. Applies new settings without
+    /// touching existing displays and workspaces.
+    pub fn on_config_reloaded(&mut self, config: ExhibitorConfig) {
+        self.edge_switcher = EdgeSwitcher::new(&config);
+        self.click_counter = ClickCounter::new(&config);
+        self.gestures.set_config(&config);
+        self.compositor.set_config(&config);
+        self.decorator.borrow_mut().set_config(&config);
+        self.pointer.borrow_mut().set_cursor_config(config.cursor);
+        self.preserve_aspect_ratio = config.preserve_aspect_ratio;
+        self.profiles = config.profiles.clone();
+        self.active_profile = None;
+        self.apply_profile();
+        self.screencast = config.screencast.clone();
+        self.apply_screencast();
+        self.switches = config.switches.clone();
+        self.auto_rotation = config.auto_rotation;
+        self.auto_rotation_output = config.auto_rotation_output.clone();
+        if self.visualize_damage != config.visualize_damage {
+            self.visualize_damage = config.visualize_damage;
+            for display in self.displays.values_mut() {
+                display.set_damage_visualization(self.visualize_damage);
+            }
+        }
+        if self.linear_blending != config.linear_blending {
+            self.linear_blending = config.linear_blending;
+            for display in self.displays.values_mut() {
+                display.set_linear_blending(self.linear_blending);
+            }
+        }
+        if self.dim_unfocused != config.dim_unfocused {
+            self.dim_unfocused = config.dim_unfocused;
+            for display in self.displays.values_mut() {
+                display.set_dim_unfocused(self.dim_unfocused);
+            }
+        }
+        if self.wallpapers != config.wallpapers {
+            self.wallpapers = config.wallpapers.clone();
+            for display in self.displays.values_mut() {
+                Self::apply_wallpaper(&self.wallpapers, display);
+            }
+        }
+        if *self.auto_hide.borrow().get_config() != config.auto_hide {
+            self.auto_hide.borrow_mut().set_config(config.auto_hide.clone());
+            self.arrange_layers();
+        }
+        if self.coordinator.get_size_mismatch_policy() != config.size_mismatch_policy {
+            self.coordinator.set_size_mismatch_policy(config.size_mismatch_policy);
+            for display in self.displays.values_mut() {
+                display.request_redraw();
+            }
+        }
+    }
+
+    /// This method is called when drag-and-drop operation started.
+    pub fn on_drag_started(&mut self) {
+        self.pointer.borrow_mut().on_drag_started();
+        self.coordinator.notify();
+    }
+
+    /// This method is called when drag-and-drop operation ended.
+    pub fn on_drag_ended(&mut self) {
+        self.pointer.borrow_mut().on_drag_ended();
+        self.edge_switcher.reset();
+        self.coordinator.notify();
+    }
+
+    /// This method is called when client requested interactive move of surface. Moved surface
+    /// follows the pointer and is drawn on all displays it overlaps until button is released.
+    pub fn on_move_requested(&mut self, sid: SurfaceId) {
+        if self.pointer.borrow().is_dragging() {
+            return;
+        }
+
+        if let Some(origin) = self.compositor.start_moving(sid) {
+            self.pointer.borrow_mut().on_move_started(sid, origin);
+            self.coordinator.notify();
+        }
+    }
+
+    /// This method is called when client requested to make surface fullscreen or to restore it.
+    /// Fullscreen surface is drawn alone on the display (chosen by client or the one containing
+    /// the surface) while its workspace is visible. Its frame stays in layout so restoring brings
+    /// back previous size and placement.
+    pub fn on_fullscreen_requested(&mut self,
+                                   sid: SurfaceId,
+                                   fullscreen: bool,
+                                   output_id: Option<i32>) {
+        self.restore_fullscreen(sid);
+        if !fullscreen {
+            return;
+        }
+
+        let position = self.compositor.get_surface_position(sid);
+        let pointer_area = self.pointer.borrow().get_display_area();
+        let id = output_id.or_else(|| {
+            self.displays
+                .iter()
+                .find(|&(_, display)| {
+                    let area = display.get_info().area;
+                    position.map_or(area == pointer_area, |position| area.contains(&position))
+                })
+                .map(|(id, _)| *id)
+        });
+
+        let id = if let Some(id) = id {
+            id
+        } else {
+            return;
+        };
+
+        let size = if let Some(display) = self.displays.get_mut(&id) {
+            if let Some(old_sid) = display.get_fullscreen_sid() {
+                self.coordinator.set_surface_fullscreen(old_sid, None);
+                self.compositor.refresh_surface(old_sid);
+            }
+            display.set_fullscreen_sid(Some(sid));
+            display.get_info().area.size
+        } else {
+            return;
+        };
+
+        self.coordinator.set_surface_fullscreen(sid, Some(size));
+        self.coordinator.notify();
+    }
+
+    /// This method is called when client requested to maximize surface or to restore it.
+    pub fn on_maximize_requested(&mut self, sid: SurfaceId, maximized: bool) {
+        self.compositor.set_maximized(sid, maximized);
+    }
+
+    /// This method is called when surface needs to be configured before it is mapped.
+    pub fn on_initial_size_requested(&mut self, sid: SurfaceId) {
+        self.compositor.configure_initially(sid);
+    }
+
+    /// This method is called when client requested placing of pop-up. The pop-up is placed relative
+    /// to its parent and adjusted to fit in display containing its anchor point.
+    pub fn on_placement_requested(&mut self, sid: SurfaceId) {
+        let info = if let Some(info) = self.coordinator.get_surface(sid) {
+            info
+        } else {
+            return;
+        };
+        let positioner = if let Some(positioner) = info.positioner {
+            positioner
+        } else {
+            return;
+        };
+
+        let mut area = positioner.get_area();
+        if let Some(origin) = self.find_global_position(info.parent_sid) {
+            let anchor = origin + positioner.anchor_rect.pos;
+            for display in self.displays.values() {
+                let display_area = display.get_info().area;
+                if display_area.contains(&anchor) {
+                    let bounds = Area::new(display_area.pos - origin, display_area.size);
+                    area = positioner.get_constrained_area(bounds);
+                    break;
+                }
+            }
+        }
+
+        self.coordinator.place_surface(sid, area);
+        self.coordinator.notify();
     }
 
     /// This method is called when keyboard focus changed.
     pub fn on_keyboard_focus_changed(&mut self, sid: SurfaceId) {
         self.pointer.borrow_mut().on_keyboard_focus_changed(sid);
+        self.decorator.borrow_mut().on_keyboard_focus_changed(sid);
+    }
+
+    /// This method is called when no input arrived for `timeout` milliseconds. Powers displays
+    /// off if the timeout is the one from configuration and it is enabled.
+    pub fn on_idle(&mut self, timeout: u32) {
+        if self.idle.power_off && (self.idle.timeout > 0) && (timeout == 1000 * self.idle.timeout) {
+            log_info1!("Exhibitor: user idle, powering displays off");
+            self.set_power(false);
+        }
+    }
+
+    /// This method is called when session was locked or unlocked. Interactive operations are
+    /// cancelled so nothing but lock surfaces reacts to input while locked.
+    pub fn on_session_lock_changed(&mut self, locked: bool) {
+        log_info1!("Exhibitor: session {}", if locked { "locked" } else { "unlocked" });
+        if locked {
+            self.menu.borrow_mut().close();
+            if self.resize_grab.take().is_some() {
+                self.pointer.borrow_mut().on_resize_ended();
+            }
+        }
+        for display in self.displays.values_mut() {
+            display.request_redraw();
+        }
+    }
+
+    /// This method is called when lock surface was assigned to output. Lock surface is sized to
+    /// cover the whole display.
+    pub fn on_lock_surface_added(&mut self, sid: SurfaceId, output_id: i32) {
+        if let Some(display) = self.displays.get_mut(&output_id) {
+            let size = display.get_info().area.size;
+            self.coordinator.reconfigure(sid, size, surface_state::REGULAR);
+            display.request_redraw();
+        }
+    }
+
+    /// This method is called when state of layer surface was committed or the surface was
+    /// removed. Layer surface without output is placed on the display with pointer. Layer surfaces
+    /// are sized according to their anchors and margins and workspaces are reflowed into space not
+    /// reserved by exclusive zones.
+    pub fn on_layer_surface_changed(&mut self, sid: SurfaceId) {
+        if let Some(surface) = self.coordinator.get_layer_surface(sid) {
+            if surface.output_id.is_none() {
+                let position = self.pointer.borrow().get_global_position();
+                let info = self.displays
+                    .values()
+                    .map(|display| display.get_info())
+                    .find(|info| info.area.contains(&position));
+                if let Some(info) = info {
+                    self.coordinator.set_layer_surface_output(sid, info.id);
+                }
+            }
+        } else {
+            self.auto_hide.borrow_mut().remove(sid);
+        }
+        self.arrange_layers();
+    }
+
+    /// This method is called when virtual terminal was released or acquired. Drawing is suspended
+    /// while other virtual terminal is active.
+    pub fn on_vt_changed(&mut self, acquired: bool) {
+        log_info1!("Exhibitor: virtual terminal {}",
+                   if acquired { "acquired" } else { "released" });
+        for display in self.displays.values_mut() {
+            display.set_active(acquired);
+        }
+    }
+
+    /// This method is called when input arrived after user was idle. Powers displays on.
+    pub fn on_resumed(&mut self) {
+        if self.idle.power_off && !self.powered {
+            log_info1!("Exhibitor: user active, powering displays on");
+            self.set_power(true);
+        }
     }
 }
 
@@ -148,42 +636,1006 @@ impl Exhibitor {
 impl Exhibitor {
     /// Handle pointer motion event.
     pub fn on_motion(&mut self, vector: Vector) {
+        let old_position = self.pointer.borrow().get_global_position();
         self.pointer.borrow_mut().move_and_cast(vector, &self.displays);
+        self.update_selection();
+        self.resize_at_border(old_position);
+        self.switch_workspace_at_edge();
+        self.update_auto_hide();
         self.coordinator.notify();
     }
 
     /// Handle pointer position event.
     pub fn on_position(&mut self, position: OptionalPosition) {
+        let old_position = self.pointer.borrow().get_global_position();
         self.pointer.borrow_mut().update_position(position, &self.displays);
+        self.update_selection();
+        self.resize_at_border(old_position);
+        self.switch_workspace_at_edge();
+        self.update_auto_hide();
         self.coordinator.notify();
     }
 
     /// Handle pointer button event.
+    ///
+    /// Pressing button while meta key is held starts interactive move of hovered surface, or resize
+    /// if the button is right one and the surface is floating. Pressing button on border between
+    /// tiled frames starts resizing them, double-clicking it makes the frames equal. Right button
+    /// on title bar opens context menu. Pointer focus is latched while any button is pressed.
+    /// While overview of workspaces is shown clicks are not passed to clients and releasing button
+    /// switches to clicked workspace. Buttons of seats other than the default one only move
+    /// keyboard focus of their seat to hovered surface. While region of screen is selected
+    /// interactively clicks are used only for the selection. Clicking layer surface focuses it
+    /// only if its keyboard interactivity allows it.
     pub fn on_button(&mut self, button: Button) {
+        if self.selection.is_some() {
+            self.on_selection_clicked(button);
+            return;
+        }
+
+        if self.displays.values().any(|display| display.is_overview()) {
+            if button.value == 0 {
+                self.on_overview_clicked();
+            }
+            return;
+        }
+
+        self.pointer.borrow_mut().on_button(&button);
+
+        // Other seats share the pointer but have their own keyboard focus
+        if !button.seat.is_default() {
+            if button.value != 0 {
+                let pfsid = self.pointer.borrow().get_pointer_focussed_sid();
+                self.coordinator.set_seat_keyboard_focus(button.seat, pfsid);
+            }
+            return;
+        }
+
+        // Open menu grabs all clicks
+        if self.menu.borrow().is_open() {
+            if button.value != 0 {
+                self.on_menu_clicked();
+            }
+            return;
+        }
+
         // TODO: Be more specific about button codes and values.
-        if button.value != 0 {
+        let moved = self.pointer.borrow().get_moved();
+        if self.pointer.borrow().is_dragging() {
+            if button.value == 0 {
+                self.coordinator.drop_drag();
+            }
+        } else if self.pointer.borrow().is_resizing() {
+            if button.value == 0 {
+                self.resize_grab = None;
+                self.pointer.borrow_mut().on_resize_ended();
+            }
+        } else if let Some((_, origin)) = moved {
+            if button.value == 0 {
+                let position = self.pointer.borrow().get_global_position();
+                self.pointer.borrow_mut().on_move_ended();
+                self.compositor.finish_moving(position, origin);
+            }
+        } else if button.value != 0 {
             let pfsid = self.pointer.borrow_mut().get_pointer_focussed_sid();
-            if self.pointer.borrow_mut().get_keyboard_focussed_sid() != pfsid {
+            if self.coordinator.get_layer_surface(pfsid).is_some() {
+                self.coordinator.set_keyboard_focus(pfsid);
+                return;
+            }
+            let position = self.pointer.borrow().get_surface_relative_position();
+            let part = self.decorator.borrow().find_part(pfsid, position);
+            let global_position = self.pointer.borrow().get_global_position();
+            let double_click = self.click_counter.click(button.code, global_position) == 2;
+            if self.pointer.borrow().is_move_modifier_pressed() && pfsid.is_valid() {
+                let resized = (button.code == uinput_sys::BTN_RIGHT as u16) &&
+                              self.start_resizing(pfsid, global_position);
+                if !resized {
+                    self.compositor.pop_surface(pfsid);
+                    self.on_move_requested(pfsid);
+                }
+            } else if self.compositor.has_border_at(global_position) {
+                if double_click {
+                    self.click_counter.reset();
+                    self.compositor.equalize_border(global_position);
+                } else {
+                    self.pointer.borrow_mut().on_resize_started();
+                }
+            } else if let Some((sid, DecorationPart::TitleBar)) = part {
+                if button.code == uinput_sys::BTN_RIGHT as u16 {
+                    self.open_menu(sid, global_position);
+                } else {
+                    self.on_decoration_clicked(sid, DecorationPart::TitleBar, double_click);
+                }
+            } else if let Some((sid, part)) = part {
+                self.on_decoration_clicked(sid, part, double_click);
+            } else if self.pointer.borrow_mut().get_keyboard_focussed_sid() != pfsid {
                 self.compositor.pop_surface(pfsid);
             }
         }
     }
 
+    /// Handle keyboard event. Pressing `Escape` cancels interactive selection of screen region.
+    pub fn on_key(&mut self, key: Key) {
+        if self.selection.is_some() && (key.code == uinput_sys::KEY_ESC as KeyCode) &&
+           (key.value == KeyState::Pressed as KeyValue) {
+            self.finish_selection(Err(format!("Selection cancelled")));
+        }
+        self.pointer.borrow_mut().on_key(key);
+    }
+
+    /// Handle toggling of physical switch by performing action configured for it.
+    pub fn on_switch_toggled(&mut self, switch: Switch, state: bool) {
+        if switch == Switch::TabletMode {
+            self.tablet_mode = state;
+            self.apply_rotation();
+        }
+
+        let action = match self.switches.iter().find(|config| config.switch == switch) {
+            Some(config) => config.action,
+            None => return,
+        };
+
+        match action {
+            SwitchAction::RotateOutput(transform) => {
+                let transform = if state { transform } else { Transform::Normal };
+                log_info2!("Exhibitor: switch {:?} toggled, transforming outputs to {:?}",
+                           switch,
+                           transform);
+                self.transform_displays(transform, None);
+            }
+        }
+    }
+
+    /// Handle change of orientation of the device reported by accelerometer.
+    pub fn on_orientation_changed(&mut self, transform: Transform) {
+        self.orientation = transform;
+        self.apply_rotation();
+    }
+
+    /// Handle absolute pointer position event.
+    ///
+    /// Position is normalized to the output the device is mapped to (or the one with pointer if
+    /// the device is not mapped) in its natural orientation, so it is transformed the same way as
+    /// the output before being placed in global coordinates.
+    pub fn on_absolute_position(&mut self, pos: AbsolutePosition) {
+        let (position, area) = self.find_absolute_position(&pos);
+        let old_position = self.pointer.borrow().get_global_position();
+        self.pointer.borrow_mut().move_to(position, area);
+        self.update_selection();
+        self.resize_at_border(old_position);
+        self.switch_workspace_at_edge();
+        self.update_auto_hide();
+        self.coordinator.notify();
+    }
+
     /// Handle pointer position reset event.
     pub fn on_position_reset(&self) {
         self.pointer.borrow_mut().reset_position()
     }
+
+    /// Handle touch down event. Position is interpreted the same way as absolute pointer position.
+    /// Touched surface is bound to the touch point until it is lifted and becomes active.
+    pub fn on_touch_down(&mut self, touch: TouchPoint) {
+        let (position, _) = self.find_absolute_position(&touch.position);
+        let found = self.displays.values().filter_map(|d| d.find_surface_at(position)).next();
+        if let Some((sid, relative)) = found {
+            if self.pointer.borrow().get_keyboard_focussed_sid() != sid {
+                self.compositor.pop_surface(sid);
+                self.coordinator.notify();
+            }
+            self.touch.on_down(touch.id, sid, position, relative, touch.time);
+        }
+    }
+
+    /// Handle touch motion event.
+    pub fn on_touch_motion(&mut self, touch: TouchPoint) {
+        let (position, _) = self.find_absolute_position(&touch.position);
+        self.touch.on_motion(touch.id, position, touch.time);
+    }
+
+    /// Handle touch up event.
+    pub fn on_touch_up(&mut self, id: i32, time: Milliseconds) {
+        self.touch.on_up(id, time);
+    }
+
+    /// Handle touch frame event.
+    pub fn on_touch_frame(&mut self) {
+        self.touch.on_frame();
+    }
+
+    /// Handle beginning of touchpad gesture.
+    pub fn on_gesture_begin(&mut self, kind: GestureKind, fingers: u32, time: Milliseconds) {
+        let sid = self.pointer.borrow().get_pointer_focussed_sid();
+        self.gestures.on_begin(kind, fingers, sid, time);
+    }
+
+    /// Handle update of touchpad gesture.
+    pub fn on_gesture_update(&mut self, kind: GestureKind, motion: GestureMotion) {
+        self.gestures.on_update(kind, motion);
+    }
+
+    /// Handle end of touchpad gesture by performing action bound to it if any.
+    pub fn on_gesture_end(&mut self, kind: GestureKind, cancelled: bool, time: Milliseconds) {
+        let position = self.pointer.borrow().get_global_position();
+        match self.gestures.on_end(kind, cancelled, time) {
+            Some(GestureAction::SwitchWorkspace(direction)) => {
+                log_info2!("Exhibitor: switch workspace by gesture: {:?}", direction);
+                self.compositor.switch_workspace(position, direction);
+            }
+            Some(GestureAction::EnterOverview) => {
+                for display in self.displays.values_mut() {
+                    if display.get_info().area.contains(&position) {
+                        display.set_overview(true);
+                    }
+                }
+            }
+            Some(GestureAction::LeaveOverview) => self.leave_overview(),
+            None => {}
+        }
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
 
 /// Private methods.
 impl Exhibitor {
+    /// Translates normalized absolute position to global coordinates. Returns the position and
+    /// area of display it was mapped to.
+    fn find_absolute_position(&self, pos: &AbsolutePosition) -> (Position, Area) {
+        let mut area = self.pointer.borrow().get_display_area();
+        let mut transform = Transform::Normal;
+        for display in self.displays.values() {
+            let info = display.get_info();
+            let matches = match pos.output {
+                Some(ref name) => display.get_name() == *name,
+                None => info.area == area,
+            };
+            if matches {
+                area = info.area;
+                transform = info.transform;
+                break;
+            }
+        }
+
+        let (x, y) = Self::transform_normalized(pos.x, pos.y, transform);
+        let position = Position::new(area.pos.x + (x * area.size.width as f32) as isize,
+                                     area.pos.y + (y * area.size.height as f32) as isize);
+        (position, area)
+    }
+
     /// Generate next output ID.
     fn generate_next_output_id(&mut self) -> i32 {
         self.last_output_id += 1;
         self.last_output_id
     }
+
+    /// Returns mode for output with given name from first profile describing this output.
+    fn find_profile_mode(&self, output_name: &str) -> Option<OutputMode> {
+        self.profiles
+            .iter()
+            .filter_map(|profile| profile.find_output(output_name))
+            .next()
+            .and_then(|output| output.mode)
+    }
+
+    /// Loads image configured as wallpaper of given display and passes it to the display. The last
+    /// entry for the output of the display takes precedence over entries for all outputs.
+    fn apply_wallpaper(wallpapers: &[WallpaperConfig], display: &mut Display) {
+        let name = display.get_name();
+        let config = wallpapers.iter()
+            .rev()
+            .find(|config| config.output.as_ref() == Some(&name))
+            .or_else(|| wallpapers.iter().rev().find(|config| config.output.is_none()));
+
+        match config {
+            Some(config) => {
+                match wallpaper::load(Path::new(&config.path)) {
+                    Ok(image) => display.set_wallpaper(Some(&image), config.mode),
+                    Err(err) => {
+                        log_error!("Exhibitor: {}", err);
+                        display.set_wallpaper(None, config.mode);
+                    }
+                }
+            }
+            None => display.set_wallpaper(None, WallpaperMode::Fill),
+        }
+    }
+
+    /// Sets transform of display with given name or of all displays if no name is given. Pointer
+    /// follows change of area of the display it is on.
+    fn transform_displays(&mut self, transform: Transform, name: Option<&str>) {
+        for display in self.displays.values_mut() {
+            if name.map_or(true, |name| display.get_name() == name) {
+                let old_area = display.get_info().area;
+                display.set_transform(transform);
+
+                let info = display.get_info();
+                let mut pointer = self.pointer.borrow_mut();
+                if pointer.get_display_area() == old_area {
+                    pointer.change_display(info.area);
+                }
+                self.overlap_watcher.borrow_mut().on_output_changed(info);
+            }
+        }
+        self.arrange_layers();
+        self.coordinator.notify();
+    }
+
+    /// Translates normalized position on output in its natural orientation to normalized position
+    /// on output with given transform.
+    fn transform_normalized(x: f32, y: f32, transform: Transform) -> (f32, f32) {
+        match transform {
+            Transform::Normal => (x, y),
+            Transform::Rotated90 => (y, 1.0 - x),
+            Transform::Rotated180 => (1.0 - x, 1.0 - y),
+            Transform::Rotated270 => (1.0 - y, x),
+            Transform::Flipped => (1.0 - x, y),
+            Transform::Flipped90 => (1.0 - y, 1.0 - x),
+            Transform::Flipped180 => (x, 1.0 - y),
+            Transform::Flipped270 => (y, x),
+        }
+    }
+
+    /// Rotates outputs according to orientation of the device if it is in tablet mode or restores
+    /// their normal orientation otherwise.
+    fn apply_rotation(&mut self) {
+        if self.auto_rotation {
+            let transform = if self.tablet_mode { self.orientation } else { Transform::Normal };
+            let output = self.auto_rotation_output.clone();
+            self.transform_displays(transform, output.as_ref().map(|name| name.as_str()));
+        }
+    }
+
+    /// Chooses profile matching set of connected outputs and applies modes, positions, scales and
+    /// workspace assignments from it. Nothing is done if no profile matches or matching profile
+    /// is already active.
+    fn apply_profile(&mut self) {
+        let names: Vec<String> = self.displays.values().map(|display| display.get_name()).collect();
+        let profile = match self.profiles.iter().find(|profile| profile.matches(&names)) {
+            Some(profile) => profile.clone(),
+            None => {
+                self.active_profile = None;
+                return;
+            }
+        };
+
+        if self.active_profile.as_ref() == Some(&profile.name) {
+            return;
+        }
+
+        log_info1!("Exhibitor: activating output profile '{}'", profile.name);
+        for display in self.displays.values_mut() {
+            if let Some(output) = profile.find_output(&display.get_name()) {
+                let old_area = display.get_info().area;
+                if let Some(position) = output.position {
+                    display.set_position(position);
+                }
+                if let Some(scale) = output.scale {
+                    display.set_scale(scale);
+                }
+                if let Some(transform) = output.transform {
+                    display.set_transform(transform);
+                }
+                if let Some(mode) = output.mode {
+                    display.set_mode(mode);
+                }
+
+                let info = display.get_info();
+                let mut pointer = self.pointer.borrow_mut();
+                if pointer.get_display_area() == old_area {
+                    pointer.change_display(info.area);
+                }
+                self.overlap_watcher.borrow_mut().on_output_changed(info);
+            }
+        }
+        self.arrange_layers();
+
+        for output in profile.outputs.iter() {
+            for workspace in output.workspaces.iter() {
+                self.compositor.assign_workspace(workspace, &output.name);
+            }
+        }
+
+        self.active_profile = Some(profile.name);
+        self.coordinator.notify();
+    }
+
+    /// Checks if requested configuration of outputs can be applied.
+    fn validate_output_configuration(&self,
+                                     configurations: &[OutputConfiguration])
+                                     -> Result<(), String> {
+        for configuration in configurations.iter() {
+            let info = match self.displays.get(&configuration.id) {
+                Some(display) => display.get_info(),
+                None => return Err(format!("Output {} does not exist", configuration.id)),
+            };
+            if !configuration.enabled {
+                continue;
+            }
+
+            if let Some(mode) = configuration.mode {
+                if !info.modes.is_empty() && !info.modes.iter().any(|m| m.matches(&mode)) {
+                    return Err(format!("Mode {} is not supported by '{}'", mode, info.model));
+                }
+            }
+            if let Some(scale) = configuration.scale {
+                if scale <= 0.0 {
+                    return Err(format!("Invalid scale {} of '{}'", scale, info.model));
+                }
+            }
+        }
+
+        let any_enabled = self.displays.iter().any(|(id, display)| {
+            configurations.iter()
+                .find(|configuration| configuration.id == *id)
+                .map_or(display.is_enabled(), |configuration| configuration.enabled)
+        });
+        if any_enabled {
+            Ok(())
+        } else {
+            Err("At least one output has to stay enabled".to_owned())
+        }
+    }
+
+    /// Applies configuration requested by client to display. Disabled display is powered off
+    /// and stays off until enabled again. `DisplayChanged` is emitted to inform clients about new
+    /// state of the display.
+    fn configure_display(&mut self, configuration: &OutputConfiguration) {
+        let display = match self.displays.get_mut(&configuration.id) {
+            Some(display) => display,
+            None => return,
+        };
+
+        let old_area = display.get_info().area;
+        if configuration.enabled {
+            if let Some(position) = configuration.position {
+                display.set_position(position);
+            }
+            if let Some(scale) = configuration.scale {
+                display.set_scale(scale);
+            }
+            if let Some(transform) = configuration.transform {
+                display.set_transform(transform);
+            }
+            if let Some(mode) = configuration.mode {
+                display.set_mode(mode);
+            }
+        }
+
+        if display.is_enabled() != configuration.enabled {
+            log_info1!("Exhibitor: {} display '{}'",
+                       if configuration.enabled { "enabling" } else { "disabling" },
+                       display.get_name());
+            display.set_enabled(configuration.enabled);
+            display.set_power(self.powered && configuration.enabled);
+        }
+
+        let info = display.get_info();
+        {
+            let mut pointer = self.pointer.borrow_mut();
+            if pointer.get_display_area() == old_area {
+                pointer.change_display(info.area);
+            }
+        }
+        self.overlap_watcher.borrow_mut().on_output_changed(info.clone());
+        self.signaler.emit(perceptron::DISPLAY_CHANGED, Perceptron::DisplayChanged(info));
+    }
+
+    /// Starts capturing display named in screencast configuration and stops capturing others.
+    fn apply_screencast(&mut self) {
+        for display in self.displays.values_mut() {
+            let settings = match self.screencast {
+                Some(ref screencast) if screencast.output == display.get_name() => {
+                    Some((screencast.size, screencast.rate))
+                }
+                _ => None,
+            };
+            display.set_screencast(settings);
+        }
+    }
+
+    /// Returns position of given surface in global coordinates. Position of satellite surfaces is
+    /// calculated from position of their parents.
+    fn find_global_position(&self, sid: SurfaceId) -> Option<Position> {
+        if let Some(position) = self.compositor.get_surface_position(sid) {
+            return Some(position);
+        }
+
+        if let Some(info) = self.coordinator.get_surface(sid) {
+            if info.parent_sid.is_valid() {
+                return self.find_global_position(info.parent_sid)
+                    .map(|position| position + info.relative_position);
+            }
+        }
+        None
+    }
+
+    /// Finds display and area in global coordinates (`None` for whole display) to take screenshot
+    /// of.
+    fn find_screenshot_area(&self,
+                            target: &ScreenshotTarget)
+                            -> Result<(i32, Option<Area>), String> {
+        let area = match *target {
+            ScreenshotTarget::Output(ref name) => {
+                return self.displays
+                    .values()
+                    .find(|display| display.get_name() == *name)
+                    .map(|display| (display.get_info().id, None))
+                    .ok_or(format!("No output named '{}'", name));
+            }
+            ScreenshotTarget::Surface(sid) => {
+                let info = self.coordinator
+                    .get_surface(sid)
+                    .ok_or(format!("No surface {}", sid.as_number()))?;
+                let size = if let Some(ref buffer) = info.buffer {
+                    buffer.get_size()
+                } else if let Some(ref hw_image) = info.hw_image {
+                    hw_image.get_size()
+                } else {
+                    info.desired_size
+                };
+                let position = self.find_global_position(sid)
+                    .ok_or(format!("Surface {} is not shown", sid.as_number()))?;
+                Area::new(position, size)
+            }
+            ScreenshotTarget::Region(area) => area,
+            ScreenshotTarget::Selection => return Err(format!("Region was not selected")),
+        };
+
+        self.displays
+            .values()
+            .map(|display| display.get_info())
+            .find(|info| info.area.contains(&area.pos))
+            .map(|info| (info.id, Some(area)))
+            .ok_or(format!("Area {:?} is not on any output", area))
+    }
+
+    /// Starts interactive selection of screen region unless other selection is in progress.
+    fn start_selection(&mut self, purpose: SelectionPurpose) {
+        if self.selection.is_some() {
+            self.reply_selection(purpose, Err(format!("Other selection is in progress")));
+            return;
+        }
+
+        log_info2!("Exhibitor: starting region selection ({:?})", purpose);
+        let position = self.pointer.borrow().get_global_position();
+        self.selection = Some(RegionSelection::new(purpose, position));
+        self.pointer.borrow_mut().on_selection_started();
+        self.update_selection();
+    }
+
+    /// Updates selected region to current position of pointer and shows it on all displays.
+    fn update_selection(&mut self) {
+        let area = if let Some(ref mut selection) = self.selection {
+            selection.update(self.pointer.borrow().get_global_position());
+            selection.get_area()
+        } else {
+            return;
+        };
+
+        for display in self.displays.values_mut() {
+            display.set_selection(Some(area));
+        }
+    }
+
+    /// Handles button pressed during interactive selection of screen region. Left button anchors
+    /// the region when pressed and finishes the selection when released. Other buttons cancel it.
+    fn on_selection_clicked(&mut self, button: Button) {
+        let anchored = match self.selection {
+            Some(ref selection) => selection.is_anchored(),
+            None => return,
+        };
+
+        if button.code != uinput_sys::BTN_LEFT as u16 {
+            if button.value != 0 {
+                self.finish_selection(Err(format!("Selection cancelled")));
+            }
+        } else if button.value != 0 {
+            if let Some(ref mut selection) = self.selection {
+                selection.anchor();
+            }
+            self.update_selection();
+        } else if anchored {
+            let area = self.selection.as_ref().map(|selection| selection.get_area());
+            self.finish_selection(area.ok_or(format!("Selection cancelled")));
+        }
+    }
+
+    /// Ends interactive selection of screen region and passes the result further.
+    fn finish_selection(&mut self, result: Result<Area, String>) {
+        let purpose = match self.selection.take() {
+            Some(selection) => selection.get_purpose(),
+            None => return,
+        };
+
+        log_info2!("Exhibitor: region selection finished: {:?}", result);
+        self.pointer.borrow_mut().on_selection_ended();
+        for display in self.displays.values_mut() {
+            display.set_selection(None);
+        }
+        self.reply_selection(purpose, result);
+    }
+
+    /// Emits selected region or takes screenshot of it, depending on purpose of the selection.
+    fn reply_selection(&mut self, purpose: SelectionPurpose, result: Result<Area, String>) {
+        match (purpose, result) {
+            (SelectionPurpose::Report(id), result) => {
+                let perceptron = Perceptron::RegionSelected(id, result);
+                self.signaler.emit(perceptron::REGION_SELECTED, perceptron);
+            }
+            (SelectionPurpose::Screenshot(id), Ok(area)) => {
+                self.on_screenshot_requested(id, ScreenshotTarget::Region(area));
+            }
+            (SelectionPurpose::Screenshot(id), Err(err)) => {
+                let perceptron = Perceptron::ScreenshotTaken(id, Err(err));
+                self.signaler.emit(perceptron::SCREENSHOT_TAKEN, perceptron);
+            }
+        }
+    }
+
+    /// Ends fullscreen state of given surface if it is fullscreen on any display and reconfigures
+    /// it to size of its frame.
+    fn restore_fullscreen(&mut self, sid: SurfaceId) {
+        let mut restored = false;
+        for display in self.displays.values_mut() {
+            if display.get_fullscreen_sid() == Some(sid) {
+                display.set_fullscreen_sid(None);
+                restored = true;
+            }
+        }
+
+        if restored {
+            self.coordinator.set_surface_fullscreen(sid, None);
+            self.compositor.refresh_surface(sid);
+        }
+    }
+
+    /// Handles click on decoration of surface with given ID. Double-click on title bar maximizes or
+    /// restores the surface. Click on border of floating surface starts resizing it.
+    fn on_decoration_clicked(&mut self, sid: SurfaceId, part: DecorationPart, double_click: bool) {
+        match part {
+            DecorationPart::TitleBar if double_click => {
+                self.click_counter.reset();
+                self.compositor.toggle_maximized(sid);
+            }
+            DecorationPart::TitleBar => {
+                self.compositor.pop_surface(sid);
+                self.on_move_requested(sid);
+            }
+            DecorationPart::Border => {
+                let position = self.pointer.borrow().get_global_position();
+                if !self.start_resizing(sid, position) {
+                    self.compositor.pop_surface(sid);
+                }
+            }
+            DecorationPart::CloseButton => self.coordinator.request_close(sid),
+            DecorationPart::MaximizeButton => self.compositor.toggle_maximized(sid),
+            DecorationPart::Tab(tab_sid) => {
+                self.compositor.pop_surface(tab_sid);
+                self.coordinator.notify();
+            }
+        }
+    }
+
+    /// Opens context menu of surface with given ID at given position in global coordinates.
+    fn open_menu(&mut self, sid: SurfaceId, position: Position) {
+        let mut items: Vec<MenuItem> = self.compositor
+            .get_other_workspace_titles(sid)
+            .into_iter()
+            .map(|title| MenuItem::MoveToWorkspace(title))
+            .collect();
+        items.push(MenuItem::ToggleAnchor);
+        items.push(MenuItem::ToggleFullscreen);
+        items.push(MenuItem::Close);
+        items.push(MenuItem::Kill);
+        self.menu.borrow_mut().open(sid, items, position);
+    }
+
+    /// Handles click while context menu is open. The menu is closed and item under pointer (if
+    /// any) is executed on surface the menu was opened for.
+    fn on_menu_clicked(&mut self) {
+        let (pfsid, position) = {
+            let pointer = self.pointer.borrow();
+            (pointer.get_pointer_focussed_sid(), pointer.get_surface_relative_position())
+        };
+        let item = self.menu.borrow().find_item(pfsid, position);
+        let sid = self.menu.borrow().get_target_sid();
+        self.menu.borrow_mut().close();
+
+        match item {
+            Some(MenuItem::MoveToWorkspace(title)) => {
+                let mut command = Command::default();
+                command.action = Action::Jump;
+                command.direction = Direction::Workspace;
+                command.string = title;
+                self.compositor.execute_command_on(sid, command);
+            }
+            Some(MenuItem::ToggleAnchor) => {
+                let mut command = Command::default();
+                command.action = Action::Anchor;
+                self.compositor.execute_command_on(sid, command);
+            }
+            Some(MenuItem::ToggleFullscreen) => {
+                let fullscreen = self.displays
+                    .values()
+                    .any(|display| display.get_fullscreen_sid() == Some(sid));
+                self.on_fullscreen_requested(sid, !fullscreen, None);
+            }
+            Some(MenuItem::Close) => self.coordinator.request_close(sid),
+            Some(MenuItem::Kill) => self.coordinator.request_kill(sid),
+            None => {}
+        }
+    }
+
+    /// Starts interactive resize of floating surface with given ID grabbed at given position in
+    /// global coordinates. Returns `false` if the surface is not floating.
+    fn start_resizing(&mut self, sid: SurfaceId, position: Position) -> bool {
+        if let Some(area) = self.compositor.start_resizing(sid) {
+            let decoration = self.coordinator
+                .get_surface(sid)
+                .map_or(Size::default(), |info| info.margins.grow(Size::default()));
+            self.resize_grab = Some(ResizeGrab::new(sid, area, decoration, position));
+            self.pointer.borrow_mut().on_resize_started();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Moves dragged border between frames by distance pointer traveled from `old_position` or
+    /// resizes grabbed floating frame. Aspect ratio of the floating frame is preserved if it is
+    /// configured so, unless aspect ratio modifier is pressed (or the other way round).
+    fn resize_at_border(&mut self, old_position: Position) {
+        let (resizing, position, modifier) = {
+            let pointer = self.pointer.borrow();
+            (pointer.is_resizing(),
+             pointer.get_global_position(),
+             pointer.is_aspect_ratio_modifier_pressed())
+        };
+
+        if resizing && (position != old_position) {
+            if let Some(ref grab) = self.resize_grab {
+                let area = grab.calculate_area(position, self.preserve_aspect_ratio != modifier);
+                self.compositor.resize_floating(grab.get_sid(), area);
+            } else {
+                self.compositor.move_border(old_position, position - old_position);
+            }
+        }
+    }
+
+    /// Switches to workspace clicked in overview and leaves the overview.
+    fn on_overview_clicked(&mut self) {
+        let position = self.pointer.borrow().get_global_position();
+        let title = self.displays.values().filter_map(|d| d.find_workspace_at(position)).next();
+        if let Some(title) = title {
+            self.compositor.switch_to_workspace(&title);
+        }
+        self.leave_overview();
+    }
+
+    /// Hides overview of workspaces on all displays.
+    fn leave_overview(&mut self) {
+        for display in self.displays.values_mut() {
+            display.set_overview(false);
+        }
+    }
+
+    /// Switches workspace when dragged item is held at left or right edge of display long enough.
+    ///
+    /// Pointer pushed against the edge keeps generating motion events so the check is performed on
+    /// every motion.
+    fn switch_workspace_at_edge(&mut self) {
+        let (dragging, position, area) = {
+            let pointer = self.pointer.borrow();
+            (pointer.is_dragging(), pointer.get_global_position(), pointer.get_display_area())
+        };
+
+        if dragging {
+            if let Some(direction) = self.edge_switcher.update(position, area) {
+                log_info2!("Exhibitor: switch workspace at edge: {:?}", direction);
+                self.compositor.switch_workspace(position, direction);
+            }
+        }
+    }
+
+    /// Reveals or conceals automatically hidden layer surfaces depending on pointer position.
+    fn update_auto_hide(&self) {
+        let position = self.pointer.borrow().get_global_position();
+        for display in self.displays.values() {
+            display.update_auto_hide(position);
+        }
+    }
+
+    /// Arranges layer surfaces on all displays, configures their sizes and reflows workspaces into
+    /// space not reserved by them.
+    fn arrange_layers(&mut self) {
+        for display in self.displays.values_mut() {
+            for (sid, area) in display.arrange_layers().areas {
+                self.coordinator.reconfigure(sid, area.size, surface_state::REGULAR);
+            }
+            display.request_redraw();
+        }
+    }
+
+    /// Handle power command: `Forward` toggles power of displays, `Begin` powers them on and `End`
+    /// powers them off.
+    fn change_power(&mut self, direction: Direction) {
+        match direction {
+            Direction::Forward => {
+                let powered = !self.powered;
+                self.set_power(powered);
+            }
+            Direction::Begin => self.set_power(true),
+            Direction::End => self.set_power(false),
+            _ => log_warn2!("Exhibitor: unexpected power direction: {:?}", direction),
+        }
+        log_info1!("Exhibitor: displays powered: {}", self.powered);
+    }
+
+    /// Handle rotate command for display with given name or the one with pointer if name is empty:
+    /// `North`, `East`, `South` and `West` set orientation in which top of the screen is placed,
+    /// `Forward` and `Backward` rotate by further quarter turn and `Begin` toggles flipping.
+    fn change_rotation(&mut self, direction: Direction, name: &str) {
+        let area = self.pointer.borrow().get_display_area();
+        let display = self.displays.values().find(|display| {
+            if name.is_empty() {
+                display.get_info().area == area
+            } else {
+                display.get_name() == name
+            }
+        });
+        let (name, current) = match display {
+            Some(display) => (display.get_name(), display.get_info().transform),
+            None => {
+                log_warn2!("Exhibitor: no display to rotate");
+                return;
+            }
+        };
+
+        let flipped = current.is_flipped();
+        let transform = match direction {
+            Direction::North => Transform::from_parts(0, flipped),
+            Direction::East => Transform::from_parts(1, flipped),
+            Direction::South => Transform::from_parts(2, flipped),
+            Direction::West => Transform::from_parts(3, flipped),
+            Direction::Forward => current.rotated(1),
+            Direction::Backward => current.rotated(3),
+            Direction::Begin => current.flipped(),
+            _ => {
+                log_warn2!("Exhibitor: unexpected rotate direction: {:?}", direction);
+                return;
+            }
+        };
+        log_info1!("Exhibitor: transforming display '{}' to {:?}", name, transform);
+        self.transform_displays(transform, Some(&name));
+    }
+
+    /// Handle mode command for display with pointer: `Forward` and `Backward` switch to next or
+    /// previous mode supported by the display, otherwise mode given in format
+    /// `<width>x<height>[@<rate>]` is set.
+    fn change_mode(&mut self, direction: Direction, string: &str) {
+        let area = self.pointer.borrow().get_display_area();
+        let display = match self.displays.values_mut().find(|d| d.get_info().area == area) {
+            Some(display) => display,
+            None => {
+                log_warn2!("Exhibitor: no display to change mode of");
+                return;
+            }
+        };
+
+        let info = display.get_info();
+        let mode = match direction {
+            Direction::Forward | Direction::Backward => {
+                let current = OutputMode::new(info.pixel_size, info.refresh_rate);
+                let len = info.modes.len();
+                if len == 0 {
+                    log_warn2!("Exhibitor: modes of '{}' are not known", display.get_name());
+                    return;
+                }
+                let index = info.modes.iter().position(|mode| *mode == current).unwrap_or(0);
+                let index = if direction == Direction::Forward {
+                    (index + 1) % len
+                } else {
+                    (index + len - 1) % len
+                };
+                info.modes[index]
+            }
+            _ => {
+                if let Some(mode) = OutputMode::parse(string) {
+                    mode
+                } else {
+                    log_warn2!("Exhibitor: invalid mode: '{}'", string);
+                    return;
+                }
+            }
+        };
+        log_info1!("Exhibitor: setting mode of display '{}' to {}", display.get_name(), mode);
+        display.set_mode(mode);
+    }
+
+    /// Handle zoom command: scales focused window to fill the display it is shown on or restores
+    /// it if it is already zoomed. The client is not asked to resize the window.
+    fn toggle_zoom(&mut self) {
+        let sid = self.coordinator.get_keyboard_focused_sid();
+        if let Some(display) = self.displays
+            .values_mut()
+            .find(|display| display.get_zoomed_sid() == Some(sid)) {
+            display.set_zoomed_sid(None);
+            return;
+        }
+
+        if let Some(display) = self.displays.values_mut().find(|display| display.is_showing(sid)) {
+            log_info2!("Exhibitor: zooming {} on display '{}'", sid, display.get_name());
+            display.set_zoomed_sid(Some(sid));
+        } else {
+            log_warn2!("Exhibitor: no visible window to zoom");
+        }
+    }
+
+    /// Powers all displays on or off.
+    fn set_power(&mut self, on: bool) {
+        self.powered = on;
+        for display in self.displays.values_mut() {
+            let enabled = display.is_enabled();
+            display.set_power(on && enabled);
+        }
+    }
+
+    /// Saves contents of all displays to files.
+    fn take_screenshot(&mut self) {
+        for display in self.displays.values_mut() {
+            display.request_screenshot();
+        }
+    }
+
+    /// Handle redraw debugging command: `Forward` switches to next redraw mode, `End` draws one
+    /// frame in single-step mode, `Begin` toggles damage visualization.
+    fn change_redraw(&mut self, direction: Direction) {
+        match direction {
+            Direction::Forward => {
+                self.redraw_mode = self.redraw_mode.next();
+                log_info1!("Exhibitor: redraw mode: {:?}", self.redraw_mode);
+                for display in self.displays.values_mut() {
+                    display.set_redraw_mode(self.redraw_mode);
+                }
+            }
+            Direction::End => {
+                for display in self.displays.values_mut() {
+                    display.step();
+                }
+            }
+            Direction::Begin => {
+                self.visualize_damage = !self.visualize_damage;
+                log_info1!("Exhibitor: damage visualization: {}", self.visualize_damage);
+                for display in self.displays.values_mut() {
+                    display.set_damage_visualization(self.visualize_damage);
+                }
+            }
+            _ => log_warn2!("Exhibitor: unexpected redraw direction: {:?}", direction),
+        }
+    }
+
+    /// Handle tracing debugging command: `Forward` toggles tracing of emitted signals, `Begin`
+    /// logs current subscriptions.
+    fn change_tracing(&mut self, direction: Direction) {
+        match direction {
+            Direction::Forward => {
+                let tracing = !self.signaler.is_tracing();
+                log_info1!("Exhibitor: signal tracing: {}", tracing);
+                self.signaler.set_tracing(tracing);
+            }
+            Direction::Begin => {
+                log_info1!("Exhibitor: subscriptions:");
+                for (id, subscribers) in self.signaler.get_subscriptions() {
+                    log_info1!("    {} ({}): {} subscriber(s)",
+                               perceptron::get_signal_name(id),
+                               id,
+                               subscribers);
+                }
+            }
+            _ => log_warn2!("Exhibitor: unexpected tracing direction: {:?}", direction),
+        }
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -0,0 +1,191 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains functionality related to handling touchpad gestures.
+//!
+//! Gesture is either consumed by compositor (three-finger swipe switches workspaces, three-finger
+//! pinch enters or leaves overview of workspaces) or forwarded to surface focused by pointer when
+//! the gesture began. Decision is made once per gesture.
+
+// -------------------------------------------------------------------------------------------------
+
+use dharma::Signaler;
+use qualia::{signals, Perceptron, Direction, ExhibitorConfig, GestureKind, GestureMotion};
+use qualia::{Milliseconds, SurfaceId};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Minimal number of fingers for gesture to be consumed by compositor.
+const MIN_FINGERS: u32 = 3;
+
+/// Horizontal distance in pixels swipe has to travel to switch workspace.
+const SWIPE_DISTANCE: f32 = 100.0;
+
+/// Pinch scale below which overview is entered.
+const PINCH_IN_SCALE: f32 = 0.8;
+
+/// Pinch scale above which overview is left.
+const PINCH_OUT_SCALE: f32 = 1.25;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Actions performed by compositor in response to consumed gestures.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GestureAction {
+    /// Switch to next (`Forward`) or previous (`Backward`) workspace.
+    SwitchWorkspace(Direction),
+
+    /// Show overview of all workspaces.
+    EnterOverview,
+
+    /// Hide overview of workspaces.
+    LeaveOverview,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// State of ongoing gesture.
+#[derive(Clone, Copy, Debug)]
+enum Gesture {
+    /// Gesture is consumed by compositor. Keeps accumulated motion and last scale.
+    Consumed(GestureKind, f32, f32),
+
+    /// Gesture is forwarded to surface with given ID.
+    Forwarded(GestureKind, SurfaceId),
+
+    /// Gesture is neither consumed nor forwarded.
+    Ignored,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Decides what to do with touchpad gestures.
+pub struct Gestures {
+    /// Tells if three-finger swipe switches workspaces.
+    swipe_workspaces: bool,
+
+    /// Tells if three-finger pinch enters and leaves overview.
+    pinch_overview: bool,
+
+    /// Tells if gestures not consumed by compositor are forwarded to clients.
+    forward: bool,
+
+    /// Ongoing gesture.
+    gesture: Gesture,
+
+    /// Signaler.
+    signaler: Signaler<Perceptron>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Gestures {
+    /// Constructs new `Gestures`.
+    pub fn new(signaler: Signaler<Perceptron>, config: &ExhibitorConfig) -> Self {
+        Gestures {
+            swipe_workspaces: config.swipe_workspaces,
+            pinch_overview: config.pinch_overview,
+            forward: config.forward_gestures,
+            gesture: Gesture::Ignored,
+            signaler: signaler,
+        }
+    }
+
+    /// Applies new configuration. Ongoing gesture is not affected.
+    pub fn set_config(&mut self, config: &ExhibitorConfig) {
+        self.swipe_workspaces = config.swipe_workspaces;
+        self.pinch_overview = config.pinch_overview;
+        self.forward = config.forward_gestures;
+    }
+
+    /// Handles beginning of gesture. `sid` is ID of surface focused by pointer.
+    pub fn on_begin(&mut self,
+                    kind: GestureKind,
+                    fingers: u32,
+                    sid: SurfaceId,
+                    time: Milliseconds) {
+        let consumed = (fingers >= MIN_FINGERS) &&
+                       match kind {
+                           GestureKind::Swipe => self.swipe_workspaces,
+                           GestureKind::Pinch => self.pinch_overview,
+                       };
+
+        self.gesture = if consumed {
+            Gesture::Consumed(kind, 0.0, 1.0)
+        } else if self.forward && sid.is_valid() {
+            self.signaler.emit_signal::<signals::GestureBegin>((sid, kind, fingers, time));
+            Gesture::Forwarded(kind, sid)
+        } else {
+            Gesture::Ignored
+        };
+    }
+
+    /// Handles update of gesture.
+    pub fn on_update(&mut self, kind: GestureKind, motion: GestureMotion) {
+        match self.gesture {
+            Gesture::Consumed(gesture_kind, distance, _) if gesture_kind == kind => {
+                self.gesture = Gesture::Consumed(kind, distance + motion.delta.x, motion.scale);
+            }
+            Gesture::Forwarded(gesture_kind, sid) if gesture_kind == kind => {
+                self.signaler.emit_signal::<signals::GestureUpdate>((sid, kind, motion));
+            }
+            _ => {}
+        }
+    }
+
+    /// Handles end of gesture. Returns action to be performed by compositor if any.
+    pub fn on_end(&mut self,
+                  kind: GestureKind,
+                  cancelled: bool,
+                  time: Milliseconds)
+                  -> Option<GestureAction> {
+        let gesture = self.gesture;
+        self.gesture = Gesture::Ignored;
+        match gesture {
+            Gesture::Consumed(gesture_kind, distance, scale) if gesture_kind == kind => {
+                if cancelled {
+                    None
+                } else {
+                    Self::decide(kind, distance, scale)
+                }
+            }
+            Gesture::Forwarded(gesture_kind, sid) if gesture_kind == kind => {
+                self.signaler.emit_signal::<signals::GestureEnd>((sid, kind, cancelled, time));
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Stops forwarding gesture to destroyed surface.
+    pub fn on_surface_destroyed(&mut self, sid: SurfaceId) {
+        if let Gesture::Forwarded(_, gesture_sid) = self.gesture {
+            if gesture_sid == sid {
+                self.gesture = Gesture::Ignored;
+            }
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Private methods.
+impl Gestures {
+    /// Chooses action for finished gesture of given kind with given accumulated horizontal motion
+    /// and final scale.
+    fn decide(kind: GestureKind, distance: f32, scale: f32) -> Option<GestureAction> {
+        match kind {
+            GestureKind::Swipe if distance <= -SWIPE_DISTANCE => {
+                Some(GestureAction::SwitchWorkspace(Direction::Forward))
+            }
+            GestureKind::Swipe if distance >= SWIPE_DISTANCE => {
+                Some(GestureAction::SwitchWorkspace(Direction::Backward))
+            }
+            GestureKind::Pinch if scale < PINCH_IN_SCALE => Some(GestureAction::EnterOverview),
+            GestureKind::Pinch if scale > PINCH_OUT_SCALE => Some(GestureAction::LeaveOverview),
+            _ => None,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,224 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains context menu drawn by compositor.
+//!
+//! Menu is drawn to surface of its own which is placed above all other surfaces. While menu is
+//! open it grabs pointer: click on menu item chooses it and click anywhere else closes the menu.
+
+// -------------------------------------------------------------------------------------------------
+
+use qualia::{Area, Coordinator, MemoryPoolId, MemoryViewId, Position, Size, SurfaceContext};
+use qualia::{AlphaMode, PixelFormat, SurfaceId};
+
+use canvas::{Canvas, Color, GLYPH_HEIGHT};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Height of single item.
+const ITEM_HEIGHT: usize = 16;
+
+/// Horizontal space between border of menu and item label.
+const PADDING: usize = 8;
+
+/// Width of border around menu.
+const BORDER_WIDTH: usize = 1;
+
+/// Color of menu background.
+const BACKGROUND_COLOR: Color = [0x30, 0x30, 0x30, 0xF0];
+
+/// Color of border and labels.
+const FOREGROUND_COLOR: Color = [0xF0, 0xF0, 0xF0, 0xFF];
+
+// -------------------------------------------------------------------------------------------------
+
+/// Actions available in context menu of a window.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MenuItem {
+    /// Moves window to workspace with given title.
+    MoveToWorkspace(String),
+
+    /// Toggles window between anchored and floating.
+    ToggleAnchor,
+
+    /// Makes window fullscreen or restores it.
+    ToggleFullscreen,
+
+    /// Asks client to close the window.
+    Close,
+
+    /// Disconnects client owning the window.
+    Kill,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl MenuItem {
+    /// Returns text displayed for the item.
+    fn get_label(&self) -> String {
+        match *self {
+            MenuItem::MoveToWorkspace(ref title) => format!("Move to {}", title),
+            MenuItem::ToggleAnchor => "Float/Anchor".to_owned(),
+            MenuItem::ToggleFullscreen => "Fullscreen".to_owned(),
+            MenuItem::Close => "Close".to_owned(),
+            MenuItem::Kill => "Kill".to_owned(),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// State of open menu.
+struct OpenMenu {
+    /// ID of surface menu is drawn to.
+    sid: SurfaceId,
+
+    /// ID of surface the menu refers to.
+    target_sid: SurfaceId,
+
+    /// Items in order of displaying.
+    items: Vec<MenuItem>,
+
+    /// Position of top-left corner of the menu in global coordinates.
+    position: Position,
+
+    /// IDs of memory pool and view holding drawn image.
+    memory: (MemoryPoolId, MemoryViewId),
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Draws context menu and tells which item was chosen.
+pub struct Menu {
+    menu: Option<OpenMenu>,
+    coordinator: Coordinator,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Menu {
+    /// Constructs new `Menu`.
+    pub fn new(coordinator: Coordinator) -> Self {
+        Menu {
+            menu: None,
+            coordinator: coordinator,
+        }
+    }
+
+    /// Opens menu with given items referring to surface `target_sid` at given position in global
+    /// coordinates. Previously open menu is closed.
+    pub fn open(&mut self, target_sid: SurfaceId, items: Vec<MenuItem>, position: Position) {
+        self.close();
+        if items.len() == 0 {
+            return;
+        }
+
+        let labels: Vec<String> = items.iter().map(|item| item.get_label()).collect();
+        let width = labels.iter().map(|label| Canvas::measure_text(label)).max().unwrap_or(0);
+        let size = Size::new(width + 2 * (PADDING + BORDER_WIDTH),
+                             items.len() * ITEM_HEIGHT + 2 * BORDER_WIDTH);
+
+        let stride = 4 * size.width;
+        let buffer = Self::draw(size, &labels).into_buffer();
+        let mpid = self.coordinator.create_pool_from_buffer(buffer);
+        let mvid = match self.coordinator.create_memory_view(mpid,
+                                                             0,
+                                                             size.width,
+                                                             size.height,
+                                                             stride,
+                                                             PixelFormat::Argb8888,
+                                                             AlphaMode::Straight) {
+            Some(mvid) => mvid,
+            None => {
+                self.coordinator.destroy_memory_pool(mpid);
+                return;
+            }
+        };
+
+        let sid = self.coordinator.create_surface();
+        self.coordinator.attach(mvid, sid);
+        self.coordinator.damage_surface(sid, Area::new(Position::default(), size));
+        self.coordinator.set_surface_requested_size(sid, size);
+        self.coordinator.commit_surface(sid);
+
+        self.menu = Some(OpenMenu {
+            sid: sid,
+            target_sid: target_sid,
+            items: items,
+            position: position,
+            memory: (mpid, mvid),
+        });
+        self.coordinator.notify();
+    }
+
+    /// Closes the menu if it is open.
+    pub fn close(&mut self) {
+        if let Some(menu) = self.menu.take() {
+            let (mpid, mvid) = menu.memory;
+            self.coordinator.destroy_memory_view(mvid);
+            self.coordinator.destroy_memory_pool(mpid);
+            self.coordinator.destroy_surface(menu.sid);
+            self.coordinator.notify();
+        }
+    }
+
+    /// Checks if menu is open.
+    pub fn is_open(&self) -> bool {
+        self.menu.is_some()
+    }
+
+    /// Returns ID of surface the open menu refers to.
+    pub fn get_target_sid(&self) -> SurfaceId {
+        self.menu.as_ref().map_or(SurfaceId::invalid(), |menu| menu.target_sid)
+    }
+
+    /// Returns item at given position relative to surface with given ID, or `None` if the surface
+    /// is not the menu.
+    pub fn find_item(&self, sid: SurfaceId, position: Position) -> Option<MenuItem> {
+        if let Some(ref menu) = self.menu {
+            if (menu.sid == sid) && (position.y >= BORDER_WIDTH as isize) {
+                let index = (position.y as usize - BORDER_WIDTH) / ITEM_HEIGHT;
+                return menu.items.get(index).cloned();
+            }
+        }
+        None
+    }
+
+    /// Prepares rendering context for the menu if it is open and overlaps given area.
+    pub fn prepare_context(&self, area: &Area) -> Option<SurfaceContext> {
+        if let Some(ref menu) = self.menu {
+            if let Some(info) = self.coordinator.get_surface(menu.sid) {
+                if Area::new(menu.position, info.requested_size).is_overlapping(area) {
+                    return self.coordinator
+                        .get_renderer_context(menu.sid)
+                        .and_then(|contexts| contexts.into_iter().find(|c| c.id == menu.sid))
+                        .map(|context| context.moved(menu.position));
+                }
+            }
+        }
+        None
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Private methods.
+impl Menu {
+    /// Draws menu image of given size with given labels.
+    fn draw(size: Size, labels: &Vec<String>) -> Canvas {
+        let mut canvas = Canvas::new(size);
+        canvas.fill(Area::new(Position::default(), size), BACKGROUND_COLOR);
+        canvas.draw_outline(Area::new(Position::default(), size), FOREGROUND_COLOR);
+
+        let padding = (ITEM_HEIGHT - GLYPH_HEIGHT) / 2;
+        let max_width = size.width - 2 * (PADDING + BORDER_WIDTH);
+        for (i, label) in labels.iter().enumerate() {
+            let position = Position::new((BORDER_WIDTH + PADDING) as isize,
+                                         (BORDER_WIDTH + i * ITEM_HEIGHT + padding) as isize);
+            canvas.draw_text(position, label, FOREGROUND_COLOR, max_width);
+        }
+        canvas
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,186 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains functionality related to tracking which outputs surfaces overlap,
+//! informing about surfaces entering and leaving outputs and choosing scale and transform clients
+//! should prefer when drawing their buffers.
+
+// -------------------------------------------------------------------------------------------------
+
+use std::collections::{BTreeSet, HashMap};
+
+use dharma::Signaler;
+
+use qualia::{Area, Coordinator, OutputInfo, SurfaceContext, SurfaceId, Transform};
+use qualia::{perceptron, Perceptron};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Buffer scale and transform preferred for given surface.
+#[derive(Clone, Copy, PartialEq)]
+struct Preference {
+    scale: f32,
+    transform: Transform,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Preference {
+    /// Preference assumed for surfaces client was not yet informed about.
+    fn default() -> Self {
+        Preference {
+            scale: 1.0,
+            transform: Transform::Normal,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Tracks outputs overlapped by surfaces and notifies about surfaces entering or leaving outputs
+/// and about changes of preferred buffer scale and transform.
+pub struct OverlapWatcher {
+    /// Information about known outputs.
+    outputs: HashMap<i32, OutputInfo>,
+
+    /// IDs of outputs overlapped by surfaces.
+    overlaps: HashMap<SurfaceId, BTreeSet<i32>>,
+
+    /// Last preferences sent for surfaces.
+    preferences: HashMap<SurfaceId, Preference>,
+
+    /// Signaler.
+    signaler: Signaler<Perceptron>,
+
+    /// Coordinator.
+    coordinator: Coordinator,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl OverlapWatcher {
+    /// `OverlapWatcher` constructor.
+    pub fn new(signaler: Signaler<Perceptron>, coordinator: Coordinator) -> Self {
+        OverlapWatcher {
+            outputs: HashMap::new(),
+            overlaps: HashMap::new(),
+            preferences: HashMap::new(),
+            signaler: signaler,
+            coordinator: coordinator,
+        }
+    }
+
+    /// Handle appearance of new output or change of its properties. Preferences of surfaces
+    /// overlapping the output are updated as its scale or transform might have changed.
+    pub fn on_output_changed(&mut self, info: OutputInfo) {
+        let output_id = info.id;
+        self.outputs.insert(output_id, info);
+
+        let sids: Vec<SurfaceId> = self.overlaps
+            .iter()
+            .filter(|&(_, outputs)| outputs.contains(&output_id))
+            .map(|(sid, _)| *sid)
+            .collect();
+        for sid in sids {
+            self.update_preference(sid);
+        }
+    }
+
+    /// Update overlaps of surfaces drawn on output with given ID. `surfaces` is the list of all
+    /// surfaces drawn on the output; surfaces not present on the list are considered hidden and
+    /// leave the output.
+    pub fn update(&mut self, output_id: i32, surfaces: &Vec<SurfaceContext>) {
+        let output_area = if let Some(info) = self.outputs.get(&output_id) {
+            info.area
+        } else {
+            return;
+        };
+
+        let mut entered = Vec::new();
+        let mut left = Vec::new();
+        for (sid, outputs) in self.overlaps.iter_mut() {
+            if outputs.contains(&output_id) &&
+               surfaces.iter().find(|context| context.id == *sid).is_none() {
+                outputs.remove(&output_id);
+                left.push(*sid);
+            }
+        }
+
+        for context in surfaces.iter() {
+            if let Some(info) = self.coordinator.get_surface(context.id) {
+                let area = Area::new(context.pos, info.requested_size);
+                let outputs = self.overlaps.entry(context.id).or_insert(BTreeSet::new());
+                let was_overlapping = outputs.contains(&output_id);
+                if area.is_overlapping(&output_area) {
+                    outputs.insert(output_id);
+                } else {
+                    outputs.remove(&output_id);
+                }
+                match (was_overlapping, outputs.contains(&output_id)) {
+                    (false, true) => entered.push(context.id),
+                    (true, false) => left.push(context.id),
+                    _ => {}
+                }
+            }
+        }
+
+        for sid in entered {
+            self.signaler.emit(perceptron::SURFACE_OUTPUT_ENTERED,
+                               Perceptron::SurfaceOutputEntered(sid, output_id));
+            self.update_preference(sid);
+        }
+
+        for sid in left {
+            self.signaler.emit(perceptron::SURFACE_OUTPUT_LEFT,
+                               Perceptron::SurfaceOutputLeft(sid, output_id));
+            self.update_preference(sid);
+        }
+    }
+
+    /// Forget everything about destroyed surface.
+    pub fn on_surface_destroyed(&mut self, sid: SurfaceId) {
+        self.overlaps.remove(&sid);
+        self.preferences.remove(&sid);
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Private methods.
+impl OverlapWatcher {
+    /// Choose preference for given surface and emit signal if it changed.
+    ///
+    /// Highest scale among overlapped outputs is preferred; transform is taken from the output with
+    /// that scale. If surface does not overlap any output the last preference is kept.
+    fn update_preference(&mut self, sid: SurfaceId) {
+        let mut preference: Option<Preference> = None;
+        if let Some(outputs) = self.overlaps.get(&sid) {
+            for id in outputs.iter() {
+                if let Some(info) = self.outputs.get(id) {
+                    let better = match preference {
+                        Some(ref p) => info.scale > p.scale,
+                        None => true,
+                    };
+                    if better {
+                        preference = Some(Preference {
+                            scale: info.scale,
+                            transform: info.transform,
+                        });
+                    }
+                }
+            }
+        }
+
+        if let Some(preference) = preference {
+            let last = *self.preferences.get(&sid).unwrap_or(&Preference::default());
+            if preference != last {
+                self.preferences.insert(sid, preference);
+                let signal =
+                    Perceptron::PreferredBufferChanged(sid, preference.scale, preference.transform);
+                self.signaler.emit(perceptron::PREFERRED_BUFFER_CHANGED, signal);
+            }
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -6,11 +6,18 @@
 // -------------------------------------------------------------------------------------------------
 
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
-use dharma::Signaler;
+use uinput_sys;
 
-use qualia::{Buffer, Coordinator, Area, OptionalPosition, Position, Vector, SurfaceId,
+use dharma::{event_kind, Dispatcher, Signaler, Timer, TimerEventHandler};
+
+use qualia::{AlphaMode, Buffer, Coordinator, Area, OptionalPosition, Position, Vector, SurfaceId,
              SurfaceContext, perceptron, Perceptron, Milliseconds};
+use qualia::{Button, CursorConfig, Key, KeyCode, KeyState, KeyValue};
+use qualia::PixelFormat;
 
 use display::Display;
 
@@ -46,6 +53,39 @@ pub struct Pointer {
     /// Default surface ID of cursor surface.
     default_csid: SurfaceId,
 
+    /// Surface ID of drag icon.
+    disid: SurfaceId,
+
+    /// Tells if drag-and-drop operation is in progress.
+    dragging: bool,
+
+    /// Surface moved interactively and offset of its origin relative to pointer.
+    moved: Option<(SurfaceId, Vector)>,
+
+    /// Tells if border between frames is being dragged.
+    resizing: bool,
+
+    /// Tells if region of screen is being selected interactively.
+    selecting: bool,
+
+    /// Tells if key modifier allowing to move surfaces by dragging them is pressed.
+    move_modifier: bool,
+
+    /// Tells if key modifier inverting preservation of aspect ratio during resize is pressed.
+    aspect_ratio_modifier: bool,
+
+    /// Codes of currently pressed buttons. Pointer focus is latched while any button is pressed.
+    pressed_buttons: Vec<u16>,
+
+    /// Configuration of hiding the cursor.
+    cursor_config: CursorConfig,
+
+    /// Timer hiding the cursor after period of pointer inactivity.
+    hide_timer: Timer,
+
+    /// Tells if the cursor is hidden. Set from main dispatcher thread when `hide_timer` expires.
+    hidden: Arc<AtomicBool>,
+
     /// Signaler.
     signaler: Signaler<Perceptron>,
 
@@ -56,8 +96,12 @@ pub struct Pointer {
 // -------------------------------------------------------------------------------------------------
 
 impl Pointer {
-    /// `Pointer` constructor.
-    pub fn new(signaler: Signaler<Perceptron>, mut coordinator: Coordinator) -> Self {
+    /// `Pointer` constructor. Timer hiding the cursor is added to given `Dispatcher`.
+    pub fn new(signaler: Signaler<Perceptron>,
+               mut coordinator: Coordinator,
+               mut dispatcher: Dispatcher,
+               cursor_config: CursorConfig)
+               -> Self {
         let mut data = vec![200; 4 * DEFAULT_CURSOR_SIZE * DEFAULT_CURSOR_SIZE];
         for z in 0..(DEFAULT_CURSOR_SIZE * DEFAULT_CURSOR_SIZE) {
             data[4 * z + 3] = 100;
@@ -73,12 +117,25 @@ impl Pointer {
                                                            0,
                                                            DEFAULT_CURSOR_SIZE,
                                                            DEFAULT_CURSOR_SIZE,
-                                                           DEFAULT_CURSOR_SIZE) {
+                                                           DEFAULT_CURSOR_SIZE,
+                                                           PixelFormat::Argb8888,
+                                                           AlphaMode::Straight) {
             coordinator.attach(mvid, default_csid);
             coordinator.commit_surface(default_csid);
         }
 
-        Pointer {
+        let hide_timer = Timer::new();
+        let hidden = Arc::new(AtomicBool::new(false));
+        let timer_hidden = hidden.clone();
+        let mut timer_signaler = signaler.clone();
+        let handler = Box::new(move || {
+            timer_hidden.store(true, Ordering::SeqCst);
+            timer_signaler.emit(perceptron::NOTIFY, Perceptron::Notify);
+        });
+        dispatcher.add_source(Box::new(TimerEventHandler::new(hide_timer.clone(), handler)),
+                              event_kind::READ);
+
+        let mut mine = Pointer {
             position: Position::default(),
             last_position: OptionalPosition::default(),
             last_surface_relative: Position::default(),
@@ -87,9 +144,22 @@ impl Pointer {
             pfsid: SurfaceId::invalid(),
             kfsid: SurfaceId::invalid(),
             default_csid: default_csid,
+            disid: SurfaceId::invalid(),
+            dragging: false,
+            moved: None,
+            resizing: false,
+            selecting: false,
+            move_modifier: false,
+            aspect_ratio_modifier: false,
+            pressed_buttons: Vec::new(),
+            cursor_config: cursor_config,
+            hide_timer: hide_timer,
+            hidden: hidden,
             signaler: signaler,
             coordinator: coordinator,
-        }
+        };
+        mine.show_cursor();
+        mine
     }
 
     pub fn change_display(&mut self, area: Area) {
@@ -107,11 +177,21 @@ impl Pointer {
         self.position
     }
 
+    /// Returns area of display on which the pointer is placed.
+    pub fn get_display_area(&self) -> Area {
+        self.display_area
+    }
+
     /// Returns ID of the cursor surface.
     pub fn get_cursor_sid(&self) -> SurfaceId {
         self.csid
     }
 
+    /// Checks if the cursor is hidden because of pointer inactivity or typing.
+    pub fn is_cursor_hidden(&self) -> bool {
+        self.hidden.load(Ordering::SeqCst)
+    }
+
     /// Return ID of the surface with keyboard focus.
     pub fn get_keyboard_focussed_sid(&self) -> SurfaceId {
         self.kfsid
@@ -121,6 +201,47 @@ impl Pointer {
     pub fn get_pointer_focussed_sid(&self) -> SurfaceId {
         self.pfsid
     }
+
+    /// Returns position of the pointer relative to the surface with pointer focus.
+    pub fn get_surface_relative_position(&self) -> Position {
+        self.last_surface_relative
+    }
+
+    /// Returns ID of the drag icon surface.
+    pub fn get_drag_icon_sid(&self) -> SurfaceId {
+        self.disid
+    }
+
+    /// Checks if drag-and-drop operation is in progress.
+    pub fn is_dragging(&self) -> bool {
+        self.dragging
+    }
+
+    /// Returns ID of moved surface and its position in global coordinates if interactive move is
+    /// in progress.
+    pub fn get_moved(&self) -> Option<(SurfaceId, Position)> {
+        self.moved.map(|(sid, offset)| (sid, self.position + offset))
+    }
+
+    /// Checks if border between frames is being dragged.
+    pub fn is_resizing(&self) -> bool {
+        self.resizing
+    }
+
+    /// Checks if key modifier allowing to move surfaces by dragging them is pressed.
+    pub fn is_move_modifier_pressed(&self) -> bool {
+        self.move_modifier
+    }
+
+    /// Checks if key modifier inverting preservation of aspect ratio during resize is pressed.
+    pub fn is_aspect_ratio_modifier_pressed(&self) -> bool {
+        self.aspect_ratio_modifier
+    }
+
+    /// Checks if pointer focus is latched because a button is pressed.
+    pub fn is_grabbed(&self) -> bool {
+        !self.pressed_buttons.is_empty()
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -131,6 +252,14 @@ impl Pointer {
     pub fn move_and_cast(&mut self, vector: Vector, displays: &HashMap<i32, Display>) {
         let moved = self.position.clone() + vector.clone();
         self.position = self.cast(moved, displays);
+        self.show_cursor();
+    }
+
+    /// Place the pointer at given position on display with given area.
+    pub fn move_to(&mut self, position: Position, display_area: Area) {
+        self.position = position.casted(&display_area);
+        self.display_area = display_area;
+        self.show_cursor();
     }
 
     /// Change position of the pointer and cast to correct output.
@@ -165,8 +294,16 @@ impl Pointer {
     /// Checks for change of surface pointer is hovering or relative position to this surface and
     /// notify rest of the application about changes.
     pub fn update_hover_state(&mut self, display_area: Area, surfaces: &Vec<SurfaceContext>) {
-        // Check if this update is for display on which this pointer is placed
-        if self.display_area != display_area {
+        // Check if this update is for display on which this pointer is placed. Focus does not
+        // change while surface is moved or resized or region of screen is selected.
+        if (self.display_area != display_area) || self.moved.is_some() || self.resizing ||
+           self.selecting {
+            return;
+        }
+
+        // While button is pressed focus stays on surface where it was pressed (implicit grab)
+        if self.is_grabbed() && !self.dragging {
+            self.update_grab_state(display_area, surfaces);
             return;
         }
 
@@ -177,27 +314,37 @@ impl Pointer {
 
         // Find surface pointer hovers
         for context in surfaces.iter().rev() {
-            if let Some(info) = self.coordinator.get_surface(context.id) {
-                let surface_area = Area::new(context.pos, info.requested_size);
-                if surface_area.contains(&display_relative) {
+            if let Some(placement) = self.coordinator.get_surface_placement(context) {
+                let window = Area::new(context.pos + placement.window.pos, placement.window.size);
+                if window.contains(&display_relative) {
                     sid = context.id;
-                    surface_relative = display_relative - context.pos.clone() + info.offset;
+                    surface_relative = placement.to_surface(display_relative - context.pos);
                     break;
                 }
             }
         }
 
         // Handle focus change if hovered surface is different than current one or handle motion
-        // otherwise
+        // otherwise. While dragging, drag target is changed instead of pointer focus.
         if sid != self.pfsid {
             self.pfsid = sid;
-            self.csid = self.default_csid;
-            self.coordinator.set_pointer_focus(sid, surface_relative)
+            self.last_surface_relative = surface_relative;
+            if self.dragging {
+                self.coordinator.set_drag_target(sid, surface_relative)
+            } else {
+                self.csid = self.default_csid;
+                self.coordinator.set_pointer_focus(sid, surface_relative)
+            }
         } else if self.pfsid.is_valid() && (surface_relative != self.last_surface_relative) {
             let now = Milliseconds::now();
             self.last_surface_relative = surface_relative;
-            self.signaler.emit(perceptron::POINTER_RELATIVE_MOTION,
-                               Perceptron::PointerRelativeMotion(sid, surface_relative, now));
+            if self.dragging {
+                self.signaler.emit(perceptron::DRAG_MOTION,
+                                   Perceptron::DragMotion(sid, surface_relative, now));
+            } else {
+                self.signaler.emit(perceptron::POINTER_RELATIVE_MOTION,
+                                   Perceptron::PointerRelativeMotion(sid, surface_relative, now));
+            }
         }
     }
 
@@ -206,6 +353,24 @@ impl Pointer {
         if self.csid == sid {
             self.csid = SurfaceId::invalid();
         }
+        if self.disid == sid {
+            self.disid = SurfaceId::invalid();
+        }
+        if self.moved.map(|(msid, _)| msid == sid).unwrap_or(false) {
+            self.moved = None;
+        }
+    }
+
+    /// Tracks pressed buttons. Pointer focus does not change until all buttons are released.
+    pub fn on_button(&mut self, button: &Button) {
+        if button.value != 0 {
+            if !self.pressed_buttons.contains(&button.code) {
+                self.pressed_buttons.push(button.code);
+            }
+        } else {
+            self.pressed_buttons.retain(|code| *code != button.code);
+        }
+        self.show_cursor();
     }
 
     /// Sets surface ID of currently keyboard focused surface.
@@ -222,12 +387,139 @@ impl Pointer {
     pub fn on_surface_change(&mut self, sid: SurfaceId) {
         self.csid = sid;
     }
+
+    /// Handles start of drag-and-drop operation. Pointer focus is removed from currently hovered
+    /// surface and from now on pointer motion is reported as drag motion.
+    pub fn on_drag_started(&mut self) {
+        if let Some(drag) = self.coordinator.get_drag() {
+            self.dragging = true;
+            self.disid = drag.icon;
+            self.pfsid = SurfaceId::invalid();
+            self.coordinator.set_pointer_focus(SurfaceId::invalid(), Position::default());
+        }
+    }
+
+    /// Handles start of interactive move of surface. `origin` is current position of the surface
+    /// in global coordinates. Pointer focus is removed until the move ends.
+    pub fn on_move_started(&mut self, sid: SurfaceId, origin: Position) {
+        self.moved = Some((sid, origin - self.position));
+        self.pfsid = SurfaceId::invalid();
+        self.coordinator.set_pointer_focus(SurfaceId::invalid(), Position::default());
+    }
+
+    /// Handles end of interactive move. Pointer focus will be restored on next hover state update.
+    pub fn on_move_ended(&mut self) {
+        self.moved = None;
+    }
+
+    /// Handles start of dragging border between frames or resizing floating frame. Pointer focus is
+    /// removed until the resize ends.
+    pub fn on_resize_started(&mut self) {
+        self.resizing = true;
+        self.pfsid = SurfaceId::invalid();
+        self.coordinator.set_pointer_focus(SurfaceId::invalid(), Position::default());
+    }
+
+    /// Handles end of dragging border between frames or resizing floating frame. Pointer focus will
+    /// be restored on next hover state update.
+    pub fn on_resize_ended(&mut self) {
+        self.resizing = false;
+    }
+
+    /// Handles start of interactive selection of screen region. Pointer focus is removed until the
+    /// selection ends.
+    pub fn on_selection_started(&mut self) {
+        self.selecting = true;
+        self.pfsid = SurfaceId::invalid();
+        self.coordinator.set_pointer_focus(SurfaceId::invalid(), Position::default());
+    }
+
+    /// Handles end of interactive selection of screen region. Pointer focus will be restored on
+    /// next hover state update.
+    pub fn on_selection_ended(&mut self) {
+        self.selecting = false;
+    }
+
+    /// Handles keyboard event by tracking state of meta keys which allow to move surfaces by
+    /// dragging them and shift keys which invert preservation of aspect ratio during resize. If
+    /// enabled, pressing other non-modifier key hides the cursor.
+    pub fn on_key(&mut self, key: Key) {
+        if (key.code == uinput_sys::KEY_LEFTMETA as KeyCode) ||
+           (key.code == uinput_sys::KEY_RIGHTMETA as KeyCode) {
+            self.move_modifier = key.value == KeyState::Pressed as KeyValue;
+        } else if (key.code == uinput_sys::KEY_LEFTSHIFT as KeyCode) ||
+                  (key.code == uinput_sys::KEY_RIGHTSHIFT as KeyCode) {
+            self.aspect_ratio_modifier = key.value == KeyState::Pressed as KeyValue;
+        } else if (key.code != uinput_sys::KEY_LEFTCTRL as KeyCode) &&
+                  (key.code != uinput_sys::KEY_RIGHTCTRL as KeyCode) &&
+                  (key.code != uinput_sys::KEY_LEFTALT as KeyCode) &&
+                  (key.code != uinput_sys::KEY_RIGHTALT as KeyCode) &&
+                  (key.value == KeyState::Pressed as KeyValue) &&
+                  self.cursor_config.hide_while_typing {
+            self.hide_cursor();
+        }
+    }
+
+    /// Applies new configuration of hiding the cursor. Cursor is shown until next timeout.
+    pub fn set_cursor_config(&mut self, config: CursorConfig) {
+        self.cursor_config = config;
+        self.show_cursor();
+    }
+
+    /// Shows the cursor if it was hidden and restarts timer hiding it.
+    fn show_cursor(&mut self) {
+        self.hidden.store(false, Ordering::SeqCst);
+        if self.cursor_config.hide_timeout > 0 {
+            let timeout = Duration::from_secs(self.cursor_config.hide_timeout as u64);
+            self.hide_timer.arm(timeout, None);
+        } else {
+            self.hide_timer.disarm();
+        }
+    }
+
+    /// Hides the cursor. Displays are notified so they are redrawn without it.
+    fn hide_cursor(&mut self) {
+        if !self.hidden.swap(true, Ordering::SeqCst) {
+            self.hide_timer.disarm();
+            self.signaler.emit(perceptron::NOTIFY, Perceptron::Notify);
+        }
+    }
+
+    /// Handles end of drag-and-drop operation. Pointer focus will be restored on next hover state
+    /// update.
+    pub fn on_drag_ended(&mut self) {
+        self.dragging = false;
+        self.disid = SurfaceId::invalid();
+        self.pfsid = SurfaceId::invalid();
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
 
 /// Helper methods
 impl Pointer {
+    /// Reports motion relative to the surface holding implicit grab even if pointer left it.
+    /// Nothing is reported if the surface is not drawn on given display.
+    fn update_grab_state(&mut self, display_area: Area, surfaces: &Vec<SurfaceContext>) {
+        if !self.pfsid.is_valid() {
+            return;
+        }
+
+        let display_relative = self.position - display_area.pos;
+        if let Some(context) = surfaces.iter().rev().find(|context| context.id == self.pfsid) {
+            if let Some(placement) = self.coordinator.get_surface_placement(context) {
+                let surface_relative = placement.to_surface(display_relative - context.pos);
+                if surface_relative != self.last_surface_relative {
+                    self.last_surface_relative = surface_relative;
+                    let motion = Perceptron::PointerRelativeMotion(self.pfsid,
+                                                                   surface_relative,
+                                                                   Milliseconds::now());
+                    self.signaler.emit(perceptron::POINTER_RELATIVE_MOTION, motion);
+                }
+            }
+        }
+    }
+
     /// Cast position to one of available displays:
     /// - if position is in one of the displays - return it without change
     /// - otherwise cast it to last used display.
@@ -0,0 +1,90 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains functionality related to interactive selection of screen region.
+//!
+//! While selecting, the screen is dimmed. Pressing left button anchors one corner of the region and
+//! the opposite corner follows the pointer until the button is released.
+
+// -------------------------------------------------------------------------------------------------
+
+use std::cmp::{max, min};
+
+use qualia::{Area, Position};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Tells what to do with selected region.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SelectionPurpose {
+    /// Report the region as reply to request with given ID.
+    Report(u32),
+
+    /// Take screenshot of the region as reply to request with given ID.
+    Screenshot(u32),
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// State of interactive selection of screen region.
+pub struct RegionSelection {
+    /// Tells what to do with selected region.
+    purpose: SelectionPurpose,
+
+    /// Position in global coordinates where selection was started. `None` if button was not
+    /// pressed yet.
+    anchor: Option<Position>,
+
+    /// Current position of pointer in global coordinates.
+    position: Position,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl RegionSelection {
+    /// Constructs new `RegionSelection` with pointer at given position.
+    pub fn new(purpose: SelectionPurpose, position: Position) -> Self {
+        RegionSelection {
+            purpose: purpose,
+            anchor: None,
+            position: position,
+        }
+    }
+
+    /// Returns purpose of the selection.
+    pub fn get_purpose(&self) -> SelectionPurpose {
+        self.purpose
+    }
+
+    /// Checks if a corner of the region was already anchored.
+    pub fn is_anchored(&self) -> bool {
+        self.anchor.is_some()
+    }
+
+    /// Anchors corner of the region at current position of pointer.
+    pub fn anchor(&mut self) {
+        self.anchor = Some(self.position);
+    }
+
+    /// Updates position of pointer.
+    pub fn update(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    /// Returns region spanned between anchored corner and pointer. Both corners are included, so
+    /// the region is never empty. Returns empty area at pointer position if not anchored yet.
+    pub fn get_area(&self) -> Area {
+        match self.anchor {
+            Some(anchor) => {
+                let left = min(anchor.x, self.position.x);
+                let top = min(anchor.y, self.position.y);
+                let right = max(anchor.x, self.position.x);
+                let bottom = max(anchor.y, self.position.y);
+                Area::create(left, top, (right - left + 1) as usize, (bottom - top + 1) as usize)
+            }
+            None => Area::new(self.position, Default::default()),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,161 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains functionality related to interactive resizing of floating frames.
+//!
+//! Edges of the frame to be moved are chosen by position at which the frame was grabbed: grabbing
+//! near a corner moves two edges, grabbing near middle of a side moves only that side. Edges
+//! opposite to moved ones stay in place.
+
+// -------------------------------------------------------------------------------------------------
+
+use qualia::{Area, Position, Size, SurfaceId};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Minimal width and height of resized frame.
+const MIN_LENGTH: isize = 32;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Describes which edges of frame are moved by resize.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Edges {
+    left: bool,
+    right: bool,
+    top: bool,
+    bottom: bool,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Edges {
+    /// Chooses edges nearest to given position relative to frame of given size.
+    fn new(position: Position, size: Size) -> Self {
+        let (width, height) = (size.width as isize, size.height as isize);
+        let mut edges = Edges {
+            left: 3 * position.x < width,
+            right: 3 * position.x > 2 * width,
+            top: 3 * position.y < height,
+            bottom: 3 * position.y > 2 * height,
+        };
+
+        // Frame grabbed in the middle is resized as if grabbed by bottom-right corner.
+        if !(edges.left || edges.right || edges.top || edges.bottom) {
+            edges.right = true;
+            edges.bottom = true;
+        }
+        edges
+    }
+
+    /// Checks if width is changed.
+    fn is_horizontal(&self) -> bool {
+        self.left || self.right
+    }
+
+    /// Checks if height is changed.
+    fn is_vertical(&self) -> bool {
+        self.top || self.bottom
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// State of interactive resize of floating frame.
+pub struct ResizeGrab {
+    /// ID of surface of resized frame.
+    sid: SurfaceId,
+
+    /// Area of the frame in global coordinates when the resize started.
+    area: Area,
+
+    /// Space taken by decoration. It is not taken into account when preserving aspect ratio.
+    decoration: Size,
+
+    /// Global position of pointer when the resize started.
+    origin: Position,
+
+    /// Moved edges.
+    edges: Edges,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl ResizeGrab {
+    /// Constructs new `ResizeGrab` for frame with given area and decoration size grabbed at given
+    /// position. Area and position are in global coordinates.
+    pub fn new(sid: SurfaceId, area: Area, decoration: Size, origin: Position) -> Self {
+        ResizeGrab {
+            sid: sid,
+            area: area,
+            decoration: decoration,
+            origin: origin,
+            edges: Edges::new(origin - area.pos, area.size),
+        }
+    }
+
+    /// Returns ID of surface of resized frame.
+    pub fn get_sid(&self) -> SurfaceId {
+        self.sid
+    }
+
+    /// Calculates new area of the frame for given position of pointer in global coordinates.
+    ///
+    /// If `preserve_aspect_ratio` is `true` the surface (without decoration) keeps proportions it
+    /// had when the resize started. When both width and height are changed the one changed
+    /// relatively more decides the size.
+    pub fn calculate_area(&self, position: Position, preserve_aspect_ratio: bool) -> Area {
+        let vector = position - self.origin;
+        let dx = if self.edges.left { -vector.x } else if self.edges.right { vector.x } else { 0 };
+        let dy = if self.edges.top { -vector.y } else if self.edges.bottom { vector.y } else { 0 };
+        let (decoration_width, decoration_height) =
+            (self.decoration.width as isize, self.decoration.height as isize);
+        let initial_width = self.area.size.width as isize - decoration_width;
+        let initial_height = self.area.size.height as isize - decoration_height;
+
+        let mut width = (initial_width + dx).max(MIN_LENGTH);
+        let mut height = (initial_height + dy).max(MIN_LENGTH);
+
+        if preserve_aspect_ratio && (initial_width > 0) && (initial_height > 0) {
+            let ratio = initial_width as f32 / initial_height as f32;
+            let width_scale = width as f32 / initial_width as f32;
+            let height_scale = height as f32 / initial_height as f32;
+            let width_decides = if self.edges.is_horizontal() && self.edges.is_vertical() {
+                (width_scale - 1.0).abs() >= (height_scale - 1.0).abs()
+            } else {
+                self.edges.is_horizontal()
+            };
+
+            if width_decides {
+                height = (width as f32 / ratio).round() as isize;
+            } else {
+                width = (height as f32 * ratio).round() as isize;
+            }
+
+            if width < MIN_LENGTH {
+                width = MIN_LENGTH;
+                height = (width as f32 / ratio).round() as isize;
+            }
+            if height < MIN_LENGTH {
+                height = MIN_LENGTH;
+                width = (height as f32 * ratio).round() as isize;
+            }
+        }
+
+        let width = width + decoration_width;
+        let height = height + decoration_height;
+        let x = if self.edges.left {
+            self.area.pos.x + self.area.size.width as isize - width
+        } else {
+            self.area.pos.x
+        };
+        let y = if self.edges.top {
+            self.area.pos.y + self.area.size.height as isize - height
+        } else {
+            self.area.pos.y
+        };
+        Area::new(Position::new(x, y), Size::new(width as usize, height as usize))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -2,6 +2,11 @@
 // the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
 
 //! This module define surface history functionality.
+//!
+//! Surface IDs do not survive restart of compositor so history is saved as list of fingerprints
+//! (application ID and title) of surfaces, one per line, the most recent first. Surfaces matching
+//! saved fingerprints are placed in history according to their saved order when their clients
+//! reconnect.
 
 // TODO: Add unit tests.
 
@@ -9,8 +14,12 @@
 
 // TODO: Check what is better: VecDeque or LinkedList.
 use std::collections::VecDeque as Ordering;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
 
-use qualia::SurfaceId;
+use qualia::{Illusion, SurfaceId};
 
 // -------------------------------------------------------------------------------------------------
 
@@ -18,6 +27,84 @@ mod magic {
     pub const AVARAGE_NUM_SURFACES: usize = 10;
     pub const PEEK_TO_AVARAGE_RATIO: usize = 3;
     pub const OPTIMAL_TO_AVARAGE_RATIO: usize = 2;
+    pub const MAX_SAVED_SURFACES: usize = 100;
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Identification of surface which stays the same after restart of compositor.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fingerprint {
+    pub app_id: String,
+    pub title: String,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Fingerprint {
+    /// `Fingerprint` constructor. Returns `None` if there is nothing to identify surface by.
+    pub fn new(app_id: Option<&String>, title: Option<&String>) -> Option<Self> {
+        if app_id.is_none() && title.is_none() {
+            return None;
+        }
+        Some(Fingerprint {
+                 app_id: app_id.map(|s| Self::sanitize(s)).unwrap_or_default(),
+                 title: title.map(|s| Self::sanitize(s)).unwrap_or_default(),
+             })
+    }
+
+    /// Parses fingerprint from line of history file.
+    fn from_line(line: &str) -> Option<Self> {
+        if line.is_empty() {
+            return None;
+        }
+        let mut parts = line.splitn(2, '\t');
+        let app_id = parts.next().map(|s| s.to_owned());
+        let title = parts.next().map(|s| s.to_owned());
+        Self::new(app_id.as_ref(), title.as_ref())
+    }
+
+    /// Formats fingerprint as line of history file.
+    fn to_line(&self) -> String {
+        format!("{}\t{}\n", self.app_id, self.title)
+    }
+
+    /// Replaces characters used as separators in history file.
+    fn sanitize(text: &String) -> String {
+        text.replace(|c| c == '\t' || c == '\n', " ")
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Reads fingerprints saved in history file. Missing file is treated as empty history.
+pub fn read_fingerprints(path: &Path) -> Result<Vec<Fingerprint>, Illusion> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(ref err) if err.kind() == ::std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(Illusion::from(err)),
+    };
+
+    let mut result = Vec::new();
+    for line in BufReader::new(file).lines() {
+        if let Some(fingerprint) = Fingerprint::from_line(&line?) {
+            result.push(fingerprint);
+        }
+    }
+    Ok(result)
+}
+
+/// Writes fingerprints to history file. File is replaced at once so it is never left half-written.
+pub fn write_fingerprints(path: &Path, fingerprints: &Vec<Fingerprint>) -> Result<(), Illusion> {
+    let temp_path = path.with_extension("tmp");
+    {
+        let mut file = File::create(&temp_path)?;
+        for fingerprint in fingerprints.iter().take(magic::MAX_SAVED_SURFACES) {
+            file.write_all(fingerprint.to_line().as_bytes())?;
+        }
+    }
+    fs::rename(&temp_path, path)?;
+    Ok(())
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -25,6 +112,12 @@ mod magic {
 /// Provides functionality to manage surface history as resizable list.
 pub struct SurfaceHistory {
     history: Ordering<SurfaceId>,
+
+    /// Fingerprints read from history file. Entries are taken out when matching surfaces appear.
+    saved: Vec<Option<Fingerprint>>,
+
+    /// Positions in saved history of surfaces placed according to it.
+    ranks: HashMap<SurfaceId, usize>,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -32,7 +125,59 @@ pub struct SurfaceHistory {
 impl SurfaceHistory {
     /// `SurfaceHistory` constructor.
     pub fn new() -> Self {
-        SurfaceHistory { history: Ordering::with_capacity(magic::AVARAGE_NUM_SURFACES) }
+        SurfaceHistory {
+            history: Ordering::with_capacity(magic::AVARAGE_NUM_SURFACES),
+            saved: Vec::new(),
+            ranks: HashMap::new(),
+        }
+    }
+
+    /// Sets history saved before restart. Surfaces matching saved fingerprints will be placed
+    /// according to saved order by `add_restored`.
+    pub fn set_saved(&mut self, saved: Vec<Fingerprint>) {
+        self.saved = saved.into_iter().map(|fingerprint| Some(fingerprint)).collect();
+    }
+
+    /// Adds surface to history. If it matches one of saved fingerprints (preferably with the same
+    /// title, otherwise just with the same application ID) it is placed after surfaces which were
+    /// more recent before restart. Other surfaces are added as the latest.
+    ///
+    /// Returns `true` if surface became the latest in history.
+    pub fn add_restored(&mut self, sid: SurfaceId, fingerprint: &Fingerprint) -> bool {
+        let rank = {
+            let matches = |exact: bool| {
+                self.saved.iter().position(|saved| match *saved {
+                    Some(ref saved) => {
+                        saved.app_id == fingerprint.app_id &&
+                        (!exact || saved.title == fingerprint.title)
+                    }
+                    None => false,
+                })
+            };
+            matches(true).or_else(|| matches(false))
+        };
+
+        let rank = if let Some(rank) = rank {
+            self.saved[rank] = None;
+            rank
+        } else {
+            self.add(sid);
+            return true;
+        };
+
+        let position = {
+            let ranks = &self.ranks;
+            self.history.iter().position(|other| ranks.get(other).map_or(false, |r| *r > rank))
+        };
+        let position = position.unwrap_or(self.history.len());
+        self.history.insert(position, sid);
+        self.ranks.insert(sid, rank);
+        position == 0
+    }
+
+    /// Returns saved fingerprints which did not match any surface yet.
+    pub fn get_unclaimed(&self) -> Vec<Fingerprint> {
+        self.saved.iter().filter_map(|saved| saved.clone()).collect()
     }
 
     /// Add surface as the latest in history.
@@ -56,12 +201,14 @@ impl SurfaceHistory {
 
     /// Make given surface the latest in history.
     pub fn pop(&mut self, sid: SurfaceId) {
+        self.ranks.remove(&sid);
         self.simple_remove(sid);
         self.add(sid);
     }
 
     /// Remove surface. Shrink underlying memory pool if needed.
     pub fn remove(&mut self, sid: SurfaceId) {
+        self.ranks.remove(&sid);
         self.simple_remove(sid);
 
         let len = self.history.len();
@@ -0,0 +1,99 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains functionality related to routing touch events to surfaces.
+//!
+//! Touch point is bound to surface it touched down on until it is lifted, even if it moves outside
+//! of the surface.
+
+// -------------------------------------------------------------------------------------------------
+
+use std::collections::HashMap;
+
+use dharma::Signaler;
+use qualia::{perceptron, Milliseconds, Perceptron, Position, SurfaceId};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Routes touch events to touched surfaces.
+pub struct Touch {
+    /// Touch points with IDs of touched surfaces and global positions of their origins.
+    points: HashMap<i32, (SurfaceId, Position)>,
+
+    /// Surfaces which received touch events since last touch frame.
+    touched: Vec<SurfaceId>,
+
+    /// Signaler.
+    signaler: Signaler<Perceptron>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Touch {
+    /// Constructs new `Touch`.
+    pub fn new(signaler: Signaler<Perceptron>) -> Self {
+        Touch {
+            points: HashMap::new(),
+            touched: Vec::new(),
+            signaler: signaler,
+        }
+    }
+
+    /// Handles touch down of point with given ID at given global position on surface with given ID
+    /// at given position relative to that surface.
+    pub fn on_down(&mut self,
+                   id: i32,
+                   sid: SurfaceId,
+                   position: Position,
+                   relative: Position,
+                   time: Milliseconds) {
+        self.points.insert(id, (sid, position - relative));
+        self.mark_touched(sid);
+        self.signaler.emit(perceptron::TOUCH_DOWN, Perceptron::TouchDown(sid, id, relative, time));
+    }
+
+    /// Handles motion of touch point with given ID to given global position.
+    pub fn on_motion(&mut self, id: i32, position: Position, time: Milliseconds) {
+        let point = self.points.get(&id).cloned();
+        if let Some((sid, origin)) = point {
+            self.mark_touched(sid);
+            self.signaler.emit(perceptron::TOUCH_MOTION,
+                               Perceptron::TouchMotion(sid, id, position - origin, time));
+        }
+    }
+
+    /// Handles lifting touch point with given ID.
+    pub fn on_up(&mut self, id: i32, time: Milliseconds) {
+        if let Some((sid, _)) = self.points.remove(&id) {
+            self.mark_touched(sid);
+            self.signaler.emit(perceptron::TOUCH_UP, Perceptron::TouchUp(sid, id, time));
+        }
+    }
+
+    /// Handles end of set of touch events by sending frame event to every touched surface.
+    pub fn on_frame(&mut self) {
+        for sid in self.touched.drain(..) {
+            self.signaler.emit(perceptron::TOUCH_FRAME, Perceptron::TouchFrame(sid));
+        }
+    }
+
+    /// Forgets touch points bound to destroyed surface.
+    pub fn on_surface_destroyed(&mut self, sid: SurfaceId) {
+        self.points.retain(|_, point| point.0 != sid);
+        self.touched.retain(|touched_sid| *touched_sid != sid);
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Private methods.
+impl Touch {
+    /// Remembers that surface with given ID should receive frame event.
+    fn mark_touched(&mut self, sid: SurfaceId) {
+        if !self.touched.contains(&sid) {
+            self.touched.push(sid);
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -23,8 +23,12 @@ impl Displaying for Frame {
     fn to_array(&self, coordinator: &Coordinator) -> Vec<SurfaceContext> {
         // FIXME: Do not allocate here.
         let mut result = Vec::new();
+        let mut layers = Vec::new();
         for frame in self.time_rev_iter() {
-            if frame.get_sid().is_valid() {
+            if frame.is_floating_layer() {
+                // Floating frames are drawn above anchored ones
+                layers.push(frame);
+            } else if frame.get_sid().is_valid() {
                 if let Some(ref mut array) = coordinator.get_renderer_context(frame.get_sid()) {
                     for ref mut c in array.iter() {
                         result.push(c.moved(frame.get_position()));
@@ -34,6 +38,9 @@ impl Displaying for Frame {
                 result.append(&mut frame.to_array(coordinator));
             }
         }
+        for layer in layers {
+            result.append(&mut layer.to_array(coordinator));
+        }
         result
     }
 }
@@ -12,6 +12,11 @@ use qualia::{SurfaceId, Area, Position, Size};
 
 // -------------------------------------------------------------------------------------------------
 
+/// Weight of newly created frames.
+pub const DEFAULT_WEIGHT: f32 = 1.0;
+
+// -------------------------------------------------------------------------------------------------
+
 /// Alias for optional frame.
 type Link = Option<Frame>;
 
@@ -149,6 +154,9 @@ pub struct Parameters {
     /// Size.
     pub size: Size,
 
+    /// Share of space of directed parent relative to weights of siblings.
+    pub weight: f32,
+
     /// Title.
     pub title: String,
 }
@@ -164,6 +172,7 @@ impl Parameters {
             geometry: Geometry::Floating,
             pos: Position::default(),
             size: Size::default(),
+            weight: DEFAULT_WEIGHT,
             title: "PERCEPTIA".to_owned(),
         }
     }
@@ -176,6 +185,7 @@ impl Parameters {
             geometry: Geometry::Stacked,
             pos: area.pos,
             size: area.size,
+            weight: DEFAULT_WEIGHT,
             title: title,
         }
     }
@@ -188,6 +198,7 @@ impl Parameters {
             geometry: Geometry::Stacked,
             pos: Position::default(),
             size: Size::default(),
+            weight: DEFAULT_WEIGHT,
             title: title,
         }
     }
@@ -200,6 +211,7 @@ impl Parameters {
             geometry: geometry,
             pos: Position::default(),
             size: Size::default(),
+            weight: DEFAULT_WEIGHT,
             title: "".to_owned(),
         }
     }
@@ -212,6 +224,7 @@ impl Parameters {
             geometry: geometry,
             pos: Position::default(),
             size: Size::default(),
+            weight: DEFAULT_WEIGHT,
             title: "".to_owned(),
         }
     }
@@ -256,6 +269,7 @@ impl Frame {
                                geometry: geometry,
                                pos: pos,
                                size: size,
+                               weight: DEFAULT_WEIGHT,
                                title: title,
                            },
                            node: Node::default(),
@@ -365,6 +379,12 @@ impl Frame {
         unsafe { (*self.inner).params.size.clone() }
     }
 
+    /// Gets weight.
+    #[inline]
+    pub fn get_weight(&self) -> f32 {
+        unsafe { (*self.inner).params.weight }
+    }
+
     /// Gets area.
     #[inline]
     pub fn get_area(&self) -> Area {
@@ -405,6 +425,14 @@ impl Frame {
         }
     }
 
+    /// Sets weight without adjusting any sizes and positions.
+    #[inline]
+    pub fn set_plumbing_weight(&mut self, weight: f32) {
+        unsafe {
+            (*self.inner).params.weight = weight;
+        }
+    }
+
     /// Sets geometry without adjusting any sizes an positions.
     #[inline]
     pub fn set_plumbing_geometry(&mut self, geometry: Geometry) {
@@ -413,6 +441,14 @@ impl Frame {
         }
     }
 
+    /// Sets title without informing other parts of application.
+    #[inline]
+    pub fn set_plumbing_title(&mut self, title: String) {
+        unsafe {
+            (*self.inner).params.title = title;
+        }
+    }
+
     /// Sets mode without any checks.
     #[inline]
     pub fn set_plumbing_mode(&mut self, mode: Mode) {
@@ -750,6 +786,13 @@ impl Frame {
         }
         result
     }
+
+    /// Returns `true` if frame is container with `Floating` geometry. Such container is a layer
+    /// holding not anchored frames. It covers whole area of its parent, does not take part in
+    /// packing of its siblings and is drawn above them.
+    pub fn is_floating_layer(&self) -> bool {
+        (self.get_mode() == Mode::Container) && (self.get_geometry() == Geometry::Floating)
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -0,0 +1,126 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains reverse index from surface IDs to frames.
+
+// -------------------------------------------------------------------------------------------------
+
+use std::collections::HashMap;
+
+use qualia::SurfaceId;
+
+use frame::Frame;
+use settling::Settling;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Reverse index from surface IDs to frames holding them.
+///
+/// Finding frame with `Searching::find_with_sid` requires walking the whole tree. This index
+/// allows to do it in constant time but must be kept in sync by code adding and removing frames
+/// with surfaces. Restructuring which may move surface ID to other frame (deramifying) should be
+/// done via index.
+pub struct FrameIndex {
+    frames: HashMap<SurfaceId, Frame>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl FrameIndex {
+    /// Constructs new empty `FrameIndex`.
+    pub fn new() -> Self {
+        FrameIndex { frames: HashMap::new() }
+    }
+
+    /// Adds frame to the index. Frames without surface are ignored.
+    pub fn insert(&mut self, frame: &Frame) {
+        let sid = frame.get_sid();
+        if sid.is_valid() {
+            self.frames.insert(sid, frame.clone());
+        }
+    }
+
+    /// Removes frame holding surface with given ID from the index.
+    pub fn remove(&mut self, sid: SurfaceId) -> Option<Frame> {
+        self.frames.remove(&sid)
+    }
+
+    /// Finds frame holding surface with given ID.
+    pub fn find(&self, sid: SurfaceId) -> Option<Frame> {
+        self.frames.get(&sid).cloned()
+    }
+
+    /// Finds frame holding surface with given ID if it is contained in `ancestor` or is the
+    /// `ancestor` itself.
+    pub fn find_within(&self, sid: SurfaceId, ancestor: &Frame) -> Option<Frame> {
+        if let Some(frame) = self.frames.get(&sid) {
+            let mut current = Some(frame.clone());
+            while let Some(candidate) = current {
+                if candidate.equals_exact(ancestor) {
+                    return Some(frame.clone());
+                }
+                current = candidate.get_parent();
+            }
+        }
+        None
+    }
+
+    /// Returns number of indexed frames.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Deramifies given frame and updates the index. When single leaf is collapsed into its
+    /// parent the surface ID is moved to the parent and the leaf is destroyed.
+    pub fn deramify(&mut self, frame: &mut Frame) {
+        frame.deramify();
+        self.insert(frame);
+    }
+
+    /// Checks if index is consistent with the tree with given root: every frame with surface in
+    /// the tree must be indexed, surface IDs must be unique and index must not contain any other
+    /// frames.
+    ///
+    /// Returns description of first found inconsistency.
+    pub fn verify(&self, root: &Frame) -> Result<(), String> {
+        let mut count = 0;
+        self.verify_subtree(root, &mut count)?;
+        if count == self.frames.len() {
+            Ok(())
+        } else {
+            Err(format!("Index has {} entries while tree has {} surfaces",
+                        self.frames.len(),
+                        count))
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Private methods.
+impl FrameIndex {
+    /// Helper method for `verify`. Counts frames with surfaces.
+    fn verify_subtree(&self, frame: &Frame, count: &mut usize) -> Result<(), String> {
+        let sid = frame.get_sid();
+        if sid.is_valid() {
+            *count += 1;
+            match self.frames.get(&sid) {
+                Some(indexed) if indexed.equals_exact(frame) => {}
+                Some(indexed) => {
+                    return Err(format!("Surface {} indexed as {:?} but found in {:?}",
+                                       sid,
+                                       indexed,
+                                       frame));
+                }
+                None => return Err(format!("Surface {} not indexed", sid)),
+            }
+        }
+
+        for subframe in frame.time_iter() {
+            self.verify_subtree(&subframe, count)?;
+        }
+        Ok(())
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
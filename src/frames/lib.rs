@@ -26,6 +26,9 @@
 //!  - `searching` - gives more advance or common ways to find specified frames
 //!  - `settle` - implements common ways of adding or moving frames
 //!
+//! Searching for frame with given surface ID requires walking the tree. `FrameIndex` may be used
+//! to find such frames in constant time if kept in sync with the tree.
+//!
 //! ## Implementation
 //!
 //! Frame tree is cyclic graph with each node optionally pointing to:
@@ -54,6 +57,9 @@ pub use frame::{Frame, FrameSpaceIterator, FrameTimeIterator, Mode, Geometry, Si
 mod displaying;
 pub use displaying::Displaying;
 
+mod index;
+pub use index::FrameIndex;
+
 pub mod packing;
 pub mod searching;
 pub mod settling;
@@ -8,19 +8,35 @@
 use qualia::{Position, Size, Vector};
 use qualia::{SurfaceAccess, surface_state};
 
-use frame::{Frame, Geometry};
+use frame::{Frame, Geometry, DEFAULT_WEIGHT};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Minimal size frames can be shrunk to by moving their borders.
+pub const MIN_SIZE: usize = 32;
 
 // -------------------------------------------------------------------------------------------------
 
 /// Extension trait for `Frame` adding more packing functionality.
 pub trait Packing {
-    /// TODO: Implement relaxing. Currently relaxing is equivalent to homogenizing.
+    /// Make all subsurfaces share space of the frame proportionally to their weights and have
+    /// proper layout. Relaxing works only on directed frames.
     fn relax(&mut self, sa: &mut SurfaceAccess);
 
-    /// Make all subsurfaces have the same size and proper layout.
+    /// Make all subsurfaces have the same size and proper layout by resetting their weights.
     /// Homogenizing works only on directed frames.
     fn homogenize(&mut self, sa: &mut SurfaceAccess);
 
+    /// Moves border between the frame and its next sibling by `offset` pixels along direction of
+    /// their parent resizing both of them. Sizes are limited so none of them gets smaller than
+    /// `MIN_SIZE`. Their weights are updated so new proportions are preserved by later relaxing.
+    /// Nothing is done if parent is not directed or the frame is the last one.
+    fn move_border(&mut self, offset: isize, sa: &mut SurfaceAccess);
+
+    /// Returns size new frame with default weight would get if it was appended to the frame and the
+    /// frame was relaxed. The frame is not changed.
+    fn calculate_appended_size(&self) -> Size;
+
     /// Set size of the frame and resize its subframe accordingly.
     fn set_size(&mut self, size: Size, sa: &mut SurfaceAccess);
 
@@ -30,7 +46,8 @@ pub trait Packing {
     /// Move the frame and all subframes by given vector.
     fn move_with_contents(&mut self, vector: Vector);
 
-    /// Remove given frame and relax old parent.
+    /// Remove given frame and relax old parent. Weight of the frame is reset as it was relative to
+    /// its old siblings.
     fn remove_self(&mut self, sa: &mut SurfaceAccess);
 }
 
@@ -38,52 +55,140 @@ pub trait Packing {
 
 impl Packing for Frame {
     fn relax(&mut self, sa: &mut SurfaceAccess) {
-        self.homogenize(sa);
-    }
+        // Floating layers always cover whole frame
+        for mut layer in self.space_iter().filter(|frame| frame.is_floating_layer()) {
+            layer.set_size(self.get_size(), sa);
+            layer.set_position(self.get_position());
+        }
 
-    fn homogenize(&mut self, sa: &mut SurfaceAccess) {
-        let len = self.count_children();
-        if len < 1 {
+        let total_weight: f32 = self.space_iter()
+            .filter(|frame| !frame.is_floating_layer())
+            .map(|frame| frame.get_weight())
+            .sum();
+        if total_weight <= 0.0 {
             return;
         }
 
-        // Decide how to resize and move twigs
-        let mut size = Size::new(0, 0);
-        let mut increment = Vector::new(0, 0);
-        match self.get_geometry() {
-            Geometry::Stacked => {
-                size = self.get_size();
-            }
-            Geometry::Vertical => {
-                size.width = self.get_size().width;
-                size.height = self.get_size().height / len;
-                increment.y = size.height as isize;
-            }
-            Geometry::Horizontal => {
-                size.height = self.get_size().height;
-                size.width = self.get_size().width / len;
-                increment.x = size.width as isize;
-            }
+        // Decide along which axis to split the space
+        let geometry = self.get_geometry();
+        let length = match geometry {
+            Geometry::Stacked => 0,
+            Geometry::Vertical => self.get_size().height,
+            Geometry::Horizontal => self.get_size().width,
             Geometry::Floating => {
                 // Nothing to do for not-directed frames
                 return;
             }
+        };
+
+        // Resize and reposition all subframes recursively. Borders are calculated from
+        // accumulated weights so the last frame ends exactly at the end of the parent.
+        let mut weight = 0.0;
+        let mut start = 0;
+        for mut frame in self.space_iter().filter(|frame| !frame.is_floating_layer()) {
+            weight += frame.get_weight();
+            let end = (length as f32 * weight / total_weight).round() as usize;
+            let mut size = self.get_size();
+            let mut pos = self.get_position();
+            match geometry {
+                Geometry::Vertical => {
+                    size.height = end - start;
+                    pos.y += start as isize;
+                }
+                Geometry::Horizontal => {
+                    size.width = end - start;
+                    pos.x += start as isize;
+                }
+                _ => {}
+            }
+            frame.set_size(size, sa);
+            frame.set_position(pos);
+            start = end;
         }
+    }
 
-        // Resize and reposition all subframes recursively
-        let mut pos = self.get_position();
+    fn homogenize(&mut self, sa: &mut SurfaceAccess) {
         for mut frame in self.space_iter() {
-            frame.set_size(size.clone(), sa);
-            frame.set_position(pos.clone());
-            pos = pos + increment.clone();
+            frame.set_plumbing_weight(DEFAULT_WEIGHT);
+        }
+        self.relax(sa);
+    }
+
+    fn move_border(&mut self, offset: isize, sa: &mut SurfaceAccess) {
+        let geometry = match self.get_parent() {
+            Some(parent) => parent.get_geometry(),
+            None => return,
+        };
+        let mut next = match self.get_next_space() {
+            Some(next) => next,
+            None => return,
+        };
+
+        let mut size = self.get_size();
+        let mut next_size = next.get_size();
+        let mut next_pos = next.get_position();
+        {
+            let (length, next_length, next_start) = match geometry {
+                Geometry::Vertical => (&mut size.height, &mut next_size.height, &mut next_pos.y),
+                Geometry::Horizontal => (&mut size.width, &mut next_size.width, &mut next_pos.x),
+                _ => return,
+            };
+
+            let min = MIN_SIZE as isize - *length as isize;
+            let max = *next_length as isize - MIN_SIZE as isize;
+            if min > max {
+                return;
+            }
+            let offset = offset.max(min).min(max);
+            *length = (*length as isize + offset) as usize;
+            *next_length = (*next_length as isize - offset) as usize;
+            *next_start += offset;
+        }
+
+        // Split joint weight of both frames according to their new lengths
+        let (length, next_length) = match geometry {
+            Geometry::Vertical => (size.height, next_size.height),
+            _ => (size.width, next_size.width),
+        };
+        let weight = self.get_weight() + next.get_weight();
+        let ratio = length as f32 / (length + next_length) as f32;
+        self.set_plumbing_weight(weight * ratio);
+        next.set_plumbing_weight(weight * (1.0 - ratio));
+
+        self.set_size(size, sa);
+        next.set_size(next_size, sa);
+        next.set_position(next_pos);
+    }
+
+    fn calculate_appended_size(&self) -> Size {
+        let weight: f32 = self.space_iter()
+            .filter(|frame| !frame.is_floating_layer())
+            .map(|frame| frame.get_weight())
+            .sum();
+
+        // Appended frame ends at the end of the frame, see `relax`.
+        let mut size = self.get_size();
+        let ratio = weight / (weight + DEFAULT_WEIGHT);
+        match self.get_geometry() {
+            Geometry::Vertical => size.height -= (size.height as f32 * ratio).round() as usize,
+            Geometry::Horizontal => size.width -= (size.width as f32 * ratio).round() as usize,
+            _ => {}
         }
+        size
     }
 
     fn set_size(&mut self, size: Size, sa: &mut SurfaceAccess) {
         // Set size for given frame.
         let old_size = self.get_size();
+        let state = match self.get_parent() {
+            Some(ref parent) if parent.is_floating_layer() => surface_state::REGULAR,
+            Some(ref parent) if parent.get_geometry() == Geometry::Stacked => {
+                surface_state::MAXIMIZED
+            }
+            _ => surface_state::TILED,
+        };
         self.set_plumbing_size(size.clone());
-        sa.reconfigure(self.get_sid(), size.clone(), surface_state::MAXIMIZED);
+        sa.reconfigure(self.get_sid(), size.clone(), state);
 
         // Set size to frames children.
         match self.get_geometry() {
@@ -109,6 +214,9 @@ impl Packing for Frame {
                     self.relax(sa);
                 }
             }
+            Geometry::Floating => {
+                // Floating frames keep their sizes
+            }
             _ => {
                 for mut frame in self.space_iter() {
                     frame.set_size(size.clone(), sa);
@@ -136,6 +244,7 @@ impl Packing for Frame {
     fn remove_self(&mut self, sa: &mut SurfaceAccess) {
         if let Some(ref mut parent) = self.get_parent() {
             self.remove();
+            self.set_plumbing_weight(DEFAULT_WEIGHT);
             parent.relax(sa);
         }
     }
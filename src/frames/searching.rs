@@ -23,10 +23,18 @@ pub trait Searching {
     /// Finds frame with given surface ID.
     fn find_with_sid(&self, sid: SurfaceId) -> Option<Frame>;
 
+    /// Collects surface IDs of all frames contained in frame `self` (including `self`).
+    fn collect_sids(&self) -> Vec<SurfaceId>;
+
     /// Finds leaf frame contained in frame `self` containing `point` or the closest one if `point`
-    /// lies outside `self`.
+    /// lies outside `self`. Frames in floating layers take precedence over anchored ones.
     fn find_pointed(&self, point: Position) -> Frame;
 
+    /// Finds frame contained in frame `self` whose border with its next sibling in directed frame
+    /// lies not farther from `point` than `tolerance`. Borders of the innermost frames are
+    /// preferred. Floating frames have no borders.
+    fn find_border(&self, point: Position, tolerance: isize) -> Option<Frame>;
+
     /// Finds top-most frame bordering with frame `self` in given direction.
     fn find_contiguous(&self, direction: Direction, distance: u32) -> Option<Frame>;
 
@@ -73,10 +81,29 @@ impl Searching for Frame {
         }
     }
 
+    fn collect_sids(&self) -> Vec<SurfaceId> {
+        let mut result = Vec::new();
+        if self.get_sid().is_valid() {
+            result.push(self.get_sid());
+        }
+        for subsurface in self.time_iter() {
+            result.append(&mut subsurface.collect_sids());
+        }
+        result
+    }
+
     fn find_pointed(&self, mut point: Position) -> Frame {
         point = point.casted(&self.get_area());
 
-        for ref frame in self.time_iter() {
+        for ref layer in self.time_iter().filter(|frame| frame.is_floating_layer()) {
+            for ref frame in layer.time_iter() {
+                if frame.get_area().contains(&point) {
+                    return frame.find_pointed(point);
+                }
+            }
+        }
+
+        for ref frame in self.time_iter().filter(|frame| !frame.is_floating_layer()) {
             if frame.get_area().contains(&point) {
                 return if self.get_mode() == Mode::Leaf {
                            frame.clone()
@@ -88,6 +115,45 @@ impl Searching for Frame {
         self.clone()
     }
 
+    fn find_border(&self, point: Position, tolerance: isize) -> Option<Frame> {
+        let mut current = self.find_pointed(point);
+        while !current.equals_exact(self) {
+            let parent = match current.get_parent() {
+                Some(parent) => parent,
+                None => break,
+            };
+
+            // Floating frames can not be resized by moving borders
+            if parent.is_floating_layer() {
+                return None;
+            }
+
+            let area = current.get_area();
+            let bounds = match parent.get_geometry() {
+                Geometry::Vertical => {
+                    Some((point.y, area.pos.y, area.pos.y + area.size.height as isize))
+                }
+                Geometry::Horizontal => {
+                    Some((point.x, area.pos.x, area.pos.x + area.size.width as isize))
+                }
+                _ => None,
+            };
+
+            if let Some((coordinate, start, end)) = bounds {
+                if (end - coordinate).abs() <= tolerance && current.get_next_space().is_some() {
+                    return Some(current);
+                }
+                if (coordinate - start).abs() <= tolerance {
+                    if let Some(prev) = current.get_prev_space() {
+                        return Some(prev);
+                    }
+                }
+            }
+            current = parent;
+        }
+        None
+    }
+
     fn find_contiguous(&self, direction: Direction, distance: u32) -> Option<Frame> {
         // If distance is zero, this is the last step of recurrence
         if distance == 0 {
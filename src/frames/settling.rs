@@ -7,7 +7,7 @@
 
 use qualia::{SurfaceAccess, SurfaceId};
 
-use frame::{Frame, Geometry, Mode, Side};
+use frame::{Frame, Geometry, Mode, Side, DEFAULT_WEIGHT};
 use searching::Searching;
 use packing::Packing;
 
@@ -38,6 +38,7 @@ pub trait Settling {
     fn ramify(&mut self, geometry: Geometry) -> Frame;
 
     /// Removes unnecessary layers of container frames containing only one container or leaf frame.
+    /// Floating layers are never removed nor collapsed.
     fn deramify(&mut self);
 
     /// Places frame `self` on given `side` of `target` frame.
@@ -111,8 +112,10 @@ impl Settling for Frame {
                                        self.get_position(),
                                        self.get_size(),
                                        self.get_title());
+        distancer.set_plumbing_weight(self.get_weight());
         self.prejoin(&mut distancer);
         self.remove();
+        self.set_plumbing_weight(DEFAULT_WEIGHT);
         self.set_plumbing_mode(frame_mode);
         distancer.prepend(self);
         distancer
@@ -120,8 +123,12 @@ impl Settling for Frame {
 
     fn deramify(&mut self) {
         let len = self.count_children();
-        if len == 1 {
+        if (len == 1) && !self.is_floating_layer() {
             let mut first = self.get_first_time().expect("should have exactly one child");
+            if first.is_floating_layer() {
+                return;
+            }
+
             let len = first.count_children();
             if len == 1 {
                 let mut second = first.get_first_time().expect("should have exactly one child");
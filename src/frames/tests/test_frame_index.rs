@@ -0,0 +1,162 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Tests for `FrameIndex` functionality.
+
+// -------------------------------------------------------------------------------------------------
+
+extern crate frames;
+extern crate qualia;
+
+mod common;
+
+use frames::{Frame, FrameIndex};
+use frames::Geometry::Stacked;
+use frames::Side::On;
+use frames::settling::Settling;
+
+use common::{assertions, layouts, surface_access_mock};
+
+use qualia::SurfaceId;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Adds all frames from given tree to index.
+fn index_tree(index: &mut FrameIndex, frame: &Frame) {
+    index.insert(frame);
+    for subframe in frame.time_iter() {
+        index_tree(index, &subframe);
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if all frames with surfaces are indexed and frames without surfaces are not.
+#[test]
+fn should_index_frames_with_surfaces() {
+    let (r, _, _, _, _, _, _, a, _, _, _, _, _, _, _, i)
+      = layouts::make_positioned_for_jumping();
+
+    let mut index = FrameIndex::new();
+    index_tree(&mut index, &r);
+
+    assert_eq!(index.len(), 9);
+    assert!(index.verify(&r).is_ok());
+    assertions::assert_frame_equal_exact(&index.find(SurfaceId::new(1)).unwrap(), &a);
+    assertions::assert_frame_equal_exact(&index.find(SurfaceId::new(9)).unwrap(), &i);
+    assert!(index.find(SurfaceId::new(10)).is_none());
+
+    r.destroy();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if verification detects frames missing in index and stale entries.
+#[test]
+fn should_detect_inconsistencies() {
+    let mut sa = surface_access_mock::SurfaceAccessMock::new();
+    let (r, _, _, _, _, _, _, _, _, _, _, mut e, _, _, _, _)
+      = layouts::make_positioned_for_jumping();
+
+    let mut index = FrameIndex::new();
+    assert!(index.verify(&r).is_err());
+
+    index_tree(&mut index, &r);
+    e.destroy_self(&mut sa);
+    assert!(index.verify(&r).is_err());
+
+    index.remove(SurfaceId::new(5));
+    assert!(index.verify(&r).is_ok());
+
+    r.destroy();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if index stays consistent after frame jumps on other leaf.
+#[test]
+fn should_stay_consistent_after_jumping() {
+    let mut sa = surface_access_mock::SurfaceAccessMock::new();
+    let (r, _, mut fghi, _, _, _, _, mut a, _, _, _, _, mut f, _, _, _)
+      = layouts::make_positioned_for_jumping();
+
+    let mut index = FrameIndex::new();
+    index_tree(&mut index, &r);
+
+    f.jump(On, &mut a, &mut sa);
+    index.deramify(&mut fghi);
+
+    assert!(index.verify(&r).is_ok());
+    assertions::assert_frame_equal_exact(&index.find(SurfaceId::new(6)).unwrap(), &f);
+
+    r.destroy();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if index follows surface ID moved to parent when deramifying single leaf.
+#[test]
+fn should_stay_consistent_after_deramifying_leaf() {
+    let mut sa = surface_access_mock::SurfaceAccessMock::new();
+    let mut r = Frame::new_root();
+    let mut c = Frame::new_container(Stacked);
+    let mut l1 = Frame::new_leaf(SurfaceId::new(1), Stacked);
+    let mut l2 = Frame::new_leaf(SurfaceId::new(2), Stacked);
+    r.append(&mut c);
+    c.append(&mut l1);
+    c.append(&mut l2);
+
+    let mut index = FrameIndex::new();
+    index_tree(&mut index, &r);
+
+    index.remove(SurfaceId::new(2));
+    l2.destroy_self(&mut sa);
+    index.deramify(&mut c);
+
+    assert!(index.verify(&r).is_ok());
+    assert_eq!(index.len(), 1);
+    assertions::assert_frame_equal_exact(&index.find(SurfaceId::new(1)).unwrap(), &c);
+
+    r.destroy();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if index does not change when deramifying non-leaf.
+#[test]
+fn should_stay_consistent_after_deramifying_nonleaf() {
+    let (r, _, mut a2, _, _, _, _, d1, _, _) = layouts::make_simple_for_deramifying();
+
+    let mut index = FrameIndex::new();
+    index_tree(&mut index, &r);
+
+    index.deramify(&mut a2);
+
+    assert!(index.verify(&r).is_ok());
+    assert_eq!(index.len(), 4);
+    assertions::assert_frame_equal_exact(&index.find(SurfaceId::new(11)).unwrap(), &d1);
+
+    r.destroy();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if searching is limited to subtree of given frame.
+#[test]
+fn should_find_within_subtree() {
+    let (r, _, fghi, _, abcde, _, _, _, _, _, _, _, _, _, h, _)
+      = layouts::make_positioned_for_jumping();
+
+    let mut index = FrameIndex::new();
+    index_tree(&mut index, &r);
+
+    assertions::assert_frame_equal_exact(&index.find_within(SurfaceId::new(8), &fghi).unwrap(),
+                                         &h);
+    assertions::assert_frame_equal_exact(&index.find_within(SurfaceId::new(8), &h).unwrap(), &h);
+    assert!(index.find_within(SurfaceId::new(8), &abcde).is_none());
+    assert!(index.find_within(SurfaceId::new(10), &r).is_none());
+
+    r.destroy();
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -10,11 +10,13 @@ extern crate qualia;
 
 mod common;
 
+use frames::Frame;
+use frames::Geometry::{Floating, Horizontal, Stacked, Vertical};
 use frames::packing::Packing;
 
 use common::{assertions, layouts, surface_access_mock};
 
-use qualia::{Position, Size};
+use qualia::{Position, Size, SurfaceId};
 
 // -------------------------------------------------------------------------------------------------
 
@@ -47,3 +49,162 @@ fn test_homogenizing() {
 }
 
 // -------------------------------------------------------------------------------------------------
+
+/// Test homogenizing frame containing floating layer. Floating layer should cover whole frame and
+/// should not take space of anchored frames. Floating frames should keep their sizes while their
+/// positions should follow the layer.
+#[test]
+fn test_homogenizing_with_floating_layer() {
+    let mut sa = surface_access_mock::SurfaceAccessMock::new();
+    let mut r = Frame::new_container(Horizontal);
+    let mut a = Frame::new_leaf(SurfaceId::new(1), Stacked);
+    let mut b = Frame::new_leaf(SurfaceId::new(2), Stacked);
+    let mut c = Frame::new_leaf(SurfaceId::new(3), Stacked);
+    let mut l = Frame::new_container(Floating);
+    r.append(&mut a);
+    r.append(&mut l);
+    r.append(&mut b);
+    l.append(&mut c);
+    r.set_plumbing_position_and_size(Position::new(0, 0), Size::new(300, 200));
+    l.set_plumbing_position_and_size(Position::new(0, 0), Size::new(300, 200));
+    c.set_plumbing_position_and_size(Position::new(50, 40), Size::new(100, 80));
+
+    r.homogenize(&mut sa);
+
+    assertions::assert_area(&a, Position::new(  0,  0), Size::new(150, 200));
+    assertions::assert_area(&b, Position::new(150,  0), Size::new(150, 200));
+    assertions::assert_area(&l, Position::new(  0,  0), Size::new(300, 200));
+    assertions::assert_area(&c, Position::new( 50, 40), Size::new(100,  80));
+
+    r.set_position(Position::new(10, 20));
+    r.set_size(Size::new(400, 200), &mut sa);
+
+    assertions::assert_area(&a, Position::new( 10, 20), Size::new(200, 200));
+    assertions::assert_area(&b, Position::new(210, 20), Size::new(200, 200));
+    assertions::assert_area(&l, Position::new( 10, 20), Size::new(400, 200));
+    assertions::assert_area(&c, Position::new( 60, 60), Size::new(100,  80));
+
+    r.destroy();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Test moving border between frames. Both neighbours should be resized and their contents should
+/// follow. Frames should not be shrunk below minimal size.
+#[test]
+fn test_moving_border() {
+    let mut sa = surface_access_mock::SurfaceAccessMock::new();
+    let mut r = Frame::new_container(Horizontal);
+    let mut v = Frame::new_container(Vertical);
+    let mut a = Frame::new_leaf(SurfaceId::new(1), Stacked);
+    let mut b = Frame::new_leaf(SurfaceId::new(2), Stacked);
+    let mut c = Frame::new_leaf(SurfaceId::new(3), Stacked);
+    r.append(&mut a);
+    r.append(&mut v);
+    v.append(&mut b);
+    v.append(&mut c);
+    r.set_plumbing_position_and_size(Position::new(  0,  0), Size::new(300, 200));
+    a.set_plumbing_position_and_size(Position::new(  0,  0), Size::new(100, 200));
+    v.set_plumbing_position_and_size(Position::new(100,  0), Size::new(200, 200));
+    b.set_plumbing_position_and_size(Position::new(100,  0), Size::new(200,  80));
+    c.set_plumbing_position_and_size(Position::new(100, 80), Size::new(200, 120));
+
+    a.move_border(50, &mut sa);
+
+    assertions::assert_area(&a, Position::new(  0,  0), Size::new(150, 200));
+    assertions::assert_area(&v, Position::new(150,  0), Size::new(150, 200));
+    assertions::assert_area(&b, Position::new(150,  0), Size::new(150,  80));
+    assertions::assert_area(&c, Position::new(150, 80), Size::new(150, 120));
+
+    b.move_border(1000, &mut sa);
+
+    assertions::assert_area(&b, Position::new(150,   0), Size::new(150, 168));
+    assertions::assert_area(&c, Position::new(150, 168), Size::new(150,  32));
+
+    a.move_border(-1000, &mut sa);
+    c.move_border(10, &mut sa);
+
+    assertions::assert_area(&a, Position::new( 0,   0), Size::new( 32, 200));
+    assertions::assert_area(&v, Position::new(32,   0), Size::new(268, 200));
+    assertions::assert_area(&c, Position::new(32, 168), Size::new(268,  32));
+
+    r.destroy();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Test relaxing frames with weights. Proportions set by moving borders should be preserved when
+/// frame is resized and when siblings are added or removed. Homogenizing should reset them.
+#[test]
+fn test_relaxing_with_weights() {
+    let mut sa = surface_access_mock::SurfaceAccessMock::new();
+    let mut r = Frame::new_container(Horizontal);
+    let mut a = Frame::new_leaf(SurfaceId::new(1), Stacked);
+    let mut b = Frame::new_leaf(SurfaceId::new(2), Stacked);
+    let mut c = Frame::new_leaf(SurfaceId::new(3), Stacked);
+    r.append(&mut a);
+    r.append(&mut b);
+    r.set_plumbing_position_and_size(Position::new(0, 0), Size::new(300, 200));
+    r.homogenize(&mut sa);
+    a.move_border(75, &mut sa);
+
+    assertions::assert_area(&a, Position::new(  0, 0), Size::new(225, 200));
+    assertions::assert_area(&b, Position::new(225, 0), Size::new( 75, 200));
+
+    r.set_size(Size::new(600, 200), &mut sa);
+
+    assertions::assert_area(&a, Position::new(  0, 0), Size::new(450, 200));
+    assertions::assert_area(&b, Position::new(450, 0), Size::new(150, 200));
+
+    r.append(&mut c);
+    r.relax(&mut sa);
+
+    assertions::assert_area(&a, Position::new(  0, 0), Size::new(300, 200));
+    assertions::assert_area(&b, Position::new(300, 0), Size::new(100, 200));
+    assertions::assert_area(&c, Position::new(400, 0), Size::new(200, 200));
+
+    c.remove_self(&mut sa);
+
+    assertions::assert_area(&a, Position::new(  0, 0), Size::new(450, 200));
+    assertions::assert_area(&b, Position::new(450, 0), Size::new(150, 200));
+
+    r.homogenize(&mut sa);
+
+    assertions::assert_area(&a, Position::new(  0, 0), Size::new(300, 200));
+    assertions::assert_area(&b, Position::new(300, 0), Size::new(300, 200));
+
+    c.destroy();
+    r.destroy();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Test calculating size of frame before appending it. It should be equal to size the frame gets
+/// after appending and relaxing.
+#[test]
+fn test_calculating_appended_size() {
+    let mut sa = surface_access_mock::SurfaceAccessMock::new();
+    let mut r = Frame::new_container(Horizontal);
+    let mut a = Frame::new_leaf(SurfaceId::new(1), Stacked);
+    let mut b = Frame::new_leaf(SurfaceId::new(2), Stacked);
+    let mut c = Frame::new_leaf(SurfaceId::new(3), Stacked);
+    r.set_plumbing_position_and_size(Position::new(0, 0), Size::new(300, 200));
+
+    assert_eq!(r.calculate_appended_size(), Size::new(300, 200));
+
+    r.append(&mut a);
+    r.append(&mut b);
+    r.homogenize(&mut sa);
+    a.move_border(50, &mut sa);
+
+    assert_eq!(r.calculate_appended_size(), Size::new(100, 200));
+
+    r.append(&mut c);
+    r.relax(&mut sa);
+
+    assertions::assert_area(&c, Position::new(200, 0), Size::new(100, 200));
+
+    r.destroy();
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -11,7 +11,7 @@ extern crate qualia;
 mod common;
 
 use frames::Frame;
-use frames::Geometry::{Horizontal, Stacked, Vertical};
+use frames::Geometry::{Floating, Horizontal, Stacked, Vertical};
 use frames::searching::Searching;
 
 use common::{assertions, layouts};
@@ -94,6 +94,30 @@ fn test_find_with_sid() {
 
 // -------------------------------------------------------------------------------------------------
 
+/// Check collecting surface IDs.
+///
+///  - All surface IDs should be collected from root.
+///  - Only surface IDs of contained frames should be collected from container.
+///  - Leaf should return only its own surface ID.
+#[test]
+fn test_collect_sids() {
+    let (r, _, h, _, v1, _, _, _, _, _, _, _, _) = layouts::make_simple_frames_appending();
+
+    let mut sids: Vec<u64> = r.collect_sids().iter().map(|sid| sid.as_number()).collect();
+    sids.sort();
+    assert_eq!(sids, vec![11, 12, 13, 21, 22, 23, 31, 32, 33]);
+
+    let mut sids: Vec<u64> = h.collect_sids().iter().map(|sid| sid.as_number()).collect();
+    sids.sort();
+    assert_eq!(sids, vec![21, 22, 23]);
+
+    assert_eq!(v1.collect_sids(), vec![SurfaceId::new(11)]);
+
+    r.destroy();
+}
+
+// -------------------------------------------------------------------------------------------------
+
 /// Find contiguous frame on the same level.
 ///
 ///  - 0*South from A should be A.
@@ -534,3 +558,85 @@ fn test_find_adjacent_frames() {
 }
 
 // -------------------------------------------------------------------------------------------------
+
+/// When searching inside frame with floating layer, floating frame containing the point should be
+/// found even if it is less recently used than anchored frame below it. If no floating frame
+/// contains the point, anchored frame should be found.
+///
+///
+///     ┌────────────────────────┐
+///     │┌──────────┬───────────┐│
+///     ││    ┌─────┴─────┐     ││
+///     ││  A │   ×  F  × │  B  ││
+///     ││    └─────┬─────┘  ×  ││
+///     │└──────────┴───────────┘│
+///     └────────────────────────┘
+///
+#[test]
+fn test_find_floating_pointed() {
+    let mut r = Frame::new_container(Horizontal);
+    let mut a = Frame::new_leaf(SurfaceId::new(1), Stacked);
+    let mut b = Frame::new_leaf(SurfaceId::new(2), Stacked);
+    let mut f = Frame::new_leaf(SurfaceId::new(3), Stacked);
+    let mut l = Frame::new_container(Floating);
+    l.append(&mut f);
+    r.append(&mut l);
+    r.append(&mut a);
+    r.append(&mut b);
+    a.pop();
+    r.set_plumbing_position_and_size(Position::new( 0,  0), Size::new(100, 60));
+    l.set_plumbing_position_and_size(Position::new( 0,  0), Size::new(100, 60));
+    a.set_plumbing_position_and_size(Position::new( 0,  0), Size::new( 50, 60));
+    b.set_plumbing_position_and_size(Position::new(50,  0), Size::new( 50, 60));
+    f.set_plumbing_position_and_size(Position::new(30, 20), Size::new( 40, 20));
+
+    assertions::assert_frame_equal_exact(&r.find_pointed(Position::new(35, 25)), &f);
+    assertions::assert_frame_equal_exact(&r.find_pointed(Position::new(65, 25)), &f);
+    assertions::assert_frame_equal_exact(&r.find_pointed(Position::new(80, 45)), &b);
+    assertions::assert_frame_equal_exact(&r.find_pointed(Position::new(10, 10)), &a);
+
+    r.destroy();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Test finding border between frames.
+///
+///     ┌─────┬──────────┐
+///     │     │    B     │
+///     │  A  ├──────────┤
+///     │     │    C     │
+///     └─────┴──────────┘
+///
+/// - pointing border between `A` and `B`/`C` should return `A`
+/// - pointing border between `B` and `C` should return `B`
+/// - pointing inside frames or at outer border should return nothing
+#[test]
+fn test_find_border() {
+    let mut r = Frame::new_container(Horizontal);
+    let mut v = Frame::new_container(Vertical);
+    let mut a = Frame::new_leaf(SurfaceId::new(1), Stacked);
+    let mut b = Frame::new_leaf(SurfaceId::new(2), Stacked);
+    let mut c = Frame::new_leaf(SurfaceId::new(3), Stacked);
+    r.append(&mut a);
+    r.append(&mut v);
+    v.append(&mut b);
+    v.append(&mut c);
+    r.set_plumbing_position_and_size(Position::new(  0,  0), Size::new(300, 200));
+    a.set_plumbing_position_and_size(Position::new(  0,  0), Size::new(100, 200));
+    v.set_plumbing_position_and_size(Position::new(100,  0), Size::new(200, 200));
+    b.set_plumbing_position_and_size(Position::new(100,  0), Size::new(200,  80));
+    c.set_plumbing_position_and_size(Position::new(100, 80), Size::new(200, 120));
+
+    assertions::assert_frame_equal_exact(&r.find_border(Position::new( 98,  50), 3).unwrap(), &a);
+    assertions::assert_frame_equal_exact(&r.find_border(Position::new(101, 150), 3).unwrap(), &a);
+    assertions::assert_frame_equal_exact(&r.find_border(Position::new(150,  81), 3).unwrap(), &b);
+    assertions::assert_frame_equal_exact(&r.find_border(Position::new(150,  78), 3).unwrap(), &b);
+    assert!(r.find_border(Position::new( 50,  50), 3).is_none());
+    assert!(r.find_border(Position::new(150, 150), 3).is_none());
+    assert!(r.find_border(Position::new(298, 150), 3).is_none());
+
+    r.destroy();
+}
+
+// -------------------------------------------------------------------------------------------------
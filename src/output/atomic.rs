@@ -0,0 +1,835 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains support for atomic modesetting.
+//!
+//! Every frame is presented by single property-based commit setting framebuffer of primary plane
+//! and of overlay planes assigned to the CRTC. The first commit additionally sets the mode. Commits
+//! may be tested before applying to check if given plane configuration is supported by hardware.
+//!
+//! Display is powered off by deactivating the CRTC and powered on by the next commit which sets the
+//! mode again. When atomic API is not available connector `DPMS` property is used instead.
+//!
+//! Colorimetry and HDR metadata signalled to display (connector `Colorspace` and
+//! `HDR_OUTPUT_METADATA` properties) are set together with the mode, so changing them requires
+//! modeset.
+
+// -------------------------------------------------------------------------------------------------
+
+use std;
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::os::unix::io::RawFd;
+
+use libc;
+
+use qualia::{Area, ColorDescription, Illusion, Primaries};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Client capability exposing all planes including primary and cursor ones.
+const DRM_CLIENT_CAP_UNIVERSAL_PLANES: u64 = 2;
+
+/// Client capability enabling atomic API.
+const DRM_CLIENT_CAP_ATOMIC: u64 = 3;
+
+/// DRM object type of CRTC.
+const DRM_MODE_OBJECT_CRTC: u32 = 0xcccccccc;
+
+/// DRM object type of connector.
+const DRM_MODE_OBJECT_CONNECTOR: u32 = 0xc0c0c0c0;
+
+/// DRM object type of plane.
+const DRM_MODE_OBJECT_PLANE: u32 = 0xeeeeeeee;
+
+/// Commit flag requesting page flip event.
+const DRM_MODE_PAGE_FLIP_EVENT: u32 = 0x0001;
+
+/// Commit flag requesting only to check if the configuration is valid.
+const DRM_MODE_ATOMIC_TEST_ONLY: u32 = 0x0100;
+
+/// Commit flag requesting not to wait for the commit to be applied.
+const DRM_MODE_ATOMIC_NONBLOCK: u32 = 0x0200;
+
+/// Commit flag allowing to change the mode.
+const DRM_MODE_ATOMIC_ALLOW_MODESET: u32 = 0x0400;
+
+/// Value of plane `type` property for overlay planes.
+const DRM_PLANE_TYPE_OVERLAY: u64 = 0;
+
+/// Value of plane `type` property for primary planes.
+const DRM_PLANE_TYPE_PRIMARY: u64 = 1;
+
+/// Value of connector `DPMS` property for powered on display.
+const DRM_MODE_DPMS_ON: u64 = 0;
+
+/// Value of connector `DPMS` property for powered off display.
+const DRM_MODE_DPMS_OFF: u64 = 3;
+
+/// Name of connector property describing colorimetry of sent signal.
+const COLORSPACE_PROPERTY: &'static str = "Colorspace";
+
+/// Name of connector property holding HDR metadata infoframe.
+const HDR_OUTPUT_METADATA_PROPERTY: &'static str = "HDR_OUTPUT_METADATA";
+
+// -------------------------------------------------------------------------------------------------
+
+/// Mirror of `drmModeRes`.
+#[repr(C)]
+#[allow(dead_code)]
+struct DrmModeRes {
+    count_fbs: libc::c_int,
+    fbs: *mut u32,
+    count_crtcs: libc::c_int,
+    crtcs: *mut u32,
+    count_connectors: libc::c_int,
+    connectors: *mut u32,
+    count_encoders: libc::c_int,
+    encoders: *mut u32,
+    min_width: u32,
+    max_width: u32,
+    min_height: u32,
+    max_height: u32,
+}
+
+/// Mirror of `drmModeModeInfo`.
+#[repr(C)]
+#[allow(dead_code)]
+struct DrmModeModeInfo {
+    clock: u32,
+    hdisplay: u16,
+    hsync_start: u16,
+    hsync_end: u16,
+    htotal: u16,
+    hskew: u16,
+    vdisplay: u16,
+    vsync_start: u16,
+    vsync_end: u16,
+    vtotal: u16,
+    vscan: u16,
+    vrefresh: u32,
+    flags: u32,
+    kind: u32,
+    name: [libc::c_char; 32],
+}
+
+/// Leading part of `drmModeConnector`. Only accessed via pointers returned by libdrm.
+#[repr(C)]
+#[allow(dead_code)]
+struct DrmModeConnector {
+    connector_id: u32,
+    encoder_id: u32,
+    connector_type: u32,
+    connector_type_id: u32,
+    connection: libc::c_int,
+    mm_width: u32,
+    mm_height: u32,
+    subpixel: libc::c_int,
+    count_modes: libc::c_int,
+    modes: *mut DrmModeModeInfo,
+}
+
+/// Mirror of `drmModeObjectProperties`.
+#[repr(C)]
+#[allow(dead_code)]
+struct DrmModeObjectProperties {
+    count_props: u32,
+    props: *mut u32,
+    prop_values: *mut u64,
+}
+
+/// Mirror of `drmModePropertyRes`.
+#[repr(C)]
+#[allow(dead_code)]
+struct DrmModePropertyRes {
+    prop_id: u32,
+    flags: u32,
+    name: [libc::c_char; 32],
+    count_values: libc::c_int,
+    values: *mut u64,
+    count_enums: libc::c_int,
+    enums: *mut DrmModePropertyEnum,
+    count_blobs: libc::c_int,
+    blob_ids: *mut u32,
+}
+
+/// Mirror of `drm_mode_property_enum`.
+#[repr(C)]
+struct DrmModePropertyEnum {
+    value: u64,
+    name: [libc::c_char; 32],
+}
+
+/// Mirror of `drmModePlaneRes`.
+#[repr(C)]
+#[allow(dead_code)]
+struct DrmModePlaneRes {
+    count_planes: u32,
+    planes: *mut u32,
+}
+
+/// Mirror of `drmModePlane`.
+#[repr(C)]
+#[allow(dead_code)]
+struct DrmModePlane {
+    count_formats: u32,
+    formats: *mut u32,
+    plane_id: u32,
+    crtc_id: u32,
+    fb_id: u32,
+    crtc_x: u32,
+    crtc_y: u32,
+    x: u32,
+    y: u32,
+    possible_crtcs: u32,
+    gamma_size: u32,
+}
+
+#[link(name = "drm")]
+extern "C" {
+    fn drmSetClientCap(fd: libc::c_int, capability: u64, value: u64) -> libc::c_int;
+    fn drmModeGetResources(fd: libc::c_int) -> *mut DrmModeRes;
+    fn drmModeFreeResources(ptr: *mut DrmModeRes);
+    fn drmModeGetConnector(fd: libc::c_int, connector_id: u32) -> *mut DrmModeConnector;
+    fn drmModeFreeConnector(ptr: *mut DrmModeConnector);
+    fn drmModeConnectorSetProperty(fd: libc::c_int,
+                                   connector_id: u32,
+                                   property_id: u32,
+                                   value: u64)
+                                   -> libc::c_int;
+    fn drmModeObjectGetProperties(fd: libc::c_int,
+                                  object_id: u32,
+                                  object_type: u32)
+                                  -> *mut DrmModeObjectProperties;
+    fn drmModeFreeObjectProperties(ptr: *mut DrmModeObjectProperties);
+    fn drmModeGetProperty(fd: libc::c_int, property_id: u32) -> *mut DrmModePropertyRes;
+    fn drmModeFreeProperty(ptr: *mut DrmModePropertyRes);
+    fn drmModeGetPlaneResources(fd: libc::c_int) -> *mut DrmModePlaneRes;
+    fn drmModeFreePlaneResources(ptr: *mut DrmModePlaneRes);
+    fn drmModeGetPlane(fd: libc::c_int, plane_id: u32) -> *mut DrmModePlane;
+    fn drmModeFreePlane(ptr: *mut DrmModePlane);
+    fn drmModeCreatePropertyBlob(fd: libc::c_int,
+                                 data: *const libc::c_void,
+                                 size: libc::size_t,
+                                 id: *mut u32)
+                                 -> libc::c_int;
+    fn drmModeDestroyPropertyBlob(fd: libc::c_int, id: u32) -> libc::c_int;
+    fn drmModeRmFB(fd: libc::c_int, fb_id: u32) -> libc::c_int;
+    fn drmModeAtomicAlloc() -> *mut libc::c_void;
+    fn drmModeAtomicFree(req: *mut libc::c_void);
+    fn drmModeAtomicAddProperty(req: *mut libc::c_void,
+                                object_id: u32,
+                                property_id: u32,
+                                value: u64)
+                                -> libc::c_int;
+    fn drmModeAtomicCommit(fd: libc::c_int,
+                           req: *mut libc::c_void,
+                           flags: u32,
+                           user_data: *mut libc::c_void)
+                           -> libc::c_int;
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Describes which framebuffer should be displayed on given plane and where.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PlaneAssignment {
+    /// ID of the plane.
+    pub plane_id: u32,
+
+    /// ID of framebuffer to display.
+    pub fb: u32,
+
+    /// Part of framebuffer to display.
+    pub source: Area,
+
+    /// Area of the output to display the framebuffer on.
+    pub destination: Area,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Map from property name to property ID.
+type PropertyIds = HashMap<String, u32>;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wrapper for atomic request freeing it when dropped.
+struct AtomicRequest {
+    req: *mut libc::c_void,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl AtomicRequest {
+    /// Allocates new request.
+    fn new() -> Result<Self, Illusion> {
+        let req = unsafe { drmModeAtomicAlloc() };
+        if req.is_null() {
+            Err(Illusion::General(format!("Failed to allocate atomic request")))
+        } else {
+            Ok(AtomicRequest { req: req })
+        }
+    }
+
+    /// Adds property with given name to the request.
+    fn add(&mut self,
+           object_id: u32,
+           props: &PropertyIds,
+           name: &str,
+           value: u64)
+           -> Result<(), Illusion> {
+        if let Some(prop_id) = props.get(name) {
+            if unsafe { drmModeAtomicAddProperty(self.req, object_id, *prop_id, value) } >= 0 {
+                return Ok(());
+            }
+        }
+        Err(Illusion::General(format!("Failed to add property '{}' of object {}", name, object_id)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Drop for AtomicRequest {
+    fn drop(&mut self) {
+        unsafe { drmModeAtomicFree(self.req) };
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Performs atomic commits for single CRTC.
+pub struct AtomicModesetter {
+    /// DRM device file descriptor.
+    fd: RawFd,
+
+    /// ID of driven CRTC.
+    crtc_id: u32,
+
+    /// ID of connector attached to the CRTC.
+    connector_id: u32,
+
+    /// ID of primary plane of the CRTC.
+    primary_plane_id: u32,
+
+    /// IDs of overlay planes usable with the CRTC.
+    overlay_plane_ids: Vec<u32>,
+
+    /// Property IDs of the CRTC.
+    crtc_props: PropertyIds,
+
+    /// Property IDs of the connector.
+    connector_props: PropertyIds,
+
+    /// Property IDs of the planes.
+    plane_props: HashMap<u32, PropertyIds>,
+
+    /// ID of blob holding the mode.
+    mode_blob_id: u32,
+
+    /// Area of the whole output.
+    area: Area,
+
+    /// Tells if the mode still has to be set.
+    modeset_needed: bool,
+
+    /// IDs of overlay planes enabled by last commit.
+    enabled_overlays: Vec<u32>,
+
+    /// Values of connector `Colorspace` property by name. Empty if the property is not supported.
+    colorspaces: HashMap<String, u64>,
+
+    /// Description of colors of displayed contents.
+    color_description: ColorDescription,
+
+    /// ID of blob holding HDR metadata. Zero if no metadata is sent.
+    hdr_blob_id: u32,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl AtomicModesetter {
+    /// Creates new `AtomicModesetter`. Returns error if driver does not support atomic API.
+    ///
+    /// `mode_index` is index of mode in list of modes of the connector and `area` is area of output
+    /// in output coordinates.
+    pub fn new(fd: RawFd,
+               crtc_id: u32,
+               connector_id: u32,
+               mode_index: usize,
+               area: Area)
+               -> Result<Self, Illusion> {
+        unsafe {
+            if drmSetClientCap(fd, DRM_CLIENT_CAP_UNIVERSAL_PLANES, 1) != 0 ||
+               drmSetClientCap(fd, DRM_CLIENT_CAP_ATOMIC, 1) != 0 {
+                return Err(Illusion::General(format!("Atomic modesetting not supported")));
+            }
+        }
+
+        let crtc_index = Self::get_crtc_index(fd, crtc_id)?;
+        let crtc_props = Self::get_property_ids(fd, crtc_id, DRM_MODE_OBJECT_CRTC)?;
+        let connector_props = Self::get_property_ids(fd, connector_id, DRM_MODE_OBJECT_CONNECTOR)?;
+
+        // Find planes usable with the CRTC
+        let mut primary_plane_id = None;
+        let mut overlay_plane_ids = Vec::new();
+        let mut plane_props = HashMap::new();
+        for plane_id in Self::get_plane_ids(fd)? {
+            let possible_crtcs = unsafe {
+                let plane = drmModeGetPlane(fd, plane_id);
+                if plane.is_null() {
+                    continue;
+                }
+                let possible_crtcs = (*plane).possible_crtcs;
+                drmModeFreePlane(plane);
+                possible_crtcs
+            };
+            if possible_crtcs & (1 << crtc_index) == 0 {
+                continue;
+            }
+
+            match Self::get_property_value(fd, plane_id, DRM_MODE_OBJECT_PLANE, "type") {
+                Some(DRM_PLANE_TYPE_PRIMARY) => {
+                    if primary_plane_id.is_none() {
+                        primary_plane_id = Some(plane_id);
+                    }
+                }
+                Some(DRM_PLANE_TYPE_OVERLAY) => overlay_plane_ids.push(plane_id),
+                _ => continue,
+            }
+            plane_props.insert(plane_id,
+                               Self::get_property_ids(fd, plane_id, DRM_MODE_OBJECT_PLANE)?);
+        }
+
+        let primary_plane_id = if let Some(primary_plane_id) = primary_plane_id {
+            primary_plane_id
+        } else {
+            return Err(Illusion::General(format!("No primary plane for CRTC {}", crtc_id)));
+        };
+
+        let mode_blob_id = Self::create_mode_blob(fd, connector_id, mode_index)?;
+        let colorspaces = connector_props.get(COLORSPACE_PROPERTY)
+            .map(|id| Self::get_property_enums(fd, *id))
+            .unwrap_or(HashMap::new());
+
+        Ok(AtomicModesetter {
+               fd: fd,
+               crtc_id: crtc_id,
+               connector_id: connector_id,
+               primary_plane_id: primary_plane_id,
+               overlay_plane_ids: overlay_plane_ids,
+               crtc_props: crtc_props,
+               connector_props: connector_props,
+               plane_props: plane_props,
+               mode_blob_id: mode_blob_id,
+               area: area,
+               modeset_needed: true,
+               enabled_overlays: Vec::new(),
+               colorspaces: colorspaces,
+               color_description: ColorDescription::srgb(),
+               hdr_blob_id: 0,
+           })
+    }
+
+    /// Checks if HDR10 contents (BT.2020 primaries with HDR metadata) can be signalled to the
+    /// display.
+    pub fn supports_hdr(&self) -> bool {
+        self.connector_props.contains_key(HDR_OUTPUT_METADATA_PROPERTY) &&
+        self.colorspaces.contains_key(Self::get_colorspace_name(Primaries::Bt2020))
+    }
+
+    /// Sets description of colors of displayed contents sent to display with the next modeset.
+    /// Does nothing if the description did not change.
+    pub fn set_color_description(&mut self,
+                                 description: ColorDescription)
+                                 -> Result<(), Illusion> {
+        if description == self.color_description {
+            return Ok(());
+        }
+        if description.is_hdr() && !self.supports_hdr() {
+            return Err(Illusion::General(format!("HDR is not supported by connector {}",
+                                                 self.connector_id)));
+        }
+
+        let blob_id = if description.is_hdr() {
+            let data = description.to_hdr_output_metadata();
+            let mut blob_id = 0;
+            let result = unsafe {
+                drmModeCreatePropertyBlob(self.fd,
+                                          data.as_ptr() as *const libc::c_void,
+                                          data.len(),
+                                          &mut blob_id)
+            };
+            if result != 0 {
+                return Err(Illusion::General(format!("Failed to create HDR metadata blob")));
+            }
+            blob_id
+        } else {
+            0
+        };
+
+        if self.hdr_blob_id != 0 {
+            unsafe { drmModeDestroyPropertyBlob(self.fd, self.hdr_blob_id) };
+        }
+        self.hdr_blob_id = blob_id;
+        self.color_description = description;
+        self.modeset_needed = true;
+        Ok(())
+    }
+
+    /// Returns IDs of overlay planes which can be assigned to the CRTC.
+    pub fn get_overlay_planes(&self) -> Vec<u32> {
+        self.overlay_plane_ids.clone()
+    }
+
+    /// Checks if displaying given framebuffer on primary plane along with given plane assignments
+    /// would succeed.
+    pub fn test(&self, fb: u32, assignments: &[PlaneAssignment]) -> bool {
+        let flags = DRM_MODE_ATOMIC_TEST_ONLY | self.get_modeset_flag();
+        self.prepare_request(fb, assignments)
+            .and_then(|req| self.commit_request(req, flags, 0))
+            .is_ok()
+    }
+
+    /// Sets the mode and displays given framebuffer blocking until the commit is applied. Does
+    /// nothing if mode was already set.
+    pub fn modeset(&mut self, fb: u32) -> Result<(), Illusion> {
+        if self.modeset_needed {
+            let req = self.prepare_request(fb, &[])?;
+            self.commit_request(req, DRM_MODE_ATOMIC_ALLOW_MODESET, 0)?;
+            self.modeset_needed = false;
+        }
+        Ok(())
+    }
+
+    /// Schedules displaying given framebuffer on primary plane along with given plane
+    /// assignments. Page flip event with `user_data` will be emitted when done. Overlay planes
+    /// enabled by previous commit but not present in `assignments` are disabled.
+    pub fn commit(&mut self,
+                  fb: u32,
+                  assignments: &[PlaneAssignment],
+                  user_data: i32)
+                  -> Result<(), Illusion> {
+        let flags = DRM_MODE_PAGE_FLIP_EVENT | DRM_MODE_ATOMIC_NONBLOCK | self.get_modeset_flag();
+        let req = self.prepare_request(fb, assignments)?;
+        self.commit_request(req, flags, user_data)?;
+        self.modeset_needed = false;
+        self.enabled_overlays = assignments.iter().map(|a| a.plane_id).collect();
+        Ok(())
+    }
+
+    /// Changes mode to the one with given index in list of modes of the connector. `area` is new
+    /// area of output. The mode is set by the next commit.
+    pub fn set_mode(&mut self, mode_index: usize, area: Area) -> Result<(), Illusion> {
+        let mode_blob_id = Self::create_mode_blob(self.fd, self.connector_id, mode_index)?;
+        unsafe { drmModeDestroyPropertyBlob(self.fd, self.mode_blob_id) };
+        self.mode_blob_id = mode_blob_id;
+        self.area = area;
+        self.modeset_needed = true;
+        Ok(())
+    }
+
+    /// Makes the next commit set the mode again, e.g. after other DRM master changed it.
+    pub fn request_modeset(&mut self) {
+        self.modeset_needed = true;
+    }
+
+    /// Deactivates the CRTC powering the display off. The next commit activates it again.
+    pub fn deactivate(&mut self) -> Result<(), Illusion> {
+        let mut req = AtomicRequest::new()?;
+        req.add(self.crtc_id, &self.crtc_props, "ACTIVE", 0)?;
+        self.commit_request(req, DRM_MODE_ATOMIC_ALLOW_MODESET, 0)?;
+        self.modeset_needed = true;
+        Ok(())
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Powers display driven by given connector on or off using legacy `DPMS` property.
+pub fn set_dpms(fd: RawFd, connector_id: u32, on: bool) -> Result<(), Illusion> {
+    let mut prop_id = None;
+    AtomicModesetter::for_each_property(fd, connector_id, DRM_MODE_OBJECT_CONNECTOR, |name, id, _| {
+        if name == "DPMS" {
+            prop_id = Some(id);
+        }
+    });
+
+    let value = if on { DRM_MODE_DPMS_ON } else { DRM_MODE_DPMS_OFF };
+    if let Some(prop_id) = prop_id {
+        if unsafe { drmModeConnectorSetProperty(fd, connector_id, prop_id, value) } == 0 {
+            return Ok(());
+        }
+    }
+    Err(Illusion::General(format!("Failed to set DPMS of connector {}", connector_id)))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Removes framebuffer with given ID, e.g. when its size does not match new mode of output.
+pub fn remove_framebuffer(fd: RawFd, fb: u32) -> Result<(), Illusion> {
+    if unsafe { drmModeRmFB(fd, fb) } == 0 {
+        Ok(())
+    } else {
+        Err(Illusion::General(format!("Failed to remove framebuffer {}", fb)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Private methods.
+impl AtomicModesetter {
+    /// Returns flag allowing modeset if mode was not set yet.
+    fn get_modeset_flag(&self) -> u32 {
+        if self.modeset_needed { DRM_MODE_ATOMIC_ALLOW_MODESET } else { 0 }
+    }
+
+    /// Builds atomic request.
+    fn prepare_request(&self,
+                       fb: u32,
+                       assignments: &[PlaneAssignment])
+                       -> Result<AtomicRequest, Illusion> {
+        let mut req = AtomicRequest::new()?;
+
+        if self.modeset_needed {
+            req.add(self.connector_id, &self.connector_props, "CRTC_ID", self.crtc_id as u64)?;
+            req.add(self.crtc_id, &self.crtc_props, "MODE_ID", self.mode_blob_id as u64)?;
+            req.add(self.crtc_id, &self.crtc_props, "ACTIVE", 1)?;
+            self.add_colorimetry(&mut req)?;
+        }
+
+        let primary = PlaneAssignment {
+            plane_id: self.primary_plane_id,
+            fb: fb,
+            source: self.area,
+            destination: self.area,
+        };
+        self.add_plane(&mut req, &primary)?;
+
+        for assignment in assignments.iter() {
+            if !self.overlay_plane_ids.contains(&assignment.plane_id) {
+                let text = format!("Plane {} can not be used with CRTC {}",
+                                   assignment.plane_id,
+                                   self.crtc_id);
+                return Err(Illusion::General(text));
+            }
+            self.add_plane(&mut req, assignment)?;
+        }
+
+        for plane_id in self.enabled_overlays.iter() {
+            if assignments.iter().find(|a| a.plane_id == *plane_id).is_none() {
+                let props = self.get_plane_props(*plane_id)?;
+                req.add(*plane_id, props, "FB_ID", 0)?;
+                req.add(*plane_id, props, "CRTC_ID", 0)?;
+            }
+        }
+
+        Ok(req)
+    }
+
+    /// Adds connector properties describing colors of displayed contents to the request. Drivers
+    /// not supporting them get only sRGB contents.
+    fn add_colorimetry(&self, req: &mut AtomicRequest) -> Result<(), Illusion> {
+        let name = Self::get_colorspace_name(self.color_description.primaries);
+        if let Some(value) = self.colorspaces.get(name) {
+            req.add(self.connector_id, &self.connector_props, COLORSPACE_PROPERTY, *value)?;
+        }
+        if self.connector_props.contains_key(HDR_OUTPUT_METADATA_PROPERTY) {
+            req.add(self.connector_id,
+                    &self.connector_props,
+                    HDR_OUTPUT_METADATA_PROPERTY,
+                    self.hdr_blob_id as u64)?;
+        }
+        Ok(())
+    }
+
+    /// Returns name of value of connector `Colorspace` property for given primaries.
+    fn get_colorspace_name(primaries: Primaries) -> &'static str {
+        match primaries {
+            Primaries::Srgb => "Default",
+            Primaries::Bt2020 => "BT2020_RGB",
+        }
+    }
+
+    /// Adds properties of plane assignment to the request.
+    fn add_plane(&self, req: &mut AtomicRequest, a: &PlaneAssignment) -> Result<(), Illusion> {
+        let props = self.get_plane_props(a.plane_id)?;
+        let id = a.plane_id;
+
+        // Source coordinates are in 16.16 fixed point format
+        req.add(id, props, "FB_ID", a.fb as u64)?;
+        req.add(id, props, "CRTC_ID", self.crtc_id as u64)?;
+        req.add(id, props, "SRC_X", (a.source.pos.x as u64) << 16)?;
+        req.add(id, props, "SRC_Y", (a.source.pos.y as u64) << 16)?;
+        req.add(id, props, "SRC_W", (a.source.size.width as u64) << 16)?;
+        req.add(id, props, "SRC_H", (a.source.size.height as u64) << 16)?;
+        req.add(id, props, "CRTC_X", a.destination.pos.x as u64)?;
+        req.add(id, props, "CRTC_Y", a.destination.pos.y as u64)?;
+        req.add(id, props, "CRTC_W", a.destination.size.width as u64)?;
+        req.add(id, props, "CRTC_H", a.destination.size.height as u64)
+    }
+
+    /// Returns property IDs of given plane.
+    fn get_plane_props(&self, plane_id: u32) -> Result<&PropertyIds, Illusion> {
+        self.plane_props
+            .get(&plane_id)
+            .ok_or(Illusion::General(format!("Unknown plane {}", plane_id)))
+    }
+
+    /// Commits the request.
+    fn commit_request(&self,
+                      req: AtomicRequest,
+                      flags: u32,
+                      user_data: i32)
+                      -> Result<(), Illusion> {
+        let data = user_data as isize as *mut libc::c_void;
+        let result = unsafe { drmModeAtomicCommit(self.fd, req.req, flags, data) };
+        if result == 0 {
+            Ok(())
+        } else {
+            Err(Illusion::General(format!("Atomic commit failed (crtc_id: {}, error: {})",
+                                          self.crtc_id,
+                                          result)))
+        }
+    }
+
+    /// Returns index of CRTC in the list of CRTCs of the device.
+    fn get_crtc_index(fd: RawFd, crtc_id: u32) -> Result<usize, Illusion> {
+        let mut index = None;
+        unsafe {
+            let resources = drmModeGetResources(fd);
+            if !resources.is_null() {
+                let count = (*resources).count_crtcs as usize;
+                let crtcs = std::slice::from_raw_parts((*resources).crtcs, count);
+                index = crtcs.iter().position(|id| *id == crtc_id);
+                drmModeFreeResources(resources);
+            }
+        }
+        index.ok_or(Illusion::General(format!("Failed to find CRTC {}", crtc_id)))
+    }
+
+    /// Returns IDs of all planes of the device.
+    fn get_plane_ids(fd: RawFd) -> Result<Vec<u32>, Illusion> {
+        unsafe {
+            let resources = drmModeGetPlaneResources(fd);
+            if resources.is_null() {
+                return Err(Illusion::General(format!("Failed to get plane resources")));
+            }
+            let count = (*resources).count_planes as usize;
+            let ids = std::slice::from_raw_parts((*resources).planes, count).to_vec();
+            drmModeFreePlaneResources(resources);
+            Ok(ids)
+        }
+    }
+
+    /// Calls `func` with name, ID and value of every property of given object.
+    fn for_each_property<F>(fd: RawFd, object_id: u32, object_type: u32, mut func: F) -> bool
+        where F: FnMut(String, u32, u64)
+    {
+        unsafe {
+            let props = drmModeObjectGetProperties(fd, object_id, object_type);
+            if props.is_null() {
+                return false;
+            }
+            let count = (*props).count_props as usize;
+            let ids = std::slice::from_raw_parts((*props).props, count);
+            let values = std::slice::from_raw_parts((*props).prop_values, count);
+            for (id, value) in ids.iter().zip(values.iter()) {
+                let prop = drmModeGetProperty(fd, *id);
+                if !prop.is_null() {
+                    let name = CStr::from_ptr((*prop).name.as_ptr()).to_string_lossy().into_owned();
+                    func(name, *id, *value);
+                    drmModeFreeProperty(prop);
+                }
+            }
+            drmModeFreeObjectProperties(props);
+            true
+        }
+    }
+
+    /// Returns map from property names to property IDs of given object.
+    fn get_property_ids(fd: RawFd,
+                        object_id: u32,
+                        object_type: u32)
+                        -> Result<PropertyIds, Illusion> {
+        let mut ids = PropertyIds::new();
+        if Self::for_each_property(fd, object_id, object_type, |name, id, _| {
+            ids.insert(name, id);
+        }) {
+            Ok(ids)
+        } else {
+            Err(Illusion::General(format!("Failed to get properties of object {}", object_id)))
+        }
+    }
+
+    /// Returns map from names to values of enum property with given ID.
+    fn get_property_enums(fd: RawFd, prop_id: u32) -> HashMap<String, u64> {
+        let mut enums = HashMap::new();
+        unsafe {
+            let prop = drmModeGetProperty(fd, prop_id);
+            if !prop.is_null() {
+                let count = (*prop).count_enums as usize;
+                if count > 0 {
+                    for e in std::slice::from_raw_parts((*prop).enums, count) {
+                        let name = CStr::from_ptr(e.name.as_ptr()).to_string_lossy().into_owned();
+                        enums.insert(name, e.value);
+                    }
+                }
+                drmModeFreeProperty(prop);
+            }
+        }
+        enums
+    }
+
+    /// Returns value of property with given name.
+    fn get_property_value(fd: RawFd, object_id: u32, object_type: u32, name: &str) -> Option<u64> {
+        let mut result = None;
+        Self::for_each_property(fd, object_id, object_type, |prop_name, _, value| {
+            if prop_name == name {
+                result = Some(value);
+            }
+        });
+        result
+    }
+
+    /// Creates property blob holding mode of connector with given index.
+    fn create_mode_blob(fd: RawFd, connector_id: u32, mode_index: usize) -> Result<u32, Illusion> {
+        let mut blob_id = 0;
+        let result = unsafe {
+            let connector = drmModeGetConnector(fd, connector_id);
+            if connector.is_null() {
+                return Err(Illusion::General(format!("Failed to get connector {}", connector_id)));
+            }
+            let result = if (mode_index as libc::c_int) < (*connector).count_modes {
+                drmModeCreatePropertyBlob(fd,
+                                          (*connector).modes.offset(mode_index as isize) as
+                                          *const libc::c_void,
+                                          std::mem::size_of::<DrmModeModeInfo>(),
+                                          &mut blob_id)
+            } else {
+                -1
+            };
+            drmModeFreeConnector(connector);
+            result
+        };
+
+        if result == 0 {
+            Ok(blob_id)
+        } else {
+            Err(Illusion::General(format!("Failed to create mode blob")))
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Drop for AtomicModesetter {
+    fn drop(&mut self) {
+        unsafe { drmModeDestroyPropertyBlob(self.fd, self.mode_blob_id) };
+        if self.hdr_blob_id != 0 {
+            unsafe { drmModeDestroyPropertyBlob(self.fd, self.hdr_blob_id) };
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,274 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains support for hardware cursor planes.
+//!
+//! Cursor image is copied to GBM buffer object which is then set as cursor of CRTC. Only
+//! shared-memory buffers not bigger than cursor plane can be displayed this way; in other cases
+//! caller should fall back to drawing pointer with renderer.
+
+// -------------------------------------------------------------------------------------------------
+
+use std::os::unix::io::RawFd;
+
+use libc;
+
+use qualia::{BufferTransform, Coordinator, MemoryView, Pixmap, Position, Size, SurfaceContext};
+use qualia::SurfaceId;
+
+// -------------------------------------------------------------------------------------------------
+
+/// DRM capability describing maximal width of cursor.
+const DRM_CAP_CURSOR_WIDTH: u64 = 0x8;
+
+/// DRM capability describing maximal height of cursor.
+const DRM_CAP_CURSOR_HEIGHT: u64 = 0x9;
+
+/// Cursor size used if driver does not report one.
+const DEFAULT_CURSOR_SIZE: u64 = 64;
+
+/// GBM fourcc code of ARGB8888 format.
+const GBM_FORMAT_ARGB8888: u32 = 0x34325241;
+
+/// GBM flag for buffers used as cursors.
+const GBM_BO_USE_CURSOR: u32 = 1 << 1;
+
+/// GBM flag for buffers written with `gbm_bo_write`.
+const GBM_BO_USE_WRITE: u32 = 1 << 3;
+
+/// Number of bytes per pixel in ARGB8888 format.
+const BYTES_PER_PIXEL: usize = 4;
+
+// -------------------------------------------------------------------------------------------------
+
+#[link(name = "drm")]
+extern "C" {
+    fn drmGetCap(fd: libc::c_int, capability: u64, value: *mut u64) -> libc::c_int;
+    fn drmModeSetCursor(fd: libc::c_int,
+                        crtc_id: u32,
+                        bo_handle: u32,
+                        width: u32,
+                        height: u32)
+                        -> libc::c_int;
+    fn drmModeMoveCursor(fd: libc::c_int, crtc_id: u32, x: libc::c_int, y: libc::c_int)
+                         -> libc::c_int;
+}
+
+#[link(name = "gbm")]
+extern "C" {
+    fn gbm_bo_create(device: *mut libc::c_void,
+                     width: u32,
+                     height: u32,
+                     format: u32,
+                     flags: u32)
+                     -> *mut libc::c_void;
+    fn gbm_bo_destroy(bo: *mut libc::c_void);
+    fn gbm_bo_write(bo: *mut libc::c_void, buf: *const libc::c_void, count: libc::size_t)
+                    -> libc::c_int;
+    fn gbm_bo_get_handle(bo: *mut libc::c_void) -> u64;
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Hardware cursor of single CRTC.
+pub struct HwCursor {
+    /// DRM device file descriptor.
+    fd: RawFd,
+
+    /// ID of CRTC the cursor belongs to.
+    crtc_id: u32,
+
+    /// GBM buffer object holding cursor image.
+    bo: *mut libc::c_void,
+
+    /// Size of cursor plane.
+    size: Size,
+
+    /// Surface and buffer size of the image currently held in buffer object.
+    owner: Option<(SurfaceId, Size)>,
+
+    /// Tells if cursor is currently shown.
+    visible: bool,
+
+    /// Set to `false` when driver refused to set the cursor. Renderer is used since then.
+    supported: bool,
+
+    /// Last position cursor was moved to.
+    position: Position,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl HwCursor {
+    /// Creates new `HwCursor`. Returns `None` if cursor buffer could not be created.
+    ///
+    /// `device` is pointer to GBM device.
+    pub fn new(device: *mut libc::c_void, fd: RawFd, crtc_id: u32) -> Option<Self> {
+        let width = Self::get_cap(fd, DRM_CAP_CURSOR_WIDTH);
+        let height = Self::get_cap(fd, DRM_CAP_CURSOR_HEIGHT);
+        let bo = unsafe {
+            gbm_bo_create(device,
+                          width as u32,
+                          height as u32,
+                          GBM_FORMAT_ARGB8888,
+                          GBM_BO_USE_CURSOR | GBM_BO_USE_WRITE)
+        };
+
+        if bo.is_null() {
+            log_warn2!("Failed to create cursor buffer; falling back to software cursor");
+            None
+        } else {
+            Some(HwCursor {
+                     fd: fd,
+                     crtc_id: crtc_id,
+                     bo: bo,
+                     size: Size::new(width as usize, height as usize),
+                     owner: None,
+                     visible: false,
+                     supported: true,
+                     position: Position::default(),
+                 })
+        }
+    }
+
+    /// Shows given pointer surface on cursor plane. `origin` is position of the output in global
+    /// coordinates.
+    ///
+    /// Returns `false` if surface can not be displayed by hardware. Cursor is then hidden and
+    /// pointer has to be drawn by renderer.
+    pub fn update(&mut self,
+                  pointer: &SurfaceContext,
+                  origin: Position,
+                  coordinator: &Coordinator)
+                  -> bool {
+        if !self.supported {
+            return false;
+        }
+
+        let surface = if let Some(surface) = coordinator.get_surface(pointer.id) {
+            surface
+        } else {
+            // No cursor surface - nothing to draw
+            self.hide();
+            return true;
+        };
+
+        let buffer = if let Some(ref buffer) = surface.buffer {
+            buffer.clone()
+        } else {
+            self.hide();
+            return false;
+        };
+
+        // Scaled or transformed cursors are drawn by renderer
+        if surface.buffer_transform != BufferTransform::default() {
+            self.hide();
+            return false;
+        }
+
+        let buffer_size = buffer.get_size();
+        if (buffer_size.width > self.size.width) || (buffer_size.height > self.size.height) {
+            self.hide();
+            return false;
+        }
+
+        let owner = Some((pointer.id, buffer_size));
+        if (self.owner != owner) || (pointer.damage.len() > 0) {
+            if !self.upload(&buffer) {
+                self.hide();
+                return false;
+            }
+            self.owner = owner;
+            self.visible = false;
+        }
+
+        if !self.visible {
+            let handle = unsafe { gbm_bo_get_handle(self.bo) } as u32;
+            let result = unsafe {
+                drmModeSetCursor(self.fd,
+                                 self.crtc_id,
+                                 handle,
+                                 self.size.width as u32,
+                                 self.size.height as u32)
+            };
+            if result != 0 {
+                log_warn2!("Failed to set hardware cursor (crtc_id: {}); falling back to software \
+                            cursor",
+                           self.crtc_id);
+                self.supported = false;
+                return false;
+            }
+            self.visible = true;
+        }
+
+        let position = Position::new(pointer.pos.x - surface.offset.x - origin.x,
+                                     pointer.pos.y - surface.offset.y - origin.y);
+        if position != self.position {
+            unsafe {
+                drmModeMoveCursor(self.fd,
+                                  self.crtc_id,
+                                  position.x as libc::c_int,
+                                  position.y as libc::c_int)
+            };
+            self.position = position;
+        }
+        true
+    }
+
+    /// Hides the cursor.
+    pub fn hide(&mut self) {
+        if self.visible {
+            unsafe { drmModeSetCursor(self.fd, self.crtc_id, 0, 0, 0) };
+            self.visible = false;
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Private methods.
+impl HwCursor {
+    /// Queries DRM capability describing cursor size.
+    fn get_cap(fd: RawFd, capability: u64) -> u64 {
+        let mut value = 0;
+        let result = unsafe { drmGetCap(fd, capability, &mut value) };
+        if (result == 0) && (value > 0) {
+            value
+        } else {
+            DEFAULT_CURSOR_SIZE
+        }
+    }
+
+    /// Copies contents of buffer to cursor buffer object padding it with transparent pixels. Only
+    /// buffers with 32-bit pixels can be copied without conversion.
+    fn upload(&self, buffer: &MemoryView) -> bool {
+        if buffer.get_format().get_bytes_per_pixel() != BYTES_PER_PIXEL {
+            return false;
+        }
+
+        let row_len = self.size.width * BYTES_PER_PIXEL;
+        let src_row_len = buffer.get_width() * BYTES_PER_PIXEL;
+        let src_stride = buffer.get_stride();
+        let mut data = vec![0u8; row_len * self.size.height];
+        for (y, row) in buffer.as_slice().chunks(src_stride).enumerate() {
+            let start = y * row_len;
+            data[start..(start + src_row_len)].copy_from_slice(&row[..src_row_len]);
+        }
+
+        let result = unsafe {
+            gbm_bo_write(self.bo, data.as_ptr() as *const libc::c_void, data.len())
+        };
+        result == 0
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Drop for HwCursor {
+    fn drop(&mut self) {
+        self.hide();
+        unsafe { gbm_bo_destroy(self.bo) };
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
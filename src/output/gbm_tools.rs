@@ -34,23 +34,28 @@ impl GbmBucket {
         };
 
         // Create surface
-        let surface = if let Some(surface) = libgbm::Surface::new(&device,
-                                                                  size.width as u32,
-                                                                  size.height as u32,
-                                                                  libgbm::format::XRGB8888,
-                                                                  libgbm::USE_SCANOUT |
-                                                                  libgbm::USE_RENDERING) {
-            surface
-        } else {
-            return Err(Illusion::General(format!("Failed to create GBM surface")));
-        };
-
+        let surface = Self::create_surface(&device, size)?;
 
         Ok(GbmBucket {
                device: device,
                surface: surface,
            })
     }
+
+    /// Creates GBM surface of given size suitable for scanout and rendering.
+    pub fn create_surface(device: &libgbm::Device,
+                          size: Size)
+                          -> Result<libgbm::Surface, Illusion> {
+        if let Some(surface) = libgbm::Surface::new(device,
+                                                    size.width as u32,
+                                                    size.height as u32,
+                                                    libgbm::format::XRGB8888,
+                                                    libgbm::USE_SCANOUT | libgbm::USE_RENDERING) {
+            Ok(surface)
+        } else {
+            Err(Illusion::General(format!("Failed to create GBM surface")))
+        }
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
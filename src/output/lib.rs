@@ -11,13 +11,22 @@ extern crate egl;
 extern crate drm as libdrm;
 extern crate gbm_rs as libgbm;
 
+#[macro_use(timber)]
+extern crate timber;
+#[macro_use]
 extern crate qualia;
 extern crate dharma;
 extern crate renderer_gl;
 
+pub mod atomic;
+pub mod cursor;
 pub mod gbm_tools;
 pub mod output;
+pub mod screencast;
+pub mod synthetic;
 
+pub use atomic::PlaneAssignment;
 pub use output::Output;
+pub use synthetic::SyntheticOutput;
 
 // -------------------------------------------------------------------------------------------------
@@ -12,9 +12,13 @@ use std::collections::HashMap;
 use std::collections::VecDeque;
 
 use qualia::{Coordinator, DrmBundle, Illusion, Area, OutputInfo, Position, Size, SurfaceContext};
+use qualia::{Buffer, ColorDescription, OutputMode, Transform, Vector, WallpaperMode};
 use renderer_gl::{egl_tools, RendererGl};
 
+use atomic::{self, AtomicModesetter, PlaneAssignment};
+use cursor::HwCursor;
 use gbm_tools::GbmBucket;
+use screencast::Screencast;
 
 // -------------------------------------------------------------------------------------------------
 
@@ -27,9 +31,18 @@ pub struct Output {
     /// Size of the output in pixels.
     size: Size,
 
+    /// Position of the output in global coordinates.
+    position: Position,
+
     /// Size of the output in millimeters.
     physical_size: Size,
 
+    /// Number of pixels per logical unit of global coordinates. May be fractional.
+    scale: f32,
+
+    /// Transform of the output advertised to clients.
+    transform: Transform,
+
     /// Id of the output. Guarantied to be unique in application.
     id: i32,
 
@@ -39,6 +52,11 @@ pub struct Output {
     /// Map from Buffer Object handle to Framebuffer id.
     buffers: HashMap<u32, u32>,
 
+    /// Hardware cursor. `None` if cursor plane is not available.
+    ///
+    /// NOTE: Cursor buffer is allocated from GBM device so cursor must be dropped before `gbm`.
+    cursor: Option<HwCursor>,
+
     /// Collection of GBM-related data.
     gbm: GbmBucket,
 
@@ -48,6 +66,9 @@ pub struct Output {
     /// DRM mode.
     mode: drm_mode::ModeInfo,
 
+    /// DRM modes supported by the connector.
+    modes: Vec<drm_mode::ModeInfo>,
+
     /// Renderer.
     renderer: RendererGl,
 
@@ -59,21 +80,56 @@ pub struct Output {
 
     /// Current framebuffer id.
     fb: u32,
+
+    /// Atomic modesetting support. `None` if driver does not support atomic API in which case
+    /// legacy API is used.
+    atomic: Option<AtomicModesetter>,
+
+    /// Overlay planes to be displayed with next frame.
+    planes: Vec<PlaneAssignment>,
+
+    /// Capture of contents of the output. `None` if output is not captured.
+    screencast: Option<Screencast>,
+
+    /// Tells if whole frame should be captured when the output is drawn next time.
+    capture_requested: bool,
+
+    /// Frame captured on request. `None` if not requested or already taken.
+    captured_frame: Option<Buffer>,
+
+    /// Tells if the display is powered on.
+    powered: bool,
 }
 
 // -------------------------------------------------------------------------------------------------
 
 impl Output {
-    /// `Output` constructor.
-    pub fn new(drm: DrmBundle, id: i32) -> Result<Self, Illusion> {
+    /// `Output` constructor. If `requested_mode` is given first mode matching it is chosen. If it
+    /// is not given or no such mode exists first mode is used.
+    pub fn new(drm: DrmBundle,
+               id: i32,
+               requested_mode: Option<OutputMode>)
+               -> Result<Self, Illusion> {
         // Get size
         let mode;
+        let mode_index;
         let size;
         let modes;
         let physical_size;
         if let Some(connector) = drm_mode::get_connector(drm.fd, drm.connector_id) {
             modes = connector.get_modes();
-            mode = modes.get(0).unwrap().clone();
+            if modes.is_empty() {
+                return Err(Illusion::General(format!("Connector has no modes")));
+            }
+            let chosen = requested_mode.and_then(|requested_mode| {
+                let chosen = Self::find_mode(&modes, &requested_mode);
+                if chosen.is_none() {
+                    log_warn2!("Mode {} not available", requested_mode);
+                }
+                chosen
+            });
+            mode_index = chosen.unwrap_or(0);
+            mode = modes[mode_index].clone();
             size = Size::new(mode.get_hdisplay() as usize, mode.get_vdisplay() as usize);
             physical_size = Size::new(connector.get_mm_width() as usize,
                                       connector.get_mm_height() as usize);
@@ -88,6 +144,28 @@ impl Output {
         let egl = egl_tools::EglBucket::new(gbm.device.c_struct() as *mut _,
                                             gbm.surface.c_struct() as *mut _)?;
 
+        // Atomic modesetting
+        let area = Area::new(Position::new(0, 0), size.clone());
+        let atomic = match AtomicModesetter::new(drm.fd,
+                                                 drm.crtc_id,
+                                                 drm.connector_id,
+                                                 mode_index,
+                                                 area) {
+            Ok(atomic) => {
+                if atomic.supports_hdr() {
+                    log_info2!("Connector {} supports HDR metadata", drm.connector_id);
+                }
+                Some(atomic)
+            }
+            Err(err) => {
+                log_warn2!("{}; falling back to legacy modesetting", err);
+                None
+            }
+        };
+
+        // Hardware cursor
+        let cursor = HwCursor::new(gbm.device.c_struct() as *mut _, drm.fd, drm.crtc_id);
+
         // Create renderer
         let renderer = RendererGl::new(egl, size.clone());
 
@@ -95,15 +173,26 @@ impl Output {
         let mut mine = Output {
             id: id,
             size: size,
+            position: Position::default(),
             physical_size: physical_size,
-            name: "".to_owned(),
+            scale: 1.0,
+            transform: Transform::Normal,
+            name: Self::get_connector_name(&drm),
             renderer: renderer,
             mode: mode,
+            modes: modes,
             drm: drm,
+            cursor: cursor,
             gbm: gbm,
             buffers: HashMap::new(),
             bo: VecDeque::with_capacity(1),
             fb: INVALID_FRAMEBUFFER,
+            atomic: atomic,
+            planes: Vec::new(),
+            screencast: None,
+            capture_requested: false,
+            captured_frame: None,
+            powered: true,
         };
 
         // Initialize renderer
@@ -119,12 +208,267 @@ impl Output {
 // Public methods
 impl Output {
     /// Draw passed scene using renderer.
+    ///
+    /// Pointer is displayed on cursor plane if possible. Otherwise it is drawn by renderer.
+    ///
+    /// Positions of surfaces are given in global coordinates and are translated to coordinates of
+    /// the output before passing them to renderer. Renderer scales them to pixels. Cursor plane is
+    /// not used on scaled outputs.
+    ///
+    /// If output is captured and capture is due, the scene is drawn also to capture target. The
+    /// same applies to frames captured on request. Pointer is always drawn in captured frames.
     pub fn draw(&mut self,
                 surfaces: &Vec<SurfaceContext>,
                 pointer: SurfaceContext,
                 coordinator: &Coordinator)
                 -> Result<(), Illusion> {
-        self.renderer.draw(surfaces, pointer, coordinator)
+        let origin = self.position;
+        let on_plane = if let Some(ref mut cursor) = self.cursor {
+            if self.scale == 1.0 && self.transform == Transform::Normal {
+                cursor.update(&pointer, origin, coordinator)
+            } else {
+                cursor.hide();
+                false
+            }
+        } else {
+            false
+        };
+
+        let offset = Vector::new(-origin.x, -origin.y);
+        let surfaces: Vec<SurfaceContext> =
+            surfaces.iter().map(|context| context.moved(offset)).collect();
+        let pointer = pointer.moved(offset);
+        let drawn_pointer = if on_plane { None } else { Some(pointer.clone()) };
+        self.renderer.draw(&surfaces, drawn_pointer, coordinator)?;
+
+        if let Some(ref mut screencast) = self.screencast {
+            if screencast.is_due() {
+                let frame = {
+                    let target = screencast.get_target();
+                    let pointer = Some(pointer.clone());
+                    self.renderer.draw_to_target(target, &surfaces, pointer, coordinator)?;
+                    self.renderer.read_target_pixels(target)?
+                };
+                screencast.set_frame(frame);
+            }
+        }
+
+        if self.capture_requested {
+            self.capture_requested = false;
+            let frame = self.renderer.capture(&surfaces, Some(pointer), coordinator)?;
+            self.captured_frame = Some(frame);
+        }
+        Ok(())
+    }
+
+    /// Requests capturing whole frame in size of the output when it is drawn next time.
+    pub fn request_capture(&mut self) {
+        self.capture_requested = true;
+    }
+
+    /// Returns frame captured on request if it was not taken yet.
+    pub fn take_captured_frame(&mut self) -> Option<Buffer> {
+        self.captured_frame.take()
+    }
+
+    /// Starts capturing contents of the output scaled to given size at most `rate` times per
+    /// second. Capture with other settings is stopped.
+    pub fn start_screencast(&mut self, size: Size, rate: u32) -> Result<(), Illusion> {
+        if let Some(ref screencast) = self.screencast {
+            if screencast.has_settings(size, rate) {
+                return Ok(());
+            }
+        }
+
+        self.stop_screencast();
+        let target = self.renderer.create_render_target(size)?;
+        self.screencast = Some(Screencast::new(target, rate));
+        Ok(())
+    }
+
+    /// Stops capturing contents of the output.
+    pub fn stop_screencast(&mut self) {
+        if let Some(screencast) = self.screencast.take() {
+            if let Err(err) = self.renderer.destroy_render_target(screencast.into_target()) {
+                log_warn2!("Failed to destroy screencast target: {}", err);
+            }
+        }
+    }
+
+    /// Returns last captured frame if it was not taken yet.
+    pub fn take_screencast_frame(&mut self) -> Option<Buffer> {
+        self.screencast.as_mut().and_then(|screencast| screencast.take_frame())
+    }
+
+    /// Sets position of the output in global coordinates.
+    pub fn set_position(&mut self, position: Position) {
+        self.position = position;
+    }
+
+    /// Sets number of pixels per logical unit. Area of the output in global coordinates shrinks
+    /// accordingly.
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = scale;
+        self.renderer.set_scale(scale);
+    }
+
+    /// Sets rotation and flipping of the output. Width and height of area of the output in global
+    /// coordinates are swapped if the output is rotated by 90 or 270 degrees.
+    pub fn set_transform(&mut self, transform: Transform) {
+        self.transform = transform;
+        self.renderer.set_transform(transform);
+    }
+
+    /// Returns IDs of overlay planes which can be assigned to this output. Empty if atomic
+    /// modesetting is not supported.
+    pub fn get_overlay_planes(&self) -> Vec<u32> {
+        if let Some(ref atomic) = self.atomic {
+            atomic.get_overlay_planes()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Checks if HDR contents can be signalled to the display. Requires atomic modesetting.
+    pub fn supports_hdr(&self) -> bool {
+        self.atomic.as_ref().map_or(false, |atomic| atomic.supports_hdr())
+    }
+
+    /// Sets description of colors of displayed contents signalled to the display. Meant for
+    /// surfaces with HDR contents scanned out directly; contents drawn by renderer are sRGB.
+    /// Display is set up again with the next frame if the description changed.
+    pub fn set_color_description(&mut self,
+                                 description: ColorDescription)
+                                 -> Result<(), Illusion> {
+        if let Some(ref mut atomic) = self.atomic {
+            atomic.set_color_description(description)
+        } else if description.is_hdr() {
+            Err(Illusion::General(format!("HDR requires atomic modesetting")))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Sets overlay planes to be displayed starting from next frame. Assignment is tested first
+    /// and if hardware can not display it, it is rejected and `false` is returned.
+    pub fn assign_planes(&mut self, planes: Vec<PlaneAssignment>) -> bool {
+        let accepted = if let Some(ref atomic) = self.atomic {
+            (planes.len() == 0) || atomic.test(self.fb, &planes)
+        } else {
+            planes.len() == 0
+        };
+
+        if accepted {
+            self.planes = planes;
+        }
+        accepted
+    }
+
+    /// Enables or disables tinting of regions repainted in last frames.
+    pub fn set_damage_visualization(&mut self, enabled: bool) {
+        self.renderer.set_damage_visualization(enabled);
+    }
+
+    /// Sets region being interactively selected (relative to the output) or ends selection.
+    pub fn set_selection(&mut self, selection: Option<Area>) {
+        self.renderer.set_selection(selection);
+    }
+
+    /// Enables or disables blending colors in linear space.
+    pub fn set_linear_blending(&mut self, enabled: bool) {
+        self.renderer.set_linear_blending(enabled);
+    }
+
+    /// Sets image drawn as background. `None` removes the image.
+    pub fn set_wallpaper(&mut self,
+                         image: Option<&Buffer>,
+                         mode: WallpaperMode)
+                         -> Result<(), Illusion> {
+        self.renderer.set_wallpaper(image, mode)
+    }
+
+    /// Powers the display on or off. Page flips must not be scheduled while display is off.
+    ///
+    /// With atomic modesetting display is powered on by next page flip so new frame has to be
+    /// drawn.
+    pub fn set_power(&mut self, on: bool) -> Result<(), Illusion> {
+        if self.powered != on {
+            if let Some(ref mut atomic) = self.atomic {
+                if !on {
+                    atomic.deactivate()?;
+                }
+            } else {
+                atomic::set_dpms(self.drm.fd, self.drm.connector_id, on)?;
+            }
+            self.powered = on;
+        }
+        Ok(())
+    }
+
+    /// Switches the output to the first of its modes matching `requested` one. Nothing is done if
+    /// current mode matches. New mode is set when next frame is swapped so the output has to be
+    /// redrawn.
+    pub fn set_mode(&mut self, requested: &OutputMode) -> Result<(), Illusion> {
+        if Self::to_output_mode(&self.mode).matches(requested) {
+            return Ok(());
+        }
+
+        let index = if let Some(index) = Self::find_mode(&self.modes, requested) {
+            index
+        } else {
+            return Err(Illusion::General(format!("Mode {} not available", requested)));
+        };
+        let mode = self.modes[index].clone();
+        let size = Size::new(mode.get_hdisplay() as usize, mode.get_vdisplay() as usize);
+
+        // EGL surface is replaced before GBM surface it was created for is dropped
+        let surface = GbmBucket::create_surface(&self.gbm.device, size)?;
+        self.renderer.resize(surface.c_struct() as *mut _, size)?;
+        if let Some(bo) = self.bo.pop_front() {
+            self.gbm.surface.release_buffer(bo);
+        }
+        self.gbm.surface = surface;
+
+        // Framebuffers of old size can not be displayed any more
+        for (_, fb) in self.buffers.drain() {
+            if let Err(err) = atomic::remove_framebuffer(self.drm.fd, fb) {
+                log_warn2!("{}", err);
+            }
+        }
+        self.fb = INVALID_FRAMEBUFFER;
+        self.planes.clear();
+
+        if let Some(ref mut atomic) = self.atomic {
+            atomic.set_mode(index, Area::new(Position::new(0, 0), size))?;
+        }
+        self.mode = mode;
+        self.size = size;
+        Ok(())
+    }
+
+    /// Sets mode of the output again after it was changed by other DRM master (e.g. after
+    /// switching virtual terminals). With atomic modesetting mode is set by next page flip so new
+    /// frame has to be drawn.
+    pub fn restore_mode(&mut self) -> Result<(), Illusion> {
+        if let Some(ref mut atomic) = self.atomic {
+            atomic.request_modeset();
+            return Ok(());
+        }
+
+        if self.fb == INVALID_FRAMEBUFFER {
+            return Ok(());
+        }
+
+        match drm_mode::set_crtc(self.drm.fd,
+                                 self.drm.crtc_id,
+                                 self.fb,
+                                 0,
+                                 0,
+                                 &[self.drm.connector_id],
+                                 &self.mode) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(Illusion::General(format!("Failed to restore CRTC"))),
+        }
     }
 }
 
@@ -137,10 +481,22 @@ impl Output {
         self.size.clone()
     }
 
-    /// Return area of the output in global coordinates.
+    /// Return area of the output in global coordinates. Size of the area is the size of the
+    /// output in logical units in orientation seen by user.
     pub fn get_area(&self) -> Area {
-        // TODO: Make Output aware of its position.
-        Area::new(Position::new(0, 0), self.size.clone())
+        let size = if self.transform.is_transposing() {
+            Size::new(self.size.height, self.size.width)
+        } else {
+            self.size
+        };
+        let size = Size::new((size.width as f32 / self.scale).round() as usize,
+                             (size.height as f32 / self.scale).round() as usize);
+        Area::new(self.position, size)
+    }
+
+    /// Checks if the display is powered on.
+    pub fn is_powered(&self) -> bool {
+        self.powered
     }
 
     /// Get name of the output. This name should uniquely identify output.
@@ -150,11 +506,27 @@ impl Output {
 
     /// Get info about output.
     pub fn get_info(&self) -> OutputInfo {
-        OutputInfo::new(self.get_area(),
-                        self.physical_size,
-                        60, // TODO: make output aware of its refresh rate.
-                        self.get_name(),
-                        self.get_name())
+        let mut info = OutputInfo::new(self.id,
+                                       self.get_area(),
+                                       self.physical_size,
+                                       self.mode.get_vrefresh() as usize,
+                                       self.get_name(),
+                                       self.get_name());
+        info.drm_device = Some(self.drm.path.clone());
+        info.scale = self.scale;
+        info.pixel_size = self.size;
+        info.transform = self.transform;
+        for mode in self.modes.iter().map(Self::to_output_mode) {
+            if !info.modes.contains(&mode) {
+                info.modes.push(mode);
+            }
+        }
+        info
+    }
+
+    /// Returns name of output which will be created for given DRM bundle.
+    pub fn get_connector_name(drm: &DrmBundle) -> String {
+        format!("connector-{}", drm.connector_id)
     }
 }
 
@@ -162,6 +534,17 @@ impl Output {
 
 // Private methods
 impl Output {
+    /// Returns index of the first of given DRM modes matching `requested` mode.
+    fn find_mode(modes: &[drm_mode::ModeInfo], requested: &OutputMode) -> Option<usize> {
+        modes.iter().position(|mode| Self::to_output_mode(mode).matches(requested))
+    }
+
+    /// Converts DRM mode to `OutputMode`.
+    fn to_output_mode(mode: &drm_mode::ModeInfo) -> OutputMode {
+        OutputMode::new(Size::new(mode.get_hdisplay() as usize, mode.get_vdisplay() as usize),
+                        mode.get_vrefresh() as usize)
+    }
+
     /// Swap device buffers.
     /// Create buffer if necessary.
     fn swap_gbm_buffers(&mut self) -> Result<u32, Illusion> {
@@ -181,6 +564,13 @@ impl Output {
             } else {
                 match drm_mode::add_fb(self.drm.fd, width, height, 24, 32, stride, handle) {
                     Ok(fb) => {
+                        if let Some(ref mut atomic) = self.atomic {
+                            atomic.modeset(fb)?;
+                            self.buffers.insert(handle, fb);
+                            self.fb = fb;
+                            return Ok(fb);
+                        }
+
                         match drm_mode::set_crtc(self.drm.fd,
                                                  self.drm.crtc_id,
                                                  fb,
@@ -211,7 +601,14 @@ impl Output {
     }
 
     /// Schedule pageflip. Handler is registers by DeviceManager.
-    pub fn schedule_pageflip(&self) -> Result<(), Illusion> {
+    ///
+    /// With atomic modesetting current framebuffer and assigned overlay planes are presented in
+    /// single commit.
+    pub fn schedule_pageflip(&mut self) -> Result<(), Illusion> {
+        if let Some(ref mut atomic) = self.atomic {
+            return atomic.commit(self.fb, &self.planes, self.id);
+        }
+
         match drm_mode::page_flip(self.drm.fd,
                                   self.drm.crtc_id,
                                   self.fb,
@@ -0,0 +1,110 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains functionality for capturing contents of output to memory.
+//!
+//! Captured scene is drawn to off-screen render target so frames may have resolution and rate
+//! different from the output (e.g. 1080p at 30 frames per second for 4k display running at 144Hz).
+//! Frames are captured only when output is redrawn.
+
+// -------------------------------------------------------------------------------------------------
+
+use std::time::{Duration, Instant};
+
+use qualia::{Buffer, Size};
+use renderer_gl::RenderTarget;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Decides which of drawn frames should be captured to not exceed given frame rate.
+///
+/// Frames are scheduled at fixed intervals, so the rate is kept on average even if it is not a
+/// divisor of rate of the output. If drawing stalls schedule is restarted.
+pub struct FrameThrottle {
+    interval: Duration,
+    next: Option<Instant>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl FrameThrottle {
+    /// Constructs new `FrameThrottle` for given number of frames per second.
+    pub fn new(rate: u32) -> Self {
+        FrameThrottle {
+            interval: Duration::new(0, 1_000_000_000 / rate),
+            next: None,
+        }
+    }
+
+    /// Checks if frame drawn at given time should be captured.
+    pub fn is_due(&mut self, now: Instant) -> bool {
+        match self.next {
+            Some(next) if now < next => false,
+            Some(next) => {
+                let following = next + self.interval;
+                self.next = Some(if following > now { following } else { now + self.interval });
+                true
+            }
+            None => {
+                self.next = Some(now + self.interval);
+                true
+            }
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// State of capturing of one output.
+pub struct Screencast {
+    target: RenderTarget,
+    rate: u32,
+    throttle: FrameThrottle,
+    frame: Option<Buffer>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Screencast {
+    /// Constructs new `Screencast` drawing to given target at most `rate` times per second.
+    pub fn new(target: RenderTarget, rate: u32) -> Self {
+        Screencast {
+            target: target,
+            rate: rate,
+            throttle: FrameThrottle::new(rate),
+            frame: None,
+        }
+    }
+
+    /// Checks if this screencast captures frames of given size at given rate.
+    pub fn has_settings(&self, size: Size, rate: u32) -> bool {
+        (self.target.get_size() == size) && (self.rate == rate)
+    }
+
+    /// Checks if frame drawn now should be captured.
+    pub fn is_due(&mut self) -> bool {
+        self.throttle.is_due(Instant::now())
+    }
+
+    /// Returns target captured frames are drawn to.
+    pub fn get_target(&self) -> &RenderTarget {
+        &self.target
+    }
+
+    /// Stores captured frame replacing not yet taken one.
+    pub fn set_frame(&mut self, frame: Buffer) {
+        self.frame = Some(frame);
+    }
+
+    /// Returns last captured frame if it was not taken yet.
+    pub fn take_frame(&mut self) -> Option<Buffer> {
+        self.frame.take()
+    }
+
+    /// Destroys the screencast returning its render target.
+    pub fn into_target(self) -> RenderTarget {
+        self.target
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
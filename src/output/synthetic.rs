@@ -0,0 +1,179 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains synthetic output used for automated pixel tests.
+//!
+//! `SyntheticOutput` renders with the same renderer as physical outputs but to off-screen pixel
+//! buffer and copies every frame to memory, so rendered frames can be compared with golden images
+//! on machines without GPU or display.
+
+// -------------------------------------------------------------------------------------------------
+
+use qualia::{Area, Buffer, Coordinator, Illusion, OutputInfo, Pixmap, Position, Size};
+use qualia::{SurfaceContext, Transform};
+use renderer_gl::{egl_tools, RendererGl};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Offset basis of 64-bit FNV-1a hash.
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+
+/// Prime of 64-bit FNV-1a hash.
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// Number of bytes per pixel of frames.
+const BYTES_PER_PIXEL: usize = 4;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Output rendering to memory instead of physical device. Intended for tests.
+pub struct SyntheticOutput {
+    /// Size of the output in pixels.
+    size: Size,
+
+    /// Number of pixels per logical unit.
+    scale: f32,
+
+    /// Rotation and flipping of the output.
+    transform: Transform,
+
+    /// Id of the output.
+    id: i32,
+
+    /// Renderer.
+    renderer: RendererGl,
+
+    /// Last rendered frame.
+    frame: Buffer,
+
+    /// Number of frames rendered so far.
+    frame_count: u32,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl SyntheticOutput {
+    /// `SyntheticOutput` constructor.
+    pub fn new(size: Size, id: i32) -> Result<Self, Illusion> {
+        let egl = egl_tools::EglBucket::new_offscreen(size)?;
+        let mut mine = SyntheticOutput {
+            size: size,
+            scale: 1.0,
+            transform: Transform::Normal,
+            id: id,
+            renderer: RendererGl::new(egl, size),
+            frame: Buffer::empty(),
+            frame_count: 0,
+        };
+        mine.renderer.initialize()?;
+        Ok(mine)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Public methods
+impl SyntheticOutput {
+    /// Draw passed scene and copy it to memory.
+    pub fn draw(&mut self,
+                surfaces: &Vec<SurfaceContext>,
+                pointer: Option<SurfaceContext>,
+                coordinator: &Coordinator)
+                -> Result<(), Illusion> {
+        self.renderer.draw(surfaces, pointer, coordinator)?;
+        self.frame = self.renderer.read_pixels()?;
+        self.renderer.swap_buffers()?;
+        self.frame_count += 1;
+        Ok(())
+    }
+
+    /// Sets number of pixels per logical unit. Positions and sizes of drawn surfaces are then
+    /// multiplied by the scale.
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = scale;
+        self.renderer.set_scale(scale);
+    }
+
+    /// Sets rotation and flipping of the output. Scene is given in orientation seen by user.
+    pub fn set_transform(&mut self, transform: Transform) {
+        self.transform = transform;
+        self.renderer.set_transform(transform);
+    }
+
+    /// Returns last rendered frame. Rows are ordered from top to bottom and pixels are in RGBA
+    /// format.
+    pub fn get_frame(&self) -> &Buffer {
+        &self.frame
+    }
+
+    /// Returns number of frames rendered so far.
+    pub fn get_frame_count(&self) -> u32 {
+        self.frame_count
+    }
+
+    /// Returns color of pixel at given position in last rendered frame as RGBA or `None` if
+    /// position is outside of the output or nothing was rendered yet.
+    pub fn get_pixel(&self, pos: Position) -> Option<[u8; 4]> {
+        let area = Area::new(Position::default(), self.frame.get_size());
+        if area.contains(&pos) {
+            let offset = pos.y as usize * self.frame.get_width() + pos.x as usize;
+            let index = BYTES_PER_PIXEL * offset;
+            let data = self.frame.as_slice();
+            Some([data[index], data[index + 1], data[index + 2], data[index + 3]])
+        } else {
+            None
+        }
+    }
+
+    /// Returns checksum of last rendered frame. Uses 64-bit FNV-1a hash so checksums are stable
+    /// between builds and can be stored along with tests.
+    pub fn get_checksum(&self) -> u64 {
+        self.frame.as_slice().iter().fold(FNV_OFFSET_BASIS, |hash, byte| {
+            (hash ^ (*byte as u64)).wrapping_mul(FNV_PRIME)
+        })
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Getters
+impl SyntheticOutput {
+    /// Get size of the output in pixels.
+    pub fn get_size(&self) -> Size {
+        self.size
+    }
+
+    /// Return area of the output in global coordinates. Size of the area is the size of the
+    /// output in logical units in orientation seen by user.
+    pub fn get_area(&self) -> Area {
+        let size = if self.transform.is_transposing() {
+            Size::new(self.size.height, self.size.width)
+        } else {
+            self.size
+        };
+        let size = Size::new((size.width as f32 / self.scale).round() as usize,
+                             (size.height as f32 / self.scale).round() as usize);
+        Area::new(Position::new(0, 0), size)
+    }
+
+    /// Get name of the output.
+    pub fn get_name(&self) -> String {
+        format!("synthetic-{}", self.id)
+    }
+
+    /// Get info about output.
+    pub fn get_info(&self) -> OutputInfo {
+        let mut info = OutputInfo::new(self.id,
+                                       self.get_area(),
+                                       Size::new(0, 0),
+                                       60,
+                                       self.get_name(),
+                                       self.get_name());
+        info.scale = self.scale;
+        info.pixel_size = self.size;
+        info.transform = self.transform;
+        info
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
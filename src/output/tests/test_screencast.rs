@@ -0,0 +1,52 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Tests for throttling of captured frames.
+
+// -------------------------------------------------------------------------------------------------
+
+extern crate output;
+
+use std::time::{Duration, Instant};
+
+use output::screencast::FrameThrottle;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if first drawn frame is always captured.
+#[test]
+fn should_capture_first_frame() {
+    let mut throttle = FrameThrottle::new(1);
+    assert!(throttle.is_due(Instant::now()));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if frames drawn at higher rate than requested are captured at requested rate.
+#[test]
+fn should_keep_requested_rate() {
+    let mut throttle = FrameThrottle::new(30);
+    let start = Instant::now();
+
+    // Simulate one second of output running at about 144Hz.
+    let captured = (0..144)
+        .filter(|i| throttle.is_due(start + Duration::new(0, i * 6_944_444)))
+        .count();
+    assert_eq!(captured, 30);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if schedule is restarted after drawing stalls instead of capturing burst of frames.
+#[test]
+fn should_restart_schedule_after_stall() {
+    let mut throttle = FrameThrottle::new(10);
+    let start = Instant::now();
+
+    assert!(throttle.is_due(start));
+    assert!(throttle.is_due(start + Duration::new(5, 0)));
+    assert!(!throttle.is_due(start + Duration::new(5, 50_000_000)));
+    assert!(throttle.is_due(start + Duration::new(5, 100_000_000)));
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,229 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Pixel tests of renderer using synthetic output.
+//!
+//! These tests require EGL implementation supporting pixel buffers. Software rasterizer (e.g. Mesa
+//! llvmpipe) is sufficient.
+
+// -------------------------------------------------------------------------------------------------
+
+extern crate dharma;
+extern crate qualia;
+extern crate output;
+
+use qualia::{AlphaMode, Buffer, Coordinator, Position, Size, SurfaceContext, SurfaceId};
+use qualia::{PixelFormat, Transform};
+use output::SyntheticOutput;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Size of the output used in tests.
+const OUTPUT_SIZE: Size = Size {
+    width: 32,
+    height: 24,
+};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Creates surface filled with single color. `bgra` is color in format used by clients.
+fn create_surface(coordinator: &mut Coordinator, size: Size, bgra: [u8; 4]) -> SurfaceId {
+    create_surface_with_format(coordinator, size, PixelFormat::Argb8888, &bgra)
+}
+
+/// Creates surface with pixels of given format filled with single color. `pixel` is the color
+/// encoded in that format. Formats without alpha channel are opaque.
+fn create_surface_with_format(coordinator: &mut Coordinator,
+                              size: Size,
+                              format: PixelFormat,
+                              pixel: &[u8])
+                              -> SurfaceId {
+    let alpha_mode = match format {
+        PixelFormat::Argb8888 => AlphaMode::Straight,
+        _ => AlphaMode::Opaque,
+    };
+    let stride = format.get_bytes_per_pixel() * size.width;
+    let mut data = Vec::with_capacity(stride * size.height);
+    for _ in 0..(size.width * size.height) {
+        data.extend_from_slice(pixel);
+    }
+
+    let mpid = coordinator.create_pool_from_buffer(Buffer::new(size.width,
+                                                               size.height,
+                                                               stride,
+                                                               data));
+    let mvid = coordinator.create_memory_view(mpid,
+                                              0,
+                                              size.width,
+                                              size.height,
+                                              stride,
+                                              format,
+                                              alpha_mode)
+        .unwrap();
+    let sid = coordinator.create_surface();
+    coordinator.attach(mvid, sid);
+    coordinator.commit_surface(sid);
+    sid
+}
+
+/// Checks if colors are equal allowing for rounding errors.
+fn assert_color(actual: Option<[u8; 4]>, expected: [u8; 4]) {
+    let actual = actual.expect("pixel should be inside frame");
+    for (a, e) in actual.iter().zip(expected.iter()) {
+        assert!((*a as i32 - *e as i32).abs() <= 1,
+                "Color {:?} differs from {:?}",
+                actual,
+                expected);
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if empty scene is filled with background color.
+#[test]
+fn should_draw_background_on_empty_scene() {
+    let coordinator = Coordinator::new(dharma::Signaler::new());
+    let mut output = SyntheticOutput::new(OUTPUT_SIZE, 1).unwrap();
+
+    output.draw(&Vec::new(), None, &coordinator).unwrap();
+
+    assert_eq!(output.get_frame_count(), 1);
+    assert_color(output.get_pixel(Position::new(0, 0)), [0, 77, 128, 255]);
+    assert_color(output.get_pixel(Position::new(31, 23)), [0, 77, 128, 255]);
+    assert!(output.get_pixel(Position::new(32, 24)).is_none());
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if surface is drawn in correct place with correct colors.
+#[test]
+fn should_draw_surface_in_place() {
+    let mut coordinator = Coordinator::new(dharma::Signaler::new());
+    let sid = create_surface(&mut coordinator, Size::new(4, 2), [0, 0, 255, 255]);
+    let mut output = SyntheticOutput::new(OUTPUT_SIZE, 1).unwrap();
+
+    let surfaces = vec![SurfaceContext::new(sid, Position::new(10, 5))];
+    output.draw(&surfaces, None, &coordinator).unwrap();
+
+    assert_color(output.get_pixel(Position::new(10, 5)), [255, 0, 0, 255]);
+    assert_color(output.get_pixel(Position::new(13, 6)), [255, 0, 0, 255]);
+    assert_color(output.get_pixel(Position::new(9, 5)), [0, 77, 128, 255]);
+    assert_color(output.get_pixel(Position::new(14, 5)), [0, 77, 128, 255]);
+    assert_color(output.get_pixel(Position::new(10, 7)), [0, 77, 128, 255]);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if checksums are stable for the same scene and differ for different scenes.
+#[test]
+fn should_produce_stable_checksums() {
+    let mut coordinator = Coordinator::new(dharma::Signaler::new());
+    let sid = create_surface(&mut coordinator, Size::new(3, 3), [0, 255, 0, 255]);
+    let mut output = SyntheticOutput::new(OUTPUT_SIZE, 1).unwrap();
+
+    output.draw(&Vec::new(), None, &coordinator).unwrap();
+    let empty_checksum = output.get_checksum();
+
+    let surfaces = vec![SurfaceContext::new(sid, Position::new(1, 1))];
+    output.draw(&surfaces, None, &coordinator).unwrap();
+    let first_checksum = output.get_checksum();
+    output.draw(&surfaces, None, &coordinator).unwrap();
+    let second_checksum = output.get_checksum();
+
+    assert_eq!(output.get_frame_count(), 3);
+    assert_eq!(first_checksum, second_checksum);
+    assert!(first_checksum != empty_checksum);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if positions and sizes of surfaces are scaled on scaled output.
+#[test]
+fn should_scale_surfaces() {
+    let mut coordinator = Coordinator::new(dharma::Signaler::new());
+    let sid = create_surface(&mut coordinator, Size::new(4, 2), [0, 0, 255, 255]);
+    let mut output = SyntheticOutput::new(OUTPUT_SIZE, 1).unwrap();
+    output.set_scale(2.0);
+    assert_eq!(output.get_area().size, Size::new(16, 12));
+
+    let surfaces = vec![SurfaceContext::new(sid, Position::new(5, 2))];
+    output.draw(&surfaces, None, &coordinator).unwrap();
+
+    assert_color(output.get_pixel(Position::new(10, 4)), [255, 0, 0, 255]);
+    assert_color(output.get_pixel(Position::new(17, 7)), [255, 0, 0, 255]);
+    assert_color(output.get_pixel(Position::new(9, 4)), [0, 77, 128, 255]);
+    assert_color(output.get_pixel(Position::new(18, 4)), [0, 77, 128, 255]);
+    assert_color(output.get_pixel(Position::new(10, 8)), [0, 77, 128, 255]);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if brightness and saturation of dimmed surfaces are reduced.
+#[test]
+fn should_dim_surfaces() {
+    let mut coordinator = Coordinator::new(dharma::Signaler::new());
+    let sid = create_surface(&mut coordinator, Size::new(2, 2), [0, 0, 255, 255]);
+    let mut output = SyntheticOutput::new(OUTPUT_SIZE, 1).unwrap();
+
+    let mut dimmed = SurfaceContext::new(sid, Position::new(4, 0));
+    dimmed.dim = 0.5;
+    let surfaces = vec![SurfaceContext::new(sid, Position::new(0, 0)), dimmed];
+    output.draw(&surfaces, None, &coordinator).unwrap();
+
+    assert_color(output.get_pixel(Position::new(1, 1)), [255, 0, 0, 255]);
+    assert_color(output.get_pixel(Position::new(5, 1)), [77, 14, 14, 255]);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if scene is rotated on rotated output.
+#[test]
+fn should_rotate_surfaces() {
+    let mut coordinator = Coordinator::new(dharma::Signaler::new());
+    let sid = create_surface(&mut coordinator, Size::new(4, 2), [0, 0, 255, 255]);
+    let mut output = SyntheticOutput::new(OUTPUT_SIZE, 1).unwrap();
+    output.set_transform(Transform::Rotated90);
+    assert_eq!(output.get_area().size, Size::new(24, 32));
+
+    let surfaces = vec![SurfaceContext::new(sid, Position::new(5, 2))];
+    output.draw(&surfaces, None, &coordinator).unwrap();
+
+    assert_color(output.get_pixel(Position::new(28, 5)), [255, 0, 0, 255]);
+    assert_color(output.get_pixel(Position::new(29, 8)), [255, 0, 0, 255]);
+    assert_color(output.get_pixel(Position::new(27, 5)), [0, 77, 128, 255]);
+    assert_color(output.get_pixel(Position::new(30, 5)), [0, 77, 128, 255]);
+    assert_color(output.get_pixel(Position::new(28, 9)), [0, 77, 128, 255]);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if colors of surfaces are drawn correctly regardless of pixel format of their buffers.
+#[test]
+fn should_draw_surfaces_of_all_formats() {
+    let mut coordinator = Coordinator::new(dharma::Signaler::new());
+    let size = Size::new(2, 2);
+    let argb = create_surface_with_format(&mut coordinator,
+                                          size,
+                                          PixelFormat::Argb8888,
+                                          &[255, 0, 0, 255]);
+    let xrgb = create_surface_with_format(&mut coordinator,
+                                          size,
+                                          PixelFormat::Xrgb8888,
+                                          &[0, 255, 0, 0]);
+    let rgb565 = create_surface_with_format(&mut coordinator,
+                                            size,
+                                            PixelFormat::Rgb565,
+                                            &[0x00, 0xF8]);
+    let mut output = SyntheticOutput::new(OUTPUT_SIZE, 1).unwrap();
+
+    let surfaces = vec![SurfaceContext::new(argb, Position::new(0, 0)),
+                        SurfaceContext::new(xrgb, Position::new(4, 0)),
+                        SurfaceContext::new(rgb565, Position::new(8, 0))];
+    output.draw(&surfaces, None, &coordinator).unwrap();
+
+    assert_color(output.get_pixel(Position::new(1, 1)), [0, 0, 255, 255]);
+    assert_color(output.get_pixel(Position::new(5, 1)), [0, 255, 0, 255]);
+    assert_color(output.get_pixel(Position::new(9, 1)), [255, 0, 0, 255]);
+}
+
+// -------------------------------------------------------------------------------------------------
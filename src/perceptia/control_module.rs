@@ -0,0 +1,246 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementation of `dharma::Module` serving requests from control socket (see
+//! `qualia::control`).
+//!
+//! Connections are accepted and requests read in dispatcher thread. Screenshot and region
+//! selection requests are passed to Exhibitor and connections kept until the screenshot is taken
+//! or the region selected. Encoding and sending the reply happens in thread of this module.
+
+// -------------------------------------------------------------------------------------------------
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use dharma::{event_kind, EventHandler, EventKind, InitResult, Module, ModuleConstructor, Signaler};
+use qualia::{control, png, Area, Buffer, Context, ControlReply, ControlRequest};
+use qualia::{perceptron, Perceptron};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Time in milliseconds to wait for request after accepting connection. Requests are read in
+/// dispatcher thread so it must be short.
+const READ_TIMEOUT: u64 = 100;
+
+/// Time in milliseconds to wait for client to receive reply.
+const WRITE_TIMEOUT: u64 = 1000;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Connections waiting for reply shared between module and socket handler.
+struct PendingRequests {
+    /// Connections by ID of request.
+    connections: HashMap<u32, UnixStream>,
+
+    /// ID of the last request.
+    last_id: u32,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Handler of control socket accepting connections and reading requests.
+struct ControlHandler {
+    listener: UnixListener,
+    pending: Arc<Mutex<PendingRequests>>,
+    signaler: Signaler<Perceptron>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl ControlHandler {
+    /// Reads request from given connection and passes it further. Invalid requests are replied
+    /// to at once.
+    fn handle_connection(&mut self, stream: UnixStream) {
+        let _ = stream.set_read_timeout(Some(Duration::from_millis(READ_TIMEOUT)));
+        let mut line = String::new();
+        let result = BufReader::new(&stream)
+            .read_line(&mut line)
+            .map_err(|err| err.into())
+            .and_then(|_| ControlRequest::parse(&line));
+
+        match result {
+            Ok(ControlRequest::Screenshot(target)) => {
+                let id = self.add_pending(stream);
+                self.signaler.emit(perceptron::SCREENSHOT_REQUESTED,
+                                   Perceptron::ScreenshotRequested(id, target));
+            }
+            Ok(ControlRequest::SelectRegion) => {
+                let id = self.add_pending(stream);
+                self.signaler.emit(perceptron::REGION_SELECTION_REQUESTED,
+                                   Perceptron::RegionSelectionRequested(id));
+            }
+            Err(err) => send_reply(stream, ControlReply::Error(format!("{}", err))),
+        }
+    }
+
+    /// Keeps connection until reply is ready. Returns ID of the request.
+    fn add_pending(&mut self, stream: UnixStream) -> u32 {
+        let mut pending = self.pending.lock().unwrap();
+        pending.last_id = pending.last_id.wrapping_add(1);
+        let id = pending.last_id;
+        pending.connections.insert(id, stream);
+        id
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl EventHandler for ControlHandler {
+    fn get_fd(&self) -> RawFd {
+        self.listener.as_raw_fd()
+    }
+
+    fn process_event(&mut self, _: EventKind) {
+        match self.listener.accept() {
+            Ok((stream, _)) => self.handle_connection(stream),
+            Err(err) => log_warn2!("Failed to accept control connection: {}", err),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub struct ControlModule {
+    pending: Arc<Mutex<PendingRequests>>,
+    listening: bool,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl ControlModule {
+    /// `ControlModule` constructor.
+    pub fn new() -> Self {
+        ControlModule {
+            pending: Arc::new(Mutex::new(PendingRequests {
+                                             connections: HashMap::new(),
+                                             last_id: 0,
+                                         })),
+            listening: false,
+        }
+    }
+
+    /// Creates control socket. Socket left by previous instance is replaced unless the instance
+    /// still listens on it.
+    fn listen() -> Option<UnixListener> {
+        let path = control::get_socket_path();
+        if UnixStream::connect(&path).is_ok() {
+            log_warn1!("Control socket {:?} is already in use", path);
+            return None;
+        }
+
+        let _ = fs::remove_file(&path);
+        match UnixListener::bind(&path) {
+            Ok(listener) => {
+                log_info1!("Listening for control requests on {:?}", path);
+                Some(listener)
+            }
+            Err(err) => {
+                log_error!("Failed to create control socket {:?}: {}", path, err);
+                None
+            }
+        }
+    }
+
+    /// Sends taken screenshot or error to client which requested it.
+    fn reply_screenshot(&mut self, id: u32, result: &Result<Buffer, String>) {
+        let reply = match *result {
+            Ok(ref image) => ControlReply::Image(png::encode(image)),
+            Err(ref err) => ControlReply::Error(err.clone()),
+        };
+        self.reply(id, reply);
+    }
+
+    /// Sends selected region or error to client which requested it.
+    fn reply_region(&mut self, id: u32, result: &Result<Area, String>) {
+        let reply = match *result {
+            Ok(area) => ControlReply::Region(area),
+            Err(ref err) => ControlReply::Error(err.clone()),
+        };
+        self.reply(id, reply);
+    }
+
+    /// Sends reply to client which sent request with given ID.
+    fn reply(&mut self, id: u32, reply: ControlReply) {
+        let stream = self.pending.lock().unwrap().connections.remove(&id);
+        if let Some(stream) = stream {
+            send_reply(stream, reply);
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Module for ControlModule {
+    type T = Perceptron;
+    type C = Context;
+
+    fn initialize(&mut self, context: &mut Self::C) -> InitResult {
+        if let Some(listener) = Self::listen() {
+            let handler = ControlHandler {
+                listener: listener,
+                pending: self.pending.clone(),
+                signaler: context.get_signaler().clone(),
+            };
+            context.add_event_handler(Box::new(handler), event_kind::READ);
+            self.listening = true;
+        }
+        vec![perceptron::SCREENSHOT_TAKEN, perceptron::REGION_SELECTED]
+    }
+
+    fn execute(&mut self, package: &Self::T) {
+        match *package {
+            Perceptron::ScreenshotTaken(id, ref result) => self.reply_screenshot(id, result),
+            Perceptron::RegionSelected(id, ref result) => self.reply_region(id, result),
+            _ => {}
+        }
+    }
+
+    fn finalize(&mut self) {
+        if self.listening {
+            let _ = fs::remove_file(control::get_socket_path());
+        }
+        log_info1!("Finalized Control module");
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub struct ControlModuleConstructor {}
+
+// -------------------------------------------------------------------------------------------------
+
+impl ControlModuleConstructor {
+    /// Constructs new `ControlModuleConstructor`.
+    pub fn new() -> Box<ModuleConstructor<T = Perceptron, C = Context>> {
+        Box::new(ControlModuleConstructor {})
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl ModuleConstructor for ControlModuleConstructor {
+    type T = Perceptron;
+    type C = Context;
+
+    fn construct(&self) -> Box<Module<T = Self::T, C = Self::C>> {
+        Box::new(ControlModule::new())
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Sends reply to client and closes connection.
+fn send_reply(mut stream: UnixStream, reply: ControlReply) {
+    let _ = stream.set_write_timeout(Some(Duration::from_millis(WRITE_TIMEOUT)));
+    if let Err(err) = stream.write_all(format!("{}\n", reply.to_line()).as_bytes()) {
+        log_warn2!("Failed to send control reply: {}", err);
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
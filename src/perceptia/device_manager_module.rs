@@ -6,13 +6,15 @@
 // -------------------------------------------------------------------------------------------------
 
 use dharma::{InitResult, Module, ModuleConstructor};
-use qualia::{Context, Perceptron};
+use qualia::{Coordinator, Context, InputManager, perceptron, Perceptron};
 use device_manager::DeviceManager;
 
 // -------------------------------------------------------------------------------------------------
 
 pub struct DeviceManagerModule<'a> {
     manager: Option<DeviceManager<'a>>,
+    coordinator: Option<Coordinator>,
+    input_manager: Option<InputManager>,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -20,7 +22,11 @@ pub struct DeviceManagerModule<'a> {
 impl<'a> DeviceManagerModule<'a> {
     /// `DeviceManagerModule` constructor.
     pub fn new() -> Self {
-        DeviceManagerModule { manager: None }
+        DeviceManagerModule {
+            manager: None,
+            coordinator: None,
+            input_manager: None,
+        }
     }
 }
 
@@ -32,11 +38,41 @@ impl<'a> Module for DeviceManagerModule<'a> {
 
     fn initialize(&mut self, context: &mut Self::C) -> InitResult {
         self.manager = Some(DeviceManager::new(context.clone()));
-        Vec::new()
+        self.coordinator = Some(context.get_coordinator().clone());
+        self.input_manager = Some(context.get_input_manager().clone());
+        vec![perceptron::KEYBOARD_FOCUS_CHANGED,
+             perceptron::INPUT_DEVICE_LOST,
+             perceptron::SESSION_STATE_CHANGED,
+             perceptron::COMMAND]
     }
 
-    // FIXME: Finnish handling signals in `DeviceManagerModule`.
-    fn execute(&mut self, package: &Self::T) {}
+    fn execute(&mut self, package: &Self::T) {
+        match *package {
+            Perceptron::KeyboardFocusChanged(_, sid) => {
+                if let (Some(coordinator), Some(input_manager)) =
+                    (self.coordinator.as_ref(), self.input_manager.as_mut()) {
+                    let app_id = coordinator.get_surface(sid).and_then(|info| info.app_id);
+                    input_manager.set_focused_app(app_id);
+                }
+            }
+            Perceptron::InputDeviceLost(id) => {
+                if let Some(ref mut manager) = self.manager {
+                    manager.on_device_lost(id);
+                }
+            }
+            Perceptron::SessionStateChanged(active) => {
+                if let Some(ref mut manager) = self.manager {
+                    manager.on_session_state_changed(active);
+                }
+            }
+            Perceptron::Command(ref command) => {
+                if let Some(ref mut manager) = self.manager {
+                    manager.on_command(command.clone());
+                }
+            }
+            _ => {}
+        }
+    }
 
     fn finalize(&mut self) {
         log_info1!("Finalized Device Manager module");
@@ -6,7 +6,7 @@
 // -------------------------------------------------------------------------------------------------
 
 use dharma::{InitResult, Module, ModuleConstructor};
-use qualia::{Context, perceptron, Perceptron};
+use qualia::{Config, Context, perceptron, Perceptron};
 use exhibitor::Exhibitor;
 
 // -------------------------------------------------------------------------------------------------
@@ -14,6 +14,7 @@ use exhibitor::Exhibitor;
 /// Implementation of `dharma::Module` for Device Manager.
 pub struct ExhibitorModule {
     exhibitor: Option<Exhibitor>,
+    config: Option<Config>,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -21,7 +22,10 @@ pub struct ExhibitorModule {
 impl ExhibitorModule {
     /// `ExhibitorModule` constructor.
     pub fn new() -> Self {
-        ExhibitorModule { exhibitor: None }
+        ExhibitorModule {
+            exhibitor: None,
+            config: None,
+        }
     }
 }
 
@@ -34,19 +38,55 @@ impl Module for ExhibitorModule {
     fn initialize(&mut self, context: &mut Self::C) -> InitResult {
         log_info1!("Starting Exhibitor module");
         self.exhibitor = Some(Exhibitor::new(context.get_signaler().clone(),
-                                             context.get_coordinator().clone()));
+                                             context.get_coordinator().clone(),
+                                             context.get_dispatcher().clone(),
+                                             context.get_config().get_exhibitor_config()));
+        self.config = Some(context.get_config().clone());
         vec![perceptron::NOTIFY,
              perceptron::PAGE_FLIP,
              perceptron::OUTPUT_FOUND,
+             perceptron::DISPLAY_CHANGED,
              perceptron::COMMAND,
+             perceptron::CONFIG_RELOADED,
              perceptron::INPUT_POINTER_MOTION,
              perceptron::INPUT_POINTER_POSITION,
+             perceptron::INPUT_POINTER_ABSOLUTE_POSITION,
              perceptron::INPUT_POINTER_BUTTON,
              perceptron::INPUT_POINTER_POSITION_RESET,
+             perceptron::INPUT_KEYBOARD,
+             perceptron::SWITCH_TOGGLED,
+             perceptron::ORIENTATION_CHANGED,
              perceptron::CURSOR_SURFACE_CHANGE,
              perceptron::SURFACE_READY,
              perceptron::SURFACE_DESTROYED,
-             perceptron::KEYBOARD_FOCUS_CHANGED]
+             perceptron::SURFACE_TITLE_CHANGED,
+             perceptron::KEYBOARD_FOCUS_CHANGED,
+             perceptron::DRAG_STARTED,
+             perceptron::DRAG_ENDED,
+             perceptron::MOVE_REQUESTED,
+             perceptron::PLACEMENT_REQUESTED,
+             perceptron::DECORATION_MODE_REQUESTED,
+             perceptron::FULLSCREEN_REQUESTED,
+             perceptron::MAXIMIZE_REQUESTED,
+             perceptron::INITIAL_SIZE_REQUESTED,
+             perceptron::INPUT_TOUCH_DOWN,
+             perceptron::INPUT_TOUCH_MOTION,
+             perceptron::INPUT_TOUCH_UP,
+             perceptron::INPUT_TOUCH_FRAME,
+             perceptron::INPUT_GESTURE_BEGIN,
+             perceptron::INPUT_GESTURE_UPDATE,
+             perceptron::INPUT_GESTURE_END,
+             perceptron::IDLE,
+             perceptron::RESUMED,
+             perceptron::SESSION_LOCK_CHANGED,
+             perceptron::LOCK_SURFACE_ADDED,
+             perceptron::LAYER_SURFACE_CHANGED,
+             perceptron::VT_RELEASED,
+             perceptron::VT_ACQUIRED,
+             perceptron::CAPTURE_REQUESTED,
+             perceptron::OUTPUT_CONFIGURATION_REQUESTED,
+             perceptron::SCREENSHOT_REQUESTED,
+             perceptron::REGION_SELECTION_REQUESTED]
     }
 
     fn execute(&mut self, package: &Self::T) {
@@ -54,22 +94,88 @@ impl Module for ExhibitorModule {
             match *package {
                 Perceptron::Notify => exhibitor.on_notify(),
                 Perceptron::OutputFound(bundle) => exhibitor.on_output_found(bundle),
-                Perceptron::PageFlip(id) => exhibitor.on_pageflip(id),
+                Perceptron::PageFlip(id, time) => exhibitor.on_pageflip(id, time),
+                Perceptron::DisplayChanged(ref info) => exhibitor.on_display_changed(info.clone()),
                 Perceptron::Command(ref command) => exhibitor.on_command(command.clone()),
+                Perceptron::ConfigReloaded => {
+                    if let Some(ref config) = self.config {
+                        exhibitor.on_config_reloaded(config.get_exhibitor_config());
+                    }
+                }
 
                 Perceptron::InputPointerMotion(ref vector) => exhibitor.on_motion(vector.clone()),
                 Perceptron::InputPointerPosition(ref pos) => exhibitor.on_position(pos.clone()),
+                Perceptron::InputPointerAbsolutePosition(ref pos) => {
+                    exhibitor.on_absolute_position(pos.clone())
+                }
                 Perceptron::InputPointerButton(ref btn) => exhibitor.on_button(btn.clone()),
                 Perceptron::InputPointerPositionReset => exhibitor.on_position_reset(),
+                Perceptron::InputKeyboard(ref key) => exhibitor.on_key(key.clone()),
+                Perceptron::SwitchToggled(switch, state) => {
+                    exhibitor.on_switch_toggled(switch, state)
+                }
+                Perceptron::OrientationChanged(transform) => {
+                    exhibitor.on_orientation_changed(transform)
+                }
+                Perceptron::InputTouchDown(ref touch) => exhibitor.on_touch_down(touch.clone()),
+                Perceptron::InputTouchMotion(ref touch) => exhibitor.on_touch_motion(touch.clone()),
+                Perceptron::InputTouchUp(id, time) => exhibitor.on_touch_up(id, time),
+                Perceptron::InputTouchFrame => exhibitor.on_touch_frame(),
+                Perceptron::InputGestureBegin(kind, fingers, time) => {
+                    exhibitor.on_gesture_begin(kind, fingers, time)
+                }
+                Perceptron::InputGestureUpdate(kind, motion) => {
+                    exhibitor.on_gesture_update(kind, motion)
+                }
+                Perceptron::InputGestureEnd(kind, cancelled, time) => {
+                    exhibitor.on_gesture_end(kind, cancelled, time)
+                }
 
                 Perceptron::CursorSurfaceChange(sid) => exhibitor.on_cursor_surface_change(sid),
 
                 Perceptron::SurfaceReady(sid) => exhibitor.on_surface_ready(sid),
                 Perceptron::SurfaceDestroyed(sid) => exhibitor.on_surface_destroyed(sid),
+                Perceptron::SurfaceTitleChanged(sid) => exhibitor.on_surface_title_changed(sid),
 
                 Perceptron::KeyboardFocusChanged(_, sid) => {
                     exhibitor.on_keyboard_focus_changed(sid)
                 }
+
+                Perceptron::DragStarted => exhibitor.on_drag_started(),
+                Perceptron::DragEnded(_) => exhibitor.on_drag_ended(),
+                Perceptron::MoveRequested(sid) => exhibitor.on_move_requested(sid),
+                Perceptron::PlacementRequested(sid) => exhibitor.on_placement_requested(sid),
+                Perceptron::DecorationModeRequested(sid) => {
+                    exhibitor.on_decoration_mode_requested(sid)
+                }
+                Perceptron::FullscreenRequested(sid, fullscreen, output_id) => {
+                    exhibitor.on_fullscreen_requested(sid, fullscreen, output_id)
+                }
+                Perceptron::MaximizeRequested(sid, maximized) => {
+                    exhibitor.on_maximize_requested(sid, maximized)
+                }
+                Perceptron::InitialSizeRequested(sid) => exhibitor.on_initial_size_requested(sid),
+                Perceptron::Idle(timeout) => exhibitor.on_idle(timeout),
+                Perceptron::Resumed => exhibitor.on_resumed(),
+                Perceptron::SessionLockChanged(locked) => exhibitor.on_session_lock_changed(locked),
+                Perceptron::LockSurfaceAdded(sid, output_id) => {
+                    exhibitor.on_lock_surface_added(sid, output_id)
+                }
+                Perceptron::LayerSurfaceChanged(sid) => exhibitor.on_layer_surface_changed(sid),
+                Perceptron::VtReleased => exhibitor.on_vt_changed(false),
+                Perceptron::VtAcquired => exhibitor.on_vt_changed(true),
+                Perceptron::CaptureRequested(output_id) => {
+                    exhibitor.on_capture_requested(output_id)
+                }
+                Perceptron::OutputConfigurationRequested(id, ref configurations, test) => {
+                    exhibitor.on_output_configuration_requested(id, configurations.clone(), test)
+                }
+                Perceptron::ScreenshotRequested(id, ref target) => {
+                    exhibitor.on_screenshot_requested(id, target.clone())
+                }
+                Perceptron::RegionSelectionRequested(id) => {
+                    exhibitor.on_region_selection_requested(id)
+                }
                 _ => {}
             }
         }
@@ -0,0 +1,126 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementation of `dharma::Module` exporting screencast frames for recording tools.
+//!
+//! When export is enabled in screencast configuration, frames captured by Exhibitor are copied to
+//! shared memory ring in runtime directory (see `qualia::frame_export`). Copying happens outside of
+//! Exhibitor thread so it does not delay drawing. Export is recreated when size of frames changes
+//! and stopped when export is disabled by configuration reload.
+
+// -------------------------------------------------------------------------------------------------
+
+use dharma::{InitResult, Module, ModuleConstructor};
+use qualia::{Buffer, Config, Context, Env, FrameExport, Pixmap, perceptron, Perceptron};
+
+// -------------------------------------------------------------------------------------------------
+
+pub struct FrameExportModule {
+    config: Option<Config>,
+    export: Option<FrameExport>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl FrameExportModule {
+    /// `FrameExportModule` constructor.
+    pub fn new() -> Self {
+        FrameExportModule {
+            config: None,
+            export: None,
+        }
+    }
+
+    /// Returns name of exported output if export is enabled.
+    fn get_exported_output(&self) -> Option<String> {
+        self.config
+            .as_ref()
+            .and_then(|config| config.get_exhibitor_config().screencast)
+            .and_then(|screencast| if screencast.export { Some(screencast.output) } else { None })
+    }
+
+    /// Exports frame creating new export if needed.
+    fn export_frame(&mut self, frame: &Buffer) {
+        let output = match self.get_exported_output() {
+            Some(output) => output,
+            None => return,
+        };
+
+        let (width, height, stride) = (frame.get_width(), frame.get_height(), frame.get_stride());
+        if !self.export.as_ref().map_or(false, |export| export.accepts(width, height, stride)) {
+            // Old export has to be removed before creating files with the same names
+            self.export = None;
+            let dir = Env::get_runtime_dir_path();
+            match FrameExport::new(&dir, &format!("perceptia-{}", output), width, height, stride) {
+                Ok(export) => {
+                    log_info1!("Exporting frames to {:?}", export.get_ring_path());
+                    self.export = Some(export);
+                }
+                Err(err) => {
+                    log_error!("Failed to start frame export: {}", err);
+                    return;
+                }
+            }
+        }
+
+        if let Some(ref mut export) = self.export {
+            if let Err(err) = export.export(frame) {
+                log_warn2!("Failed to export frame: {}", err);
+            }
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Module for FrameExportModule {
+    type T = Perceptron;
+    type C = Context;
+
+    fn initialize(&mut self, context: &mut Self::C) -> InitResult {
+        self.config = Some(context.get_config().clone());
+        vec![perceptron::SCREENCAST_FRAME, perceptron::CONFIG_RELOADED]
+    }
+
+    fn execute(&mut self, package: &Self::T) {
+        match *package {
+            Perceptron::ScreencastFrame(_, ref frame) => self.export_frame(frame),
+            Perceptron::ConfigReloaded => {
+                // Output may have changed so files will be created again with the next frame
+                self.export = None;
+            }
+            _ => {}
+        }
+    }
+
+    fn finalize(&mut self) {
+        self.export = None;
+        log_info1!("Finalized Frame Export module");
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub struct FrameExportModuleConstructor {}
+
+// -------------------------------------------------------------------------------------------------
+
+impl FrameExportModuleConstructor {
+    /// Constructs new `FrameExportModuleConstructor`.
+    pub fn new() -> Box<ModuleConstructor<T = Perceptron, C = Context>> {
+        Box::new(FrameExportModuleConstructor {})
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl ModuleConstructor for FrameExportModuleConstructor {
+    type T = Perceptron;
+    type C = Context;
+
+    fn construct(&self) -> Box<Module<T = Self::T, C = Self::C>> {
+        Box::new(FrameExportModule::new())
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,148 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementation of `dharma::Module` detecting user inactivity.
+//!
+//! Module records time of the last input. Timer periodically compares time elapsed since then with
+//! idle timeout from configuration and timeouts requested by clients and signals `Idle` once for
+//! every timeout which passed. First input after that is signalled as `Resumed`.
+
+// -------------------------------------------------------------------------------------------------
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use dharma::{event_kind, InitResult, Module, ModuleConstructor, Signaler};
+use dharma::{Timer, TimerEventHandler};
+use qualia::{Context, perceptron, Perceptron};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Interval in milliseconds between checks of idle timeouts.
+const CHECK_INTERVAL: u64 = 100;
+
+// -------------------------------------------------------------------------------------------------
+
+/// State of user activity shared between module and timer.
+struct IdleState {
+    /// Time of the last input.
+    last_activity: Instant,
+
+    /// Timeouts for which `Idle` was already signalled since the last input.
+    passed: Vec<u32>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub struct IdleModule {
+    state: Arc<Mutex<IdleState>>,
+    signaler: Option<Signaler<Perceptron>>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl IdleModule {
+    /// `IdleModule` constructor.
+    pub fn new() -> Self {
+        IdleModule {
+            state: Arc::new(Mutex::new(IdleState {
+                                           last_activity: Instant::now(),
+                                           passed: Vec::new(),
+                                       })),
+            signaler: None,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Module for IdleModule {
+    type T = Perceptron;
+    type C = Context;
+
+    fn initialize(&mut self, context: &mut Self::C) -> InitResult {
+        self.signaler = Some(context.get_signaler().clone());
+
+        let config = context.get_config().get_idle_config();
+        let coordinator = context.get_coordinator().clone();
+        let mut signaler = context.get_signaler().clone();
+        let state = self.state.clone();
+        let handler = Box::new(move || {
+            let mut timeouts = coordinator.get_idle_timeouts();
+            if config.timeout > 0 {
+                timeouts.push(1000 * config.timeout);
+            }
+
+            let mut state = state.lock().unwrap();
+            let idle_time = state.last_activity.elapsed();
+            for timeout in timeouts {
+                if (idle_time >= Duration::from_millis(timeout as u64)) &&
+                   !state.passed.contains(&timeout) {
+                    state.passed.push(timeout);
+                    signaler.emit(perceptron::IDLE, Perceptron::Idle(timeout));
+                }
+            }
+        });
+
+        let interval = Duration::from_millis(CHECK_INTERVAL);
+        let timer = Timer::new();
+        timer.arm(interval, Some(interval));
+        context.add_event_handler(Box::new(TimerEventHandler::new(timer, handler)),
+                                  event_kind::READ);
+
+        vec![perceptron::INPUT_POINTER_MOTION,
+             perceptron::INPUT_POINTER_POSITION,
+             perceptron::INPUT_POINTER_BUTTON,
+             perceptron::INPUT_POINTER_AXIS,
+             perceptron::INPUT_POINTER_ABSOLUTE_POSITION,
+             perceptron::INPUT_KEYBOARD,
+             perceptron::INPUT_TOUCH_DOWN,
+             perceptron::INPUT_TOUCH_MOTION,
+             perceptron::INPUT_TOUCH_UP,
+             perceptron::INPUT_GESTURE_BEGIN,
+             perceptron::INPUT_GESTURE_UPDATE,
+             perceptron::INPUT_GESTURE_END]
+    }
+
+    fn execute(&mut self, _package: &Self::T) {
+        // All subscribed signals come from input devices.
+        let mut state = self.state.lock().unwrap();
+        state.last_activity = Instant::now();
+        if !state.passed.is_empty() {
+            state.passed.clear();
+            if let Some(ref mut signaler) = self.signaler {
+                signaler.emit(perceptron::RESUMED, Perceptron::Resumed);
+            }
+        }
+    }
+
+    fn finalize(&mut self) {
+        log_info1!("Finalized Idle module");
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub struct IdleModuleConstructor {}
+
+// -------------------------------------------------------------------------------------------------
+
+impl IdleModuleConstructor {
+    /// Constructs new `IdleModuleConstructor`.
+    pub fn new() -> Box<ModuleConstructor<T = Perceptron, C = Context>> {
+        Box::new(IdleModuleConstructor {})
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl ModuleConstructor for IdleModuleConstructor {
+    type T = Perceptron;
+    type C = Context;
+
+    fn construct(&self) -> Box<Module<T = Self::T, C = Self::C>> {
+        Box::new(IdleModule::new())
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
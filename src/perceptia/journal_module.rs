@@ -0,0 +1,182 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementation of `dharma::Module` recording window lifecycle journal (see `qualia::journal`).
+//!
+//! When journal is enabled in configuration, new journal file is created in data directory at
+//! start, like log file. Opening, closing and focusing top-level windows is appended to it.
+//! Popups, subsurfaces and layer surfaces are not recorded. Surfaces may be already destroyed
+//! when their events are processed so application IDs and titles of windows are remembered when
+//! they are opened.
+
+// -------------------------------------------------------------------------------------------------
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+
+use dharma::{InitResult, Module, ModuleConstructor};
+use qualia::{Context, Coordinator, Env, perceptron, Perceptron, SurfaceId};
+use qualia::{JournalEntry, JournalEvent};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Application ID and title of window.
+type WindowInfo = (Option<String>, Option<String>);
+
+// -------------------------------------------------------------------------------------------------
+
+pub struct JournalModule {
+    coordinator: Option<Coordinator>,
+    file: Option<File>,
+
+    /// Information about open windows.
+    windows: HashMap<SurfaceId, WindowInfo>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl JournalModule {
+    /// `JournalModule` constructor.
+    pub fn new() -> Self {
+        JournalModule {
+            coordinator: None,
+            file: None,
+            windows: HashMap::new(),
+        }
+    }
+
+    /// Creates new journal file in data directory.
+    fn open_journal() -> Option<File> {
+        let path = Env::get_data_dir_path().join(Env::get_data_file_name("journal", "tsv"));
+        match OpenOptions::new().append(true).create(true).open(&path) {
+            Ok(file) => {
+                log_info1!("Recording window journal in {:?}", path);
+                Some(file)
+            }
+            Err(err) => {
+                log_error!("Failed to create journal file {:?}: {}", path, err);
+                None
+            }
+        }
+    }
+
+    /// Returns application ID and title of given surface if it is a top-level window.
+    fn get_window_info(&self, sid: SurfaceId) -> Option<WindowInfo> {
+        let coordinator = match self.coordinator {
+            Some(ref coordinator) => coordinator,
+            None => return None,
+        };
+
+        if coordinator.get_layer_surface(sid).is_some() {
+            return None;
+        }
+        match coordinator.get_surface(sid) {
+            Some(ref info) if !info.parent_sid.is_valid() => {
+                Some((info.app_id.clone(), info.title.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Appends entry to journal file.
+    fn record(&mut self, event: JournalEvent, sid: SurfaceId, info: &WindowInfo) {
+        let entry = JournalEntry::new(event, sid, info.0.as_ref(), info.1.as_ref());
+        if let Some(ref mut file) = self.file {
+            if let Err(err) = file.write_all(entry.to_line().as_bytes()) {
+                log_warn2!("Failed to write journal entry: {}", err);
+            }
+        }
+    }
+
+    /// Handles showing of surface.
+    fn on_surface_ready(&mut self, sid: SurfaceId) {
+        if let Some(info) = self.get_window_info(sid) {
+            self.record(JournalEvent::Open, sid, &info);
+            self.windows.insert(sid, info);
+        }
+    }
+
+    /// Handles hiding or destruction of surface.
+    fn on_surface_destroyed(&mut self, sid: SurfaceId) {
+        if let Some(info) = self.windows.remove(&sid) {
+            self.record(JournalEvent::Close, sid, &info);
+        }
+    }
+
+    /// Handles change of keyboard focus. Application ID and title are refreshed as they might have
+    /// changed since the window was opened.
+    fn on_keyboard_focus_changed(&mut self, sid: SurfaceId) {
+        if self.windows.contains_key(&sid) {
+            if let Some(info) = self.get_window_info(sid) {
+                self.windows.insert(sid, info);
+            }
+            if let Some(info) = self.windows.get(&sid).cloned() {
+                self.record(JournalEvent::Focus, sid, &info);
+            }
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Module for JournalModule {
+    type T = Perceptron;
+    type C = Context;
+
+    fn initialize(&mut self, context: &mut Self::C) -> InitResult {
+        if !context.get_config().get_journal_config().enabled {
+            return Vec::new();
+        }
+
+        self.file = Self::open_journal();
+        if self.file.is_none() {
+            return Vec::new();
+        }
+
+        self.coordinator = Some(context.get_coordinator().clone());
+        vec![perceptron::SURFACE_READY,
+             perceptron::SURFACE_DESTROYED,
+             perceptron::KEYBOARD_FOCUS_CHANGED]
+    }
+
+    fn execute(&mut self, package: &Self::T) {
+        match *package {
+            Perceptron::SurfaceReady(sid) => self.on_surface_ready(sid),
+            Perceptron::SurfaceDestroyed(sid) => self.on_surface_destroyed(sid),
+            Perceptron::KeyboardFocusChanged(_, sid) => self.on_keyboard_focus_changed(sid),
+            _ => {}
+        }
+    }
+
+    fn finalize(&mut self) {
+        self.file = None;
+        log_info1!("Finalized Journal module");
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub struct JournalModuleConstructor {}
+
+// -------------------------------------------------------------------------------------------------
+
+impl JournalModuleConstructor {
+    /// Constructs new `JournalModuleConstructor`.
+    pub fn new() -> Box<ModuleConstructor<T = Perceptron, C = Context>> {
+        Box::new(JournalModuleConstructor {})
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl ModuleConstructor for JournalModuleConstructor {
+    type T = Perceptron;
+    type C = Context;
+
+    fn construct(&self) -> Box<Module<T = Self::T, C = Self::C>> {
+        Box::new(JournalModule::new())
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,241 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementation of `dharma::Module` serving compositor metrics (see `qualia::metrics`) in
+//! Prometheus text format.
+//!
+//! Metrics are served over HTTP on unix socket in runtime directory and/or on TCP address if
+//! enabled in configuration. Every request gets the same reply regardless of its path, so
+//! metrics can be also read with e.g. `curl --unix-socket $XDG_RUNTIME_DIR/perceptia-metrics x`.
+
+// -------------------------------------------------------------------------------------------------
+
+use std::fs;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use dharma::{event_kind, EventHandler, EventKind, InitResult, Module, ModuleConstructor};
+use qualia::{Context, Coordinator, Env, Perceptron};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Name of unix socket in runtime directory.
+const SOCKET_NAME: &'static str = "perceptia-metrics";
+
+/// Time in milliseconds to wait for request after accepting connection. Requests are read in
+/// dispatcher thread so it must be short.
+const READ_TIMEOUT: u64 = 100;
+
+/// Time in milliseconds to wait for client to receive reply.
+const WRITE_TIMEOUT: u64 = 1000;
+
+/// Maximal size of request which is read. Rest of the request is ignored.
+const MAX_REQUEST_SIZE: usize = 1024;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Returns path to metrics socket.
+fn get_socket_path() -> PathBuf {
+    Env::get_runtime_dir_path().join(SOCKET_NAME)
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Reads request from given connection and replies with metrics.
+fn serve<S: Read + Write>(mut stream: S, coordinator: &Coordinator) {
+    let mut request = [0; MAX_REQUEST_SIZE];
+    if let Err(err) = stream.read(&mut request) {
+        log_warn2!("Failed to read metrics request: {}", err);
+        return;
+    }
+
+    let body = coordinator.format_metrics();
+    let reply = format!("HTTP/1.0 200 OK\r\n\
+                         Content-Type: text/plain; version=0.0.4\r\n\
+                         Content-Length: {}\r\n\
+                         \r\n\
+                         {}",
+                        body.len(),
+                        body);
+    if let Err(err) = stream.write_all(reply.as_bytes()) {
+        log_warn2!("Failed to send metrics: {}", err);
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Handler of unix metrics socket.
+struct UnixMetricsHandler {
+    listener: UnixListener,
+    coordinator: Coordinator,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl EventHandler for UnixMetricsHandler {
+    fn get_fd(&self) -> RawFd {
+        self.listener.as_raw_fd()
+    }
+
+    fn process_event(&mut self, _: EventKind) {
+        match self.listener.accept() {
+            Ok((stream, _)) => {
+                let _ = stream.set_read_timeout(Some(Duration::from_millis(READ_TIMEOUT)));
+                let _ = stream.set_write_timeout(Some(Duration::from_millis(WRITE_TIMEOUT)));
+                serve(stream, &self.coordinator);
+            }
+            Err(err) => log_warn2!("Failed to accept metrics connection: {}", err),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Handler of TCP metrics socket.
+struct TcpMetricsHandler {
+    listener: TcpListener,
+    coordinator: Coordinator,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl EventHandler for TcpMetricsHandler {
+    fn get_fd(&self) -> RawFd {
+        self.listener.as_raw_fd()
+    }
+
+    fn process_event(&mut self, _: EventKind) {
+        match self.listener.accept() {
+            Ok((stream, _)) => {
+                let _ = stream.set_read_timeout(Some(Duration::from_millis(READ_TIMEOUT)));
+                let _ = stream.set_write_timeout(Some(Duration::from_millis(WRITE_TIMEOUT)));
+                serve(stream, &self.coordinator);
+            }
+            Err(err) => log_warn2!("Failed to accept metrics connection: {}", err),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub struct MetricsModule {
+    listening: bool,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl MetricsModule {
+    /// `MetricsModule` constructor.
+    pub fn new() -> Self {
+        MetricsModule { listening: false }
+    }
+
+    /// Creates unix metrics socket. Socket left by previous instance is replaced unless the
+    /// instance still listens on it.
+    fn listen_on_socket() -> Option<UnixListener> {
+        let path = get_socket_path();
+        if UnixStream::connect(&path).is_ok() {
+            log_warn1!("Metrics socket {:?} is already in use", path);
+            return None;
+        }
+
+        let _ = fs::remove_file(&path);
+        match UnixListener::bind(&path) {
+            Ok(listener) => {
+                log_info1!("Serving metrics on {:?}", path);
+                Some(listener)
+            }
+            Err(err) => {
+                log_error!("Failed to create metrics socket {:?}: {}", path, err);
+                None
+            }
+        }
+    }
+
+    /// Creates TCP metrics socket bound to given address.
+    fn listen_on_address(address: &str) -> Option<TcpListener> {
+        match TcpListener::bind(address) {
+            Ok(listener) => {
+                log_info1!("Serving metrics on {}", address);
+                Some(listener)
+            }
+            Err(err) => {
+                log_error!("Failed to serve metrics on {}: {}", address, err);
+                None
+            }
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Module for MetricsModule {
+    type T = Perceptron;
+    type C = Context;
+
+    fn initialize(&mut self, context: &mut Self::C) -> InitResult {
+        let config = context.get_config().get_metrics_config();
+        let coordinator = context.get_coordinator().clone();
+
+        if config.socket {
+            if let Some(listener) = Self::listen_on_socket() {
+                let handler = UnixMetricsHandler {
+                    listener: listener,
+                    coordinator: coordinator.clone(),
+                };
+                context.add_event_handler(Box::new(handler), event_kind::READ);
+                self.listening = true;
+            }
+        }
+
+        if let Some(ref address) = config.address {
+            if let Some(listener) = Self::listen_on_address(address) {
+                let handler = TcpMetricsHandler {
+                    listener: listener,
+                    coordinator: coordinator.clone(),
+                };
+                context.add_event_handler(Box::new(handler), event_kind::READ);
+            }
+        }
+        Vec::new()
+    }
+
+    fn execute(&mut self, _package: &Self::T) {}
+
+    fn finalize(&mut self) {
+        if self.listening {
+            let _ = fs::remove_file(get_socket_path());
+        }
+        log_info1!("Finalized Metrics module");
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub struct MetricsModuleConstructor {}
+
+// -------------------------------------------------------------------------------------------------
+
+impl MetricsModuleConstructor {
+    /// Constructs new `MetricsModuleConstructor`.
+    pub fn new() -> Box<ModuleConstructor<T = Perceptron, C = Context>> {
+        Box::new(MetricsModuleConstructor {})
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl ModuleConstructor for MetricsModuleConstructor {
+    type T = Perceptron;
+    type C = Context;
+
+    fn construct(&self) -> Box<Module<T = Self::T, C = Self::C>> {
+        Box::new(MetricsModule::new())
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
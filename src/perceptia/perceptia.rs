@@ -12,15 +12,28 @@ extern crate exhibitor;
 extern crate device_manager;
 extern crate wayland_frontend;
 
+mod control_module;
 mod device_manager_module;
 mod exhibitor_module;
+mod frame_export_module;
+mod idle_module;
+mod journal_module;
+mod metrics_module;
 mod wayland_service;
+mod watchdog;
 
-use dharma::{EventLoopInfo, Dispatcher, SignalEventHandler, Signaler};
+use std::os::unix::process::CommandExt;
+
+use dharma::{EventLoopInfo, Dispatcher, SignalEventHandler, Signaler, Watchdog};
 use qualia::{Context, Coordinator, InputManager};
 
+use control_module::ControlModuleConstructor;
 use device_manager_module::DeviceManagerModuleConstructor;
 use exhibitor_module::ExhibitorModuleConstructor;
+use frame_export_module::FrameExportModuleConstructor;
+use idle_module::IdleModuleConstructor;
+use journal_module::JournalModuleConstructor;
+use metrics_module::MetricsModuleConstructor;
 use wayland_service::WaylandServiceConstructor;
 
 fn main() {
@@ -31,14 +44,22 @@ fn main() {
     // Prepare tools
     let env = qualia::Env::create();
     let config = env.read_config();
-    let keymap = qualia::Keymap::new(&env).unwrap();
-    let settings = qualia::Settings::new(keymap.get_settings());
+    let keymap = qualia::Keymap::new(&env, &config.get_xkb_config()).unwrap();
+    let settings = qualia::Settings::new(keymap.get_settings(), config.get_key_repeat_config());
 
     // Prepare state
-    let signaler = Signaler::new();
+    let mut signaler = Signaler::new();
+    signaler.set_tracer(Box::new(|id, subscribers, latency| {
+        log_info1!("Signal {} ({}): {} subscriber(s), dispatched in {}us",
+                   qualia::perceptron::get_signal_name(id),
+                   id,
+                   subscribers,
+                   1_000_000 * latency.as_secs() + latency.subsec_nanos() as u64 / 1000);
+    }));
+    signaler.set_tracing(qualia::Env::is_signal_tracing_requested());
     let mut dispatcher = Dispatcher::new();
     let coordinator = Coordinator::new(signaler.clone());
-    let input_manager = InputManager::new(&config, signaler.clone());
+    let input_manager = InputManager::new(&config, signaler.clone(), dispatcher.clone());
     let context = Context::new(config.clone(),
                                settings.clone(),
                                signaler.clone(),
@@ -46,9 +67,17 @@ fn main() {
                                coordinator.clone(),
                                input_manager.clone());
 
-    let signal_source = Box::new(SignalEventHandler::new(dispatcher.clone(), signaler.clone()));
+    let mut signal_source = Box::new(SignalEventHandler::new(dispatcher.clone(), signaler.clone()));
+    let mut reload_context = context.clone();
+    signal_source.set_hangup_handler(Box::new(move || reload_context.reload_config()));
     dispatcher.add_source(signal_source, dharma::event_kind::READ);
 
+    let mut thread_watchdog = Watchdog::new();
+    watchdog::start(config.get_watchdog_config(),
+                    thread_watchdog.clone(),
+                    signaler.clone(),
+                    &mut dispatcher);
+
     // Create loops
     let mut utils_info: EventLoopInfo<_, _> =
         EventLoopInfo::new("p:utils".to_owned(), signaler.clone(), context.clone());
@@ -59,13 +88,27 @@ fn main() {
     let wayland_info: EventLoopInfo<_, _> =
         EventLoopInfo::new("p:wayland".to_owned(), signaler.clone(), context.clone());
 
+    utils_info.set_watchdog(thread_watchdog.clone());
+    exhibitor_info.set_watchdog(thread_watchdog.clone());
+
     // Create modules and services
     let device_manager_module = DeviceManagerModuleConstructor::new();
     let exhibitor_module = ExhibitorModuleConstructor::new();
-    let wayland_service = WaylandServiceConstructor::new(context.clone());
+    let idle_module = IdleModuleConstructor::new();
+    let frame_export_module = FrameExportModuleConstructor::new();
+    let control_module = ControlModuleConstructor::new();
+    let metrics_module = MetricsModuleConstructor::new();
+    let journal_module = JournalModuleConstructor::new();
+    let wayland_service = WaylandServiceConstructor::new(context.clone(),
+                                                         thread_watchdog.register("p:wayland"));
 
     // Assign modules to threads
     utils_info.add_module(device_manager_module);
+    utils_info.add_module(idle_module);
+    utils_info.add_module(frame_export_module);
+    utils_info.add_module(control_module);
+    utils_info.add_module(metrics_module);
+    utils_info.add_module(journal_module);
     exhibitor_info.add_module(exhibitor_module);
 
     // Start threads
@@ -83,4 +126,25 @@ fn main() {
         jh.join().unwrap();
     }
     log_info1!("Joined all threads!");
+
+    // Start again if requested. Wayland thread has to finish first to hand display socket over.
+    if qualia::functions::is_restart_requested() {
+        for jh in join_handles.drain(..) {
+            jh.join().unwrap();
+        }
+        std::mem::drop(env);
+        restart();
+    }
+}
+
+/// Replaces current process with new instance of compositor started with the same arguments.
+fn restart() {
+    match std::env::current_exe() {
+        Ok(path) => {
+            log_info1!("Restarting {:?}", path);
+            let err = std::process::Command::new(path).args(std::env::args_os().skip(1)).exec();
+            log_error!("Failed to restart: {}", err);
+        }
+        Err(err) => log_error!("Failed to find executable to restart: {}", err),
+    }
 }
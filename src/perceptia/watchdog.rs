@@ -0,0 +1,86 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module sets up periodic checking if threads of application still respond.
+
+// -------------------------------------------------------------------------------------------------
+
+use std::time::Duration;
+
+use dharma::{event_kind, Dispatcher, Signaler, ThreadReport, Timer, TimerEventHandler, Watchdog};
+use qualia::{functions, perceptron, Perceptron, WatchdogConfig};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Interval in milliseconds between checks of threads.
+const CHECK_INTERVAL: u64 = 1000;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Adds timer to `dispatcher` which pings threads registered in `watchdog` and checks if they
+/// responded. When stuck thread is found state of all threads is logged and, if configured,
+/// application quits. Does nothing if watchdog is disabled in configuration.
+pub fn start(config: WatchdogConfig,
+             watchdog: Watchdog,
+             mut signaler: Signaler<Perceptron>,
+             dispatcher: &mut Dispatcher) {
+    if config.timeout == 0 {
+        return;
+    }
+
+    let timeout = Duration::from_secs(config.timeout as u64);
+    let mut reported = false;
+    let handler = Box::new(move || {
+        let stuck = watchdog.find_stuck(timeout);
+        if stuck.is_empty() {
+            reported = false;
+        } else if !reported {
+            reported = true;
+            for report in stuck.iter() {
+                log_error!("Thread '{}' did not respond for {}ms",
+                           report.name,
+                           as_millis(report.since_beat));
+            }
+            log_threads(&watchdog.get_reports());
+            if config.restart {
+                log_error!("Quitting to be restarted");
+                functions::quit();
+            }
+        }
+        signaler.ping();
+    });
+
+    let interval = Duration::from_millis(CHECK_INTERVAL);
+    let timer = Timer::new();
+    timer.arm(interval, Some(interval));
+    dispatcher.add_source(Box::new(TimerEventHandler::new(timer, handler)), event_kind::READ);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Logs state of all monitored threads.
+fn log_threads(reports: &Vec<ThreadReport>) {
+    log_error!("State of threads:");
+    for report in reports.iter() {
+        if let Some((id, duration)) = report.signal {
+            log_error!("  '{}': processing signal {} ({}) for {}ms",
+                       report.name,
+                       perceptron::get_signal_name(id),
+                       id,
+                       as_millis(duration));
+        } else {
+            log_error!("  '{}': idle, last responded {}ms ago",
+                       report.name,
+                       as_millis(report.since_beat));
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Converts `Duration` to milliseconds.
+fn as_millis(duration: Duration) -> u64 {
+    1000 * duration.as_secs() + duration.subsec_nanos() as u64 / 1_000_000
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -10,7 +10,7 @@ use std::any::Any;
 
 use dharma;
 
-use qualia::{Context, perceptron, Perceptron};
+use qualia::{functions, Action, Context, perceptron, Perceptron};
 
 use wayland_frontend::{Engine, Gateway, constants};
 
@@ -30,6 +30,7 @@ pub struct WaylandService {
     engine: Engine,
     context: Context,
     receiver: dharma::Receiver<Perceptron>,
+    heartbeat: dharma::Heartbeat,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -37,13 +38,17 @@ pub struct WaylandService {
 /// Public methods.
 impl WaylandService {
     /// Creates new `WaylandService`.
-    pub fn new(mut context: Context) -> Self {
+    pub fn new(mut context: Context, heartbeat: dharma::Heartbeat) -> Self {
         dharma::system::block_signals();
         WaylandService {
             engine: Engine::new(context.get_coordinator().clone(),
-                                context.get_settings().clone()),
+                                context.get_settings().clone(),
+                                context.get_config().get_security_config(),
+                                context.get_config().get_xkb_config(),
+                                context.get_config().get_seat_configs()),
             context: context,
             receiver: dharma::Receiver::new(),
+            heartbeat: heartbeat,
         }
     }
 }
@@ -66,6 +71,10 @@ impl WaylandService {
     fn initialize(&mut self) {
         self.context.get_signaler().register(&self.receiver);
         for s in vec![perceptron::DISPLAY_CREATED,
+                      perceptron::DISPLAY_DESTROYED,
+                      perceptron::DISPLAY_CHANGED,
+                      perceptron::CONFIG_RELOADED,
+                      perceptron::COMMAND,
                       perceptron::INPUT_KEYBOARD,
                       perceptron::INPUT_POINTER_BUTTON,
                       perceptron::INPUT_POINTER_AXIS,
@@ -73,7 +82,34 @@ impl WaylandService {
                       perceptron::POINTER_FOCUS_CHANGED,
                       perceptron::POINTER_RELATIVE_MOTION,
                       perceptron::KEYBOARD_FOCUS_CHANGED,
-                      perceptron::SURFACE_RECONFIGURED] {
+                      perceptron::SEAT_KEYBOARD_FOCUS_CHANGED,
+                      perceptron::SEAT_CAPABILITIES_CHANGED,
+                      perceptron::IDLE,
+                      perceptron::RESUMED,
+                      perceptron::TOUCH_DOWN,
+                      perceptron::TOUCH_MOTION,
+                      perceptron::TOUCH_UP,
+                      perceptron::TOUCH_FRAME,
+                      perceptron::GESTURE_BEGIN,
+                      perceptron::GESTURE_UPDATE,
+                      perceptron::GESTURE_END,
+                      perceptron::SURFACE_RECONFIGURED,
+                      perceptron::SURFACE_CLOSE_REQUESTED,
+                      perceptron::SURFACE_KILL_REQUESTED,
+                      perceptron::DECORATION_MODE_CHANGED,
+                      perceptron::SURFACE_OUTPUT_ENTERED,
+                      perceptron::SURFACE_OUTPUT_LEFT,
+                      perceptron::PREFERRED_BUFFER_CHANGED,
+                      perceptron::TRANSFER_OFFERED,
+                      perceptron::TRANSFER_REQUESTED,
+                      perceptron::DRAG_FOCUS_CHANGED,
+                      perceptron::DRAG_MOTION,
+                      perceptron::DRAG_DROPPED,
+                      perceptron::DRAG_ENDED,
+                      perceptron::DRAG_TRANSFER_REQUESTED,
+                      perceptron::OUTPUT_CAPTURED,
+                      perceptron::OUTPUTS_CONFIGURED,
+                      perceptron::FRAME_PRESENTED] {
             self.context.get_signaler().subscribe(s, &self.receiver);
         }
         log_info1!("Started Wayland service");
@@ -102,8 +138,10 @@ impl WaylandService {
                 self.execute_plain(package);
                 true
             }
-            dharma::ReceiveResult::Defined(_, package) => {
+            dharma::ReceiveResult::Defined(id, package) => {
+                self.heartbeat.begin(id);
                 self.execute_defined(package);
+                self.heartbeat.end();
                 true
             }
             dharma::ReceiveResult::Custom(id, package) => {
@@ -134,6 +172,21 @@ impl WaylandService {
             Perceptron::DisplayCreated(info) => {
                 self.engine.on_display_created(info);
             }
+            Perceptron::DisplayDestroyed(id) => {
+                self.engine.on_display_destroyed(id);
+            }
+            Perceptron::DisplayChanged(info) => {
+                self.engine.on_display_changed(info);
+            }
+            Perceptron::ConfigReloaded => {
+                let config = self.context.get_config().get_security_config();
+                self.engine.on_security_config_changed(config);
+            }
+            Perceptron::Command(command) => {
+                if command.action == Action::Layout {
+                    self.engine.on_keyboard_layout_switch(command.direction);
+                }
+            }
             Perceptron::InputKeyboard(key) => {
                 self.engine.on_keyboard_input(key, None);
             }
@@ -155,11 +208,92 @@ impl WaylandService {
             Perceptron::KeyboardFocusChanged(old_sid, new_sid) => {
                 self.engine.on_keyboard_focus_changed(old_sid, new_sid);
             }
+            Perceptron::SeatKeyboardFocusChanged(seat, old_sid, new_sid) => {
+                self.engine.on_seat_keyboard_focus_changed(seat, old_sid, new_sid);
+            }
+            Perceptron::SeatCapabilitiesChanged(seat, capabilities) => {
+                self.engine.on_seat_capabilities_changed(seat, capabilities);
+            }
+            Perceptron::Idle(timeout) => {
+                self.engine.on_idle(timeout);
+            }
+            Perceptron::Resumed => {
+                self.engine.on_resumed();
+            }
+            Perceptron::TouchDown(sid, id, pos, time) => {
+                self.engine.on_touch_down(sid, id, pos, time);
+            }
+            Perceptron::TouchMotion(sid, id, pos, time) => {
+                self.engine.on_touch_motion(sid, id, pos, time);
+            }
+            Perceptron::TouchUp(sid, id, time) => {
+                self.engine.on_touch_up(sid, id, time);
+            }
+            Perceptron::TouchFrame(sid) => {
+                self.engine.on_touch_frame(sid);
+            }
+            Perceptron::GestureBegin(sid, kind, fingers, time) => {
+                self.engine.on_gesture_begin(sid, kind, fingers, time);
+            }
+            Perceptron::GestureUpdate(sid, kind, motion) => {
+                self.engine.on_gesture_update(sid, kind, motion);
+            }
+            Perceptron::GestureEnd(sid, kind, cancelled, time) => {
+                self.engine.on_gesture_end(sid, kind, cancelled, time);
+            }
             Perceptron::SurfaceReconfigured(sid) => {
                 if let Some(info) = self.context.get_coordinator().get_surface(sid) {
                     self.engine.on_surface_reconfigured(sid, info.desired_size, info.state_flags);
                 }
             }
+            Perceptron::SurfaceCloseRequested(sid) => {
+                self.engine.on_surface_close_requested(sid);
+            }
+            Perceptron::SurfaceKillRequested(sid) => {
+                self.engine.on_surface_kill_requested(sid);
+            }
+            Perceptron::DecorationModeChanged(sid) => {
+                self.engine.on_decoration_mode_changed(sid);
+            }
+            Perceptron::SurfaceOutputEntered(sid, output_id) => {
+                self.engine.on_surface_output_entered(sid, output_id);
+            }
+            Perceptron::SurfaceOutputLeft(sid, output_id) => {
+                self.engine.on_surface_output_left(sid, output_id);
+            }
+            Perceptron::PreferredBufferChanged(sid, scale, transform) => {
+                self.engine.on_preferred_buffer_changed(sid, scale, transform);
+            }
+            Perceptron::TransferOffered => {
+                self.engine.on_transfer_offered();
+            }
+            Perceptron::TransferRequested(mime_type, fd) => {
+                self.engine.on_transfer_requested(mime_type, fd);
+            }
+            Perceptron::DragFocusChanged(old_sid, new_sid, pos) => {
+                self.engine.on_drag_focus_changed(old_sid, new_sid, pos);
+            }
+            Perceptron::DragMotion(sid, pos, time) => {
+                self.engine.on_drag_motion(sid, pos, time);
+            }
+            Perceptron::DragDropped(sid) => {
+                self.engine.on_drag_dropped(sid);
+            }
+            Perceptron::DragEnded(performed) => {
+                self.engine.on_drag_ended(performed);
+            }
+            Perceptron::DragTransferRequested(mime_type, fd) => {
+                self.engine.on_drag_transfer_requested(mime_type, fd);
+            }
+            Perceptron::OutputCaptured(output_id, frame) => {
+                self.engine.on_output_captured(output_id, frame);
+            }
+            Perceptron::OutputsConfigured(id, result) => {
+                self.engine.on_outputs_configured(id, result);
+            }
+            Perceptron::FramePresented(output_id, sids, time) => {
+                self.engine.on_frame_presented(output_id, sids, time);
+            }
             _ => {}
         }
     }
@@ -195,11 +329,18 @@ impl WaylandService {
     fn execute_special(&mut self, command: dharma::SpecialCommand) -> bool {
         match command {
             dharma::SpecialCommand::Terminate => false,
+            dharma::SpecialCommand::Ping => {
+                self.heartbeat.beat();
+                true
+            }
         }
     }
 
     /// Finalizes service.
     fn finalize(&mut self) {
+        if functions::is_restart_requested() {
+            self.engine.prepare_restart();
+        }
         log_info1!("Stopped Wayland service");
     }
 }
@@ -208,14 +349,18 @@ impl WaylandService {
 
 pub struct WaylandServiceConstructor {
     context: Context,
+    heartbeat: dharma::Heartbeat,
 }
 
 // -------------------------------------------------------------------------------------------------
 
 impl WaylandServiceConstructor {
     /// Constructs new `WaylandServiceConstructor`.
-    pub fn new(context: Context) -> Box<dharma::ServiceConstructor> {
-        Box::new(WaylandServiceConstructor { context: context })
+    pub fn new(context: Context, heartbeat: dharma::Heartbeat) -> Box<dharma::ServiceConstructor> {
+        Box::new(WaylandServiceConstructor {
+                     context: context,
+                     heartbeat: heartbeat,
+                 })
     }
 }
 
@@ -223,7 +368,7 @@ impl WaylandServiceConstructor {
 
 impl dharma::ServiceConstructor for WaylandServiceConstructor {
     fn construct(&self) -> Box<dharma::Service> {
-        Box::new(WaylandService::new(self.context.clone()))
+        Box::new(WaylandService::new(self.context.clone(), self.heartbeat.clone()))
     }
 }
 
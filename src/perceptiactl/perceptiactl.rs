@@ -16,6 +16,8 @@ extern crate renderer_gl;
 
 mod info;
 mod about;
+mod screenshot;
+mod select_region;
 
 fn main() {
     timber::init(std::path::Path::new("/dev/null")).unwrap();
@@ -29,6 +31,34 @@ fn main() {
             .about("Prints basic information about system."))
         .subcommand(clap::SubCommand::with_name("about")
             .about("Prints information about this program."))
+        .subcommand(clap::SubCommand::with_name("screenshot")
+            .about("Saves screenshot of output, surface or region to PNG file.")
+            .arg(clap::Arg::with_name("output")
+                .long("output")
+                .takes_value(true)
+                .value_name("NAME")
+                .help("Name of output to take screenshot of"))
+            .arg(clap::Arg::with_name("surface")
+                .long("surface")
+                .takes_value(true)
+                .value_name("ID")
+                .help("ID of surface to take screenshot of"))
+            .arg(clap::Arg::with_name("region")
+                .long("region")
+                .takes_value(true)
+                .value_name("X,Y,WIDTH,HEIGHT")
+                .help("Area in global coordinates to take screenshot of"))
+            .arg(clap::Arg::with_name("select")
+                .long("select")
+                .help("Lets user select area to take screenshot of with pointer"))
+            .group(clap::ArgGroup::with_name("target")
+                .args(&["output", "surface", "region", "select"])
+                .required(true))
+            .arg(clap::Arg::with_name("file")
+                .required(true)
+                .help("Path to PNG file or '-' for standard output")))
+        .subcommand(clap::SubCommand::with_name("select-region")
+            .about("Lets user select area with pointer and prints it as X,Y,WIDTH,HEIGHT."))
         .get_matches();
 
     match matches.subcommand() {
@@ -38,6 +68,12 @@ fn main() {
         ("about", Some(_)) => {
             about::process();
         }
+        ("screenshot", Some(matches)) => {
+            screenshot::process(matches);
+        }
+        ("select-region", Some(_)) => {
+            select_region::process();
+        }
         _ => println!("Error during parsing arguments!"),
     }
 }
@@ -0,0 +1,58 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+use std;
+use std::io::Write;
+
+use clap;
+
+use qualia::{control, ControlReply, ControlRequest};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Requests screenshot from running compositor and writes it to given file or standard output.
+pub fn process(matches: &clap::ArgMatches) {
+    let target = if let Some(name) = matches.value_of("output") {
+        format!("output {}", name)
+    } else if let Some(sid) = matches.value_of("surface") {
+        format!("surface {}", sid)
+    } else if let Some(region) = matches.value_of("region") {
+        format!("region {}", region.replace(',', " "))
+    } else if matches.is_present("select") {
+        "select".to_owned()
+    } else {
+        fail("No screenshot target given".to_owned())
+    };
+
+    let request = match ControlRequest::parse(&format!("screenshot {}", target)) {
+        Ok(request) => request,
+        Err(err) => fail(format!("{}", err)),
+    };
+
+    let image = match control::send_request(&request) {
+        Ok(ControlReply::Image(image)) => image,
+        Ok(ControlReply::Error(message)) => fail(message),
+        Ok(_) => fail("Unexpected reply from Perceptia".to_owned()),
+        Err(err) => fail(format!("Failed to communicate with Perceptia: {}", err)),
+    };
+
+    let path = matches.value_of("file").unwrap_or("-");
+    let result = if path == "-" {
+        std::io::stdout().write_all(&image)
+    } else {
+        std::fs::File::create(path).and_then(|mut file| file.write_all(&image))
+    };
+    if let Err(err) = result {
+        fail(format!("Failed to write {}: {}", path, err));
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Prints error and exits with failure.
+pub fn fail(message: String) -> ! {
+    writeln!(std::io::stderr(), "Error: {}", message).ok();
+    std::process::exit(1);
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,23 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+use qualia::{control, ControlReply, ControlRequest};
+
+use screenshot::fail;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Lets user select region of screen in running compositor and prints it in format accepted by
+/// `--region` option of `screenshot` subcommand.
+pub fn process() {
+    let area = match control::send_request(&ControlRequest::SelectRegion) {
+        Ok(ControlReply::Region(area)) => area,
+        Ok(ControlReply::Error(message)) => fail(message),
+        Ok(_) => fail("Unexpected reply from Perceptia".to_owned()),
+        Err(err) => fail(format!("Failed to communicate with Perceptia: {}", err)),
+    };
+
+    println!("{},{},{},{}", area.pos.x, area.pos.y, area.size.width, area.size.height);
+}
+
+// -------------------------------------------------------------------------------------------------
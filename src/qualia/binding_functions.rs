@@ -60,6 +60,14 @@ pub trait InputContext {
 
     /// Just like `get_code` but returns number if number key was pressed, `None` otherwise.
     fn get_code_as_number(&self) -> Option<i32>;
+
+    /// Just like `get_code` but returns number of function key (e.g. 5 for `F5`) if function key
+    /// was pressed, `None` otherwise.
+    fn get_code_as_function_number(&self) -> Option<i32>;
+
+    /// Just like `get_code` but returns name of media key (e.g. `play_pause`) if media key was
+    /// pressed, `None` otherwise.
+    fn get_code_as_media_key(&self) -> Option<&'static str>;
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -105,6 +113,164 @@ pub fn quit(context: &mut InputContext) {
 
 // -------------------------------------------------------------------------------------------------
 
+/// Restarts the application in place keeping Wayland display socket open.
+#[allow(unused_variables)]
+pub fn restart(context: &mut InputContext) {
+    functions::restart();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Switches to virtual terminal with number of pressed function key.
+///
+/// E.g. if key [F2] was pressed, will switch to second virtual terminal.
+pub fn switch_vt(context: &mut InputContext) {
+    if let Some(number) = context.get_code_as_function_number() {
+        context.set_action(Action::Terminal);
+        context.set_direction(Direction::Forward);
+        context.set_magnitude(number);
+        context.execute_command();
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Performs action configured for pressed media key.
+///
+/// E.g. if key [XF86AudioRaiseVolume] was pressed, will execute command configured as `volume_up`.
+pub fn media_key(context: &mut InputContext) {
+    if let Some(name) = context.get_code_as_media_key() {
+        context.set_action(Action::Media);
+        context.set_string(name.to_owned());
+        context.execute_command();
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Saves contents of all displays to PNG files in data directory.
+pub fn take_screenshot(context: &mut InputContext) {
+    context.set_action(Action::Screenshot);
+    context.execute_command();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Reloads configuration file by sending `SIGHUP` to itself.
+#[allow(unused_variables)]
+pub fn reload_config(context: &mut InputContext) {
+    functions::reload_config();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Switches to next redraw mode (continuous, damage-only, single-step). Used for debugging.
+pub fn cycle_redraw_mode(context: &mut InputContext) {
+    context.set_action(Action::Redraw);
+    context.set_direction(Direction::Forward);
+    context.execute_command();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Toggles tinting of regions repainted in last frames. Used for debugging.
+pub fn toggle_damage_visualization(context: &mut InputContext) {
+    context.set_action(Action::Redraw);
+    context.set_direction(Direction::Begin);
+    context.execute_command();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Draws one frame when in single-step redraw mode. Used for debugging.
+pub fn redraw_step(context: &mut InputContext) {
+    context.set_action(Action::Redraw);
+    context.set_direction(Direction::End);
+    context.execute_command();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Enables or disables logging of every emitted signal. Used for debugging.
+pub fn toggle_signal_tracing(context: &mut InputContext) {
+    context.set_action(Action::Trace);
+    context.set_direction(Direction::Forward);
+    context.execute_command();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Logs list of signals with number of their subscribers. Used for debugging.
+pub fn log_subscriptions(context: &mut InputContext) {
+    context.set_action(Action::Trace);
+    context.set_direction(Direction::Begin);
+    context.execute_command();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Switches to next keyboard layout.
+pub fn next_keyboard_layout(context: &mut InputContext) {
+    context.set_action(Action::Layout);
+    context.set_direction(Direction::Forward);
+    context.execute_command();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Switches to previous keyboard layout.
+pub fn previous_keyboard_layout(context: &mut InputContext) {
+    context.set_action(Action::Layout);
+    context.set_direction(Direction::Backward);
+    context.execute_command();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Turns displays off if they are on or on if they are off.
+pub fn toggle_display_power(context: &mut InputContext) {
+    context.set_action(Action::Power);
+    context.set_direction(Direction::Forward);
+    context.execute_command();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Rotates display with pointer by further 90 degrees.
+pub fn rotate_display(context: &mut InputContext) {
+    context.set_action(Action::Rotate);
+    context.set_direction(Direction::Forward);
+    context.execute_command();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Restores normal orientation of display with pointer.
+pub fn reset_display_rotation(context: &mut InputContext) {
+    context.set_action(Action::Rotate);
+    context.set_direction(Direction::North);
+    context.execute_command();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Switches display with pointer to its next mode.
+pub fn next_display_mode(context: &mut InputContext) {
+    context.set_action(Action::Mode);
+    context.set_direction(Direction::Forward);
+    context.execute_command();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Scales focused window to fill display or restores its normal size.
+pub fn toggle_zoom(context: &mut InputContext) {
+    context.set_action(Action::Zoom);
+    context.execute_command();
+}
+
+// -------------------------------------------------------------------------------------------------
+
 /// Sets focus action in command but do not execute.
 pub fn put_focus(context: &mut InputContext) {
     put_action(context, Action::Focus);
@@ -202,6 +368,26 @@ pub fn stackize(context: &mut InputContext) {
 
 // -------------------------------------------------------------------------------------------------
 
+/// Shows next frame in stacked frame containing selection.
+pub fn next_tab(context: &mut InputContext) {
+    context.set_action(Action::Tab);
+    context.set_direction(Direction::Forward);
+    context.set_magnitude(1);
+    context.execute_command();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Shows previous frame in stacked frame containing selection.
+pub fn previous_tab(context: &mut InputContext) {
+    context.set_action(Action::Tab);
+    context.set_direction(Direction::Backward);
+    context.set_magnitude(1);
+    context.execute_command();
+}
+
+// -------------------------------------------------------------------------------------------------
+
 /// Executes command for circling surfaces forward.
 pub fn cicle_history_forward(context: &mut InputContext) {
     context.set_action(Action::Focus);
@@ -222,6 +408,46 @@ pub fn cicle_history_backward(context: &mut InputContext) {
 
 // -------------------------------------------------------------------------------------------------
 
+/// Executes command moving current workspace to next display.
+pub fn move_workspace_to_next_display(context: &mut InputContext) {
+    context.set_action(Action::Move);
+    context.set_direction(Direction::Forward);
+    context.set_magnitude(1);
+    context.execute_command();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Executes command moving current workspace to previous display.
+pub fn move_workspace_to_previous_display(context: &mut InputContext) {
+    context.set_action(Action::Move);
+    context.set_direction(Direction::Backward);
+    context.set_magnitude(1);
+    context.execute_command();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Executes command swapping current workspace with visible workspace of next display.
+pub fn swap_workspace_with_next_display(context: &mut InputContext) {
+    context.set_action(Action::Swap);
+    context.set_direction(Direction::Workspace);
+    context.set_magnitude(1);
+    context.execute_command();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Executes command swapping current workspace with visible workspace of previous display.
+pub fn swap_workspace_with_previous_display(context: &mut InputContext) {
+    context.set_action(Action::Swap);
+    context.set_direction(Direction::Workspace);
+    context.set_magnitude(-1);
+    context.execute_command();
+}
+
+// -------------------------------------------------------------------------------------------------
+
 /// Executes command focusing surface on the right.
 pub fn focus_right(context: &mut InputContext) {
     context.set_action(Action::Focus);
@@ -320,6 +546,31 @@ pub fn ramify(context: &mut InputContext) {
 
 // -------------------------------------------------------------------------------------------------
 
+/// Toggles selected frame between anchored and floating.
+pub fn toggle_anchor(context: &mut InputContext) {
+    context.set_action(Action::Anchor);
+    context.execute_command();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Minimizes selected frame.
+pub fn minimize(context: &mut InputContext) {
+    context.set_action(Action::Minimize);
+    context.execute_command();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Restores most recently minimized frame.
+pub fn restore_minimized(context: &mut InputContext) {
+    context.set_action(Action::Minimize);
+    context.set_direction(Direction::Backward);
+    context.execute_command();
+}
+
+// -------------------------------------------------------------------------------------------------
+
 /// Executes command diving into frame on the right.
 pub fn dive_right(context: &mut InputContext) {
     context.set_action(Action::Dive);
@@ -405,6 +656,71 @@ pub fn focus_workspace(context: &mut InputContext) {
 
 // -------------------------------------------------------------------------------------------------
 
+/// Creates new workspace on current display and focuses it.
+pub fn create_workspace(context: &mut InputContext) {
+    context.set_action(Action::Create);
+    context.set_direction(Direction::Workspace);
+    context.set_magnitude(1);
+    context.execute_command();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Focuses next workspace on current display.
+pub fn focus_next_workspace(context: &mut InputContext) {
+    context.set_action(Action::Focus);
+    context.set_direction(Direction::Workspace);
+    context.set_magnitude(1);
+    context.execute_command();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Focuses previous workspace on current display.
+pub fn focus_previous_workspace(context: &mut InputContext) {
+    context.set_action(Action::Focus);
+    context.set_direction(Direction::Workspace);
+    context.set_magnitude(-1);
+    context.execute_command();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Jumps selected frame to next workspace on current display (focuses workspace).
+pub fn dive_to_next_workspace(context: &mut InputContext) {
+    context.set_action(Action::Dive);
+    context.set_direction(Direction::Workspace);
+    context.set_magnitude(1);
+    context.execute_command();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Jumps selected frame to previous workspace on current display (focuses workspace).
+pub fn dive_to_previous_workspace(context: &mut InputContext) {
+    context.set_action(Action::Dive);
+    context.set_direction(Direction::Workspace);
+    context.set_magnitude(-1);
+    context.execute_command();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Renames current workspace basing on key code.
+///
+/// E.g. if key [5] was pressed, current workspace will be titled "5".
+pub fn rename_workspace(context: &mut InputContext) {
+    if let Some(number) = context.get_code_as_number() {
+        context.set_action(Action::Configure);
+        context.set_direction(Direction::Workspace);
+        context.set_magnitude(1);
+        context.set_string(number.to_string());
+        context.execute_command();
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
 /// Switches normal mode off and insert mode on.
 pub fn swap_mode_normal_to_insert(context: &mut InputContext) {
     log_info2!("Swap mode from normal to insert");
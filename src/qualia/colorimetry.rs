@@ -0,0 +1,174 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains description of colors of surfaces and outputs.
+//!
+//! Contents are assumed to be sRGB unless described otherwise. Description of HDR contents (e.g.
+//! HDR10 video) is passed to display in HDMI/DisplayPort infoframe when the contents are scanned
+//! out directly, so the display can map them to its capabilities itself.
+
+// -------------------------------------------------------------------------------------------------
+
+/// Size of `struct hdr_output_metadata` passed to `HDR_OUTPUT_METADATA` connector property.
+pub const HDR_OUTPUT_METADATA_SIZE: usize = 32;
+
+/// Value of `metadata_type` field of `struct hdr_output_metadata` for static metadata type 1.
+const HDMI_STATIC_METADATA_TYPE1: u8 = 0;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Color primaries and white point.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Primaries {
+    /// Primaries of sRGB (the same as of BT.709).
+    Srgb,
+
+    /// Wide gamut primaries of BT.2020 used by HDR10.
+    Bt2020,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Function mapping encoded values to light.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TransferFunction {
+    /// Transfer function of sRGB.
+    Srgb,
+
+    /// Perceptual quantizer (SMPTE ST 2084) used by HDR10.
+    Pq,
+
+    /// Hybrid log-gamma (ARIB STD-B67).
+    Hlg,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl TransferFunction {
+    /// Returns EOTF value of CTA-861 infoframe.
+    pub fn get_eotf(&self) -> u8 {
+        match *self {
+            TransferFunction::Srgb => 0,
+            TransferFunction::Pq => 2,
+            TransferFunction::Hlg => 3,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Static HDR metadata of mastering display and contents (SMPTE ST 2086 and CTA-861.3).
+///
+/// Chromaticity coordinates are in units of 0.00002, maximal luminance of mastering display and
+/// light levels of contents in nits and minimal luminance in units of 0.0001 nit.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HdrMetadata {
+    /// Coordinates of red, green and blue primaries of mastering display.
+    pub primaries: [(u16, u16); 3],
+
+    /// Coordinates of white point of mastering display.
+    pub white_point: (u16, u16),
+
+    /// Maximal luminance of mastering display.
+    pub max_luminance: u16,
+
+    /// Minimal luminance of mastering display.
+    pub min_luminance: u16,
+
+    /// Maximal content light level.
+    pub max_cll: u16,
+
+    /// Maximal frame-average light level.
+    pub max_fall: u16,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Description of colors of contents.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ColorDescription {
+    /// Color primaries of the contents.
+    pub primaries: Primaries,
+
+    /// Transfer function the contents are encoded with.
+    pub transfer: TransferFunction,
+
+    /// Static HDR metadata. `None` if not provided.
+    pub hdr_metadata: Option<HdrMetadata>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl ColorDescription {
+    /// Returns description of sRGB contents.
+    pub fn srgb() -> Self {
+        ColorDescription {
+            primaries: Primaries::Srgb,
+            transfer: TransferFunction::Srgb,
+            hdr_metadata: None,
+        }
+    }
+
+    /// Checks if contents use HDR transfer function.
+    pub fn is_hdr(&self) -> bool {
+        self.transfer != TransferFunction::Srgb
+    }
+
+    /// Returns contents of `struct hdr_output_metadata` describing these contents as expected by
+    /// `HDR_OUTPUT_METADATA` connector property. Fields are little-endian (native byte order of
+    /// supported platforms). Missing metadata is sent as zeros meaning "unknown".
+    pub fn to_hdr_output_metadata(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(HDR_OUTPUT_METADATA_SIZE);
+        push_u32(&mut data, HDMI_STATIC_METADATA_TYPE1 as u32);
+        data.push(self.transfer.get_eotf());
+        data.push(HDMI_STATIC_METADATA_TYPE1);
+
+        let metadata = self.hdr_metadata.unwrap_or(HdrMetadata {
+                                                       primaries: [(0, 0); 3],
+                                                       white_point: (0, 0),
+                                                       max_luminance: 0,
+                                                       min_luminance: 0,
+                                                       max_cll: 0,
+                                                       max_fall: 0,
+                                                   });
+        for &(x, y) in metadata.primaries.iter() {
+            push_u16(&mut data, x);
+            push_u16(&mut data, y);
+        }
+        push_u16(&mut data, metadata.white_point.0);
+        push_u16(&mut data, metadata.white_point.1);
+        push_u16(&mut data, metadata.max_luminance);
+        push_u16(&mut data, metadata.min_luminance);
+        push_u16(&mut data, metadata.max_cll);
+        push_u16(&mut data, metadata.max_fall);
+
+        // Padding to alignment of `u32`
+        data.resize(HDR_OUTPUT_METADATA_SIZE, 0);
+        data
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Default for ColorDescription {
+    fn default() -> Self {
+        ColorDescription::srgb()
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Appends little-endian `u16` to data.
+fn push_u16(data: &mut Vec<u8>, value: u16) {
+    data.push(value as u8);
+    data.push((value >> 8) as u8);
+}
+
+/// Appends little-endian `u32` to data.
+fn push_u32(data: &mut Vec<u8>, value: u32) {
+    for i in 0..4 {
+        data.push((value >> (8 * i)) as u8);
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
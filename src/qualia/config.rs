@@ -5,13 +5,31 @@
 
 // -------------------------------------------------------------------------------------------------
 
+use std::collections::HashMap;
 use std::default::Default;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
 use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use uinput_sys;
 
-use defs::{modifier, mode_name};
+use defs::{media_key, modifier, mode_name, KeyCode, OutputMode, Position, SeatId, Size};
+use enums::{SizeMismatchPolicy, Switch, Transform};
+use errors::Illusion;
 use input_manager::Binding;
+use key_binder::KeyCommand;
 use binding_functions;
+use config_parser;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Commands executed by media keys unless configured otherwise. Volume is controlled by PipeWire.
+const DEFAULT_MEDIA_KEY_COMMANDS: [(&'static str, &'static str); 4] =
+    [(media_key::VOLUME_UP, "wpctl set-volume -l 1.0 @DEFAULT_AUDIO_SINK@ 5%+"),
+     (media_key::VOLUME_DOWN, "wpctl set-volume @DEFAULT_AUDIO_SINK@ 5%-"),
+     (media_key::MUTE, "wpctl set-mute @DEFAULT_AUDIO_SINK@ toggle"),
+     (media_key::MIC_MUTE, "wpctl set-mute @DEFAULT_AUDIO_SOURCE@ toggle")];
 
 // -------------------------------------------------------------------------------------------------
 
@@ -57,12 +75,568 @@ impl BindingEntry {
 
 // -------------------------------------------------------------------------------------------------
 
+/// Helper structure used to keep configuration entry for one binding handled by `KeyBinder`.
+#[derive(Clone, Debug)]
+pub struct CommandEntry {
+    pub mode_name: String,
+    pub keysym: u32,
+    pub modifiers: modifier::ModifierType,
+    pub key_command: KeyCommand,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Profile of pointer acceleration.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AccelProfile {
+    /// Pointer moves proportionally to device motion.
+    Flat,
+
+    /// Pointer moves further when device moves faster.
+    Adaptive,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Method of scrolling with touchpad.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScrollMethod {
+    /// Touchpad does not scroll.
+    NoScroll,
+
+    /// Scrolling by moving two fingers.
+    TwoFinger,
+
+    /// Scrolling by moving one finger along right or bottom edge of touchpad.
+    Edge,
+}
+
+// -------------------------------------------------------------------------------------------------
+
 /// Configuration of input devices.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct InputConfig {
     pub touchpad_scale: f32,
     pub touchpad_pressure_threshold: i32,
     pub mouse_scale: f32,
+
+    /// Profile of acceleration of mouse motion.
+    pub accel_profile: AccelProfile,
+
+    /// Tells if scrolling direction should be reversed so content follows fingers.
+    pub natural_scrolling: bool,
+
+    /// Tells if tapping touchpad should click.
+    pub tap_to_click: bool,
+
+    /// Method of scrolling with touchpad.
+    pub scroll_method: ScrollMethod,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Configuration of repeating held keys.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KeyRepeatConfig {
+    /// Number of repeats per second. Zero disables repeating.
+    pub rate: u32,
+
+    /// Time in milliseconds key has to be held before it starts repeating.
+    pub delay: u32,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Identification of input device used to choose configuration for it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InputDeviceId {
+    /// Vendor ID of the device.
+    pub vendor: u16,
+
+    /// Product ID of the device.
+    pub product: u16,
+
+    /// Name reported by the device.
+    pub name: String,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl InputDeviceId {
+    /// Checks if given pattern matches the device. Pattern is `<vendor>:<product>` (IDs written as
+    /// four hexadecimal digits), name of the device or both separated by space. Returns `None` if
+    /// pattern does not match, otherwise rank of the match; more specific patterns rank higher.
+    pub fn match_pattern(&self, pattern: &str) -> Option<u32> {
+        let ids = format!("{:04x}:{:04x}", self.vendor, self.product);
+        if pattern == format!("{} {}", ids, self.name) {
+            Some(3)
+        } else if pattern == self.name {
+            Some(2)
+        } else if pattern.eq_ignore_ascii_case(&ids) {
+            Some(1)
+        } else {
+            None
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Configuration of input devices matching given pattern. Entries not present in configuration
+/// file are `None` and keep values of general input configuration.
+#[derive(Clone, Debug)]
+pub struct InputDeviceConfig {
+    /// Pattern matched against device identification. See `InputDeviceId::match_pattern`.
+    pub device: String,
+
+    pub touchpad_scale: Option<f32>,
+    pub touchpad_pressure_threshold: Option<i32>,
+    pub mouse_scale: Option<f32>,
+    pub accel_profile: Option<AccelProfile>,
+    pub natural_scrolling: Option<bool>,
+    pub tap_to_click: Option<bool>,
+    pub scroll_method: Option<ScrollMethod>,
+
+    /// Name of output touchscreen or tablet is mapped to.
+    pub output: Option<String>,
+
+    /// Calibration matrix applied to normalized coordinates of touchscreen or tablet.
+    pub calibration: Option<[f32; 6]>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Configuration of additional seat.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SeatConfig {
+    /// Name of the seat advertised to clients.
+    pub name: String,
+
+    /// Patterns of devices assigned to the seat. See `InputDeviceId::match_pattern`.
+    pub devices: Vec<String>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Mapping of absolute coordinates of touchscreen or tablet to output.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TouchConfig {
+    /// Name of output the device is mapped to. `None` means output the pointer is on.
+    pub output: Option<String>,
+
+    /// Calibration matrix `[a, b, c, d, e, f]` translating normalized coordinates to
+    /// `x' = a*x + b*y + c` and `y' = d*x + e*y + f`.
+    pub calibration: [f32; 6],
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl TouchConfig {
+    /// Applies calibration matrix to given normalized coordinates.
+    pub fn calibrate(&self, x: f32, y: f32) -> (f32, f32) {
+        let m = &self.calibration;
+        (m[0] * x + m[1] * y + m[2], m[3] * x + m[4] * y + m[5])
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Translation of one key into sequence of keys.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyRemapConfig {
+    /// Code of remapped key.
+    pub code: KeyCode,
+
+    /// Codes of keys the key is translated to. One key is pressed and released together with
+    /// remapped key. Longer sequence is a macro typed when remapped key is pressed.
+    pub sequence: Vec<KeyCode>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Configuration of keyboard devices.
+#[derive(Clone, Debug)]
+pub struct KeyboardConfig {
+    /// Name of the device this configuration applies to. `None` means all keyboards.
+    pub device: Option<String>,
+
+    /// Key translations applied before key bindings and `xkb` translation.
+    pub remaps: Vec<KeyRemapConfig>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Names of `xkb` components key map is constructed from.
+#[derive(Clone, Debug, PartialEq)]
+pub struct XkbConfig {
+    /// Name of rules file.
+    pub rules: String,
+
+    /// Keyboard model.
+    pub model: String,
+
+    /// Comma-separated list of layouts. Layouts can be cycled at runtime.
+    pub layout: String,
+
+    /// Comma-separated list of variants; one for every layout.
+    pub variant: String,
+
+    /// Comma-separated list of options. `None` means options defined by rules.
+    pub options: Option<String>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Default for XkbConfig {
+    fn default() -> Self {
+        XkbConfig {
+            rules: "evdev".to_owned(),
+            model: "evdev".to_owned(),
+            layout: "us".to_owned(),
+            variant: "".to_owned(),
+            options: None,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Configuration of access to privileged protocols.
+#[derive(Clone, Debug)]
+pub struct SecurityConfig {
+    /// Names of executables allowed to bind privileged globals (e.g. clipboard managers).
+    pub privileged_clients: Vec<String>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Configuration of actions performed when media keys are pressed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MediaKeysConfig {
+    /// Shell commands executed when media keys with given names (see `defs::media_key`) are
+    /// pressed. Empty command disables the key.
+    pub commands: HashMap<String, String>,
+
+    /// Tells if playback keys without configured command should control media player over MPRIS.
+    pub mpris: bool,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Configuration of endpoints exporting compositor metrics.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MetricsConfig {
+    /// Tells if metrics should be served on unix socket in runtime directory.
+    pub socket: bool,
+
+    /// Address (e.g. `127.0.0.1:9100`) of TCP socket on which metrics should be served over HTTP.
+    pub address: Option<String>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Configuration of window lifecycle journal.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct JournalConfig {
+    /// Tells if opening, closing and focusing windows should be recorded.
+    pub enabled: bool,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Configuration of detecting user inactivity.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IdleConfig {
+    /// Time in seconds without input after which user is considered idle. Zero disables idle
+    /// detection by compositor (clients may still request their own timeouts).
+    pub timeout: u32,
+
+    /// Tells if displays should be powered off when user becomes idle and powered back on with
+    /// next input.
+    pub power_off: bool,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Configuration of hiding the cursor.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CursorConfig {
+    /// Time in seconds without pointer motion after which the cursor is hidden. Zero disables
+    /// hiding.
+    pub hide_timeout: u32,
+
+    /// Tells if the cursor should be hidden while typing.
+    pub hide_while_typing: bool,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Configuration of hiding layer surfaces (e.g. docks) until pointer reaches their edge.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AutoHideConfig {
+    /// Namespaces of layer surfaces which are hidden automatically.
+    pub namespaces: Vec<String>,
+
+    /// Time in milliseconds pointer has to be held at the edge before surface is revealed.
+    pub reveal_delay: u64,
+
+    /// Time in milliseconds after pointer leaves the surface before it is concealed.
+    pub conceal_delay: u64,
+
+    /// Duration in milliseconds of sliding the surface in or out.
+    pub animation_time: u64,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Configuration of detecting stuck threads.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WatchdogConfig {
+    /// Time in seconds after which thread not responding is considered stuck. Zero disables
+    /// watchdog.
+    pub timeout: u32,
+
+    /// Tells if application should quit when thread gets stuck so that it can be restarted by
+    /// supervising service manager.
+    pub restart: bool,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Initial layout of workspace.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WorkspaceLayout {
+    /// Surfaces are placed on stack - only one is visible at a time.
+    Stacked,
+
+    /// Surfaces are placed in one row.
+    Vertical,
+
+    /// Surfaces are placed in one column.
+    Horizontal,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Configuration of workspace existing from start instead of being created on demand.
+#[derive(Clone, Debug)]
+pub struct WorkspaceConfig {
+    /// Title of the workspace.
+    pub name: String,
+
+    /// Name of output the workspace should be placed on whenever this output is present.
+    pub output: Option<String>,
+
+    /// Layout of the workspace when it is created.
+    pub layout: WorkspaceLayout,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Settings applied to one output when output profile is activated.
+#[derive(Clone, Debug)]
+pub struct OutputProfileConfig {
+    /// Name of the output.
+    pub name: String,
+
+    /// Resolution and optionally refresh rate of mode to be used.
+    pub mode: Option<OutputMode>,
+
+    /// Position of the output in global coordinates.
+    pub position: Option<Position>,
+
+    /// Scale of the output. May be fractional (e.g. 1.5).
+    pub scale: Option<f32>,
+
+    /// Rotation and flipping of the output.
+    pub transform: Option<Transform>,
+
+    /// Titles of workspaces to be placed on the output.
+    pub workspaces: Vec<String>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Named set of output settings activated when exactly the outputs it describes are connected.
+#[derive(Clone, Debug)]
+pub struct ProfileConfig {
+    /// Name of the profile.
+    pub name: String,
+
+    /// Settings of outputs.
+    pub outputs: Vec<OutputProfileConfig>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl ProfileConfig {
+    /// Checks if this profile describes exactly given set of outputs.
+    pub fn matches(&self, output_names: &[String]) -> bool {
+        (self.outputs.len() == output_names.len()) &&
+        output_names.iter().all(|name| self.outputs.iter().any(|output| output.name == *name))
+    }
+
+    /// Searches for settings of output with given name.
+    pub fn find_output(&self, output_name: &str) -> Option<&OutputProfileConfig> {
+        self.outputs.iter().find(|output| output.name == output_name)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Settings of capturing contents of one output to memory.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScreencastConfig {
+    /// Name of the captured output.
+    pub output: String,
+
+    /// Size of captured frames. Scene is scaled if it differs from size of the output.
+    pub size: Size,
+
+    /// Maximal number of captured frames per second.
+    pub rate: u32,
+
+    /// Tells if captured frames should be exported to shared memory for recording tools.
+    pub export: bool,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Action performed when physical switch is toggled.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SwitchAction {
+    /// Transform all outputs while switch is on and restore normal orientation when it is off.
+    RotateOutput(Transform),
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Configuration of reaction to toggling of one physical switch.
+#[derive(Clone, Debug)]
+pub struct SwitchConfig {
+    /// The switch.
+    pub switch: Switch,
+
+    /// Action performed when the switch is toggled.
+    pub action: SwitchAction,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Way background image is fitted to the screen.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WallpaperMode {
+    /// Image is scaled preserving aspect ratio to cover the whole screen and cut off at edges.
+    Fill,
+
+    /// Image is scaled preserving aspect ratio to fit the screen, leaving margins of background
+    /// color.
+    Fit,
+
+    /// Image is repeated in original size starting from top left corner.
+    Tile,
+
+    /// Image is placed in the middle in original size.
+    Center,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Configuration of background image.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WallpaperConfig {
+    /// Name of the output the image is displayed on. The image is used for all outputs without
+    /// own wallpaper if `None`.
+    pub output: Option<String>,
+
+    /// Path to PNG or JPEG file.
+    pub path: String,
+
+    /// Way the image is fitted to the screen.
+    pub mode: WallpaperMode,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Configuration of compositing.
+#[derive(Clone, Debug)]
+pub struct ExhibitorConfig {
+    /// Time in milliseconds dragged item has to be held at display edge to switch workspace.
+    pub edge_switch_delay: u64,
+
+    /// Tells if windows with equal minimal and maximal size should be made floating.
+    pub float_unresizable: bool,
+
+    /// Tells if interactive resize of floating windows should preserve their aspect ratio.
+    pub preserve_aspect_ratio: bool,
+
+    /// Tells if horizontal swipe with three or more fingers should switch workspaces.
+    pub swipe_workspaces: bool,
+
+    /// Tells if pinch with three or more fingers should toggle overview of workspaces.
+    pub pinch_overview: bool,
+
+    /// Tells if gestures not handled by compositor should be sent to clients.
+    pub forward_gestures: bool,
+
+    /// Maximal time in milliseconds between clicks making a double-click.
+    pub double_click_interval: u64,
+
+    /// Titles given to new workspaces in order. Workspaces are numbered when all are used.
+    pub workspace_names: Vec<String>,
+
+    /// Workspaces created at start.
+    pub workspaces: Vec<WorkspaceConfig>,
+
+    /// Output profiles in order of priority.
+    pub profiles: Vec<ProfileConfig>,
+
+    /// Capture of output contents. `None` if nothing should be captured.
+    pub screencast: Option<ScreencastConfig>,
+
+    /// IDs of applications surfaces of which are hidden in screen captures.
+    pub capture_excluded_apps: Vec<String>,
+
+    /// Tells if regions repainted in last frames should be tinted.
+    pub visualize_damage: bool,
+
+    /// Tells if colors should be blended in linear space instead of directly on sRGB values.
+    pub linear_blending: bool,
+
+    /// Fraction by which brightness and saturation of unfocused windows are reduced. Zero disables
+    /// dimming.
+    pub dim_unfocused: f32,
+
+    /// Tells if compositor should draw title bars and borders around windows of clients which do
+    /// not express their preference.
+    pub decorations: bool,
+
+    /// Tells how to draw surfaces whose size differs from size configured by compositor.
+    pub size_mismatch_policy: SizeMismatchPolicy,
+
+    /// Actions performed when physical switches are toggled.
+    pub switches: Vec<SwitchConfig>,
+
+    /// Background images. Later entries override earlier ones for the same output.
+    pub wallpapers: Vec<WallpaperConfig>,
+
+    /// Tells if outputs should follow orientation of the device while it is in tablet mode.
+    pub auto_rotation: bool,
+
+    /// Name of the output rotated automatically. All outputs are rotated if `None`.
+    pub auto_rotation_output: Option<String>,
+
+    /// Path to file surface history is saved in. History is not saved if `None`.
+    pub history_file: Option<String>,
+
+    /// Configuration of detecting user inactivity used to power displays off.
+    pub idle: IdleConfig,
+
+    /// Configuration of hiding the cursor.
+    pub cursor: CursorConfig,
+
+    /// Configuration of hiding layer surfaces.
+    pub auto_hide: AutoHideConfig,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -81,8 +655,134 @@ struct InnerConfig {
     /// In future will be replaced by non-linear scale per dimension.
     mouse_scale: f32,
 
+    /// Profile of acceleration of mouse motion.
+    accel_profile: AccelProfile,
+
+    /// Tells if scrolling direction should be reversed.
+    natural_scrolling: bool,
+
+    /// Tells if tapping touchpad should click.
+    tap_to_click: bool,
+
+    /// Method of scrolling with touchpad.
+    scroll_method: ScrollMethod,
+
+    /// Input configuration overriding general one for matching devices.
+    input_devices: Vec<InputDeviceConfig>,
+
+    /// Seats other than the default one.
+    seats: Vec<SeatConfig>,
+
+    /// IDs of applications receiving all keys while focused.
+    bypass_bindings_apps: Vec<String>,
+
+    /// Number of key repeats per second.
+    repeat_rate: u32,
+
+    /// Time in milliseconds key has to be held before it starts repeating.
+    repeat_delay: u32,
+
+    /// Configuration of keyboards in order of appearance in configuration file.
+    keyboards: Vec<KeyboardConfig>,
+
+    /// Names of `xkb` components key map is constructed from.
+    xkb: XkbConfig,
+
+    /// Time in milliseconds dragged item has to be held at display edge to switch workspace.
+    edge_switch_delay: u64,
+
+    /// Tells if windows with equal minimal and maximal size should be made floating.
+    float_unresizable: bool,
+
+    /// Tells if interactive resize of floating windows should preserve their aspect ratio.
+    preserve_aspect_ratio: bool,
+
+    /// Tells if swipe with three or more fingers should switch workspaces.
+    swipe_workspaces: bool,
+
+    /// Tells if pinch with three or more fingers should toggle overview of workspaces.
+    pinch_overview: bool,
+
+    /// Tells if gestures not handled by compositor should be sent to clients.
+    forward_gestures: bool,
+
+    /// Maximal time in milliseconds between clicks making a double-click.
+    double_click_interval: u64,
+
+    /// Titles given to new workspaces in order.
+    workspace_names: Vec<String>,
+
+    /// Workspaces created at start.
+    workspaces: Vec<WorkspaceConfig>,
+
+    /// Output profiles in order of priority.
+    profiles: Vec<ProfileConfig>,
+
+    /// Capture of output contents.
+    screencast: Option<ScreencastConfig>,
+
+    /// IDs of applications surfaces of which are hidden in screen captures.
+    capture_excluded_apps: Vec<String>,
+
+    /// Tells if regions repainted in last frames should be tinted.
+    visualize_damage: bool,
+
+    /// Tells if colors should be blended in linear space.
+    linear_blending: bool,
+
+    /// Fraction by which colors of unfocused windows are dimmed.
+    dim_unfocused: f32,
+
+    /// Tells if compositor should draw title bars and borders around windows.
+    decorations: bool,
+
+    /// Tells how to draw surfaces whose size differs from configured one.
+    size_mismatch_policy: SizeMismatchPolicy,
+
+    /// Actions performed when physical switches are toggled.
+    switches: Vec<SwitchConfig>,
+
+    /// Background images.
+    wallpapers: Vec<WallpaperConfig>,
+
+    /// Tells if outputs should follow orientation of the device while it is in tablet mode.
+    auto_rotation: bool,
+
+    /// Name of the output rotated automatically.
+    auto_rotation_output: Option<String>,
+
+    /// Path to file surface history is saved in.
+    history_file: Option<String>,
+
     /// Set of key bindings.
     bindings: Vec<BindingEntry>,
+
+    /// Set of bindings translating key symbols to commands.
+    commands: Vec<CommandEntry>,
+
+    /// Names of executables allowed to bind privileged globals.
+    privileged_clients: Vec<String>,
+
+    /// Detecting stuck threads.
+    watchdog: WatchdogConfig,
+
+    /// Detecting user inactivity.
+    idle: IdleConfig,
+
+    /// Hiding the cursor.
+    cursor: CursorConfig,
+
+    /// Hiding layer surfaces.
+    auto_hide: AutoHideConfig,
+
+    /// Actions of media keys.
+    media_keys: MediaKeysConfig,
+
+    /// Exporting metrics.
+    metrics: MetricsConfig,
+
+    /// Window lifecycle journal.
+    journal: JournalConfig,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -91,11 +791,201 @@ struct InnerConfig {
 #[derive(Clone)]
 pub struct Config {
     inner: Arc<Mutex<InnerConfig>>,
+
+    /// Number of times the configuration was replaced. Lets holders of values copied from
+    /// configuration cheaply check if they are outdated.
+    generation: Arc<AtomicUsize>,
 }
 
 // -------------------------------------------------------------------------------------------------
 
 impl Config {
+    /// Reads configuration from file. Entries not present in file have default values. Key
+    /// bindings from file are added to default ones overriding them if they use the same keys.
+    pub fn load(path: &Path) -> Result<Self, Illusion> {
+        let mut text = String::new();
+        File::open(path)?.read_to_string(&mut text)?;
+        Self::parse(&text)
+    }
+
+    /// Parses configuration from given text. See `config_parser` for description of format.
+    pub fn parse(text: &str) -> Result<Self, Illusion> {
+        let parsed = config_parser::parse(text)?;
+        let config = Config::default();
+        {
+            let mut mine = config.inner.lock().unwrap();
+            if let Some(touchpad_scale) = parsed.touchpad_scale {
+                mine.touchpad_scale = touchpad_scale;
+            }
+            if let Some(touchpad_pressure_threshold) = parsed.touchpad_pressure_threshold {
+                mine.touchpad_pressure_threshold = touchpad_pressure_threshold;
+            }
+            if let Some(mouse_scale) = parsed.mouse_scale {
+                mine.mouse_scale = mouse_scale;
+            }
+            if let Some(accel_profile) = parsed.accel_profile {
+                mine.accel_profile = accel_profile;
+            }
+            if let Some(natural_scrolling) = parsed.natural_scrolling {
+                mine.natural_scrolling = natural_scrolling;
+            }
+            if let Some(tap_to_click) = parsed.tap_to_click {
+                mine.tap_to_click = tap_to_click;
+            }
+            if let Some(scroll_method) = parsed.scroll_method {
+                mine.scroll_method = scroll_method;
+            }
+            if let Some(bypass_bindings_apps) = parsed.bypass_bindings_apps {
+                mine.bypass_bindings_apps = bypass_bindings_apps;
+            }
+            if let Some(repeat_rate) = parsed.repeat_rate {
+                mine.repeat_rate = repeat_rate;
+            }
+            if let Some(repeat_delay) = parsed.repeat_delay {
+                mine.repeat_delay = repeat_delay;
+            }
+            if let Some(edge_switch_delay) = parsed.edge_switch_delay {
+                mine.edge_switch_delay = edge_switch_delay;
+            }
+            if let Some(float_unresizable) = parsed.float_unresizable {
+                mine.float_unresizable = float_unresizable;
+            }
+            if let Some(preserve_aspect_ratio) = parsed.preserve_aspect_ratio {
+                mine.preserve_aspect_ratio = preserve_aspect_ratio;
+            }
+            if let Some(swipe_workspaces) = parsed.swipe_workspaces {
+                mine.swipe_workspaces = swipe_workspaces;
+            }
+            if let Some(pinch_overview) = parsed.pinch_overview {
+                mine.pinch_overview = pinch_overview;
+            }
+            if let Some(forward_gestures) = parsed.forward_gestures {
+                mine.forward_gestures = forward_gestures;
+            }
+            if let Some(double_click_interval) = parsed.double_click_interval {
+                mine.double_click_interval = double_click_interval;
+            }
+            if let Some(workspace_names) = parsed.workspace_names {
+                mine.workspace_names = workspace_names;
+            }
+            mine.input_devices.extend(parsed.input_devices);
+            mine.seats.extend(parsed.seats);
+            mine.keyboards.extend(parsed.keyboards);
+            if let Some(xkb_rules) = parsed.xkb_rules {
+                mine.xkb.rules = xkb_rules;
+            }
+            if let Some(xkb_model) = parsed.xkb_model {
+                mine.xkb.model = xkb_model;
+            }
+            if let Some(xkb_layout) = parsed.xkb_layout {
+                mine.xkb.layout = xkb_layout;
+            }
+            if let Some(xkb_variant) = parsed.xkb_variant {
+                mine.xkb.variant = xkb_variant;
+            }
+            if let Some(xkb_options) = parsed.xkb_options {
+                mine.xkb.options = Some(xkb_options);
+            }
+            mine.workspaces.extend(parsed.workspaces);
+            mine.profiles.extend(parsed.profiles);
+            mine.switches.extend(parsed.switches);
+            mine.wallpapers.extend(parsed.wallpapers);
+            if let Some(screencast) = parsed.screencast {
+                mine.screencast = Some(screencast);
+            }
+            if let Some(visualize_damage) = parsed.visualize_damage {
+                mine.visualize_damage = visualize_damage;
+            }
+            if let Some(linear_blending) = parsed.linear_blending {
+                mine.linear_blending = linear_blending;
+            }
+            if let Some(dim_unfocused) = parsed.dim_unfocused {
+                mine.dim_unfocused = dim_unfocused;
+            }
+            if let Some(decorations) = parsed.decorations {
+                mine.decorations = decorations;
+            }
+            if let Some(size_mismatch_policy) = parsed.size_mismatch_policy {
+                mine.size_mismatch_policy = size_mismatch_policy;
+            }
+            if let Some(auto_rotation) = parsed.auto_rotation {
+                mine.auto_rotation = auto_rotation;
+            }
+            if let Some(auto_rotation_output) = parsed.auto_rotation_output {
+                mine.auto_rotation_output = Some(auto_rotation_output);
+            }
+            if let Some(history_file) = parsed.history_file {
+                mine.history_file = Some(history_file);
+            }
+            if let Some(privileged_clients) = parsed.privileged_clients {
+                mine.privileged_clients = privileged_clients;
+            }
+            if let Some(watchdog_timeout) = parsed.watchdog_timeout {
+                mine.watchdog.timeout = watchdog_timeout;
+            }
+            if let Some(watchdog_restart) = parsed.watchdog_restart {
+                mine.watchdog.restart = watchdog_restart;
+            }
+            if let Some(idle_timeout) = parsed.idle_timeout {
+                mine.idle.timeout = idle_timeout;
+            }
+            if let Some(idle_power_off) = parsed.idle_power_off {
+                mine.idle.power_off = idle_power_off;
+            }
+            if let Some(cursor_hide_timeout) = parsed.cursor_hide_timeout {
+                mine.cursor.hide_timeout = cursor_hide_timeout;
+            }
+            if let Some(cursor_hide_while_typing) = parsed.cursor_hide_while_typing {
+                mine.cursor.hide_while_typing = cursor_hide_while_typing;
+            }
+            if let Some(auto_hide_namespaces) = parsed.auto_hide_namespaces {
+                mine.auto_hide.namespaces = auto_hide_namespaces;
+            }
+            if let Some(auto_hide_reveal_delay) = parsed.auto_hide_reveal_delay {
+                mine.auto_hide.reveal_delay = auto_hide_reveal_delay;
+            }
+            if let Some(auto_hide_conceal_delay) = parsed.auto_hide_conceal_delay {
+                mine.auto_hide.conceal_delay = auto_hide_conceal_delay;
+            }
+            if let Some(auto_hide_animation_time) = parsed.auto_hide_animation_time {
+                mine.auto_hide.animation_time = auto_hide_animation_time;
+            }
+            if let Some(capture_excluded_apps) = parsed.capture_excluded_apps {
+                mine.capture_excluded_apps = capture_excluded_apps;
+            }
+            if let Some(media_keys_mpris) = parsed.media_keys_mpris {
+                mine.media_keys.mpris = media_keys_mpris;
+            }
+            mine.media_keys.commands.extend(parsed.media_keys_commands);
+            if let Some(metrics_socket) = parsed.metrics_socket {
+                mine.metrics.socket = metrics_socket;
+            }
+            if let Some(metrics_address) = parsed.metrics_address {
+                mine.metrics.address = Some(metrics_address);
+            }
+            if let Some(journal_enabled) = parsed.journal_enabled {
+                mine.journal.enabled = journal_enabled;
+            }
+            mine.bindings.extend(parsed.bindings);
+            mine.commands.extend(parsed.commands);
+        }
+        Ok(config)
+    }
+
+    /// Replaces contents of this configuration with contents of `other`. All clones of this
+    /// configuration will see new values.
+    pub fn replace(&self, other: &Config) {
+        let new_inner = other.inner.lock().unwrap().clone();
+        let mut mine = self.inner.lock().unwrap();
+        *mine = new_inner;
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Returns number of times this configuration was replaced.
+    pub fn get_generation(&self) -> usize {
+        self.generation.load(Ordering::SeqCst)
+    }
+
     /// Returns configuration for input devices.
     pub fn get_input_config(&self) -> InputConfig {
         let mine = self.inner.lock().unwrap();
@@ -103,7 +993,198 @@ impl Config {
             touchpad_scale: mine.touchpad_scale,
             touchpad_pressure_threshold: mine.touchpad_pressure_threshold,
             mouse_scale: mine.mouse_scale,
+            accel_profile: mine.accel_profile,
+            natural_scrolling: mine.natural_scrolling,
+            tap_to_click: mine.tap_to_click,
+            scroll_method: mine.scroll_method,
+        }
+    }
+
+    /// Returns configuration for input device with given identification. Entries from blocks
+    /// matching the device override general ones; more specific blocks are applied last.
+    pub fn get_input_config_for_device(&self, device: &InputDeviceId) -> InputConfig {
+        let mine = self.inner.lock().unwrap();
+        let mut config = InputConfig {
+            touchpad_scale: mine.touchpad_scale,
+            touchpad_pressure_threshold: mine.touchpad_pressure_threshold,
+            mouse_scale: mine.mouse_scale,
+            accel_profile: mine.accel_profile,
+            natural_scrolling: mine.natural_scrolling,
+            tap_to_click: mine.tap_to_click,
+            scroll_method: mine.scroll_method,
+        };
+
+        let mut matching: Vec<(u32, &InputDeviceConfig)> = mine.input_devices
+            .iter()
+            .filter_map(|entry| device.match_pattern(&entry.device).map(|rank| (rank, entry)))
+            .collect();
+        matching.sort_by_key(|&(rank, _)| rank);
+
+        for (_, entry) in matching {
+            if let Some(touchpad_scale) = entry.touchpad_scale {
+                config.touchpad_scale = touchpad_scale;
+            }
+            if let Some(touchpad_pressure_threshold) = entry.touchpad_pressure_threshold {
+                config.touchpad_pressure_threshold = touchpad_pressure_threshold;
+            }
+            if let Some(mouse_scale) = entry.mouse_scale {
+                config.mouse_scale = mouse_scale;
+            }
+            if let Some(accel_profile) = entry.accel_profile {
+                config.accel_profile = accel_profile;
+            }
+            if let Some(natural_scrolling) = entry.natural_scrolling {
+                config.natural_scrolling = natural_scrolling;
+            }
+            if let Some(tap_to_click) = entry.tap_to_click {
+                config.tap_to_click = tap_to_click;
+            }
+            if let Some(scroll_method) = entry.scroll_method {
+                config.scroll_method = scroll_method;
+            }
         }
+        config
+    }
+
+    /// Returns mapping of touchscreen or tablet with given identification. Entries from more
+    /// specific matching blocks take precedence.
+    pub fn get_touch_config_for_device(&self, device: &InputDeviceId) -> TouchConfig {
+        let mine = self.inner.lock().unwrap();
+        let mut config = TouchConfig {
+            output: None,
+            calibration: [1.0, 0.0, 0.0, 0.0, 1.0, 0.0],
+        };
+
+        let mut matching: Vec<(u32, &InputDeviceConfig)> = mine.input_devices
+            .iter()
+            .filter_map(|entry| device.match_pattern(&entry.device).map(|rank| (rank, entry)))
+            .collect();
+        matching.sort_by_key(|&(rank, _)| rank);
+
+        for (_, entry) in matching {
+            if let Some(ref output) = entry.output {
+                config.output = Some(output.clone());
+            }
+            if let Some(calibration) = entry.calibration {
+                config.calibration = calibration;
+            }
+        }
+        config
+    }
+
+    /// Returns configuration of seats other than the default one. Seat described by `n`-th entry
+    /// has index `n + 1`.
+    pub fn get_seat_configs(&self) -> Vec<SeatConfig> {
+        let mine = self.inner.lock().unwrap();
+        mine.seats.clone()
+    }
+
+    /// Returns ID of seat device with given identification is assigned to. Devices not assigned
+    /// to any configured seat belong to the default seat.
+    pub fn get_seat_for_device(&self, device: &InputDeviceId) -> SeatId {
+        let mine = self.inner.lock().unwrap();
+        mine.seats
+            .iter()
+            .position(|seat| {
+                seat.devices.iter().any(|pattern| device.match_pattern(pattern).is_some())
+            })
+            .map_or(SeatId::default_seat(), |index| SeatId::new(index + 1))
+    }
+
+    /// Returns IDs of applications for which key bindings are not executed while they have
+    /// keyboard focus.
+    pub fn get_bypass_bindings_apps(&self) -> Vec<String> {
+        let mine = self.inner.lock().unwrap();
+        mine.bypass_bindings_apps.clone()
+    }
+
+    /// Returns configuration of repeating held keys.
+    pub fn get_key_repeat_config(&self) -> KeyRepeatConfig {
+        let mine = self.inner.lock().unwrap();
+        KeyRepeatConfig {
+            rate: mine.repeat_rate,
+            delay: mine.repeat_delay,
+        }
+    }
+
+    /// Returns configuration of keyboards.
+    pub fn get_keyboard_configs(&self) -> Vec<KeyboardConfig> {
+        let mine = self.inner.lock().unwrap();
+        mine.keyboards.clone()
+    }
+
+    /// Returns names of `xkb` components key map should be constructed from.
+    pub fn get_xkb_config(&self) -> XkbConfig {
+        let mine = self.inner.lock().unwrap();
+        mine.xkb.clone()
+    }
+
+    /// Returns configuration of compositing.
+    pub fn get_exhibitor_config(&self) -> ExhibitorConfig {
+        let mine = self.inner.lock().unwrap();
+        ExhibitorConfig {
+            edge_switch_delay: mine.edge_switch_delay,
+            float_unresizable: mine.float_unresizable,
+            preserve_aspect_ratio: mine.preserve_aspect_ratio,
+            swipe_workspaces: mine.swipe_workspaces,
+            pinch_overview: mine.pinch_overview,
+            forward_gestures: mine.forward_gestures,
+            double_click_interval: mine.double_click_interval,
+            workspace_names: mine.workspace_names.clone(),
+            workspaces: mine.workspaces.clone(),
+            profiles: mine.profiles.clone(),
+            screencast: mine.screencast.clone(),
+            capture_excluded_apps: mine.capture_excluded_apps.clone(),
+            visualize_damage: mine.visualize_damage,
+            linear_blending: mine.linear_blending,
+            dim_unfocused: mine.dim_unfocused,
+            decorations: mine.decorations,
+            size_mismatch_policy: mine.size_mismatch_policy,
+            switches: mine.switches.clone(),
+            wallpapers: mine.wallpapers.clone(),
+            auto_rotation: mine.auto_rotation,
+            auto_rotation_output: mine.auto_rotation_output.clone(),
+            history_file: mine.history_file.clone(),
+            idle: mine.idle,
+            cursor: mine.cursor,
+            auto_hide: mine.auto_hide.clone(),
+        }
+    }
+
+    /// Returns configuration of access to privileged protocols.
+    pub fn get_security_config(&self) -> SecurityConfig {
+        let mine = self.inner.lock().unwrap();
+        SecurityConfig { privileged_clients: mine.privileged_clients.clone() }
+    }
+
+    /// Returns configuration of detecting stuck threads.
+    pub fn get_watchdog_config(&self) -> WatchdogConfig {
+        let mine = self.inner.lock().unwrap();
+        mine.watchdog
+    }
+
+    /// Returns configuration of detecting user inactivity.
+    pub fn get_idle_config(&self) -> IdleConfig {
+        let mine = self.inner.lock().unwrap();
+        mine.idle
+    }
+
+    /// Returns configuration of actions of media keys.
+    pub fn get_media_keys_config(&self) -> MediaKeysConfig {
+        let mine = self.inner.lock().unwrap();
+        mine.media_keys.clone()
+    }
+
+    /// Returns configuration of exporting metrics.
+    pub fn get_metrics_config(&self) -> MetricsConfig {
+        let mine = self.inner.lock().unwrap();
+        mine.metrics.clone()
+    }
+
+    /// Returns configuration of window lifecycle journal.
+    pub fn get_journal_config(&self) -> JournalConfig {
+        let mine = self.inner.lock().unwrap();
+        mine.journal
     }
 
     /// Returns configuration for key binding.
@@ -111,6 +1192,12 @@ impl Config {
         let mine = self.inner.lock().unwrap();
         mine.bindings.clone()
     }
+
+    /// Returns configuration for key binder.
+    pub fn get_command_binding_config(&self) -> Vec<CommandEntry> {
+        let mine = self.inner.lock().unwrap();
+        mine.commands.clone()
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -122,12 +1209,157 @@ impl Default for Config {
                 touchpad_scale: 0.5,
                 touchpad_pressure_threshold: 70,
                 mouse_scale: 1.0,
+                accel_profile: AccelProfile::Adaptive,
+                natural_scrolling: false,
+                tap_to_click: true,
+                scroll_method: ScrollMethod::TwoFinger,
+                input_devices: Vec::new(),
+                seats: Vec::new(),
+                bypass_bindings_apps: Vec::new(),
+                repeat_rate: 25,
+                repeat_delay: 600,
+                keyboards: Vec::new(),
+                xkb: XkbConfig::default(),
+                edge_switch_delay: 500,
+                float_unresizable: true,
+                preserve_aspect_ratio: false,
+                swipe_workspaces: true,
+                pinch_overview: true,
+                forward_gestures: true,
+                double_click_interval: 400,
+                workspace_names: Vec::new(),
+                workspaces: Vec::new(),
+                profiles: Vec::new(),
+                screencast: None,
+                capture_excluded_apps: Vec::new(),
+                visualize_damage: false,
+                linear_blending: true,
+                dim_unfocused: 0.0,
+                decorations: false,
+                size_mismatch_policy: SizeMismatchPolicy::Clip,
+                switches: Vec::new(),
+                wallpapers: Vec::new(),
+                auto_rotation: true,
+                auto_rotation_output: None,
+                history_file: None,
                 bindings: vec![
                     // common
                     BindingEntry::new(mode_name::COMMON,
                                       uinput_sys::KEY_ESC,
                                       modifier::LCTL | modifier::LMTA,
                                       binding_functions::quit),
+                    BindingEntry::new(mode_name::COMMON,
+                                      uinput_sys::KEY_F5,
+                                      modifier::LCTL | modifier::LMTA,
+                                      binding_functions::reload_config),
+                    BindingEntry::new(mode_name::COMMON,
+                                      uinput_sys::KEY_SYSRQ,
+                                      modifier::LCTL | modifier::LMTA,
+                                      binding_functions::take_screenshot),
+                    BindingEntry::new(mode_name::COMMON,
+                                      uinput_sys::KEY_SPACE,
+                                      modifier::LMTA,
+                                      binding_functions::next_keyboard_layout),
+                    BindingEntry::new(mode_name::COMMON,
+                                      uinput_sys::KEY_SPACE,
+                                      modifier::LMTA | modifier::LSHF,
+                                      binding_functions::previous_keyboard_layout),
+                    BindingEntry::new(mode_name::COMMON,
+                                      uinput_sys::KEY_F9,
+                                      modifier::LCTL | modifier::LMTA,
+                                      binding_functions::toggle_signal_tracing),
+                    BindingEntry::new(mode_name::COMMON,
+                                      uinput_sys::KEY_F10,
+                                      modifier::LCTL | modifier::LMTA,
+                                      binding_functions::toggle_damage_visualization),
+                    BindingEntry::new(mode_name::COMMON,
+                                      uinput_sys::KEY_F11,
+                                      modifier::LCTL | modifier::LMTA,
+                                      binding_functions::cycle_redraw_mode),
+                    BindingEntry::new(mode_name::COMMON,
+                                      uinput_sys::KEY_F12,
+                                      modifier::LCTL | modifier::LMTA,
+                                      binding_functions::redraw_step),
+                    BindingEntry::new(mode_name::COMMON,
+                                      uinput_sys::KEY_VOLUMEUP,
+                                      modifier::NONE,
+                                      binding_functions::media_key),
+                    BindingEntry::new(mode_name::COMMON,
+                                      uinput_sys::KEY_VOLUMEDOWN,
+                                      modifier::NONE,
+                                      binding_functions::media_key),
+                    BindingEntry::new(mode_name::COMMON,
+                                      uinput_sys::KEY_MUTE,
+                                      modifier::NONE,
+                                      binding_functions::media_key),
+                    BindingEntry::new(mode_name::COMMON,
+                                      uinput_sys::KEY_MICMUTE,
+                                      modifier::NONE,
+                                      binding_functions::media_key),
+                    BindingEntry::new(mode_name::COMMON,
+                                      uinput_sys::KEY_PLAYPAUSE,
+                                      modifier::NONE,
+                                      binding_functions::media_key),
+                    BindingEntry::new(mode_name::COMMON,
+                                      uinput_sys::KEY_NEXTSONG,
+                                      modifier::NONE,
+                                      binding_functions::media_key),
+                    BindingEntry::new(mode_name::COMMON,
+                                      uinput_sys::KEY_PREVIOUSSONG,
+                                      modifier::NONE,
+                                      binding_functions::media_key),
+                    BindingEntry::new(mode_name::COMMON,
+                                      uinput_sys::KEY_STOPCD,
+                                      modifier::NONE,
+                                      binding_functions::media_key),
+                    BindingEntry::new(mode_name::COMMON,
+                                      uinput_sys::KEY_F1,
+                                      modifier::LCTL | modifier::LALT,
+                                      binding_functions::switch_vt),
+                    BindingEntry::new(mode_name::COMMON,
+                                      uinput_sys::KEY_F2,
+                                      modifier::LCTL | modifier::LALT,
+                                      binding_functions::switch_vt),
+                    BindingEntry::new(mode_name::COMMON,
+                                      uinput_sys::KEY_F3,
+                                      modifier::LCTL | modifier::LALT,
+                                      binding_functions::switch_vt),
+                    BindingEntry::new(mode_name::COMMON,
+                                      uinput_sys::KEY_F4,
+                                      modifier::LCTL | modifier::LALT,
+                                      binding_functions::switch_vt),
+                    BindingEntry::new(mode_name::COMMON,
+                                      uinput_sys::KEY_F5,
+                                      modifier::LCTL | modifier::LALT,
+                                      binding_functions::switch_vt),
+                    BindingEntry::new(mode_name::COMMON,
+                                      uinput_sys::KEY_F6,
+                                      modifier::LCTL | modifier::LALT,
+                                      binding_functions::switch_vt),
+                    BindingEntry::new(mode_name::COMMON,
+                                      uinput_sys::KEY_F7,
+                                      modifier::LCTL | modifier::LALT,
+                                      binding_functions::switch_vt),
+                    BindingEntry::new(mode_name::COMMON,
+                                      uinput_sys::KEY_F8,
+                                      modifier::LCTL | modifier::LALT,
+                                      binding_functions::switch_vt),
+                    BindingEntry::new(mode_name::COMMON,
+                                      uinput_sys::KEY_F9,
+                                      modifier::LCTL | modifier::LALT,
+                                      binding_functions::switch_vt),
+                    BindingEntry::new(mode_name::COMMON,
+                                      uinput_sys::KEY_F10,
+                                      modifier::LCTL | modifier::LALT,
+                                      binding_functions::switch_vt),
+                    BindingEntry::new(mode_name::COMMON,
+                                      uinput_sys::KEY_F11,
+                                      modifier::LCTL | modifier::LALT,
+                                      binding_functions::switch_vt),
+                    BindingEntry::new(mode_name::COMMON,
+                                      uinput_sys::KEY_F12,
+                                      modifier::LCTL | modifier::LALT,
+                                      binding_functions::switch_vt),
                     // normal
                     BindingEntry::new(mode_name::NORMAL,
                                       uinput_sys::KEY_ESC,
@@ -179,6 +1411,18 @@ impl Default for Config {
                                       uinput_sys::KEY_END,
                                       modifier::NONE,
                                       binding_functions::ramify),
+                    BindingEntry::new(mode_name::NORMAL,
+                                      uinput_sys::KEY_A,
+                                      modifier::NONE,
+                                      binding_functions::toggle_anchor),
+                    BindingEntry::new(mode_name::NORMAL,
+                                      uinput_sys::KEY_M,
+                                      modifier::NONE,
+                                      binding_functions::minimize),
+                    BindingEntry::new(mode_name::NORMAL,
+                                      uinput_sys::KEY_M,
+                                      modifier::LSHF,
+                                      binding_functions::restore_minimized),
 
                     // directions
                     BindingEntry::new(mode_name::NORMAL,
@@ -236,6 +1480,32 @@ impl Default for Config {
                                       uinput_sys::KEY_TAB,
                                       modifier::LMTA | modifier::LSHF,
                                       binding_functions::cicle_history_backward),
+                    // switch tabs
+                    BindingEntry::new(mode_name::INSERT,
+                                      uinput_sys::KEY_RIGHTBRACE,
+                                      modifier::LMTA,
+                                      binding_functions::next_tab),
+                    BindingEntry::new(mode_name::INSERT,
+                                      uinput_sys::KEY_LEFTBRACE,
+                                      modifier::LMTA,
+                                      binding_functions::previous_tab),
+                    // move workspace between displays
+                    BindingEntry::new(mode_name::INSERT,
+                                      uinput_sys::KEY_DOT,
+                                      modifier::LMTA | modifier::LSHF,
+                                      binding_functions::move_workspace_to_next_display),
+                    BindingEntry::new(mode_name::INSERT,
+                                      uinput_sys::KEY_COMMA,
+                                      modifier::LMTA | modifier::LSHF,
+                                      binding_functions::move_workspace_to_previous_display),
+                    BindingEntry::new(mode_name::INSERT,
+                                      uinput_sys::KEY_DOT,
+                                      modifier::LMTA | modifier::LCTL,
+                                      binding_functions::swap_workspace_with_next_display),
+                    BindingEntry::new(mode_name::INSERT,
+                                      uinput_sys::KEY_COMMA,
+                                      modifier::LMTA | modifier::LCTL,
+                                      binding_functions::swap_workspace_with_previous_display),
                     // focus workspace
                     BindingEntry::new(mode_name::INSERT,
                                       uinput_sys::KEY_1,
@@ -388,6 +1658,67 @@ impl Default for Config {
                                       uinput_sys::KEY_9,
                                       modifier::LMTA | modifier::LCTL | modifier::LSHF,
                                       binding_functions::dive_to_workspace),
+                    // managing workspaces
+                    BindingEntry::new(mode_name::INSERT,
+                                      uinput_sys::KEY_N,
+                                      modifier::LMTA,
+                                      binding_functions::create_workspace),
+                    BindingEntry::new(mode_name::INSERT,
+                                      uinput_sys::KEY_PAGEDOWN,
+                                      modifier::LMTA,
+                                      binding_functions::focus_next_workspace),
+                    BindingEntry::new(mode_name::INSERT,
+                                      uinput_sys::KEY_PAGEUP,
+                                      modifier::LMTA,
+                                      binding_functions::focus_previous_workspace),
+                    BindingEntry::new(mode_name::INSERT,
+                                      uinput_sys::KEY_PAGEDOWN,
+                                      modifier::LMTA | modifier::LSHF,
+                                      binding_functions::dive_to_next_workspace),
+                    BindingEntry::new(mode_name::INSERT,
+                                      uinput_sys::KEY_PAGEUP,
+                                      modifier::LMTA | modifier::LSHF,
+                                      binding_functions::dive_to_previous_workspace),
+                    BindingEntry::new(mode_name::INSERT,
+                                      uinput_sys::KEY_1,
+                                      modifier::LMTA | modifier::LCTL | modifier::LALT,
+                                      binding_functions::rename_workspace),
+                    BindingEntry::new(mode_name::INSERT,
+                                      uinput_sys::KEY_2,
+                                      modifier::LMTA | modifier::LCTL | modifier::LALT,
+                                      binding_functions::rename_workspace),
+                    BindingEntry::new(mode_name::INSERT,
+                                      uinput_sys::KEY_3,
+                                      modifier::LMTA | modifier::LCTL | modifier::LALT,
+                                      binding_functions::rename_workspace),
+                    BindingEntry::new(mode_name::INSERT,
+                                      uinput_sys::KEY_4,
+                                      modifier::LMTA | modifier::LCTL | modifier::LALT,
+                                      binding_functions::rename_workspace),
+                    BindingEntry::new(mode_name::INSERT,
+                                      uinput_sys::KEY_5,
+                                      modifier::LMTA | modifier::LCTL | modifier::LALT,
+                                      binding_functions::rename_workspace),
+                    BindingEntry::new(mode_name::INSERT,
+                                      uinput_sys::KEY_6,
+                                      modifier::LMTA | modifier::LCTL | modifier::LALT,
+                                      binding_functions::rename_workspace),
+                    BindingEntry::new(mode_name::INSERT,
+                                      uinput_sys::KEY_7,
+                                      modifier::LMTA | modifier::LCTL | modifier::LALT,
+                                      binding_functions::rename_workspace),
+                    BindingEntry::new(mode_name::INSERT,
+                                      uinput_sys::KEY_8,
+                                      modifier::LMTA | modifier::LCTL | modifier::LALT,
+                                      binding_functions::rename_workspace),
+                    BindingEntry::new(mode_name::INSERT,
+                                      uinput_sys::KEY_9,
+                                      modifier::LMTA | modifier::LCTL | modifier::LALT,
+                                      binding_functions::rename_workspace),
+                    BindingEntry::new(mode_name::INSERT,
+                                      uinput_sys::KEY_10,
+                                      modifier::LMTA | modifier::LCTL | modifier::LALT,
+                                      binding_functions::rename_workspace),
 
                     // other commands
                     BindingEntry::new(mode_name::INSERT,
@@ -399,7 +1730,41 @@ impl Default for Config {
                                       modifier::LMTA,
                                       binding_functions::ramify),
                 ],
+                commands: Vec::new(),
+                privileged_clients: vec![String::from("wl-copy"),
+                                         String::from("wl-paste"),
+                                         String::from("clipman")],
+                watchdog: WatchdogConfig {
+                    timeout: 10,
+                    restart: false,
+                },
+                idle: IdleConfig {
+                    timeout: 300,
+                    power_off: true,
+                },
+                cursor: CursorConfig {
+                    hide_timeout: 0,
+                    hide_while_typing: false,
+                },
+                auto_hide: AutoHideConfig {
+                    namespaces: Vec::new(),
+                    reveal_delay: 300,
+                    conceal_delay: 800,
+                    animation_time: 200,
+                },
+                media_keys: MediaKeysConfig {
+                    commands: DEFAULT_MEDIA_KEY_COMMANDS.iter()
+                        .map(|&(key, command)| (key.to_owned(), command.to_owned()))
+                        .collect(),
+                    mpris: true,
+                },
+                metrics: MetricsConfig {
+                    socket: false,
+                    address: None,
+                },
+                journal: JournalConfig { enabled: false },
             })),
+            generation: Arc::new(AtomicUsize::new(0)),
         }
     }
 }
@@ -0,0 +1,1715 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains parser of configuration file.
+//!
+//! Configuration file is written in TOML. All sections and entries are optional; entries not
+//! present in file keep their default values. Example:
+//!
+//! ```toml
+//! [input]
+//! touchpad_scale = 0.5
+//! touchpad_pressure_threshold = 70
+//! mouse_scale = 1.0
+//! accel_profile = "adaptive"
+//! natural_scrolling = false
+//! tap_to_click = true
+//! scroll_method = "two_finger"
+//! bypass_bindings_apps = ["virt-manager", "org.remmina.Remmina"]
+//! double_click_interval = 400
+//! repeat_rate = 25
+//! repeat_delay = 600
+//!
+//! [input."046d:c52b Logitech USB Receiver"]
+//! mouse_scale = 1.5
+//! accel_profile = "flat"
+//!
+//! [input."ELAN Touchscreen"]
+//! output = "connector-30"
+//! calibration = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0]
+//!
+//! [[seat]]
+//! name = "seat1"
+//! devices = ["0458:0007", "Dell USB Keyboard"]
+//!
+//! [[keyboard]]
+//! device = "AT Translated Set 2 keyboard"
+//!
+//! [[keyboard.remap]]
+//! key = "CAPSLOCK"
+//! to = ["ESC"]
+//!
+//! [[keyboard.remap]]
+//! key = "F12"
+//! to = ["LEFTCTRL", "C"]
+//!
+//! [xkb]
+//! layout = "us,pl"
+//! variant = "dvorak,"
+//! options = "compose:ralt"
+//!
+//! [workspaces]
+//! names = ["web", "code", "chat"]
+//! edge_switch_delay = 500
+//! float_unresizable = true
+//! preserve_aspect_ratio = false
+//!
+//! [gestures]
+//! swipe_workspaces = true
+//! pinch_overview = true
+//! forward = true
+//!
+//! [renderer]
+//! visualize_damage = false
+//! linear_blending = true
+//! dim_unfocused = 0.3
+//! decorations = true
+//! size_mismatch = "clip"
+//!
+//! [screencast]
+//! output = "connector-30"
+//! size = "1920x1080"
+//! rate = 30
+//! export = true
+//!
+//! [security]
+//! privileged_clients = ["wl-copy", "wl-paste"]
+//! capture_excluded_apps = ["org.keepassxc.KeePassXC"]
+//!
+//! [watchdog]
+//! timeout = 10
+//! restart = false
+//!
+//! [idle]
+//! timeout = 300
+//! power_off = true
+//!
+//! [cursor]
+//! hide_timeout = 5
+//! hide_while_typing = true
+//!
+//! [auto_hide]
+//! namespaces = ["dock"]
+//! reveal_delay = 300
+//! conceal_delay = 800
+//! animation_time = 200
+//!
+//! [media_keys]
+//! volume_up = "pactl set-sink-volume @DEFAULT_SINK@ +5%"
+//! play_pause = "playerctl play-pause"
+//! mpris = true
+//!
+//! [metrics]
+//! socket = true
+//! address = "127.0.0.1:9100"
+//!
+//! [journal]
+//! enabled = true
+//!
+//! [[workspace]]
+//! name = "mail"
+//! output = "HDMI-A-1"
+//! layout = "horizontal"
+//!
+//! [[profile]]
+//! name = "docked"
+//!
+//! [[profile.output]]
+//! name = "connector-30"
+//! mode = "1920x1080@60"
+//! position = [0, 0]
+//! scale = 1
+//! rotate = 0
+//! workspaces = ["mail", "web"]
+//!
+//! [rotation]
+//! automatic = true
+//! output = "connector-30"
+//!
+//! [history]
+//! file = "/home/user/.local/share/perceptia/history"
+//!
+//! [[switch]]
+//! name = "tablet_mode"
+//! rotate = 90
+//!
+//! [[wallpaper]]
+//! path = "/home/user/pictures/mountains.jpg"
+//! mode = "fill"
+//!
+//! [[wallpaper]]
+//! output = "connector-30"
+//! path = "/home/user/pictures/pattern.png"
+//! mode = "tile"
+//!
+//! [[bindings]]
+//! mode = "insert"
+//! key = "W"
+//! modifiers = ["LMTA", "LSHF"]
+//! action = "create_workspace"
+//!
+//! [[commands]]
+//! keysym = "m"
+//! modifiers = ["LMTA"]
+//! switch_mode = "move"
+//!
+//! [[commands]]
+//! mode = "move"
+//! keysym = "Left"
+//! action = "jump"
+//! direction = "west"
+//! magnitude = 1
+//!
+//! [[commands]]
+//! mode = "move"
+//! keysym = "Escape"
+//! switch_mode = "default"
+//! ```
+//!
+//! Tables nested in `input` section override input settings for devices matching their names.
+//! Device is matched by `<vendor>:<product>` IDs written as four hexadecimal digits, by its name or
+//! by both separated by space. If more tables match, the most specific one takes precedence.
+//! Devices are matched when they are opened. Touchscreens and tablets are mapped to `output` (or to
+//! output the pointer is on if not given) and their coordinates, normalized to range from 0 to 1,
+//! are adjusted by `calibration` matrix `[a, b, c, d, e, f]` to `x' = a*x + b*y + c` and
+//! `y' = d*x + e*y + f` before transform of the output is applied.
+//!
+//! `accel_profile` is either `flat` (pointer moves proportionally to the mouse) or `adaptive`
+//! (faster motion moves pointer further). `natural_scrolling` reverses direction of scrolling.
+//! Touchpads click when tapped if `tap_to_click` is enabled (one, two and three fingers give left,
+//! right and middle button) and scroll according to `scroll_method`: `two_finger`, `edge` (one
+//! finger moving along right or bottom edge) or `none`. Input settings are applied to already
+//! opened devices when configuration is reloaded.
+//!
+//! Held keys repeat `repeat_rate` times per second after `repeat_delay` milliseconds. Clients are
+//! told these values and repeat keys themselves; compositor repeats its key bindings which send
+//! commands (e.g. moving focus or resizing) the same way. Zero `repeat_rate` disables repeating.
+//!
+//! While surface of application with ID listed in `bypass_bindings_apps` has keyboard focus all
+//! keys, including ones bound to compositor functions and commands, are passed to it.
+//!
+//! Two clicks in the same place within `double_click_interval` milliseconds make a double-click.
+//! Double-click on title bar maximizes or restores the window; on border between tiled frames it
+//! makes the frames equal.
+//!
+//! `seat` entries define seats besides default `seat0`, each advertised to clients as separate
+//! `wl_seat`. Input devices matching one of `devices` patterns (as in `input` tables) are assigned
+//! to the first such seat; other devices belong to `seat0`. Every seat has its own keyboard focus
+//! which is moved by clicking with pointing device assigned to the seat. The pointer itself is
+//! shared by all seats. Devices are assigned when they are opened.
+//!
+//! `keyboard` entries translate keys of keyboard with given `device` name (or of all keyboards if
+//! `device` is not given) before key bindings and `xkb` translation. Key remapped to one key is
+//! pressed and released together with it. Key remapped to longer sequence works as macro: keys
+//! from the sequence are pressed and released one after another when the key is pressed. Entries
+//! for specific device override entries for all keyboards.
+//!
+//! `xkb` section names components of key map sent to clients: `rules`, `model`, `layout`,
+//! `variant` and `options` as understood by `xkbcommon`. More layouts can be given separated by
+//! commas; `next_keyboard_layout` and `previous_keyboard_layout` functions (or `layout` command)
+//! cycle between them. If key map can not be constructed default `us` layout is used. Key map is
+//! constructed at start so changes in this section require restart.
+//!
+//! When `float_unresizable` is enabled windows which can not be resized (their minimal and
+//! maximal sizes are equal) are placed in floating layer instead of being tiled.
+//!
+//! Floating windows are resized by dragging their borders or by dragging them with right button
+//! while meta key is held. When `preserve_aspect_ratio` is enabled such resize keeps proportions
+//! of the window, which is handy for video players or image viewers. Holding shift during resize
+//! inverts this setting.
+//!
+//! Touchpad gestures with three or more fingers are handled by compositor: horizontal swipe
+//! switches to neighbouring workspace if `swipe_workspaces` is enabled and pinch shows overview of
+//! all workspaces of the display (or hides it) if `pinch_overview` is enabled. Clicking workspace
+//! in overview switches to it. Other gestures are sent to focused application if `forward` is
+//! enabled.
+//!
+//! Threads of compositor report periodically they are alive. If one of them does not respond for
+//! `timeout` seconds its state and state of all other threads are logged and, if `restart` is
+//! enabled, compositor quits so that service manager supervising it can start it again (single
+//! stuck thread can not be safely restarted). Zero `timeout` disables the watchdog. Watchdog is
+//! set up at start so changes in this section require restart.
+//!
+//! User is considered idle when no input arrived for `timeout` seconds of `idle` section. Clients
+//! (e.g. screen lockers) are informed about inactivity with their own timeouts through
+//! `ext-idle-notify` protocol independently of this setting. Zero `timeout` disables idle
+//! detection by compositor. If `power_off` is enabled displays are turned off when user becomes
+//! idle and turned back on with next input. Displays can be also toggled manually with `power`
+//! command. Changes in this section require restart.
+//!
+//! Cursor is hidden when pointer did not move for `hide_timeout` seconds of `cursor` section and,
+//! if `hide_while_typing` is enabled, when a key other than modifier is pressed. It is shown again
+//! with next pointer motion or button press. Zero `hide_timeout` (the default) disables hiding
+//! after timeout.
+//!
+//! Layer surfaces (e.g. docks) with namespace listed in `namespaces` of `auto_hide` section and
+//! anchored to one edge of output do not reserve space for themselves and are slid off the
+//! output. Surface is revealed when pointer is held at its edge for `reveal_delay` milliseconds
+//! and concealed `conceal_delay` milliseconds after pointer leaves it. Sliding takes
+//! `animation_time` milliseconds.
+//!
+//! Media keys (`XF86AudioRaiseVolume`, `XF86AudioPlay` and similar) are bound to `media_key`
+//! function in `common` mode. It executes shell command given in `media_keys` section for
+//! `volume_up`, `volume_down`, `mute`, `mic_mute`, `play_pause`, `next`, `previous` or `stop`.
+//! Volume keys control PipeWire with `wpctl` by default. Playback keys without command are sent to
+//! the first media player found on session bus via MPRIS if `mpris` is enabled (the default).
+//! Empty command disables the key. The same actions can be triggered with `media` command with
+//! name of the key in `string`.
+//!
+//! Statistics of compositor (frame drawing times, numbers of clients and surfaces, usage of memory
+//! shared by clients) are served in Prometheus text format on unix socket
+//! `$XDG_RUNTIME_DIR/perceptia-metrics` if `socket` of `metrics` section is enabled and over HTTP
+//! on TCP `address` if given. Both are disabled by default. Changes in this section require
+//! restart.
+//!
+//! If `enabled` of `journal` section is set opening, closing and focusing windows is recorded
+//! together with time and application ID in `journal-<time>.tsv` file in data directory. New
+//! journal is started with every session, like log file. Disabled by default; changes require
+//! restart.
+//!
+//! `workspace` entries define workspaces created at start. `output` and `layout` (one of `stacked`,
+//! `vertical` and `horizontal`) are optional.
+//!
+//! `decorations` makes compositor draw title bars and borders with close and maximize buttons
+//! around windows. Clients negotiating decoration mode with `xdg-decoration` protocol get the mode
+//! they prefer regardless of this setting.
+//!
+//! `linear_blending` makes translucent surfaces, decorations and overlays blend in linear light
+//! instead of directly on sRGB-encoded values, which avoids washed-out or muddy edges of
+//! translucent content. It requires OpenGL ES 3.0 and may be disabled for comparison.
+//!
+//! `dim_unfocused` is a number between 0 and 1 telling how much brightness and saturation of all
+//! windows except the focused one are reduced, which makes the focused window easy to spot in
+//! dense layouts. Zero (the default) disables dimming.
+//!
+//! `size_mismatch` decides how windows whose size does not match size of space reserved for them
+//! by layout (e.g. clients not obeying configured size yet) are drawn: `clip` cuts off parts which
+//! do not fit, `center` centers the window cutting off parts which do not fit on both sides and
+//! `scale` stretches the window to fill the space.
+//!
+//! `screencast` captures contents of given output scaled to `size` at most `rate` times per
+//! second. Surfaces of applications with IDs listed in `capture_excluded_apps` are blacked out in
+//! captured frames. Exclusion of selected surface can be also toggled with `conceal` command. When
+//! `export` is enabled captured frames are written to shared memory ring
+//! `$XDG_RUNTIME_DIR/perceptia-<output>.frames` and announced in named pipe
+//! `$XDG_RUNTIME_DIR/perceptia-<output>.pipe` for recording tools.
+//!
+//! `minimize` command hides selected window until it is restored by `minimize` command with
+//! `backward` direction. Windows are restored in reverse order of minimizing.
+//!
+//! `tab` command with `forward` or `backward` direction shows next or previous window in stacked
+//! frame containing selected window.
+//!
+//! `profile` entries describe settings of outputs applied when exactly the outputs listed in
+//! profile are connected. First matching profile is used. `mode` gives resolution and optionally
+//! refresh rate in Hz (e.g. `1920x1080` or `1920x1080@60`); first mode of the output matching it
+//! is used. `scale` of output may be fractional (e.g. `1.5`); sizes and positions of windows are
+//! then given in logical pixels, which are multiplied by the scale to get pixels of the output.
+//! `rotate` turns the output by given number of degrees (one of 0, 90, 180 and 270) and `flipped`
+//! mirrors it horizontally.
+//!
+//! `rotate` command turns the output with pointer: `north`, `east`, `south` and `west` directions
+//! set orientation in which top of the screen is placed, `forward` and `backward` rotate by further
+//! 90 degrees and `begin` toggles flipping.
+//!
+//! `mode` command changes mode of the output with pointer: `forward` and `backward` directions
+//! switch to next or previous mode supported by the output, otherwise mode given in `string` (in
+//! the same format as `mode` of profile) is set.
+//!
+//! `zoom` command scales focused window, keeping its aspect ratio, to fill the output it is shown
+//! on, e.g. to read small text. The window is not resized, only drawn bigger, and pointer positions
+//! are translated accordingly. The same command restores normal view.
+//!
+//! `rotation` settings control rotating outputs according to orientation reported by
+//! accelerometer via `iio-sensor-proxy`. When `automatic` is enabled outputs follow orientation of
+//! the device while it is in tablet mode. Only output given in `output` is rotated if it is set.
+//!
+//! When `file` of `history` section is set, order in which windows were focused is saved there
+//! and read at start. Windows of clients reconnecting after restart of compositor are identified
+//! by application ID and title and take their places in history (used by focusing windows in
+//! `forward` and `backward` direction and when focusing workspaces) so that most recently focused
+//! window gets focus again. Windows of clients not reconnecting are forgotten after the next
+//! change of history. History is read at start so changes in this section require restart.
+//!
+//! `switch` entries define reactions to physical switches (`lid`, `tablet_mode` or `dock`).
+//! `rotate` rotates all outputs counter-clockwise by given number of degrees (one of 0, 90, 180 and
+//! 270) when the switch is turned on and restores their orientation when it is turned off.
+//!
+//! `wallpaper` entries set PNG or JPEG image drawn behind windows. Entry with `output` applies to
+//! that output; entry without `output` applies to all other outputs. `mode` is one of `fill`
+//! (scaled to cover the whole output, cut off at edges; the default), `fit` (scaled to fit the
+//! output, margins filled with background color), `tile` (repeated in original size) and `center`
+//! (placed in the middle in original size). Images are loaded when output appears or when
+//! configuration is reloaded and the entries changed.
+//!
+//! `restart` function quits compositor and starts it again in place, which is handy when iterating
+//! over configuration requiring restart. Wayland display socket stays open so clients able to
+//! reconnect (and new ones) connect to the new instance; connections of other clients are lost.
+//!
+//! `switch_vt` function switches to virtual terminal with number of the function key it is bound
+//! to (e.g. `F2` switches to second terminal). It is bound to `ctrl+alt+F1` to `ctrl+alt+F12` by
+//! default and works only when session is controlled via `logind`.
+//!
+//! `take_screenshot` function saves contents of every display to PNG file in data directory
+//! (`$XDG_DATA_HOME`). It is bound to `ctrl+meta+Print` by default.
+//!
+//! `bindings` execute predefined functions while `commands` are handled by `KeyBinder` and send
+//! commands to Exhibitor. Key symbols are named as in `xkbcommon`. `commands` entries without
+//! `mode` belong to `default` mode and mode given in `switch_mode` must have at least one entry.
+
+// -------------------------------------------------------------------------------------------------
+
+use toml;
+use uinput_sys;
+use xkbcommon::xkb;
+
+use binding_functions::{self, Executor};
+use config::{BindingEntry, CommandEntry, InputDeviceConfig, KeyboardConfig, KeyRemapConfig};
+use config::{AccelProfile, OutputProfileConfig, ScrollMethod};
+use config::{ProfileConfig, ScreencastConfig, SeatConfig, SwitchAction, SwitchConfig};
+use config::{WallpaperConfig, WallpaperMode, WorkspaceConfig, WorkspaceLayout};
+use defs::{media_key, modifier, mode_name, Command, KeyCode, OutputMode, Position, Size};
+use enums::{Action, Direction, SizeMismatchPolicy, Switch, Transform};
+use errors::Illusion;
+use key_binder::{self, KeyCommand};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Configuration values read from file. `None` means value was not present in file.
+#[derive(Default)]
+pub struct ParsedConfig {
+    pub touchpad_scale: Option<f32>,
+    pub touchpad_pressure_threshold: Option<i32>,
+    pub mouse_scale: Option<f32>,
+    pub accel_profile: Option<AccelProfile>,
+    pub natural_scrolling: Option<bool>,
+    pub tap_to_click: Option<bool>,
+    pub scroll_method: Option<ScrollMethod>,
+    pub bypass_bindings_apps: Option<Vec<String>>,
+    pub repeat_rate: Option<u32>,
+    pub repeat_delay: Option<u32>,
+    pub double_click_interval: Option<u64>,
+    pub input_devices: Vec<InputDeviceConfig>,
+    pub seats: Vec<SeatConfig>,
+    pub edge_switch_delay: Option<u64>,
+    pub float_unresizable: Option<bool>,
+    pub preserve_aspect_ratio: Option<bool>,
+    pub swipe_workspaces: Option<bool>,
+    pub pinch_overview: Option<bool>,
+    pub forward_gestures: Option<bool>,
+    pub workspace_names: Option<Vec<String>>,
+    pub visualize_damage: Option<bool>,
+    pub linear_blending: Option<bool>,
+    pub dim_unfocused: Option<f32>,
+    pub decorations: Option<bool>,
+    pub size_mismatch_policy: Option<SizeMismatchPolicy>,
+    pub auto_rotation: Option<bool>,
+    pub auto_rotation_output: Option<String>,
+    pub history_file: Option<String>,
+    pub privileged_clients: Option<Vec<String>>,
+    pub capture_excluded_apps: Option<Vec<String>>,
+    pub watchdog_timeout: Option<u32>,
+    pub watchdog_restart: Option<bool>,
+    pub idle_timeout: Option<u32>,
+    pub idle_power_off: Option<bool>,
+    pub cursor_hide_timeout: Option<u32>,
+    pub cursor_hide_while_typing: Option<bool>,
+    pub auto_hide_namespaces: Option<Vec<String>>,
+    pub auto_hide_reveal_delay: Option<u64>,
+    pub auto_hide_conceal_delay: Option<u64>,
+    pub auto_hide_animation_time: Option<u64>,
+    pub media_keys_commands: Vec<(String, String)>,
+    pub media_keys_mpris: Option<bool>,
+    pub metrics_socket: Option<bool>,
+    pub metrics_address: Option<String>,
+    pub journal_enabled: Option<bool>,
+    pub screencast: Option<ScreencastConfig>,
+    pub keyboards: Vec<KeyboardConfig>,
+    pub xkb_rules: Option<String>,
+    pub xkb_model: Option<String>,
+    pub xkb_layout: Option<String>,
+    pub xkb_variant: Option<String>,
+    pub xkb_options: Option<String>,
+    pub workspaces: Vec<WorkspaceConfig>,
+    pub profiles: Vec<ProfileConfig>,
+    pub switches: Vec<SwitchConfig>,
+    pub wallpapers: Vec<WallpaperConfig>,
+    pub bindings: Vec<BindingEntry>,
+    pub commands: Vec<CommandEntry>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Parses configuration file contents. Returns `Illusion::InvalidArgument` describing first
+/// found problem if text is not valid TOML or contains unknown or malformed entries.
+pub fn parse(text: &str) -> Result<ParsedConfig, Illusion> {
+    let value = match text.parse::<toml::Value>() {
+        Ok(value) => value,
+        Err(err) => return Err(Illusion::InvalidArgument(format!("Malformed TOML: {}", err))),
+    };
+
+    let mut result = ParsedConfig::default();
+    let root = value.as_table().expect("TOML document must be a table");
+    for (name, section) in root.iter() {
+        match name.as_ref() {
+            "input" => parse_input(section, &mut result)?,
+            "seat" => parse_seats(section, &mut result)?,
+            "keyboard" => parse_keyboards(section, &mut result)?,
+            "xkb" => parse_xkb(section, &mut result)?,
+            "workspaces" => parse_workspaces(section, &mut result)?,
+            "gestures" => parse_gestures(section, &mut result)?,
+            "renderer" => parse_renderer(section, &mut result)?,
+            "rotation" => parse_rotation(section, &mut result)?,
+            "history" => parse_history(section, &mut result)?,
+            "security" => parse_security(section, &mut result)?,
+            "watchdog" => parse_watchdog(section, &mut result)?,
+            "idle" => parse_idle(section, &mut result)?,
+            "cursor" => parse_cursor(section, &mut result)?,
+            "auto_hide" => parse_auto_hide(section, &mut result)?,
+            "media_keys" => parse_media_keys(section, &mut result)?,
+            "metrics" => parse_metrics(section, &mut result)?,
+            "journal" => parse_journal(section, &mut result)?,
+            "screencast" => parse_screencast(section, &mut result)?,
+            "workspace" => parse_workspace_definitions(section, &mut result)?,
+            "profile" => parse_profiles(section, &mut result)?,
+            "switch" => parse_switches(section, &mut result)?,
+            "wallpaper" => parse_wallpapers(section, &mut result)?,
+            "bindings" => parse_bindings(section, &mut result)?,
+            "commands" => parse_commands(section, &mut result)?,
+            _ => return Err(invalid(name, "unknown section")),
+        }
+    }
+    Ok(result)
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Parses `input` section.
+fn parse_input(section: &toml::Value, result: &mut ParsedConfig) -> Result<(), Illusion> {
+    for (key, value) in as_table("input", section)?.iter() {
+        let path = format!("input.{}", key);
+        match key.as_ref() {
+            "touchpad_scale" => result.touchpad_scale = Some(as_float(&path, value)? as f32),
+            "touchpad_pressure_threshold" => {
+                result.touchpad_pressure_threshold = Some(as_integer(&path, value)? as i32)
+            }
+            "mouse_scale" => result.mouse_scale = Some(as_float(&path, value)? as f32),
+            "accel_profile" => result.accel_profile = Some(as_accel_profile(&path, value)?),
+            "natural_scrolling" => result.natural_scrolling = Some(as_bool(&path, value)?),
+            "tap_to_click" => result.tap_to_click = Some(as_bool(&path, value)?),
+            "scroll_method" => result.scroll_method = Some(as_scroll_method(&path, value)?),
+            "bypass_bindings_apps" => {
+                result.bypass_bindings_apps = Some(as_strings(&path, value)?)
+            }
+            "double_click_interval" => {
+                result.double_click_interval = Some(as_integer(&path, value)? as u64)
+            }
+            "repeat_rate" => result.repeat_rate = Some(as_integer(&path, value)? as u32),
+            "repeat_delay" => result.repeat_delay = Some(as_integer(&path, value)? as u32),
+            _ if value.is_table() => {
+                let path = format!("input.{:?}", key);
+                result.input_devices.push(parse_input_device(&path, key, value)?);
+            }
+            _ => return Err(invalid(&path, "unknown entry")),
+        }
+    }
+    Ok(())
+}
+
+/// Parses table with input settings for devices matching given pattern.
+fn parse_input_device(path: &str,
+                      pattern: &str,
+                      value: &toml::Value)
+                      -> Result<InputDeviceConfig, Illusion> {
+    let mut device = InputDeviceConfig {
+        device: pattern.to_owned(),
+        touchpad_scale: None,
+        touchpad_pressure_threshold: None,
+        mouse_scale: None,
+        accel_profile: None,
+        natural_scrolling: None,
+        tap_to_click: None,
+        scroll_method: None,
+        output: None,
+        calibration: None,
+    };
+
+    if pattern.is_empty() {
+        return Err(invalid(path, "expected non-empty device pattern"));
+    }
+
+    for (key, value) in as_table(path, value)?.iter() {
+        let path = format!("{}.{}", path, key);
+        match key.as_ref() {
+            "touchpad_scale" => device.touchpad_scale = Some(as_float(&path, value)? as f32),
+            "touchpad_pressure_threshold" => {
+                device.touchpad_pressure_threshold = Some(as_integer(&path, value)? as i32)
+            }
+            "mouse_scale" => device.mouse_scale = Some(as_float(&path, value)? as f32),
+            "accel_profile" => device.accel_profile = Some(as_accel_profile(&path, value)?),
+            "natural_scrolling" => device.natural_scrolling = Some(as_bool(&path, value)?),
+            "tap_to_click" => device.tap_to_click = Some(as_bool(&path, value)?),
+            "scroll_method" => device.scroll_method = Some(as_scroll_method(&path, value)?),
+            "output" => device.output = Some(as_non_empty_str(&path, value)?.to_owned()),
+            "calibration" => device.calibration = Some(as_calibration(&path, value)?),
+            _ => return Err(invalid(&path, "unknown entry")),
+        }
+    }
+    Ok(device)
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Parses array of `seat` tables.
+fn parse_seats(section: &toml::Value, result: &mut ParsedConfig) -> Result<(), Illusion> {
+    let array = section.as_array().ok_or_else(|| invalid("seat", "expected array of tables"))?;
+    for (i, entry) in array.iter().enumerate() {
+        let path = format!("seat[{}]", i);
+        let mut name = None;
+        let mut devices = Vec::new();
+        for (key, value) in as_table(&path, entry)?.iter() {
+            let path = format!("{}.{}", path, key);
+            match key.as_ref() {
+                "name" => {
+                    let string = as_non_empty_str(&path, value)?.to_owned();
+                    if (string == "seat0") || result.seats.iter().any(|s| s.name == string) {
+                        return Err(invalid(&path, "seat names must be unique"));
+                    }
+                    name = Some(string);
+                }
+                "devices" => devices = as_strings(&path, value)?,
+                _ => return Err(invalid(&path, "unknown entry")),
+            }
+        }
+
+        let name = name.ok_or_else(|| invalid(&path, "missing name"))?;
+        result.seats.push(SeatConfig {
+                              name: name,
+                              devices: devices,
+                          });
+    }
+    Ok(())
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Parses array of `keyboard` tables.
+fn parse_keyboards(section: &toml::Value, result: &mut ParsedConfig) -> Result<(), Illusion> {
+    let array = section.as_array().ok_or_else(|| invalid("keyboard", "expected array of tables"))?;
+    for (i, entry) in array.iter().enumerate() {
+        let path = format!("keyboard[{}]", i);
+        let mut keyboard = KeyboardConfig {
+            device: None,
+            remaps: Vec::new(),
+        };
+
+        for (key, value) in as_table(&path, entry)?.iter() {
+            let path = format!("{}.{}", path, key);
+            match key.as_ref() {
+                "device" => keyboard.device = Some(as_non_empty_str(&path, value)?.to_owned()),
+                "remap" => keyboard.remaps = parse_key_remaps(&path, value)?,
+                _ => return Err(invalid(&path, "unknown entry")),
+            }
+        }
+        result.keyboards.push(keyboard);
+    }
+    Ok(())
+}
+
+/// Parses array of `remap` tables of one keyboard.
+fn parse_key_remaps(path: &str, value: &toml::Value) -> Result<Vec<KeyRemapConfig>, Illusion> {
+    let array = value.as_array().ok_or_else(|| invalid(path, "expected array of tables"))?;
+    let mut remaps: Vec<KeyRemapConfig> = Vec::with_capacity(array.len());
+    for (i, entry) in array.iter().enumerate() {
+        let path = format!("{}[{}]", path, i);
+        let mut code = None;
+        let mut sequence = Vec::new();
+        for (key, value) in as_table(&path, entry)?.iter() {
+            let path = format!("{}.{}", path, key);
+            match key.as_ref() {
+                "key" => {
+                    let key_code = as_key_code(&path, value)?;
+                    if remaps.iter().any(|r| r.code == key_code) {
+                        return Err(invalid(&path, "key can be remapped only once per keyboard"));
+                    }
+                    code = Some(key_code);
+                }
+                "to" => {
+                    let array = value.as_array()
+                        .ok_or_else(|| invalid(&path, "expected array of strings"))?;
+                    for item in array.iter() {
+                        sequence.push(as_key_code(&path, item)?);
+                    }
+                }
+                _ => return Err(invalid(&path, "unknown entry")),
+            }
+        }
+
+        let code = code.ok_or_else(|| invalid(&path, "missing key"))?;
+        if sequence.is_empty() {
+            return Err(invalid(&path, "missing to"));
+        }
+        remaps.push(KeyRemapConfig {
+                        code: code,
+                        sequence: sequence,
+                    });
+    }
+    Ok(remaps)
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Parses `workspaces` section.
+fn parse_workspaces(section: &toml::Value, result: &mut ParsedConfig) -> Result<(), Illusion> {
+    for (key, value) in as_table("workspaces", section)?.iter() {
+        let path = format!("workspaces.{}", key);
+        match key.as_ref() {
+            "names" => {
+                let names = as_strings(&path, value)?;
+                for (i, name) in names.iter().enumerate() {
+                    if name.is_empty() || names[..i].contains(name) {
+                        return Err(invalid(&path, "names must be non-empty and unique"));
+                    }
+                }
+                result.workspace_names = Some(names);
+            }
+            "edge_switch_delay" => {
+                result.edge_switch_delay = Some(as_integer(&path, value)? as u64)
+            }
+            "float_unresizable" => result.float_unresizable = Some(as_bool(&path, value)?),
+            "preserve_aspect_ratio" => {
+                result.preserve_aspect_ratio = Some(as_bool(&path, value)?)
+            }
+            _ => return Err(invalid(&path, "unknown entry")),
+        }
+    }
+    Ok(())
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Parses `xkb` section.
+fn parse_xkb(section: &toml::Value, result: &mut ParsedConfig) -> Result<(), Illusion> {
+    for (key, value) in as_table("xkb", section)?.iter() {
+        let path = format!("xkb.{}", key);
+        match key.as_ref() {
+            "rules" => result.xkb_rules = Some(as_non_empty_str(&path, value)?.to_owned()),
+            "model" => result.xkb_model = Some(as_non_empty_str(&path, value)?.to_owned()),
+            "layout" => result.xkb_layout = Some(as_non_empty_str(&path, value)?.to_owned()),
+            "variant" => result.xkb_variant = Some(as_str(&path, value)?.to_owned()),
+            "options" => result.xkb_options = Some(as_str(&path, value)?.to_owned()),
+            _ => return Err(invalid(&path, "unknown entry")),
+        }
+    }
+
+    let layout = result.xkb_layout.as_ref();
+    let variant = result.xkb_variant.as_ref();
+    if let (Some(layout), Some(variant)) = (layout, variant) {
+        if variant.split(',').count() > layout.split(',').count() {
+            return Err(invalid("xkb.variant", "more variants than layouts"));
+        }
+    }
+    Ok(())
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Parses `gestures` section.
+fn parse_gestures(section: &toml::Value, result: &mut ParsedConfig) -> Result<(), Illusion> {
+    for (key, value) in as_table("gestures", section)?.iter() {
+        let path = format!("gestures.{}", key);
+        match key.as_ref() {
+            "swipe_workspaces" => result.swipe_workspaces = Some(as_bool(&path, value)?),
+            "pinch_overview" => result.pinch_overview = Some(as_bool(&path, value)?),
+            "forward" => result.forward_gestures = Some(as_bool(&path, value)?),
+            _ => return Err(invalid(&path, "unknown entry")),
+        }
+    }
+    Ok(())
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Parses `renderer` section.
+fn parse_renderer(section: &toml::Value, result: &mut ParsedConfig) -> Result<(), Illusion> {
+    for (key, value) in as_table("renderer", section)?.iter() {
+        let path = format!("renderer.{}", key);
+        match key.as_ref() {
+            "visualize_damage" => result.visualize_damage = Some(as_bool(&path, value)?),
+            "linear_blending" => result.linear_blending = Some(as_bool(&path, value)?),
+            "dim_unfocused" => result.dim_unfocused = Some(as_fraction(&path, value)?),
+            "decorations" => result.decorations = Some(as_bool(&path, value)?),
+            "size_mismatch" => {
+                let string = as_str(&path, value)?;
+                let policy = get_size_mismatch_policy(string)
+                    .ok_or_else(|| invalid(&path, "unknown policy"))?;
+                result.size_mismatch_policy = Some(policy);
+            }
+            _ => return Err(invalid(&path, "unknown entry")),
+        }
+    }
+    Ok(())
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Parses `rotation` section.
+fn parse_rotation(section: &toml::Value, result: &mut ParsedConfig) -> Result<(), Illusion> {
+    for (key, value) in as_table("rotation", section)?.iter() {
+        let path = format!("rotation.{}", key);
+        match key.as_ref() {
+            "automatic" => result.auto_rotation = Some(as_bool(&path, value)?),
+            "output" => {
+                result.auto_rotation_output = Some(as_non_empty_str(&path, value)?.to_owned())
+            }
+            _ => return Err(invalid(&path, "unknown entry")),
+        }
+    }
+    Ok(())
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Parses `history` section.
+fn parse_history(section: &toml::Value, result: &mut ParsedConfig) -> Result<(), Illusion> {
+    for (key, value) in as_table("history", section)?.iter() {
+        let path = format!("history.{}", key);
+        match key.as_ref() {
+            "file" => result.history_file = Some(as_non_empty_str(&path, value)?.to_owned()),
+            _ => return Err(invalid(&path, "unknown entry")),
+        }
+    }
+    Ok(())
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Parses `screencast` section.
+fn parse_screencast(section: &toml::Value, result: &mut ParsedConfig) -> Result<(), Illusion> {
+    let mut output = None;
+    let mut size = None;
+    let mut rate = None;
+    let mut export = false;
+    for (key, value) in as_table("screencast", section)?.iter() {
+        let path = format!("screencast.{}", key);
+        match key.as_ref() {
+            "output" => output = Some(as_non_empty_str(&path, value)?.to_owned()),
+            "size" => size = Some(as_mode(&path, value)?),
+            "rate" => {
+                let integer = as_integer(&path, value)?;
+                if integer < 1 {
+                    return Err(invalid(&path, "expected positive integer"));
+                }
+                rate = Some(integer as u32);
+            }
+            "export" => export = as_bool(&path, value)?,
+            _ => return Err(invalid(&path, "unknown entry")),
+        }
+    }
+
+    let output = output.ok_or_else(|| invalid("screencast", "missing output"))?;
+    let size = size.ok_or_else(|| invalid("screencast", "missing size"))?;
+    let rate = rate.ok_or_else(|| invalid("screencast", "missing rate"))?;
+    result.screencast = Some(ScreencastConfig {
+                                 output: output,
+                                 size: size,
+                                 rate: rate,
+                                 export: export,
+                             });
+    Ok(())
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Parses `security` section.
+fn parse_security(section: &toml::Value, result: &mut ParsedConfig) -> Result<(), Illusion> {
+    for (key, value) in as_table("security", section)?.iter() {
+        let path = format!("security.{}", key);
+        match key.as_ref() {
+            "privileged_clients" => result.privileged_clients = Some(as_strings(&path, value)?),
+            "capture_excluded_apps" => {
+                result.capture_excluded_apps = Some(as_strings(&path, value)?)
+            }
+            _ => return Err(invalid(&path, "unknown entry")),
+        }
+    }
+    Ok(())
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Parses `watchdog` section.
+fn parse_watchdog(section: &toml::Value, result: &mut ParsedConfig) -> Result<(), Illusion> {
+    for (key, value) in as_table("watchdog", section)?.iter() {
+        let path = format!("watchdog.{}", key);
+        match key.as_ref() {
+            "timeout" => result.watchdog_timeout = Some(as_integer(&path, value)? as u32),
+            "restart" => result.watchdog_restart = Some(as_bool(&path, value)?),
+            _ => return Err(invalid(&path, "unknown entry")),
+        }
+    }
+    Ok(())
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Parses `idle` section.
+fn parse_idle(section: &toml::Value, result: &mut ParsedConfig) -> Result<(), Illusion> {
+    for (key, value) in as_table("idle", section)?.iter() {
+        let path = format!("idle.{}", key);
+        match key.as_ref() {
+            "timeout" => result.idle_timeout = Some(as_integer(&path, value)? as u32),
+            "power_off" => result.idle_power_off = Some(as_bool(&path, value)?),
+            _ => return Err(invalid(&path, "unknown entry")),
+        }
+    }
+    Ok(())
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Parses `cursor` section.
+fn parse_cursor(section: &toml::Value, result: &mut ParsedConfig) -> Result<(), Illusion> {
+    for (key, value) in as_table("cursor", section)?.iter() {
+        let path = format!("cursor.{}", key);
+        match key.as_ref() {
+            "hide_timeout" => result.cursor_hide_timeout = Some(as_integer(&path, value)? as u32),
+            "hide_while_typing" => result.cursor_hide_while_typing = Some(as_bool(&path, value)?),
+            _ => return Err(invalid(&path, "unknown entry")),
+        }
+    }
+    Ok(())
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Parses `auto_hide` section.
+fn parse_auto_hide(section: &toml::Value, result: &mut ParsedConfig) -> Result<(), Illusion> {
+    for (key, value) in as_table("auto_hide", section)?.iter() {
+        let path = format!("auto_hide.{}", key);
+        match key.as_ref() {
+            "namespaces" => result.auto_hide_namespaces = Some(as_strings(&path, value)?),
+            "reveal_delay" => {
+                result.auto_hide_reveal_delay = Some(as_integer(&path, value)? as u64)
+            }
+            "conceal_delay" => {
+                result.auto_hide_conceal_delay = Some(as_integer(&path, value)? as u64)
+            }
+            "animation_time" => {
+                result.auto_hide_animation_time = Some(as_integer(&path, value)? as u64)
+            }
+            _ => return Err(invalid(&path, "unknown entry")),
+        }
+    }
+    Ok(())
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Parses `media_keys` section.
+fn parse_media_keys(section: &toml::Value, result: &mut ParsedConfig) -> Result<(), Illusion> {
+    for (key, value) in as_table("media_keys", section)?.iter() {
+        let path = format!("media_keys.{}", key);
+        if key == "mpris" {
+            result.media_keys_mpris = Some(as_bool(&path, value)?);
+        } else if media_key::find(key).is_some() {
+            result.media_keys_commands.push((key.clone(), as_str(&path, value)?.to_owned()));
+        } else {
+            return Err(invalid(&path, "unknown entry"));
+        }
+    }
+    Ok(())
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Parses `metrics` section.
+fn parse_metrics(section: &toml::Value, result: &mut ParsedConfig) -> Result<(), Illusion> {
+    for (key, value) in as_table("metrics", section)?.iter() {
+        let path = format!("metrics.{}", key);
+        match key.as_ref() {
+            "socket" => result.metrics_socket = Some(as_bool(&path, value)?),
+            "address" => result.metrics_address = Some(as_non_empty_str(&path, value)?.to_owned()),
+            _ => return Err(invalid(&path, "unknown entry")),
+        }
+    }
+    Ok(())
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Parses `journal` section.
+fn parse_journal(section: &toml::Value, result: &mut ParsedConfig) -> Result<(), Illusion> {
+    for (key, value) in as_table("journal", section)?.iter() {
+        let path = format!("journal.{}", key);
+        match key.as_ref() {
+            "enabled" => result.journal_enabled = Some(as_bool(&path, value)?),
+            _ => return Err(invalid(&path, "unknown entry")),
+        }
+    }
+    Ok(())
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Parses array of `workspace` tables.
+fn parse_workspace_definitions(section: &toml::Value,
+                               result: &mut ParsedConfig)
+                               -> Result<(), Illusion> {
+    let array = section.as_array().ok_or_else(|| invalid("workspace", "expected array of tables"))?;
+    for (i, entry) in array.iter().enumerate() {
+        let path = format!("workspace[{}]", i);
+        let table = as_table(&path, entry)?;
+
+        let mut name = None;
+        let mut output = None;
+        let mut layout = WorkspaceLayout::Stacked;
+        for (key, value) in table.iter() {
+            let path = format!("{}.{}", path, key);
+            match key.as_ref() {
+                "name" => {
+                    let string = as_non_empty_str(&path, value)?.to_owned();
+                    if result.workspaces.iter().any(|w| w.name == string) {
+                        return Err(invalid(&path, "workspace names must be unique"));
+                    }
+                    name = Some(string);
+                }
+                "output" => output = Some(as_non_empty_str(&path, value)?.to_owned()),
+                "layout" => {
+                    let string = as_str(&path, value)?;
+                    layout = get_layout(string).ok_or_else(|| invalid(&path, "unknown layout"))?;
+                }
+                _ => return Err(invalid(&path, "unknown entry")),
+            }
+        }
+
+        let name = name.ok_or_else(|| invalid(&path, "missing name"))?;
+        result.workspaces.push(WorkspaceConfig {
+                                   name: name,
+                                   output: output,
+                                   layout: layout,
+                               });
+    }
+    Ok(())
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Parses array of `profile` tables.
+fn parse_profiles(section: &toml::Value, result: &mut ParsedConfig) -> Result<(), Illusion> {
+    let array = section.as_array().ok_or_else(|| invalid("profile", "expected array of tables"))?;
+    for (i, entry) in array.iter().enumerate() {
+        let path = format!("profile[{}]", i);
+        let table = as_table(&path, entry)?;
+
+        let mut name = None;
+        let mut outputs = Vec::new();
+        for (key, value) in table.iter() {
+            let path = format!("{}.{}", path, key);
+            match key.as_ref() {
+                "name" => {
+                    let string = as_non_empty_str(&path, value)?.to_owned();
+                    if result.profiles.iter().any(|p| p.name == string) {
+                        return Err(invalid(&path, "profile names must be unique"));
+                    }
+                    name = Some(string);
+                }
+                "output" => outputs = parse_profile_outputs(&path, value)?,
+                _ => return Err(invalid(&path, "unknown entry")),
+            }
+        }
+
+        let name = name.ok_or_else(|| invalid(&path, "missing name"))?;
+        if outputs.is_empty() {
+            return Err(invalid(&path, "missing output"));
+        }
+        result.profiles.push(ProfileConfig {
+                                 name: name,
+                                 outputs: outputs,
+                             });
+    }
+    Ok(())
+}
+
+/// Parses array of `output` tables of one profile.
+fn parse_profile_outputs(path: &str,
+                         value: &toml::Value)
+                         -> Result<Vec<OutputProfileConfig>, Illusion> {
+    let array = value.as_array().ok_or_else(|| invalid(path, "expected array of tables"))?;
+    let mut outputs: Vec<OutputProfileConfig> = Vec::with_capacity(array.len());
+    for (i, entry) in array.iter().enumerate() {
+        let path = format!("{}[{}]", path, i);
+        let mut output = OutputProfileConfig {
+            name: String::new(),
+            mode: None,
+            position: None,
+            scale: None,
+            transform: None,
+            workspaces: Vec::new(),
+        };
+        let mut rotation = None;
+        let mut flipped = None;
+
+        for (key, value) in as_table(&path, entry)?.iter() {
+            let path = format!("{}.{}", path, key);
+            match key.as_ref() {
+                "name" => {
+                    output.name = as_non_empty_str(&path, value)?.to_owned();
+                    if outputs.iter().any(|o| o.name == output.name) {
+                        return Err(invalid(&path, "output names must be unique in profile"));
+                    }
+                }
+                "mode" => output.mode = Some(as_output_mode(&path, value)?),
+                "position" => output.position = Some(as_position(&path, value)?),
+                "scale" => output.scale = Some(as_float(&path, value)? as f32),
+                "rotate" => {
+                    let degrees = as_integer(&path, value)?;
+                    rotation = Some(get_rotation(degrees)
+                        .ok_or_else(|| invalid(&path, "expected one of 0, 90, 180 and 270"))?);
+                }
+                "flipped" => flipped = Some(as_bool(&path, value)?),
+                "workspaces" => output.workspaces = as_strings(&path, value)?,
+                _ => return Err(invalid(&path, "unknown entry")),
+            }
+        }
+
+        if rotation.is_some() || flipped.is_some() {
+            let rotation = rotation.unwrap_or(Transform::Normal);
+            let flipped = flipped.unwrap_or(false);
+            output.transform = Some(Transform::from_parts(rotation.get_quarter_turns(), flipped));
+        }
+
+        if output.name.is_empty() {
+            return Err(invalid(&path, "missing name"));
+        }
+        outputs.push(output);
+    }
+    Ok(outputs)
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Parses array of `switch` tables.
+fn parse_switches(section: &toml::Value, result: &mut ParsedConfig) -> Result<(), Illusion> {
+    let array = section.as_array().ok_or_else(|| invalid("switch", "expected array of tables"))?;
+    for (i, entry) in array.iter().enumerate() {
+        let path = format!("switch[{}]", i);
+        let mut switch = None;
+        let mut action = None;
+        for (key, value) in as_table(&path, entry)?.iter() {
+            let path = format!("{}.{}", path, key);
+            match key.as_ref() {
+                "name" => {
+                    let name = as_str(&path, value)?;
+                    let s = get_switch(name).ok_or_else(|| invalid(&path, "unknown switch"))?;
+                    if result.switches.iter().any(|config| config.switch == s) {
+                        return Err(invalid(&path, "switch can be configured only once"));
+                    }
+                    switch = Some(s);
+                }
+                "rotate" => {
+                    let degrees = as_integer(&path, value)?;
+                    let transform = get_rotation(degrees)
+                        .ok_or_else(|| invalid(&path, "expected one of 0, 90, 180 and 270"))?;
+                    action = Some(SwitchAction::RotateOutput(transform));
+                }
+                _ => return Err(invalid(&path, "unknown entry")),
+            }
+        }
+
+        result.switches.push(SwitchConfig {
+                                 switch: switch.ok_or_else(|| invalid(&path, "missing name"))?,
+                                 action: action.ok_or_else(|| invalid(&path, "missing action"))?,
+                             });
+    }
+    Ok(())
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Parses array of `wallpaper` tables.
+fn parse_wallpapers(section: &toml::Value, result: &mut ParsedConfig) -> Result<(), Illusion> {
+    let array = section.as_array().ok_or_else(|| invalid("wallpaper", "expected array of tables"))?;
+    for (i, entry) in array.iter().enumerate() {
+        let path = format!("wallpaper[{}]", i);
+        let mut output = None;
+        let mut file = None;
+        let mut mode = WallpaperMode::Fill;
+        for (key, value) in as_table(&path, entry)?.iter() {
+            let path = format!("{}.{}", path, key);
+            match key.as_ref() {
+                "output" => output = Some(as_non_empty_str(&path, value)?.to_owned()),
+                "path" => file = Some(as_non_empty_str(&path, value)?.to_owned()),
+                "mode" => {
+                    let name = as_str(&path, value)?;
+                    mode = get_wallpaper_mode(name).ok_or_else(|| invalid(&path, "unknown mode"))?;
+                }
+                _ => return Err(invalid(&path, "unknown entry")),
+            }
+        }
+
+        result.wallpapers.push(WallpaperConfig {
+                                   output: output,
+                                   path: file.ok_or_else(|| invalid(&path, "missing path"))?,
+                                   mode: mode,
+                               });
+    }
+    Ok(())
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Parses array of `bindings` tables.
+fn parse_bindings(section: &toml::Value, result: &mut ParsedConfig) -> Result<(), Illusion> {
+    let array = section.as_array().ok_or_else(|| invalid("bindings", "expected array of tables"))?;
+    for (i, entry) in array.iter().enumerate() {
+        let path = format!("bindings[{}]", i);
+        let table = as_table(&path, entry)?;
+
+        let mut mode = mode_name::INSERT;
+        let mut code = None;
+        let mut modifiers = modifier::NONE;
+        let mut executor = None;
+        for (key, value) in table.iter() {
+            let path = format!("{}.{}", path, key);
+            match key.as_ref() {
+                "mode" => {
+                    let name = as_str(&path, value)?;
+                    mode = get_mode_name(name).ok_or_else(|| invalid(&path, "unknown mode"))?;
+                }
+                "key" => {
+                    let name = as_str(&path, value)?;
+                    code = Some(get_key_code(name).ok_or_else(|| invalid(&path, "unknown key"))?);
+                }
+                "modifiers" => {
+                    for name in as_strings(&path, value)? {
+                        modifiers |= get_modifier(&name)
+                            .ok_or_else(|| invalid(&path, "unknown modifier"))?;
+                    }
+                }
+                "action" => {
+                    let name = as_str(&path, value)?;
+                    executor = Some(get_executor(name)
+                                        .ok_or_else(|| invalid(&path, "unknown action"))?);
+                }
+                _ => return Err(invalid(&path, "unknown entry")),
+            }
+        }
+
+        let code = code.ok_or_else(|| invalid(&path, "missing key"))?;
+        let executor = executor.ok_or_else(|| invalid(&path, "missing action"))?;
+        result.bindings.push(BindingEntry::new(mode, code, modifiers, executor));
+    }
+    Ok(())
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Parses array of `commands` tables.
+fn parse_commands(section: &toml::Value, result: &mut ParsedConfig) -> Result<(), Illusion> {
+    let array = section.as_array().ok_or_else(|| invalid("commands", "expected array of tables"))?;
+    for (i, entry) in array.iter().enumerate() {
+        let path = format!("commands[{}]", i);
+        let table = as_table(&path, entry)?;
+
+        let mut mode = key_binder::DEFAULT_MODE.to_owned();
+        let mut keysym = None;
+        let mut modifiers = modifier::NONE;
+        let mut action = None;
+        let mut command = Command::default();
+        let mut next_mode = None;
+        for (key, value) in table.iter() {
+            let path = format!("{}.{}", path, key);
+            match key.as_ref() {
+                "mode" => mode = as_non_empty_str(&path, value)?.to_owned(),
+                "keysym" => {
+                    let name = as_str(&path, value)?;
+                    keysym = Some(get_keysym(name)
+                                      .ok_or_else(|| invalid(&path, "unknown key symbol"))?);
+                }
+                "modifiers" => {
+                    for name in as_strings(&path, value)? {
+                        modifiers |= get_modifier(&name)
+                            .ok_or_else(|| invalid(&path, "unknown modifier"))?;
+                    }
+                }
+                "action" => {
+                    let name = as_str(&path, value)?;
+                    action = Some(get_action(name)
+                                      .ok_or_else(|| invalid(&path, "unknown action"))?);
+                }
+                "direction" => {
+                    let name = as_str(&path, value)?;
+                    command.direction = get_direction(name)
+                        .ok_or_else(|| invalid(&path, "unknown direction"))?;
+                }
+                "magnitude" => {
+                    let magnitude = value.as_integer()
+                        .ok_or_else(|| invalid(&path, "expected integer"))?;
+                    command.magnitude = magnitude as i32;
+                }
+                "string" => command.string = as_str(&path, value)?.to_owned(),
+                "switch_mode" => next_mode = Some(as_non_empty_str(&path, value)?.to_owned()),
+                _ => return Err(invalid(&path, "unknown entry")),
+            }
+        }
+
+        let keysym = keysym.ok_or_else(|| invalid(&path, "missing keysym"))?;
+        let command = if let Some(action) = action {
+            command.action = action;
+            Some(command)
+        } else if next_mode.is_some() {
+            None
+        } else {
+            return Err(invalid(&path, "missing action or switch_mode"));
+        };
+
+        result.commands.push(CommandEntry {
+                                 mode_name: mode,
+                                 keysym: keysym,
+                                 modifiers: modifiers,
+                                 key_command: KeyCommand {
+                                     command: command,
+                                     next_mode: next_mode,
+                                 },
+                             });
+    }
+
+    // Check if modes given in `switch_mode` have any entries
+    for (i, entry) in result.commands.iter().enumerate() {
+        if let Some(ref next_mode) = entry.key_command.next_mode {
+            if (next_mode != key_binder::DEFAULT_MODE) &&
+               !result.commands.iter().any(|e| e.mode_name == *next_mode) {
+                return Err(invalid(&format!("commands[{}].switch_mode", i), "unknown mode"));
+            }
+        }
+    }
+    Ok(())
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Constructs error for entry with given path.
+fn invalid(path: &str, description: &str) -> Illusion {
+    Illusion::InvalidArgument(format!("Config entry '{}': {}", path, description))
+}
+
+/// Returns value as table or error if it has other type.
+fn as_table<'a>(path: &str, value: &'a toml::Value) -> Result<&'a toml::value::Table, Illusion> {
+    value.as_table().ok_or_else(|| invalid(path, "expected table"))
+}
+
+/// Returns value as string or error if it has other type.
+fn as_str<'a>(path: &str, value: &'a toml::Value) -> Result<&'a str, Illusion> {
+    value.as_str().ok_or_else(|| invalid(path, "expected string"))
+}
+
+/// Returns value as non-empty string or error if it has other type or is empty.
+fn as_non_empty_str<'a>(path: &str, value: &'a toml::Value) -> Result<&'a str, Illusion> {
+    let string = as_str(path, value)?;
+    if string.is_empty() {
+        Err(invalid(path, "expected non-empty string"))
+    } else {
+        Ok(string)
+    }
+}
+
+/// Returns value as boolean or error if it has other type.
+fn as_bool(path: &str, value: &toml::Value) -> Result<bool, Illusion> {
+    value.as_bool().ok_or_else(|| invalid(path, "expected boolean"))
+}
+
+/// Returns value as non-negative integer or error if it has other type.
+fn as_integer(path: &str, value: &toml::Value) -> Result<i64, Illusion> {
+    match value.as_integer() {
+        Some(integer) if integer >= 0 => Ok(integer),
+        Some(_) => Err(invalid(path, "expected non-negative integer")),
+        None => Err(invalid(path, "expected integer")),
+    }
+}
+
+/// Returns value as positive float or error if it has other type. Integers are accepted.
+fn as_float(path: &str, value: &toml::Value) -> Result<f64, Illusion> {
+    let float = match *value {
+        toml::Value::Float(float) => float,
+        toml::Value::Integer(integer) => integer as f64,
+        _ => return Err(invalid(path, "expected number")),
+    };
+
+    if float > 0.0 {
+        Ok(float)
+    } else {
+        Err(invalid(path, "expected positive number"))
+    }
+}
+
+/// Returns value as number between zero and one or error if it has other type or is out of range.
+fn as_fraction(path: &str, value: &toml::Value) -> Result<f32, Illusion> {
+    let float = match *value {
+        toml::Value::Float(float) => float,
+        toml::Value::Integer(integer) => integer as f64,
+        _ => return Err(invalid(path, "expected number")),
+    };
+
+    if (float >= 0.0) && (float <= 1.0) {
+        Ok(float as f32)
+    } else {
+        Err(invalid(path, "expected number between 0 and 1"))
+    }
+}
+
+/// Returns value as calibration matrix or error if it is not an array of six numbers.
+fn as_calibration(path: &str, value: &toml::Value) -> Result<[f32; 6], Illusion> {
+    let array = value.as_array().ok_or_else(|| invalid(path, "expected array of six numbers"))?;
+    if array.len() != 6 {
+        return Err(invalid(path, "expected array of six numbers"));
+    }
+
+    let mut matrix = [0.0; 6];
+    for (element, item) in matrix.iter_mut().zip(array.iter()) {
+        *element = match *item {
+            toml::Value::Float(float) => float as f32,
+            toml::Value::Integer(integer) => integer as f32,
+            _ => return Err(invalid(path, "expected array of six numbers")),
+        };
+    }
+    Ok(matrix)
+}
+
+/// Returns value as acceleration profile or error if it is not a name of known profile.
+fn as_accel_profile(path: &str, value: &toml::Value) -> Result<AccelProfile, Illusion> {
+    get_accel_profile(as_str(path, value)?).ok_or_else(|| invalid(path, "unknown profile"))
+}
+
+/// Returns value as scroll method or error if it is not a name of known method.
+fn as_scroll_method(path: &str, value: &toml::Value) -> Result<ScrollMethod, Illusion> {
+    get_scroll_method(as_str(path, value)?).ok_or_else(|| invalid(path, "unknown scroll method"))
+}
+
+/// Returns value as size of mode or error if it is not a string in format `<width>x<height>`.
+fn as_mode(path: &str, value: &toml::Value) -> Result<Size, Illusion> {
+    let string = as_str(path, value)?;
+    let mut parts = string.split('x').map(|part| part.parse::<usize>());
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(Ok(width)), Some(Ok(height)), None) if (width > 0) && (height > 0) => {
+            Ok(Size::new(width, height))
+        }
+        _ => Err(invalid(path, "expected mode in format '<width>x<height>'")),
+    }
+}
+
+/// Returns value as output mode or error if it is not a string in format `<width>x<height>` or
+/// `<width>x<height>@<rate>`.
+fn as_output_mode(path: &str, value: &toml::Value) -> Result<OutputMode, Illusion> {
+    OutputMode::parse(as_str(path, value)?)
+        .ok_or_else(|| invalid(path, "expected mode in format '<width>x<height>[@<rate>]'"))
+}
+
+/// Returns value as position or error if it is not an array of two integers.
+fn as_position(path: &str, value: &toml::Value) -> Result<Position, Illusion> {
+    let array = value.as_array().ok_or_else(|| invalid(path, "expected array of two integers"))?;
+    match (array.get(0).and_then(|v| v.as_integer()),
+           array.get(1).and_then(|v| v.as_integer()),
+           array.len()) {
+        (Some(x), Some(y), 2) => Ok(Position::new(x as isize, y as isize)),
+        _ => Err(invalid(path, "expected array of two integers")),
+    }
+}
+
+/// Returns value as code of key or error if it is not a string with known key name.
+fn as_key_code(path: &str, value: &toml::Value) -> Result<KeyCode, Illusion> {
+    let name = as_str(path, value)?;
+    get_key_code(name).map(|code| code as KeyCode).ok_or_else(|| invalid(path, "unknown key"))
+}
+
+/// Returns value as vector of strings or error if it has other type.
+fn as_strings(path: &str, value: &toml::Value) -> Result<Vec<String>, Illusion> {
+    let array = value.as_array().ok_or_else(|| invalid(path, "expected array of strings"))?;
+    let mut result = Vec::with_capacity(array.len());
+    for item in array.iter() {
+        result.push(as_str(path, item)?.to_owned());
+    }
+    Ok(result)
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Returns name of mode with given name.
+fn get_mode_name(name: &str) -> Option<&'static str> {
+    match name {
+        mode_name::COMMON => Some(mode_name::COMMON),
+        mode_name::INSERT => Some(mode_name::INSERT),
+        mode_name::NORMAL => Some(mode_name::NORMAL),
+        _ => None,
+    }
+}
+
+/// Returns key symbol with given name.
+fn get_keysym(name: &str) -> Option<u32> {
+    let keysym = xkb::keysym_from_name(name, xkb::KEYSYM_NO_FLAGS);
+    if keysym != xkb::keysyms::KEY_NoSymbol {
+        Some(keysym)
+    } else {
+        None
+    }
+}
+
+/// Returns switch with given name.
+fn get_switch(name: &str) -> Option<Switch> {
+    match name {
+        "lid" => Some(Switch::Lid),
+        "tablet_mode" => Some(Switch::TabletMode),
+        "dock" => Some(Switch::Dock),
+        _ => None,
+    }
+}
+
+/// Returns transform rotating by given number of degrees.
+fn get_rotation(degrees: i64) -> Option<Transform> {
+    match degrees {
+        0 => Some(Transform::Normal),
+        90 => Some(Transform::Rotated90),
+        180 => Some(Transform::Rotated180),
+        270 => Some(Transform::Rotated270),
+        _ => None,
+    }
+}
+
+/// Returns workspace layout with given name.
+fn get_layout(name: &str) -> Option<WorkspaceLayout> {
+    match name {
+        "stacked" => Some(WorkspaceLayout::Stacked),
+        "vertical" => Some(WorkspaceLayout::Vertical),
+        "horizontal" => Some(WorkspaceLayout::Horizontal),
+        _ => None,
+    }
+}
+
+/// Returns wallpaper mode with given name.
+fn get_wallpaper_mode(name: &str) -> Option<WallpaperMode> {
+    match name {
+        "fill" => Some(WallpaperMode::Fill),
+        "fit" => Some(WallpaperMode::Fit),
+        "tile" => Some(WallpaperMode::Tile),
+        "center" => Some(WallpaperMode::Center),
+        _ => None,
+    }
+}
+
+/// Returns acceleration profile with given name.
+fn get_accel_profile(name: &str) -> Option<AccelProfile> {
+    match name {
+        "flat" => Some(AccelProfile::Flat),
+        "adaptive" => Some(AccelProfile::Adaptive),
+        _ => None,
+    }
+}
+
+/// Returns scroll method with given name.
+fn get_scroll_method(name: &str) -> Option<ScrollMethod> {
+    match name {
+        "none" => Some(ScrollMethod::NoScroll),
+        "two_finger" => Some(ScrollMethod::TwoFinger),
+        "edge" => Some(ScrollMethod::Edge),
+        _ => None,
+    }
+}
+
+/// Returns size mismatch policy with given name.
+fn get_size_mismatch_policy(name: &str) -> Option<SizeMismatchPolicy> {
+    match name {
+        "clip" => Some(SizeMismatchPolicy::Clip),
+        "center" => Some(SizeMismatchPolicy::Center),
+        "scale" => Some(SizeMismatchPolicy::Scale),
+        _ => None,
+    }
+}
+
+/// Returns action with given name.
+fn get_action(name: &str) -> Option<Action> {
+    match name {
+        "anchor" => Some(Action::Anchor),
+        "configure" => Some(Action::Configure),
+        "create" => Some(Action::Create),
+        "focus" => Some(Action::Focus),
+        "swap" => Some(Action::Swap),
+        "move" => Some(Action::Move),
+        "jump" => Some(Action::Jump),
+        "dive" => Some(Action::Dive),
+        "resize" => Some(Action::Resize),
+        "redraw" => Some(Action::Redraw),
+        "conceal" => Some(Action::Conceal),
+        "minimize" => Some(Action::Minimize),
+        "tab" => Some(Action::Tab),
+        "trace" => Some(Action::Trace),
+        "layout" => Some(Action::Layout),
+        "power" => Some(Action::Power),
+        "rotate" => Some(Action::Rotate),
+        "mode" => Some(Action::Mode),
+        "zoom" => Some(Action::Zoom),
+        "media" => Some(Action::Media),
+        "terminal" => Some(Action::Terminal),
+        "screenshot" => Some(Action::Screenshot),
+        _ => None,
+    }
+}
+
+/// Returns direction with given name.
+fn get_direction(name: &str) -> Option<Direction> {
+    match name {
+        "north" => Some(Direction::North),
+        "east" => Some(Direction::East),
+        "south" => Some(Direction::South),
+        "west" => Some(Direction::West),
+        "backward" => Some(Direction::Backward),
+        "forward" => Some(Direction::Forward),
+        "begin" => Some(Direction::Begin),
+        "end" => Some(Direction::End),
+        "up" => Some(Direction::Up),
+        "workspace" => Some(Direction::Workspace),
+        _ => None,
+    }
+}
+
+/// Returns modifier with given name.
+fn get_modifier(name: &str) -> Option<modifier::ModifierType> {
+    match name {
+        "LCTL" => Some(modifier::LCTL),
+        "RCTL" => Some(modifier::RCTL),
+        "LSHF" => Some(modifier::LSHF),
+        "RSHF" => Some(modifier::RSHF),
+        "LALT" => Some(modifier::LALT),
+        "RALT" => Some(modifier::RALT),
+        "LMTA" => Some(modifier::LMTA),
+        "RMTA" => Some(modifier::RMTA),
+        _ => None,
+    }
+}
+
+/// Returns code of key with given name. Names are the same as in `linux/input.h` without `KEY_`
+/// prefix.
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn get_key_code(name: &str) -> Option<i32> {
+    let code = match name {
+        "ESC" => uinput_sys::KEY_ESC,
+        "1" => uinput_sys::KEY_1, "2" => uinput_sys::KEY_2, "3" => uinput_sys::KEY_3,
+        "4" => uinput_sys::KEY_4, "5" => uinput_sys::KEY_5, "6" => uinput_sys::KEY_6,
+        "7" => uinput_sys::KEY_7, "8" => uinput_sys::KEY_8, "9" => uinput_sys::KEY_9,
+        "0" => uinput_sys::KEY_10,
+        "MINUS" => uinput_sys::KEY_MINUS, "EQUAL" => uinput_sys::KEY_EQUAL,
+        "BACKSPACE" => uinput_sys::KEY_BACKSPACE, "TAB" => uinput_sys::KEY_TAB,
+        "Q" => uinput_sys::KEY_Q, "W" => uinput_sys::KEY_W, "E" => uinput_sys::KEY_E,
+        "R" => uinput_sys::KEY_R, "T" => uinput_sys::KEY_T, "Y" => uinput_sys::KEY_Y,
+        "U" => uinput_sys::KEY_U, "I" => uinput_sys::KEY_I, "O" => uinput_sys::KEY_O,
+        "P" => uinput_sys::KEY_P, "A" => uinput_sys::KEY_A, "S" => uinput_sys::KEY_S,
+        "D" => uinput_sys::KEY_D, "F" => uinput_sys::KEY_F, "G" => uinput_sys::KEY_G,
+        "H" => uinput_sys::KEY_H, "J" => uinput_sys::KEY_J, "K" => uinput_sys::KEY_K,
+        "L" => uinput_sys::KEY_L, "Z" => uinput_sys::KEY_Z, "X" => uinput_sys::KEY_X,
+        "C" => uinput_sys::KEY_C, "V" => uinput_sys::KEY_V, "B" => uinput_sys::KEY_B,
+        "N" => uinput_sys::KEY_N, "M" => uinput_sys::KEY_M,
+        "LEFTBRACE" => uinput_sys::KEY_LEFTBRACE, "RIGHTBRACE" => uinput_sys::KEY_RIGHTBRACE,
+        "ENTER" => uinput_sys::KEY_ENTER, "SEMICOLON" => uinput_sys::KEY_SEMICOLON,
+        "APOSTROPHE" => uinput_sys::KEY_APOSTROPHE, "GRAVE" => uinput_sys::KEY_GRAVE,
+        "BACKSLASH" => uinput_sys::KEY_BACKSLASH, "COMMA" => uinput_sys::KEY_COMMA,
+        "DOT" => uinput_sys::KEY_DOT, "SLASH" => uinput_sys::KEY_SLASH,
+        "SPACE" => uinput_sys::KEY_SPACE,
+        "F1" => uinput_sys::KEY_F1, "F2" => uinput_sys::KEY_F2, "F3" => uinput_sys::KEY_F3,
+        "F4" => uinput_sys::KEY_F4, "F5" => uinput_sys::KEY_F5, "F6" => uinput_sys::KEY_F6,
+        "F7" => uinput_sys::KEY_F7, "F8" => uinput_sys::KEY_F8, "F9" => uinput_sys::KEY_F9,
+        "F10" => uinput_sys::KEY_F10, "F11" => uinput_sys::KEY_F11, "F12" => uinput_sys::KEY_F12,
+        "HOME" => uinput_sys::KEY_HOME, "END" => uinput_sys::KEY_END,
+        "UP" => uinput_sys::KEY_UP, "DOWN" => uinput_sys::KEY_DOWN,
+        "LEFT" => uinput_sys::KEY_LEFT, "RIGHT" => uinput_sys::KEY_RIGHT,
+        "PAGEUP" => uinput_sys::KEY_PAGEUP, "PAGEDOWN" => uinput_sys::KEY_PAGEDOWN,
+        "INSERT" => uinput_sys::KEY_INSERT, "DELETE" => uinput_sys::KEY_DELETE,
+        "CAPSLOCK" => uinput_sys::KEY_CAPSLOCK, "COMPOSE" => uinput_sys::KEY_COMPOSE,
+        "LEFTCTRL" => uinput_sys::KEY_LEFTCTRL, "RIGHTCTRL" => uinput_sys::KEY_RIGHTCTRL,
+        "LEFTSHIFT" => uinput_sys::KEY_LEFTSHIFT, "RIGHTSHIFT" => uinput_sys::KEY_RIGHTSHIFT,
+        "LEFTALT" => uinput_sys::KEY_LEFTALT, "RIGHTALT" => uinput_sys::KEY_RIGHTALT,
+        "LEFTMETA" => uinput_sys::KEY_LEFTMETA, "RIGHTMETA" => uinput_sys::KEY_RIGHTMETA,
+        "VOLUMEUP" => uinput_sys::KEY_VOLUMEUP, "VOLUMEDOWN" => uinput_sys::KEY_VOLUMEDOWN,
+        "MUTE" => uinput_sys::KEY_MUTE, "MICMUTE" => uinput_sys::KEY_MICMUTE,
+        "PLAYPAUSE" => uinput_sys::KEY_PLAYPAUSE, "STOPCD" => uinput_sys::KEY_STOPCD,
+        "NEXTSONG" => uinput_sys::KEY_NEXTSONG, "PREVIOUSSONG" => uinput_sys::KEY_PREVIOUSSONG,
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// Returns binding function with given name.
+fn get_executor(name: &str) -> Option<Executor> {
+    let executor: Executor = match name {
+        "clean_command" => binding_functions::clean_command,
+        "quit" => binding_functions::quit,
+        "restart" => binding_functions::restart,
+        "switch_vt" => binding_functions::switch_vt,
+        "media_key" => binding_functions::media_key,
+        "take_screenshot" => binding_functions::take_screenshot,
+        "reload_config" => binding_functions::reload_config,
+        "cycle_redraw_mode" => binding_functions::cycle_redraw_mode,
+        "toggle_damage_visualization" => binding_functions::toggle_damage_visualization,
+        "redraw_step" => binding_functions::redraw_step,
+        "toggle_signal_tracing" => binding_functions::toggle_signal_tracing,
+        "log_subscriptions" => binding_functions::log_subscriptions,
+        "next_keyboard_layout" => binding_functions::next_keyboard_layout,
+        "previous_keyboard_layout" => binding_functions::previous_keyboard_layout,
+        "toggle_display_power" => binding_functions::toggle_display_power,
+        "rotate_display" => binding_functions::rotate_display,
+        "reset_display_rotation" => binding_functions::reset_display_rotation,
+        "next_display_mode" => binding_functions::next_display_mode,
+        "toggle_zoom" => binding_functions::toggle_zoom,
+        "put_focus" => binding_functions::put_focus,
+        "put_swap" => binding_functions::put_swap,
+        "put_jump" => binding_functions::put_jump,
+        "put_dive" => binding_functions::put_dive,
+        "put_north" => binding_functions::put_north,
+        "put_east" => binding_functions::put_east,
+        "put_south" => binding_functions::put_south,
+        "put_west" => binding_functions::put_west,
+        "put_forward" => binding_functions::put_forward,
+        "put_backward" => binding_functions::put_backward,
+        "horizontalize" => binding_functions::horizontalize,
+        "verticalize" => binding_functions::verticalize,
+        "stackize" => binding_functions::stackize,
+        "cicle_history_forward" => binding_functions::cicle_history_forward,
+        "cicle_history_backward" => binding_functions::cicle_history_backward,
+        "move_workspace_to_next_display" => binding_functions::move_workspace_to_next_display,
+        "move_workspace_to_previous_display" => {
+            binding_functions::move_workspace_to_previous_display
+        }
+        "swap_workspace_with_next_display" => binding_functions::swap_workspace_with_next_display,
+        "swap_workspace_with_previous_display" => {
+            binding_functions::swap_workspace_with_previous_display
+        }
+        "focus_right" => binding_functions::focus_right,
+        "focus_down" => binding_functions::focus_down,
+        "focus_left" => binding_functions::focus_left,
+        "focus_up" => binding_functions::focus_up,
+        "jump_right" => binding_functions::jump_right,
+        "jump_down" => binding_functions::jump_down,
+        "jump_left" => binding_functions::jump_left,
+        "jump_up" => binding_functions::jump_up,
+        "exalt" => binding_functions::exalt,
+        "ramify" => binding_functions::ramify,
+        "toggle_anchor" => binding_functions::toggle_anchor,
+        "dive_right" => binding_functions::dive_right,
+        "dive_down" => binding_functions::dive_down,
+        "dive_left" => binding_functions::dive_left,
+        "dive_up" => binding_functions::dive_up,
+        "jump_to_workspace" => binding_functions::jump_to_workspace,
+        "dive_to_workspace" => binding_functions::dive_to_workspace,
+        "focus_workspace" => binding_functions::focus_workspace,
+        "create_workspace" => binding_functions::create_workspace,
+        "focus_next_workspace" => binding_functions::focus_next_workspace,
+        "focus_previous_workspace" => binding_functions::focus_previous_workspace,
+        "dive_to_next_workspace" => binding_functions::dive_to_next_workspace,
+        "dive_to_previous_workspace" => binding_functions::dive_to_previous_workspace,
+        "rename_workspace" => binding_functions::rename_workspace,
+        "swap_mode_normal_to_insert" => binding_functions::swap_mode_normal_to_insert,
+        "swap_mode_insert_to_normal" => binding_functions::swap_mode_insert_to_normal,
+        _ => return None,
+    };
+    Some(executor)
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -8,8 +8,9 @@
 use dharma::{Dispatcher, EventHandler, EventKind, Signaler, SignalId};
 
 use config::Config;
+use env::Env;
 use settings::Settings;
-use perceptron::Perceptron;
+use perceptron::{self, Perceptron};
 use coordinator::Coordinator;
 use input_manager::InputManager;
 
@@ -59,6 +60,33 @@ impl Context {
         self.dispatcher.add_source(event_handler, event_kind);
     }
 
+    /// Reads configuration file again and applies it to global configuration and key bindings.
+    /// Modules are informed about new configuration with `ConfigReloaded` signal. If the file is
+    /// invalid current configuration is kept.
+    pub fn reload_config(&mut self) {
+        let path = Env::get_config_path();
+        let config = if path.exists() {
+            match Config::load(&path) {
+                Ok(config) => config,
+                Err(err) => {
+                    log_warn1!("Failed to reload config file {:?}: {}; keeping current \
+                                configuration",
+                               path,
+                               err);
+                    return;
+                }
+            }
+        } else {
+            log_info1!("Config file {:?} not found; reloading default configuration", path);
+            Config::default()
+        };
+
+        self.config.replace(&config);
+        self.input_manager.reload_bindings(&self.config);
+        log_info1!("Reloaded configuration from {:?}", path);
+        self.signaler.emit(perceptron::CONFIG_RELOADED, Perceptron::ConfigReloaded);
+    }
+
     /// Get global configuration.
     pub fn get_config(&self) -> &Config {
         &self.config
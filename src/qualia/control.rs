@@ -0,0 +1,297 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains protocol of control socket which lets scripts and `perceptiactl` request
+//! actions from running compositor.
+//!
+//! Socket is created in runtime directory. Every connection carries one request and one reply,
+//! both being single lines of text. Supported requests are:
+//!
+//! ```text
+//! screenshot output <name>
+//! screenshot surface <id>
+//! screenshot region <x> <y> <width> <height>
+//! screenshot select
+//! select-region
+//! ```
+//!
+//! Region is given in global coordinates and is cut to the output containing its top left corner.
+//! Screenshot of surface contains everything visible in area of the surface.
+//!
+//! `select` and `select-region` let user select region interactively: screen is dimmed and region
+//! is dragged with pointer. Reply is sent when pointer button is released. Pressing `Escape` or
+//! other pointer button cancels the selection.
+//!
+//! Reply is either `image <base64>` with PNG image, `region <x> <y> <width> <height>` with
+//! selected region or `error <message>`.
+
+// -------------------------------------------------------------------------------------------------
+
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+
+use defs::{Area, SurfaceId, SurfaceIdType};
+use env::Env;
+use errors::Illusion;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Name of control socket in runtime directory.
+const SOCKET_NAME: &'static str = "perceptia-control";
+
+/// Characters used by base64 encoding.
+const BASE64_ALPHABET: &'static [u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Character used for padding in base64 encoding.
+const BASE64_PADDING: u8 = b'=';
+
+// -------------------------------------------------------------------------------------------------
+
+/// Returns path to control socket.
+pub fn get_socket_path() -> PathBuf {
+    Env::get_runtime_dir_path().join(SOCKET_NAME)
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Part of screen to take screenshot of.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScreenshotTarget {
+    /// Whole output with given name.
+    Output(String),
+
+    /// Area of surface with given ID.
+    Surface(SurfaceId),
+
+    /// Given area in global coordinates.
+    Region(Area),
+
+    /// Area selected interactively by user.
+    Selection,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Request sent over control socket.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ControlRequest {
+    /// Take screenshot of given target.
+    Screenshot(ScreenshotTarget),
+
+    /// Let user select region interactively and return its geometry.
+    SelectRegion,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl ControlRequest {
+    /// Parses request from line of text.
+    pub fn parse(line: &str) -> Result<Self, Illusion> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.first() {
+            Some(&"screenshot") => Self::parse_screenshot(&words[1..]),
+            Some(&"select-region") if words.len() == 1 => Ok(ControlRequest::SelectRegion),
+            Some(command) => Err(invalid(format!("unknown request '{}'", command))),
+            None => Err(invalid(format!("empty request"))),
+        }
+    }
+
+    /// Formats request as line of text (without new line character).
+    pub fn to_line(&self) -> String {
+        match *self {
+            ControlRequest::Screenshot(ScreenshotTarget::Output(ref name)) => {
+                format!("screenshot output {}", name)
+            }
+            ControlRequest::Screenshot(ScreenshotTarget::Surface(sid)) => {
+                format!("screenshot surface {}", sid.as_number())
+            }
+            ControlRequest::Screenshot(ScreenshotTarget::Region(area)) => {
+                format!("screenshot region {}", format_area(&area))
+            }
+            ControlRequest::Screenshot(ScreenshotTarget::Selection) => {
+                format!("screenshot select")
+            }
+            ControlRequest::SelectRegion => format!("select-region"),
+        }
+    }
+
+    /// Parses arguments of screenshot request.
+    fn parse_screenshot(args: &[&str]) -> Result<Self, Illusion> {
+        let target = match (args.first(), args.len()) {
+            (Some(&"output"), 2) => ScreenshotTarget::Output(args[1].to_owned()),
+            (Some(&"surface"), 2) => {
+                let sid: SurfaceIdType = parse_number(args[1])?;
+                if sid == 0 {
+                    return Err(invalid(format!("invalid surface ID")));
+                }
+                ScreenshotTarget::Surface(SurfaceId::new(sid))
+            }
+            (Some(&"region"), 5) => {
+                ScreenshotTarget::Region(parse_area(&args[1..])?)
+            }
+            (Some(&"select"), 1) => ScreenshotTarget::Selection,
+            _ => {
+                return Err(invalid(format!("expected 'output <name>', 'surface <id>', \
+                                            'region <x> <y> <width> <height>' or 'select'")));
+            }
+        };
+        Ok(ControlRequest::Screenshot(target))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Reply sent over control socket.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ControlReply {
+    /// Encoded PNG image.
+    Image(Vec<u8>),
+
+    /// Region in global coordinates.
+    Region(Area),
+
+    /// Request failed with given message.
+    Error(String),
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl ControlReply {
+    /// Parses reply from line of text.
+    pub fn parse(line: &str) -> Result<Self, Illusion> {
+        let line = line.trim_right();
+        if line.starts_with("image ") {
+            Ok(ControlReply::Image(decode_base64(&line["image ".len()..])?))
+        } else if line.starts_with("region ") {
+            let args: Vec<&str> = line["region ".len()..].split_whitespace().collect();
+            Ok(ControlReply::Region(parse_area(&args)?))
+        } else if line.starts_with("error ") {
+            Ok(ControlReply::Error(line["error ".len()..].to_owned()))
+        } else {
+            Err(invalid(format!("unknown reply")))
+        }
+    }
+
+    /// Formats reply as line of text (without new line character).
+    pub fn to_line(&self) -> String {
+        match *self {
+            ControlReply::Image(ref data) => format!("image {}", encode_base64(data)),
+            ControlReply::Region(ref area) => format!("region {}", format_area(area)),
+            ControlReply::Error(ref message) => format!("error {}", message.replace('\n', " ")),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Sends request to running compositor and waits for its reply.
+pub fn send_request(request: &ControlRequest) -> Result<ControlReply, Illusion> {
+    let mut stream = UnixStream::connect(get_socket_path())?;
+    stream.write_all(format!("{}\n", request.to_line()).as_bytes())?;
+
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line)?;
+    ControlReply::parse(&line)
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Encodes given data with base64 using standard alphabet and padding.
+pub fn encode_base64(data: &[u8]) -> String {
+    let mut result = Vec::with_capacity(4 * ((data.len() + 2) / 3));
+    for chunk in data.chunks(3) {
+        let b0 = chunk[0] as usize;
+        let b1 = if chunk.len() > 1 { chunk[1] as usize } else { 0 };
+        let b2 = if chunk.len() > 2 { chunk[2] as usize } else { 0 };
+        result.push(BASE64_ALPHABET[b0 >> 2]);
+        result.push(BASE64_ALPHABET[((b0 & 0x03) << 4) | (b1 >> 4)]);
+        if chunk.len() > 1 {
+            result.push(BASE64_ALPHABET[((b1 & 0x0F) << 2) | (b2 >> 6)]);
+        } else {
+            result.push(BASE64_PADDING);
+        }
+        if chunk.len() > 2 {
+            result.push(BASE64_ALPHABET[b2 & 0x3F]);
+        } else {
+            result.push(BASE64_PADDING);
+        }
+    }
+    String::from_utf8(result).expect("Base64 must be valid UTF-8")
+}
+
+/// Decodes data encoded with base64 using standard alphabet and padding.
+pub fn decode_base64(text: &str) -> Result<Vec<u8>, Illusion> {
+    let bytes = text.as_bytes();
+    if bytes.len() % 4 != 0 {
+        return Err(invalid(format!("length of base64 data is not multiple of four")));
+    }
+
+    let mut result = Vec::with_capacity(3 * bytes.len() / 4);
+    for (i, chunk) in bytes.chunks(4).enumerate() {
+        let is_last = 4 * (i + 1) == bytes.len();
+        let mut values = [0u32; 4];
+        let mut len = 4;
+        for j in 0..4 {
+            if chunk[j] == BASE64_PADDING && is_last && j >= 2 {
+                if j == 2 && chunk[3] != BASE64_PADDING {
+                    return Err(invalid(format!("invalid base64 padding")));
+                }
+                len = j;
+                break;
+            }
+            values[j] = match BASE64_ALPHABET.iter().position(|c| *c == chunk[j]) {
+                Some(value) => value as u32,
+                None => return Err(invalid(format!("invalid base64 character"))),
+            };
+        }
+
+        let triple = (values[0] << 18) | (values[1] << 12) | (values[2] << 6) | values[3];
+        result.push((triple >> 16) as u8);
+        if len > 2 {
+            result.push((triple >> 8) as u8);
+        }
+        if len > 3 {
+            result.push(triple as u8);
+        }
+    }
+    Ok(result)
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Parses number from request argument.
+fn parse_number<T>(arg: &str) -> Result<T, Illusion>
+    where T: std::str::FromStr
+{
+    arg.parse().map_err(|_| invalid(format!("'{}' is not valid number", arg)))
+}
+
+/// Parses non-empty area from four arguments: position and size.
+fn parse_area(args: &[&str]) -> Result<Area, Illusion> {
+    if args.len() != 4 {
+        return Err(invalid(format!("expected '<x> <y> <width> <height>'")));
+    }
+
+    let area = Area::create(parse_number(args[0])?,
+                            parse_number(args[1])?,
+                            parse_number(args[2])?,
+                            parse_number(args[3])?);
+    if area.size.width == 0 || area.size.height == 0 {
+        return Err(invalid(format!("empty region")));
+    }
+    Ok(area)
+}
+
+/// Formats area as four space-separated numbers: position and size.
+fn format_area(area: &Area) -> String {
+    format!("{} {} {} {}", area.pos.x, area.pos.y, area.size.width, area.size.height)
+}
+
+/// Constructs error for invalid request or reply.
+fn invalid(message: String) -> Illusion {
+    Illusion::InvalidArgument(message)
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -8,21 +8,72 @@
 // -------------------------------------------------------------------------------------------------
 
 use std;
+use std::os::unix::io::RawFd;
 use std::sync::{Arc, Mutex};
 
 use dharma;
 
-use defs::{Position, Size, Vector, MemoryPoolId, MemoryViewId};
-use memory::{Buffer, MappedMemory, MemoryPool, MemoryView};
+use colorimetry::ColorDescription;
+use defs::{Area, Margins, Position, Size, Vector, HwImageId, MemoryPoolId, MemoryViewId, SeatId};
+use defs::OutputConfiguration;
+use defs::seat_capability::{self, SeatCapabilities};
+use enums::{DecorationMode, SizeMismatchPolicy, Transform};
+use layer_shell::{KeyboardInteractivity, LayerSurface};
+use memory::{AlphaMode, Buffer, HwImage, MappedMemory, MemoryPool, MemoryView, PixelFormat};
+use metrics::{Metrics, StateSnapshot};
 use perceptron::{self, Perceptron};
-use surface::{Surface, SurfaceAccess, SurfaceContext, SurfaceId, SurfaceInfo};
+use positioner::Positioner;
+use surface::{Surface, SurfaceAccess, SurfaceContext, SurfaceId, SurfaceInfo, SurfacePlacement};
 use surface::{show_reason, surface_state};
+use transfer::{Drag, DragState, Transfer};
 
 // -------------------------------------------------------------------------------------------------
 
 type SurfaceMap = std::collections::HashMap<SurfaceId, Surface>;
 type MemoryViewMap = std::collections::HashMap<MemoryViewId, MemoryView>;
 type MemoryPoolMap = std::collections::HashMap<MemoryPoolId, MemoryPool>;
+type HwImageMap = std::collections::HashMap<HwImageId, HwImage>;
+type SeatMap = std::collections::HashMap<SeatId, SeatFocus>;
+type LockSurfaceMap = std::collections::HashMap<i32, SurfaceId>;
+type SeatCapabilitiesMap = std::collections::HashMap<SeatId, SeatCapabilities>;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Focus state of one seat.
+#[derive(Clone, Copy, Debug)]
+struct SeatFocus {
+    /// Keyboard-focused surface ID
+    kfsid: SurfaceId,
+
+    /// Pointer-focused surface ID
+    pfsid: SurfaceId,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Default for SeatFocus {
+    fn default() -> Self {
+        SeatFocus {
+            kfsid: SurfaceId::invalid(),
+            pfsid: SurfaceId::invalid(),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// State of locked session.
+#[derive(Clone, Debug)]
+struct SessionLock {
+    /// Tells if some client is responsible for unlocking the session.
+    held: bool,
+
+    /// Map from output ID to ID of surface covering the output.
+    surfaces: LockSurfaceMap,
+
+    /// Keyboard focus of seats to be restored after unlocking.
+    saved_focus: std::collections::HashMap<SeatId, SurfaceId>,
+}
 
 // -------------------------------------------------------------------------------------------------
 
@@ -83,6 +134,9 @@ struct InnerCoordinator {
     /// Storage for all memory pools.
     memory_pools: MemoryPoolMap,
 
+    /// Storage for all hardware images.
+    hw_images: HwImageMap,
+
     /// Counter of surface IDs
     last_surface_id: SurfaceId,
 
@@ -92,11 +146,42 @@ struct InnerCoordinator {
     /// Counter of memory pool IDs
     last_memory_pool_id: MemoryPoolId,
 
-    /// Currently keyboard-focused surface ID
-    kfsid: SurfaceId,
+    /// Counter of hardware image IDs
+    last_hw_image_id: HwImageId,
 
-    /// Currently pointer-focused surface ID
-    pfsid: SurfaceId,
+    /// Counter of output configuration request IDs
+    last_output_configuration_id: u32,
+
+    /// Focus state of seats. Seats without entry have nothing focused.
+    seats: SeatMap,
+
+    /// Kinds of input devices available in seats. Seats without entry have no devices.
+    seat_capabilities: SeatCapabilitiesMap,
+
+    /// Idle timeouts in milliseconds requested by clients. May contain duplicates.
+    idle_timeouts: Vec<u32>,
+
+    /// State of session lock. `None` if session is not locked.
+    session_lock: Option<SessionLock>,
+
+    /// Surfaces placed in layers in order of creation.
+    layer_surfaces: Vec<LayerSurface>,
+
+    /// Keyboard focus of seats to be restored after layer surface with exclusive keyboard
+    /// interactivity is gone.
+    layer_saved_focus: std::collections::HashMap<SeatId, SurfaceId>,
+
+    /// Current data transfer (selection)
+    transfer: Option<Transfer>,
+
+    /// Current drag-and-drop operation
+    drag: Option<Drag>,
+
+    /// Policy of drawing surfaces not matching size configured by compositor
+    size_mismatch_policy: SizeMismatchPolicy,
+
+    /// Statistics of compositor performance
+    metrics: Metrics,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -109,11 +194,22 @@ impl InnerCoordinator {
             surfaces: SurfaceMap::new(),
             memory_views: MemoryViewMap::new(),
             memory_pools: MemoryPoolMap::new(),
+            hw_images: HwImageMap::new(),
             last_surface_id: SurfaceId::invalid(),
             last_memory_view_id: MemoryViewId::initial(),
             last_memory_pool_id: MemoryPoolId::initial(),
-            kfsid: SurfaceId::invalid(),
-            pfsid: SurfaceId::invalid(),
+            last_hw_image_id: HwImageId::initial(),
+            last_output_configuration_id: 0,
+            seats: SeatMap::new(),
+            seat_capabilities: SeatCapabilitiesMap::new(),
+            idle_timeouts: Vec::new(),
+            session_lock: None,
+            layer_surfaces: Vec::new(),
+            layer_saved_focus: std::collections::HashMap::new(),
+            transfer: None,
+            drag: None,
+            size_mismatch_policy: SizeMismatchPolicy::Clip,
+            metrics: Metrics::new(),
         }
     }
 
@@ -147,37 +243,473 @@ impl InnerCoordinator {
                 }
             }
         }
+
+        // Surface and its satellites are moved to make space for decoration drawn below them
+        let offset = surface.get_margins().get_offset();
+        let mut result: Vec<SurfaceContext> =
+            result.iter().map(|context| context.moved(offset)).collect();
+        if let Some(decoration) = self.surfaces.get(&surface.get_decoration_sid()) {
+            result.insert(0, decoration.get_renderer_context());
+        }
         Some(result)
     }
 
-    /// Returns ID of currently keyboard-focussed surface.
+    /// Calculates which part of surface from given rendering context is drawn and where.
+    /// Returns `None` if the surface has no buffer.
+    pub fn get_surface_placement(&self, context: &SurfaceContext) -> Option<SurfacePlacement> {
+        let surface = try_get_surface_or_none!(self, context.id);
+        surface.get_size().map(|size| {
+            let info = surface.get_info();
+            let placement = SurfacePlacement::new(self.size_mismatch_policy,
+                                                  size,
+                                                  info.offset,
+                                                  info.requested_size,
+                                                  context.bounds);
+            if context.scale != 1.0 {
+                placement.scaled(context.scale)
+            } else {
+                placement
+            }
+        })
+    }
+
+    /// Returns policy of drawing surfaces whose size differs from size configured by compositor.
+    pub fn get_size_mismatch_policy(&self) -> SizeMismatchPolicy {
+        self.size_mismatch_policy
+    }
+
+    /// Sets policy of drawing surfaces whose size differs from size configured by compositor.
+    pub fn set_size_mismatch_policy(&mut self, policy: SizeMismatchPolicy) {
+        self.size_mismatch_policy = policy;
+    }
+
+    /// Records time it took to draw frame on given output.
+    pub fn record_frame(&mut self, output: &str, duration: std::time::Duration) {
+        self.metrics.record_frame(output, duration);
+    }
+
+    /// Sets number of connected clients.
+    pub fn set_client_count(&mut self, count: usize) {
+        self.metrics.set_client_count(count);
+    }
+
+    /// Returns gathered statistics and current state in Prometheus text format.
+    pub fn format_metrics(&self) -> String {
+        let snapshot = StateSnapshot {
+            surfaces: self.surfaces.len(),
+            memory_pools: self.memory_pools.len(),
+            memory_pool_bytes: self.memory_pools.values().map(|pool| pool.get_size()).sum(),
+            hw_images: self.hw_images.len(),
+        };
+        self.metrics.format(&snapshot)
+    }
+
+    /// Returns ID of currently keyboard-focussed surface of the default seat.
     pub fn get_keyboard_focused_sid(&self) -> SurfaceId {
-        self.kfsid
+        self.get_seat_keyboard_focused_sid(SeatId::default_seat())
     }
 
-    /// Informs rest of the application exhibitor set keyboard focus to given surface.
+    /// Informs rest of the application exhibitor set keyboard focus of the default seat to given
+    /// surface.
     pub fn set_keyboard_focus(&mut self, sid: SurfaceId) {
-        if self.kfsid != sid {
-            self.signaler.emit(perceptron::KEYBOARD_FOCUS_CHANGED,
-                               Perceptron::KeyboardFocusChanged(self.kfsid, sid));
-            self.kfsid = sid;
+        self.set_seat_keyboard_focus(SeatId::default_seat(), sid)
+    }
+
+    /// Returns ID of surface currently keyboard-focussed by given seat.
+    pub fn get_seat_keyboard_focused_sid(&self, seat: SeatId) -> SurfaceId {
+        self.seats.get(&seat).map_or(SurfaceId::invalid(), |focus| focus.kfsid)
+    }
+
+    /// Sets keyboard focus of given seat and informs rest of the application about it. Change of
+    /// focus of the default seat is signalled with `KeyboardFocusChanged`, of other seats with
+    /// `SeatKeyboardFocusChanged`.
+    ///
+    /// While session is locked only lock surfaces can be focused. Focus requested for other
+    /// surfaces is remembered and restored after unlocking.
+    ///
+    /// Layer surfaces without keyboard interactivity are never focused. While layer surface with
+    /// exclusive keyboard interactivity is present other surfaces can not be focused. Focus
+    /// requested for them is remembered and restored after the layer surface is gone.
+    pub fn set_seat_keyboard_focus(&mut self, seat: SeatId, sid: SurfaceId) {
+        if !self.is_input_allowed(sid) {
+            if let Some(ref mut lock) = self.session_lock {
+                lock.saved_focus.insert(seat, sid);
+            }
+            return;
+        }
+
+        let interactivity = self.get_layer_surface(sid).map(|layer| layer.keyboard_interactivity);
+        if interactivity == Some(KeyboardInteractivity::None) {
+            return;
+        }
+
+        if let Some(exclusive_sid) = self.find_exclusive_layer_surface() {
+            if !self.is_session_locked() && (sid != exclusive_sid) {
+                self.layer_saved_focus.insert(seat, sid);
+                return;
+            }
+        }
+
+        let focus = self.seats.entry(seat).or_insert_with(SeatFocus::default);
+        if focus.kfsid != sid {
+            if seat.is_default() {
+                self.signaler.emit(perceptron::KEYBOARD_FOCUS_CHANGED,
+                                   Perceptron::KeyboardFocusChanged(focus.kfsid, sid));
+            } else {
+                self.signaler.emit(perceptron::SEAT_KEYBOARD_FOCUS_CHANGED,
+                                   Perceptron::SeatKeyboardFocusChanged(seat, focus.kfsid, sid));
+            }
+            focus.kfsid = sid;
+        }
+    }
+
+    /// Returns kinds of input devices currently available in given seat.
+    pub fn get_seat_capabilities(&self, seat: SeatId) -> SeatCapabilities {
+        self.seat_capabilities.get(&seat).cloned().unwrap_or(seat_capability::NONE)
+    }
+
+    /// Sets kinds of input devices available in given seat and informs rest of the application
+    /// with `SeatCapabilitiesChanged` if they changed.
+    pub fn set_seat_capabilities(&mut self, seat: SeatId, capabilities: SeatCapabilities) {
+        if self.get_seat_capabilities(seat) != capabilities {
+            self.seat_capabilities.insert(seat, capabilities);
+            self.signaler.emit(perceptron::SEAT_CAPABILITIES_CHANGED,
+                               Perceptron::SeatCapabilitiesChanged(seat, capabilities));
+        }
+    }
+
+    /// Returns idle timeouts requested by clients.
+    pub fn get_idle_timeouts(&self) -> Vec<u32> {
+        self.idle_timeouts.clone()
+    }
+
+    /// Registers idle timeout in milliseconds. `Idle` will be signalled when user is inactive for
+    /// this time.
+    pub fn add_idle_timeout(&mut self, timeout: u32) {
+        self.idle_timeouts.push(timeout);
+    }
+
+    /// Unregisters one instance of idle timeout.
+    pub fn remove_idle_timeout(&mut self, timeout: u32) {
+        if let Some(index) = self.idle_timeouts.iter().position(|t| *t == timeout) {
+            self.idle_timeouts.remove(index);
+        }
+    }
+
+    /// Checks if session is locked.
+    pub fn is_session_locked(&self) -> bool {
+        self.session_lock.is_some()
+    }
+
+    /// Locks the session. Keyboard and pointer focus is taken from all surfaces. Returns `false` if
+    /// the session is already locked and the lock is held by other client.
+    pub fn lock_session(&mut self) -> bool {
+        if let Some(ref mut lock) = self.session_lock {
+            if lock.held {
+                return false;
+            }
+            lock.held = true;
+            return true;
+        }
+
+        let saved_focus = self.seats.iter().map(|(seat, focus)| (*seat, focus.kfsid)).collect();
+        let seats: Vec<SeatId> = self.seats.keys().cloned().collect();
+        for seat in seats {
+            self.set_seat_keyboard_focus(seat, SurfaceId::invalid());
+        }
+        self.set_pointer_focus(SurfaceId::invalid(), Position::default());
+
+        self.session_lock = Some(SessionLock {
+                                     held: true,
+                                     surfaces: LockSurfaceMap::new(),
+                                     saved_focus: saved_focus,
+                                 });
+        self.signaler.emit(perceptron::SESSION_LOCK_CHANGED, Perceptron::SessionLockChanged(true));
+        self.notify();
+        true
+    }
+
+    /// Informs that client holding the lock vanished without unlocking. Session stays locked but
+    /// other client may take over the lock.
+    pub fn abandon_session_lock(&mut self) {
+        if let Some(ref mut lock) = self.session_lock {
+            lock.held = false;
+        }
+    }
+
+    /// Unlocks the session and restores keyboard focus from before locking.
+    pub fn unlock_session(&mut self) {
+        if let Some(lock) = self.session_lock.take() {
+            for (seat, sid) in lock.saved_focus {
+                let sid = if self.surfaces.contains_key(&sid) { sid } else { SurfaceId::invalid() };
+                self.set_seat_keyboard_focus(seat, sid);
+            }
+            self.signaler.emit(perceptron::SESSION_LOCK_CHANGED,
+                               Perceptron::SessionLockChanged(false));
+            self.notify();
+        }
+    }
+
+    /// Sets given surface to cover output with given ID while session is locked. The first lock
+    /// surface receives keyboard focus. Returns `false` if session is not locked or the output
+    /// already has lock surface.
+    pub fn add_lock_surface(&mut self, sid: SurfaceId, output_id: i32) -> bool {
+        let first = if let Some(ref mut lock) = self.session_lock {
+            if lock.surfaces.contains_key(&output_id) {
+                return false;
+            }
+            lock.surfaces.insert(output_id, sid);
+            lock.surfaces.len() == 1
+        } else {
+            return false;
+        };
+
+        self.signaler.emit(perceptron::LOCK_SURFACE_ADDED,
+                           Perceptron::LockSurfaceAdded(sid, output_id));
+        if first {
+            self.set_keyboard_focus(sid);
+        }
+        true
+    }
+
+    /// Stops using given surface as lock surface. If it was focused, keyboard focus moves to other
+    /// lock surface. Output without lock surface is left blank.
+    pub fn remove_lock_surface(&mut self, sid: SurfaceId) {
+        let remaining = if let Some(ref mut lock) = self.session_lock {
+            lock.surfaces.retain(|_, lock_sid| *lock_sid != sid);
+            lock.surfaces.values().next().cloned()
+        } else {
+            return;
+        };
+
+        if self.get_keyboard_focused_sid() == sid {
+            self.set_keyboard_focus(remaining.unwrap_or(SurfaceId::invalid()));
+        }
+        self.notify();
+    }
+
+    /// Returns ID of surface covering output with given ID while session is locked.
+    pub fn get_lock_surface(&self, output_id: i32) -> Option<SurfaceId> {
+        self.session_lock.as_ref().and_then(|lock| lock.surfaces.get(&output_id).cloned())
+    }
+
+    /// Returns state of given layer surface or `None` if the surface is not placed in layer.
+    pub fn get_layer_surface(&self, sid: SurfaceId) -> Option<LayerSurface> {
+        self.layer_surfaces.iter().find(|layer| layer.sid == sid).cloned()
+    }
+
+    /// Returns states of all layer surfaces in order of creation.
+    pub fn get_layer_surfaces(&self) -> Vec<LayerSurface> {
+        self.layer_surfaces.clone()
+    }
+
+    /// Adds layer surface or updates its state. Output chosen by compositor is kept if new state
+    /// does not specify it. If the surface takes keyboard focus exclusively, focus of all seats
+    /// moves to it.
+    pub fn set_layer_surface(&mut self, mut state: LayerSurface) {
+        let sid = state.sid;
+        let previous_exclusive_sid = self.find_exclusive_layer_surface();
+        match self.layer_surfaces.iter().position(|layer| layer.sid == sid) {
+            Some(index) => {
+                if state.output_id.is_none() {
+                    state.output_id = self.layer_surfaces[index].output_id;
+                }
+                if self.layer_surfaces[index] == state {
+                    return;
+                }
+                self.layer_surfaces[index] = state;
+            }
+            None => self.layer_surfaces.push(state),
+        }
+
+        self.update_layer_focus(previous_exclusive_sid);
+        self.signaler.emit(perceptron::LAYER_SURFACE_CHANGED, Perceptron::LayerSurfaceChanged(sid));
+        self.notify();
+    }
+
+    /// Sets output chosen by compositor for layer surface which did not specify one.
+    pub fn set_layer_surface_output(&mut self, sid: SurfaceId, output_id: i32) {
+        if let Some(layer) = self.layer_surfaces.iter_mut().find(|layer| layer.sid == sid) {
+            layer.output_id = Some(output_id);
+        }
+    }
+
+    /// Stops placing given surface in layer. Keyboard focus taken by the surface exclusively is
+    /// restored.
+    pub fn remove_layer_surface(&mut self, sid: SurfaceId) {
+        let previous_exclusive_sid = self.find_exclusive_layer_surface();
+        let len = self.layer_surfaces.len();
+        self.layer_surfaces.retain(|layer| layer.sid != sid);
+        if self.layer_surfaces.len() != len {
+            self.update_layer_focus(previous_exclusive_sid);
+            self.signaler.emit(perceptron::LAYER_SURFACE_CHANGED,
+                               Perceptron::LayerSurfaceChanged(sid));
+            self.notify();
         }
     }
 
     /// Returns ID of currently pointer-focussed surface.
     pub fn get_pointer_focused_sid(&self) -> SurfaceId {
-        self.pfsid
+        self.seats.get(&SeatId::default_seat()).map_or(SurfaceId::invalid(), |focus| focus.pfsid)
     }
 
-    /// Informs rest of the application exhibitor set pointer focus to given surface.
+    /// Informs rest of the application exhibitor set pointer focus to given surface. Pointer is
+    /// shared by all seats. While session is locked surfaces other than lock surfaces can not be
+    /// focused.
     pub fn set_pointer_focus(&mut self, sid: SurfaceId, position: Position) {
-        if self.pfsid != sid {
+        let sid = if self.is_input_allowed(sid) { sid } else { SurfaceId::invalid() };
+        let focus = self.seats.entry(SeatId::default_seat()).or_insert_with(SeatFocus::default);
+        if focus.pfsid != sid {
             self.signaler.emit(perceptron::POINTER_FOCUS_CHANGED,
-                               Perceptron::PointerFocusChanged(self.pfsid, sid, position));
-            self.pfsid = sid;
+                               Perceptron::PointerFocusChanged(focus.pfsid, sid, position));
+            focus.pfsid = sid;
+        }
+    }
+
+    /// Returns current data transfer (selection).
+    pub fn get_transfer(&self) -> Option<Transfer> {
+        self.transfer.clone()
+    }
+
+    /// Sets new data transfer (selection) and informs rest of the application about it. `None`
+    /// means selection was cleared.
+    pub fn set_transfer(&mut self, transfer: Option<Transfer>) {
+        self.transfer = transfer;
+        self.signaler.emit(perceptron::TRANSFER_OFFERED, Perceptron::TransferOffered);
+    }
+
+    /// Informs rest of the application about request from client to receive data of current
+    /// transfer in given MIME type via given file descriptor.
+    pub fn request_transfer(&mut self, mime_type: String, fd: RawFd) {
+        self.signaler.emit(perceptron::TRANSFER_REQUESTED,
+                           Perceptron::TransferRequested(mime_type, fd));
+    }
+
+    /// Returns current drag-and-drop operation.
+    pub fn get_drag(&self) -> Option<Drag> {
+        self.drag.clone()
+    }
+
+    /// Starts drag-and-drop operation if no other is in progress.
+    pub fn start_drag(&mut self, transfer: Option<Transfer>, origin: SurfaceId, icon: SurfaceId) {
+        if self.drag.is_none() {
+            self.drag = Some(Drag::new(transfer, origin, icon));
+            self.signaler.emit(perceptron::DRAG_STARTED, Perceptron::DragStarted);
+        } else {
+            log_warn2!("Drag already in progress");
+        }
+    }
+
+    /// Informs rest of the application exhibitor changed surface over which data is dragged.
+    pub fn set_drag_target(&mut self, sid: SurfaceId, position: Position) {
+        if let Some(ref mut drag) = self.drag {
+            if drag.state == DragState::Dragging && drag.target != sid {
+                self.signaler.emit(perceptron::DRAG_FOCUS_CHANGED,
+                                   Perceptron::DragFocusChanged(drag.target, sid, position));
+                drag.target = sid;
+            }
+        }
+    }
+
+    /// Drops dragged data on current target. If there is no target or no data to transfer the drag
+    /// is cancelled.
+    pub fn drop_drag(&mut self) {
+        let target = if let Some(ref mut drag) = self.drag {
+            if drag.state != DragState::Dragging {
+                return;
+            }
+            if drag.target.is_valid() && drag.transfer.is_some() {
+                drag.state = DragState::Dropped;
+                Some(drag.target)
+            } else {
+                None
+            }
+        } else {
+            return;
+        };
+
+        if let Some(target) = target {
+            self.signaler.emit(perceptron::DRAG_DROPPED, Perceptron::DragDropped(target));
+        } else {
+            self.end_drag(false);
+        }
+    }
+
+    /// Ends drag-and-drop operation. `performed` tells if data was successfully dropped.
+    pub fn end_drag(&mut self, performed: bool) {
+        if self.drag.is_some() {
+            self.drag = None;
+            self.signaler.emit(perceptron::DRAG_ENDED, Perceptron::DragEnded(performed));
         }
     }
 
+    /// Informs rest of the application about request from client to receive dragged data in given
+    /// MIME type via given file descriptor.
+    pub fn request_drag_transfer(&mut self, mime_type: String, fd: RawFd) {
+        self.signaler.emit(perceptron::DRAG_TRANSFER_REQUESTED,
+                           Perceptron::DragTransferRequested(mime_type, fd));
+    }
+
+    /// Informs rest of the application about request from client to move given surface
+    /// interactively.
+    pub fn request_move(&mut self, sid: SurfaceId) {
+        self.signaler.emit(perceptron::MOVE_REQUESTED, Perceptron::MoveRequested(sid));
+    }
+
+    /// Informs rest of the application about request from client to capture contents of output
+    /// with given ID.
+    pub fn request_capture(&mut self, output_id: i32) {
+        self.signaler.emit(perceptron::CAPTURE_REQUESTED, Perceptron::CaptureRequested(output_id));
+    }
+
+    /// Informs rest of the application about request from client to configure outputs. If `test`
+    /// is set the configuration is only validated. Returns ID of the request which will be passed
+    /// back with the result.
+    pub fn request_output_configuration(&mut self,
+                                        configurations: Vec<OutputConfiguration>,
+                                        test: bool)
+                                        -> u32 {
+        self.last_output_configuration_id = self.last_output_configuration_id.wrapping_add(1);
+        let id = self.last_output_configuration_id;
+        self.signaler.emit(perceptron::OUTPUT_CONFIGURATION_REQUESTED,
+                           Perceptron::OutputConfigurationRequested(id, configurations, test));
+        id
+    }
+
+    /// Informs rest of the application about request from client to make given surface fullscreen
+    /// (optionally on output with given ID) or to restore it.
+    pub fn request_fullscreen(&mut self, sid: SurfaceId, fullscreen: bool, output_id: Option<i32>) {
+        self.signaler.emit(perceptron::FULLSCREEN_REQUESTED,
+                           Perceptron::FullscreenRequested(sid, fullscreen, output_id));
+    }
+
+    /// Informs rest of the application about request from client to maximize or restore given
+    /// surface.
+    pub fn request_maximized(&mut self, sid: SurfaceId, maximized: bool) {
+        self.signaler.emit(perceptron::MAXIMIZE_REQUESTED,
+                           Perceptron::MaximizeRequested(sid, maximized));
+    }
+
+    /// Informs rest of the application that given surface needs to be configured before client
+    /// attaches its first buffer.
+    pub fn request_initial_size(&mut self, sid: SurfaceId) {
+        self.signaler.emit(perceptron::INITIAL_SIZE_REQUESTED,
+                           Perceptron::InitialSizeRequested(sid));
+    }
+
+    /// Informs rest of the application about request from compositor to close given surface.
+    pub fn request_close(&mut self, sid: SurfaceId) {
+        self.signaler.emit(perceptron::SURFACE_CLOSE_REQUESTED,
+                           Perceptron::SurfaceCloseRequested(sid));
+    }
+
+    /// Informs rest of the application about request from compositor to disconnect client owning
+    /// given surface.
+    pub fn request_kill(&mut self, sid: SurfaceId) {
+        self.signaler.emit(perceptron::SURFACE_KILL_REQUESTED,
+                           Perceptron::SurfaceKillRequested(sid));
+    }
+
     /// Creates new memory pool from mapped memory. Returns ID of newly created pool.
     pub fn create_pool_from_memory(&mut self, memory: MappedMemory) -> MemoryPoolId {
         let mpid = self.generate_next_memory_pool_id();
@@ -211,11 +743,14 @@ impl InnerCoordinator {
                               offset: usize,
                               width: usize,
                               height: usize,
-                              stride: usize)
+                              stride: usize,
+                              format: PixelFormat,
+                              alpha_mode: AlphaMode)
                               -> Option<MemoryViewId> {
         let id = self.generate_next_memory_view_id();
         if let Some(memory_pool) = self.memory_pools.get(&mpid) {
-            let memory_view = memory_pool.get_memory_view(offset, width, height, stride);
+            let memory_view =
+                memory_pool.get_memory_view(offset, width, height, stride, format, alpha_mode);
             self.memory_views.insert(id, memory_view);
             Some(id)
         } else {
@@ -229,6 +764,23 @@ impl InnerCoordinator {
         self.memory_views.remove(&mvid);
     }
 
+    /// Returns memory view with given ID.
+    pub fn get_memory_view(&self, mvid: MemoryViewId) -> Option<MemoryView> {
+        self.memory_views.get(&mvid).cloned()
+    }
+
+    /// Stores new hardware image.
+    pub fn create_hw_image(&mut self, image: HwImage) -> HwImageId {
+        let id = self.generate_next_hw_image_id();
+        self.hw_images.insert(id, image);
+        id
+    }
+
+    /// Destroys hardware image. Surfaces still using the image keep their copies of it.
+    pub fn destroy_hw_image(&mut self, hiid: HwImageId) {
+        self.hw_images.remove(&hiid);
+    }
+
     /// Creates new surface with newly generated unique ID.
     pub fn create_surface(&mut self) -> SurfaceId {
         let id = self.generate_next_surface_id();
@@ -245,6 +797,19 @@ impl InnerCoordinator {
     pub fn destroy_surface(&mut self, sid: SurfaceId) {
         self.detach_surface(sid);
         self.surfaces.remove(&sid);
+
+        // Exhibitor takes care of focus of the default seat; focus of other seats is dropped here
+        let seats: Vec<SeatId> = self.seats
+            .iter()
+            .filter(|&(seat, focus)| !seat.is_default() && (focus.kfsid == sid))
+            .map(|(seat, _)| *seat)
+            .collect();
+        for seat in seats {
+            self.set_seat_keyboard_focus(seat, SurfaceId::invalid());
+        }
+
+        self.remove_lock_surface(sid);
+        self.remove_layer_surface(sid);
     }
 
     /// Sets given buffer as pending for given surface.
@@ -254,6 +819,46 @@ impl InnerCoordinator {
         surface.attach(view.clone());
     }
 
+    /// Sets given hardware image as pending for given surface.
+    pub fn attach_hw_image(&mut self, hiid: HwImageId, sid: SurfaceId) {
+        if let Some(image) = self.hw_images.get(&hiid).cloned() {
+            let surface = try_get_surface!(self, sid);
+            surface.attach_hw_image(image);
+        } else {
+            log_warn2!("Hardware image {:?} not found!", hiid);
+        }
+    }
+
+    /// Adds given region to pending damage of given surface.
+    pub fn damage_surface(&mut self, sid: SurfaceId, area: Area) {
+        let surface = try_get_surface!(self, sid);
+        surface.damage(area);
+    }
+
+    /// Adds damage region given in buffer coordinates to given surface.
+    pub fn damage_surface_buffer(&mut self, sid: SurfaceId, area: Area) {
+        let surface = try_get_surface!(self, sid);
+        surface.damage_buffer(area);
+    }
+
+    /// Sets scale of buffer of given surface to be applied on commit.
+    pub fn set_surface_buffer_scale(&mut self, sid: SurfaceId, scale: u32) {
+        let surface = try_get_surface!(self, sid);
+        surface.set_buffer_scale(scale);
+    }
+
+    /// Sets transformation of buffer of given surface to be applied on commit.
+    pub fn set_surface_buffer_transform(&mut self, sid: SurfaceId, transform: Transform) {
+        let surface = try_get_surface!(self, sid);
+        surface.set_buffer_transform(transform);
+    }
+
+    /// Removes damage regions of given surface which were already drawn in given generation.
+    pub fn discard_damage(&mut self, sid: SurfaceId, drawn: &Vec<Area>, generation: u64) {
+        let surface = try_get_surface!(self, sid);
+        surface.discard_damage(drawn, generation);
+    }
+
     /// Sets pending buffer of given surface as current. Corrects sizes adds `drawable` show reason.
     pub fn commit_surface(&mut self, sid: SurfaceId) {
         if {
@@ -296,12 +901,108 @@ impl InnerCoordinator {
         surface.set_requested_size(size)
     }
 
+    /// Sets minimal and maximal size requested for given surface. Zero means no limit.
+    pub fn set_surface_size_limits(&mut self, sid: SurfaceId, min_size: Size, max_size: Size) {
+        let surface = try_get_surface!(self, sid);
+        surface.set_min_size(min_size);
+        surface.set_max_size(max_size);
+    }
+
+    /// Sets window geometry for given surface. It will be applied on next commit.
+    pub fn set_surface_window_geometry(&mut self, sid: SurfaceId, geometry: Area) {
+        let surface = try_get_surface!(self, sid);
+        surface.set_window_geometry(geometry)
+    }
+
+    /// Sets identifier of application owning given surface.
+    pub fn set_surface_app_id(&mut self, sid: SurfaceId, app_id: String) {
+        let surface = try_get_surface!(self, sid);
+        surface.set_app_id(app_id)
+    }
+
+    /// Sets title of window of given surface and informs rest of the application about the change.
+    pub fn set_surface_title(&mut self, sid: SurfaceId, title: String) {
+        let surface = try_get_surface!(self, sid);
+        surface.set_title(title);
+        self.signaler.emit(perceptron::SURFACE_TITLE_CHANGED, Perceptron::SurfaceTitleChanged(sid));
+    }
+
+    /// Sets if contents of given surface should be hidden in screen captures.
+    pub fn set_surface_capture_excluded(&mut self, sid: SurfaceId, excluded: bool) {
+        let surface = try_get_surface!(self, sid);
+        surface.set_capture_excluded(excluded)
+    }
+
+    /// Sets description of colors of contents of given surface.
+    pub fn set_surface_color_description(&mut self,
+                                         sid: SurfaceId,
+                                         description: ColorDescription) {
+        let surface = try_get_surface!(self, sid);
+        surface.set_color_description(description)
+    }
+
+    /// Sets region of given surface declared by client as opaque.
+    pub fn set_surface_opaque_region(&mut self, sid: SurfaceId, region: Option<Area>) {
+        let surface = try_get_surface!(self, sid);
+        surface.set_opaque_region(region)
+    }
+
+    /// Sets decoration surface of given surface and space reserved for it. The surface is shrunk
+    /// to make space for the decoration and notification is sent if its size changed. Invalid
+    /// decoration ID and empty margins remove the decoration.
+    pub fn set_surface_decoration(&mut self,
+                                  sid: SurfaceId,
+                                  decoration_sid: SurfaceId,
+                                  margins: Margins) {
+        let surface = try_get_surface!(self, sid);
+        let old_size = surface.get_desired_size();
+        surface.set_decoration(decoration_sid, margins);
+        if surface.get_desired_size() != old_size {
+            self.signaler.emit(perceptron::SURFACE_RECONFIGURED,
+                               Perceptron::SurfaceReconfigured(sid));
+        }
+    }
+
+    /// Stores decoration mode preferred by client for given surface and informs rest of the
+    /// application that the mode should be chosen.
+    pub fn request_decoration_mode(&mut self, sid: SurfaceId, mode: Option<DecorationMode>) {
+        let surface = try_get_surface!(self, sid);
+        surface.set_requested_decoration_mode(mode);
+        self.signaler.emit(perceptron::DECORATION_MODE_REQUESTED,
+                           Perceptron::DecorationModeRequested(sid));
+    }
+
+    /// Sets decoration mode chosen for given surface and informs client about it.
+    pub fn set_surface_decoration_mode(&mut self, sid: SurfaceId, mode: DecorationMode) {
+        let surface = try_get_surface!(self, sid);
+        surface.set_decoration_mode(mode);
+        self.signaler.emit(perceptron::DECORATION_MODE_CHANGED,
+                           Perceptron::DecorationModeChanged(sid));
+    }
+
     /// Sets satellite surface position relative to its parent.
     pub fn set_surface_relative_position(&mut self, sid: SurfaceId, position: Position) {
         let surface = try_get_surface!(self, sid);
         surface.set_relative_position(position)
     }
 
+    /// Sets rules for placing pop-up relative to its parent and informs rest of the application
+    /// that the pop-up needs to be placed.
+    pub fn set_surface_positioner(&mut self, sid: SurfaceId, positioner: Positioner) {
+        let surface = try_get_surface!(self, sid);
+        surface.set_positioner(positioner);
+        self.signaler.emit(perceptron::PLACEMENT_REQUESTED, Perceptron::PlacementRequested(sid));
+    }
+
+    /// Places satellite surface in given area relative to its parent and sends notification about
+    /// this event.
+    pub fn place_surface(&mut self, sid: SurfaceId, area: Area) {
+        let surface = try_get_surface!(self, sid);
+        surface.set_relative_position(area.pos);
+        surface.set_desired_size(area.size);
+        self.signaler.emit(perceptron::SURFACE_RECONFIGURED, Perceptron::SurfaceReconfigured(sid));
+    }
+
     /// Relates two surfaces.
     pub fn relate_surfaces(&mut self, sid: SurfaceId, parent_sid: SurfaceId) {
         {
@@ -338,6 +1039,20 @@ impl InnerCoordinator {
                        size: Size,
                        state_flags: surface_state::SurfaceState) {
         let surface = try_get_surface!(self, sid);
+
+        // Fullscreen surfaces cover whole output regardless of layout.
+        if surface.get_state_flags().contains(surface_state::FULLSCREEN) {
+            return;
+        }
+
+        // Given size includes space reserved for decoration. Size limits set by client are
+        // respected even if the surface will not fill its frame.
+        let (min_size, max_size) = surface.get_size_limits();
+        let size = surface.get_margins().shrink(size).clamped(min_size, max_size);
+
+        // Suspension is not decided by layout so it must be preserved.
+        let state_flags = state_flags | (surface.get_state_flags() & surface_state::SUSPENDED);
+
         if (surface.get_desired_size() != size) || (surface.get_state_flags() != state_flags) {
             surface.set_desired_size(size);
             surface.set_state_flags(state_flags);
@@ -345,25 +1060,129 @@ impl InnerCoordinator {
                                Perceptron::SurfaceReconfigured(sid));
         }
     }
-}
-
-// -------------------------------------------------------------------------------------------------
 
-// Helper functions
-impl InnerCoordinator {
-    // FIXME: Finish implementation of Coordinator (counter)
-    fn generate_next_surface_id(&mut self) -> SurfaceId {
-        self.last_surface_id = SurfaceId::new(self.last_surface_id.as_number() as u64 + 1);
-        self.last_surface_id
+    /// Sets size and state surface is expected to have when it is mapped. Unlike `reconfigure`
+    /// notification is sent even if nothing changed because client waits for initial
+    /// configuration before attaching first buffer. Zero size lets client choose the size.
+    pub fn set_surface_initial_size(&mut self,
+                                    sid: SurfaceId,
+                                    size: Size,
+                                    state_flags: surface_state::SurfaceState) {
+        let surface = try_get_surface!(self, sid);
+        let size = if size.is_zero() {
+            size
+        } else {
+            let (min_size, max_size) = surface.get_size_limits();
+            surface.get_margins().shrink(size).clamped(min_size, max_size)
+        };
+        let state_flags = state_flags | (surface.get_state_flags() & surface_state::SUSPENDED);
+        surface.set_desired_size(size);
+        surface.set_state_flags(state_flags);
+        self.signaler.emit(perceptron::SURFACE_RECONFIGURED, Perceptron::SurfaceReconfigured(sid));
     }
-    // FIXME: Finish implementation of Coordinator
-    fn generate_next_memory_pool_id(&mut self) -> MemoryPoolId {
+
+    /// Makes surface fullscreen with given size or, if `None` is given, ends fullscreen state so
+    /// the surface can be reconfigured by layout again. Sends notification in both cases.
+    pub fn set_surface_fullscreen(&mut self, sid: SurfaceId, size: Option<Size>) {
+        let surface = try_get_surface!(self, sid);
+        let old_state_flags = surface.get_state_flags();
+        if let Some(size) = size {
+            surface.set_desired_size(size);
+            surface.set_state_flags((old_state_flags & surface_state::SUSPENDED) |
+                                    surface_state::FULLSCREEN);
+        } else {
+            surface.set_state_flags(old_state_flags - surface_state::FULLSCREEN);
+        }
+        self.signaler.emit(perceptron::SURFACE_RECONFIGURED, Perceptron::SurfaceReconfigured(sid));
+    }
+
+    /// Marks surface as suspended (e.g. when it is placed on invisible workspace) or resumes it
+    /// and sends notification if the state changed. Clients may stop rendering suspended surfaces.
+    pub fn set_surface_suspended(&mut self, sid: SurfaceId, suspended: bool) {
+        let surface = try_get_surface!(self, sid);
+        let old_state_flags = surface.get_state_flags();
+        let state_flags = if suspended {
+            old_state_flags | surface_state::SUSPENDED
+        } else {
+            old_state_flags - surface_state::SUSPENDED
+        };
+        if state_flags != old_state_flags {
+            surface.set_state_flags(state_flags);
+            self.signaler.emit(perceptron::SURFACE_RECONFIGURED,
+                               Perceptron::SurfaceReconfigured(sid));
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Helper functions
+impl InnerCoordinator {
+    // FIXME: Finish implementation of Coordinator (counter)
+    fn generate_next_surface_id(&mut self) -> SurfaceId {
+        self.last_surface_id = SurfaceId::new(self.last_surface_id.as_number() as u64 + 1);
+        self.last_surface_id
+    }
+    // FIXME: Finish implementation of Coordinator
+    fn generate_next_memory_pool_id(&mut self) -> MemoryPoolId {
         self.last_memory_pool_id.increment()
     }
     // FIXME: Finish implementation of Coordinator
     fn generate_next_memory_view_id(&mut self) -> MemoryViewId {
         self.last_memory_view_id.increment()
     }
+
+    fn generate_next_hw_image_id(&mut self) -> HwImageId {
+        self.last_hw_image_id.increment()
+    }
+
+    /// Checks if given surface may receive input. While session is locked only lock surfaces can.
+    fn is_input_allowed(&self, sid: SurfaceId) -> bool {
+        match self.session_lock {
+            Some(ref lock) => !sid.is_valid() || lock.surfaces.values().any(|s| *s == sid),
+            None => true,
+        }
+    }
+
+    /// Returns ID of layer surface taking keyboard focus exclusively. If there are many such
+    /// surfaces the one in the highest layer created the latest wins.
+    fn find_exclusive_layer_surface(&self) -> Option<SurfaceId> {
+        self.layer_surfaces
+            .iter()
+            .filter(|layer| layer.is_keyboard_exclusive())
+            .max_by_key(|layer| layer.layer)
+            .map(|layer| layer.sid)
+    }
+
+    /// Moves keyboard focus of all seats to layer surface which started taking focus exclusively
+    /// or restores focus from before if there is no such surface any more.
+    fn update_layer_focus(&mut self, previous_exclusive_sid: Option<SurfaceId>) {
+        let exclusive_sid = self.find_exclusive_layer_surface();
+        if exclusive_sid == previous_exclusive_sid {
+            return;
+        }
+
+        if previous_exclusive_sid.is_none() {
+            self.layer_saved_focus =
+                self.seats.iter().map(|(seat, focus)| (*seat, focus.kfsid)).collect();
+        }
+
+        if let Some(exclusive_sid) = exclusive_sid {
+            let mut seats: Vec<SeatId> = self.seats.keys().cloned().collect();
+            if !seats.contains(&SeatId::default_seat()) {
+                seats.push(SeatId::default_seat());
+            }
+            for seat in seats {
+                self.set_seat_keyboard_focus(seat, exclusive_sid);
+            }
+        } else {
+            let saved_focus: Vec<(SeatId, SurfaceId)> = self.layer_saved_focus.drain().collect();
+            for (seat, sid) in saved_focus {
+                let sid = if self.surfaces.contains_key(&sid) { sid } else { SurfaceId::invalid() };
+                self.set_seat_keyboard_focus(seat, sid);
+            }
+        }
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -406,6 +1225,42 @@ impl Coordinator {
         mine.get_renderer_context(sid)
     }
 
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn get_surface_placement(&self, context: &SurfaceContext) -> Option<SurfacePlacement> {
+        let mine = self.inner.lock().unwrap();
+        mine.get_surface_placement(context)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn get_size_mismatch_policy(&self) -> SizeMismatchPolicy {
+        let mine = self.inner.lock().unwrap();
+        mine.get_size_mismatch_policy()
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn set_size_mismatch_policy(&mut self, policy: SizeMismatchPolicy) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.set_size_mismatch_policy(policy)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn record_frame(&self, output: &str, duration: std::time::Duration) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.record_frame(output, duration)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn set_client_count(&self, count: usize) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.set_client_count(count)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn format_metrics(&self) -> String {
+        let mine = self.inner.lock().unwrap();
+        mine.format_metrics()
+    }
+
     /// Lock and call corresponding method from `InnerCoordinator`.
     pub fn get_keyboard_focused_sid(&self) -> SurfaceId {
         let mine = self.inner.lock().unwrap();
@@ -418,6 +1273,120 @@ impl Coordinator {
         mine.set_keyboard_focus(sid)
     }
 
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn get_seat_keyboard_focused_sid(&self, seat: SeatId) -> SurfaceId {
+        let mine = self.inner.lock().unwrap();
+        mine.get_seat_keyboard_focused_sid(seat)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn set_seat_keyboard_focus(&mut self, seat: SeatId, sid: SurfaceId) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.set_seat_keyboard_focus(seat, sid)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn get_seat_capabilities(&self, seat: SeatId) -> SeatCapabilities {
+        let mine = self.inner.lock().unwrap();
+        mine.get_seat_capabilities(seat)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn set_seat_capabilities(&mut self, seat: SeatId, capabilities: SeatCapabilities) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.set_seat_capabilities(seat, capabilities)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn get_idle_timeouts(&self) -> Vec<u32> {
+        let mine = self.inner.lock().unwrap();
+        mine.get_idle_timeouts()
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn add_idle_timeout(&mut self, timeout: u32) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.add_idle_timeout(timeout)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn remove_idle_timeout(&mut self, timeout: u32) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.remove_idle_timeout(timeout)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn is_session_locked(&self) -> bool {
+        let mine = self.inner.lock().unwrap();
+        mine.is_session_locked()
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn lock_session(&mut self) -> bool {
+        let mut mine = self.inner.lock().unwrap();
+        mine.lock_session()
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn abandon_session_lock(&mut self) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.abandon_session_lock()
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn unlock_session(&mut self) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.unlock_session()
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn add_lock_surface(&mut self, sid: SurfaceId, output_id: i32) -> bool {
+        let mut mine = self.inner.lock().unwrap();
+        mine.add_lock_surface(sid, output_id)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn remove_lock_surface(&mut self, sid: SurfaceId) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.remove_lock_surface(sid)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn get_lock_surface(&self, output_id: i32) -> Option<SurfaceId> {
+        let mine = self.inner.lock().unwrap();
+        mine.get_lock_surface(output_id)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn get_layer_surface(&self, sid: SurfaceId) -> Option<LayerSurface> {
+        let mine = self.inner.lock().unwrap();
+        mine.get_layer_surface(sid)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn get_layer_surfaces(&self) -> Vec<LayerSurface> {
+        let mine = self.inner.lock().unwrap();
+        mine.get_layer_surfaces()
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn set_layer_surface(&self, state: LayerSurface) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.set_layer_surface(state)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn set_layer_surface_output(&mut self, sid: SurfaceId, output_id: i32) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.set_layer_surface_output(sid, output_id)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn remove_layer_surface(&self, sid: SurfaceId) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.remove_layer_surface(sid)
+    }
+
     /// Lock and call corresponding method from `InnerCoordinator`.
     pub fn get_pointer_focused_sid(&self) -> SurfaceId {
         let mine = self.inner.lock().unwrap();
@@ -430,6 +1399,111 @@ impl Coordinator {
         mine.set_pointer_focus(sid, position)
     }
 
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn get_transfer(&self) -> Option<Transfer> {
+        let mine = self.inner.lock().unwrap();
+        mine.get_transfer()
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn set_transfer(&self, transfer: Option<Transfer>) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.set_transfer(transfer)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn request_transfer(&self, mime_type: String, fd: RawFd) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.request_transfer(mime_type, fd)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn get_drag(&self) -> Option<Drag> {
+        let mine = self.inner.lock().unwrap();
+        mine.get_drag()
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn start_drag(&self, transfer: Option<Transfer>, origin: SurfaceId, icon: SurfaceId) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.start_drag(transfer, origin, icon)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn set_drag_target(&self, sid: SurfaceId, position: Position) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.set_drag_target(sid, position)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn drop_drag(&self) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.drop_drag()
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn end_drag(&self, performed: bool) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.end_drag(performed)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn request_drag_transfer(&self, mime_type: String, fd: RawFd) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.request_drag_transfer(mime_type, fd)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn request_move(&self, sid: SurfaceId) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.request_move(sid)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn request_capture(&self, output_id: i32) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.request_capture(output_id)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn request_output_configuration(&self,
+                                        configurations: Vec<OutputConfiguration>,
+                                        test: bool)
+                                        -> u32 {
+        let mut mine = self.inner.lock().unwrap();
+        mine.request_output_configuration(configurations, test)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn request_fullscreen(&self, sid: SurfaceId, fullscreen: bool, output_id: Option<i32>) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.request_fullscreen(sid, fullscreen, output_id)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn request_maximized(&self, sid: SurfaceId, maximized: bool) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.request_maximized(sid, maximized)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn request_initial_size(&self, sid: SurfaceId) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.request_initial_size(sid)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn request_close(&self, sid: SurfaceId) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.request_close(sid)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn request_kill(&self, sid: SurfaceId) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.request_kill(sid)
+    }
+
     /// Lock and call corresponding method from `InnerCoordinator`.
     pub fn create_pool_from_memory(&mut self, memory: MappedMemory) -> MemoryPoolId {
         let mut mine = self.inner.lock().unwrap();
@@ -460,10 +1534,12 @@ impl Coordinator {
                               offset: usize,
                               width: usize,
                               height: usize,
-                              stride: usize)
+                              stride: usize,
+                              format: PixelFormat,
+                              alpha_mode: AlphaMode)
                               -> Option<MemoryViewId> {
         let mut mine = self.inner.lock().unwrap();
-        mine.create_memory_view(mpid, offset, width, height, stride)
+        mine.create_memory_view(mpid, offset, width, height, stride, format, alpha_mode)
     }
 
     /// Lock and call corresponding method from `InnerCoordinator`.
@@ -472,6 +1548,24 @@ impl Coordinator {
         mine.destroy_memory_view(mpid);
     }
 
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn get_memory_view(&self, mvid: MemoryViewId) -> Option<MemoryView> {
+        let mine = self.inner.lock().unwrap();
+        mine.get_memory_view(mvid)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn create_hw_image(&mut self, image: HwImage) -> HwImageId {
+        let mut mine = self.inner.lock().unwrap();
+        mine.create_hw_image(image)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn destroy_hw_image(&mut self, hiid: HwImageId) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.destroy_hw_image(hiid);
+    }
+
     /// Lock and call corresponding method from `InnerCoordinator`.
     pub fn create_surface(&mut self) -> SurfaceId {
         let mut mine = self.inner.lock().unwrap();
@@ -496,6 +1590,42 @@ impl Coordinator {
         mine.attach(mvid, sid);
     }
 
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn attach_hw_image(&self, hiid: HwImageId, sid: SurfaceId) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.attach_hw_image(hiid, sid);
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn damage_surface(&self, sid: SurfaceId, area: Area) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.damage_surface(sid, area);
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn damage_surface_buffer(&self, sid: SurfaceId, area: Area) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.damage_surface_buffer(sid, area);
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn set_surface_buffer_scale(&self, sid: SurfaceId, scale: u32) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.set_surface_buffer_scale(sid, scale);
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn set_surface_buffer_transform(&self, sid: SurfaceId, transform: Transform) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.set_surface_buffer_transform(sid, transform);
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn discard_damage(&self, sid: SurfaceId, drawn: &Vec<Area>, generation: u64) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.discard_damage(sid, drawn, generation);
+    }
+
     /// Lock and call corresponding method from `InnerCoordinator`.
     pub fn commit_surface(&self, sid: SurfaceId) {
         let mut mine = self.inner.lock().unwrap();
@@ -527,12 +1657,87 @@ impl Coordinator {
         mine.set_surface_requested_size(sid, size)
     }
 
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn set_surface_size_limits(&self, sid: SurfaceId, min_size: Size, max_size: Size) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.set_surface_size_limits(sid, min_size, max_size)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn set_surface_window_geometry(&self, sid: SurfaceId, geometry: Area) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.set_surface_window_geometry(sid, geometry)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn set_surface_app_id(&self, sid: SurfaceId, app_id: String) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.set_surface_app_id(sid, app_id)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn set_surface_title(&self, sid: SurfaceId, title: String) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.set_surface_title(sid, title)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn set_surface_capture_excluded(&self, sid: SurfaceId, excluded: bool) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.set_surface_capture_excluded(sid, excluded)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn set_surface_color_description(&self, sid: SurfaceId, description: ColorDescription) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.set_surface_color_description(sid, description)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn set_surface_opaque_region(&self, sid: SurfaceId, region: Option<Area>) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.set_surface_opaque_region(sid, region)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn set_surface_decoration(&self,
+                                  sid: SurfaceId,
+                                  decoration_sid: SurfaceId,
+                                  margins: Margins) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.set_surface_decoration(sid, decoration_sid, margins)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn request_decoration_mode(&self, sid: SurfaceId, mode: Option<DecorationMode>) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.request_decoration_mode(sid, mode)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn set_surface_decoration_mode(&self, sid: SurfaceId, mode: DecorationMode) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.set_surface_decoration_mode(sid, mode)
+    }
+
     /// Lock and call corresponding method from `InnerCoordinator`.
     pub fn set_surface_relative_position(&self, sid: SurfaceId, offset: Vector) {
         let mut mine = self.inner.lock().unwrap();
         mine.set_surface_relative_position(sid, offset)
     }
 
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn set_surface_positioner(&self, sid: SurfaceId, positioner: Positioner) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.set_surface_positioner(sid, positioner)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn place_surface(&self, sid: SurfaceId, area: Area) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.place_surface(sid, area)
+    }
+
     /// Lock and call corresponding method from `InnerCoordinator`.
     pub fn relate_surfaces(&self, sid: SurfaceId, parent_sid: SurfaceId) {
         let mut mine = self.inner.lock().unwrap();
@@ -550,6 +1755,27 @@ impl Coordinator {
         let mut mine = self.inner.lock().unwrap();
         mine.set_surface_as_cursor(sid);
     }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn set_surface_suspended(&self, sid: SurfaceId, suspended: bool) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.set_surface_suspended(sid, suspended);
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn set_surface_fullscreen(&self, sid: SurfaceId, size: Option<Size>) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.set_surface_fullscreen(sid, size);
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    pub fn set_surface_initial_size(&self,
+                                    sid: SurfaceId,
+                                    size: Size,
+                                    state_flags: surface_state::SurfaceState) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.set_surface_initial_size(sid, size, state_flags);
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
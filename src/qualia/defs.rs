@@ -37,6 +37,7 @@ pub struct SurfaceId(SurfaceIdType);
 
 define_id!(pub MemoryPoolId: usize);
 define_id!(pub MemoryViewId: usize);
+define_id!(pub HwImageId: usize);
 
 // -------------------------------------------------------------------------------------------------
 
@@ -89,6 +90,52 @@ impl std::fmt::Display for SurfaceId {
 
 // -------------------------------------------------------------------------------------------------
 
+/// Structure representing seat ID. Seat with index zero is the default seat; following seats are
+/// defined in configuration.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub struct SeatId(usize);
+
+// -------------------------------------------------------------------------------------------------
+
+impl SeatId {
+    /// Creates new seat ID.
+    pub fn new(index: usize) -> Self {
+        SeatId(index)
+    }
+
+    /// Returns ID of the default seat.
+    pub fn default_seat() -> Self {
+        SeatId(0)
+    }
+
+    /// Checks if this is the default seat.
+    pub fn is_default(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns index of the seat.
+    pub fn get_index(&self) -> usize {
+        self.0
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// These flags describe kinds of input devices available in seat. Values match capabilities of
+/// Wayland `wl_seat`.
+pub mod seat_capability {
+    bitflags!(
+        pub flags SeatCapabilities: u32 {
+            const NONE = 0b000,
+            const POINTER = 0b001,
+            const KEYBOARD = 0b010,
+            const TOUCH = 0b100,
+        }
+    );
+}
+
+// -------------------------------------------------------------------------------------------------
+
 /// These flags describe key modifiers.
 pub mod modifier {
     pub type ModifierType = u16;
@@ -117,6 +164,28 @@ pub mod mode_name {
 
 // -------------------------------------------------------------------------------------------------
 
+/// Names of media keys used in configuration and in `media` command.
+pub mod media_key {
+    pub const VOLUME_UP: &'static str = "volume_up";
+    pub const VOLUME_DOWN: &'static str = "volume_down";
+    pub const MUTE: &'static str = "mute";
+    pub const MIC_MUTE: &'static str = "mic_mute";
+    pub const PLAY_PAUSE: &'static str = "play_pause";
+    pub const NEXT: &'static str = "next";
+    pub const PREVIOUS: &'static str = "previous";
+    pub const STOP: &'static str = "stop";
+
+    /// Returns static name of media key equal to given one or `None` if the name is unknown.
+    pub fn find(name: &str) -> Option<&'static str> {
+        [VOLUME_UP, VOLUME_DOWN, MUTE, MIC_MUTE, PLAY_PAUSE, NEXT, PREVIOUS, STOP]
+            .iter()
+            .find(|key| **key == name)
+            .cloned()
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
 /// Type defining position, point coordinates or 2D vector.
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -318,6 +387,17 @@ impl Size {
     pub fn is_zero(&self) -> bool {
         self.width == 0 && self.height == 0
     }
+
+    /// Returns size limited to be between `min` and `max`. Zero dimension of limit means the
+    /// dimension is not limited.
+    pub fn clamped(&self, min: Size, max: Size) -> Size {
+        fn clamp(value: usize, min: usize, max: usize) -> usize {
+            let value = value.max(min);
+            if max > 0 { value.min(max.max(min)) } else { value }
+        }
+        Size::new(clamp(self.width, min.width, max.width),
+                  clamp(self.height, min.height, max.height))
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -365,6 +445,73 @@ impl Area {
         (pos.x < margin_right)
     }
 
+    /// Check if `Area` has common part with other area. Areas only touching each other are not
+    /// overlapping.
+    pub fn is_overlapping(&self, other: &Area) -> bool {
+        (self.pos.x < other.pos.x + other.size.width as isize) &&
+        (other.pos.x < self.pos.x + self.size.width as isize) &&
+        (self.pos.y < other.pos.y + other.size.height as isize) &&
+        (other.pos.y < self.pos.y + self.size.height as isize)
+    }
+
+    /// Returns common part of this and other area or `None` if areas do not overlap.
+    pub fn intersected(&self, other: &Area) -> Option<Area> {
+        if self.is_overlapping(other) {
+            let left = std::cmp::max(self.pos.x, other.pos.x);
+            let top = std::cmp::max(self.pos.y, other.pos.y);
+            let right = std::cmp::min(self.pos.x + self.size.width as isize,
+                                      other.pos.x + other.size.width as isize);
+            let bottom = std::cmp::min(self.pos.y + self.size.height as isize,
+                                       other.pos.y + other.size.height as isize);
+            Some(Area::create(left, top, (right - left) as usize, (bottom - top) as usize))
+        } else {
+            None
+        }
+    }
+
+    /// Returns parts of this area not covered by other area. The parts do not overlap: they are
+    /// strips above and below the other area spanning whole width of this area and strips on its
+    /// left and right side.
+    pub fn subtracted(&self, other: &Area) -> Vec<Area> {
+        let hole = match self.intersected(other) {
+            Some(hole) => hole,
+            None => return vec![self.clone()],
+        };
+
+        let mut parts = Vec::with_capacity(4);
+        let right = self.pos.x + self.size.width as isize;
+        let bottom = self.pos.y + self.size.height as isize;
+        let hole_right = hole.pos.x + hole.size.width as isize;
+        let hole_bottom = hole.pos.y + hole.size.height as isize;
+        if hole.pos.y > self.pos.y {
+            let height = (hole.pos.y - self.pos.y) as usize;
+            parts.push(Area::create(self.pos.x, self.pos.y, self.size.width, height));
+        }
+        if hole_bottom < bottom {
+            let height = (bottom - hole_bottom) as usize;
+            parts.push(Area::create(self.pos.x, hole_bottom, self.size.width, height));
+        }
+        if hole.pos.x > self.pos.x {
+            let width = (hole.pos.x - self.pos.x) as usize;
+            parts.push(Area::create(self.pos.x, hole.pos.y, width, hole.size.height));
+        }
+        if hole_right < right {
+            let width = (right - hole_right) as usize;
+            parts.push(Area::create(hole_right, hole.pos.y, width, hole.size.height));
+        }
+        parts
+    }
+
+    /// Returns the smallest area with integer coordinates covering this area scaled by given
+    /// factor.
+    pub fn scaled(&self, scale: f32) -> Area {
+        let left = (self.pos.x as f32 * scale).floor() as isize;
+        let top = (self.pos.y as f32 * scale).floor() as isize;
+        let right = ((self.pos.x + self.size.width as isize) as f32 * scale).ceil() as isize;
+        let bottom = ((self.pos.y + self.size.height as isize) as f32 * scale).ceil() as isize;
+        Area::create(left, top, (right - left) as usize, (bottom - top) as usize)
+    }
+
     /// Calculate position in center of the area.
     pub fn calculate_center(&self) -> Position {
         Position::new((self.pos.x + self.size.width as isize) / 2,
@@ -412,12 +559,65 @@ impl std::default::Default for Area {
 
 // -------------------------------------------------------------------------------------------------
 
+/// Type defining widths of space reserved on each side of an area (e.g. for decorations).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Margins {
+    pub top: usize,
+    pub right: usize,
+    pub bottom: usize,
+    pub left: usize,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Margins {
+    /// `Margins` constructor.
+    pub fn new(top: usize, right: usize, bottom: usize, left: usize) -> Self {
+        Margins {
+            top: top,
+            right: right,
+            bottom: bottom,
+            left: left,
+        }
+    }
+
+    /// Returns position of inner area relative to the outer one.
+    pub fn get_offset(&self) -> Vector {
+        Vector::new(self.left as isize, self.top as isize)
+    }
+
+    /// Returns size of inner area for given outer size. Margins wider than the outer size give
+    /// zero size.
+    pub fn shrink(&self, size: Size) -> Size {
+        Size::new(size.width.saturating_sub(self.left + self.right),
+                  size.height.saturating_sub(self.top + self.bottom))
+    }
+
+    /// Returns size of outer area for given inner size.
+    pub fn grow(&self, size: Size) -> Size {
+        Size::new(size.width + self.left + self.right, size.height + self.top + self.bottom)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl std::default::Default for Margins {
+    fn default() -> Self {
+        Margins::new(0, 0, 0, 0)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
 /// Data for button event.
 #[derive(Clone, Copy, Debug)]
 pub struct Button {
     pub code: u16,
     pub value: i32,
     pub time: timing::Milliseconds,
+
+    /// Seat the device reporting the event is assigned to.
+    pub seat: SeatId,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -429,6 +629,7 @@ impl Button {
             code: code,
             value: value,
             time: milliseconds,
+            seat: SeatId::default_seat(),
         }
     }
 
@@ -438,6 +639,94 @@ impl Button {
             code: code,
             value: value,
             time: timing::Milliseconds::now(),
+            seat: SeatId::default_seat(),
+        }
+    }
+
+    /// Returns copy of the event assigned to given seat.
+    pub fn with_seat(&self, seat: SeatId) -> Self {
+        let mut button = *self;
+        button.seat = seat;
+        button
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Absolute position reported by touchscreen or tablet. Coordinates are normalized to range from 0
+/// to 1 and are relative to output with given name or to output the pointer is on if `None`.
+#[derive(Clone, Debug)]
+pub struct AbsolutePosition {
+    pub output: Option<String>,
+    pub x: f32,
+    pub y: f32,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl AbsolutePosition {
+    /// Constructs `AbsolutePosition`.
+    pub fn new(output: Option<String>, x: f32, y: f32) -> Self {
+        AbsolutePosition {
+            output: output,
+            x: x,
+            y: y,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Data for touch down and touch motion events. `id` identifies touch point (e.g. finger) from
+/// touch down until touch up.
+#[derive(Clone, Debug)]
+pub struct TouchPoint {
+    pub id: i32,
+    pub position: AbsolutePosition,
+    pub time: timing::Milliseconds,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl TouchPoint {
+    /// Constructs `TouchPoint` with current time.
+    pub fn new_now(id: i32, position: AbsolutePosition) -> Self {
+        TouchPoint {
+            id: id,
+            position: position,
+            time: timing::Milliseconds::now(),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Data for touchpad gesture update events.
+#[derive(Clone, Copy, Debug)]
+pub struct GestureMotion {
+    /// Motion of center of fingers since last update.
+    pub delta: Slide,
+
+    /// Distance between fingers relative to the distance when gesture began. Always `1.0` for
+    /// swipe.
+    pub scale: f32,
+
+    /// Clockwise rotation of fingers in degrees since last update. Always `0.0` for swipe.
+    pub rotation: f32,
+
+    pub time: timing::Milliseconds,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl GestureMotion {
+    /// Constructs `GestureMotion` with current time.
+    pub fn new_now(delta: Slide, scale: f32, rotation: f32) -> Self {
+        GestureMotion {
+            delta: delta,
+            scale: scale,
+            rotation: rotation,
+            time: timing::Milliseconds::now(),
         }
     }
 }
@@ -474,32 +763,156 @@ impl Axis {
 
 // -------------------------------------------------------------------------------------------------
 
+/// Resolution and refresh rate of output.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OutputMode {
+    /// Size of the output in pixels.
+    pub size: Size,
+
+    /// Refresh rate in Hz. When choosing mode zero means any rate is accepted.
+    pub refresh_rate: usize,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl OutputMode {
+    /// `OutputMode` constructor.
+    pub fn new(size: Size, refresh_rate: usize) -> Self {
+        OutputMode {
+            size: size,
+            refresh_rate: refresh_rate,
+        }
+    }
+
+    /// Parses mode in format `<width>x<height>` or `<width>x<height>@<rate>`. Returns `None` if
+    /// the string is not in one of these formats or any of the numbers is zero.
+    pub fn parse(string: &str) -> Option<Self> {
+        let mut parts = string.splitn(2, '@');
+        let resolution = parts.next().unwrap_or("");
+        let refresh_rate = match parts.next() {
+            Some(rate) => {
+                match rate.parse::<usize>() {
+                    Ok(rate) if rate > 0 => rate,
+                    _ => return None,
+                }
+            }
+            None => 0,
+        };
+
+        let mut parts = resolution.split('x').map(|part| part.parse::<usize>());
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(Ok(width)), Some(Ok(height)), None) if (width > 0) && (height > 0) => {
+                Some(OutputMode::new(Size::new(width, height), refresh_rate))
+            }
+            _ => None,
+        }
+    }
+
+    /// Checks if this mode satisfies `requested` one. Refresh rates are compared only if
+    /// requested rate is given.
+    pub fn matches(&self, requested: &OutputMode) -> bool {
+        (self.size == requested.size) &&
+        ((requested.refresh_rate == 0) || (self.refresh_rate == requested.refresh_rate))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl std::fmt::Display for OutputMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}x{}", self.size.width, self.size.height)?;
+        if self.refresh_rate != 0 {
+            write!(f, "@{}", self.refresh_rate)?;
+        }
+        Ok(())
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
 /// Set of informations about output.
 #[derive(Clone, Debug)]
 pub struct OutputInfo {
+    pub id: i32,
     pub area: Area,
     pub physical_size: Size,
     pub refresh_rate: usize,
     pub make: String,
     pub model: String,
+    pub scale: f32,
+    pub pixel_size: Size,
+    pub transform: enums::Transform,
+    pub drm_device: Option<std::path::PathBuf>,
+    pub modes: Vec<OutputMode>,
+    pub enabled: bool,
 }
 
 // -------------------------------------------------------------------------------------------------
 
 impl OutputInfo {
-    /// Constructs new `OutputInfo`.
-    pub fn new(area: Area,
+    /// Constructs new `OutputInfo` of enabled output with default scale, no transformation, no DRM
+    /// device and no known modes. Size of the output in pixels is the same as size of its area.
+    pub fn new(id: i32,
+               area: Area,
                physical_size: Size,
                refresh_rate: usize,
                make: String,
                model: String)
                -> Self {
         OutputInfo {
+            id: id,
             area: area,
             physical_size: physical_size,
             refresh_rate: refresh_rate,
             make: make,
             model: model,
+            scale: 1.0,
+            pixel_size: area.size,
+            transform: enums::Transform::Normal,
+            drm_device: None,
+            modes: Vec::new(),
+            enabled: true,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Configuration of output requested by client. Properties which are not given are left unchanged.
+/// Properties of disabled outputs are ignored.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutputConfiguration {
+    /// ID of the output.
+    pub id: i32,
+
+    /// Tells if the output should be enabled or disabled.
+    pub enabled: bool,
+
+    /// Resolution and refresh rate of the output.
+    pub mode: Option<OutputMode>,
+
+    /// Position of the output in global coordinates.
+    pub position: Option<Position>,
+
+    /// Rotation and flipping of the output.
+    pub transform: Option<enums::Transform>,
+
+    /// Number of pixels per logical unit.
+    pub scale: Option<f32>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl OutputConfiguration {
+    /// Constructs new `OutputConfiguration` leaving all properties of the output unchanged.
+    pub fn new(id: i32, enabled: bool) -> Self {
+        OutputConfiguration {
+            id: id,
+            enabled: enabled,
+            mode: None,
+            position: None,
+            transform: None,
+            scale: None,
         }
     }
 }
@@ -507,8 +920,9 @@ impl OutputInfo {
 // -------------------------------------------------------------------------------------------------
 
 /// Structure containing all data needed to initialize DRM output.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub struct DrmBundle {
+    pub path: std::path::PathBuf,
     pub fd: Fd,
     pub crtc_id: u32,
     pub connector_id: u32,
@@ -10,11 +10,13 @@ use std::fmt;
 // -------------------------------------------------------------------------------------------------
 
 /// Enum describing kind of input device.
-#[derive(PartialEq)]
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
 pub enum DeviceKind {
     Keyboard,
     Mouse,
     Touchpad,
+    Touchscreen,
+    Switch,
     Unknown,
 }
 
@@ -26,6 +28,8 @@ impl fmt::Debug for DeviceKind {
             DeviceKind::Keyboard => write!(f, "keyboard"),
             DeviceKind::Mouse => write!(f, "mouse"),
             DeviceKind::Touchpad => write!(f, "touchpad"),
+            DeviceKind::Touchscreen => write!(f, "touchscreen"),
+            DeviceKind::Switch => write!(f, "switch"),
             DeviceKind::Unknown => write!(f, "unknown device"),
         }
     }
@@ -54,6 +58,9 @@ pub enum Action {
     /// Change configuration.
     Configure,
 
+    /// Create new entity.
+    Create,
+
     /// Change focus.
     Focus,
 
@@ -71,6 +78,45 @@ pub enum Action {
 
     /// Change size.
     Resize,
+
+    /// Change the way displays are redrawn (for debugging).
+    Redraw,
+
+    /// Hide; reveal contents in screen captures.
+    Conceal,
+
+    /// Minimize; restore minimized.
+    Minimize,
+
+    /// Switch visible frame in stacked frame.
+    Tab,
+
+    /// Trace emitted signals; list subscriptions (for debugging).
+    Trace,
+
+    /// Switch keyboard layout.
+    Layout,
+
+    /// Turn displays on or off.
+    Power,
+
+    /// Rotate or flip display.
+    Rotate,
+
+    /// Change resolution or refresh rate of display.
+    Mode,
+
+    /// Scale window to fill display; restore its normal size.
+    Zoom,
+
+    /// Perform action of media key (change volume, control playback).
+    Media,
+
+    /// Switch virtual terminal.
+    Terminal,
+
+    /// Take screenshot.
+    Screenshot,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -134,3 +180,142 @@ impl Direction {
 }
 
 // -------------------------------------------------------------------------------------------------
+
+/// Transformation of output or buffer contents: counter-clockwise rotation by multiple of 90
+/// degrees optionally preceded by flipping around vertical axis.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Transform {
+    Normal,
+    Rotated90,
+    Rotated180,
+    Rotated270,
+    Flipped,
+    Flipped90,
+    Flipped180,
+    Flipped270,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Transform {
+    /// Checks if the transformation swaps width and height.
+    pub fn is_transposing(&self) -> bool {
+        match *self {
+            Transform::Rotated90 |
+            Transform::Rotated270 |
+            Transform::Flipped90 |
+            Transform::Flipped270 => true,
+            _ => false,
+        }
+    }
+
+    /// Returns transformation reverting this one.
+    pub fn inverted(&self) -> Self {
+        match *self {
+            Transform::Rotated90 => Transform::Rotated270,
+            Transform::Rotated270 => Transform::Rotated90,
+            other => other,
+        }
+    }
+
+    /// Constructs transformation from number of quarter turns and flipping.
+    pub fn from_parts(quarter_turns: u32, flipped: bool) -> Self {
+        match (quarter_turns % 4, flipped) {
+            (0, false) => Transform::Normal,
+            (1, false) => Transform::Rotated90,
+            (2, false) => Transform::Rotated180,
+            (3, false) => Transform::Rotated270,
+            (0, true) => Transform::Flipped,
+            (1, true) => Transform::Flipped90,
+            (2, true) => Transform::Flipped180,
+            _ => Transform::Flipped270,
+        }
+    }
+
+    /// Returns number of quarter turns of the transformation.
+    pub fn get_quarter_turns(&self) -> u32 {
+        match *self {
+            Transform::Normal | Transform::Flipped => 0,
+            Transform::Rotated90 | Transform::Flipped90 => 1,
+            Transform::Rotated180 | Transform::Flipped180 => 2,
+            Transform::Rotated270 | Transform::Flipped270 => 3,
+        }
+    }
+
+    /// Checks if the transformation flips contents.
+    pub fn is_flipped(&self) -> bool {
+        match *self {
+            Transform::Flipped |
+            Transform::Flipped90 |
+            Transform::Flipped180 |
+            Transform::Flipped270 => true,
+            _ => false,
+        }
+    }
+
+    /// Returns transformation rotated by further given number of quarter turns. Flipping is kept.
+    pub fn rotated(&self, quarter_turns: u32) -> Self {
+        Self::from_parts(self.get_quarter_turns() + quarter_turns, self.is_flipped())
+    }
+
+    /// Returns transformation with flipping toggled. Rotation is kept.
+    pub fn flipped(&self) -> Self {
+        Self::from_parts(self.get_quarter_turns(), !self.is_flipped())
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Physical switches reported by input devices.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Switch {
+    /// Lid of a laptop.
+    Lid,
+
+    /// Convertible device folded to tablet mode.
+    TabletMode,
+
+    /// Device placed in a docking station.
+    Dock,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Mode of drawing window decorations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecorationMode {
+    /// Decorations are drawn by client.
+    ClientSide,
+
+    /// Decorations are drawn by compositor.
+    ServerSide,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Kind of touchpad gesture.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GestureKind {
+    /// Fingers move together in one direction.
+    Swipe,
+
+    /// Fingers move closer or further apart or rotate around their center.
+    Pinch,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Describes how surfaces whose size differs from size configured by compositor are drawn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SizeMismatchPolicy {
+    /// Surface is drawn in its own size. Parts exceeding reserved space are cut off.
+    Clip,
+
+    /// Surface is centered in reserved space. Parts exceeding it are cut off equally on both sides.
+    Center,
+
+    /// Surface is scaled to fill reserved space.
+    Scale,
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -20,10 +20,15 @@ use log;
 
 // -------------------------------------------------------------------------------------------------
 
+const CONFIG_DIR_VAR: &'static str = "XDG_CONFIG_HOME";
 const DATA_DIR_VAR: &'static str = "XDG_DATA_HOME";
 const RUNTIME_DIR_VAR: &'static str = "XDG_RUNTIME_DIR";
+const TRACE_SIGNALS_VAR: &'static str = "PERCEPTIA_TRACE_SIGNALS";
 
+const DEFAULT_CONFIG_DIR: &'static str = ".config";
 const DEFAULT_DATA_DIR: &'static str = "/tmp/perceptia";
+
+const CONFIG_FILE_NAME: &'static str = "perceptia.toml";
 const DEFAULT_RUNTIME_DIR: &'static str = "/tmp";
 
 // -------------------------------------------------------------------------------------------------
@@ -88,10 +93,27 @@ impl Env {
         }
     }
 
-    /// Reads in configuration.
-    /// TODO: Read configuration from file.
+    /// Reads in configuration from `$XDG_CONFIG_HOME/perceptia/perceptia.toml`. If file does not
+    /// exist or is invalid default configuration is used.
     pub fn read_config(&self) -> config::Config {
-        config::Config::default()
+        let path = Self::get_config_path();
+        if !path.exists() {
+            log_info1!("Config file {:?} not found; using default configuration", path);
+            return config::Config::default();
+        }
+
+        match config::Config::load(&path) {
+            Ok(config) => {
+                log_info1!("Read configuration from {:?}", path);
+                config
+            }
+            Err(err) => {
+                log_warn1!("Failed to read config file {:?}: {}; using default configuration",
+                           path,
+                           err);
+                config::Config::default()
+            }
+        }
     }
 
     /// Opens file in predefined directory.
@@ -134,7 +156,7 @@ impl Env {
 
     /// Create data directory.
     fn create_data_dir(&mut self) -> Result<(), Illusion> {
-        let path = Self::read_path(DATA_DIR_VAR, DEFAULT_DATA_DIR);
+        let path = Self::get_data_dir_path();
         let result = Self::mkdir(&path);
         if result.is_ok() {
             self.data_dir = Some(path);
@@ -144,8 +166,8 @@ impl Env {
 
     /// Create runtime directory.
     fn create_runtime_dir(&mut self) -> Result<(), Illusion> {
-        let path = Self::read_path(RUNTIME_DIR_VAR, DEFAULT_RUNTIME_DIR);
-        let path = path.join(format!("perceptia-{}", Self::get_time_representation()));
+        let time = Self::get_time_representation();
+        let path = Self::get_runtime_dir_path().join(format!("perceptia-{}", time));
         let result = Self::mkdir(&path);
         if result.is_ok() {
             self.runtime_dir = Some(path);
@@ -183,6 +205,40 @@ impl Env {
         path
     }
 
+    /// Returns path to configuration file. If `XDG_CONFIG_HOME` is not set `$HOME/.config` is
+    /// used.
+    pub fn get_config_path() -> std::path::PathBuf {
+        let dir = match std::env::var(CONFIG_DIR_VAR) {
+            Ok(dir) => std::path::PathBuf::from(dir),
+            Err(_) => {
+                let home = std::env::var("HOME").unwrap_or("/".to_owned());
+                std::path::PathBuf::from(home).join(DEFAULT_CONFIG_DIR)
+            }
+        };
+        dir.join("perceptia").join(CONFIG_FILE_NAME)
+    }
+
+    /// Returns path to data directory. If `XDG_DATA_HOME` is not set `/tmp/perceptia` is used.
+    pub fn get_data_dir_path() -> std::path::PathBuf {
+        Self::read_path(DATA_DIR_VAR, DEFAULT_DATA_DIR)
+    }
+
+    /// Returns path to directory shared by all instances for files existing while application is
+    /// running. If `XDG_RUNTIME_DIR` is not set `/tmp` is used.
+    pub fn get_runtime_dir_path() -> std::path::PathBuf {
+        Self::read_path(RUNTIME_DIR_VAR, DEFAULT_RUNTIME_DIR)
+    }
+
+    /// Returns name of file in data directory unique for given prefix, current time and extension.
+    pub fn get_data_file_name(prefix: &str, extension: &str) -> String {
+        format!("{}-{}.{}", prefix, Self::get_time_representation(), extension)
+    }
+
+    /// Checks if tracing of signals was requested by setting `PERCEPTIA_TRACE_SIGNALS` variable.
+    pub fn is_signal_tracing_requested() -> bool {
+        std::env::var(TRACE_SIGNALS_VAR).is_ok()
+    }
+
     /// Helper function for creating directory.
     fn mkdir(path: &std::path::PathBuf) -> Result<(), Illusion> {
         if path.exists() {
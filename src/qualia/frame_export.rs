@@ -0,0 +1,279 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains functionality for exporting captured frames to other processes (e.g.
+//! screen recorders).
+//!
+//! Frames are copied to a ring of slots in a file which recording tools map to their memory. After
+//! every frame a notification is written to a named pipe so tools can wait for frames without
+//! polling. Both files are created in runtime directory and removed when export stops.
+//!
+//! Layout of the ring file (all numbers little-endian):
+//!  - header: magic `PRCF`, version, number of slots, width, height and stride of frames and DRM
+//!    format of pixels (`u32` each; format is `AB24` as frames are in RGBA byte order) padded to
+//!    32 bytes,
+//!  - table of slots: sequence number and timestamp in nanoseconds (`u64` each) for every slot,
+//!  - pixel data of every slot, each starting at page boundary.
+//!
+//! Sequence number of slot is zero while the frame is being written, so readers may detect torn
+//! frames by comparing sequence numbers before and after copying. Notification consists of
+//! sequence number (`u64`) and slot index (`u32`) padded to 16 bytes. Notifications are dropped
+//! when nobody reads the pipe and its buffer fills up, so readers should skip to the notification
+//! with the highest sequence number.
+
+// -------------------------------------------------------------------------------------------------
+
+use std;
+use std::ffi::CString;
+use std::io::Write;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
+
+use libc;
+use nix::sys::mman;
+use time;
+
+use errors::Illusion;
+use memory::{Buffer, Pixmap};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Magic number at the beginning of the ring file ("PRCF").
+pub const MAGIC: u32 = 0x46435250;
+
+/// Version of layout of the ring file.
+pub const VERSION: u32 = 1;
+
+/// DRM format of exported frames (`DRM_FORMAT_ABGR8888`).
+pub const FORMAT: u32 = 0x34324241;
+
+/// Number of slots in the ring.
+pub const SLOT_COUNT: usize = 4;
+
+/// Size of the ring file header in bytes.
+pub const HEADER_SIZE: usize = 8 * 4;
+
+/// Size of entry in table of slots in bytes.
+pub const SLOT_ENTRY_SIZE: usize = 2 * 8;
+
+/// Size of notification written to the pipe in bytes.
+pub const NOTIFICATION_SIZE: usize = 16;
+
+/// Alignment of pixel data of slots.
+const PAGE_SIZE: usize = 4096;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Returns offset of pixel data of slot with given index in ring of frames with given stride and
+/// height.
+pub fn get_slot_offset(slot: usize, stride: usize, height: usize) -> usize {
+    let table_end = HEADER_SIZE + SLOT_COUNT * SLOT_ENTRY_SIZE;
+    align(table_end) + slot * align(stride * height)
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Rounds size up to the page boundary.
+fn align(size: usize) -> usize {
+    (size + PAGE_SIZE - 1) / PAGE_SIZE * PAGE_SIZE
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Exports frames of one size to the ring file and informs about them via the pipe.
+pub struct FrameExport {
+    ring_path: PathBuf,
+    pipe_path: PathBuf,
+    pipe: std::fs::File,
+    data: *mut u8,
+    size: usize,
+    width: usize,
+    height: usize,
+    stride: usize,
+    sequence: u64,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl FrameExport {
+    /// Creates ring file `<name>.frames` and pipe `<name>.pipe` in given directory for frames of
+    /// given size.
+    pub fn new(dir: &Path,
+               name: &str,
+               width: usize,
+               height: usize,
+               stride: usize)
+               -> Result<Self, Illusion> {
+        let ring_path = dir.join(format!("{}.frames", name));
+        let pipe_path = dir.join(format!("{}.pipe", name));
+        let size = get_slot_offset(SLOT_COUNT, stride, height);
+
+        let ring = std::fs::OpenOptions::new().read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(&ring_path)?;
+        ring.set_len(size as u64)?;
+
+        let data = match mman::mmap(std::ptr::null_mut(),
+                                    size,
+                                    mman::PROT_READ | mman::PROT_WRITE,
+                                    mman::MAP_SHARED,
+                                    ring.as_raw_fd(),
+                                    0) {
+            Ok(memory) => memory as *mut u8,
+            Err(err) => {
+                let _ = std::fs::remove_file(&ring_path);
+                return Err(Illusion::General(format!("Failed to map frame ring: {:?}", err)));
+            }
+        };
+
+        // Pipe is opened for reading too so opening does not block and writing does not fail
+        // when there are no readers.
+        let _ = std::fs::remove_file(&pipe_path);
+        let pipe = match Self::create_pipe(&pipe_path) {
+            Ok(pipe) => pipe,
+            Err(err) => {
+                let _ = mman::munmap(data as *mut _, size);
+                let _ = std::fs::remove_file(&ring_path);
+                return Err(err);
+            }
+        };
+
+        let mut mine = FrameExport {
+            ring_path: ring_path,
+            pipe_path: pipe_path,
+            pipe: pipe,
+            data: data,
+            size: size,
+            width: width,
+            height: height,
+            stride: stride,
+            sequence: 0,
+        };
+        mine.write_header();
+        Ok(mine)
+    }
+
+    /// Checks if frames of given size may be exported.
+    pub fn accepts(&self, width: usize, height: usize, stride: usize) -> bool {
+        self.width == width && self.height == height && self.stride == stride
+    }
+
+    /// Returns path to the ring file.
+    pub fn get_ring_path(&self) -> &Path {
+        &self.ring_path
+    }
+
+    /// Returns path to the pipe.
+    pub fn get_pipe_path(&self) -> &Path {
+        &self.pipe_path
+    }
+
+    /// Copies frame to the next slot and notifies readers. Frame must have size the export was
+    /// created for.
+    pub fn export(&mut self, frame: &Buffer) -> Result<(), Illusion> {
+        if !self.accepts(frame.get_width(), frame.get_height(), frame.get_stride()) {
+            return Err(Illusion::InvalidArgument(format!("Frame size does not match export")));
+        }
+
+        self.sequence += 1;
+        let slot = ((self.sequence - 1) % SLOT_COUNT as u64) as usize;
+        let entry = HEADER_SIZE + slot * SLOT_ENTRY_SIZE;
+        let offset = get_slot_offset(slot, self.stride, self.height);
+        let length = self.stride * self.height;
+        {
+            let memory = unsafe { std::slice::from_raw_parts_mut(self.data, self.size) };
+            Self::put_u64(memory, entry, 0);
+            memory[offset..offset + length].copy_from_slice(&frame.as_slice()[..length]);
+            Self::put_u64(memory, entry + 8, time::precise_time_ns());
+            Self::put_u64(memory, entry, self.sequence);
+        }
+
+        let mut notification = [0u8; NOTIFICATION_SIZE];
+        Self::put_u64(&mut notification, 0, self.sequence);
+        Self::put_u32(&mut notification, 8, slot as u32);
+        match self.pipe.write(&notification) {
+            Ok(_) => Ok(()),
+            Err(ref err) if err.kind() == std::io::ErrorKind::WouldBlock => Ok(()),
+            Err(err) => Err(Illusion::from(err)),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Helper methods.
+impl FrameExport {
+    /// Creates named pipe and opens it in non-blocking mode.
+    fn create_pipe(path: &Path) -> Result<std::fs::File, Illusion> {
+        let c_path = CString::new(path.as_os_str().as_bytes())
+            .map_err(|_| Illusion::InvalidArgument(format!("Invalid pipe path {:?}", path)))?;
+        if unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) } != 0 {
+            let err = std::io::Error::last_os_error();
+            return Err(Illusion::General(format!("Failed to create pipe {:?}: {}", path, err)));
+        }
+
+        let pipe = std::fs::OpenOptions::new().read(true)
+            .write(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(path);
+        if pipe.is_err() {
+            let _ = std::fs::remove_file(path);
+        }
+        Ok(pipe?)
+    }
+
+    /// Writes header and clears table of slots.
+    fn write_header(&mut self) {
+        let memory = unsafe { std::slice::from_raw_parts_mut(self.data, self.size) };
+        let header = [MAGIC,
+                      VERSION,
+                      SLOT_COUNT as u32,
+                      self.width as u32,
+                      self.height as u32,
+                      self.stride as u32,
+                      FORMAT];
+        for (i, value) in header.iter().enumerate() {
+            Self::put_u32(memory, 4 * i, *value);
+        }
+        for slot in 0..SLOT_COUNT {
+            Self::put_u64(memory, HEADER_SIZE + slot * SLOT_ENTRY_SIZE, 0);
+            Self::put_u64(memory, HEADER_SIZE + slot * SLOT_ENTRY_SIZE + 8, 0);
+        }
+    }
+
+    /// Writes little-endian `u32` at given offset.
+    fn put_u32(memory: &mut [u8], offset: usize, value: u32) {
+        for i in 0..4 {
+            memory[offset + i] = (value >> (8 * i)) as u8;
+        }
+    }
+
+    /// Writes little-endian `u64` at given offset.
+    fn put_u64(memory: &mut [u8], offset: usize, value: u64) {
+        for i in 0..8 {
+            memory[offset + i] = (value >> (8 * i)) as u8;
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Drop for FrameExport {
+    fn drop(&mut self) {
+        if let Err(err) = mman::munmap(self.data as *mut _, self.size) {
+            log_warn2!("Failed to unmap frame ring: {:?}", err);
+        }
+        for path in vec![&self.ring_path, &self.pipe_path] {
+            if let Err(err) = std::fs::remove_file(path) {
+                log_warn2!("Failed to remove {:?}: {}", path, err);
+            }
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -7,6 +7,7 @@
 
 use libc;
 use std;
+use std::sync::atomic::{AtomicBool, Ordering, ATOMIC_BOOL_INIT};
 
 use log;
 
@@ -14,6 +15,9 @@ use log;
 
 const UNKNOWN_MESSAGE: &'static str = "<unknown>";
 
+/// Tells if application should start again after quitting.
+static RESTART_REQUESTED: AtomicBool = ATOMIC_BOOL_INIT;
+
 // -------------------------------------------------------------------------------------------------
 
 /// Shuts down the application by sending `SIGTERM` to itself.
@@ -24,6 +28,31 @@ pub fn quit() {
 
 // -------------------------------------------------------------------------------------------------
 
+/// Shuts down the application like `quit` but asks it to execute itself again. Wayland display
+/// socket is kept open in the meantime so clients can reconnect.
+pub fn restart() {
+    log_info1!("RESTART!");
+    RESTART_REQUESTED.store(true, Ordering::SeqCst);
+    unsafe { libc::kill(libc::getpid(), libc::SIGTERM) };
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Checks if restart was requested with `restart`.
+pub fn is_restart_requested() -> bool {
+    RESTART_REQUESTED.load(Ordering::SeqCst)
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Requests reloading configuration by sending `SIGHUP` to itself.
+pub fn reload_config() {
+    log_info1!("Reload configuration");
+    unsafe { libc::kill(libc::getpid(), libc::SIGHUP) };
+}
+
+// -------------------------------------------------------------------------------------------------
+
 /// Hook function for panics.
 ///
 /// Logs panic message and location and quits application.
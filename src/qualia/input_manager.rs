@@ -6,18 +6,29 @@
 //!
 //! This functionality is inspired by `vim`. As there we have here modes to be able to change
 //! applications behavior depending on which modes are on or off.
+//!
+//! Bindings translating key symbols to commands are handled by `KeyBinder` and take precedence
+//! over bindings executing functions.
+//!
+//! No bindings are executed while surface of application configured to bypass them has keyboard
+//! focus.
+//!
+//! Bindings sending commands which move focus, frames or selection or change size are repeated
+//! while their key is held. Repeating stops when any key is pressed or released.
 
 // -------------------------------------------------------------------------------------------------
 
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use uinput_sys;
 
-use dharma::Signaler;
+use dharma::{event_kind, Dispatcher, Signaler, Timer, TimerEventHandler};
 
-use defs::{modifier, mode_name, Command, KeyCode, KeyValue};
+use defs::{media_key, modifier, mode_name, Command, KeyCode, KeyValue};
 use enums::{Action, Direction, KeyState};
-use config::Config;
+use config::{Config, KeyRepeatConfig};
+use key_binder::KeyBinder;
 use binding_functions::{self, Executor};
 use perceptron::{self, Perceptron};
 
@@ -119,29 +130,57 @@ impl Mode {
 /// For thread-safe public version see `InputManager`.
 struct InnerInputManager {
     modes: Vec<Mode>,
+    key_binder: KeyBinder,
     code: KeyCode,
     command: Command,
     signaler: Signaler<Perceptron>,
+
+    /// IDs of applications bypassing bindings.
+    bypass_apps: Vec<String>,
+
+    /// ID of application with keyboard focus.
+    focused_app: Option<String>,
+
+    /// Tells if bindings are currently bypassed.
+    bypass: bool,
+
+    /// Rate and delay of repeating held bindings.
+    repeat_config: KeyRepeatConfig,
+
+    /// Timer triggering repeats of held binding.
+    repeat_timer: Timer,
+
+    /// Command sent by last executed binding.
+    last_command: Option<Command>,
+
+    /// Command repeated while its key is held.
+    repeated_command: Option<Command>,
 }
 
 // -------------------------------------------------------------------------------------------------
 
 impl InnerInputManager {
     /// `InnerInputManager` constructor.
-    pub fn new(config: &Config, signaler: Signaler<Perceptron>) -> Self {
+    pub fn new(config: &Config, signaler: Signaler<Perceptron>, repeat_timer: Timer) -> Self {
         // Create manager
         let mut inner = InnerInputManager {
             modes: Vec::new(),
+            key_binder: KeyBinder::new(&config.get_command_binding_config(),
+                                        &config.get_xkb_config()),
             code: 0,
             command: Command::default(),
             signaler: signaler,
+            bypass_apps: config.get_bypass_bindings_apps(),
+            focused_app: None,
+            bypass: false,
+            repeat_config: config.get_key_repeat_config(),
+            repeat_timer: repeat_timer,
+            last_command: None,
+            repeated_command: None,
         };
 
         // Create binding from configuration
-        let bindings = config.get_key_binding_config();
-        for b in bindings.iter() {
-            inner.add_binding(b.mode_name.to_owned(), b.binding.clone(), b.executor);
-        }
+        inner.add_config_bindings(config);
 
         // Activate default modes
         inner.make_mode_active(mode_name::COMMON.to_string(), true);
@@ -149,6 +188,38 @@ impl InnerInputManager {
         inner
     }
 
+    /// Helper method for adding all bindings from configuration.
+    fn add_config_bindings(&mut self, config: &Config) {
+        let bindings = config.get_key_binding_config();
+        for b in bindings.iter() {
+            self.add_binding(b.mode_name.to_owned(), b.binding.clone(), b.executor);
+        }
+    }
+
+    /// Helper method for starting repeating of given command if it is repeatable and repeating is
+    /// enabled.
+    fn start_repeat(&mut self, command: Command) {
+        let repeatable = match command.action {
+            Action::Focus | Action::Swap | Action::Move | Action::Jump | Action::Dive |
+            Action::Resize | Action::Tab => true,
+            _ => false,
+        };
+
+        if repeatable && (self.repeat_config.rate > 0) {
+            let delay = Duration::from_millis(self.repeat_config.delay as u64);
+            let interval = Duration::new(0, 1_000_000_000 / self.repeat_config.rate);
+            self.repeat_timer.arm(delay, Some(interval));
+            self.repeated_command = Some(command);
+        }
+    }
+
+    /// Helper method for stopping repeating of command.
+    fn stop_repeat(&mut self) {
+        if self.repeated_command.take().is_some() {
+            self.repeat_timer.disarm();
+        }
+    }
+
     /// Helper method for finding executor for given binding in active modes.
     fn find_executor(&self, binding: &Binding) -> Option<Executor> {
         for ref mode in self.modes.iter() {
@@ -161,21 +232,53 @@ impl InnerInputManager {
         None
     }
 
-    /// Tries for find executor matching to given key and state of modifiers and execute it if
-    /// found.
+    /// Tries for find command or executor matching to given key and state of modifiers and
+    /// execute it if found.
     pub fn catch_key(&mut self,
                      code: KeyCode,
                      value: KeyValue,
                      modifiers: modifier::ModifierType)
                      -> KeyCatchResult {
+        self.stop_repeat();
+        if self.bypass {
+            return KeyCatchResult::Passed;
+        }
+
         self.code = code;
-        if let Some(executor) = self.find_executor(&Binding::create(code, modifiers)) {
+        self.last_command = None;
+        let binding = Binding::create(code, modifiers);
+        let result = if let Some(key_command) = self.key_binder.find(&binding).cloned() {
+            if value == KeyState::Pressed as KeyValue {
+                if let Some(command) = key_command.command {
+                    if key_command.next_mode.is_none() {
+                        self.last_command = Some(command.clone());
+                    }
+                    self.signaler.emit(perceptron::COMMAND, Perceptron::Command(command));
+                }
+                if let Some(ref next_mode) = key_command.next_mode {
+                    self.key_binder.switch_mode(next_mode);
+                }
+            }
+            KeyCatchResult::Caught
+        } else if let Some(executor) = self.find_executor(&binding) {
             if value == KeyState::Pressed as KeyValue {
                 executor(self);
             }
             KeyCatchResult::Caught
         } else {
             KeyCatchResult::Passed
+        };
+
+        if let Some(command) = self.last_command.take() {
+            self.start_repeat(command);
+        }
+        result
+    }
+
+    /// Sends again command of held binding.
+    pub fn repeat(&mut self) {
+        if let Some(ref command) = self.repeated_command {
+            self.signaler.emit(perceptron::COMMAND, Perceptron::Command(command.clone()));
         }
     }
 
@@ -208,6 +311,40 @@ impl InnerInputManager {
             self.modes.push(mode);
         }
     }
+
+    /// Sets ID of application with keyboard focus and decides if bindings should be bypassed.
+    pub fn set_focused_app(&mut self, app_id: Option<String>) {
+        self.bypass = match app_id {
+            Some(ref app_id) => self.bypass_apps.contains(app_id),
+            None => false,
+        };
+        self.focused_app = app_id;
+    }
+
+    /// Replaces all bindings with ones from given configuration. Modes active before reload stay
+    /// active. Key binder returns to its default mode.
+    pub fn reload_bindings(&mut self, config: &Config) {
+        self.key_binder = KeyBinder::new(&config.get_command_binding_config(),
+                                         &config.get_xkb_config());
+        self.bypass_apps = config.get_bypass_bindings_apps();
+        self.repeat_config = config.get_key_repeat_config();
+        self.stop_repeat();
+        let focused_app = self.focused_app.take();
+        self.set_focused_app(focused_app);
+
+        let active_modes: Vec<String> = self.modes
+            .iter()
+            .filter(|mode| mode.is_active())
+            .map(|mode| mode.get_name())
+            .collect();
+
+        self.modes.clear();
+        self.add_config_bindings(config);
+
+        for mode_name in active_modes {
+            self.make_mode_active(mode_name, true);
+        }
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -223,9 +360,20 @@ pub struct InputManager {
 // -------------------------------------------------------------------------------------------------
 
 impl InputManager {
-    /// `InputManager` constructor.
-    pub fn new(config: &Config, signaler: Signaler<Perceptron>) -> Self {
-        InputManager { inner: Arc::new(Mutex::new(InnerInputManager::new(config, signaler))) }
+    /// `InputManager` constructor. Held bindings are repeated by timer added to given
+    /// `Dispatcher`.
+    pub fn new(config: &Config,
+               signaler: Signaler<Perceptron>,
+               mut dispatcher: Dispatcher)
+               -> Self {
+        let timer = Timer::new();
+        let inner = InnerInputManager::new(config, signaler, timer.clone());
+        let manager = InputManager { inner: Arc::new(Mutex::new(inner)) };
+
+        let mut repeating_manager = manager.clone();
+        let handler = TimerEventHandler::new(timer, Box::new(move || repeating_manager.repeat()));
+        dispatcher.add_source(Box::new(handler), event_kind::READ);
+        manager
     }
 
     /// Lock and call corresponding method from `InnerInputManager`.
@@ -238,6 +386,12 @@ impl InputManager {
         mine.catch_key(code, value, modifiers)
     }
 
+    /// Lock and call corresponding method from `InnerInputManager`.
+    pub fn repeat(&mut self) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.repeat()
+    }
+
     /// Lock and call corresponding method from `InnerInputManager`.
     pub fn make_mode_active(&mut self, mode_name: String, active: bool) {
         let mut mine = self.inner.lock().unwrap();
@@ -249,6 +403,18 @@ impl InputManager {
         let mut mine = self.inner.lock().unwrap();
         mine.add_binding(mode_name, binding, executor)
     }
+
+    /// Lock and call corresponding method from `InnerInputManager`.
+    pub fn set_focused_app(&mut self, app_id: Option<String>) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.set_focused_app(app_id)
+    }
+
+    /// Lock and call corresponding method from `InnerInputManager`.
+    pub fn reload_bindings(&mut self, config: &Config) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.reload_bindings(config)
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -289,6 +455,7 @@ impl binding_functions::InputContext for InnerInputManager {
     }
 
     fn execute_command(&mut self) {
+        self.last_command = Some(self.command.clone());
         self.signaler.emit(perceptron::COMMAND, Perceptron::Command(self.command.clone()));
         self.clean_command();
     }
@@ -331,6 +498,38 @@ impl binding_functions::InputContext for InnerInputManager {
             _ => None,
         }
     }
+
+    fn get_code_as_function_number(&self) -> Option<i32> {
+        match self.code as i32 {
+            uinput_sys::KEY_F1 => Some(1),
+            uinput_sys::KEY_F2 => Some(2),
+            uinput_sys::KEY_F3 => Some(3),
+            uinput_sys::KEY_F4 => Some(4),
+            uinput_sys::KEY_F5 => Some(5),
+            uinput_sys::KEY_F6 => Some(6),
+            uinput_sys::KEY_F7 => Some(7),
+            uinput_sys::KEY_F8 => Some(8),
+            uinput_sys::KEY_F9 => Some(9),
+            uinput_sys::KEY_F10 => Some(10),
+            uinput_sys::KEY_F11 => Some(11),
+            uinput_sys::KEY_F12 => Some(12),
+            _ => None,
+        }
+    }
+
+    fn get_code_as_media_key(&self) -> Option<&'static str> {
+        match self.code as i32 {
+            uinput_sys::KEY_VOLUMEUP => Some(media_key::VOLUME_UP),
+            uinput_sys::KEY_VOLUMEDOWN => Some(media_key::VOLUME_DOWN),
+            uinput_sys::KEY_MUTE => Some(media_key::MUTE),
+            uinput_sys::KEY_MICMUTE => Some(media_key::MIC_MUTE),
+            uinput_sys::KEY_PLAYPAUSE => Some(media_key::PLAY_PAUSE),
+            uinput_sys::KEY_NEXTSONG => Some(media_key::NEXT),
+            uinput_sys::KEY_PREVIOUSSONG => Some(media_key::PREVIOUS),
+            uinput_sys::KEY_STOPCD => Some(media_key::STOP),
+            _ => None,
+        }
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
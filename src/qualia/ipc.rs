@@ -3,6 +3,11 @@
 
 //! This module provides inter process communication via DBUS. This is used to communicate with
 //! `logind` to take sessions and devices.
+//!
+//! Devices taken from `logind` are paused when session becomes inactive (e.g. after switching
+//! virtual terminal) and resumed when it becomes active again. `logind` informs about it with
+//! `PauseDevice` and `ResumeDevice` signals of session object, which are handled by Device
+//! Manager.
 
 // Quick reminder - this command list all available sessions for current user:
 //
@@ -13,8 +18,10 @@
 
 // -------------------------------------------------------------------------------------------------
 
-use dbus::{BusName, BusType, Connection, Interface, Member, Message, MessageItem, Path};
+use dbus::{BusName, BusType, Connection, ConnectionItem, Interface, Member, Message, MessageItem};
+use dbus::Path;
 use nix::unistd::getpid;
+use std::collections::HashSet;
 use std::os::unix::io::RawFd;
 use std::sync::{Arc, Mutex};
 
@@ -26,6 +33,11 @@ const LOGIN_DESTINATION: &'static str = "org.freedesktop.login1";
 const LOGIN_OBJECT_PATH: &'static str = "/org/freedesktop/login1";
 const MANAGER_INTERFACE: &'static str = "org.freedesktop.login1.Manager";
 const SESSION_INTERFACE: &'static str = "org.freedesktop.login1.Session";
+const SEAT_INTERFACE: &'static str = "org.freedesktop.login1.Seat";
+const PROPERTIES_INTERFACE: &'static str = "org.freedesktop.DBus.Properties";
+
+/// Type of `PauseDevice` signal which has to be acknowledged with `PauseDeviceComplete`.
+pub const PAUSE_TYPE_PAUSE: &'static str = "pause";
 
 /// Response timeout in milliseconds.
 const TIMEOUT: i32 = 1000;
@@ -106,8 +118,10 @@ struct InnerIpc {
     login_destination: BusName<'static>,
     login_object_path: Path<'static>,
     session_object_path: Option<Path<'static>>,
+    seat_object_path: Option<Path<'static>>,
     session_interface: Interface<'static>,
     manager_interface: Interface<'static>,
+    taken_devices: HashSet<u64>,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -125,11 +139,30 @@ impl InnerIpc {
             login_destination: BusName::new(LOGIN_DESTINATION).unwrap(),
             login_object_path: Path::new(LOGIN_OBJECT_PATH).unwrap(),
             session_object_path: None,
+            seat_object_path: None,
             session_interface: Interface::new(SESSION_INTERFACE).unwrap(),
             manager_interface: Interface::new(MANAGER_INTERFACE).unwrap(),
+            taken_devices: HashSet::new(),
         }
     }
 
+    /// Calls method of session object with given arguments and waits for reply.
+    fn call_session(&self, message_name: &str, items: &[MessageItem]) -> Result<Message, Illusion> {
+        let connection = get_connection_or_return!(self.connection);
+        let session_object_path = get_session_or_return!(self.session_object_path);
+        let member = Member::new(message_name).unwrap();
+
+        // Prepare message
+        let mut message = Message::method_call(&self.login_destination,
+                                               &session_object_path,
+                                               &self.session_interface,
+                                               &member);
+        message.append_items(items);
+
+        // Send message and get result
+        Ok(assert_reply!(connection.send_with_reply_and_block(message, TIMEOUT)))
+    }
+
     /// Communicate with `logind` to obtain path to object representing session we are assigned to.
     fn get_session_by_pid(&mut self) -> Option<Path<'static>> {
         let connection = get_connection_or_return!(self.connection, None);
@@ -201,6 +234,33 @@ impl InnerIpc {
         }
     }
 
+    /// Communicate with `logind` to obtain path to object representing seat the session is
+    /// assigned to.
+    fn get_seat(&mut self) -> Option<Path<'static>> {
+        let connection = get_connection_or_return!(self.connection, None);
+        let session_object_path = get_session_or_return!(self.session_object_path, None);
+        let message_name = "Get";
+        let member = Member::new(message_name).expect(message_name);
+
+        // Prepare message
+        let mut message = Message::method_call(&self.login_destination,
+                                               &session_object_path,
+                                               &Interface::new(PROPERTIES_INTERFACE).unwrap(),
+                                               &member);
+        message.append_items(&[SESSION_INTERFACE.into(), "Seat".into()]);
+
+        // Send message and get result. `Seat` property is structure of seat name and object path.
+        let r = assert_reply!(connection.send_with_reply_and_block(message, TIMEOUT), None);
+        if let MessageItem::Variant(value) = r.get1().expect(message_name) {
+            if let MessageItem::Struct(ref structure) = *value {
+                if let Some(&MessageItem::ObjectPath(ref path)) = structure.get(1) {
+                    return Some(path.clone());
+                }
+            }
+        }
+        None
+    }
+
     /// Communicate to `logind` to take control over session.
     fn take_control(&mut self) -> Result<(), Illusion> {
         let connection = get_connection_or_return!(self.connection);
@@ -219,11 +279,24 @@ impl InnerIpc {
         assert_reply!(connection.send_with_reply_and_block(message, TIMEOUT));
         Ok(())
     }
+
+    /// Subscribes for signals of session object (device pausing and resuming, property changes).
+    fn subscribe(&mut self) -> Result<(), Illusion> {
+        let connection = get_connection_or_return!(self.connection);
+        let session_object_path = get_session_or_return!(self.session_object_path);
+        let rule = format!("type='signal',sender='{}',path='{}'",
+                           LOGIN_DESTINATION,
+                           session_object_path);
+        connection.add_match(&rule).map_err(|err| {
+                Illusion::General(format!("Failed to subscribe for session signals: {:?}", err))
+            })
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
 
 /// Structure proving access to interprocess communication.
+#[derive(Clone)]
 pub struct Ipc {
     inner: Arc<Mutex<InnerIpc>>,
 }
@@ -244,31 +317,95 @@ impl Ipc {
         if mine.session_object_path.is_none() {
             mine.session_object_path = mine.get_session_by_seat("seat0");
         }
-        mine.take_control()
+        mine.seat_object_path = mine.get_seat();
+        mine.take_control()?;
+        mine.subscribe()
+    }
+
+    /// Returns file descriptor of connection to DBUS. It becomes readable when signals from
+    /// `logind` arrive.
+    pub fn get_fd(&self) -> Option<RawFd> {
+        let mine = self.inner.lock().unwrap();
+        let connection = get_connection_or_return!(mine.connection, None);
+        connection.watch_fds().first().map(|watch| watch.fd())
+    }
+
+    /// Returns signals received from `logind` since last call.
+    pub fn receive_signals(&self) -> Vec<Message> {
+        let mine = self.inner.lock().unwrap();
+        let connection = get_connection_or_return!(mine.connection, Vec::new());
+        let mut messages = Vec::new();
+        for item in connection.incoming(0) {
+            match item {
+                ConnectionItem::Signal(message) => messages.push(message),
+                ConnectionItem::Nothing => break,
+                _ => {}
+            }
+        }
+        messages
     }
 
     /// Communicate to `logind` to take control over given device.
     pub fn take_device(&self, rdev: u64) -> Result<RawFd, Illusion> {
+        let mut mine = self.inner.lock().unwrap();
+        let message_name = "TakeDevice";
+        let (major, minor) = major_minor(rdev);
+        let r = mine.call_session(message_name, &[major.into(), minor.into()])?;
+        match r.get1().expect(message_name) {
+            MessageItem::UnixFd(fd) => {
+                mine.taken_devices.insert(rdev);
+                Ok(fd.into_fd())
+            }
+            _ => Err(Illusion::Unknown(format!("Received wrong answer!"))),
+        }
+    }
+
+    /// Checks if given device was taken from `logind`. Access to such devices (including DRM
+    /// master) is managed by `logind` when session is paused or resumed.
+    pub fn is_device_taken(&self, rdev: u64) -> bool {
+        let mine = self.inner.lock().unwrap();
+        mine.taken_devices.contains(&rdev)
+    }
+
+    /// Communicate to `logind` to switch the seat to virtual terminal with given number.
+    pub fn switch_to(&self, vt: u32) -> Result<(), Illusion> {
         let mine = self.inner.lock().unwrap();
         let connection = get_connection_or_return!(mine.connection);
-        let session_object_path = get_session_or_return!(mine.session_object_path);
-        let message_name = "TakeDevice";
-        let member = Member::new(message_name).unwrap();
+        let seat_object_path = match mine.seat_object_path {
+            Some(ref seat) => seat,
+            None => return Err(Illusion::General(format!("Seat object path is unknown!"))),
+        };
+        let member = Member::new("SwitchTo").unwrap();
 
         // Prepare message
         let mut message = Message::method_call(&mine.login_destination,
-                                               &session_object_path,
-                                               &mine.session_interface,
+                                               &seat_object_path,
+                                               &Interface::new(SEAT_INTERFACE).unwrap(),
                                                &member);
-        let (major, minor) = major_minor(rdev);
-        message.append_items(&[major.into(), minor.into()]);
+        message.append_items(&[vt.into()]);
 
         // Send message and get result
-        let r = assert_reply!(connection.send_with_reply_and_block(message, TIMEOUT));
-        match r.get1().expect(message_name) {
-            MessageItem::UnixFd(fd) => Ok(fd.into_fd()),
-            _ => Err(Illusion::Unknown(format!("Received wrong answer!"))),
+        assert_reply!(connection.send_with_reply_and_block(message, TIMEOUT));
+        Ok(())
+    }
+
+    /// Communicate to `logind` to give back control over given device so it can be taken again
+    /// later. Does nothing if the device was not taken from `logind`.
+    pub fn release_device(&self, rdev: u64) -> Result<(), Illusion> {
+        let mut mine = self.inner.lock().unwrap();
+        if mine.taken_devices.remove(&rdev) {
+            let (major, minor) = major_minor(rdev);
+            mine.call_session("ReleaseDevice", &[major.into(), minor.into()])?;
         }
+        Ok(())
+    }
+
+    /// Acknowledges to `logind` that device with given numbers is not used any more after it was
+    /// paused.
+    pub fn pause_device_complete(&self, major: u32, minor: u32) -> Result<(), Illusion> {
+        let mine = self.inner.lock().unwrap();
+        mine.call_session("PauseDeviceComplete", &[major.into(), minor.into()])?;
+        Ok(())
     }
 }
 
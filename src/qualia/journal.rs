@@ -0,0 +1,105 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains entries of window lifecycle journal.
+//!
+//! Journal is an append-only file in data directory meant for time-tracking tools. Every line
+//! describes one event and consists of tab-separated fields:
+//!
+//! - time since Unix epoch in seconds with millisecond precision
+//! - kind of event: `open`, `close` or `focus`
+//! - ID of the surface (lets pair events of many windows of the same application)
+//! - application ID (empty if not known)
+//! - title (empty if not known)
+
+// -------------------------------------------------------------------------------------------------
+
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use defs::SurfaceId;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Kind of window lifecycle event.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JournalEvent {
+    /// Window was shown.
+    Open,
+
+    /// Window was hidden or destroyed.
+    Close,
+
+    /// Window gained keyboard focus.
+    Focus,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl fmt::Display for JournalEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            JournalEvent::Open => write!(f, "open"),
+            JournalEvent::Close => write!(f, "close"),
+            JournalEvent::Focus => write!(f, "focus"),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Single entry of window lifecycle journal.
+#[derive(Clone, Debug, PartialEq)]
+pub struct JournalEntry {
+    /// Time of the event since Unix epoch.
+    pub time: Duration,
+
+    /// Kind of the event.
+    pub event: JournalEvent,
+
+    /// ID of the window surface.
+    pub sid: SurfaceId,
+
+    /// Application ID of the window.
+    pub app_id: String,
+
+    /// Title of the window.
+    pub title: String,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl JournalEntry {
+    /// Constructs new `JournalEntry` for event happening now.
+    pub fn new(event: JournalEvent,
+               sid: SurfaceId,
+               app_id: Option<&String>,
+               title: Option<&String>)
+               -> Self {
+        JournalEntry {
+            time: SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default(),
+            event: event,
+            sid: sid,
+            app_id: app_id.map(|s| Self::sanitize(s)).unwrap_or_default(),
+            title: title.map(|s| Self::sanitize(s)).unwrap_or_default(),
+        }
+    }
+
+    /// Formats entry as line of journal file.
+    pub fn to_line(&self) -> String {
+        format!("{}.{:03}\t{}\t{}\t{}\t{}\n",
+                self.time.as_secs(),
+                self.time.subsec_nanos() / 1_000_000,
+                self.event,
+                self.sid.as_number(),
+                self.app_id,
+                self.title)
+    }
+
+    /// Replaces characters used as separators in journal file.
+    fn sanitize(text: &String) -> String {
+        text.replace(|c| c == '\t' || c == '\n', " ")
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,104 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains functionality related to translating key symbols into commands.
+//!
+//! This functionality is inspired by `i3`. Bindings are grouped in modes (e.g. `default`,
+//! `resize`, `launch`) and exactly one of them is active at a time. Binding may emit command for
+//! Exhibitor, switch to other mode or both.
+
+// -------------------------------------------------------------------------------------------------
+
+use std::collections::HashMap;
+
+use config::{CommandEntry, XkbConfig};
+use defs::Command;
+use input_manager::Binding;
+use keymap::XkbKeymap;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Name of mode active at start and after reloading configuration.
+pub const DEFAULT_MODE: &'static str = "default";
+
+// -------------------------------------------------------------------------------------------------
+
+/// Describes what should happen when key bound in `KeyBinder` is pressed.
+#[derive(Clone, Debug)]
+pub struct KeyCommand {
+    /// Command to be sent to Exhibitor.
+    pub command: Option<Command>,
+
+    /// Name of mode to switch to.
+    pub next_mode: Option<String>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Maps key symbols pressed together with modifiers to commands.
+///
+/// Key symbols are translated to key codes once when `KeyBinder` is created so catching keys does
+/// not require keeping `xkb` state.
+pub struct KeyBinder {
+    modes: HashMap<String, HashMap<Binding, KeyCommand>>,
+    current_mode: String,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl KeyBinder {
+    /// Constructs new `KeyBinder` from given entries. Key symbols are resolved using first layout
+    /// of key map described by `xkb_config`. Entries with key symbols not present in key map are
+    /// ignored.
+    pub fn new(entries: &[CommandEntry], xkb_config: &XkbConfig) -> Self {
+        let mut mine = KeyBinder {
+            modes: HashMap::new(),
+            current_mode: DEFAULT_MODE.to_owned(),
+        };
+
+        if entries.is_empty() {
+            return mine;
+        }
+
+        let keymap = if let Some(keymap) = XkbKeymap::new(xkb_config) {
+            keymap
+        } else {
+            log_warn1!("Key binder: failed to create key map; command bindings are disabled");
+            return mine;
+        };
+
+        for entry in entries.iter() {
+            if let Some(code) = keymap.find_key_code(entry.keysym) {
+                mine.modes
+                    .entry(entry.mode_name.clone())
+                    .or_insert_with(HashMap::new)
+                    .insert(Binding::create(code, entry.modifiers), entry.key_command.clone());
+            } else {
+                log_warn1!("Key binder: no key produces key symbol {:#x}", entry.keysym);
+            }
+        }
+        mine
+    }
+
+    /// Searches for command bound to given binding in current mode.
+    pub fn find(&self, binding: &Binding) -> Option<&KeyCommand> {
+        if let Some(bindings) = self.modes.get(&self.current_mode) {
+            bindings.get(binding)
+        } else {
+            None
+        }
+    }
+
+    /// Makes mode with given name the current one.
+    pub fn switch_mode(&mut self, mode_name: &str) {
+        log_info2!("Key binder: switch mode from '{}' to '{}'", self.current_mode, mode_name);
+        self.current_mode = mode_name.to_owned();
+    }
+
+    /// Returns name of current mode.
+    pub fn get_current_mode(&self) -> &str {
+        &self.current_mode
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,65 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains functionality related to translating keys of one keyboard before they are
+//! handled by key bindings and `xkb`.
+
+// -------------------------------------------------------------------------------------------------
+
+use std::collections::HashMap;
+
+use config::KeyboardConfig;
+use defs::{KeyCode, KeyValue};
+use enums::KeyState;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Translates key codes of one keyboard to other key codes or to sequences of key codes.
+pub struct KeyRemapper {
+    remaps: HashMap<KeyCode, Vec<KeyCode>>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl KeyRemapper {
+    /// Constructs new `KeyRemapper` for device with given name. Entries for all keyboards are
+    /// applied first so entries for the device can override them.
+    pub fn new(configs: &[KeyboardConfig], device_name: &str) -> Self {
+        let mut remaps = HashMap::new();
+        let general = configs.iter().filter(|config| config.device.is_none());
+        let specific = configs.iter().filter(|config| match config.device {
+                                                 Some(ref device) => device == device_name,
+                                                 None => false,
+                                             });
+        for config in general.chain(specific) {
+            for remap in config.remaps.iter() {
+                remaps.insert(remap.code, remap.sequence.clone());
+            }
+        }
+        KeyRemapper { remaps: remaps }
+    }
+
+    /// Translates key event to list of key events. Not remapped keys are passed unchanged. Key
+    /// remapped to single key keeps the value. Key remapped to sequence of keys presses and
+    /// releases all of them in order when pressed and gives no events otherwise.
+    pub fn remap(&self, code: KeyCode, value: KeyValue) -> Vec<(KeyCode, KeyValue)> {
+        match self.remaps.get(&code) {
+            None => vec![(code, value)],
+            Some(sequence) if sequence.len() == 1 => vec![(sequence[0], value)],
+            Some(sequence) => {
+                if value == KeyState::Pressed as KeyValue {
+                    let mut events = Vec::with_capacity(2 * sequence.len());
+                    for code in sequence.iter() {
+                        events.push((*code, KeyState::Pressed as KeyValue));
+                        events.push((*code, KeyState::Released as KeyValue));
+                    }
+                    events
+                } else {
+                    Vec::new()
+                }
+            }
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
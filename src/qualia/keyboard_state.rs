@@ -11,13 +11,15 @@ use defs::{KeyCode, KeyValue};
 
 // -------------------------------------------------------------------------------------------------
 
-/// This struct represents state of keyboard modifiers (shift, ctrl, etc...).
+/// This struct represents state of keyboard modifiers (shift, ctrl, etc...) and index of active
+/// layout.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct KeyMods {
     pub depressed: u32,
     pub latched: u32,
     pub locked: u32,
     pub effective: u32,
+    pub layout: u32,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -30,16 +32,18 @@ impl KeyMods {
             latched: 0,
             locked: 0,
             effective: 0,
+            layout: 0,
         }
     }
 
-    /// Constructs `KeyMods` from given modifiers.
-    pub fn new(depressed: u32, latched: u32, locked: u32, effective: u32) -> Self {
+    /// Constructs `KeyMods` from given modifiers and layout index.
+    pub fn new(depressed: u32, latched: u32, locked: u32, effective: u32, layout: u32) -> Self {
         KeyMods {
             depressed: depressed,
             latched: latched,
             locked: locked,
             effective: effective,
+            layout: layout,
         }
     }
 }
@@ -50,6 +54,7 @@ impl KeyMods {
 pub struct KeyboardState {
     xkb_state: xkb::State,
     mods: KeyMods,
+    num_layouts: u32,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -60,6 +65,7 @@ impl KeyboardState {
         KeyboardState {
             xkb_state: xkb::State::new(&keymap),
             mods: KeyMods::default(),
+            num_layouts: keymap.num_layouts(),
         }
     }
 
@@ -74,10 +80,47 @@ impl KeyboardState {
         // Offset the key code by 8, as the evdev XKB rules reflect X's
         // broken key code system, which starts at 8.
         self.xkb_state.update_key(code as u32 + 8, direction);
+        self.refresh_mods()
+    }
+
+    /// Locks next (if `forward` is `true`) or previous layout. Returns `true` when active layout
+    /// changed, false otherwise.
+    pub fn cycle_layout(&mut self, forward: bool) -> bool {
+        if self.num_layouts < 2 {
+            return false;
+        }
+
+        let current = self.xkb_state.serialize_layout(xkb::STATE_LAYOUT_EFFECTIVE);
+        let layout = if forward {
+            (current + 1) % self.num_layouts
+        } else {
+            (current + self.num_layouts - 1) % self.num_layouts
+        };
+
+        let depressed = self.xkb_state.serialize_mods(xkb::STATE_MODS_DEPRESSED);
+        let latched = self.xkb_state.serialize_mods(xkb::STATE_MODS_LATCHED);
+        let locked = self.xkb_state.serialize_mods(xkb::STATE_MODS_LOCKED);
+        self.xkb_state.update_mask(depressed, latched, locked, 0, 0, layout);
+        self.refresh_mods()
+    }
+
+    /// Returns state of modifiers.
+    pub fn get_mods(&self) -> KeyMods {
+        self.mods
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Private methods.
+impl KeyboardState {
+    /// Reads modifiers and layout from `xkb` state. Returns `true` when they changed.
+    fn refresh_mods(&mut self) -> bool {
         let mods = KeyMods::new(self.xkb_state.serialize_mods(xkb::STATE_MODS_DEPRESSED),
                                 self.xkb_state.serialize_mods(xkb::STATE_MODS_LATCHED),
                                 self.xkb_state.serialize_mods(xkb::STATE_MODS_LOCKED),
-                                self.xkb_state.serialize_mods(xkb::STATE_MODS_EFFECTIVE));
+                                self.xkb_state.serialize_mods(xkb::STATE_MODS_EFFECTIVE),
+                                self.xkb_state.serialize_layout(xkb::STATE_LAYOUT_EFFECTIVE));
 
         if mods != self.mods {
             self.mods = mods;
@@ -86,11 +129,6 @@ impl KeyboardState {
             false
         }
     }
-
-    /// Returns state of modifiers.
-    pub fn get_mods(&self) -> KeyMods {
-        self.mods
-    }
 }
 
 // -------------------------------------------------------------------------------------------------
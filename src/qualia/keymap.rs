@@ -12,13 +12,22 @@ use xkbcommon::xkb;
 use nix;
 use nix::sys::mman;
 
+use config::XkbConfig;
 use errors::Illusion;
+use defs::KeyCode;
 use env;
 
 // -------------------------------------------------------------------------------------------------
 
 const DEFAULT_FORMAT: u32 = xkb::KEYMAP_FORMAT_TEXT_V1;
 
+/// Offset between evdev and `xkb` key codes. The evdev XKB rules reflect X's broken key code
+/// system, which starts at 8.
+const EVDEV_OFFSET: u32 = 8;
+
+/// Highest evdev key code searched when resolving key symbols.
+const MAX_KEY_CODE: u32 = 255;
+
 // -------------------------------------------------------------------------------------------------
 
 /// Structure containing settings for key map.
@@ -41,17 +50,21 @@ pub struct XkbKeymap {
 // -------------------------------------------------------------------------------------------------
 
 impl XkbKeymap {
-    /// Constructs new `XkbKeymap`.
-    ///
-    /// TODO: Keyboard layout should be customizable.
+    /// Constructs new `XkbKeymap` with default `us` layout.
     pub fn default() -> Option<Self> {
-        let rules = "evdev".to_owned();
-        let model = "evdev".to_owned();
-        let layout = "us".to_owned();
-        let variant = "".to_owned();
+        Self::new(&XkbConfig::default())
+    }
 
+    /// Constructs new `XkbKeymap` from components named in given configuration.
+    pub fn new(config: &XkbConfig) -> Option<Self> {
         let context = xkb::Context::new(xkb::CONTEXT_NO_FLAGS);
-        let k = xkb::Keymap::new_from_names(&context, &rules, &model, &layout, &variant, None, 0x0);
+        let k = xkb::Keymap::new_from_names(&context,
+                                            &config.rules,
+                                            &config.model,
+                                            &config.layout,
+                                            &config.variant,
+                                            config.options.clone(),
+                                            xkb::KEYMAP_COMPILE_NO_FLAGS);
         if let Some(keymap) = k {
             Some(XkbKeymap {
                      context: context,
@@ -61,6 +74,17 @@ impl XkbKeymap {
             None
         }
     }
+
+    /// Searches for evdev code of key producing given key symbol when no modifiers are active.
+    pub fn find_key_code(&self, keysym: xkb::Keysym) -> Option<KeyCode> {
+        let state = xkb::State::new(&self.keymap);
+        for code in 0..(MAX_KEY_CODE + 1) {
+            if state.key_get_one_sym(code + EVDEV_OFFSET) == keysym {
+                return Some(code as KeyCode);
+            }
+        }
+        None
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -78,9 +102,13 @@ pub struct Keymap {
 // -------------------------------------------------------------------------------------------------
 
 impl Keymap {
-    /// `Keymap` constructor.
-    pub fn new(env: &env::Env) -> Result<Self, Illusion> {
-        let k = XkbKeymap::default();
+    /// `Keymap` constructor. Falls back to default key map if one described by `config` can not
+    /// be constructed.
+    pub fn new(env: &env::Env, config: &XkbConfig) -> Result<Self, Illusion> {
+        let k = XkbKeymap::new(config).or_else(|| {
+            log_warn1!("Failed to create key map from {:?}; using default one", config);
+            XkbKeymap::default()
+        });
         let xkb_keymap = if let Some(xkb_keymap) = k {
             xkb_keymap
         } else {
@@ -0,0 +1,264 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains description of surfaces placed in layers above or below windows (panels,
+//! docks, launchers, notifications, backgrounds).
+//!
+//! Semantics follow `zwlr_layer_surface_v1`: surface is anchored to edges of output and placed
+//! with given margins from them. Surface anchored to two opposite edges is stretched between them
+//! if its size in that direction is zero. Surfaces may reserve space along edge they are anchored
+//! to (exclusive zone) so windows and other layer surfaces do not cover them.
+
+// -------------------------------------------------------------------------------------------------
+
+use std::cmp::Reverse;
+
+use defs::{Area, Margins, Position, Size};
+use positioner::edges;
+use surface::SurfaceId;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Layer in which surface is placed. Layers are drawn in order of declaration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Layer {
+    /// Below everything, above wallpaper.
+    Background,
+
+    /// Below windows.
+    Bottom,
+
+    /// Above windows but below fullscreen surfaces.
+    Top,
+
+    /// Above everything.
+    Overlay,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Tells if and when layer surface receives keyboard focus.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum KeyboardInteractivity {
+    /// Surface never receives keyboard focus (e.g. bars).
+    None,
+
+    /// Surface in top or overlay layer takes keyboard focus and keeps it until it is unmapped or
+    /// changes interactivity (e.g. launchers, lock screens).
+    Exclusive,
+
+    /// Surface receives keyboard focus when clicked and loses it as other surfaces do.
+    OnDemand,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// State of surface placed in layer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayerSurface {
+    /// ID of the surface.
+    pub sid: SurfaceId,
+
+    /// ID of output the surface is placed on. `None` if compositor should choose one.
+    pub output_id: Option<i32>,
+
+    /// Layer the surface is placed in.
+    pub layer: Layer,
+
+    /// Namespace given by client describing purpose of the surface.
+    pub namespace: String,
+
+    /// Size requested by client. Zero width or height means the surface should be stretched
+    /// between edges it is anchored to.
+    pub size: Size,
+
+    /// Edges of output the surface is anchored to.
+    pub anchor: edges::Edges,
+
+    /// Distances from anchored edges.
+    pub margins: Margins,
+
+    /// Size of area along anchored edge which should not be covered by windows. Zero means no
+    /// area is reserved, negative value that the surface does not want to be moved because of
+    /// areas reserved by other surfaces.
+    pub exclusive_zone: i32,
+
+    /// Tells if and when the surface receives keyboard focus.
+    pub keyboard_interactivity: KeyboardInteractivity,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl LayerSurface {
+    /// Constructs new `LayerSurface` with default state.
+    pub fn new(sid: SurfaceId, output_id: Option<i32>, layer: Layer, namespace: String) -> Self {
+        LayerSurface {
+            sid: sid,
+            output_id: output_id,
+            layer: layer,
+            namespace: namespace,
+            size: Size::default(),
+            anchor: edges::NONE,
+            margins: Margins::default(),
+            exclusive_zone: 0,
+            keyboard_interactivity: KeyboardInteractivity::None,
+        }
+    }
+
+    /// Checks if the surface takes keyboard focus from all other surfaces.
+    pub fn is_keyboard_exclusive(&self) -> bool {
+        (self.keyboard_interactivity == KeyboardInteractivity::Exclusive) &&
+        (self.layer >= Layer::Top)
+    }
+
+    /// Calculates area of the surface placed on output with given area. Surface anchored to both
+    /// or none of opposite edges is centered between them.
+    pub fn calculate_area(&self, output: Area) -> Area {
+        let (x, width) = place(output.pos.x,
+                               output.size.width,
+                               self.size.width,
+                               self.margins.left,
+                               self.margins.right,
+                               self.anchor.contains(edges::LEFT),
+                               self.anchor.contains(edges::RIGHT));
+        let (y, height) = place(output.pos.y,
+                                output.size.height,
+                                self.size.height,
+                                self.margins.top,
+                                self.margins.bottom,
+                                self.anchor.contains(edges::TOP),
+                                self.anchor.contains(edges::BOTTOM));
+        Area::new(Position::new(x, y), Size::new(width, height))
+    }
+
+    /// Returns edge along which the surface reserves space. Returns `None` if the surface does not
+    /// reserve space.
+    pub fn get_exclusive_edge(&self) -> Option<edges::Edges> {
+        if self.exclusive_zone > 0 {
+            self.get_anchored_edge()
+        } else {
+            None
+        }
+    }
+
+    /// Returns edge the surface is attached to: the one it is anchored to alone or together with
+    /// both perpendicular edges. Returns `None` if anchors do not point out single edge.
+    pub fn get_anchored_edge(&self) -> Option<edges::Edges> {
+        let horizontal = edges::LEFT | edges::RIGHT;
+        let vertical = edges::TOP | edges::BOTTOM;
+        for edge in [edges::TOP, edges::BOTTOM, edges::LEFT, edges::RIGHT].iter() {
+            let perpendicular = if vertical.contains(*edge) { horizontal } else { vertical };
+            if (self.anchor == *edge) || (self.anchor == (*edge | perpendicular)) {
+                return Some(*edge);
+            }
+        }
+        None
+    }
+
+    /// Returns given area without space reserved by the surface.
+    fn reserve(&self, area: Area) -> Area {
+        let mut area = area;
+        match self.get_exclusive_edge() {
+            Some(edges::TOP) => {
+                let length = self.exclusive_zone as usize + self.margins.top;
+                area.pos.y += length.min(area.size.height) as isize;
+                area.size.height = area.size.height.saturating_sub(length);
+            }
+            Some(edges::BOTTOM) => {
+                let length = self.exclusive_zone as usize + self.margins.bottom;
+                area.size.height = area.size.height.saturating_sub(length);
+            }
+            Some(edges::LEFT) => {
+                let length = self.exclusive_zone as usize + self.margins.left;
+                area.pos.x += length.min(area.size.width) as isize;
+                area.size.width = area.size.width.saturating_sub(length);
+            }
+            Some(edges::RIGHT) => {
+                let length = self.exclusive_zone as usize + self.margins.right;
+                area.size.width = area.size.width.saturating_sub(length);
+            }
+            _ => {}
+        }
+        area
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Placement of layer surfaces on one output.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayerArrangement {
+    /// Part of output not reserved by layer surfaces, where windows are placed.
+    pub usable_area: Area,
+
+    /// Areas of layer surfaces.
+    pub areas: Vec<(SurfaceId, Area)>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl LayerArrangement {
+    /// Arranges given layer surfaces on output with given area.
+    ///
+    /// Surfaces reserving space are placed first, from the uppermost layer, each in space left by
+    /// previous ones. Then surfaces with zero exclusive zone are placed in space left for windows
+    /// and surfaces with negative exclusive zone on whole output.
+    pub fn new(output: Area, surfaces: &[LayerSurface]) -> Self {
+        let mut reserving: Vec<&LayerSurface> = surfaces.iter()
+            .filter(|surface| surface.get_exclusive_edge().is_some())
+            .collect();
+        reserving.sort_by_key(|surface| Reverse(surface.layer));
+
+        let mut usable_area = output;
+        let mut areas = Vec::with_capacity(surfaces.len());
+        for surface in reserving {
+            areas.push((surface.sid, surface.calculate_area(usable_area)));
+            usable_area = surface.reserve(usable_area);
+        }
+
+        for surface in surfaces.iter().filter(|surface| surface.get_exclusive_edge().is_none()) {
+            let bounds = if surface.exclusive_zone < 0 { output } else { usable_area };
+            areas.push((surface.sid, surface.calculate_area(bounds)));
+        }
+
+        LayerArrangement {
+            usable_area: usable_area,
+            areas: areas,
+        }
+    }
+
+    /// Returns area of given surface or `None` if it was not arranged.
+    pub fn get_area(&self, sid: SurfaceId) -> Option<Area> {
+        self.areas.iter().find(|&&(id, _)| id == sid).map(|&(_, area)| area)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Places surface of given length along one axis of output. Returns position and length of the
+/// surface.
+fn place(start: isize,
+         available: usize,
+         length: usize,
+         margin_start: usize,
+         margin_end: usize,
+         anchor_start: bool,
+         anchor_end: bool)
+         -> (isize, usize) {
+    let length = if length == 0 && anchor_start && anchor_end {
+        available.saturating_sub(margin_start + margin_end)
+    } else {
+        length
+    };
+
+    let offset = if anchor_start && !anchor_end {
+        margin_start as isize
+    } else if anchor_end && !anchor_start {
+        available as isize - margin_end as isize - length as isize
+    } else {
+        (available as isize - length as isize) / 2
+    };
+    (start + offset, length)
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -6,10 +6,12 @@
 
 extern crate backtrace;
 extern crate dbus;
+extern crate image;
 extern crate libc;
 extern crate libudev; // for implementation of `From` in `errors`.
 extern crate nix;
 extern crate time;
+extern crate toml;
 extern crate xkbcommon;
 extern crate uinput_sys;
 #[macro_use]
@@ -20,11 +22,14 @@ extern crate timber;
 extern crate dharma;
 
 pub mod enums;
-pub use enums::{DeviceKind, KeyState, Action, Direction};
+pub use enums::{DeviceKind, KeyState, Action, DecorationMode, Direction, Switch, Transform};
+pub use enums::{GestureKind, SizeMismatchPolicy};
 
 pub mod perceptron;
 pub use perceptron::Perceptron;
 
+pub mod signals;
+
 pub mod errors;
 pub use errors::Illusion;
 
@@ -32,18 +37,42 @@ pub use errors::Illusion;
 pub mod macros;
 
 pub mod timing;
-pub use timing::Milliseconds;
+pub use timing::{Milliseconds, PresentationTime};
 
 pub mod defs;
-pub use defs::{Area, Point, Position, OptionalPosition, Size, Slide, Vector};
-pub use defs::{Axis, Button, Command, DrmBundle, modifier, Key, KeyCode, KeyValue, OutputInfo};
-pub use defs::{MemoryPoolId, MemoryViewId};
+pub use defs::{Area, Margins, Point, Position, OptionalPosition, Size, Slide, Vector};
+pub use defs::{AbsolutePosition, Axis, Button, Command, DrmBundle, modifier};
+pub use defs::{GestureMotion, Key, KeyCode, KeyValue, OutputInfo, OutputMode, TouchPoint};
+pub use defs::OutputConfiguration;
+pub use defs::{HwImageId, MemoryPoolId, MemoryViewId, SeatId, seat_capability};
 
 pub mod config;
-pub use config::{Config, InputConfig};
+pub use config::{CommandEntry, Config, ExhibitorConfig, InputConfig, SecurityConfig};
+pub use config::{AccelProfile, InputDeviceConfig, InputDeviceId, KeyRepeatConfig, ScrollMethod};
+pub use config::{KeyboardConfig, KeyRemapConfig, OutputProfileConfig, ProfileConfig};
+pub use config::{ScreencastConfig, SeatConfig, SwitchAction, SwitchConfig, TouchConfig};
+pub use config::{WatchdogConfig, WorkspaceConfig, WorkspaceLayout, XkbConfig};
+pub use config::{AutoHideConfig, CursorConfig, IdleConfig, WallpaperConfig, WallpaperMode};
+pub use config::{JournalConfig, MediaKeysConfig, MetricsConfig};
+
+mod config_parser;
+
+pub mod colorimetry;
+pub use colorimetry::{ColorDescription, HdrMetadata, Primaries, TransferFunction};
 
 pub mod memory;
-pub use memory::{Buffer, Pixmap, MappedMemory, MemoryPool, MemoryView};
+pub use memory::{AlphaMode, Buffer, Pixmap, MappedMemory, MemoryPool, MemoryView, PixelFormat};
+pub use memory::{Dmabuf, DmabufPlane, HwImage};
+
+pub mod metrics;
+pub use metrics::{Metrics, StateSnapshot};
+
+pub mod journal;
+pub use journal::{JournalEntry, JournalEvent};
+
+pub mod png;
+
+pub mod wallpaper;
 
 #[macro_use]
 pub mod log;
@@ -54,6 +83,12 @@ pub mod functions;
 pub mod env;
 pub use env::Env;
 
+pub mod frame_export;
+pub use frame_export::FrameExport;
+
+pub mod control;
+pub use control::{ControlReply, ControlRequest, ScreenshotTarget};
+
 pub mod keyboard_state;
 pub use keyboard_state::{KeyboardState, KeyMods};
 
@@ -63,8 +98,18 @@ pub use keymap::{Keymap, Settings as KeymapSettings, XkbKeymap};
 pub mod settings;
 pub use settings::Settings;
 
+pub mod transfer;
+pub use transfer::{Drag, DragState, Transfer};
+
+pub mod positioner;
+pub use positioner::Positioner;
+
+pub mod layer_shell;
+pub use layer_shell::{KeyboardInteractivity, Layer, LayerArrangement, LayerSurface};
+
 pub mod surface;
 pub use surface::{SurfaceAccess, SurfaceContext, SurfaceId, SurfaceIdType, SurfaceInfo};
+pub use surface::{BufferTransform, SurfacePlacement};
 pub use surface::{show_reason, surface_state};
 
 pub mod coordinator;
@@ -74,6 +119,12 @@ mod binding_functions;
 pub mod input_manager;
 pub use input_manager::{InputManager, KeyCatchResult};
 
+pub mod key_binder;
+pub use key_binder::{KeyBinder, KeyCommand};
+
+pub mod key_remapper;
+pub use key_remapper::KeyRemapper;
+
 pub mod context;
 pub use context::Context;
 
@@ -16,15 +16,27 @@
 //! to construct `MemoryView` is through `MemoryPool`. Both have counted reference to
 //! `MappedMemory` and `MappedMemory` is destructed when its reference count goes to zero, so
 //! `MemoryView`s can be safely used even after `MappedMemory` was removed from `MemoryPool`.
+//!
+//! Clients using GPU for rendering may share their buffers without copying them through CPU
+//! memory. Such buffers are represented by `HwImage` and have to be imported directly by renderer.
+//!
+//! Pixels of images shared by clients have color channels premultiplied by alpha, while images
+//! drawn by compositor itself use straight alpha. Formats without alpha channel are opaque
+//! regardless of contents of their padding bytes. `AlphaMode` tells renderer how to blend them.
+//!
+//! Layout of pixels in memory is described by `PixelFormat`. Images drawn by compositor itself use
+//! the same layout as most clients (`PixelFormat::Argb8888`), except for wallpapers which are
+//! uploaded directly by renderer.
 
 use std;
 use std::os::unix::io::RawFd;
 use std::sync::Arc;
 
 use nix::sys::mman;
+use nix::unistd;
 
 use errors;
-use defs::Size;
+use defs::{Area, Position, Size};
 
 // -------------------------------------------------------------------------------------------------
 
@@ -48,6 +60,67 @@ pub trait Pixmap {
 
 // -------------------------------------------------------------------------------------------------
 
+/// DRM fourcc code of XRGB8888 format.
+const DRM_FORMAT_XRGB8888: u32 = 0x34325258;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Describes how alpha channel of an image should be interpreted when blending.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AlphaMode {
+    /// Alpha channel is ignored and image fully covers what is below it.
+    Opaque,
+
+    /// Color channels are not multiplied by alpha.
+    Straight,
+
+    /// Color channels are already multiplied by alpha.
+    Premultiplied,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl AlphaMode {
+    /// Returns alpha mode of client buffer with given DRM fourcc format. Clients are expected to
+    /// share premultiplied pixels so only formats without alpha channel are treated differently.
+    pub fn from_drm_format(format: u32) -> Self {
+        match format {
+            DRM_FORMAT_XRGB8888 => AlphaMode::Opaque,
+            _ => AlphaMode::Premultiplied,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Describes layout of pixels in memory. Names follow `wl_shm` formats, so components are listed
+/// from the most significant bits of little-endian pixel value.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PixelFormat {
+    /// 32-bit pixels with blue, green, red and alpha bytes in this order.
+    Argb8888,
+
+    /// 32-bit pixels with blue, green and red bytes followed by undefined padding byte.
+    Xrgb8888,
+
+    /// 16-bit pixels with 5 bits of red, 6 bits of green and 5 bits of blue.
+    Rgb565,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl PixelFormat {
+    /// Returns number of bytes of one pixel.
+    pub fn get_bytes_per_pixel(&self) -> usize {
+        match *self {
+            PixelFormat::Argb8888 | PixelFormat::Xrgb8888 => 4,
+            PixelFormat::Rgb565 => 2,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
 /// Container for all data required to draw an image.
 #[derive(Clone, Debug)]
 pub struct Buffer {
@@ -97,10 +170,34 @@ impl Buffer {
         self.data = other.data.clone();
     }
 
+    /// Returns number of bytes between beginnings of consecutive rows.
+    pub fn get_stride(&self) -> usize {
+        self.stride
+    }
+
     /// Checks if buffer contains drawable data.
     pub fn is_empty(&self) -> bool {
         (self.width == 0) || (self.height == 0) || (self.stride == 0) || (self.data.len() == 0)
     }
+
+    /// Returns copy of given area of the buffer. Pixels are assumed to have four bytes. Parts of
+    /// the area outside of the buffer are cut off.
+    pub fn crop(&self, area: &Area) -> Buffer {
+        let bounds = Area::new(Position::default(), self.get_size());
+        let area = match bounds.intersected(area) {
+            Some(area) => area,
+            None => return Buffer::empty(),
+        };
+
+        let (x, y) = (area.pos.x as usize, area.pos.y as usize);
+        let stride = 4 * area.size.width;
+        let mut data = Vec::with_capacity(stride * area.size.height);
+        for row in y..(y + area.size.height) {
+            let start = row * self.stride + 4 * x;
+            data.extend_from_slice(&self.data[start..start + stride]);
+        }
+        Buffer::new(area.size.width, area.size.height, stride, data)
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -140,6 +237,7 @@ impl Pixmap for Buffer {
 /// Represents memory shared with client.
 pub struct MappedMemory {
     data: *const u8,
+    size: usize,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -160,7 +258,12 @@ impl MappedMemory {
                          mman::MAP_SHARED,
                          fd,
                          0) {
-            Ok(memory) => Ok(MappedMemory { data: memory as *const u8 }),
+            Ok(memory) => {
+                Ok(MappedMemory {
+                       data: memory as *const u8,
+                       size: size,
+                   })
+            }
             Err(err) => Err(errors::Illusion::General(format!("Failed to map memory! {:?}", err))),
         }
     }
@@ -175,6 +278,8 @@ pub struct MemoryView {
     width: usize,
     height: usize,
     stride: usize,
+    format: PixelFormat,
+    alpha_mode: AlphaMode,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -218,6 +323,42 @@ impl Pixmap for MemoryView {
 
 // -------------------------------------------------------------------------------------------------
 
+impl MemoryView {
+    /// Returns number of bytes between beginnings of consecutive rows.
+    #[inline]
+    pub fn get_stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Returns layout of pixels of the view.
+    #[inline]
+    pub fn get_format(&self) -> PixelFormat {
+        self.format
+    }
+
+    /// Returns how alpha channel of the view should be interpreted.
+    #[inline]
+    pub fn get_alpha_mode(&self) -> AlphaMode {
+        self.alpha_mode
+    }
+
+    /// Returns writable slice of memory shared with client or `None` if view does not point to
+    /// shared memory.
+    ///
+    /// This is unsafe because client may modify the memory at the same time.
+    pub unsafe fn as_mut_slice(&self) -> Option<&mut [u8]> {
+        match *self.memory {
+            MemoryKind::Mapped(_) => {
+                Some(std::slice::from_raw_parts_mut(self.data as *mut u8,
+                                                    self.height * self.stride))
+            }
+            MemoryKind::Buffered(_) => None,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
 impl Clone for MemoryView {
     fn clone(&self) -> Self {
         MemoryView {
@@ -226,6 +367,8 @@ impl Clone for MemoryView {
             width: self.width,
             height: self.height,
             stride: self.stride,
+            format: self.format,
+            alpha_mode: self.alpha_mode,
         }
     }
 }
@@ -259,12 +402,22 @@ impl MemoryPool {
         MemoryPool { memory: Arc::new(MemoryKind::Buffered(buffer)) }
     }
 
+    /// Returns size of stored memory in bytes.
+    pub fn get_size(&self) -> usize {
+        match *self.memory {
+            MemoryKind::Mapped(ref map) => map.size,
+            MemoryKind::Buffered(ref buffer) => buffer.data.len(),
+        }
+    }
+
     /// Returns `MemoryView`s into `Buffer`s and `MappedMemory`s stored in `MemoryPool`.
     pub fn get_memory_view(&self,
                            offset: usize,
                            width: usize,
                            height: usize,
-                           stride: usize)
+                           stride: usize,
+                           format: PixelFormat,
+                           alpha_mode: AlphaMode)
                            -> MemoryView {
         // FIXME: Check if boundaries given as arguments are correct.
         match *self.memory {
@@ -275,6 +428,8 @@ impl MemoryPool {
                     width: width,
                     height: height,
                     stride: stride,
+                    format: format,
+                    alpha_mode: alpha_mode,
                 }
             }
             MemoryKind::Buffered(ref buffer) => {
@@ -284,6 +439,8 @@ impl MemoryPool {
                     width: width,
                     height: height,
                     stride: stride,
+                    format: format,
+                    alpha_mode: alpha_mode,
                 }
             }
         }
@@ -291,3 +448,97 @@ impl MemoryPool {
 }
 
 // -------------------------------------------------------------------------------------------------
+
+/// Describes one plane of DMA-BUF buffer.
+#[derive(Clone, Copy, Debug)]
+pub struct DmabufPlane {
+    /// File descriptor of the plane.
+    pub fd: RawFd,
+
+    /// Offset of the plane data in bytes.
+    pub offset: u32,
+
+    /// Stride of the plane in bytes.
+    pub stride: u32,
+
+    /// Format modifier describing layout of the data (e.g. tiling).
+    pub modifier: u64,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Describes buffer shared with client via DMA-BUF. `Dmabuf` owns file descriptors of its planes
+/// and closes them when dropped.
+#[derive(Debug)]
+pub struct Dmabuf {
+    /// Width of the buffer in pixels.
+    pub width: usize,
+
+    /// Height of the buffer in pixels.
+    pub height: usize,
+
+    /// DRM fourcc code of pixel format.
+    pub format: u32,
+
+    /// Planes of the buffer.
+    pub planes: Vec<DmabufPlane>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Drop for Dmabuf {
+    fn drop(&mut self) {
+        for plane in self.planes.iter() {
+            // Nothing sensible can be done if closing fails.
+            unistd::close(plane.fd).ok();
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Represents image stored in GPU memory. Its data is not accessible for CPU so renderer has to
+/// import it directly (e.g. as `EGLImage`).
+#[derive(Clone, Debug)]
+pub struct HwImage {
+    dmabuf: Arc<Dmabuf>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl HwImage {
+    /// Constructs new `HwImage` from DMA-BUF.
+    pub fn new_from_dmabuf(dmabuf: Dmabuf) -> Self {
+        HwImage { dmabuf: Arc::new(dmabuf) }
+    }
+
+    /// Returns description of underlying DMA-BUF.
+    pub fn get_dmabuf(&self) -> &Dmabuf {
+        &self.dmabuf
+    }
+
+    /// Get width and height of the image.
+    pub fn get_size(&self) -> Size {
+        Size {
+            width: self.dmabuf.width,
+            height: self.dmabuf.height,
+        }
+    }
+
+    /// Return width of the image.
+    pub fn get_width(&self) -> usize {
+        self.dmabuf.width
+    }
+
+    /// Return height of the image.
+    pub fn get_height(&self) -> usize {
+        self.dmabuf.height
+    }
+
+    /// Returns how alpha channel of the image should be interpreted.
+    pub fn get_alpha_mode(&self) -> AlphaMode {
+        AlphaMode::from_drm_format(self.dmabuf.format)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
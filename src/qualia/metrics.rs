@@ -0,0 +1,152 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains functionality related to gathering statistics about state of compositor
+//! and formatting them in Prometheus text exposition format.
+//!
+//! Exported metrics are:
+//!
+//! - `perceptia_frame_render_seconds` - histogram of time spent drawing frames, per output
+//! - `perceptia_clients` - number of connected clients
+//! - `perceptia_surfaces` - number of surfaces
+//! - `perceptia_memory_pools` - number of memory pools shared by clients
+//! - `perceptia_memory_pool_bytes` - total size of memory pools
+//! - `perceptia_hw_images` - number of images imported to GPU
+
+// -------------------------------------------------------------------------------------------------
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::Duration;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Number of buckets of frame render time histogram.
+const NUM_BUCKETS: usize = 8;
+
+/// Upper bounds in seconds of buckets of frame render time histogram.
+const FRAME_TIME_BUCKETS: [f64; NUM_BUCKETS] =
+    [0.001, 0.002, 0.004, 0.008, 0.016, 0.033, 0.066, 0.1];
+
+// -------------------------------------------------------------------------------------------------
+
+/// Momentary state of compositor reported together with gathered statistics.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct StateSnapshot {
+    /// Number of surfaces.
+    pub surfaces: usize,
+
+    /// Number of memory pools.
+    pub memory_pools: usize,
+
+    /// Total size of memory pools in bytes.
+    pub memory_pool_bytes: usize,
+
+    /// Number of hardware images.
+    pub hw_images: usize,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Statistics of frames drawn on one output.
+#[derive(Clone, Copy, Debug, Default)]
+struct FrameStatistics {
+    /// Numbers of frames drawn in time not longer than bounds from `FRAME_TIME_BUCKETS`.
+    buckets: [u64; NUM_BUCKETS],
+
+    /// Number of all drawn frames.
+    count: u64,
+
+    /// Total time of drawing all frames in seconds.
+    sum: f64,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Gathers statistics of compositor.
+#[derive(Clone, Debug, Default)]
+pub struct Metrics {
+    /// Frame statistics by name of output. Sorted to keep the output stable.
+    frames: BTreeMap<String, FrameStatistics>,
+
+    /// Number of connected clients.
+    clients: usize,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Metrics {
+    /// Constructs new `Metrics`.
+    pub fn new() -> Self {
+        Metrics::default()
+    }
+
+    /// Records time it took to draw frame on output with given name.
+    pub fn record_frame(&mut self, output: &str, duration: Duration) {
+        let seconds = duration.as_secs() as f64 + duration.subsec_nanos() as f64 / 1.0e9;
+        let statistics = self.frames.entry(output.to_owned()).or_insert_with(Default::default);
+        for (bucket, bound) in statistics.buckets.iter_mut().zip(FRAME_TIME_BUCKETS.iter()) {
+            if seconds <= *bound {
+                *bucket += 1;
+            }
+        }
+        statistics.count += 1;
+        statistics.sum += seconds;
+    }
+
+    /// Sets number of connected clients.
+    pub fn set_client_count(&mut self, count: usize) {
+        self.clients = count;
+    }
+
+    /// Formats gathered statistics together with given state in Prometheus text format.
+    pub fn format(&self, snapshot: &StateSnapshot) -> String {
+        let mut text = String::new();
+        let name = "perceptia_frame_render_seconds";
+        write_header(&mut text, name, "Time spent drawing frames.", "histogram");
+        for (output, statistics) in self.frames.iter() {
+            for (bucket, bound) in statistics.buckets.iter().zip(FRAME_TIME_BUCKETS.iter()) {
+                let _ = writeln!(text, "{}_bucket{{output=\"{}\",le=\"{}\"}} {}",
+                                 name, output, bound, bucket);
+            }
+            let _ = writeln!(text, "{}_bucket{{output=\"{}\",le=\"+Inf\"}} {}",
+                             name, output, statistics.count);
+            let _ = writeln!(text, "{}_sum{{output=\"{}\"}} {}", name, output, statistics.sum);
+            let _ = writeln!(text, "{}_count{{output=\"{}\"}} {}", name, output, statistics.count);
+        }
+
+        write_gauge(&mut text, "perceptia_clients", "Number of connected clients.", self.clients);
+        write_gauge(&mut text, "perceptia_surfaces", "Number of surfaces.", snapshot.surfaces);
+        write_gauge(&mut text,
+                    "perceptia_memory_pools",
+                    "Number of memory pools shared by clients.",
+                    snapshot.memory_pools);
+        write_gauge(&mut text,
+                    "perceptia_memory_pool_bytes",
+                    "Total size of memory pools shared by clients.",
+                    snapshot.memory_pool_bytes);
+        write_gauge(&mut text,
+                    "perceptia_hw_images",
+                    "Number of images imported to GPU.",
+                    snapshot.hw_images);
+        text
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Writes `HELP` and `TYPE` lines of metric.
+fn write_header(text: &mut String, name: &str, help: &str, kind: &str) {
+    let _ = writeln!(text, "# HELP {} {}", name, help);
+    let _ = writeln!(text, "# TYPE {} {}", name, kind);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Writes gauge metric with single value.
+fn write_gauge(text: &mut String, name: &str, help: &str, value: usize) {
+    write_header(text, name, help, "gauge");
+    let _ = writeln!(text, "{} {}", name, value);
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -6,12 +6,18 @@
 // -------------------------------------------------------------------------------------------------
 
 use std;
+use std::os::unix::io::RawFd;
 
-use dharma::SignalId;
+use dharma::{EventHandlerId, SignalId};
 
-use timing::Milliseconds;
-use defs::{Command, DrmBundle, OutputInfo, SurfaceId};
-use defs::{Axis, Position, OptionalPosition, Vector, Button, Key};
+use enums::{GestureKind, Switch, Transform};
+use timing::{Milliseconds, PresentationTime};
+use control::ScreenshotTarget;
+use defs::{Area, Command, DrmBundle, OutputConfiguration, OutputInfo, SeatId, SurfaceId};
+use memory::{Buffer, Pixmap};
+use defs::{AbsolutePosition, Axis, Position, OptionalPosition, Vector, Button, Key};
+use defs::{GestureMotion, TouchPoint};
+use defs::seat_capability::SeatCapabilities;
 
 // -------------------------------------------------------------------------------------------------
 
@@ -21,20 +27,173 @@ pub const PAGE_FLIP: SignalId = 2;
 pub const OUTPUT_FOUND: SignalId = 3;
 pub const COMMAND: SignalId = 5;
 pub const DISPLAY_CREATED: SignalId = 6;
+pub const CONFIG_RELOADED: SignalId = 7;
+pub const SCREENCAST_FRAME: SignalId = 8;
 pub const INPUT_POINTER_MOTION: SignalId = 10;
 pub const INPUT_POINTER_POSITION: SignalId = 11;
 pub const INPUT_POINTER_BUTTON: SignalId = 12;
 pub const INPUT_POINTER_AXIS: SignalId = 13;
 pub const INPUT_POINTER_POSITION_RESET: SignalId = 14;
 pub const INPUT_KEYBOARD: SignalId = 15;
+pub const SWITCH_TOGGLED: SignalId = 16;
+pub const ORIENTATION_CHANGED: SignalId = 17;
+pub const INPUT_POINTER_ABSOLUTE_POSITION: SignalId = 18;
 pub const SURFACE_READY: SignalId = 20;
 pub const SURFACE_DESTROYED: SignalId = 21;
 pub const SURFACE_RECONFIGURED: SignalId = 22;
+pub const PREFERRED_BUFFER_CHANGED: SignalId = 23;
+pub const SURFACE_CLOSE_REQUESTED: SignalId = 24;
 pub const CURSOR_SURFACE_CHANGE: SignalId = 25;
+pub const SURFACE_OUTPUT_ENTERED: SignalId = 26;
+pub const SURFACE_OUTPUT_LEFT: SignalId = 27;
+pub const SURFACE_TITLE_CHANGED: SignalId = 28;
+pub const DECORATION_MODE_CHANGED: SignalId = 29;
 pub const SURFACE_FRAME: SignalId = 30;
 pub const POINTER_FOCUS_CHANGED: SignalId = 31;
 pub const POINTER_RELATIVE_MOTION: SignalId = 32;
 pub const KEYBOARD_FOCUS_CHANGED: SignalId = 33;
+pub const SURFACE_KILL_REQUESTED: SignalId = 34;
+pub const TOUCH_DOWN: SignalId = 35;
+pub const TOUCH_MOTION: SignalId = 36;
+pub const TOUCH_UP: SignalId = 37;
+pub const TOUCH_FRAME: SignalId = 38;
+pub const TRANSFER_OFFERED: SignalId = 40;
+pub const TRANSFER_REQUESTED: SignalId = 41;
+pub const DRAG_STARTED: SignalId = 42;
+pub const DRAG_FOCUS_CHANGED: SignalId = 43;
+pub const DRAG_MOTION: SignalId = 44;
+pub const DRAG_DROPPED: SignalId = 45;
+pub const DRAG_ENDED: SignalId = 46;
+pub const DRAG_TRANSFER_REQUESTED: SignalId = 47;
+pub const MOVE_REQUESTED: SignalId = 50;
+pub const PLACEMENT_REQUESTED: SignalId = 51;
+pub const DECORATION_MODE_REQUESTED: SignalId = 52;
+pub const FULLSCREEN_REQUESTED: SignalId = 53;
+pub const MAXIMIZE_REQUESTED: SignalId = 54;
+pub const INITIAL_SIZE_REQUESTED: SignalId = 55;
+pub const INPUT_TOUCH_DOWN: SignalId = 60;
+pub const INPUT_TOUCH_MOTION: SignalId = 61;
+pub const INPUT_TOUCH_UP: SignalId = 62;
+pub const INPUT_TOUCH_FRAME: SignalId = 63;
+pub const INPUT_GESTURE_BEGIN: SignalId = 64;
+pub const INPUT_GESTURE_UPDATE: SignalId = 65;
+pub const INPUT_GESTURE_END: SignalId = 66;
+pub const GESTURE_BEGIN: SignalId = 70;
+pub const GESTURE_UPDATE: SignalId = 71;
+pub const GESTURE_END: SignalId = 72;
+pub const SEAT_KEYBOARD_FOCUS_CHANGED: SignalId = 73;
+pub const SEAT_CAPABILITIES_CHANGED: SignalId = 74;
+pub const INPUT_DEVICE_LOST: SignalId = 75;
+pub const IDLE: SignalId = 76;
+pub const RESUMED: SignalId = 77;
+pub const DISPLAY_DESTROYED: SignalId = 78;
+pub const SESSION_LOCK_CHANGED: SignalId = 79;
+pub const LOCK_SURFACE_ADDED: SignalId = 80;
+pub const SESSION_STATE_CHANGED: SignalId = 81;
+pub const VT_RELEASED: SignalId = 82;
+pub const VT_ACQUIRED: SignalId = 83;
+pub const CAPTURE_REQUESTED: SignalId = 84;
+pub const OUTPUT_CAPTURED: SignalId = 85;
+pub const SCREENSHOT_REQUESTED: SignalId = 86;
+pub const SCREENSHOT_TAKEN: SignalId = 87;
+pub const REGION_SELECTION_REQUESTED: SignalId = 88;
+pub const REGION_SELECTED: SignalId = 89;
+pub const LAYER_SURFACE_CHANGED: SignalId = 90;
+pub const DISPLAY_CHANGED: SignalId = 91;
+pub const OUTPUT_CONFIGURATION_REQUESTED: SignalId = 92;
+pub const OUTPUTS_CONFIGURED: SignalId = 93;
+pub const FRAME_PRESENTED: SignalId = 94;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Returns name of signal with given ID. Used for debugging.
+pub fn get_signal_name(id: SignalId) -> &'static str {
+    match id {
+        NOTIFY => "NOTIFY",
+        VERTICAL_BLANK => "VERTICAL_BLANK",
+        PAGE_FLIP => "PAGE_FLIP",
+        OUTPUT_FOUND => "OUTPUT_FOUND",
+        COMMAND => "COMMAND",
+        DISPLAY_CREATED => "DISPLAY_CREATED",
+        CONFIG_RELOADED => "CONFIG_RELOADED",
+        SCREENCAST_FRAME => "SCREENCAST_FRAME",
+        INPUT_POINTER_MOTION => "INPUT_POINTER_MOTION",
+        INPUT_POINTER_POSITION => "INPUT_POINTER_POSITION",
+        INPUT_POINTER_BUTTON => "INPUT_POINTER_BUTTON",
+        INPUT_POINTER_AXIS => "INPUT_POINTER_AXIS",
+        INPUT_POINTER_POSITION_RESET => "INPUT_POINTER_POSITION_RESET",
+        INPUT_KEYBOARD => "INPUT_KEYBOARD",
+        SWITCH_TOGGLED => "SWITCH_TOGGLED",
+        ORIENTATION_CHANGED => "ORIENTATION_CHANGED",
+        INPUT_POINTER_ABSOLUTE_POSITION => "INPUT_POINTER_ABSOLUTE_POSITION",
+        SURFACE_READY => "SURFACE_READY",
+        SURFACE_DESTROYED => "SURFACE_DESTROYED",
+        SURFACE_RECONFIGURED => "SURFACE_RECONFIGURED",
+        PREFERRED_BUFFER_CHANGED => "PREFERRED_BUFFER_CHANGED",
+        SURFACE_CLOSE_REQUESTED => "SURFACE_CLOSE_REQUESTED",
+        CURSOR_SURFACE_CHANGE => "CURSOR_SURFACE_CHANGE",
+        SURFACE_OUTPUT_ENTERED => "SURFACE_OUTPUT_ENTERED",
+        SURFACE_OUTPUT_LEFT => "SURFACE_OUTPUT_LEFT",
+        SURFACE_TITLE_CHANGED => "SURFACE_TITLE_CHANGED",
+        DECORATION_MODE_CHANGED => "DECORATION_MODE_CHANGED",
+        SURFACE_FRAME => "SURFACE_FRAME",
+        POINTER_FOCUS_CHANGED => "POINTER_FOCUS_CHANGED",
+        POINTER_RELATIVE_MOTION => "POINTER_RELATIVE_MOTION",
+        KEYBOARD_FOCUS_CHANGED => "KEYBOARD_FOCUS_CHANGED",
+        SURFACE_KILL_REQUESTED => "SURFACE_KILL_REQUESTED",
+        TOUCH_DOWN => "TOUCH_DOWN",
+        TOUCH_MOTION => "TOUCH_MOTION",
+        TOUCH_UP => "TOUCH_UP",
+        TOUCH_FRAME => "TOUCH_FRAME",
+        TRANSFER_OFFERED => "TRANSFER_OFFERED",
+        TRANSFER_REQUESTED => "TRANSFER_REQUESTED",
+        DRAG_STARTED => "DRAG_STARTED",
+        DRAG_FOCUS_CHANGED => "DRAG_FOCUS_CHANGED",
+        DRAG_MOTION => "DRAG_MOTION",
+        DRAG_DROPPED => "DRAG_DROPPED",
+        DRAG_ENDED => "DRAG_ENDED",
+        DRAG_TRANSFER_REQUESTED => "DRAG_TRANSFER_REQUESTED",
+        MOVE_REQUESTED => "MOVE_REQUESTED",
+        PLACEMENT_REQUESTED => "PLACEMENT_REQUESTED",
+        DECORATION_MODE_REQUESTED => "DECORATION_MODE_REQUESTED",
+        FULLSCREEN_REQUESTED => "FULLSCREEN_REQUESTED",
+        MAXIMIZE_REQUESTED => "MAXIMIZE_REQUESTED",
+        INITIAL_SIZE_REQUESTED => "INITIAL_SIZE_REQUESTED",
+        INPUT_TOUCH_DOWN => "INPUT_TOUCH_DOWN",
+        INPUT_TOUCH_MOTION => "INPUT_TOUCH_MOTION",
+        INPUT_TOUCH_UP => "INPUT_TOUCH_UP",
+        INPUT_TOUCH_FRAME => "INPUT_TOUCH_FRAME",
+        INPUT_GESTURE_BEGIN => "INPUT_GESTURE_BEGIN",
+        INPUT_GESTURE_UPDATE => "INPUT_GESTURE_UPDATE",
+        INPUT_GESTURE_END => "INPUT_GESTURE_END",
+        GESTURE_BEGIN => "GESTURE_BEGIN",
+        GESTURE_UPDATE => "GESTURE_UPDATE",
+        GESTURE_END => "GESTURE_END",
+        SEAT_KEYBOARD_FOCUS_CHANGED => "SEAT_KEYBOARD_FOCUS_CHANGED",
+        SEAT_CAPABILITIES_CHANGED => "SEAT_CAPABILITIES_CHANGED",
+        INPUT_DEVICE_LOST => "INPUT_DEVICE_LOST",
+        IDLE => "IDLE",
+        RESUMED => "RESUMED",
+        DISPLAY_DESTROYED => "DISPLAY_DESTROYED",
+        SESSION_LOCK_CHANGED => "SESSION_LOCK_CHANGED",
+        LOCK_SURFACE_ADDED => "LOCK_SURFACE_ADDED",
+        SESSION_STATE_CHANGED => "SESSION_STATE_CHANGED",
+        VT_RELEASED => "VT_RELEASED",
+        VT_ACQUIRED => "VT_ACQUIRED",
+        CAPTURE_REQUESTED => "CAPTURE_REQUESTED",
+        OUTPUT_CAPTURED => "OUTPUT_CAPTURED",
+        SCREENSHOT_REQUESTED => "SCREENSHOT_REQUESTED",
+        SCREENSHOT_TAKEN => "SCREENSHOT_TAKEN",
+        REGION_SELECTION_REQUESTED => "REGION_SELECTION_REQUESTED",
+        REGION_SELECTED => "REGION_SELECTED",
+        LAYER_SURFACE_CHANGED => "LAYER_SURFACE_CHANGED",
+        DISPLAY_CHANGED => "DISPLAY_CHANGED",
+        OUTPUT_CONFIGURATION_REQUESTED => "OUTPUT_CONFIGURATION_REQUESTED",
+        OUTPUTS_CONFIGURED => "OUTPUTS_CONFIGURED",
+        FRAME_PRESENTED => "FRAME_PRESENTED",
+        _ => "UNKNOWN",
+    }
+}
 
 // -------------------------------------------------------------------------------------------------
 
@@ -47,24 +206,86 @@ pub enum Perceptron {
     CustomEmpty,
     CustomId(u64),
     VerticalBlank(i32),
-    PageFlip(i32),
+    PageFlip(i32, PresentationTime),
     OutputFound(DrmBundle),
     Command(Command),
     DisplayCreated(OutputInfo),
+    ConfigReloaded,
+    ScreencastFrame(i32, Buffer),
     InputPointerMotion(Vector),
     InputPointerPosition(OptionalPosition),
     InputPointerButton(Button),
     InputPointerAxis(Axis),
     InputPointerPositionReset,
     InputKeyboard(Key),
+    SwitchToggled(Switch, bool),
+    OrientationChanged(Transform),
+    InputPointerAbsolutePosition(AbsolutePosition),
     SurfaceReady(SurfaceId),
     SurfaceDestroyed(SurfaceId),
     SurfaceReconfigured(SurfaceId),
+    PreferredBufferChanged(SurfaceId, f32, Transform),
+    SurfaceCloseRequested(SurfaceId),
+    SurfaceOutputEntered(SurfaceId, i32),
+    SurfaceOutputLeft(SurfaceId, i32),
+    SurfaceTitleChanged(SurfaceId),
+    DecorationModeChanged(SurfaceId),
     CursorSurfaceChange(SurfaceId),
     SurfaceFrame(SurfaceId, Milliseconds),
     PointerFocusChanged(SurfaceId, SurfaceId, Position),
     PointerRelativeMotion(SurfaceId, Position, Milliseconds),
     KeyboardFocusChanged(SurfaceId, SurfaceId),
+    SurfaceKillRequested(SurfaceId),
+    TouchDown(SurfaceId, i32, Position, Milliseconds),
+    TouchMotion(SurfaceId, i32, Position, Milliseconds),
+    TouchUp(SurfaceId, i32, Milliseconds),
+    TouchFrame(SurfaceId),
+    TransferOffered,
+    TransferRequested(String, RawFd),
+    DragStarted,
+    DragFocusChanged(SurfaceId, SurfaceId, Position),
+    DragMotion(SurfaceId, Position, Milliseconds),
+    DragDropped(SurfaceId),
+    DragEnded(bool),
+    DragTransferRequested(String, RawFd),
+    MoveRequested(SurfaceId),
+    PlacementRequested(SurfaceId),
+    DecorationModeRequested(SurfaceId),
+    FullscreenRequested(SurfaceId, bool, Option<i32>),
+    MaximizeRequested(SurfaceId, bool),
+    InitialSizeRequested(SurfaceId),
+    InputTouchDown(TouchPoint),
+    InputTouchMotion(TouchPoint),
+    InputTouchUp(i32, Milliseconds),
+    InputTouchFrame,
+    InputGestureBegin(GestureKind, u32, Milliseconds),
+    InputGestureUpdate(GestureKind, GestureMotion),
+    InputGestureEnd(GestureKind, bool, Milliseconds),
+    GestureBegin(SurfaceId, GestureKind, u32, Milliseconds),
+    GestureUpdate(SurfaceId, GestureKind, GestureMotion),
+    GestureEnd(SurfaceId, GestureKind, bool, Milliseconds),
+    SeatKeyboardFocusChanged(SeatId, SurfaceId, SurfaceId),
+    SeatCapabilitiesChanged(SeatId, SeatCapabilities),
+    InputDeviceLost(EventHandlerId),
+    Idle(u32),
+    Resumed,
+    DisplayDestroyed(i32),
+    SessionLockChanged(bool),
+    LockSurfaceAdded(SurfaceId, i32),
+    SessionStateChanged(bool),
+    VtReleased,
+    VtAcquired,
+    CaptureRequested(i32),
+    OutputCaptured(i32, Buffer),
+    ScreenshotRequested(u32, ScreenshotTarget),
+    ScreenshotTaken(u32, Result<Buffer, String>),
+    RegionSelectionRequested(u32),
+    RegionSelected(u32, Result<Area, String>),
+    LayerSurfaceChanged(SurfaceId),
+    DisplayChanged(OutputInfo),
+    OutputConfigurationRequested(u32, Vec<OutputConfiguration>, bool),
+    OutputsConfigured(u32, Result<(), String>),
+    FramePresented(i32, Vec<SurfaceId>, PresentationTime),
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -76,10 +297,16 @@ impl std::fmt::Display for Perceptron {
             Perceptron::CustomEmpty => write!(f, "CustomEmpty"),
             Perceptron::CustomId(ref id) => write!(f, "CustomId({:?})", id),
             Perceptron::VerticalBlank(ref data) => write!(f, "VerticalBlank({:?})", data),
-            Perceptron::PageFlip(ref data) => write!(f, "PageFlip({:?})", data),
+            Perceptron::PageFlip(ref data, ref time) => {
+                write!(f, "PageFlip({:?}, {:?})", data, time)
+            }
             Perceptron::OutputFound(ref bundle) => write!(f, "OutputFound({:?})", bundle),
             Perceptron::Command(ref command) => write!(f, "Command({:?})", command),
             Perceptron::DisplayCreated(ref info) => write!(f, "DisplayCreated({:?})", info),
+            Perceptron::ConfigReloaded => write!(f, "ConfigReloaded"),
+            Perceptron::ScreencastFrame(ref id, ref frame) => {
+                write!(f, "ScreencastFrame({}, {:?})", id, frame.get_size())
+            }
             Perceptron::InputPointerMotion(ref vector) => {
                 write!(f, "InputPointerMotion({:?})", vector)
             }
@@ -90,11 +317,39 @@ impl std::fmt::Display for Perceptron {
             Perceptron::InputPointerAxis(ref axis) => write!(f, "InputPointerAxis({:?})", axis),
             Perceptron::InputPointerPositionReset => write!(f, "InputPointerPositionReset"),
             Perceptron::InputKeyboard(ref key) => write!(f, "InputKeyboard({:?})", key),
+            Perceptron::SwitchToggled(ref switch, ref state) => {
+                write!(f, "SwitchToggled({:?}, {})", switch, state)
+            }
+            Perceptron::OrientationChanged(ref transform) => {
+                write!(f, "OrientationChanged({:?})", transform)
+            }
+            Perceptron::InputPointerAbsolutePosition(ref pos) => {
+                write!(f, "InputPointerAbsolutePosition({:?})", pos)
+            }
 
             Perceptron::SurfaceReady(ref sid) => write!(f, "SurfaceReady({})", sid),
             Perceptron::SurfaceDestroyed(ref sid) => write!(f, "SurfaceDestroyed({})", sid),
             Perceptron::SurfaceReconfigured(ref sid) => write!(f, "SurfaceReconfigured({})", sid),
+            Perceptron::PreferredBufferChanged(ref sid, ref scale, ref transform) => {
+                write!(f, "PreferredBufferChanged({}, {}, {:?})", sid, scale, transform)
+            }
             Perceptron::CursorSurfaceChange(ref sid) => write!(f, "CursorSurfaceChange({})", sid),
+            Perceptron::SurfaceOutputEntered(ref sid, ref output_id) => {
+                write!(f, "SurfaceOutputEntered({}, {})", sid, output_id)
+            }
+            Perceptron::SurfaceOutputLeft(ref sid, ref output_id) => {
+                write!(f, "SurfaceOutputLeft({}, {})", sid, output_id)
+            }
+            Perceptron::SurfaceCloseRequested(ref sid) => {
+                write!(f, "SurfaceCloseRequested({})", sid)
+            }
+            Perceptron::SurfaceKillRequested(ref sid) => {
+                write!(f, "SurfaceKillRequested({})", sid)
+            }
+            Perceptron::SurfaceTitleChanged(ref sid) => write!(f, "SurfaceTitleChanged({})", sid),
+            Perceptron::DecorationModeChanged(ref sid) => {
+                write!(f, "DecorationModeChanged({})", sid)
+            }
 
             Perceptron::SurfaceFrame(sid, time) => {
                 write!(f, "SurfaceFrame(sid: {}, milliseconds: {})", sid, time.get_value())
@@ -108,6 +363,134 @@ impl std::fmt::Display for Perceptron {
             Perceptron::KeyboardFocusChanged(ref old_sid, ref new_sid) => {
                 write!(f, "KeyboardFocusChanged({:?}, {:?})", old_sid, new_sid)
             }
+            Perceptron::TouchDown(ref sid, ref id, ref pos, ref time) => {
+                write!(f, "TouchDown({:?}, {}, {:?}, {:?})", sid, id, pos, time.get_value())
+            }
+            Perceptron::TouchMotion(ref sid, ref id, ref pos, ref time) => {
+                write!(f, "TouchMotion({:?}, {}, {:?}, {:?})", sid, id, pos, time.get_value())
+            }
+            Perceptron::TouchUp(ref sid, ref id, ref time) => {
+                write!(f, "TouchUp({:?}, {}, {:?})", sid, id, time.get_value())
+            }
+            Perceptron::TouchFrame(ref sid) => write!(f, "TouchFrame({:?})", sid),
+
+            Perceptron::TransferOffered => write!(f, "TransferOffered"),
+            Perceptron::TransferRequested(ref mime_type, ref fd) => {
+                write!(f, "TransferRequested({:?}, {:?})", mime_type, fd)
+            }
+            Perceptron::DragStarted => write!(f, "DragStarted"),
+            Perceptron::DragFocusChanged(ref old_sid, ref new_sid, ref pos) => {
+                write!(f, "DragFocusChanged(old: {:?}, new: {:?}, {:?})", old_sid, new_sid, pos)
+            }
+            Perceptron::DragMotion(ref sid, ref pos, ref time) => {
+                write!(f, "DragMotion({:?}, {:?}, {:?})", sid, pos, time.get_value())
+            }
+            Perceptron::DragDropped(ref sid) => write!(f, "DragDropped({})", sid),
+            Perceptron::DragEnded(ref performed) => write!(f, "DragEnded({})", performed),
+            Perceptron::DragTransferRequested(ref mime_type, ref fd) => {
+                write!(f, "DragTransferRequested({:?}, {:?})", mime_type, fd)
+            }
+            Perceptron::MoveRequested(ref sid) => write!(f, "MoveRequested({})", sid),
+            Perceptron::PlacementRequested(ref sid) => write!(f, "PlacementRequested({})", sid),
+            Perceptron::DecorationModeRequested(ref sid) => {
+                write!(f, "DecorationModeRequested({})", sid)
+            }
+            Perceptron::FullscreenRequested(ref sid, ref fullscreen, ref output_id) => {
+                write!(f, "FullscreenRequested({}, {}, {:?})", sid, fullscreen, output_id)
+            }
+            Perceptron::MaximizeRequested(ref sid, ref maximized) => {
+                write!(f, "MaximizeRequested({}, {})", sid, maximized)
+            }
+            Perceptron::InitialSizeRequested(ref sid) => {
+                write!(f, "InitialSizeRequested({})", sid)
+            }
+            Perceptron::InputTouchDown(ref touch) => write!(f, "InputTouchDown({:?})", touch),
+            Perceptron::InputTouchMotion(ref touch) => write!(f, "InputTouchMotion({:?})", touch),
+            Perceptron::InputTouchUp(ref id, ref time) => {
+                write!(f, "InputTouchUp({}, {:?})", id, time.get_value())
+            }
+            Perceptron::InputTouchFrame => write!(f, "InputTouchFrame"),
+            Perceptron::InputGestureBegin(ref kind, ref fingers, ref time) => {
+                write!(f, "InputGestureBegin({:?}, {}, {:?})", kind, fingers, time.get_value())
+            }
+            Perceptron::InputGestureUpdate(ref kind, ref motion) => {
+                write!(f, "InputGestureUpdate({:?}, {:?})", kind, motion)
+            }
+            Perceptron::InputGestureEnd(ref kind, ref cancelled, ref time) => {
+                write!(f, "InputGestureEnd({:?}, {}, {:?})", kind, cancelled, time.get_value())
+            }
+            Perceptron::GestureBegin(ref sid, ref kind, ref fingers, ref time) => {
+                write!(f,
+                       "GestureBegin({:?}, {:?}, {}, {:?})",
+                       sid,
+                       kind,
+                       fingers,
+                       time.get_value())
+            }
+            Perceptron::GestureUpdate(ref sid, ref kind, ref motion) => {
+                write!(f, "GestureUpdate({:?}, {:?}, {:?})", sid, kind, motion)
+            }
+            Perceptron::GestureEnd(ref sid, ref kind, ref cancelled, ref time) => {
+                write!(f,
+                       "GestureEnd({:?}, {:?}, {}, {:?})",
+                       sid,
+                       kind,
+                       cancelled,
+                       time.get_value())
+            }
+            Perceptron::SeatKeyboardFocusChanged(ref seat, ref old_sid, ref new_sid) => {
+                write!(f, "SeatKeyboardFocusChanged({:?}, {:?}, {:?})", seat, old_sid, new_sid)
+            }
+            Perceptron::SeatCapabilitiesChanged(ref seat, ref capabilities) => {
+                write!(f, "SeatCapabilitiesChanged({:?}, {:?})", seat, capabilities)
+            }
+            Perceptron::InputDeviceLost(ref id) => write!(f, "InputDeviceLost({})", id),
+            Perceptron::Idle(ref timeout) => write!(f, "Idle({})", timeout),
+            Perceptron::Resumed => write!(f, "Resumed"),
+            Perceptron::DisplayDestroyed(ref id) => write!(f, "DisplayDestroyed({})", id),
+            Perceptron::SessionLockChanged(ref locked) => {
+                write!(f, "SessionLockChanged({})", locked)
+            }
+            Perceptron::LockSurfaceAdded(ref sid, ref output_id) => {
+                write!(f, "LockSurfaceAdded({:?}, {})", sid, output_id)
+            }
+            Perceptron::SessionStateChanged(ref active) => {
+                write!(f, "SessionStateChanged({})", active)
+            }
+            Perceptron::VtReleased => write!(f, "VtReleased"),
+            Perceptron::VtAcquired => write!(f, "VtAcquired"),
+            Perceptron::CaptureRequested(ref output_id) => {
+                write!(f, "CaptureRequested({})", output_id)
+            }
+            Perceptron::OutputCaptured(ref output_id, ref frame) => {
+                write!(f, "OutputCaptured({}, {:?})", output_id, frame.get_size())
+            }
+            Perceptron::ScreenshotRequested(ref id, ref target) => {
+                write!(f, "ScreenshotRequested({}, {:?})", id, target)
+            }
+            Perceptron::ScreenshotTaken(ref id, ref result) => {
+                match *result {
+                    Ok(ref image) => write!(f, "ScreenshotTaken({}, {:?})", id, image.get_size()),
+                    Err(ref err) => write!(f, "ScreenshotTaken({}, {:?})", id, err),
+                }
+            }
+            Perceptron::RegionSelectionRequested(ref id) => {
+                write!(f, "RegionSelectionRequested({})", id)
+            }
+            Perceptron::RegionSelected(ref id, ref result) => {
+                write!(f, "RegionSelected({}, {:?})", id, result)
+            }
+            Perceptron::LayerSurfaceChanged(ref sid) => write!(f, "LayerSurfaceChanged({})", sid),
+            Perceptron::DisplayChanged(ref info) => write!(f, "DisplayChanged({:?})", info),
+            Perceptron::OutputConfigurationRequested(ref id, ref configurations, ref test) => {
+                write!(f, "OutputConfigurationRequested({}, {:?}, {})", id, configurations, test)
+            }
+            Perceptron::OutputsConfigured(ref id, ref result) => {
+                write!(f, "OutputsConfigured({}, {:?})", id, result)
+            }
+            Perceptron::FramePresented(ref output_id, ref sids, ref time) => {
+                write!(f, "FramePresented({}, {:?}, {:?})", output_id, sids, time)
+            }
         }
     }
 }
@@ -0,0 +1,130 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module provides minimal PNG encoder used for saving screenshots.
+//!
+//! Image data is stored in uncompressed deflate blocks. Files are bigger than necessary, but no
+//! compression library is needed and encoding is fast enough to not stall redrawing.
+
+// -------------------------------------------------------------------------------------------------
+
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use errors::Illusion;
+use memory::{Buffer, Pixmap};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Signature every PNG file starts with.
+const SIGNATURE: [u8; 8] = [137, 80, 78, 71, 13, 10, 26, 10];
+
+/// Maximal length of data in one uncompressed deflate block.
+const MAX_BLOCK_LEN: usize = 0xFFFF;
+
+/// Number of bytes per pixel in encoded image (RGB).
+const BYTES_PER_PIXEL: usize = 3;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Encodes given buffer to PNG. Pixels of the buffer are expected to have red, green, blue and
+/// alpha bytes in this order. Alpha is dropped as screenshots are opaque.
+pub fn encode(buffer: &Buffer) -> Vec<u8> {
+    let width = buffer.get_width();
+    let height = buffer.get_height();
+
+    let mut header = Vec::with_capacity(13);
+    push_u32(&mut header, width as u32);
+    push_u32(&mut header, height as u32);
+    header.extend_from_slice(&[8, 2, 0, 0, 0]); // 8 bits per channel, RGB, no interlacing
+
+    let mut raw = Vec::with_capacity(height * (1 + BYTES_PER_PIXEL * width));
+    let data = buffer.as_slice();
+    for y in 0..height {
+        raw.push(0); // no filtering
+        let row = &data[y * buffer.get_stride()..];
+        for x in 0..width {
+            raw.extend_from_slice(&row[4 * x..4 * x + 3]);
+        }
+    }
+
+    let mut png = Vec::new();
+    png.extend_from_slice(&SIGNATURE);
+    push_chunk(&mut png, b"IHDR", &header);
+    push_chunk(&mut png, b"IDAT", &deflate_stored(&raw));
+    push_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Encodes given buffer to PNG and writes it to file with given path.
+pub fn write(path: &Path, buffer: &Buffer) -> Result<(), Illusion> {
+    let mut file = File::create(path)?;
+    file.write_all(&encode(buffer))?;
+    Ok(())
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Appends big-endian representation of given number.
+fn push_u32(data: &mut Vec<u8>, value: u32) {
+    let bytes = [(value >> 24) as u8, (value >> 16) as u8, (value >> 8) as u8, value as u8];
+    data.extend_from_slice(&bytes);
+}
+
+/// Appends chunk with given type and data followed by its checksum.
+fn push_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    push_u32(png, data.len() as u32);
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    push_u32(png, crc);
+}
+
+/// Wraps given data in zlib stream made of uncompressed deflate blocks.
+fn deflate_stored(data: &[u8]) -> Vec<u8> {
+    let mut stream = Vec::with_capacity(data.len() + 5 * (data.len() / MAX_BLOCK_LEN + 1) + 6);
+    stream.extend_from_slice(&[0x78, 0x01]);
+
+    let blocks: Vec<&[u8]> = data.chunks(MAX_BLOCK_LEN).collect();
+    if blocks.is_empty() {
+        stream.extend_from_slice(&[0x01, 0x00, 0x00, 0xFF, 0xFF]);
+    }
+    for (i, block) in blocks.iter().enumerate() {
+        let last = if i + 1 == blocks.len() { 1 } else { 0 };
+        let len = block.len() as u16;
+        stream.push(last);
+        stream.extend_from_slice(&[len as u8, (len >> 8) as u8, !len as u8, (!len >> 8) as u8]);
+        stream.extend_from_slice(block);
+    }
+
+    push_u32(&mut stream, adler32(data));
+    stream
+}
+
+/// Calculates CRC-32 checksum used in chunks.
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for byte in data.iter() {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+/// Calculates Adler-32 checksum used in zlib stream.
+pub fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in data.iter() {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,245 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains logic of placing pop-ups relative to their parents.
+//!
+//! Semantics follow `xdg_positioner`: pop-up is placed at anchor point on edge of anchor rectangle
+//! and extends in direction given by gravity. If the pop-up does not fit in given bounds it may be
+//! flipped to opposite side of anchor rectangle, slid along the bounds or resized.
+//!
+//! The same rules are used for input method pop-ups (e.g. candidate lists) which are anchored to
+//! text cursor rectangle of the surface having text input focus.
+
+// -------------------------------------------------------------------------------------------------
+
+use std;
+
+use defs::{Area, Position, Size, Vector};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Edges of anchor rectangle (for anchor) or directions (for gravity). No edge means center.
+pub mod edges {
+    bitflags!(
+        pub flags Edges: u32 {
+            const NONE = 0b0000,
+            const TOP = 0b0001,
+            const BOTTOM = 0b0010,
+            const LEFT = 0b0100,
+            const RIGHT = 0b1000,
+        }
+    );
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Adjustments allowed when pop-up does not fit in bounds.
+pub mod constraint_adjustment {
+    bitflags!(
+        pub flags ConstraintAdjustment: u32 {
+            const NONE = 0b000000,
+            const SLIDE_X = 0b000001,
+            const SLIDE_Y = 0b000010,
+            const FLIP_X = 0b000100,
+            const FLIP_Y = 0b001000,
+            const RESIZE_X = 0b010000,
+            const RESIZE_Y = 0b100000,
+        }
+    );
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Rules for placing pop-up. Coordinates are relative to parent surface.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Positioner {
+    /// Size of the pop-up.
+    pub size: Size,
+
+    /// Rectangle the pop-up is anchored to.
+    pub anchor_rect: Area,
+
+    /// Edges of anchor rectangle defining anchor point.
+    pub anchor: edges::Edges,
+
+    /// Direction in which pop-up extends from anchor point.
+    pub gravity: edges::Edges,
+
+    /// Adjustments allowed when pop-up does not fit in bounds.
+    pub constraint_adjustment: constraint_adjustment::ConstraintAdjustment,
+
+    /// Offset added to position of the pop-up.
+    pub offset: Vector,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Positioner {
+    /// Constructs new `Positioner` centering empty pop-up on empty anchor rectangle.
+    pub fn new() -> Self {
+        Positioner {
+            size: Size::default(),
+            anchor_rect: Area::default(),
+            anchor: edges::NONE,
+            gravity: edges::NONE,
+            constraint_adjustment: constraint_adjustment::NONE,
+            offset: Vector::default(),
+        }
+    }
+
+    /// Constructs new `Positioner` for input method pop-up of given size. The pop-up is placed
+    /// below the text cursor rectangle starting at its left edge. If it does not fit on screen it
+    /// is flipped above the cursor and slid horizontally, so the cursor itself is never covered.
+    pub fn for_text_cursor(size: Size, cursor_rect: Area) -> Self {
+        Positioner {
+            size: size,
+            anchor_rect: cursor_rect,
+            anchor: edges::BOTTOM | edges::LEFT,
+            gravity: edges::BOTTOM | edges::RIGHT,
+            constraint_adjustment: constraint_adjustment::FLIP_Y |
+                                   constraint_adjustment::SLIDE_X |
+                                   constraint_adjustment::SLIDE_Y,
+            offset: Vector::default(),
+        }
+    }
+
+    /// Checks if size of the pop-up was set. Incomplete positioner can not be used.
+    pub fn is_complete(&self) -> bool {
+        !self.size.is_zero()
+    }
+
+    /// Returns area of the pop-up placed without taking any constraints into account.
+    pub fn get_area(&self) -> Area {
+        Area::new(self.calculate_position(self.anchor, self.gravity), self.size)
+    }
+
+    /// Returns area of the pop-up adjusted to fit in given bounds as far as allowed constraint
+    /// adjustments let. Each axis is adjusted independently by first trying to flip, then slide
+    /// and then resize the pop-up.
+    pub fn get_constrained_area(&self, bounds: Area) -> Area {
+        let mut area = self.get_area();
+        let adjustment = self.constraint_adjustment;
+
+        // Adjust horizontally
+        let (start, end) = (bounds.pos.x, bounds.pos.x + bounds.size.width as isize);
+        if !fits(area.pos.x, area.size.width, start, end) &&
+           adjustment.contains(constraint_adjustment::FLIP_X) {
+            let anchor = flip(self.anchor, edges::LEFT, edges::RIGHT);
+            let gravity = flip(self.gravity, edges::LEFT, edges::RIGHT);
+            let x = self.calculate_position(anchor, gravity).x;
+            if fits(x, area.size.width, start, end) {
+                area.pos.x = x;
+            }
+        }
+        if !fits(area.pos.x, area.size.width, start, end) &&
+           adjustment.contains(constraint_adjustment::SLIDE_X) {
+            area.pos.x = slide(area.pos.x, area.size.width, start, end);
+        }
+        if !fits(area.pos.x, area.size.width, start, end) &&
+           adjustment.contains(constraint_adjustment::RESIZE_X) {
+            let (x, width) = clip(area.pos.x, area.size.width, start, end);
+            area.pos.x = x;
+            area.size.width = width;
+        }
+
+        // Adjust vertically
+        let (start, end) = (bounds.pos.y, bounds.pos.y + bounds.size.height as isize);
+        if !fits(area.pos.y, area.size.height, start, end) &&
+           adjustment.contains(constraint_adjustment::FLIP_Y) {
+            let anchor = flip(self.anchor, edges::TOP, edges::BOTTOM);
+            let gravity = flip(self.gravity, edges::TOP, edges::BOTTOM);
+            let y = self.calculate_position(anchor, gravity).y;
+            if fits(y, area.size.height, start, end) {
+                area.pos.y = y;
+            }
+        }
+        if !fits(area.pos.y, area.size.height, start, end) &&
+           adjustment.contains(constraint_adjustment::SLIDE_Y) {
+            area.pos.y = slide(area.pos.y, area.size.height, start, end);
+        }
+        if !fits(area.pos.y, area.size.height, start, end) &&
+           adjustment.contains(constraint_adjustment::RESIZE_Y) {
+            let (y, height) = clip(area.pos.y, area.size.height, start, end);
+            area.pos.y = y;
+            area.size.height = height;
+        }
+
+        area
+    }
+
+    /// Calculates position of the pop-up for given anchor and gravity.
+    fn calculate_position(&self, anchor: edges::Edges, gravity: edges::Edges) -> Position {
+        let rect = self.anchor_rect;
+        let (width, height) = (self.size.width as isize, self.size.height as isize);
+
+        let x = if anchor.contains(edges::LEFT) {
+            rect.pos.x
+        } else if anchor.contains(edges::RIGHT) {
+            rect.pos.x + rect.size.width as isize
+        } else {
+            rect.pos.x + rect.size.width as isize / 2
+        };
+
+        let y = if anchor.contains(edges::TOP) {
+            rect.pos.y
+        } else if anchor.contains(edges::BOTTOM) {
+            rect.pos.y + rect.size.height as isize
+        } else {
+            rect.pos.y + rect.size.height as isize / 2
+        };
+
+        let x = if gravity.contains(edges::LEFT) {
+            x - width
+        } else if gravity.contains(edges::RIGHT) {
+            x
+        } else {
+            x - width / 2
+        };
+
+        let y = if gravity.contains(edges::TOP) {
+            y - height
+        } else if gravity.contains(edges::BOTTOM) {
+            y
+        } else {
+            y - height / 2
+        };
+
+        Position::new(x, y) + self.offset
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Checks if segment of given position and length lays between `start` and `end`.
+fn fits(position: isize, length: usize, start: isize, end: isize) -> bool {
+    (start <= position) && (position + length as isize <= end)
+}
+
+/// Swaps given opposite edges if exactly one of them is set.
+fn flip(value: edges::Edges, first: edges::Edges, second: edges::Edges) -> edges::Edges {
+    if value.contains(first) != value.contains(second) {
+        value ^ (first | second)
+    } else {
+        value
+    }
+}
+
+/// Moves segment of given position and length to lay between `start` and `end`. If the segment is
+/// longer than available space it is aligned to `start`.
+fn slide(position: isize, length: usize, start: isize, end: isize) -> isize {
+    std::cmp::max(std::cmp::min(position, end - length as isize), start)
+}
+
+/// Returns part of segment of given position and length laying between `start` and `end`. Segment
+/// not overlapping the space at all is left untouched.
+fn clip(position: isize, length: usize, start: isize, end: isize) -> (isize, usize) {
+    let clipped_start = std::cmp::max(position, start);
+    let clipped_end = std::cmp::min(position + length as isize, end);
+    if clipped_start < clipped_end {
+        (clipped_start, (clipped_end - clipped_start) as usize)
+    } else {
+        (position, length)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -7,6 +7,7 @@
 
 use std::sync::{Arc, Mutex};
 
+use config::KeyRepeatConfig;
 use keymap;
 
 // -------------------------------------------------------------------------------------------------
@@ -15,6 +16,7 @@ use keymap;
 #[derive(Clone)]
 struct InnerSettings {
     pub keymap: keymap::Settings,
+    pub key_repeat: KeyRepeatConfig,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -29,8 +31,13 @@ pub struct Settings {
 
 impl Settings {
     /// `Settings` constructor.
-    pub fn new(keymap: keymap::Settings) -> Self {
-        Settings { inner: Arc::new(Mutex::new(InnerSettings { keymap: keymap })) }
+    pub fn new(keymap: keymap::Settings, key_repeat: KeyRepeatConfig) -> Self {
+        Settings {
+            inner: Arc::new(Mutex::new(InnerSettings {
+                                           keymap: keymap,
+                                           key_repeat: key_repeat,
+                                       })),
+        }
     }
 
     /// Get key map related settings.
@@ -38,6 +45,12 @@ impl Settings {
         let mine = self.inner.lock().unwrap();
         mine.keymap.clone()
     }
+
+    /// Get key repeat rate and delay advertised to clients.
+    pub fn get_key_repeat(&self) -> KeyRepeatConfig {
+        let mine = self.inner.lock().unwrap();
+        mine.key_repeat
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -0,0 +1,332 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Typed counterparts of signals defined in `perceptron`.
+//!
+//! Every signal ID from `perceptron` has here a type implementing `dharma::Signal` which binds the
+//! ID with data carried by corresponding `Perceptron` variant. Using these types with
+//! `Signaler::subscribe_signal` and `Signaler::emit_signal` makes mismatches between signal IDs and
+//! `Perceptron` variants compilation errors:
+//!
+//! ```ignore
+//! signaler.subscribe_signal::<signals::SurfaceReady>(&receiver);
+//! signaler.emit_signal::<signals::SurfaceFrame>((sid, time));
+//! ```
+//!
+//! Signals with single value carry it directly, signals with more values carry a tuple and signals
+//! without values carry `()`. Received packages can be decoded with `Signal::unwrap`.
+
+// -------------------------------------------------------------------------------------------------
+
+use std::os::unix::io::RawFd;
+
+use dharma::{EventHandlerId, Signal, SignalId};
+
+use defs;
+use enums::{GestureKind, Switch, Transform};
+use timing::{Milliseconds, PresentationTime};
+use control::ScreenshotTarget;
+use defs::{Area, DrmBundle, OutputConfiguration, OutputInfo, SeatId, SurfaceId};
+use memory::Buffer;
+use defs::{AbsolutePosition, Axis, Position, OptionalPosition, Vector, Button, Key};
+use defs::{GestureMotion, TouchPoint};
+use defs::seat_capability::SeatCapabilities;
+use perceptron::{self, Perceptron};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Defines types implementing `Signal` for given `Perceptron` variants.
+///
+/// Each entry has form `Variant = SIGNAL_ID;` for variants without values, `Variant = ID(Type);`
+/// for variants with single value or `Variant = ID(name: Type, ...);` for variants with more
+/// values.
+macro_rules! signals {
+    () => {};
+
+    ($name:ident = $id:ident($($field:ident: $ty:ty),+); $($rest:tt)*) => {
+        pub struct $name;
+
+        impl Signal<Perceptron> for $name {
+            type Payload = ($($ty),+);
+
+            fn id() -> SignalId {
+                perceptron::$id
+            }
+
+            fn wrap(payload: Self::Payload) -> Perceptron {
+                let ($($field),+) = payload;
+                Perceptron::$name($($field),+)
+            }
+
+            fn unwrap(package: Perceptron) -> Option<Self::Payload> {
+                match package {
+                    Perceptron::$name($($field),+) => Some(($($field),+)),
+                    _ => None,
+                }
+            }
+        }
+
+        signals! { $($rest)* }
+    };
+
+    ($name:ident = $id:ident($ty:ty); $($rest:tt)*) => {
+        pub struct $name;
+
+        impl Signal<Perceptron> for $name {
+            type Payload = $ty;
+
+            fn id() -> SignalId {
+                perceptron::$id
+            }
+
+            fn wrap(payload: Self::Payload) -> Perceptron {
+                Perceptron::$name(payload)
+            }
+
+            fn unwrap(package: Perceptron) -> Option<Self::Payload> {
+                match package {
+                    Perceptron::$name(payload) => Some(payload),
+                    _ => None,
+                }
+            }
+        }
+
+        signals! { $($rest)* }
+    };
+
+    ($name:ident = $id:ident; $($rest:tt)*) => {
+        pub struct $name;
+
+        impl Signal<Perceptron> for $name {
+            type Payload = ();
+
+            fn id() -> SignalId {
+                perceptron::$id
+            }
+
+            fn wrap(_payload: Self::Payload) -> Perceptron {
+                Perceptron::$name
+            }
+
+            fn unwrap(package: Perceptron) -> Option<Self::Payload> {
+                match package {
+                    Perceptron::$name => Some(()),
+                    _ => None,
+                }
+            }
+        }
+
+        signals! { $($rest)* }
+    };
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Signals related to outputs, commands and configuration.
+signals! {
+    Notify = NOTIFY;
+    VerticalBlank = VERTICAL_BLANK(i32);
+    PageFlip = PAGE_FLIP(output_id: i32, time: PresentationTime);
+    OutputFound = OUTPUT_FOUND(DrmBundle);
+    Command = COMMAND(defs::Command);
+    DisplayCreated = DISPLAY_CREATED(OutputInfo);
+    DisplayDestroyed = DISPLAY_DESTROYED(i32);
+    DisplayChanged = DISPLAY_CHANGED(OutputInfo);
+    ConfigReloaded = CONFIG_RELOADED;
+    ScreencastFrame = SCREENCAST_FRAME(output_id: i32, buffer: Buffer);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Signals related to input devices.
+signals! {
+    InputPointerMotion = INPUT_POINTER_MOTION(Vector);
+    InputPointerPosition = INPUT_POINTER_POSITION(OptionalPosition);
+    InputPointerButton = INPUT_POINTER_BUTTON(Button);
+    InputPointerAxis = INPUT_POINTER_AXIS(Axis);
+    InputPointerPositionReset = INPUT_POINTER_POSITION_RESET;
+    InputKeyboard = INPUT_KEYBOARD(Key);
+    SwitchToggled = SWITCH_TOGGLED(switch: Switch, on: bool);
+    OrientationChanged = ORIENTATION_CHANGED(Transform);
+    InputPointerAbsolutePosition = INPUT_POINTER_ABSOLUTE_POSITION(AbsolutePosition);
+    InputDeviceLost = INPUT_DEVICE_LOST(EventHandlerId);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Signals related to surfaces.
+signals! {
+    SurfaceReady = SURFACE_READY(SurfaceId);
+    SurfaceDestroyed = SURFACE_DESTROYED(SurfaceId);
+    SurfaceReconfigured = SURFACE_RECONFIGURED(SurfaceId);
+    PreferredBufferChanged = PREFERRED_BUFFER_CHANGED(sid: SurfaceId,
+                                                      scale: f32,
+                                                      transform: Transform);
+    SurfaceCloseRequested = SURFACE_CLOSE_REQUESTED(SurfaceId);
+    CursorSurfaceChange = CURSOR_SURFACE_CHANGE(SurfaceId);
+    SurfaceOutputEntered = SURFACE_OUTPUT_ENTERED(sid: SurfaceId, output_id: i32);
+    SurfaceOutputLeft = SURFACE_OUTPUT_LEFT(sid: SurfaceId, output_id: i32);
+    SurfaceTitleChanged = SURFACE_TITLE_CHANGED(SurfaceId);
+    DecorationModeChanged = DECORATION_MODE_CHANGED(SurfaceId);
+    SurfaceFrame = SURFACE_FRAME(sid: SurfaceId, time: Milliseconds);
+    PointerFocusChanged = POINTER_FOCUS_CHANGED(old_sid: SurfaceId,
+                                                new_sid: SurfaceId,
+                                                position: Position);
+    PointerRelativeMotion = POINTER_RELATIVE_MOTION(sid: SurfaceId,
+                                                    position: Position,
+                                                    time: Milliseconds);
+    KeyboardFocusChanged = KEYBOARD_FOCUS_CHANGED(old_sid: SurfaceId, new_sid: SurfaceId);
+    SurfaceKillRequested = SURFACE_KILL_REQUESTED(SurfaceId);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Signals related to touch, data transfer and drag-and-drop.
+signals! {
+    TouchDown = TOUCH_DOWN(sid: SurfaceId, id: i32, position: Position, time: Milliseconds);
+    TouchMotion = TOUCH_MOTION(sid: SurfaceId, id: i32, position: Position, time: Milliseconds);
+    TouchUp = TOUCH_UP(sid: SurfaceId, id: i32, time: Milliseconds);
+    TouchFrame = TOUCH_FRAME(SurfaceId);
+    TransferOffered = TRANSFER_OFFERED;
+    TransferRequested = TRANSFER_REQUESTED(mime_type: String, fd: RawFd);
+    DragStarted = DRAG_STARTED;
+    DragFocusChanged = DRAG_FOCUS_CHANGED(old_sid: SurfaceId,
+                                          new_sid: SurfaceId,
+                                          position: Position);
+    DragMotion = DRAG_MOTION(sid: SurfaceId, position: Position, time: Milliseconds);
+    DragDropped = DRAG_DROPPED(SurfaceId);
+    DragEnded = DRAG_ENDED(bool);
+    DragTransferRequested = DRAG_TRANSFER_REQUESTED(mime_type: String, fd: RawFd);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Signals requesting changes of surface state.
+signals! {
+    MoveRequested = MOVE_REQUESTED(SurfaceId);
+    PlacementRequested = PLACEMENT_REQUESTED(SurfaceId);
+    DecorationModeRequested = DECORATION_MODE_REQUESTED(SurfaceId);
+    FullscreenRequested = FULLSCREEN_REQUESTED(sid: SurfaceId,
+                                               enable: bool,
+                                               output_id: Option<i32>);
+    MaximizeRequested = MAXIMIZE_REQUESTED(sid: SurfaceId, enable: bool);
+    InitialSizeRequested = INITIAL_SIZE_REQUESTED(SurfaceId);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Signals related to touch screens and touchpads.
+signals! {
+    InputTouchDown = INPUT_TOUCH_DOWN(TouchPoint);
+    InputTouchMotion = INPUT_TOUCH_MOTION(TouchPoint);
+    InputTouchUp = INPUT_TOUCH_UP(id: i32, time: Milliseconds);
+    InputTouchFrame = INPUT_TOUCH_FRAME;
+    InputGestureBegin = INPUT_GESTURE_BEGIN(kind: GestureKind, fingers: u32, time: Milliseconds);
+    InputGestureUpdate = INPUT_GESTURE_UPDATE(kind: GestureKind, motion: GestureMotion);
+    InputGestureEnd = INPUT_GESTURE_END(kind: GestureKind, cancelled: bool, time: Milliseconds);
+    GestureBegin = GESTURE_BEGIN(sid: SurfaceId,
+                                 kind: GestureKind,
+                                 fingers: u32,
+                                 time: Milliseconds);
+    GestureUpdate = GESTURE_UPDATE(sid: SurfaceId, kind: GestureKind, motion: GestureMotion);
+    GestureEnd = GESTURE_END(sid: SurfaceId,
+                             kind: GestureKind,
+                             cancelled: bool,
+                             time: Milliseconds);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Signals related to seats.
+signals! {
+    SeatKeyboardFocusChanged = SEAT_KEYBOARD_FOCUS_CHANGED(seat: SeatId,
+                                                           old_sid: SurfaceId,
+                                                           new_sid: SurfaceId);
+    SeatCapabilitiesChanged = SEAT_CAPABILITIES_CHANGED(seat: SeatId,
+                                                        capabilities: SeatCapabilities);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Signals related to user activity. `Idle` carries timeout in milliseconds which elapsed.
+signals! {
+    Idle = IDLE(u32);
+    Resumed = RESUMED;
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Signals related to locking the session. `LockSurfaceAdded` carries ID of output the surface
+// covers.
+signals! {
+    SessionLockChanged = SESSION_LOCK_CHANGED(bool);
+    LockSurfaceAdded = LOCK_SURFACE_ADDED(sid: SurfaceId, output_id: i32);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Signals related to `logind` session. `SessionStateChanged` carries information if the session
+// became active (e.g. after switching back to its virtual terminal). `VtReleased` is emitted after
+// devices were given up on switching to other virtual terminal and `VtAcquired` after they were
+// taken back.
+signals! {
+    SessionStateChanged = SESSION_STATE_CHANGED(bool);
+    VtReleased = VT_RELEASED;
+    VtAcquired = VT_ACQUIRED;
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Signals related to capturing contents of outputs on request of clients. `OutputCaptured` carries
+// whole frame of the output in the same format as `ScreencastFrame`.
+signals! {
+    CaptureRequested = CAPTURE_REQUESTED(output_id: i32);
+    OutputCaptured = OUTPUT_CAPTURED(output_id: i32, buffer: Buffer);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Signals related to screenshots requested over control socket. Requests are identified by number
+// assigned by the socket handler. `ScreenshotTaken` carries cropped image or description of error.
+signals! {
+    ScreenshotRequested = SCREENSHOT_REQUESTED(id: u32, target: ScreenshotTarget);
+    ScreenshotTaken = SCREENSHOT_TAKEN(id: u32, result: Result<Buffer, String>);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Signals related to interactive selection of screen region requested over control socket.
+// `RegionSelected` carries selected region in global coordinates or description of error (e.g. when
+// user cancelled the selection).
+signals! {
+    RegionSelectionRequested = REGION_SELECTION_REQUESTED(id: u32);
+    RegionSelected = REGION_SELECTED(id: u32, result: Result<Area, String>);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Signal emitted when layer surface was added, removed or its state changed.
+signals! {
+    LayerSurfaceChanged = LAYER_SURFACE_CHANGED(SurfaceId);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Signals related to configuration of outputs requested by clients. Requests are identified by
+// number assigned by `Coordinator`. `test` tells if configuration should be only validated.
+// `OutputsConfigured` carries description of error if configuration was rejected.
+signals! {
+    OutputConfigurationRequested =
+        OUTPUT_CONFIGURATION_REQUESTED(id: u32, outputs: Vec<OutputConfiguration>, test: bool);
+    OutputsConfigured = OUTPUTS_CONFIGURED(id: u32, result: Result<(), String>);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Signals related to presentation of frames. `FramePresented` lists surfaces drawn in the frame
+// which was displayed on output at given time.
+signals! {
+    FramePresented = FRAME_PRESENTED(output_id: i32, sids: Vec<SurfaceId>, time: PresentationTime);
+}
+
+// -------------------------------------------------------------------------------------------------
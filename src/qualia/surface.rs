@@ -5,17 +5,47 @@
 
 // -------------------------------------------------------------------------------------------------
 
-use memory::{MemoryView, Pixmap};
-use defs::{Position, Size, Vector};
+use colorimetry::ColorDescription;
+use memory::{AlphaMode, HwImage, MemoryView, Pixmap};
+use defs::{Area, Margins, Position, Size, Vector};
+use enums::{DecorationMode, SizeMismatchPolicy, Transform};
+use positioner::Positioner;
 pub use defs::{SurfaceId, SurfaceIdType};
 
 // -------------------------------------------------------------------------------------------------
 
+/// Maximal number of damage regions kept for surface. When exceeded the regions are merged.
+const MAX_DAMAGE_REGIONS: usize = 32;
+
+// -------------------------------------------------------------------------------------------------
+
 /// This structure defines how the surface should be drawn.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct SurfaceContext {
     pub id: SurfaceId,
     pub pos: Position,
+
+    /// Regions of the surface (in surface coordinates) changed since it was last drawn.
+    pub damage: Vec<Area>,
+
+    /// Number of commits of the surface contents. Renderer uploads contents only if they were
+    /// committed since last upload.
+    pub generation: u64,
+
+    /// Generation of contents `damage` is relative to. Contents uploaded in older generations
+    /// have to be uploaded whole.
+    pub damage_generation: u64,
+
+    /// Size of space reserved for the surface by compositor. `None` if surface decides its size
+    /// itself.
+    pub bounds: Option<Size>,
+
+    /// Factor by which the surface is scaled when drawn (e.g. in overview of workspaces).
+    pub scale: f32,
+
+    /// Fraction by which brightness and saturation of the surface are reduced when drawn. Zero
+    /// leaves colors unchanged.
+    pub dim: f32,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -23,17 +53,315 @@ pub struct SurfaceContext {
 impl SurfaceContext {
     /// `SurfaceContext` constructor.
     pub fn new(id: SurfaceId, pos: Position) -> Self {
-        SurfaceContext { id: id, pos: pos }
+        SurfaceContext {
+            id: id,
+            pos: pos,
+            damage: Vec::new(),
+            generation: 0,
+            damage_generation: 0,
+            bounds: None,
+            scale: 1.0,
+            dim: 0.0,
+        }
     }
 
     /// Creates new context with position moved by given vector.
     pub fn moved(&self, vector: Vector) -> Self {
-        SurfaceContext::new(self.id, self.pos + vector)
+        SurfaceContext {
+            id: self.id,
+            pos: self.pos + vector,
+            damage: self.damage.clone(),
+            generation: self.generation,
+            damage_generation: self.damage_generation,
+            bounds: self.bounds,
+            scale: self.scale,
+            dim: self.dim,
+        }
+    }
+
+    /// Creates new context scaled by given factor around given origin.
+    pub fn scaled(&self, origin: Position, scale: f32) -> Self {
+        SurfaceContext {
+            id: self.id,
+            pos: origin + (self.pos - origin).scaled(scale),
+            damage: self.damage.clone(),
+            generation: self.generation,
+            damage_generation: self.damage_generation,
+            bounds: self.bounds,
+            scale: self.scale * scale,
+            dim: self.dim,
+        }
     }
 }
 
 // -------------------------------------------------------------------------------------------------
 
+/// Describes how contents of buffer are laid out on surface. Buffer is drawn scaled down by
+/// `scale` with transformation inverse to `transform` (as clients render contents already
+/// transformed, e.g. to match rotated output).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BufferTransform {
+    /// Scale of buffer relative to surface.
+    pub scale: u32,
+
+    /// Transformation the buffer contents were rendered with.
+    pub transform: Transform,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl BufferTransform {
+    /// Constructs new `BufferTransform`.
+    pub fn new(scale: u32, transform: Transform) -> Self {
+        BufferTransform {
+            scale: scale,
+            transform: transform,
+        }
+    }
+
+    /// Returns size of surface with buffer of given size.
+    pub fn get_surface_size(&self, buffer_size: Size) -> Size {
+        let scale = self.scale.max(1) as usize;
+        let size = Size::new(buffer_size.width / scale, buffer_size.height / scale);
+        if self.transform.is_transposing() {
+            Size::new(size.height, size.width)
+        } else {
+            size
+        }
+    }
+
+    /// Translates position in surface of given size to buffer coordinates. Positions on edges
+    /// are translated as well, so corners of surface map to corners of buffer.
+    pub fn to_buffer_position(&self, position: Position, surface_size: Size) -> Position {
+        let scale = self.scale.max(1) as isize;
+        let position = transform_position(self.transform, position, surface_size);
+        Position::new(scale * position.x, scale * position.y)
+    }
+
+    /// Translates area in surface of given size to buffer coordinates.
+    pub fn to_buffer_area(&self, area: &Area, surface_size: Size) -> Area {
+        let (start, end) = (area.pos, Self::get_end(area));
+        Self::spanned(self.to_buffer_position(start, surface_size),
+                      self.to_buffer_position(end, surface_size))
+    }
+
+    /// Translates area in buffer of given size to surface coordinates. Area is extended to whole
+    /// surface pixels if buffer is scaled.
+    pub fn to_surface_area(&self, area: &Area, buffer_size: Size) -> Area {
+        let scale = self.scale.max(1) as isize;
+        let end = Self::get_end(area);
+        let start = Position::new(div_floor(area.pos.x, scale), div_floor(area.pos.y, scale));
+        let end = Position::new(div_ceil(end.x, scale), div_ceil(end.y, scale));
+        let size = Size::new(buffer_size.width / scale as usize,
+                             buffer_size.height / scale as usize);
+        let inverted = self.transform.inverted();
+        Self::spanned(transform_position(inverted, start, size),
+                      transform_position(inverted, end, size))
+    }
+
+    /// Returns position of corner of given area opposite to its position.
+    fn get_end(area: &Area) -> Position {
+        Position::new(area.pos.x + area.size.width as isize,
+                      area.pos.y + area.size.height as isize)
+    }
+
+    /// Returns area spanned between given corners.
+    fn spanned(a: Position, b: Position) -> Area {
+        let pos = Position::new(a.x.min(b.x), a.y.min(b.y));
+        let size = Size::new((a.x - b.x).abs() as usize, (a.y - b.y).abs() as usize);
+        Area::new(pos, size)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Default for BufferTransform {
+    fn default() -> Self {
+        BufferTransform::new(1, Transform::Normal)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Describes which part of surface is drawn and where.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SurfacePlacement {
+    /// Part of surface to be drawn (in surface coordinates).
+    pub source: Area,
+
+    /// Area the drawn part covers, relative to position from rendering context.
+    pub target: Area,
+
+    /// Area covered by the window itself (excluding e.g. client-side shadows), relative to
+    /// position from rendering context.
+    pub window: Area,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl SurfacePlacement {
+    /// Calculates placement of surface of given size. `offset` and `window_size` describe part of
+    /// the surface being the window itself and `bounds` is the size of space
+    /// reserved for the window. Whole surface is drawn as is if the window matches the bounds, no
+    /// space is reserved or (in case of `Clip` policy) the window fits in the bounds.
+    pub fn new(policy: SizeMismatchPolicy,
+               surface_size: Size,
+               offset: Vector,
+               window_size: Size,
+               bounds: Option<Size>)
+               -> Self {
+        let natural = SurfacePlacement {
+            source: Area::new(Position::default(), surface_size),
+            target: Area::new(Position::default() - offset, surface_size),
+            window: Area::new(Position::default(), window_size),
+        };
+
+        let bounds = match bounds {
+            Some(bounds) if !bounds.is_zero() && (bounds != window_size) => bounds,
+            _ => return natural,
+        };
+        if window_size.is_zero() {
+            return natural;
+        }
+
+        match policy {
+            SizeMismatchPolicy::Clip => {
+                if (window_size.width <= bounds.width) && (window_size.height <= bounds.height) {
+                    return natural;
+                }
+                let size = Size::new(window_size.width.min(bounds.width),
+                                     window_size.height.min(bounds.height));
+                Self::cut(Area::new(offset, size), Area::new(Position::default(), size))
+            }
+            SizeMismatchPolicy::Center => {
+                let (source_x, target_x, width) =
+                    Self::center(offset.x, window_size.width, bounds.width);
+                let (source_y, target_y, height) =
+                    Self::center(offset.y, window_size.height, bounds.height);
+                let size = Size::new(width, height);
+                Self::cut(Area::new(Position::new(source_x, source_y), size),
+                          Area::new(Position::new(target_x, target_y), size))
+            }
+            SizeMismatchPolicy::Scale => {
+                Self::cut(Area::new(offset, window_size),
+                          Area::new(Position::default(), bounds))
+            }
+        }
+    }
+
+    /// Creates new placement with drawn area scaled by given factor.
+    pub fn scaled(&self, scale: f32) -> Self {
+        SurfacePlacement {
+            source: self.source,
+            target: Self::scale_area(&self.target, scale),
+            window: Self::scale_area(&self.window, scale),
+        }
+    }
+
+    /// Translates position relative to position from rendering context to surface coordinates.
+    pub fn to_surface(&self, position: Position) -> Position {
+        let vector = position - self.target.pos;
+        let x = Self::rescale(vector.x, self.source.size.width, self.target.size.width, false);
+        let y = Self::rescale(vector.y, self.source.size.height, self.target.size.height, false);
+        self.source.pos + Position::new(x, y)
+    }
+
+    /// Translates damaged region of surface to area relative to position from rendering context.
+    /// Returns `None` if the region is not drawn.
+    pub fn map_damage(&self, region: &Area) -> Option<Area> {
+        self.source.intersected(region).map(|area| {
+            let (source, target) = (self.source.size, self.target.size);
+            let vector = area.pos - self.source.pos;
+            let left = Self::rescale(vector.x, target.width, source.width, false);
+            let top = Self::rescale(vector.y, target.height, source.height, false);
+            let right = Self::rescale(vector.x + area.size.width as isize,
+                                      target.width,
+                                      source.width,
+                                      true);
+            let bottom = Self::rescale(vector.y + area.size.height as isize,
+                                       target.height,
+                                       source.height,
+                                       true);
+            Area::new(self.target.pos + Position::new(left, top),
+                      Size::new((right - left) as usize, (bottom - top) as usize))
+        })
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Private methods.
+impl SurfacePlacement {
+    /// Constructs placement drawing given part of buffer in given area. Only the drawn part of
+    /// the window is visible so both cover the same area.
+    fn cut(source: Area, target: Area) -> Self {
+        SurfacePlacement {
+            source: source,
+            target: target,
+            window: target,
+        }
+    }
+
+    /// Centers segment of window of given offset in buffer and length in bounds of given length.
+    /// Returns start of drawn part of buffer, start of the part relative to bounds and its length.
+    fn center(offset: isize, length: usize, bounds: usize) -> (isize, isize, usize) {
+        if length > bounds {
+            (offset + ((length - bounds) / 2) as isize, 0, bounds)
+        } else {
+            (offset, ((bounds - length) / 2) as isize, length)
+        }
+    }
+
+    /// Scales position and size of given area by given factor.
+    fn scale_area(area: &Area, scale: f32) -> Area {
+        Area::new(area.pos.scaled(scale),
+                  Size::new((area.size.width as f32 * scale).round() as usize,
+                            (area.size.height as f32 * scale).round() as usize))
+    }
+
+    /// Scales given value by ratio `to / from` rounding towards zero or up.
+    fn rescale(value: isize, to: usize, from: usize, round_up: bool) -> isize {
+        if (from == 0) || (to == from) {
+            value
+        } else if round_up {
+            (value * to as isize + from as isize - 1) / from as isize
+        } else {
+            value * to as isize / from as isize
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Transforms position in area of given size. Corners of the area are mapped to corners of the
+/// transformed area.
+fn transform_position(transform: Transform, position: Position, size: Size) -> Position {
+    let (x, y) = (position.x, position.y);
+    let (width, height) = (size.width as isize, size.height as isize);
+    match transform {
+        Transform::Normal => Position::new(x, y),
+        Transform::Rotated90 => Position::new(y, width - x),
+        Transform::Rotated180 => Position::new(width - x, height - y),
+        Transform::Rotated270 => Position::new(height - y, x),
+        Transform::Flipped => Position::new(width - x, y),
+        Transform::Flipped90 => Position::new(y, x),
+        Transform::Flipped180 => Position::new(x, height - y),
+        Transform::Flipped270 => Position::new(height - y, width - x),
+    }
+}
+
+/// Divides rounding towards negative infinity.
+fn div_floor(value: isize, divisor: isize) -> isize {
+    if value < 0 { (value - divisor + 1) / divisor } else { value / divisor }
+}
+
+/// Divides rounding towards positive infinity.
+fn div_ceil(value: isize, divisor: isize) -> isize {
+    if value > 0 { (value + divisor - 1) / divisor } else { value / divisor }
+}
+
+// -------------------------------------------------------------------------------------------------
+
 /// These flags describe readiness of `Surface` to be displayed.
 pub mod show_reason {
     bitflags!(
@@ -56,6 +384,8 @@ pub mod surface_state {
             const MAXIMIZED = 0b0001,
             const FULLSCREEN = 0x0010,
             const RESIZING = 0x0100,
+            const SUSPENDED = 0x1000,
+            const TILED = 0x10000,
         }
     );
 }
@@ -67,10 +397,26 @@ pub struct SurfaceInfo {
     pub id: SurfaceId,
     pub offset: Vector,
     pub parent_sid: SurfaceId,
+    pub relative_position: Position,
+    pub positioner: Option<Positioner>,
     pub desired_size: Size,
     pub requested_size: Size,
+    pub min_size: Size,
+    pub max_size: Size,
+    pub window_geometry: Option<Area>,
+    pub app_id: Option<String>,
+    pub title: Option<String>,
+    pub capture_excluded: bool,
+    pub decoration_sid: SurfaceId,
+    pub margins: Margins,
+    pub requested_decoration_mode: Option<DecorationMode>,
+    pub decoration_mode: DecorationMode,
     pub state_flags: surface_state::SurfaceState,
     pub buffer: Option<MemoryView>,
+    pub hw_image: Option<HwImage>,
+    pub buffer_transform: BufferTransform,
+    pub alpha_mode: AlphaMode,
+    pub color_description: ColorDescription,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -90,6 +436,46 @@ pub struct Surface {
     /// Size requested by client.
     requested_size: Size,
 
+    /// Minimal size requested by client. Zero means no limit.
+    min_size: Size,
+
+    /// Maximal size requested by client. Zero means no limit.
+    max_size: Size,
+
+    /// Part of the surface being the window itself (excluding e.g. client-side shadows).
+    window_geometry: Option<Area>,
+
+    /// Window geometry to be used after commit.
+    pending_window_geometry: Option<Area>,
+
+    /// Identifier of application given by client.
+    app_id: Option<String>,
+
+    /// Title of the window given by client.
+    title: Option<String>,
+
+    /// Tells if contents of the surface should be hidden in screen captures.
+    capture_excluded: bool,
+
+    /// Description of colors of surface contents. Placeholder until color management protocol
+    /// is supported; always sRGB for now.
+    color_description: ColorDescription,
+
+    /// Region declared by client as fully opaque, in surface coordinates.
+    opaque_region: Option<Area>,
+
+    /// ID of surface drawn by compositor below this one as its decoration.
+    decoration_sid: SurfaceId,
+
+    /// Space reserved around the surface for decoration. Size desired by compositor excludes it.
+    margins: Margins,
+
+    /// Decoration mode preferred by client. `None` if client has no preference.
+    requested_decoration_mode: Option<DecorationMode>,
+
+    /// Decoration mode chosen by compositor.
+    decoration_mode: DecorationMode,
+
     /// ID of parent surface.
     parent_sid: SurfaceId,
 
@@ -100,12 +486,42 @@ pub struct Surface {
     /// For surfaces without parent this must be {0, 0}.
     relative_position: Position,
 
+    /// Rules for placing the surface relative to its parent if it is a pop-up.
+    positioner: Option<Positioner>,
+
     /// Data required for draw.
     buffer: Option<MemoryView>,
 
     /// Data to be used after commit.
     pending_buffer: Option<MemoryView>,
 
+    /// Image stored in GPU memory required for draw. Surface has either `buffer` or `hw_image`.
+    hw_image: Option<HwImage>,
+
+    /// Image stored in GPU memory to be used after commit.
+    pending_hw_image: Option<HwImage>,
+
+    /// Scale and transformation of buffer or hardware image.
+    buffer_transform: BufferTransform,
+
+    /// Scale and transformation to be used after commit.
+    pending_buffer_transform: BufferTransform,
+
+    /// Regions changed since the surface was last drawn.
+    damage: Vec<Area>,
+
+    /// Number of commits with buffer or hardware image.
+    generation: u64,
+
+    /// Generation of contents `damage` is relative to.
+    damage_generation: u64,
+
+    /// Regions reported by client to be applied on commit.
+    pending_damage: Vec<Area>,
+
+    /// Regions reported by client in buffer coordinates to be applied on commit.
+    pending_buffer_damage: Vec<Area>,
+
     /// Flags describing logical state of surface
     state_flags: surface_state::SurfaceState,
 
@@ -123,11 +539,34 @@ impl Surface {
             offset: Vector::default(),
             desired_size: Size::default(),
             requested_size: Size::default(),
+            min_size: Size::default(),
+            max_size: Size::default(),
+            window_geometry: None,
+            pending_window_geometry: None,
+            app_id: None,
+            title: None,
+            capture_excluded: false,
+            color_description: ColorDescription::srgb(),
+            opaque_region: None,
+            decoration_sid: SurfaceId::invalid(),
+            margins: Margins::default(),
+            requested_decoration_mode: None,
+            decoration_mode: DecorationMode::ClientSide,
             parent_sid: SurfaceId::invalid(),
             satellites: vec![*id],
             relative_position: Position::default(),
+            positioner: None,
             buffer: None,
             pending_buffer: None,
+            hw_image: None,
+            pending_hw_image: None,
+            buffer_transform: BufferTransform::default(),
+            pending_buffer_transform: BufferTransform::default(),
+            damage: Vec::new(),
+            generation: 0,
+            damage_generation: 0,
+            pending_damage: Vec::new(),
+            pending_buffer_damage: Vec::new(),
             show_reasons: show_reason::NONE,
             state_flags: surface_state::REGULAR,
         }
@@ -145,6 +584,77 @@ impl Surface {
         self.requested_size = size
     }
 
+    /// Sets minimal size requested by client.
+    #[inline]
+    pub fn set_min_size(&mut self, size: Size) {
+        self.min_size = size
+    }
+
+    /// Sets maximal size requested by client.
+    #[inline]
+    pub fn set_max_size(&mut self, size: Size) {
+        self.max_size = size
+    }
+
+    /// Sets identifier of application.
+    #[inline]
+    pub fn set_app_id(&mut self, app_id: String) {
+        self.app_id = Some(app_id)
+    }
+
+    /// Sets title of the window.
+    #[inline]
+    pub fn set_title(&mut self, title: String) {
+        self.title = Some(title)
+    }
+
+    /// Sets if contents of the surface should be hidden in screen captures.
+    #[inline]
+    pub fn set_capture_excluded(&mut self, excluded: bool) {
+        self.capture_excluded = excluded
+    }
+
+    /// Sets description of colors of surface contents.
+    #[inline]
+    pub fn set_color_description(&mut self, description: ColorDescription) {
+        self.color_description = description
+    }
+
+    /// Sets region declared by client as opaque. `None` means the whole surface may be
+    /// translucent.
+    #[inline]
+    pub fn set_opaque_region(&mut self, region: Option<Area>) {
+        self.opaque_region = region
+    }
+
+    /// Sets decoration surface and space reserved for it. Desired size is adjusted so that the
+    /// surface together with decoration keeps occupying the same space.
+    pub fn set_decoration(&mut self, sid: SurfaceId, margins: Margins) {
+        if !self.desired_size.is_zero() {
+            self.desired_size = margins.shrink(self.margins.grow(self.desired_size));
+        }
+        self.decoration_sid = sid;
+        self.margins = margins;
+    }
+
+    /// Sets decoration mode preferred by client.
+    #[inline]
+    pub fn set_requested_decoration_mode(&mut self, mode: Option<DecorationMode>) {
+        self.requested_decoration_mode = mode
+    }
+
+    /// Sets decoration mode chosen by compositor.
+    #[inline]
+    pub fn set_decoration_mode(&mut self, mode: DecorationMode) {
+        self.decoration_mode = mode
+    }
+
+    /// Sets window geometry to be applied on next commit.
+    #[inline]
+    pub fn set_window_geometry(&mut self, geometry: Area) {
+        self.pending_window_geometry = Some(geometry)
+    }
+
     /// Sets size desired by compositor.
     #[inline]
     pub fn set_desired_size(&mut self, size: Size) {
@@ -192,6 +702,12 @@ impl Surface {
         self.relative_position = position
     }
 
+    /// Sets rules for placing the surface relative to its parent.
+    #[inline]
+    pub fn set_positioner(&mut self, positioner: Positioner) {
+        self.positioner = Some(positioner)
+    }
+
     /// Sets state flags.
     #[inline]
     pub fn set_state_flags(&mut self, state_flags: surface_state::SurfaceState) {
@@ -228,27 +744,102 @@ impl Surface {
     #[inline]
     pub fn attach(&mut self, buffer: MemoryView) {
         self.pending_buffer = Some(buffer);
+        self.pending_hw_image = None;
+    }
+
+    /// Sets given hardware image as pending.
+    #[inline]
+    pub fn attach_hw_image(&mut self, image: HwImage) {
+        self.pending_hw_image = Some(image);
+        self.pending_buffer = None;
+    }
+
+    /// Adds given region to pending damage.
+    #[inline]
+    pub fn damage(&mut self, area: Area) {
+        self.pending_damage.push(area);
+    }
+
+    /// Adds damage region given in buffer coordinates. It will be translated to surface
+    /// coordinates on commit using buffer and its transformation committed together with it.
+    pub fn damage_buffer(&mut self, area: Area) {
+        self.pending_buffer_damage.push(area);
+    }
+
+    /// Sets scale of buffer to be applied on commit.
+    pub fn set_buffer_scale(&mut self, scale: u32) {
+        self.pending_buffer_transform.scale = scale;
+    }
+
+    /// Sets transformation of buffer to be applied on commit.
+    pub fn set_buffer_transform(&mut self, transform: Transform) {
+        self.pending_buffer_transform.transform = transform;
     }
 
     /// Sets pending buffer as current. If surface was committed for the first time and sizes are
     /// not set, assign size of buffer as requested size. Return `true` if surface was committed for
     /// the first time, `false` otherwise.
+    ///
+    /// Pending window geometry becomes current and defines offset and requested size.
+    ///
+    /// Pending damage is appended to damage accumulated since last draw. If size of the surface
+    /// or transformation of the buffer changed whole surface is damaged. Committing buffer or
+    /// hardware image starts new generation of contents.
     pub fn commit(&mut self) -> bool {
-        let is_first_time_committed = self.buffer.is_none();
+        let is_first_time_committed = self.buffer.is_none() && self.hw_image.is_none();
+        let old_size = self.get_size();
+        let old_buffer_transform = self.buffer_transform;
         self.buffer = self.pending_buffer.clone();
+        self.hw_image = self.pending_hw_image.clone();
+        self.buffer_transform = self.pending_buffer_transform;
 
-        if let Some(ref buffer) = self.buffer {
+        if let Some(geometry) = self.pending_window_geometry.take() {
+            self.set_offset(geometry.pos);
+            self.requested_size = geometry.size;
+            self.window_geometry = Some(geometry);
+        }
+
+        if self.buffer.is_some() || self.hw_image.is_some() {
+            self.generation += 1;
+        }
+
+        if let Some(buffer_size) = self.get_buffer_size() {
+            for region in self.pending_buffer_damage.iter() {
+                let area = self.buffer_transform.to_surface_area(region, buffer_size);
+                self.pending_damage.push(area);
+            }
+        }
+        self.pending_buffer_damage.clear();
+
+        let size = self.get_size();
+        if let Some(size) = size {
+            if (old_size != Some(size)) || (old_buffer_transform != self.buffer_transform) {
+                self.pending_damage.clear();
+                self.pending_damage.push(Area::new(Position::default(), size));
+            }
+        }
+        self.damage.append(&mut self.pending_damage);
+        if self.damage.len() > MAX_DAMAGE_REGIONS {
+            // Surface is probably not being drawn. Keep the list short.
+            let mut area = self.damage[0];
+            for region in self.damage.iter() {
+                area.inflate(region);
+            }
+            self.damage = vec![area];
+        }
+
+        if let Some(size) = size {
             // If surface was just created...
             if is_first_time_committed {
                 // ... size was not yet requested by surface ...
                 if (self.requested_size.width == 0) || (self.requested_size.height == 0) {
-                    // ... use its buffer size as requested size ...
-                    self.requested_size = buffer.get_size();
+                    // ... use its size as requested size ...
+                    self.requested_size = size;
                 }
                 // ... and if it is subsurface ...
                 if self.parent_sid.is_valid() {
                     // ... set its desired size.
-                    self.desired_size = buffer.get_size();
+                    self.desired_size = size;
                 }
             }
         }
@@ -262,23 +853,100 @@ impl Surface {
             id: self.id,
             offset: self.offset,
             parent_sid: self.parent_sid,
+            relative_position: self.relative_position,
+            positioner: self.positioner,
             desired_size: self.desired_size,
             requested_size: self.requested_size,
+            min_size: self.min_size,
+            max_size: self.max_size,
+            window_geometry: self.window_geometry,
+            app_id: self.app_id.clone(),
+            title: self.title.clone(),
+            capture_excluded: self.capture_excluded,
+            decoration_sid: self.decoration_sid,
+            margins: self.margins,
+            requested_decoration_mode: self.requested_decoration_mode,
+            decoration_mode: self.decoration_mode,
             state_flags: self.state_flags,
             buffer: self.buffer.clone(),
+            hw_image: self.hw_image.clone(),
+            buffer_transform: self.buffer_transform,
+            alpha_mode: self.get_alpha_mode(),
+            color_description: self.color_description,
         }
     }
 
+    /// Returns how alpha channel of current buffer or hardware image should be interpreted. Mode
+    /// implied by buffer format is overridden if opaque region covers the whole surface.
+    pub fn get_alpha_mode(&self) -> AlphaMode {
+        let mode = if let Some(ref buffer) = self.buffer {
+            buffer.get_alpha_mode()
+        } else if let Some(ref image) = self.hw_image {
+            image.get_alpha_mode()
+        } else {
+            AlphaMode::Straight
+        };
+
+        if let (Some(region), Some(size)) = (self.opaque_region, self.get_size()) {
+            let bounds = Area::new(Position::default(), size);
+            if region.intersected(&bounds) == Some(bounds) {
+                return AlphaMode::Opaque;
+            }
+        }
+        mode
+    }
+
     /// Returns surfaces buffer.
     pub fn get_buffer(&self) -> Option<MemoryView> {
         self.buffer.clone()
     }
 
+    /// Returns size of current buffer or hardware image.
+    pub fn get_buffer_size(&self) -> Option<Size> {
+        if let Some(ref buffer) = self.buffer {
+            Some(buffer.get_size())
+        } else if let Some(ref image) = self.hw_image {
+            Some(image.get_size())
+        } else {
+            None
+        }
+    }
+
+    /// Returns size of surface: size of current buffer or hardware image scaled and transformed
+    /// according to buffer transformation.
+    pub fn get_size(&self) -> Option<Size> {
+        self.get_buffer_size().map(|size| self.buffer_transform.get_surface_size(size))
+    }
+
+    /// Removes given damage regions. Called after the surface was drawn with damage and
+    /// generation returned in rendering context. Regions added by commits performed in the
+    /// meantime are kept and are relative to the drawn generation.
+    pub fn discard_damage(&mut self, drawn: &Vec<Area>, generation: u64) {
+        if self.damage.starts_with(drawn) {
+            self.damage.drain(0..drawn.len());
+            self.damage_generation = generation;
+        }
+    }
+
     /// Returns surfaces rendering context.
     pub fn get_renderer_context(&self) -> SurfaceContext {
+        let constrained = surface_state::TILED | surface_state::MAXIMIZED |
+                          surface_state::FULLSCREEN;
+        let bounds = if self.state_flags.intersects(constrained) && !self.desired_size.is_zero() {
+            Some(self.desired_size)
+        } else {
+            None
+        };
+
         SurfaceContext {
             id: self.id,
             pos: self.relative_position,
+            damage: self.damage.clone(),
+            generation: self.generation,
+            damage_generation: self.damage_generation,
+            bounds: bounds,
+            scale: 1.0,
+            dim: 0.0,
         }
     }
 
@@ -287,6 +955,21 @@ impl Surface {
         self.desired_size
     }
 
+    /// Returns ID of decoration surface.
+    pub fn get_decoration_sid(&self) -> SurfaceId {
+        self.decoration_sid
+    }
+
+    /// Returns minimal and maximal size requested by client. Zero dimension means no limit.
+    pub fn get_size_limits(&self) -> (Size, Size) {
+        (self.min_size, self.max_size)
+    }
+
+    /// Returns space reserved around the surface for decoration.
+    pub fn get_margins(&self) -> Margins {
+        self.margins
+    }
+
     /// Returns flags describing state of the surface.
     pub fn get_state_flags(&self) -> surface_state::SurfaceState {
         self.state_flags
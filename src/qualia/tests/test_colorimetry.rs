@@ -0,0 +1,70 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Tests for description of colors.
+
+// -------------------------------------------------------------------------------------------------
+
+extern crate qualia;
+
+use qualia::{ColorDescription, HdrMetadata, Primaries, TransferFunction};
+use qualia::colorimetry::HDR_OUTPUT_METADATA_SIZE;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Reads little-endian `u16` at given offset.
+fn get_u16(data: &[u8], offset: usize) -> u16 {
+    data[offset] as u16 | (data[offset + 1] as u16) << 8
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if HDR10 description is laid out as `struct hdr_output_metadata`.
+#[test]
+fn should_encode_hdr_output_metadata() {
+    let description = ColorDescription {
+        primaries: Primaries::Bt2020,
+        transfer: TransferFunction::Pq,
+        hdr_metadata: Some(HdrMetadata {
+                               primaries: [(35400, 14600), (8500, 39850), (6550, 2300)],
+                               white_point: (15635, 16450),
+                               max_luminance: 1000,
+                               min_luminance: 50,
+                               max_cll: 800,
+                               max_fall: 400,
+                           }),
+    };
+    assert!(description.is_hdr());
+
+    let data = description.to_hdr_output_metadata();
+    assert_eq!(data.len(), HDR_OUTPUT_METADATA_SIZE);
+    assert_eq!(&data[0..4], &[0, 0, 0, 0]);
+    assert_eq!(data[4], 2);
+    assert_eq!(data[5], 0);
+    assert_eq!(get_u16(&data, 6), 35400);
+    assert_eq!(get_u16(&data, 8), 14600);
+    assert_eq!(get_u16(&data, 14), 6550);
+    assert_eq!(get_u16(&data, 16), 2300);
+    assert_eq!(get_u16(&data, 18), 15635);
+    assert_eq!(get_u16(&data, 20), 16450);
+    assert_eq!(get_u16(&data, 22), 1000);
+    assert_eq!(get_u16(&data, 24), 50);
+    assert_eq!(get_u16(&data, 26), 800);
+    assert_eq!(get_u16(&data, 28), 400);
+    assert_eq!(&data[30..32], &[0, 0]);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if contents are sRGB by default and unknown metadata is sent as zeros.
+#[test]
+fn should_describe_srgb_by_default() {
+    let description = ColorDescription::default();
+    assert_eq!(description, ColorDescription::srgb());
+    assert!(!description.is_hdr());
+
+    let data = description.to_hdr_output_metadata();
+    assert_eq!(data, vec![0; HDR_OUTPUT_METADATA_SIZE]);
+}
+
+// -------------------------------------------------------------------------------------------------
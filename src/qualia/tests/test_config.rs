@@ -0,0 +1,864 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Tests for parsing configuration.
+
+// -------------------------------------------------------------------------------------------------
+
+extern crate qualia;
+extern crate uinput_sys;
+
+use qualia::{Action, Config, Direction, Illusion, KeyCode, KeyRemapConfig, Position, Size};
+use qualia::{InputDeviceId, OutputMode, SizeMismatchPolicy, Switch, SwitchAction, Transform};
+use qualia::{AccelProfile, ScrollMethod, SeatId, WallpaperMode, WorkspaceLayout, XkbConfig};
+use qualia::defs::{media_key, modifier, mode_name};
+use qualia::key_binder::DEFAULT_MODE;
+use qualia::input_manager::Binding;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if empty configuration file gives default configuration.
+#[test]
+fn should_use_defaults_for_empty_file() {
+    let default = Config::default();
+    let config = Config::parse("").unwrap();
+
+    assert_eq!(config.get_input_config().mouse_scale, default.get_input_config().mouse_scale);
+    assert_eq!(config.get_exhibitor_config().edge_switch_delay,
+               default.get_exhibitor_config().edge_switch_delay);
+    assert_eq!(config.get_key_binding_config().len(), default.get_key_binding_config().len());
+    assert_eq!(config.get_xkb_config(), XkbConfig::default());
+    assert_eq!(config.get_input_config(), default.get_input_config());
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if values from all sections are read.
+#[test]
+fn should_read_values() {
+    let text = r#"
+        [input]
+        touchpad_scale = 0.25
+        touchpad_pressure_threshold = 30
+        mouse_scale = 2
+        accel_profile = "flat"
+        natural_scrolling = true
+        tap_to_click = false
+        scroll_method = "edge"
+        double_click_interval = 300
+        repeat_rate = 40
+        repeat_delay = 250
+
+        [workspaces]
+        names = ["web", "code"]
+        edge_switch_delay = 800
+        float_unresizable = false
+        preserve_aspect_ratio = true
+
+        [gestures]
+        swipe_workspaces = false
+        pinch_overview = false
+        forward = false
+
+        [renderer]
+        visualize_damage = true
+        linear_blending = false
+        dim_unfocused = 0.5
+        decorations = true
+        size_mismatch = "scale"
+
+        [security]
+        privileged_clients = ["clipboard"]
+
+        [xkb]
+        layout = "us,pl"
+        variant = "dvorak,"
+        options = "compose:ralt"
+    "#;
+
+    let config = Config::parse(text).unwrap();
+
+    let input = config.get_input_config();
+    assert_eq!(input.touchpad_scale, 0.25);
+    assert_eq!(input.touchpad_pressure_threshold, 30);
+    assert_eq!(input.mouse_scale, 2.0);
+    assert_eq!(input.accel_profile, AccelProfile::Flat);
+    assert_eq!(input.natural_scrolling, true);
+    assert_eq!(input.tap_to_click, false);
+    assert_eq!(input.scroll_method, ScrollMethod::Edge);
+
+    let repeat = config.get_key_repeat_config();
+    assert_eq!(repeat.rate, 40);
+    assert_eq!(repeat.delay, 250);
+
+    let exhibitor = config.get_exhibitor_config();
+    assert_eq!(exhibitor.workspace_names, vec!["web".to_owned(), "code".to_owned()]);
+    assert_eq!(exhibitor.edge_switch_delay, 800);
+    assert!(!exhibitor.float_unresizable);
+    assert!(exhibitor.preserve_aspect_ratio);
+    assert!(!exhibitor.swipe_workspaces);
+    assert!(!exhibitor.pinch_overview);
+    assert!(!exhibitor.forward_gestures);
+    assert_eq!(exhibitor.double_click_interval, 300);
+    assert!(exhibitor.visualize_damage);
+    assert!(!exhibitor.linear_blending);
+    assert_eq!(exhibitor.dim_unfocused, 0.5);
+    assert!(exhibitor.decorations);
+    assert_eq!(exhibitor.size_mismatch_policy, SizeMismatchPolicy::Scale);
+
+    let security = config.get_security_config();
+    assert_eq!(security.privileged_clients, vec!["clipboard".to_owned()]);
+
+    let xkb = config.get_xkb_config();
+    assert_eq!(xkb.rules, "evdev");
+    assert_eq!(xkb.layout, "us,pl");
+    assert_eq!(xkb.variant, "dvorak,");
+    assert_eq!(xkb.options, Some("compose:ralt".to_owned()));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if key bindings are added after default ones.
+#[test]
+fn should_read_bindings() {
+    let text = r#"
+        [[bindings]]
+        mode = "normal"
+        key = "W"
+        modifiers = ["LMTA", "LSHF"]
+        action = "create_workspace"
+
+        [[bindings]]
+        key = "F1"
+        action = "quit"
+    "#;
+
+    let default_len = Config::default().get_key_binding_config().len();
+    let bindings = Config::parse(text).unwrap().get_key_binding_config();
+    assert_eq!(bindings.len(), default_len + 2);
+
+    let first = &bindings[default_len];
+    assert_eq!(first.mode_name, mode_name::NORMAL);
+    assert_eq!(first.binding,
+               Binding::new(uinput_sys::KEY_W, modifier::LMTA | modifier::LSHF));
+
+    let second = &bindings[default_len + 1];
+    assert_eq!(second.mode_name, mode_name::INSERT);
+    assert_eq!(second.binding, Binding::new(uinput_sys::KEY_F1, modifier::NONE));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if switching virtual terminals is bound to `ctrl+alt+F1` to `ctrl+alt+F12` by default.
+#[test]
+fn should_bind_switching_vt_by_default() {
+    let bindings = Config::default().get_key_binding_config();
+    for code in &[uinput_sys::KEY_F1, uinput_sys::KEY_F10, uinput_sys::KEY_F12] {
+        let binding = Binding::new(*code, modifier::LCTL | modifier::LALT);
+        assert!(bindings.iter().any(|entry| {
+            entry.mode_name == mode_name::COMMON && entry.binding == binding
+        }));
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if invalid files are reported as invalid argument.
+#[test]
+fn should_reject_invalid_files() {
+    let texts = [
+        "[input",
+        "[unknown]",
+        "[input]\nunknown = 1",
+        "[input]\nmouse_scale = \"fast\"",
+        "[input]\nmouse_scale = -1.0",
+        "[input]\nbypass_bindings_apps = \"virt-manager\"",
+        "[input]\nbypass_bindings_apps = [1]",
+        "[input]\ndouble_click_interval = -1",
+        "[input]\nrepeat_rate = -1",
+        "[input]\nrepeat_delay = \"long\"",
+        "[input]\naccel_profile = \"fast\"",
+        "[renderer]\ndim_unfocused = 1.5",
+        "[input]\nnatural_scrolling = 1",
+        "[input]\ntap_to_click = \"yes\"",
+        "[input]\nscroll_method = \"three_finger\"",
+        "[input.\"\"]\nmouse_scale = 1.0",
+        "[input.\"Mouse\"]\nunknown = 1",
+        "[input.\"Mouse\"]\nmouse_scale = 0",
+        "[input.\"Mouse\"]\naccel_profile = 1",
+        "[input.\"Touchscreen\"]\noutput = \"\"",
+        "[input.\"Touchscreen\"]\ncalibration = [1.0, 0.0, 0.0, 0.0, 1.0]",
+        "[input.\"Touchscreen\"]\ncalibration = [1.0, 0.0, 0.0, 0.0, 1.0, \"0\"]",
+        "[renderer]\ndecorations = \"yes\"",
+        "[renderer]\nsize_mismatch = \"stretch\"",
+        "[[keyboard]]\n[[keyboard.remap]]\nkey = \"CAPSLOCK\"",
+        "[[keyboard]]\n[[keyboard.remap]]\nkey = \"NOKEY\"\nto = [\"ESC\"]",
+        "[[keyboard]]\n[[keyboard.remap]]\nkey = \"CAPSLOCK\"\nto = []",
+        "[[keyboard]]\ndevice = \"\"",
+        "[xkb]\nlayout = \"\"",
+        "[xkb]\nlayout = \"us\"\nvariant = \"intl,dvorak\"",
+        "[xkb]\nunknown = \"us\"",
+        "[workspaces]\nnames = [\"a\", \"a\"]",
+        "[workspaces]\nfloat_unresizable = 1",
+        "[workspaces]\npreserve_aspect_ratio = \"yes\"",
+        "[gestures]\nforward = 1",
+        "[gestures]\nunknown = true",
+        "[[bindings]]\nkey = \"NOKEY\"\naction = \"quit\"",
+        "[[bindings]]\nkey = \"Q\"\naction = \"unknown\"",
+        "[[bindings]]\nkey = \"Q\"\nmodifiers = [\"HYPER\"]\naction = \"quit\"",
+        "[[bindings]]\nmode = \"visual\"\nkey = \"Q\"\naction = \"quit\"",
+        "[[bindings]]\naction = \"quit\"",
+        "[[commands]]\nkeysym = \"NoSuchKey\"\naction = \"focus\"",
+        "[[commands]]\nkeysym = \"h\"",
+        "[[commands]]\nkeysym = \"h\"\naction = \"fly\"",
+        "[[commands]]\nkeysym = \"h\"\naction = \"focus\"\ndirection = \"inside\"",
+        "[[commands]]\nkeysym = \"r\"\nswitch_mode = \"resize\"",
+        "[[workspace]]\noutput = \"HDMI-A-1\"",
+        "[[workspace]]\nname = \"a\"\n[[workspace]]\nname = \"a\"",
+        "[[workspace]]\nname = \"a\"\nlayout = \"spiral\"",
+        "[[profile]]\nname = \"a\"",
+        "[[profile]]\nname = \"a\"\n[[profile.output]]\nname = \"DP-1\"\nmode = \"big\"",
+        "[[profile]]\nname = \"a\"\n[[profile.output]]\nname = \"DP-1\"\nmode = \"800x600@0\"",
+        "[[profile]]\nname = \"a\"\n[[profile.output]]\nname = \"DP-1\"\nscale = 0",
+        "[[profile]]\nname = \"a\"\n[[profile.output]]\nname = \"DP-1\"\nrotate = 45",
+        "[screencast]\noutput = \"DP-1\"\nsize = \"1920x1080\"",
+        "[screencast]\noutput = \"DP-1\"\nsize = \"1920x1080\"\nrate = 0",
+        "[screencast]\noutput = \"DP-1\"\nsize = \"full-hd\"\nrate = 30",
+        "[security]\ncapture_excluded_apps = \"org.example.Browser\"",
+        "[security]\nunknown = true",
+        "[rotation]\nautomatic = 1",
+        "[rotation]\noutput = \"\"",
+        "[[switch]]\nname = \"hinge\"\nrotate = 90",
+        "[[switch]]\nname = \"lid\"\nrotate = 45",
+        "[[switch]]\nname = \"lid\"",
+        "[[switch]]\nrotate = 90",
+        "[[switch]]\nname = \"dock\"\nrotate = 0\n[[switch]]\nname = \"dock\"\nrotate = 90",
+        "[[wallpaper]]\nmode = \"fill\"",
+        "[[wallpaper]]\npath = \"/tmp/a.png\"\nmode = \"stretch\"",
+        "[[wallpaper]]\npath = \"/tmp/a.png\"\noutput = \"\"",
+        "[wallpaper]\npath = \"/tmp/a.png\"",
+    ];
+
+    for text in texts.iter() {
+        match Config::parse(text) {
+            Err(Illusion::InvalidArgument(_)) => {}
+            Err(err) => panic!("Unexpected error for {:?}: {}", text, err),
+            Ok(_) => panic!("Config {:?} should be rejected", text),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if replacing configuration is visible in all its clones.
+#[test]
+fn should_replace_config_in_all_clones() {
+    let config = Config::default();
+    let clone = config.clone();
+
+    let reloaded = Config::parse("[workspaces]\nedge_switch_delay = 1200").unwrap();
+    config.replace(&reloaded);
+
+    assert_eq!(clone.get_exhibitor_config().edge_switch_delay, 1200);
+    assert_eq!(clone.get_key_binding_config().len(),
+               reloaded.get_key_binding_config().len());
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if command bindings and their modes are read.
+#[test]
+fn should_read_commands() {
+    let text = r#"
+        [[commands]]
+        keysym = "m"
+        modifiers = ["LMTA"]
+        switch_mode = "move"
+
+        [[commands]]
+        mode = "move"
+        keysym = "Left"
+        action = "jump"
+        direction = "west"
+        magnitude = 2
+        switch_mode = "default"
+    "#;
+
+    let commands = Config::parse(text).unwrap().get_command_binding_config();
+    assert_eq!(commands.len(), 2);
+
+    let first = &commands[0];
+    assert_eq!(first.mode_name, DEFAULT_MODE);
+    assert_eq!(first.modifiers, modifier::LMTA);
+    assert!(first.key_command.command.is_none());
+    assert_eq!(first.key_command.next_mode, Some("move".to_owned()));
+
+    let second = &commands[1];
+    assert_eq!(second.mode_name, "move");
+    assert_eq!(second.modifiers, modifier::NONE);
+    let command = second.key_command.command.clone().unwrap();
+    assert_eq!(command.action, Action::Jump);
+    assert_eq!(command.direction, Direction::West);
+    assert_eq!(command.magnitude, 2);
+    assert_eq!(second.key_command.next_mode, Some(DEFAULT_MODE.to_owned()));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if workspace definitions are read.
+#[test]
+fn should_read_workspace_definitions() {
+    let text = r#"
+        [[workspace]]
+        name = "mail"
+        output = "HDMI-A-1"
+        layout = "horizontal"
+
+        [[workspace]]
+        name = "web"
+    "#;
+
+    let workspaces = Config::parse(text).unwrap().get_exhibitor_config().workspaces;
+    assert_eq!(workspaces.len(), 2);
+
+    assert_eq!(workspaces[0].name, "mail");
+    assert_eq!(workspaces[0].output, Some("HDMI-A-1".to_owned()));
+    assert_eq!(workspaces[0].layout, WorkspaceLayout::Horizontal);
+
+    assert_eq!(workspaces[1].name, "web");
+    assert_eq!(workspaces[1].output, None);
+    assert_eq!(workspaces[1].layout, WorkspaceLayout::Stacked);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if output profiles are read and matched against set of connected outputs.
+#[test]
+fn should_read_output_profiles() {
+    let text = r#"
+        [[profile]]
+        name = "docked"
+
+        [[profile.output]]
+        name = "connector-1"
+        position = [0, 0]
+        workspaces = ["mail"]
+
+        [[profile.output]]
+        name = "connector-2"
+        mode = "1920x1080@60"
+        position = [1366, 0]
+        scale = 2
+        rotate = 90
+        flipped = true
+    "#;
+
+    let profiles = Config::parse(text).unwrap().get_exhibitor_config().profiles;
+    assert_eq!(profiles.len(), 1);
+
+    let profile = &profiles[0];
+    assert_eq!(profile.name, "docked");
+    assert_eq!(profile.outputs.len(), 2);
+    assert_eq!(profile.outputs[0].mode, None);
+    assert_eq!(profile.outputs[0].position, Some(Position::new(0, 0)));
+    assert_eq!(profile.outputs[0].scale, None);
+    assert_eq!(profile.outputs[0].transform, None);
+    assert_eq!(profile.outputs[0].workspaces, vec!["mail".to_owned()]);
+    assert_eq!(profile.outputs[1].mode, Some(OutputMode::new(Size::new(1920, 1080), 60)));
+    assert_eq!(profile.outputs[1].position, Some(Position::new(1366, 0)));
+    assert_eq!(profile.outputs[1].scale, Some(2.0));
+    assert_eq!(profile.outputs[1].transform, Some(Transform::Flipped90));
+
+    assert!(profile.matches(&["connector-2".to_owned(), "connector-1".to_owned()]));
+    assert!(!profile.matches(&["connector-1".to_owned()]));
+    assert!(!profile.matches(&["connector-1".to_owned(), "connector-3".to_owned()]));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if fractional scale of output is accepted.
+#[test]
+fn should_read_fractional_output_scale() {
+    let text = "[[profile]]\nname = \"a\"\n[[profile.output]]\nname = \"DP-1\"\nscale = 1.5";
+    let profiles = Config::parse(text).unwrap().get_exhibitor_config().profiles;
+    assert_eq!(profiles[0].outputs[0].scale, Some(1.5));
+
+    let text = "[[profile]]\nname = \"a\"\n[[profile.output]]\nname = \"DP-1\"\nscale = -1.5";
+    assert!(Config::parse(text).is_err());
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if screencast settings are read.
+#[test]
+fn should_read_screencast() {
+    let text = r#"
+        [screencast]
+        output = "connector-30"
+        size = "1920x1080"
+        rate = 30
+    "#;
+
+    let screencast = Config::parse(text).unwrap().get_exhibitor_config().screencast.unwrap();
+    assert_eq!(screencast.output, "connector-30");
+    assert_eq!(screencast.size, Size::new(1920, 1080));
+    assert_eq!(screencast.rate, 30);
+    assert!(!screencast.export);
+
+    let text = r#"
+        [screencast]
+        output = "connector-30"
+        size = "1280x720"
+        rate = 60
+        export = true
+    "#;
+
+    let screencast = Config::parse(text).unwrap().get_exhibitor_config().screencast.unwrap();
+    assert!(screencast.export);
+
+    assert!(Config::parse("").unwrap().get_exhibitor_config().screencast.is_none());
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if applications excluded from captures are read from `security` section.
+#[test]
+fn should_read_capture_excluded_apps() {
+    let text = r#"
+        [security]
+        capture_excluded_apps = ["org.keepassxc.KeePassXC", "org.example.Browser"]
+    "#;
+
+    let apps = Config::parse(text).unwrap().get_exhibitor_config().capture_excluded_apps;
+    assert_eq!(apps, vec!["org.keepassxc.KeePassXC".to_owned(), "org.example.Browser".to_owned()]);
+
+    assert!(Config::parse("").unwrap().get_exhibitor_config().capture_excluded_apps.is_empty());
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if IDs of applications bypassing key bindings are read.
+#[test]
+fn should_read_bypass_bindings_apps() {
+    let text = r#"
+        [input]
+        bypass_bindings_apps = ["virt-manager", "org.remmina.Remmina"]
+    "#;
+
+    let apps = Config::parse(text).unwrap().get_bypass_bindings_apps();
+    assert_eq!(apps, vec!["virt-manager".to_owned(), "org.remmina.Remmina".to_owned()]);
+
+    assert!(Config::parse("").unwrap().get_bypass_bindings_apps().is_empty());
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if keyboard configuration is read.
+#[test]
+fn should_read_keyboards() {
+    let text = r#"
+        [[keyboard]]
+        [[keyboard.remap]]
+        key = "CAPSLOCK"
+        to = ["ESC"]
+
+        [[keyboard]]
+        device = "AT Translated Set 2 keyboard"
+        [[keyboard.remap]]
+        key = "F12"
+        to = ["LEFTCTRL", "C"]
+    "#;
+
+    let keyboards = Config::parse(text).unwrap().get_keyboard_configs();
+    assert_eq!(keyboards.len(), 2);
+
+    assert_eq!(keyboards[0].device, None);
+    assert_eq!(keyboards[0].remaps,
+               vec![KeyRemapConfig {
+                        code: uinput_sys::KEY_CAPSLOCK as KeyCode,
+                        sequence: vec![uinput_sys::KEY_ESC as KeyCode],
+                    }]);
+
+    assert_eq!(keyboards[1].device, Some("AT Translated Set 2 keyboard".to_owned()));
+    assert_eq!(keyboards[1].remaps,
+               vec![KeyRemapConfig {
+                        code: uinput_sys::KEY_F12 as KeyCode,
+                        sequence: vec![uinput_sys::KEY_LEFTCTRL as KeyCode,
+                                       uinput_sys::KEY_C as KeyCode],
+                    }]);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if input settings for specific devices are read and applied to matching devices with more
+/// specific patterns taking precedence.
+#[test]
+fn should_read_input_devices() {
+    let text = r#"
+        [input]
+        touchpad_scale = 0.25
+        mouse_scale = 1.0
+
+        [input."046d:c52b Logitech USB Receiver"]
+        mouse_scale = 3.0
+
+        [input."Logitech USB Receiver"]
+        mouse_scale = 2.0
+        touchpad_pressure_threshold = 40
+        accel_profile = "flat"
+        natural_scrolling = true
+
+        [input."046D:C52B"]
+        touchpad_scale = 0.5
+    "#;
+
+    let config = Config::parse(text).unwrap();
+    let logitech = InputDeviceId {
+        vendor: 0x046d,
+        product: 0xc52b,
+        name: "Logitech USB Receiver".to_owned(),
+    };
+    let input = config.get_input_config_for_device(&logitech);
+    assert_eq!(input.mouse_scale, 3.0);
+    assert_eq!(input.touchpad_pressure_threshold, 40);
+    assert_eq!(input.touchpad_scale, 0.5);
+    assert_eq!(input.accel_profile, AccelProfile::Flat);
+    assert_eq!(input.natural_scrolling, true);
+    assert_eq!(input.scroll_method, ScrollMethod::TwoFinger);
+
+    let other = InputDeviceId {
+        vendor: 0x1234,
+        product: 0x5678,
+        name: "Other Mouse".to_owned(),
+    };
+    assert_eq!(config.get_input_config_for_device(&other), config.get_input_config());
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if switch actions are read.
+#[test]
+fn should_read_switches() {
+    let text = r#"
+        [[switch]]
+        name = "tablet_mode"
+        rotate = 90
+
+        [[switch]]
+        name = "dock"
+        rotate = 180
+    "#;
+
+    let switches = Config::parse(text).unwrap().get_exhibitor_config().switches;
+    assert_eq!(switches.len(), 2);
+    assert_eq!(switches[0].switch, Switch::TabletMode);
+    assert_eq!(switches[0].action, SwitchAction::RotateOutput(Transform::Rotated90));
+    assert_eq!(switches[1].switch, Switch::Dock);
+    assert_eq!(switches[1].action, SwitchAction::RotateOutput(Transform::Rotated180));
+
+    assert!(Config::parse("").unwrap().get_exhibitor_config().switches.is_empty());
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if wallpapers are read with `fill` mode by default.
+#[test]
+fn should_read_wallpapers() {
+    let text = r#"
+        [[wallpaper]]
+        path = "/home/user/mountains.jpg"
+
+        [[wallpaper]]
+        output = "HDMI-A-1"
+        path = "/home/user/pattern.png"
+        mode = "tile"
+    "#;
+
+    let wallpapers = Config::parse(text).unwrap().get_exhibitor_config().wallpapers;
+    assert_eq!(wallpapers.len(), 2);
+    assert_eq!(wallpapers[0].output, None);
+    assert_eq!(wallpapers[0].path, "/home/user/mountains.jpg");
+    assert_eq!(wallpapers[0].mode, WallpaperMode::Fill);
+    assert_eq!(wallpapers[1].output, Some("HDMI-A-1".to_owned()));
+    assert_eq!(wallpapers[1].path, "/home/user/pattern.png");
+    assert_eq!(wallpapers[1].mode, WallpaperMode::Tile);
+
+    assert!(Config::parse("").unwrap().get_exhibitor_config().wallpapers.is_empty());
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if automatic rotation settings are read.
+#[test]
+fn should_read_rotation() {
+    let default = Config::parse("").unwrap().get_exhibitor_config();
+    assert!(default.auto_rotation);
+    assert!(default.auto_rotation_output.is_none());
+
+    let text = r#"
+        [rotation]
+        automatic = false
+        output = "connector-30"
+    "#;
+
+    let config = Config::parse(text).unwrap().get_exhibitor_config();
+    assert!(!config.auto_rotation);
+    assert_eq!(config.auto_rotation_output, Some("connector-30".to_owned()));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if path to surface history file is read.
+#[test]
+fn should_read_history() {
+    let default = Config::parse("").unwrap().get_exhibitor_config();
+    assert!(default.history_file.is_none());
+
+    let text = r#"
+        [history]
+        file = "/tmp/perceptia/history"
+    "#;
+
+    let config = Config::parse(text).unwrap().get_exhibitor_config();
+    assert_eq!(config.history_file, Some("/tmp/perceptia/history".to_owned()));
+
+    assert!(Config::parse("[history]\nfile = \"\"").is_err());
+    assert!(Config::parse("[history]\nsize = 10").is_err());
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if mapping of touchscreens to outputs is read.
+#[test]
+fn should_read_touch_mapping() {
+    let text = r#"
+        [input."ELAN Touchscreen"]
+        output = "connector-30"
+        calibration = [0, -1, 1, 1, 0, 0]
+
+        [input."04f3:2234 ELAN Touchscreen"]
+        output = "connector-31"
+    "#;
+
+    let config = Config::parse(text).unwrap();
+    let elan = InputDeviceId {
+        vendor: 0x04f3,
+        product: 0x2234,
+        name: "ELAN Touchscreen".to_owned(),
+    };
+    let touch = config.get_touch_config_for_device(&elan);
+    assert_eq!(touch.output, Some("connector-31".to_owned()));
+    assert_eq!(touch.calibration, [0.0, -1.0, 1.0, 1.0, 0.0, 0.0]);
+    assert_eq!(touch.calibrate(0.25, 0.5), (0.5, 0.25));
+
+    let other = InputDeviceId {
+        vendor: 0x1234,
+        product: 0x5678,
+        name: "Other Touchscreen".to_owned(),
+    };
+    let touch = config.get_touch_config_for_device(&other);
+    assert!(touch.output.is_none());
+    assert_eq!(touch.calibrate(0.25, 0.5), (0.25, 0.5));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if seats are read and devices are assigned to them.
+#[test]
+fn should_read_seats() {
+    let text = r#"
+        [[seat]]
+        name = "second"
+        devices = ["046d:c52b", "Second Keyboard"]
+
+        [[seat]]
+        name = "third"
+        devices = ["Third Keyboard"]
+    "#;
+
+    let config = Config::parse(text).unwrap();
+    let seats = config.get_seat_configs();
+    assert_eq!(seats.len(), 2);
+    assert_eq!(seats[0].name, "second");
+    assert_eq!(seats[1].name, "third");
+
+    let logitech = InputDeviceId {
+        vendor: 0x046d,
+        product: 0xc52b,
+        name: "Logitech USB Receiver".to_owned(),
+    };
+    let third = InputDeviceId {
+        vendor: 0x1234,
+        product: 0x0001,
+        name: "Third Keyboard".to_owned(),
+    };
+    let other = InputDeviceId {
+        vendor: 0x1234,
+        product: 0x5678,
+        name: "Other Keyboard".to_owned(),
+    };
+    assert_eq!(config.get_seat_for_device(&logitech), SeatId::new(1));
+    assert_eq!(config.get_seat_for_device(&third), SeatId::new(2));
+    assert!(config.get_seat_for_device(&other).is_default());
+
+    assert!(Config::parse("").unwrap().get_seat_configs().is_empty());
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if invalid seat definitions are rejected.
+#[test]
+fn should_reject_invalid_seats() {
+    assert!(Config::parse("[[seat]]\ndevices = [\"Keyboard\"]").is_err());
+    assert!(Config::parse("[[seat]]\nname = \"seat0\"").is_err());
+    assert!(Config::parse("[[seat]]\nname = \"a\"\n[[seat]]\nname = \"a\"").is_err());
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if watchdog settings are read.
+#[test]
+fn should_read_watchdog() {
+    let default = Config::parse("").unwrap().get_watchdog_config();
+    assert_eq!(default.timeout, 10);
+    assert!(!default.restart);
+
+    let text = r#"
+        [watchdog]
+        timeout = 30
+        restart = true
+    "#;
+
+    let config = Config::parse(text).unwrap().get_watchdog_config();
+    assert_eq!(config.timeout, 30);
+    assert!(config.restart);
+
+    assert!(Config::parse("[watchdog]\ntimeout = -1").is_err());
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if idle settings are read.
+#[test]
+fn should_read_idle() {
+    assert_eq!(Config::parse("").unwrap().get_idle_config().timeout, 300);
+    assert_eq!(Config::parse("[idle]\ntimeout = 0").unwrap().get_idle_config().timeout, 0);
+    assert!(Config::parse("[idle]\ntimeout = -1").is_err());
+    assert!(Config::parse("[idle]\ndelay = 5").is_err());
+    assert!(Config::parse("").unwrap().get_idle_config().power_off);
+    assert!(!Config::parse("[idle]\npower_off = false").unwrap().get_idle_config().power_off);
+    assert!(Config::parse("[idle]\npower_off = 1").is_err());
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if cursor hiding settings are read and invalid ones rejected.
+#[test]
+fn should_read_cursor() {
+    let config = Config::parse("").unwrap().get_exhibitor_config();
+    assert_eq!(config.cursor.hide_timeout, 0);
+    assert!(!config.cursor.hide_while_typing);
+
+    let text = "[cursor]\nhide_timeout = 5\nhide_while_typing = true";
+    let config = Config::parse(text).unwrap().get_exhibitor_config();
+    assert_eq!(config.cursor.hide_timeout, 5);
+    assert!(config.cursor.hide_while_typing);
+
+    assert!(Config::parse("[cursor]\nhide_timeout = -1").is_err());
+    assert!(Config::parse("[cursor]\nhide_while_typing = 1").is_err());
+    assert!(Config::parse("[cursor]\nsize = 24").is_err());
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if settings of hiding layer surfaces are read and invalid ones rejected.
+#[test]
+fn should_read_auto_hide() {
+    let config = Config::parse("").unwrap().get_exhibitor_config();
+    assert!(config.auto_hide.namespaces.is_empty());
+    assert_eq!(config.auto_hide.reveal_delay, 300);
+
+    let text = "[auto_hide]\nnamespaces = [\"dock\"]\nreveal_delay = 100\nconceal_delay = 0\n\
+                animation_time = 50";
+    let config = Config::parse(text).unwrap().get_exhibitor_config();
+    assert_eq!(config.auto_hide.namespaces, vec!["dock".to_owned()]);
+    assert_eq!(config.auto_hide.reveal_delay, 100);
+    assert_eq!(config.auto_hide.conceal_delay, 0);
+    assert_eq!(config.auto_hide.animation_time, 50);
+
+    assert!(Config::parse("[auto_hide]\nnamespaces = \"dock\"").is_err());
+    assert!(Config::parse("[auto_hide]\nreveal_delay = -1").is_err());
+    assert!(Config::parse("[auto_hide]\nedge = \"bottom\"").is_err());
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if commands of media keys are read, merged with default ones and invalid ones rejected.
+#[test]
+fn should_read_media_keys() {
+    let default = Config::parse("").unwrap().get_media_keys_config();
+    assert!(default.mpris);
+    assert!(default.commands.contains_key(media_key::VOLUME_UP));
+    assert!(!default.commands.contains_key(media_key::PLAY_PAUSE));
+
+    let text = r#"
+        [media_keys]
+        play_pause = "playerctl play-pause"
+        mute = ""
+        mpris = false
+    "#;
+
+    let config = Config::parse(text).unwrap().get_media_keys_config();
+    assert!(!config.mpris);
+    assert_eq!(config.commands.get(media_key::PLAY_PAUSE),
+               Some(&"playerctl play-pause".to_owned()));
+    assert_eq!(config.commands.get(media_key::MUTE), Some(&String::new()));
+    assert_eq!(config.commands.get(media_key::VOLUME_UP),
+               default.commands.get(media_key::VOLUME_UP));
+
+    let bindings = Config::default().get_key_binding_config();
+    let binding = Binding::new(uinput_sys::KEY_PLAYPAUSE, modifier::NONE);
+    assert!(bindings.iter().any(|entry| {
+        entry.mode_name == mode_name::COMMON && entry.binding == binding
+    }));
+
+    assert!(Config::parse("[media_keys]\nbrightness_up = \"light -A 5\"").is_err());
+    assert!(Config::parse("[media_keys]\nnext = 1").is_err());
+    assert!(Config::parse("[media_keys]\nmpris = \"yes\"").is_err());
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if metrics endpoints are disabled by default and can be enabled.
+#[test]
+fn should_read_metrics() {
+    let default = Config::parse("").unwrap().get_metrics_config();
+    assert!(!default.socket);
+    assert_eq!(default.address, None);
+
+    let text = r#"
+        [metrics]
+        socket = true
+        address = "127.0.0.1:9100"
+    "#;
+
+    let config = Config::parse(text).unwrap().get_metrics_config();
+    assert!(config.socket);
+    assert_eq!(config.address, Some("127.0.0.1:9100".to_owned()));
+
+    assert!(Config::parse("[metrics]\naddress = \"\"").is_err());
+    assert!(Config::parse("[metrics]\nport = 9100").is_err());
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if window journal is disabled by default and can be enabled.
+#[test]
+fn should_read_journal() {
+    assert!(!Config::parse("").unwrap().get_journal_config().enabled);
+    assert!(Config::parse("[journal]\nenabled = true").unwrap().get_journal_config().enabled);
+    assert!(Config::parse("[journal]\nenabled = 1").is_err());
+    assert!(Config::parse("[journal]\npath = \"/tmp\"").is_err());
+}
+
+// -------------------------------------------------------------------------------------------------
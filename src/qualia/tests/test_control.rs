@@ -0,0 +1,134 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Tests for protocol of control socket.
+
+// -------------------------------------------------------------------------------------------------
+
+extern crate qualia;
+
+use qualia::{Area, Pixmap, SurfaceId};
+use qualia::{ControlReply, ControlRequest, ScreenshotTarget};
+use qualia::control;
+use qualia::memory::Buffer;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if screenshot requests are parsed and formatted back.
+#[test]
+fn should_parse_screenshot_requests() {
+    let requests = vec![("screenshot output HDMI-A-1",
+                         ScreenshotTarget::Output("HDMI-A-1".to_owned())),
+                        ("screenshot surface 7", ScreenshotTarget::Surface(SurfaceId::new(7))),
+                        ("screenshot region -10 20 300 400",
+                         ScreenshotTarget::Region(Area::create(-10, 20, 300, 400))),
+                        ("screenshot select", ScreenshotTarget::Selection)];
+
+    for (line, target) in requests {
+        let request = ControlRequest::Screenshot(target);
+        assert_eq!(ControlRequest::parse(line).unwrap(), request);
+        assert_eq!(ControlRequest::parse(&format!("  {}\n", line)).unwrap(), request);
+        assert_eq!(request.to_line(), line);
+    }
+
+    assert_eq!(ControlRequest::parse("select-region\n").unwrap(), ControlRequest::SelectRegion);
+    assert_eq!(ControlRequest::SelectRegion.to_line(), "select-region");
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if invalid requests are rejected.
+#[test]
+fn should_reject_invalid_requests() {
+    for line in ["",
+                 "restart",
+                 "screenshot",
+                 "screenshot output",
+                 "screenshot surface 0",
+                 "screenshot surface abc",
+                 "screenshot region 0 0 100",
+                 "screenshot region 0 0 0 100",
+                 "screenshot region 0 0 -1 100",
+                 "screenshot select 0 0",
+                 "select-region 0 0 10 10"]
+        .iter() {
+        assert!(ControlRequest::parse(line).is_err(), "'{}' should be rejected", line);
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if base64 encoding matches known values and decoding reverses it.
+#[test]
+fn should_encode_base64() {
+    let vectors = [("", ""),
+                   ("f", "Zg=="),
+                   ("fo", "Zm8="),
+                   ("foo", "Zm9v"),
+                   ("foob", "Zm9vYg=="),
+                   ("fooba", "Zm9vYmE="),
+                   ("foobar", "Zm9vYmFy")];
+
+    for &(data, encoded) in vectors.iter() {
+        assert_eq!(control::encode_base64(data.as_bytes()), encoded);
+        assert_eq!(control::decode_base64(encoded).unwrap(), data.as_bytes());
+    }
+
+    let data: Vec<u8> = (0..256).map(|i| i as u8).collect();
+    assert_eq!(control::decode_base64(&control::encode_base64(&data)).unwrap(), data);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if malformed base64 data is rejected.
+#[test]
+fn should_reject_invalid_base64() {
+    for text in ["Zg=", "Zm9", "Zg*=", "=g==", "Zm=v", "Zg==Zm9v"].iter() {
+        assert!(control::decode_base64(text).is_err(), "'{}' should be rejected", text);
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if replies are formatted and parsed back.
+#[test]
+fn should_parse_replies() {
+    let image = ControlReply::Image(vec![137, 80, 78, 71]);
+    assert_eq!(image.to_line(), "image iVBORw==");
+    assert_eq!(ControlReply::parse("image iVBORw==\n").unwrap(), image);
+
+    let error = ControlReply::Error("No output named 'X'".to_owned());
+    assert_eq!(error.to_line(), "error No output named 'X'");
+    assert_eq!(ControlReply::parse("error No output named 'X'\n").unwrap(), error);
+
+    let region = ControlReply::Region(Area::create(-5, 10, 200, 100));
+    assert_eq!(region.to_line(), "region -5 10 200 100");
+    assert_eq!(ControlReply::parse("region -5 10 200 100\n").unwrap(), region);
+
+    assert!(ControlReply::parse("").is_err());
+    assert!(ControlReply::parse("region 0 0 10").is_err());
+    assert!(ControlReply::parse("image ???").is_err());
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if cropping copies requested area and cuts off parts outside of the buffer.
+#[test]
+fn should_crop_buffer() {
+    let data: Vec<u8> = (0..48).map(|i| i as u8).collect();
+    let buffer = Buffer::new(3, 3, 16, data); // stride with padding of one pixel
+
+    let cropped = buffer.crop(&Area::create(1, 1, 2, 2));
+    assert_eq!(cropped.get_size(), qualia::Size::new(2, 2));
+    assert_eq!(cropped.get_stride(), 8);
+    assert_eq!(cropped.as_slice(),
+               &[20, 21, 22, 23, 24, 25, 26, 27, 36, 37, 38, 39, 40, 41, 42, 43][..]);
+
+    let cropped = buffer.crop(&Area::create(-1, 2, 3, 5));
+    assert_eq!(cropped.get_size(), qualia::Size::new(2, 1));
+    assert_eq!(cropped.as_slice(), &[32, 33, 34, 35, 36, 37, 38, 39][..]);
+
+    assert!(buffer.crop(&Area::create(3, 0, 2, 2)).is_empty());
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -7,7 +7,7 @@
 
 extern crate qualia;
 
-use self::qualia::defs::{Area, Position, Size};
+use self::qualia::defs::{Area, Margins, OutputMode, Position, Size, Vector};
 
 // -------------------------------------------------------------------------------------------------
 
@@ -140,3 +140,143 @@ fn should_inflate_area() {
 }
 
 // -------------------------------------------------------------------------------------------------
+
+/// Check if overlapping of areas is detected correctly. Areas only touching edges do not overlap.
+#[test]
+fn should_correctly_check_if_areas_overlap() {
+    let area = Area::create(10, 10, 10, 10);
+
+    let overlapping: [Area; 5] = [
+            Area::create(15, 15,  2,  2),
+            Area::create( 0,  0, 11, 11),
+            Area::create(19, 19, 10, 10),
+            Area::create( 0, 15, 30,  1),
+            Area::create( 0,  0, 40, 40),
+        ];
+
+    let separate: [Area; 5] = [
+            Area::create( 0,  0, 10, 10),
+            Area::create(20, 10, 10, 10),
+            Area::create(10, 20, 10, 10),
+            Area::create(30, 30,  5,  5),
+            Area::create(15, 15,  0,  0),
+        ];
+
+    for other in &overlapping {
+        assert!(area.is_overlapping(other), "{:?} should overlap {:?}", area, other);
+        assert!(other.is_overlapping(&area), "{:?} should overlap {:?}", other, area);
+    }
+
+    for other in &separate {
+        assert!(!area.is_overlapping(other), "{:?} should not overlap {:?}", area, other);
+        assert!(!other.is_overlapping(&area), "{:?} should not overlap {:?}", other, area);
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if intersection of areas is calculated correctly.
+#[test]
+fn should_correctly_intersect_areas() {
+    let area = Area::create(10, 10, 10, 10);
+
+    let intersections: [(Area, Option<Area>); 5] = [
+            (Area::create(15, 15,  2,  2), Some(Area::create(15, 15,  2,  2))),
+            (Area::create( 0,  0, 11, 11), Some(Area::create(10, 10,  1,  1))),
+            (Area::create(15,  0, 10, 40), Some(Area::create(15, 10,  5, 10))),
+            (Area::create( 0,  0, 40, 40), Some(Area::create(10, 10, 10, 10))),
+            (Area::create(20, 10, 10, 10), None),
+        ];
+
+    for pair in &intersections {
+        assert_eq!(area.intersected(&pair.0), pair.1);
+        assert_eq!(pair.0.intersected(&area), pair.1);
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if parts of area not covered by other area are calculated correctly.
+#[test]
+fn should_correctly_subtract_areas() {
+    let area = Area::create(10, 10, 10, 10);
+
+    assert_eq!(area.subtracted(&Area::create(20, 10, 10, 10)), vec![area]);
+    assert_eq!(area.subtracted(&Area::create(0, 0, 40, 40)), vec![]);
+    assert_eq!(area.subtracted(&Area::create(0, 15, 40, 40)),
+               vec![Area::create(10, 10, 10, 5)]);
+    assert_eq!(area.subtracted(&Area::create(12, 13, 2, 3)),
+               vec![Area::create(10, 10, 10, 3),
+                    Area::create(10, 16, 10, 4),
+                    Area::create(10, 13, 2, 3),
+                    Area::create(14, 13, 6, 3)]);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if scaled area covers all pixels covered by the original area.
+#[test]
+fn should_correctly_scale_areas() {
+    let area = Area::create(1, 3, 3, 2);
+
+    assert_eq!(area.scaled(1.0), area);
+    assert_eq!(area.scaled(2.0), Area::create(2, 6, 6, 4));
+    assert_eq!(area.scaled(1.5), Area::create(1, 4, 5, 4));
+    assert_eq!(Area::create(-3, 0, 2, 2).scaled(0.5), Area::create(-2, 0, 2, 1));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if margins correctly convert between inner and outer sizes.
+#[test]
+fn should_correctly_apply_margins() {
+    let margins = Margins::new(20, 2, 3, 4);
+
+    assert_eq!(margins.get_offset(), Vector::new(4, 20));
+    assert_eq!(margins.shrink(Size::new(100, 50)), Size::new(94, 27));
+    assert_eq!(margins.grow(Size::new(94, 27)), Size::new(100, 50));
+    assert_eq!(margins.shrink(Size::new(5, 10)), Size::new(0, 0));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if size is correctly limited by minimal and maximal size where zero means no limit.
+#[test]
+fn should_correctly_clamp_size() {
+    let min = Size::new(100, 0);
+    let max = Size::new(0, 300);
+
+    assert_eq!(Size::new(50, 500).clamped(min, max), Size::new(100, 300));
+    assert_eq!(Size::new(500, 50).clamped(min, max), Size::new(500, 50));
+    assert_eq!(Size::new(50, 50).clamped(Size::new(100, 100), Size::new(80, 80)),
+               Size::new(100, 100));
+    assert_eq!(Size::new(50, 50).clamped(Size::default(), Size::default()), Size::new(50, 50));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if output modes are parsed, printed and matched against requested modes where zero
+/// refresh rate matches any rate.
+#[test]
+fn should_correctly_parse_and_match_output_modes() {
+    let full_hd = OutputMode::new(Size::new(1920, 1080), 60);
+
+    assert_eq!(OutputMode::parse("1920x1080@60"), Some(full_hd));
+    assert_eq!(OutputMode::parse("1920x1080"),
+               Some(OutputMode::new(Size::new(1920, 1080), 0)));
+    assert_eq!(OutputMode::parse("1920x1080@0"), None);
+    assert_eq!(OutputMode::parse("1920x1080@"), None);
+    assert_eq!(OutputMode::parse("1920x@60"), None);
+    assert_eq!(OutputMode::parse("0x1080"), None);
+    assert_eq!(OutputMode::parse("1920x1080x60"), None);
+
+    assert_eq!(format!("{}", full_hd), "1920x1080@60");
+    assert_eq!(format!("{}", OutputMode::new(Size::new(800, 600), 0)), "800x600");
+
+    assert!(full_hd.matches(&OutputMode::new(Size::new(1920, 1080), 0)));
+    assert!(full_hd.matches(&OutputMode::new(Size::new(1920, 1080), 60)));
+    assert!(!full_hd.matches(&OutputMode::new(Size::new(1920, 1080), 50)));
+    assert!(!full_hd.matches(&OutputMode::new(Size::new(1280, 720), 0)));
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,80 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Tests for exporting frames to shared memory ring.
+
+// -------------------------------------------------------------------------------------------------
+
+extern crate qualia;
+
+use std::fs;
+use std::io::Read;
+
+use qualia::memory::Buffer;
+use qualia::frame_export::{self, FrameExport};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Reads little-endian `u32` at given offset.
+fn get_u32(data: &[u8], offset: usize) -> u32 {
+    (0..4).fold(0, |value, i| value | (data[offset + i] as u32) << (8 * i))
+}
+
+/// Reads little-endian `u64` at given offset.
+fn get_u64(data: &[u8], offset: usize) -> u64 {
+    (0..8).fold(0, |value, i| value | (data[offset + i] as u64) << (8 * i))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if frames are written to consecutive slots of the ring with header describing them,
+/// readers are notified about every frame and files are removed when export stops.
+#[test]
+fn should_export_frames() {
+    let dir = std::env::temp_dir();
+    let name = format!("perceptia-test-{}", std::process::id());
+    let mut export = FrameExport::new(&dir, &name, 2, 1, 8).unwrap();
+    let ring_path = export.get_ring_path().to_owned();
+    let pipe_path = export.get_pipe_path().to_owned();
+
+    let first = Buffer::new(2, 1, 8, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+    let second = Buffer::new(2, 1, 8, vec![9, 10, 11, 12, 13, 14, 15, 16]);
+    export.export(&first).unwrap();
+    export.export(&second).unwrap();
+    assert!(export.export(&Buffer::new(1, 1, 4, vec![0, 0, 0, 0])).is_err());
+
+    let mut ring = Vec::new();
+    fs::File::open(&ring_path).unwrap().read_to_end(&mut ring).unwrap();
+    assert_eq!(get_u32(&ring, 0), frame_export::MAGIC);
+    assert_eq!(get_u32(&ring, 4), frame_export::VERSION);
+    assert_eq!(get_u32(&ring, 8), frame_export::SLOT_COUNT as u32);
+    assert_eq!(get_u32(&ring, 12), 2);
+    assert_eq!(get_u32(&ring, 16), 1);
+    assert_eq!(get_u32(&ring, 20), 8);
+    assert_eq!(get_u32(&ring, 24), frame_export::FORMAT);
+
+    let entry = frame_export::HEADER_SIZE;
+    assert_eq!(get_u64(&ring, entry), 1);
+    assert_eq!(get_u64(&ring, entry + frame_export::SLOT_ENTRY_SIZE), 2);
+    assert_eq!(get_u64(&ring, entry + 2 * frame_export::SLOT_ENTRY_SIZE), 0);
+
+    let offset = frame_export::get_slot_offset(0, 8, 1);
+    assert_eq!(&ring[offset..offset + 8], &[1, 2, 3, 4, 5, 6, 7, 8]);
+    let offset = frame_export::get_slot_offset(1, 8, 1);
+    assert_eq!(&ring[offset..offset + 8], &[9, 10, 11, 12, 13, 14, 15, 16]);
+
+    // Export keeps the pipe open for writing so opening it for reading does not block
+    let mut pipe = fs::File::open(&pipe_path).unwrap();
+    let mut notifications = [0u8; 2 * frame_export::NOTIFICATION_SIZE];
+    pipe.read_exact(&mut notifications).unwrap();
+    assert_eq!(get_u64(&notifications, 0), 1);
+    assert_eq!(get_u32(&notifications, 8), 0);
+    assert_eq!(get_u64(&notifications, frame_export::NOTIFICATION_SIZE), 2);
+    assert_eq!(get_u32(&notifications, frame_export::NOTIFICATION_SIZE + 8), 1);
+
+    drop(export);
+    assert!(!ring_path.exists());
+    assert!(!pipe_path.exists());
+}
+
+// -------------------------------------------------------------------------------------------------
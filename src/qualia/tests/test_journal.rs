@@ -0,0 +1,44 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Tests for formatting window lifecycle journal entries.
+
+// -------------------------------------------------------------------------------------------------
+
+extern crate qualia;
+
+use std::time::Duration;
+
+use qualia::{JournalEntry, JournalEvent, SurfaceId};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if entry is formatted as tab-separated line with time in seconds.
+#[test]
+fn should_format_entry() {
+    let app_id = "org.example.Editor".to_owned();
+    let title = "notes.txt".to_owned();
+    let mut entry = JournalEntry::new(JournalEvent::Focus,
+                                      SurfaceId::new(7),
+                                      Some(&app_id),
+                                      Some(&title));
+    entry.time = Duration::new(1500000000, 42_000_000);
+
+    assert_eq!(entry.to_line(), "1500000000.042\tfocus\t7\torg.example.Editor\tnotes.txt\n");
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if missing information is left empty and separators are removed from text.
+#[test]
+fn should_sanitize_entry() {
+    let title = "a\tb\nc".to_owned();
+    let mut entry = JournalEntry::new(JournalEvent::Close, SurfaceId::new(3), None, Some(&title));
+    entry.time = Duration::new(10, 0);
+
+    assert_eq!(entry.app_id, "");
+    assert_eq!(entry.title, "a b c");
+    assert_eq!(entry.to_line(), "10.000\tclose\t3\t\ta b c\n");
+}
+
+// -------------------------------------------------------------------------------------------------
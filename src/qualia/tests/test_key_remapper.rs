@@ -0,0 +1,96 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Tests for translating keys before key bindings.
+
+// -------------------------------------------------------------------------------------------------
+
+extern crate qualia;
+extern crate uinput_sys;
+
+use qualia::{KeyboardConfig, KeyCode, KeyRemapConfig, KeyRemapper};
+
+// -------------------------------------------------------------------------------------------------
+
+const PRESSED: i32 = 1;
+const RELEASED: i32 = 0;
+
+/// Creates keyboard configuration remapping given keys.
+fn keyboard(device: Option<&str>, remaps: &[(i32, &[i32])]) -> KeyboardConfig {
+    KeyboardConfig {
+        device: device.map(|device| device.to_owned()),
+        remaps: remaps.iter()
+            .map(|&(code, sequence)| {
+                     KeyRemapConfig {
+                         code: code as KeyCode,
+                         sequence: sequence.iter().map(|code| *code as KeyCode).collect(),
+                     }
+                 })
+            .collect(),
+    }
+}
+
+/// Returns key code of given `uinput_sys` constant.
+fn key(code: i32) -> KeyCode {
+    code as KeyCode
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if keys not present in configuration are passed unchanged.
+#[test]
+fn should_pass_not_remapped_keys() {
+    let configs = [keyboard(None, &[(uinput_sys::KEY_CAPSLOCK, &[uinput_sys::KEY_ESC])])];
+    let remapper = KeyRemapper::new(&configs, "keyboard");
+    assert_eq!(remapper.remap(key(uinput_sys::KEY_A), PRESSED),
+               vec![(key(uinput_sys::KEY_A), PRESSED)]);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if key remapped to single key keeps its value.
+#[test]
+fn should_remap_key_to_key() {
+    let configs = [keyboard(None, &[(uinput_sys::KEY_CAPSLOCK, &[uinput_sys::KEY_ESC])])];
+    let remapper = KeyRemapper::new(&configs, "keyboard");
+    assert_eq!(remapper.remap(key(uinput_sys::KEY_CAPSLOCK), PRESSED),
+               vec![(key(uinput_sys::KEY_ESC), PRESSED)]);
+    assert_eq!(remapper.remap(key(uinput_sys::KEY_CAPSLOCK), RELEASED),
+               vec![(key(uinput_sys::KEY_ESC), RELEASED)]);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if macro types whole sequence on press and gives nothing on release.
+#[test]
+fn should_expand_macro_on_press() {
+    let sequence = [uinput_sys::KEY_LEFTCTRL, uinput_sys::KEY_C];
+    let configs = [keyboard(None, &[(uinput_sys::KEY_F12, &sequence)])];
+    let remapper = KeyRemapper::new(&configs, "keyboard");
+    assert_eq!(remapper.remap(key(uinput_sys::KEY_F12), PRESSED),
+               vec![(key(uinput_sys::KEY_LEFTCTRL), PRESSED),
+                    (key(uinput_sys::KEY_LEFTCTRL), RELEASED),
+                    (key(uinput_sys::KEY_C), PRESSED),
+                    (key(uinput_sys::KEY_C), RELEASED)]);
+    assert_eq!(remapper.remap(key(uinput_sys::KEY_F12), RELEASED), vec![]);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if entries for specific device override entries for all keyboards and do not apply to
+/// other devices.
+#[test]
+fn should_prefer_device_specific_entries() {
+    let configs = [keyboard(Some("laptop"), &[(uinput_sys::KEY_CAPSLOCK, &[uinput_sys::KEY_ESC])]),
+                   keyboard(None, &[(uinput_sys::KEY_CAPSLOCK, &[uinput_sys::KEY_LEFTCTRL])])];
+
+    let remapper = KeyRemapper::new(&configs, "laptop");
+    assert_eq!(remapper.remap(key(uinput_sys::KEY_CAPSLOCK), PRESSED),
+               vec![(key(uinput_sys::KEY_ESC), PRESSED)]);
+
+    let remapper = KeyRemapper::new(&configs, "external");
+    assert_eq!(remapper.remap(key(uinput_sys::KEY_CAPSLOCK), PRESSED),
+               vec![(key(uinput_sys::KEY_LEFTCTRL), PRESSED)]);
+}
+
+// -------------------------------------------------------------------------------------------------
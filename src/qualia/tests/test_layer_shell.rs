@@ -0,0 +1,172 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Tests for placement of layer surfaces.
+
+// -------------------------------------------------------------------------------------------------
+
+extern crate dharma;
+extern crate qualia;
+
+use qualia::{Area, Coordinator, Margins, Size, SurfaceId};
+use qualia::{KeyboardInteractivity, Layer, LayerArrangement, LayerSurface};
+use qualia::positioner::edges;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Constructs layer surface with given size and anchor.
+fn make_surface(size: Size, anchor: edges::Edges) -> LayerSurface {
+    let mut surface = LayerSurface::new(SurfaceId::new(1), None, Layer::Top, "test".to_owned());
+    surface.size = size;
+    surface.anchor = anchor;
+    surface
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if surface anchored to opposite edges with zero size is stretched between them.
+#[test]
+fn should_stretch_between_opposite_edges() {
+    let output = Area::create(100, 0, 1000, 800);
+
+    let mut bar = make_surface(Size::new(0, 30), edges::TOP | edges::LEFT | edges::RIGHT);
+    assert_eq!(bar.calculate_area(output), Area::create(100, 0, 1000, 30));
+
+    bar.margins = Margins::new(5, 10, 0, 20);
+    assert_eq!(bar.calculate_area(output), Area::create(120, 5, 970, 30));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if surface is placed at anchored edges or centered if not anchored.
+#[test]
+fn should_place_at_anchored_edges() {
+    let output = Area::create(0, 0, 1000, 800);
+
+    let mut surface = make_surface(Size::new(200, 100), edges::BOTTOM | edges::RIGHT);
+    surface.margins = Margins::new(0, 10, 20, 0);
+    assert_eq!(surface.calculate_area(output), Area::create(790, 680, 200, 100));
+
+    let surface = make_surface(Size::new(200, 100), edges::NONE);
+    assert_eq!(surface.calculate_area(output), Area::create(400, 350, 200, 100));
+
+    let surface = make_surface(Size::new(200, 100), edges::LEFT | edges::RIGHT);
+    assert_eq!(surface.calculate_area(output), Area::create(400, 350, 200, 100));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if only surfaces in top and overlay layers can take keyboard focus exclusively.
+#[test]
+fn should_be_keyboard_exclusive_only_above_windows() {
+    let mut surface = make_surface(Size::new(200, 100), edges::NONE);
+    assert!(!surface.is_keyboard_exclusive());
+
+    surface.keyboard_interactivity = KeyboardInteractivity::Exclusive;
+    assert!(surface.is_keyboard_exclusive());
+
+    surface.layer = Layer::Overlay;
+    assert!(surface.is_keyboard_exclusive());
+
+    surface.layer = Layer::Bottom;
+    assert!(!surface.is_keyboard_exclusive());
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if keyboard focus follows interactivity of layer surfaces: surfaces without
+/// interactivity are never focused, exclusive surface keeps focus until it is gone and focus from
+/// before is restored afterwards.
+#[test]
+fn should_focus_layer_surfaces_according_to_interactivity() {
+    let mut coordinator = Coordinator::new(dharma::Signaler::new());
+    let window = coordinator.create_surface();
+    let other_window = coordinator.create_surface();
+    let bar = coordinator.create_surface();
+    let launcher = coordinator.create_surface();
+    coordinator.set_keyboard_focus(window);
+
+    let mut bar_state = LayerSurface::new(bar, Some(1), Layer::Top, "bar".to_owned());
+    coordinator.set_layer_surface(bar_state.clone());
+    coordinator.set_keyboard_focus(bar);
+    assert_eq!(coordinator.get_keyboard_focused_sid(), window);
+
+    let mut launcher_state = LayerSurface::new(launcher, None, Layer::Overlay, "menu".to_owned());
+    launcher_state.keyboard_interactivity = KeyboardInteractivity::Exclusive;
+    coordinator.set_layer_surface(launcher_state);
+    assert_eq!(coordinator.get_keyboard_focused_sid(), launcher);
+
+    coordinator.set_keyboard_focus(other_window);
+    assert_eq!(coordinator.get_keyboard_focused_sid(), launcher);
+
+    coordinator.remove_layer_surface(launcher);
+    assert_eq!(coordinator.get_keyboard_focused_sid(), other_window);
+
+    bar_state.keyboard_interactivity = KeyboardInteractivity::OnDemand;
+    coordinator.set_layer_surface(bar_state);
+    coordinator.set_keyboard_focus(bar);
+    assert_eq!(coordinator.get_keyboard_focused_sid(), bar);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if surface reserves space only if it is anchored to one edge alone or together with both
+/// perpendicular edges.
+#[test]
+fn should_reserve_space_along_single_edge() {
+    let mut surface = make_surface(Size::new(0, 30), edges::TOP | edges::LEFT | edges::RIGHT);
+    assert_eq!(surface.get_exclusive_edge(), None);
+    assert_eq!(surface.get_anchored_edge(), Some(edges::TOP));
+
+    surface.exclusive_zone = 30;
+    assert_eq!(surface.get_exclusive_edge(), Some(edges::TOP));
+
+    surface.anchor = edges::RIGHT;
+    assert_eq!(surface.get_exclusive_edge(), Some(edges::RIGHT));
+
+    surface.anchor = edges::TOP | edges::LEFT;
+    assert_eq!(surface.get_exclusive_edge(), None);
+    assert_eq!(surface.get_anchored_edge(), None);
+
+    surface.anchor = edges::TOP | edges::BOTTOM;
+    assert_eq!(surface.get_exclusive_edge(), None);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if surfaces reserving space are placed from the uppermost layer in space left by previous
+/// ones, surfaces with zero exclusive zone are placed in space left for windows and surfaces with
+/// negative exclusive zone on whole output.
+#[test]
+fn should_arrange_surfaces_around_exclusive_zones() {
+    let output = Area::create(0, 0, 1000, 800);
+
+    let mut wallpaper = make_surface(Size::default(), edges::Edges::all());
+    wallpaper.sid = SurfaceId::new(4);
+    wallpaper.layer = Layer::Background;
+    wallpaper.exclusive_zone = -1;
+
+    let mut dock = make_surface(Size::new(50, 0), edges::LEFT | edges::TOP | edges::BOTTOM);
+    dock.sid = SurfaceId::new(2);
+    dock.layer = Layer::Bottom;
+    dock.exclusive_zone = 50;
+
+    let mut notification = make_surface(Size::new(200, 100), edges::TOP | edges::RIGHT);
+    notification.sid = SurfaceId::new(3);
+    notification.layer = Layer::Overlay;
+
+    let mut bar = make_surface(Size::new(0, 30), edges::TOP | edges::LEFT | edges::RIGHT);
+    bar.margins = Margins::new(5, 0, 0, 0);
+    bar.exclusive_zone = 30;
+
+    let surfaces = vec![wallpaper, dock, notification, bar];
+    let arrangement = LayerArrangement::new(output, &surfaces);
+    assert_eq!(arrangement.usable_area, Area::create(50, 35, 950, 765));
+    assert_eq!(arrangement.get_area(SurfaceId::new(1)), Some(Area::create(0, 5, 1000, 30)));
+    assert_eq!(arrangement.get_area(SurfaceId::new(2)), Some(Area::create(0, 35, 50, 765)));
+    assert_eq!(arrangement.get_area(SurfaceId::new(3)), Some(Area::create(800, 35, 200, 100)));
+    assert_eq!(arrangement.get_area(SurfaceId::new(4)), Some(output));
+    assert_eq!(arrangement.get_area(SurfaceId::new(5)), None);
+}
+
+// -------------------------------------------------------------------------------------------------
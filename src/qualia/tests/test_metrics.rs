@@ -0,0 +1,66 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Tests for formatting compositor metrics.
+
+// -------------------------------------------------------------------------------------------------
+
+extern crate qualia;
+
+use std::time::Duration;
+
+use qualia::{Metrics, StateSnapshot};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if frame times are counted in cumulative buckets and state is reported as gauges.
+#[test]
+fn should_format_metrics() {
+    let mut metrics = Metrics::new();
+    metrics.record_frame("HDMI-A-1", Duration::from_millis(3));
+    metrics.record_frame("HDMI-A-1", Duration::from_millis(20));
+    metrics.record_frame("HDMI-A-1", Duration::from_millis(500));
+    metrics.set_client_count(4);
+
+    let snapshot = StateSnapshot {
+        surfaces: 7,
+        memory_pools: 2,
+        memory_pool_bytes: 8192,
+        hw_images: 1,
+    };
+    let text = metrics.format(&snapshot);
+    let lines: Vec<&str> = text.lines().collect();
+
+    let name = "perceptia_frame_render_seconds";
+    assert!(lines.contains(&"# TYPE perceptia_frame_render_seconds histogram"));
+    assert!(lines.contains(&format!("{}_bucket{{output=\"HDMI-A-1\",le=\"0.002\"}} 0", name)
+        .as_str()));
+    assert!(lines.contains(&format!("{}_bucket{{output=\"HDMI-A-1\",le=\"0.004\"}} 1", name)
+        .as_str()));
+    assert!(lines.contains(&format!("{}_bucket{{output=\"HDMI-A-1\",le=\"0.033\"}} 2", name)
+        .as_str()));
+    assert!(lines.contains(&format!("{}_bucket{{output=\"HDMI-A-1\",le=\"0.1\"}} 2", name)
+        .as_str()));
+    assert!(lines.contains(&format!("{}_bucket{{output=\"HDMI-A-1\",le=\"+Inf\"}} 3", name)
+        .as_str()));
+    assert!(lines.contains(&format!("{}_count{{output=\"HDMI-A-1\"}} 3", name).as_str()));
+
+    assert!(lines.contains(&"# TYPE perceptia_clients gauge"));
+    assert!(lines.contains(&"perceptia_clients 4"));
+    assert!(lines.contains(&"perceptia_surfaces 7"));
+    assert!(lines.contains(&"perceptia_memory_pools 2"));
+    assert!(lines.contains(&"perceptia_memory_pool_bytes 8192"));
+    assert!(lines.contains(&"perceptia_hw_images 1"));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if histogram has no samples before first frame is drawn.
+#[test]
+fn should_format_empty_metrics() {
+    let text = Metrics::new().format(&StateSnapshot::default());
+    assert!(!text.contains("_bucket"));
+    assert!(text.lines().any(|line| line == "perceptia_surfaces 0"));
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,44 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Tests for PNG encoder.
+
+// -------------------------------------------------------------------------------------------------
+
+extern crate qualia;
+
+use qualia::memory::Buffer;
+use qualia::png;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if checksums match known values.
+#[test]
+fn should_calculate_checksums() {
+    assert_eq!(png::crc32(b"IEND"), 0xAE42_6082);
+    assert_eq!(png::adler32(b"Wikipedia"), 0x11E6_0398);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if image is encoded with header describing its size and with alpha channel dropped.
+#[test]
+fn should_encode_image() {
+    let data = vec![1, 2, 3, 255, 4, 5, 6, 255, 0, 0, 0, 0, // first row with padding
+                    7, 8, 9, 255, 10, 11, 12, 255, 0, 0, 0, 0];
+    let encoded = png::encode(&Buffer::new(2, 2, 12, data));
+
+    assert_eq!(&encoded[0..8], &[137, 80, 78, 71, 13, 10, 26, 10]);
+    assert_eq!(&encoded[12..16], b"IHDR");
+    assert_eq!(&encoded[16..24], &[0, 0, 0, 2, 0, 0, 0, 2]);
+
+    // Two rows of filter type and two pixels in one uncompressed block
+    let raw = [0, 1, 2, 3, 4, 5, 6, 0, 7, 8, 9, 10, 11, 12];
+    assert_eq!(&encoded[37..41], b"IDAT");
+    assert_eq!(&encoded[41..48], &[0x78, 0x01, 0x01, 14, 0, !14, 0xFF]);
+    assert_eq!(&encoded[48..62], &raw);
+    assert_eq!(&encoded[74..78], b"IEND");
+    assert_eq!(encoded.len(), 82);
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,118 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Tests for placing pop-ups.
+
+// -------------------------------------------------------------------------------------------------
+
+extern crate qualia;
+
+use qualia::defs::{Area, Position, Size};
+use qualia::positioner::{edges, Positioner};
+use qualia::positioner::constraint_adjustment::{self, ConstraintAdjustment};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Bounds used in tests.
+fn bounds() -> Area {
+    Area::create(0, 0, 1000, 800)
+}
+
+/// Creates positioner of pop-up menu opening from bottom-right corner of given anchor rectangle.
+fn menu_positioner(anchor_rect: Area) -> Positioner {
+    let mut positioner = Positioner::new();
+    positioner.size = Size::new(200, 300);
+    positioner.anchor_rect = anchor_rect;
+    positioner.anchor = edges::BOTTOM | edges::RIGHT;
+    positioner.gravity = edges::BOTTOM | edges::RIGHT;
+    positioner
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if pop-up is placed at anchor point, extends in direction of gravity and is moved by
+/// offset.
+#[test]
+fn should_place_at_anchor_point() {
+    let mut positioner = menu_positioner(Area::create(100, 50, 40, 20));
+    assert_eq!(positioner.get_area(), Area::create(140, 70, 200, 300));
+
+    positioner.anchor = edges::TOP;
+    positioner.gravity = edges::TOP | edges::LEFT;
+    positioner.offset = Position::new(5, -5);
+    assert_eq!(positioner.get_area(), Area::create(-75, -255, 200, 300));
+
+    positioner.anchor = edges::NONE;
+    positioner.gravity = edges::NONE;
+    positioner.offset = Position::default();
+    assert_eq!(positioner.get_area(), Area::create(20, -90, 200, 300));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if pop-up fitting in bounds is not adjusted.
+#[test]
+fn should_not_adjust_fitting_popup() {
+    let mut positioner = menu_positioner(Area::create(100, 50, 40, 20));
+    positioner.constraint_adjustment = ConstraintAdjustment::all();
+    assert_eq!(positioner.get_constrained_area(bounds()), positioner.get_area());
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if pop-up is flipped to other side of anchor rectangle when it does not fit.
+#[test]
+fn should_flip_constrained_popup() {
+    let mut positioner = menu_positioner(Area::create(900, 600, 40, 20));
+    positioner.constraint_adjustment = constraint_adjustment::FLIP_X |
+                                       constraint_adjustment::FLIP_Y;
+    assert_eq!(positioner.get_constrained_area(bounds()), Area::create(700, 300, 200, 300));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if pop-up is slid along the bounds when flipping is not allowed or does not help.
+#[test]
+fn should_slide_when_flip_does_not_help() {
+    let mut positioner = menu_positioner(Area::create(900, 0, 100, 700));
+    positioner.constraint_adjustment = constraint_adjustment::FLIP_Y |
+                                       constraint_adjustment::SLIDE_X |
+                                       constraint_adjustment::SLIDE_Y;
+    assert_eq!(positioner.get_constrained_area(bounds()), Area::create(800, 500, 200, 300));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if pop-up is resized to fit in bounds when other adjustments are not allowed.
+#[test]
+fn should_resize_constrained_popup() {
+    let mut positioner = menu_positioner(Area::create(900, 600, 40, 20));
+    positioner.constraint_adjustment = constraint_adjustment::RESIZE_X |
+                                       constraint_adjustment::RESIZE_Y;
+    assert_eq!(positioner.get_constrained_area(bounds()), Area::create(940, 620, 60, 180));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if pop-up is left unconstrained if no adjustment is allowed.
+#[test]
+fn should_not_adjust_without_allowed_adjustments() {
+    let positioner = menu_positioner(Area::create(900, 600, 40, 20));
+    assert_eq!(positioner.get_constrained_area(bounds()), Area::create(940, 620, 200, 300));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if input method pop-up is placed below text cursor and is flipped above it and slid
+/// horizontally when it does not fit on screen.
+#[test]
+fn should_place_input_method_popup_at_text_cursor() {
+    let size = Size::new(200, 100);
+    let positioner = Positioner::for_text_cursor(size, Area::create(100, 50, 2, 20));
+    assert_eq!(positioner.get_constrained_area(bounds()), Area::create(100, 70, 200, 100));
+
+    let positioner = Positioner::for_text_cursor(size, Area::create(950, 700, 2, 20));
+    assert_eq!(positioner.get_constrained_area(bounds()), Area::create(800, 600, 200, 100));
+}
+
+// -------------------------------------------------------------------------------------------------
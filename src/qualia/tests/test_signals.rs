@@ -0,0 +1,58 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Tests for typed signals.
+
+// -------------------------------------------------------------------------------------------------
+
+extern crate dharma;
+extern crate qualia;
+
+use dharma::Signal;
+use qualia::{perceptron, signals, Perceptron, Switch};
+use qualia::defs::SurfaceId;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if typed signals have the same IDs as corresponding `perceptron` constants.
+#[test]
+fn should_have_ids_of_perceptron_signals() {
+    assert_eq!(signals::Notify::id(), perceptron::NOTIFY);
+    assert_eq!(signals::SurfaceReady::id(), perceptron::SURFACE_READY);
+    assert_eq!(signals::SwitchToggled::id(), perceptron::SWITCH_TOGGLED);
+    assert_eq!(signals::GestureEnd::id(), perceptron::GESTURE_END);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if data wrapped by typed signal can be unwrapped back.
+#[test]
+fn should_unwrap_wrapped_data() {
+    let sid = SurfaceId::new(3);
+    match signals::SurfaceReady::wrap(sid) {
+        Perceptron::SurfaceReady(wrapped_sid) => assert_eq!(wrapped_sid, sid),
+        _ => panic!("Wrong variant"),
+    }
+
+    let package = signals::SurfaceReady::wrap(sid);
+    assert_eq!(signals::SurfaceReady::unwrap(package), Some(sid));
+
+    let package = signals::SwitchToggled::wrap((Switch::Lid, true));
+    assert_eq!(signals::SwitchToggled::unwrap(package), Some((Switch::Lid, true)));
+
+    let package = signals::Notify::wrap(());
+    assert_eq!(signals::Notify::unwrap(package), Some(()));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if typed signal refuses to unwrap data of other signals.
+#[test]
+fn should_not_unwrap_data_of_other_signals() {
+    let sid = SurfaceId::new(3);
+    assert_eq!(signals::SurfaceDestroyed::unwrap(Perceptron::SurfaceReady(sid)), None);
+    assert_eq!(signals::SurfaceReady::unwrap(Perceptron::Notify), None);
+    assert_eq!(signals::Notify::unwrap(Perceptron::SurfaceReady(sid)), None);
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,350 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Tests for surface state.
+
+// -------------------------------------------------------------------------------------------------
+
+extern crate qualia;
+
+use qualia::defs::{Area, Margins, Position, Size};
+use qualia::enums::{DecorationMode, SizeMismatchPolicy, Transform};
+use qualia::memory::{AlphaMode, Buffer, MemoryPool, PixelFormat};
+use qualia::surface::{BufferTransform, Surface, SurfaceId, SurfacePlacement, surface_state};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if window geometry is applied only after commit and defines offset and requested size.
+#[test]
+fn should_apply_window_geometry_on_commit() {
+    let mut surface = Surface::new(&SurfaceId::new(1));
+    let geometry = Area::new(Position::new(10, 20), Size::new(300, 200));
+
+    surface.set_window_geometry(geometry);
+    let info = surface.get_info();
+    assert_eq!(info.window_geometry, None);
+    assert_eq!(info.offset, Position::default());
+    assert_eq!(info.requested_size, Size::default());
+
+    surface.commit();
+    let info = surface.get_info();
+    assert_eq!(info.window_geometry, Some(geometry));
+    assert_eq!(info.offset, Position::new(10, 20));
+    assert_eq!(info.requested_size, Size::new(300, 200));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if size limits are reported in surface information.
+#[test]
+fn should_report_size_limits() {
+    let mut surface = Surface::new(&SurfaceId::new(1));
+    surface.set_min_size(Size::new(100, 50));
+    surface.set_max_size(Size::new(800, 600));
+
+    let info = surface.get_info();
+    assert_eq!(info.min_size, Size::new(100, 50));
+    assert_eq!(info.max_size, Size::new(800, 600));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if application ID and exclusion from captures are reported in surface information.
+#[test]
+fn should_report_capture_exclusion() {
+    let mut surface = Surface::new(&SurfaceId::new(1));
+    let info = surface.get_info();
+    assert_eq!(info.app_id, None);
+    assert!(!info.capture_excluded);
+
+    surface.set_app_id("org.example.Browser".to_owned());
+    surface.set_capture_excluded(true);
+    let info = surface.get_info();
+    assert_eq!(info.app_id, Some("org.example.Browser".to_owned()));
+    assert!(info.capture_excluded);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if alpha mode of attached buffer is reported and overridden by opaque region covering the
+/// whole buffer.
+#[test]
+fn should_report_alpha_mode() {
+    let mut surface = Surface::new(&SurfaceId::new(1));
+    let pool = MemoryPool::new_from_buffer(Buffer::new(4, 2, 16, vec![0; 32]));
+    let format = PixelFormat::Argb8888;
+    surface.attach(pool.get_memory_view(0, 4, 2, 16, format, AlphaMode::Premultiplied));
+    surface.commit();
+    assert_eq!(surface.get_info().alpha_mode, AlphaMode::Premultiplied);
+
+    surface.set_opaque_region(Some(Area::new(Position::new(0, 0), Size::new(4, 1))));
+    assert_eq!(surface.get_info().alpha_mode, AlphaMode::Premultiplied);
+
+    surface.set_opaque_region(Some(Area::new(Position::new(-1, -1), Size::new(10, 10))));
+    assert_eq!(surface.get_info().alpha_mode, AlphaMode::Opaque);
+
+    surface.set_opaque_region(None);
+    assert_eq!(surface.get_info().alpha_mode, AlphaMode::Premultiplied);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if commits of contents are counted and if damage left after drawing is relative to the
+/// drawn generation.
+#[test]
+fn should_count_generations_of_contents() {
+    let mut surface = Surface::new(&SurfaceId::new(1));
+    surface.commit();
+    assert_eq!(surface.get_renderer_context().generation, 0);
+
+    let pool = MemoryPool::new_from_buffer(Buffer::new(4, 2, 16, vec![0; 32]));
+    let format = PixelFormat::Argb8888;
+    surface.attach(pool.get_memory_view(0, 4, 2, 16, format, AlphaMode::Premultiplied));
+    surface.commit();
+    let drawn = surface.get_renderer_context();
+    assert_eq!(drawn.generation, 1);
+    assert_eq!(drawn.damage_generation, 0);
+    assert_eq!(drawn.damage, vec![Area::create(0, 0, 4, 2)]);
+
+    surface.damage(Area::create(1, 1, 1, 1));
+    surface.commit();
+    surface.discard_damage(&drawn.damage, drawn.generation);
+    let context = surface.get_renderer_context();
+    assert_eq!(context.generation, 2);
+    assert_eq!(context.damage_generation, 1);
+    assert_eq!(context.damage, vec![Area::create(1, 1, 1, 1)]);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if buffer scale and transformation are applied only after commit, define size of the
+/// surface and if damage given in buffer coordinates is translated to surface coordinates.
+#[test]
+fn should_apply_buffer_scale_and_transform_on_commit() {
+    let mut surface = Surface::new(&SurfaceId::new(1));
+    let pool = MemoryPool::new_from_buffer(Buffer::new(8, 4, 32, vec![0; 128]));
+    let format = PixelFormat::Argb8888;
+    surface.attach(pool.get_memory_view(0, 8, 4, 32, format, AlphaMode::Premultiplied));
+    surface.set_buffer_scale(2);
+    surface.set_buffer_transform(Transform::Rotated90);
+    assert_eq!(surface.get_info().buffer_transform, BufferTransform::default());
+
+    surface.commit();
+    let drawn = surface.get_renderer_context();
+    assert_eq!(surface.get_info().buffer_transform,
+               BufferTransform::new(2, Transform::Rotated90));
+    assert_eq!(surface.get_size(), Some(Size::new(2, 4)));
+    assert_eq!(surface.get_info().requested_size, Size::new(2, 4));
+    assert_eq!(drawn.damage, vec![Area::create(0, 0, 2, 4)]);
+
+    surface.damage_buffer(Area::create(2, 0, 2, 2));
+    surface.commit();
+    surface.discard_damage(&drawn.damage, drawn.generation);
+    assert_eq!(surface.get_renderer_context().damage, vec![Area::create(1, 1, 1, 1)]);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if areas are translated between surface and buffer coordinates consistently for all
+/// transformations and if corners of surface are mapped to corners of buffer.
+#[test]
+fn should_translate_between_surface_and_buffer_coordinates() {
+    let transforms = [Transform::Normal,
+                      Transform::Rotated90,
+                      Transform::Rotated180,
+                      Transform::Rotated270,
+                      Transform::Flipped,
+                      Transform::Flipped90,
+                      Transform::Flipped180,
+                      Transform::Flipped270];
+    let buffer_size = Size::new(60, 40);
+    let area = Area::create(2, 4, 10, 6);
+
+    for transform in transforms.iter() {
+        let buffer_transform = BufferTransform::new(2, *transform);
+        let surface_size = buffer_transform.get_surface_size(buffer_size);
+        if transform.is_transposing() {
+            assert_eq!(surface_size, Size::new(20, 30));
+        } else {
+            assert_eq!(surface_size, Size::new(30, 20));
+        }
+
+        let buffer_area = buffer_transform.to_buffer_area(&area, surface_size);
+        assert_eq!(buffer_area.size.width * buffer_area.size.height,
+                   4 * area.size.width * area.size.height);
+        assert_eq!(buffer_transform.to_surface_area(&buffer_area, buffer_size), area);
+
+        let origin = buffer_transform.to_buffer_position(Position::new(0, 0), surface_size);
+        assert!((origin.x == 0 || origin.x == 60) && (origin.y == 0 || origin.y == 40));
+    }
+
+    let rotated = BufferTransform::new(1, Transform::Rotated90);
+    let surface_size = Size::new(40, 60);
+    assert_eq!(rotated.to_buffer_position(Position::new(0, 0), surface_size),
+               Position::new(0, 40));
+    assert_eq!(rotated.to_buffer_position(Position::new(40, 0), surface_size),
+               Position::new(0, 0));
+    assert_eq!(rotated.to_buffer_area(&Area::create(0, 0, 10, 20), surface_size),
+               Area::create(0, 30, 20, 10));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if title and decoration are reported in surface information and if desired size is
+/// adjusted to space left by decoration.
+#[test]
+fn should_report_title_and_decoration() {
+    let mut surface = Surface::new(&SurfaceId::new(1));
+    surface.set_title("Terminal".to_owned());
+    surface.set_desired_size(Size::new(400, 300));
+    surface.set_decoration(SurfaceId::new(2), Margins::new(22, 2, 2, 2));
+
+    let info = surface.get_info();
+    assert_eq!(info.title, Some("Terminal".to_owned()));
+    assert_eq!(info.decoration_sid, SurfaceId::new(2));
+    assert_eq!(info.margins, Margins::new(22, 2, 2, 2));
+    assert_eq!(info.desired_size, Size::new(396, 276));
+
+    surface.set_decoration(SurfaceId::invalid(), Margins::default());
+    let info = surface.get_info();
+    assert_eq!(info.decoration_sid, SurfaceId::invalid());
+    assert_eq!(info.desired_size, Size::new(400, 300));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if decoration mode requested by client is reported separately from the chosen one.
+#[test]
+fn should_report_decoration_mode() {
+    let mut surface = Surface::new(&SurfaceId::new(1));
+    let info = surface.get_info();
+    assert_eq!(info.requested_decoration_mode, None);
+    assert_eq!(info.decoration_mode, DecorationMode::ClientSide);
+
+    surface.set_requested_decoration_mode(Some(DecorationMode::ServerSide));
+    let info = surface.get_info();
+    assert_eq!(info.requested_decoration_mode, Some(DecorationMode::ServerSide));
+    assert_eq!(info.decoration_mode, DecorationMode::ClientSide);
+
+    surface.set_decoration_mode(DecorationMode::ServerSide);
+    let info = surface.get_info();
+    assert_eq!(info.decoration_mode, DecorationMode::ServerSide);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if only surfaces with size decided by compositor have space reserved in rendering context.
+#[test]
+fn should_report_bounds_of_constrained_surfaces() {
+    let mut surface = Surface::new(&SurfaceId::new(1));
+    surface.set_desired_size(Size::new(400, 300));
+    assert_eq!(surface.get_renderer_context().bounds, None);
+
+    surface.set_state_flags(surface_state::TILED);
+    assert_eq!(surface.get_renderer_context().bounds, Some(Size::new(400, 300)));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if whole buffer is drawn as is if window matches reserved space or no space is reserved.
+#[test]
+fn should_place_matching_surface_naturally() {
+    let buffer = Size::new(340, 240);
+    let offset = Position::new(20, 20);
+    let window = Size::new(300, 200);
+    let expected = SurfacePlacement {
+        source: Area::new(Position::new(0, 0), buffer),
+        target: Area::new(Position::new(-20, -20), buffer),
+        window: Area::new(Position::new(0, 0), window),
+    };
+
+    let policies = [SizeMismatchPolicy::Clip,
+                    SizeMismatchPolicy::Center,
+                    SizeMismatchPolicy::Scale];
+    for policy in policies.iter() {
+        assert_eq!(SurfacePlacement::new(*policy, buffer, offset, window, Some(window)), expected);
+        assert_eq!(SurfacePlacement::new(*policy, buffer, offset, window, None), expected);
+    }
+
+    let bounds = Some(Size::new(400, 300));
+    assert_eq!(SurfacePlacement::new(SizeMismatchPolicy::Clip, buffer, offset, window, bounds),
+               expected);
+    assert_eq!(expected.to_surface(Position::new(10, 10)), Position::new(30, 30));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if window exceeding reserved space is cut off.
+#[test]
+fn should_clip_surface() {
+    let placement = SurfacePlacement::new(SizeMismatchPolicy::Clip,
+                                          Size::new(340, 240),
+                                          Position::new(20, 20),
+                                          Size::new(300, 200),
+                                          Some(Size::new(200, 250)));
+
+    assert_eq!(placement.source, Area::create(20, 20, 200, 200));
+    assert_eq!(placement.target, Area::create(0, 0, 200, 200));
+    assert_eq!(placement.window, Area::create(0, 0, 200, 200));
+    assert_eq!(placement.to_surface(Position::new(10, 10)), Position::new(30, 30));
+    assert_eq!(placement.map_damage(&Area::create(0, 0, 10, 10)), None);
+    assert_eq!(placement.map_damage(&Area::create(200, 30, 40, 10)),
+               Some(Area::create(180, 10, 20, 10)));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if window is centered in reserved space and cut off equally on both sides.
+#[test]
+fn should_center_surface() {
+    let placement = SurfacePlacement::new(SizeMismatchPolicy::Center,
+                                          Size::new(300, 200),
+                                          Position::new(0, 0),
+                                          Size::new(300, 200),
+                                          Some(Size::new(200, 300)));
+
+    assert_eq!(placement.source, Area::create(50, 0, 200, 200));
+    assert_eq!(placement.target, Area::create(0, 50, 200, 200));
+    assert_eq!(placement.window, Area::create(0, 50, 200, 200));
+    assert_eq!(placement.to_surface(Position::new(0, 50)), Position::new(50, 0));
+    assert_eq!(placement.map_damage(&Area::create(0, 0, 100, 10)),
+               Some(Area::create(0, 50, 50, 10)));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if window is scaled to fill reserved space.
+#[test]
+fn should_scale_surface() {
+    let placement = SurfacePlacement::new(SizeMismatchPolicy::Scale,
+                                          Size::new(300, 200),
+                                          Position::new(0, 0),
+                                          Size::new(300, 200),
+                                          Some(Size::new(600, 100)));
+
+    assert_eq!(placement.source, Area::create(0, 0, 300, 200));
+    assert_eq!(placement.target, Area::create(0, 0, 600, 100));
+    assert_eq!(placement.to_surface(Position::new(60, 50)), Position::new(30, 100));
+    assert_eq!(placement.map_damage(&Area::create(10, 10, 10, 3)),
+               Some(Area::create(20, 5, 20, 2)));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if scaled placement draws whole surface in scaled area.
+#[test]
+fn should_scale_placement_down() {
+    let placement = SurfacePlacement::new(SizeMismatchPolicy::Clip,
+                                          Size::new(300, 200),
+                                          Position::new(10, 20),
+                                          Size::new(280, 160),
+                                          None)
+        .scaled(0.5);
+
+    assert_eq!(placement.source, Area::create(0, 0, 300, 200));
+    assert_eq!(placement.target, Area::create(-5, -10, 150, 100));
+    assert_eq!(placement.window, Area::create(0, 0, 140, 80));
+    assert_eq!(placement.to_surface(Position::new(20, 30)), Position::new(50, 80));
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,84 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Tests for placement of background images.
+
+// -------------------------------------------------------------------------------------------------
+
+extern crate qualia;
+
+use qualia::{Area, Position, Size, WallpaperMode};
+use qualia::wallpaper::{self, WallpaperTile};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Constructs tile from given area and texture coordinates.
+fn tile(x: isize, y: isize, width: usize, height: usize, texcoords: [f32; 4]) -> WallpaperTile {
+    WallpaperTile {
+        area: Area::new(Position::new(x, y), Size::new(width, height)),
+        texcoords: texcoords,
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if filling image covers the whole screen and is cut off evenly on both sides.
+#[test]
+fn should_fill_screen() {
+    let tiles = wallpaper::layout(WallpaperMode::Fill, Size::new(200, 100), Size::new(100, 100));
+    assert_eq!(tiles, vec![tile(0, 0, 100, 100, [0.25, 0.0, 0.75, 1.0])]);
+
+    let tiles = wallpaper::layout(WallpaperMode::Fill, Size::new(50, 100), Size::new(100, 100));
+    assert_eq!(tiles, vec![tile(0, 0, 100, 100, [0.0, 0.25, 1.0, 0.75])]);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if fitting image is scaled to fit the screen and placed in the middle.
+#[test]
+fn should_fit_screen() {
+    let tiles = wallpaper::layout(WallpaperMode::Fit, Size::new(200, 100), Size::new(100, 100));
+    assert_eq!(tiles, vec![tile(0, 25, 100, 50, [0.0, 0.0, 1.0, 1.0])]);
+
+    let tiles = wallpaper::layout(WallpaperMode::Fit, Size::new(10, 20), Size::new(100, 100));
+    assert_eq!(tiles, vec![tile(25, 0, 50, 100, [0.0, 0.0, 1.0, 1.0])]);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if tiles cover the whole screen and the ones at the edges are cut off.
+#[test]
+fn should_tile_screen() {
+    let tiles = wallpaper::layout(WallpaperMode::Tile, Size::new(40, 60), Size::new(100, 100));
+    assert_eq!(tiles,
+               vec![tile(0, 0, 40, 60, [0.0, 0.0, 1.0, 1.0]),
+                    tile(40, 0, 40, 60, [0.0, 0.0, 1.0, 1.0]),
+                    tile(80, 0, 20, 60, [0.0, 0.0, 0.5, 1.0]),
+                    tile(0, 60, 40, 40, [0.0, 0.0, 1.0, 40.0 / 60.0]),
+                    tile(40, 60, 40, 40, [0.0, 0.0, 1.0, 40.0 / 60.0]),
+                    tile(80, 60, 20, 40, [0.0, 0.0, 0.5, 40.0 / 60.0])]);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if centered image keeps its size and is cut off evenly if it does not fit.
+#[test]
+fn should_center_image() {
+    let tiles = wallpaper::layout(WallpaperMode::Center, Size::new(40, 60), Size::new(100, 100));
+    assert_eq!(tiles, vec![tile(30, 20, 40, 60, [0.0, 0.0, 1.0, 1.0])]);
+
+    let tiles = wallpaper::layout(WallpaperMode::Center, Size::new(200, 50), Size::new(100, 100));
+    assert_eq!(tiles, vec![tile(0, 25, 100, 50, [0.25, 0.0, 0.75, 1.0])]);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if nothing is drawn for empty image or screen.
+#[test]
+fn should_skip_empty_sizes() {
+    let empty = Size::new(0, 0);
+    assert!(wallpaper::layout(WallpaperMode::Fill, empty, Size::new(100, 100)).is_empty());
+    assert!(wallpaper::layout(WallpaperMode::Tile, Size::new(10, 10), empty).is_empty());
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -27,3 +27,33 @@ impl Milliseconds {
 }
 
 // -------------------------------------------------------------------------------------------------
+
+/// This structure represents time when frame was presented on output as reported by hardware
+/// (`CLOCK_MONOTONIC` domain) together with number of vertical blank it happened at.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PresentationTime {
+    /// Seconds part of the time.
+    pub sec: u64,
+
+    /// Nanoseconds part of the time.
+    pub nsec: u32,
+
+    /// Vertical blank counter of the output.
+    pub sequence: u64,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl PresentationTime {
+    /// Constructs new `PresentationTime` from time given in seconds and microseconds as reported by
+    /// DRM.
+    pub fn from_drm(sec: u32, usec: u32, sequence: u32) -> Self {
+        PresentationTime {
+            sec: sec as u64,
+            nsec: 1000 * usec,
+            sequence: sequence as u64,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
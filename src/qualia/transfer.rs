@@ -0,0 +1,94 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains definitions related to transferring data between clients (e.g. copying
+//! and pasting or dragging and dropping).
+
+// -------------------------------------------------------------------------------------------------
+
+use defs::SurfaceId;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Describes data offered by one client to be transferred to another client.
+///
+/// Data itself is never stored by application. Client owning the transfer is requested to write
+/// data directly to file descriptor provided by receiving client.
+#[derive(Clone, Debug)]
+pub struct Transfer {
+    /// List of MIME types the data can be provided in.
+    mime_types: Vec<String>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Transfer {
+    /// `Transfer` constructor.
+    pub fn new() -> Self {
+        Transfer { mime_types: Vec::new() }
+    }
+
+    /// Adds MIME type to list of offered types.
+    pub fn add_mime_type(&mut self, mime_type: String) {
+        if !self.mime_types.contains(&mime_type) {
+            self.mime_types.push(mime_type);
+        }
+    }
+
+    /// Returns list of offered MIME types.
+    pub fn get_mime_types(&self) -> &Vec<String> {
+        &self.mime_types
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// State of drag-and-drop operation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DragState {
+    /// Data is being dragged around.
+    Dragging,
+
+    /// Data was dropped on target surface and target client was informed about it. Drag lasts
+    /// until target client finishes receiving the data.
+    Dropped,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Describes drag-and-drop operation.
+#[derive(Clone, Debug)]
+pub struct Drag {
+    /// Dragged data. `None` if the drag was started without data source; in such case the drag
+    /// should be visible only for the client which started it.
+    pub transfer: Option<Transfer>,
+
+    /// Surface from which the drag was started.
+    pub origin: SurfaceId,
+
+    /// Surface used as drag icon. May be invalid.
+    pub icon: SurfaceId,
+
+    /// Surface over which the data is currently dragged.
+    pub target: SurfaceId,
+
+    /// State of the operation.
+    pub state: DragState,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Drag {
+    /// `Drag` constructor.
+    pub fn new(transfer: Option<Transfer>, origin: SurfaceId, icon: SurfaceId) -> Self {
+        Drag {
+            transfer: transfer,
+            origin: origin,
+            icon: icon,
+            target: SurfaceId::invalid(),
+            state: DragState::Dragging,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
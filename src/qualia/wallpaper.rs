@@ -0,0 +1,143 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains loading of background images and computing their placement on screen.
+
+// -------------------------------------------------------------------------------------------------
+
+use std::path::Path;
+
+use image;
+
+use config::WallpaperMode;
+use defs::{Area, Position, Size};
+use errors::Illusion;
+use memory::Buffer;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Part of background image drawn in given area of the screen.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WallpaperTile {
+    /// Area of the screen covered by the tile.
+    pub area: Area,
+
+    /// Left, top, right and bottom edge of drawn part of the image given as fractions of its
+    /// width and height.
+    pub texcoords: [f32; 4],
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Loads PNG or JPEG image from given file. Pixels of returned buffer have red, green, blue and
+/// alpha bytes in this order.
+pub fn load(path: &Path) -> Result<Buffer, Illusion> {
+    let image = match image::open(path) {
+        Ok(image) => image.to_rgba(),
+        Err(err) => {
+            return Err(Illusion::General(format!("Failed to load image {:?}: {}", path, err)));
+        }
+    };
+
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    Ok(Buffer::new(width, height, 4 * width, image.into_raw()))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Computes which parts of image of given size should be drawn where on screen of given size
+/// according to given mode.
+pub fn layout(mode: WallpaperMode, image: Size, screen: Size) -> Vec<WallpaperTile> {
+    if image.width == 0 || image.height == 0 || screen.width == 0 || screen.height == 0 {
+        return Vec::new();
+    }
+
+    match mode {
+        WallpaperMode::Fill => layout_fill(image, screen),
+        WallpaperMode::Fit => layout_fit(image, screen),
+        WallpaperMode::Tile => layout_tile(image, screen),
+        WallpaperMode::Center => layout_center(image, screen),
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Scales the image to cover the whole screen cutting off the same amount on both sides.
+fn layout_fill(image: Size, screen: Size) -> Vec<WallpaperTile> {
+    let (iw, ih) = (image.width as f32, image.height as f32);
+    let (sw, sh) = (screen.width as f32, screen.height as f32);
+    let scale = (sw / iw).max(sh / ih);
+    let (visible_width, visible_height) = (sw / scale / iw, sh / scale / ih);
+    let (left, top) = ((1.0 - visible_width) / 2.0, (1.0 - visible_height) / 2.0);
+    vec![WallpaperTile {
+             area: Area::new(Position::default(), screen),
+             texcoords: [left, top, left + visible_width, top + visible_height],
+         }]
+}
+
+/// Scales the image to fit the screen placing it in the middle.
+fn layout_fit(image: Size, screen: Size) -> Vec<WallpaperTile> {
+    let (iw, ih) = (image.width as f32, image.height as f32);
+    let (sw, sh) = (screen.width as f32, screen.height as f32);
+    let scale = (sw / iw).min(sh / ih);
+    let width = ((iw * scale).round() as usize).min(screen.width);
+    let height = ((ih * scale).round() as usize).min(screen.height);
+    let position = Position::new(((screen.width - width) / 2) as isize,
+                                 ((screen.height - height) / 2) as isize);
+    vec![WallpaperTile {
+             area: Area::new(position, Size::new(width, height)),
+             texcoords: [0.0, 0.0, 1.0, 1.0],
+         }]
+}
+
+/// Repeats the image in its original size starting from top left corner. Tiles at right and
+/// bottom edge are cut off.
+fn layout_tile(image: Size, screen: Size) -> Vec<WallpaperTile> {
+    let mut tiles = Vec::new();
+    let mut y = 0;
+    while y < screen.height {
+        let height = image.height.min(screen.height - y);
+        let mut x = 0;
+        while x < screen.width {
+            let width = image.width.min(screen.width - x);
+            tiles.push(WallpaperTile {
+                           area: Area::new(Position::new(x as isize, y as isize),
+                                           Size::new(width, height)),
+                           texcoords: [0.0,
+                                       0.0,
+                                       width as f32 / image.width as f32,
+                                       height as f32 / image.height as f32],
+                       });
+            x += image.width;
+        }
+        y += image.height;
+    }
+    tiles
+}
+
+/// Places the image in its original size in the middle of the screen cutting off the same amount
+/// on both sides if it is bigger than the screen.
+fn layout_center(image: Size, screen: Size) -> Vec<WallpaperTile> {
+    let (x, width, left, right) = center(image.width, screen.width);
+    let (y, height, top, bottom) = center(image.height, screen.height);
+    vec![WallpaperTile {
+             area: Area::new(Position::new(x, y), Size::new(width, height)),
+             texcoords: [left, top, right, bottom],
+         }]
+}
+
+/// Centers segment of image of given length on segment of screen of given length. Returns
+/// position and length on screen and drawn range of the image.
+fn center(image: usize, screen: usize) -> (isize, usize, f32, f32) {
+    if image <= screen {
+        (((screen - image) / 2) as isize, image, 0.0, 1.0)
+    } else {
+        let offset = (image - screen) / 2;
+        (0,
+         screen,
+         offset as f32 / image as f32,
+         (offset + screen) as f32 / image as f32)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -8,7 +8,57 @@
 use std;
 use egl;
 
-use qualia::Illusion;
+use qualia::{HwImage, Illusion, Size};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Opaque handle to `EGLImage`.
+pub type EGLImageKHR = *mut std::os::raw::c_void;
+
+/// Target for creating `EGLImage` from DMA-BUF (`EGL_EXT_image_dma_buf_import`).
+const EGL_LINUX_DMA_BUF_EXT: egl::EGLenum = 0x3270;
+
+/// Attribute names used for importing DMA-BUFs.
+const EGL_LINUX_DRM_FOURCC_EXT: egl::EGLint = 0x3271;
+const EGL_WIDTH: egl::EGLint = 0x3057;
+const EGL_HEIGHT: egl::EGLint = 0x3056;
+
+/// Attribute names of file descriptor, offset, pitch and modifier (low and high bits) for every
+/// plane.
+#[cfg_attr(rustfmt, rustfmt_skip)]
+const EGL_DMA_BUF_PLANE_ATTRIBS: [[egl::EGLint; 5]; 4] = [
+        [0x3272, 0x3273, 0x3274, 0x3443, 0x3444],
+        [0x3275, 0x3276, 0x3277, 0x3445, 0x3446],
+        [0x3278, 0x3279, 0x327A, 0x3447, 0x3448],
+        [0x3440, 0x3441, 0x3442, 0x3449, 0x344A],
+    ];
+
+/// Modifier meaning the layout of buffer is implicit and should not be passed to EGL.
+const DRM_FORMAT_MOD_INVALID: u64 = 0x00ffffffffffffff;
+
+/// Target of `glEGLImageTargetTexture2DOES`.
+const GL_TEXTURE_2D: u32 = 0x0DE1;
+
+/// Signature of `eglCreateImageKHR`.
+type CreateImageFn = extern "C" fn(egl::EGLDisplay,
+                                   egl::EGLContext,
+                                   egl::EGLenum,
+                                   *mut std::os::raw::c_void,
+                                   *const egl::EGLint)
+                                   -> EGLImageKHR;
+
+/// Signature of `eglDestroyImageKHR`.
+type DestroyImageFn = extern "C" fn(egl::EGLDisplay, EGLImageKHR) -> egl::EGLBoolean;
+
+/// Signature of `glEGLImageTargetTexture2DOES`.
+type ImageTargetTextureFn = extern "C" fn(u32, EGLImageKHR);
+
+/// Signature of `eglSwapBuffersWithDamageKHR`.
+type SwapBuffersWithDamageFn = extern "C" fn(egl::EGLDisplay,
+                                             egl::EGLSurface,
+                                             *const egl::EGLint,
+                                             egl::EGLint)
+                                             -> egl::EGLBoolean;
 
 // -------------------------------------------------------------------------------------------------
 
@@ -26,6 +76,23 @@ static CONFIG_ATTRIB_LIST: [egl::EGLint; 13] = [
 
 // -------------------------------------------------------------------------------------------------
 
+/// List of attributes for create of configuration for off-screen rendering.
+#[cfg_attr(rustfmt, rustfmt_skip)]
+static PBUFFER_CONFIG_ATTRIB_LIST: [egl::EGLint; 13] = [
+        egl::EGL_RENDERABLE_TYPE, egl::EGL_OPENGL_ES2_BIT,
+        egl::EGL_SURFACE_TYPE,    egl::EGL_PBUFFER_BIT,
+        egl::EGL_RED_SIZE,        8,
+        egl::EGL_GREEN_SIZE,      8,
+        egl::EGL_BLUE_SIZE,       8,
+        egl::EGL_ALPHA_SIZE,      8,
+        egl::EGL_NONE
+    ];
+
+/// Platform not requiring any windowing system nor GPU device (`EGL_MESA_platform_surfaceless`).
+const EGL_PLATFORM_SURFACELESS_MESA: egl::EGLenum = 0x31DD;
+
+// -------------------------------------------------------------------------------------------------
+
 /// List of attributes for create of context.
 static CONTEXT_ATTRIB_LIST: [egl::EGLint; 3] = [egl::EGL_CONTEXT_CLIENT_VERSION, 2, egl::EGL_NONE];
 
@@ -81,6 +148,83 @@ impl EglBucket {
         // Get display
         let display = Self::get_gbm_display(display_type)?;
 
+        // Initialize EGL and create context
+        let (config, context) = Self::initialize(display, &CONFIG_ATTRIB_LIST)?;
+
+        // Create window surface
+        let s = egl::create_window_surface(display, config, window_type, &SURFACE_ATTRIB_LIST);
+        let surface = if let Some(surface) = s {
+            surface
+        } else {
+            return Err(Illusion::General(format!("Failed to create EGL window surface")));
+        };
+
+        // Return bundle
+        Ok(EglBucket {
+               display: display,
+               config: config,
+               context: context,
+               surface: surface,
+           })
+    }
+
+    /// Replaces window surface with new one created for given window. Old surface is destroyed
+    /// and the context is kept.
+    pub fn replace_window_surface(&mut self,
+                                  window_type: egl::EGLNativeWindowType)
+                                  -> Result<(), Illusion> {
+        let s = egl::create_window_surface(self.display,
+                                           self.config,
+                                           window_type,
+                                           &SURFACE_ATTRIB_LIST);
+        let surface = if let Some(surface) = s {
+            surface
+        } else {
+            return Err(Illusion::General(format!("Failed to create EGL window surface")));
+        };
+
+        egl::destroy_surface(self.display, self.surface);
+        self.surface = surface;
+        Ok(())
+    }
+
+    /// Creates `EglBucket` for off-screen rendering to pixel buffer of given size.
+    ///
+    /// Surfaceless platform is preferred as it works also on machines without GPU (using software
+    /// rasterizer). Default display is used if the platform is not available.
+    pub fn new_offscreen(size: Size) -> Result<Self, Illusion> {
+        // Get display
+        let display = Self::get_surfaceless_display()?;
+
+        // Initialize EGL and create context
+        let (config, context) = Self::initialize(display, &PBUFFER_CONFIG_ATTRIB_LIST)?;
+
+        // Create pixel buffer surface
+        let attribs = [EGL_WIDTH,
+                       size.width as egl::EGLint,
+                       EGL_HEIGHT,
+                       size.height as egl::EGLint,
+                       egl::EGL_NONE];
+        let s = egl::create_pbuffer_surface(display, config, &attribs);
+        let surface = if let Some(surface) = s {
+            surface
+        } else {
+            return Err(Illusion::General(format!("Failed to create EGL pixel buffer surface")));
+        };
+
+        // Return bundle
+        Ok(EglBucket {
+               display: display,
+               config: config,
+               context: context,
+               surface: surface,
+           })
+    }
+
+    /// Initializes display, chooses configuration basing on given attributes and creates context.
+    fn initialize(display: egl::EGLDisplay,
+                  config_attribs: &[egl::EGLint])
+                  -> Result<(egl::EGLConfig, egl::EGLContext), Illusion> {
         // Initialize EGL
         let mut major = 0;
         let mut minor = 0;
@@ -93,7 +237,7 @@ impl EglBucket {
         };
 
         // Choose config
-        let config = if let Some(config) = egl::choose_config(display, &CONFIG_ATTRIB_LIST, 1) {
+        let config = if let Some(config) = egl::choose_config(display, config_attribs, 1) {
             config
         } else {
             return Err(Illusion::General(format!("Failed to choose EGL config")));
@@ -107,21 +251,7 @@ impl EglBucket {
             return Err(Illusion::General(format!("Failed to create EGL context")));
         };
 
-        // Create window surface
-        let s = egl::create_window_surface(display, config, window_type, &SURFACE_ATTRIB_LIST);
-        let surface = if let Some(surface) = s {
-            surface
-        } else {
-            return Err(Illusion::General(format!("Failed to create EGL window surface")));
-        };
-
-        // Return bundle
-        Ok(EglBucket {
-               display: display,
-               config: config,
-               context: context,
-               surface: surface,
-           })
+        Ok((config, context))
     }
 
     /// Make EGL context current.
@@ -135,6 +265,90 @@ impl EglBucket {
         }
     }
 
+    /// Checks if display supports extension with given name.
+    pub fn has_extension(&self, name: &str) -> bool {
+        if let Some(extensions) = egl::query_string(self.display, egl::EGL_EXTENSIONS) {
+            extensions.to_string_lossy().split(' ').any(|extension| extension == name)
+        } else {
+            false
+        }
+    }
+
+    /// Imports hardware image as `EGLImage`.
+    ///
+    /// Requires `EGL_KHR_image_base` and `EGL_EXT_image_dma_buf_import` extensions.
+    pub fn create_image(&self, image: &HwImage) -> Result<EglImage, Illusion> {
+        let create_image_ptr = egl::get_proc_address("eglCreateImageKHR") as *const ();
+        if create_image_ptr.is_null() {
+            return Err(Illusion::General(format!("EGL images are not supported")));
+        }
+        let create_image =
+            unsafe { std::mem::transmute::<*const (), CreateImageFn>(create_image_ptr) };
+
+        let dmabuf = image.get_dmabuf();
+        let mut attribs = vec![EGL_WIDTH,
+                               dmabuf.width as egl::EGLint,
+                               EGL_HEIGHT,
+                               dmabuf.height as egl::EGLint,
+                               EGL_LINUX_DRM_FOURCC_EXT,
+                               dmabuf.format as egl::EGLint];
+        for (plane, names) in dmabuf.planes.iter().zip(EGL_DMA_BUF_PLANE_ATTRIBS.iter()) {
+            attribs.extend_from_slice(&[names[0],
+                                        plane.fd as egl::EGLint,
+                                        names[1],
+                                        plane.offset as egl::EGLint,
+                                        names[2],
+                                        plane.stride as egl::EGLint]);
+            if plane.modifier != DRM_FORMAT_MOD_INVALID {
+                attribs.extend_from_slice(&[names[3],
+                                            (plane.modifier & 0xFFFFFFFF) as egl::EGLint,
+                                            names[4],
+                                            (plane.modifier >> 32) as egl::EGLint]);
+            }
+        }
+        attribs.push(egl::EGL_NONE);
+
+        let handle = create_image(self.display,
+                                  egl::EGL_NO_CONTEXT,
+                                  EGL_LINUX_DMA_BUF_EXT,
+                                  std::ptr::null_mut(),
+                                  attribs.as_ptr());
+        if !handle.is_null() {
+            Ok(EglImage {
+                   display: self.display,
+                   image: handle,
+               })
+        } else {
+            Err(Illusion::General(format!("Failed to create EGL image (0x{:x})", egl::get_error())))
+        }
+    }
+
+    /// Gets surfaceless display if supported or default display otherwise.
+    fn get_surfaceless_display() -> Result<egl::EGLDisplay, Illusion> {
+        let get_platform_display_ptr = egl::get_proc_address("eglGetPlatformDisplayEXT") as
+                                       *const ();
+        if !get_platform_display_ptr.is_null() {
+            let get_platform_display =
+                unsafe {
+                    std::mem::transmute::<*const (),
+                                          fn(egl::EGLenum,
+                                             egl::EGLNativeDisplayType,
+                                             *const egl::EGLint)
+                                             -> egl::EGLDisplay>(get_platform_display_ptr)
+                };
+
+            let display = get_platform_display(EGL_PLATFORM_SURFACELESS_MESA,
+                                               egl::EGL_DEFAULT_DISPLAY,
+                                               std::ptr::null());
+            if !display.is_null() {
+                return Ok(display);
+            }
+        }
+
+        egl::get_display(egl::EGL_DEFAULT_DISPLAY)
+            .ok_or(Illusion::General(format!("Failed to get EGL display")))
+    }
+
     /// Gets GBM display.
     ///
     /// First tries `eglGetDisplay`. If that fails, tries `eglGetPlatformDisplayEXT`.
@@ -203,6 +417,26 @@ impl EglContext {
             Err(Illusion::General(format!("Failed to swap EGL buffers (0x{:x})", egl::get_error())))
         }
     }
+
+    /// Swap buffers informing display which regions changed. `rects` contains `x`, `y`, `width`
+    /// and `height` of every region with origin in lower left corner.
+    ///
+    /// Requires `EGL_KHR_swap_buffers_with_damage` extension. Falls back to regular swap if the
+    /// function is not available.
+    pub fn swap_buffers_with_damage(&self, rects: &[egl::EGLint]) -> Result<(), Illusion> {
+        let swap_ptr = egl::get_proc_address("eglSwapBuffersWithDamageKHR") as *const ();
+        if swap_ptr.is_null() {
+            return self.swap_buffers();
+        }
+
+        let swap = unsafe { std::mem::transmute::<*const (), SwapBuffersWithDamageFn>(swap_ptr) };
+        let num_rects = (rects.len() / 4) as egl::EGLint;
+        if swap(self.egl.display, self.egl.surface, rects.as_ptr(), num_rects) != 0 {
+            Ok(())
+        } else {
+            Err(Illusion::General(format!("Failed to swap EGL buffers (0x{:x})", egl::get_error())))
+        }
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -214,3 +448,43 @@ impl Drop for EglContext {
 }
 
 // -------------------------------------------------------------------------------------------------
+
+/// Wraps `EGLImage` and destroys it when goes out of the scope.
+pub struct EglImage {
+    display: egl::EGLDisplay,
+    image: EGLImageKHR,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl EglImage {
+    /// Binds the image to currently bound `GL_TEXTURE_2D` texture. Texture keeps referring to the
+    /// image data even after `EglImage` is destroyed.
+    pub fn bind_to_texture(&self) -> Result<(), Illusion> {
+        let target_texture_ptr = egl::get_proc_address("glEGLImageTargetTexture2DOES") as *const ();
+        if !target_texture_ptr.is_null() {
+            let target_texture = unsafe {
+                std::mem::transmute::<*const (), ImageTargetTextureFn>(target_texture_ptr)
+            };
+            target_texture(GL_TEXTURE_2D, self.image);
+            Ok(())
+        } else {
+            Err(Illusion::General(format!("Binding EGL images to textures is not supported")))
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Drop for EglImage {
+    fn drop(&mut self) {
+        let destroy_image_ptr = egl::get_proc_address("eglDestroyImageKHR") as *const ();
+        if !destroy_image_ptr.is_null() {
+            let destroy_image =
+                unsafe { std::mem::transmute::<*const (), DestroyImageFn>(destroy_image_ptr) };
+            destroy_image(self.display, self.image);
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -1,13 +1,25 @@
 #version 100
 
 //! Fragment shader source code for OpenGL ES 2.0 (GLSL ES 100)
+//!
+//! `swizzle` tells if red and blue components of sampled color have to be swapped: 0 - no, 1 - yes.
+//! `dim` is fraction by which brightness and saturation of sampled color are reduced.
 
 varying highp vec2 v_texcoords;
 uniform sampler2D texture;
+uniform int swizzle;
+uniform mediump float dim;
 mediump vec4 color;
 
 void main(void)
 {
     color = texture2D(texture, v_texcoords);
-    gl_FragColor = vec4(color.b, color.g, color.r, color.a);
+    if (swizzle == 1) {
+        color = color.bgra;
+    }
+    if (dim > 0.0) {
+        mediump float luma = dot(color.rgb, vec3(0.2126, 0.7152, 0.0722));
+        color.rgb = mix(color.rgb, vec3(luma), dim) * (1.0 - dim);
+    }
+    gl_FragColor = color;
 }
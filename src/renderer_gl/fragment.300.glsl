@@ -1,12 +1,46 @@
 #version 300 es
 
 //! Fragment shader source code for OpenGL ES 3.0 (GLSL ES 300)
+//!
+//! `transfer` selects conversion of sampled color: 0 - none, 1 - sRGB to linear, 2 - sRGB to
+//! linear for premultiplied colors, 3 - linear to sRGB. `swizzle` tells if red and blue components
+//! of sampled color have to be swapped: 0 - no, 1 - yes. `dim` is fraction by which brightness and
+//! saturation of sampled color are reduced.
 
 in highp vec2 v_texcoords;
 uniform sampler2D texture;
+uniform int transfer;
+uniform int swizzle;
+uniform highp float dim;
 out highp vec4 color;
 
+highp vec3 to_linear(highp vec3 c)
+{
+    return mix(c / 12.92, pow((c + 0.055) / 1.055, vec3(2.4)), step(0.04045, c));
+}
+
+highp vec3 to_srgb(highp vec3 c)
+{
+    return mix(c * 12.92, 1.055 * pow(c, vec3(1.0 / 2.4)) - 0.055, step(0.0031308, c));
+}
+
 void main(void)
 {
     color = texture2D(texture, v_texcoords);
+    if (swizzle == 1) {
+        color = color.bgra;
+    }
+    if (transfer == 1) {
+        color.rgb = to_linear(color.rgb);
+    } else if (transfer == 2) {
+        if (color.a > 0.0) {
+            color.rgb = to_linear(color.rgb / color.a) * color.a;
+        }
+    } else if (transfer == 3) {
+        color.rgb = to_srgb(color.rgb);
+    }
+    if (dim > 0.0) {
+        highp float luma = dot(color.rgb, vec3(0.2126, 0.7152, 0.0722));
+        color.rgb = mix(color.rgb, vec3(luma), dim) * (1.0 - dim);
+    }
 }
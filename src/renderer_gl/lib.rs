@@ -16,8 +16,9 @@ extern crate qualia;
 
 pub mod gl_tools;
 pub mod egl_tools;
+pub mod texture_cache;
 pub mod renderer_gl;
 
-pub use renderer_gl::RendererGl;
+pub use renderer_gl::{RenderTarget, RendererGl};
 
 // -------------------------------------------------------------------------------------------------
@@ -0,0 +1,11 @@
+#version 100
+
+//! Fragment shader source code for OpenGL ES 2.0 (GLSL ES 100) used for drawing plain-colored
+//! overlays
+
+uniform mediump vec4 color;
+
+void main(void)
+{
+    gl_FragColor = color;
+}
@@ -0,0 +1,12 @@
+#version 300 es
+
+//! Fragment shader source code for OpenGL ES 3.0 (GLSL ES 300) used for drawing plain-colored
+//! overlays
+
+uniform highp vec4 color;
+out highp vec4 frag_color;
+
+void main(void)
+{
+    frag_color = color;
+}
@@ -6,17 +6,24 @@
 // -------------------------------------------------------------------------------------------------
 
 use std;
+use std::collections::VecDeque;
 use gl;
 use egl;
 
 use qualia::{Coordinator, SurfaceContext, Illusion, Size, Pixmap};
+use qualia::{AlphaMode, Area, Buffer, BufferTransform, MemoryView, PixelFormat, Position};
+use qualia::{SurfaceId, Transform};
+use qualia::WallpaperMode;
+use qualia::wallpaper;
 
 use gl_tools;
 use egl_tools;
+use texture_cache::{Contents, TextureCache};
 
 // -------------------------------------------------------------------------------------------------
 
-const MAX_TEXTURES: u32 = 32;
+/// Maximal number of textures holding contents of surfaces kept between frames.
+const TEXTURE_CACHE_SIZE: usize = 64;
 
 /// Vertex shader source code for OpenGL ES 2.0 (GLSL ES 100)
 const VERTEX_SHADER_100: &'static str = include_str!("vertex.100.glsl");
@@ -30,6 +37,64 @@ const VERTEX_SHADER_300: &'static str = include_str!("vertex.300.glsl");
 /// Fragment shader source code for OpenGL ES 3.0 (GLSL ES 300)
 const FRAGMENT_SHADER_300: &'static str = include_str!("fragment.300.glsl");
 
+/// Fragment shader source code for drawing overlays for OpenGL ES 2.0 (GLSL ES 100)
+const OVERLAY_SHADER_100: &'static str = include_str!("overlay.100.glsl");
+
+/// Fragment shader source code for drawing overlays for OpenGL ES 3.0 (GLSL ES 300)
+const OVERLAY_SHADER_300: &'static str = include_str!("overlay.300.glsl");
+
+/// Number of frames over which visualized damage fades out.
+const DAMAGE_FADE_FRAMES: usize = 6;
+
+/// Color of visualized damage in the frame it was drawn (red, green, blue, alpha).
+const DAMAGE_TINT: [f32; 4] = [1.0, 0.0, 0.3, 0.4];
+
+/// Color dimming screen outside of region being selected (red, green, blue, alpha).
+const SELECTION_DIM_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 0.5];
+
+/// Color covering surfaces excluded from captures (red, green, blue, alpha).
+const CONCEAL_COLOR: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+
+/// Color of background (red, green, blue, alpha).
+const BACKGROUND_COLOR: [f32; 4] = [0.0, 0.3, 0.5, 1.0];
+
+/// Values of `transfer` uniform of fragment shader selecting conversion of sampled colors.
+const TRANSFER_NONE: i32 = 0;
+const TRANSFER_TO_LINEAR: i32 = 1;
+const TRANSFER_TO_LINEAR_PREMULTIPLIED: i32 = 2;
+const TRANSFER_TO_SRGB: i32 = 3;
+
+/// Name of EGL extension allowing to pass damage to display on swap.
+const SWAP_WITH_DAMAGE_EXTENSION: &'static str = "EGL_KHR_swap_buffers_with_damage";
+
+// -------------------------------------------------------------------------------------------------
+
+/// Background image uploaded to texture.
+struct Wallpaper {
+    texture: gl::types::GLuint,
+    size: Size,
+    mode: WallpaperMode,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Off-screen frame buffer which can be drawn to instead of display. Created and destroyed by
+/// `RendererGl` as it must be used with the same GL context.
+pub struct RenderTarget {
+    framebuffer: gl::types::GLuint,
+    texture: gl::types::GLuint,
+    size: Size,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl RenderTarget {
+    /// Returns size of the target in pixels.
+    pub fn get_size(&self) -> Size {
+        self.size
+    }
+}
+
 // -------------------------------------------------------------------------------------------------
 
 /// GL renderer.
@@ -37,15 +102,78 @@ pub struct RendererGl {
     egl: egl_tools::EglBucket,
     size: Size,
 
+    /// Number of pixels per logical unit. Scene is given in logical coordinates.
+    scale: f32,
+
+    /// Rotation and flipping of the screen. Scene is given in orientation seen by user.
+    transform: Transform,
+
+    /// Transform applied to currently drawn view: `transform` when drawing to the screen, none
+    /// when drawing to off-screen targets, which hold the scene in orientation seen by user.
+    view_transform: Transform,
+
     // GL rendering
     program: gl::types::GLuint,
     loc_vertices: gl::types::GLint,
     loc_texcoords: gl::types::GLint,
     loc_texture: gl::types::GLint,
     loc_screen_size: gl::types::GLint,
+    loc_transform: gl::types::GLint,
+
+    /// Location of `transfer` uniform. Available only with GLSL ES 3.0 shaders.
+    loc_transfer: Option<gl::types::GLint>,
+
+    /// Location of `swizzle` uniform telling if red and blue components have to be swapped.
+    loc_swizzle: gl::types::GLint,
+
+    /// Location of `dim` uniform telling how much colors have to be dimmed.
+    loc_dim: gl::types::GLint,
+
     vbo_vertices: gl::types::GLuint,
     vbo_texcoords: gl::types::GLuint,
-    vbo_texture: [gl::types::GLuint; MAX_TEXTURES as usize],
+
+    // GL overlay rendering
+    overlay_program: gl::types::GLuint,
+    loc_overlay_vertices: gl::types::GLint,
+    loc_overlay_color: gl::types::GLint,
+    loc_overlay_screen_size: gl::types::GLint,
+    loc_overlay_transform: gl::types::GLint,
+
+    /// Textures holding contents of surfaces. Texture holding contents of drawn surface needs to
+    /// be updated only in damaged regions.
+    textures: TextureCache,
+
+    /// Areas and dimming of surfaces drawn in last frame.
+    last_layout: Vec<(SurfaceId, Area, f32)>,
+
+    /// Regions of screen changed in last drawn frame. `None` means the whole screen changed.
+    frame_damage: Option<Vec<Area>>,
+
+    /// Tells if the whole screen has to be considered changed in next frame even if layout of
+    /// surfaces did not change (e.g. after background changed).
+    damage_all: bool,
+
+    /// Tells if display supports passing damage on swap.
+    swap_with_damage: bool,
+
+    /// Tells if regions repainted in last frames should be tinted.
+    visualize_damage: bool,
+
+    /// Regions of screen repainted in last frames, the most recent first.
+    damage_history: VecDeque<Vec<Area>>,
+
+    /// Region being interactively selected. Screen outside of it is dimmed. `None` if no
+    /// selection is in progress.
+    selection: Option<Area>,
+
+    /// Tells if colors should be blended in linear space.
+    linear_blending: bool,
+
+    /// Target with sRGB encoding scene is drawn to when blending in linear space.
+    scene_target: Option<RenderTarget>,
+
+    /// Background image.
+    wallpaper: Option<Wallpaper>,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -56,14 +184,36 @@ impl RendererGl {
         RendererGl {
             egl: egl,
             size: size,
+            scale: 1.0,
+            transform: Transform::Normal,
+            view_transform: Transform::Normal,
             program: gl::types::GLuint::default(),
             loc_vertices: gl::types::GLint::default(),
             loc_texcoords: gl::types::GLint::default(),
             loc_texture: gl::types::GLint::default(),
             loc_screen_size: gl::types::GLint::default(),
+            loc_transform: gl::types::GLint::default(),
+            loc_transfer: None,
+            loc_swizzle: gl::types::GLint::default(),
+            loc_dim: gl::types::GLint::default(),
             vbo_vertices: gl::types::GLuint::default(),
             vbo_texcoords: gl::types::GLuint::default(),
-            vbo_texture: [0; MAX_TEXTURES as usize],
+            overlay_program: gl::types::GLuint::default(),
+            loc_overlay_vertices: gl::types::GLint::default(),
+            loc_overlay_color: gl::types::GLint::default(),
+            loc_overlay_screen_size: gl::types::GLint::default(),
+            loc_overlay_transform: gl::types::GLint::default(),
+            textures: TextureCache::new(TEXTURE_CACHE_SIZE),
+            last_layout: Vec::new(),
+            damage_all: false,
+            frame_damage: None,
+            swap_with_damage: false,
+            visualize_damage: false,
+            damage_history: VecDeque::new(),
+            selection: None,
+            linear_blending: false,
+            scene_target: None,
+            wallpaper: None,
         }
     }
 
@@ -76,14 +226,20 @@ impl RendererGl {
         gl::load_with(|s| egl::get_proc_address(s) as *const std::os::raw::c_void);
 
         let _context = self.egl.make_current()?;
+        self.swap_with_damage = self.egl.has_extension(SWAP_WITH_DAMAGE_EXTENSION);
 
         // Get GLSL version
-        let (vshader_src, fshader_src) = match gl_tools::get_shading_lang_version() {
+        let glsl_version = gl_tools::get_shading_lang_version();
+        let (vshader_src, fshader_src, oshader_src) = match glsl_version {
             gl_tools::GlslVersion::Glsl100 => {
-                (VERTEX_SHADER_100.to_owned(), FRAGMENT_SHADER_100.to_owned())
+                (VERTEX_SHADER_100.to_owned(),
+                 FRAGMENT_SHADER_100.to_owned(),
+                 OVERLAY_SHADER_100.to_owned())
             }
             gl_tools::GlslVersion::Glsl300 => {
-                (VERTEX_SHADER_300.to_owned(), FRAGMENT_SHADER_300.to_owned())
+                (VERTEX_SHADER_300.to_owned(),
+                 FRAGMENT_SHADER_300.to_owned(),
+                 OVERLAY_SHADER_300.to_owned())
             }
             gl_tools::GlslVersion::Unknown => {
                 return Err(Illusion::General(format!("Could not figure out GLSL version")));
@@ -91,12 +247,31 @@ impl RendererGl {
         };
 
         // Compile shades, link program and get locations
-        self.program = gl_tools::prepare_shader_program(vshader_src, fshader_src)?;
+        self.program = gl_tools::prepare_shader_program(vshader_src.clone(), fshader_src)?;
         self.loc_vertices = gl_tools::get_attrib_location(self.program, "vertices".to_owned())?;
         self.loc_texcoords = gl_tools::get_attrib_location(self.program, "texcoords".to_owned())?;
         self.loc_texture = gl_tools::get_uniform_location(self.program, "texture".to_owned())?;
         self.loc_screen_size = gl_tools::get_uniform_location(self.program,
                                                               "screen_size".to_owned())?;
+        self.loc_transform = gl_tools::get_uniform_location(self.program,
+                                                            "transform".to_owned())?;
+        self.loc_swizzle = gl_tools::get_uniform_location(self.program, "swizzle".to_owned())?;
+        self.loc_dim = gl_tools::get_uniform_location(self.program, "dim".to_owned())?;
+        if let gl_tools::GlslVersion::Glsl300 = glsl_version {
+            self.loc_transfer = Some(gl_tools::get_uniform_location(self.program,
+                                                                    "transfer".to_owned())?);
+        }
+
+        // Prepare program for drawing overlays
+        self.overlay_program = gl_tools::prepare_shader_program(vshader_src, oshader_src)?;
+        self.loc_overlay_vertices = gl_tools::get_attrib_location(self.overlay_program,
+                                                                  "vertices".to_owned())?;
+        self.loc_overlay_color = gl_tools::get_uniform_location(self.overlay_program,
+                                                                "color".to_owned())?;
+        self.loc_overlay_screen_size = gl_tools::get_uniform_location(self.overlay_program,
+                                                                      "screen_size".to_owned())?;
+        self.loc_overlay_transform = gl_tools::get_uniform_location(self.overlay_program,
+                                                                    "transform".to_owned())?;
 
         // Generate vertex buffer object
         unsafe {
@@ -104,40 +279,310 @@ impl RendererGl {
             gl::GenBuffers(1, &mut self.vbo_texcoords);
         }
 
-        // Create texture buffer
-        // FIXME: Implement support for more textures.
-        unsafe {
-            gl::GenTextures(MAX_TEXTURES as i32, (&mut self.vbo_texture).as_mut_ptr());
-            for i in 0..MAX_TEXTURES {
-                gl::ActiveTexture(gl::TEXTURE0 + 1);
-                gl::BindTexture(gl::TEXTURE_2D, self.vbo_texture[i as usize]);
-                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
-                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
-            }
-        }
-
         Ok(())
     }
 
-    /// Draw passed frame scene.
+    /// Draw passed frame scene. `pointer` is `None` if pointer is displayed in other way (e.g. on
+    /// hardware cursor plane).
     pub fn draw(&mut self,
                 surfaces: &Vec<SurfaceContext>,
-                pointer: SurfaceContext,
+                pointer: Option<SurfaceContext>,
                 coordinator: &Coordinator)
                 -> Result<(), Illusion> {
         let _context = self.egl.make_current()?;
-        self.prepare_view();
-        self.draw_bg_image();
-        self.draw_surfaces(surfaces, coordinator);
-        self.draw_pointer(pointer, coordinator);
+        self.view_transform = self.transform;
+        self.frame_damage = self.calculate_frame_damage(surfaces, &pointer, coordinator);
+        if self.visualize_damage {
+            let screen = Area::new(Position::default(), self.get_logical_size());
+            let damage = self.frame_damage.take().unwrap_or(vec![screen]);
+            self.damage_history.push_front(damage);
+            self.damage_history.truncate(DAMAGE_FADE_FRAMES);
+        }
+        if self.is_blending_linear() {
+            let size = self.size;
+            self.draw_scene_linear(0, size, surfaces, pointer, coordinator, &[])?;
+        } else {
+            self.draw_scene(surfaces, pointer, coordinator, &[], false);
+        }
+        if let Some(selection) = self.selection {
+            let screen = Area::new(Position::default(), self.get_logical_size());
+            self.draw_overlay(&screen.subtracted(&selection), SELECTION_DIM_COLOR, false);
+        }
+        if self.visualize_damage {
+            self.draw_damage_overlay();
+        }
         self.release_view();
         Ok(())
     }
 
-    /// Swap buffers.
+    /// Draw passed frame scene to off-screen target instead of display. Scene is scaled from size
+    /// of the renderer to size of the target and is not rotated. Damage tracking of display is not
+    /// affected.
+    ///
+    /// Surfaces excluded from captures are blacked out.
+    pub fn draw_to_target(&mut self,
+                          target: &RenderTarget,
+                          surfaces: &Vec<SurfaceContext>,
+                          pointer: Option<SurfaceContext>,
+                          coordinator: &Coordinator)
+                          -> Result<(), Illusion> {
+        let concealed = Self::find_concealed_areas(surfaces, coordinator);
+        let _context = self.egl.make_current()?;
+        self.view_transform = Transform::Normal;
+        if self.is_blending_linear() {
+            self.draw_scene_linear(target.framebuffer,
+                                   target.size,
+                                   surfaces,
+                                   pointer,
+                                   coordinator,
+                                   &concealed)?;
+        } else {
+            unsafe {
+                gl::BindFramebuffer(gl::FRAMEBUFFER, target.framebuffer);
+                gl::Viewport(0,
+                             0,
+                             target.size.width as gl::types::GLint,
+                             target.size.height as gl::types::GLint);
+            }
+            self.draw_scene(surfaces, pointer, coordinator, &concealed, false);
+        }
+        self.release_view();
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            gl::Viewport(0,
+                         0,
+                         self.size.width as gl::types::GLint,
+                         self.size.height as gl::types::GLint);
+        }
+        Ok(())
+    }
+
+    /// Creates off-screen render target of given size.
+    pub fn create_render_target(&self, size: Size) -> Result<RenderTarget, Illusion> {
+        self.create_target(size, gl::RGBA)
+    }
+
+    /// Creates off-screen render target of given size and internal format.
+    fn create_target(&self,
+                     size: Size,
+                     internal_format: gl::types::GLenum)
+                     -> Result<RenderTarget, Illusion> {
+        let _context = self.egl.make_current()?;
+        let mut target = RenderTarget {
+            framebuffer: 0,
+            texture: 0,
+            size: size,
+        };
+
+        let status = unsafe {
+            gl::GenTextures(1, &mut target.texture);
+            gl::BindTexture(gl::TEXTURE_2D, target.texture);
+            gl::TexImage2D(gl::TEXTURE_2D,
+                           0,
+                           internal_format as gl::types::GLint,
+                           size.width as gl::types::GLint,
+                           size.height as gl::types::GLint,
+                           0,
+                           gl::RGBA,
+                           gl::UNSIGNED_BYTE,
+                           std::ptr::null());
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
+
+            gl::GenFramebuffers(1, &mut target.framebuffer);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, target.framebuffer);
+            gl::FramebufferTexture2D(gl::FRAMEBUFFER,
+                                     gl::COLOR_ATTACHMENT0,
+                                     gl::TEXTURE_2D,
+                                     target.texture,
+                                     0);
+            let status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            status
+        };
+
+        if status == gl::FRAMEBUFFER_COMPLETE {
+            Ok(target)
+        } else {
+            self.destroy_render_target(target)?;
+            Err(Illusion::General(format!("Render target is incomplete ({:#x})", status)))
+        }
+    }
+
+    /// Releases resources of given render target.
+    pub fn destroy_render_target(&self, target: RenderTarget) -> Result<(), Illusion> {
+        let _context = self.egl.make_current()?;
+        unsafe {
+            gl::DeleteFramebuffers(1, &target.framebuffer);
+            gl::DeleteTextures(1, &target.texture);
+        }
+        Ok(())
+    }
+
+    /// Changes size of the screen after mode of the output was changed. Drawing continues to new
+    /// surface created for given window. Whole screen is redrawn in next frame.
+    pub fn resize(&mut self,
+                  window_type: egl::EGLNativeWindowType,
+                  size: Size)
+                  -> Result<(), Illusion> {
+        if let Some(target) = self.scene_target.take() {
+            self.destroy_render_target(target)?;
+        }
+        self.egl.replace_window_surface(window_type)?;
+        self.size = size;
+        self.damage_all = true;
+        self.damage_history.clear();
+        Ok(())
+    }
+
+    /// Sets number of pixels per logical unit. Positions and sizes of drawn surfaces are given in
+    /// logical units and the scene is scaled to fill the whole screen.
+    pub fn set_scale(&mut self, scale: f32) {
+        if self.scale != scale {
+            self.scale = scale;
+            self.damage_all = true;
+        }
+    }
+
+    /// Sets rotation and flipping of the screen. Scene is drawn in orientation seen by user and
+    /// rotated to fit the screen.
+    pub fn set_transform(&mut self, transform: Transform) {
+        if self.transform != transform {
+            self.transform = transform;
+            self.damage_all = true;
+        }
+    }
+
+    /// Enables or disables tinting of regions repainted in last frames.
+    pub fn set_damage_visualization(&mut self, enabled: bool) {
+        self.visualize_damage = enabled;
+        self.damage_history.clear();
+    }
+
+    /// Sets region being interactively selected or ends selection if `None`. Position of the
+    /// region is relative to the screen.
+    pub fn set_selection(&mut self, selection: Option<Area>) {
+        if self.selection != selection {
+            self.selection = selection;
+            self.damage_all = true;
+        }
+    }
+
+    /// Enables or disables blending in linear space. Blending in linear space requires sRGB
+    /// textures so is not available with OpenGL ES 2.0.
+    pub fn set_linear_blending(&mut self, enabled: bool) {
+        if enabled && self.loc_transfer.is_none() {
+            log_warn2!("Renderer: linear blending is not supported by GLSL version");
+        }
+        self.linear_blending = enabled;
+        self.damage_all = true;
+        if !enabled {
+            if let Some(target) = self.scene_target.take() {
+                if let Err(err) = self.destroy_render_target(target) {
+                    log_warn2!("Renderer: failed to destroy scene target: {}", err);
+                }
+            }
+        }
+    }
+
+    /// Sets image drawn as background and the way it is fitted to the screen. Image pixels are
+    /// expected to have red, green, blue and alpha bytes in this order. `None` removes the image
+    /// leaving plain background color.
+    pub fn set_wallpaper(&mut self,
+                         image: Option<&Buffer>,
+                         mode: WallpaperMode)
+                         -> Result<(), Illusion> {
+        let _context = self.egl.make_current()?;
+        self.damage_all = true;
+        if let Some(wallpaper) = self.wallpaper.take() {
+            unsafe {
+                gl::DeleteTextures(1, &wallpaper.texture);
+            }
+        }
+
+        if let Some(image) = image {
+            let mut wallpaper = Wallpaper {
+                texture: 0,
+                size: image.get_size(),
+                mode: mode,
+            };
+            unsafe {
+                gl::GenTextures(1, &mut wallpaper.texture);
+                gl::ActiveTexture(gl::TEXTURE0);
+                gl::BindTexture(gl::TEXTURE_2D, wallpaper.texture);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+                gl::TexImage2D(gl::TEXTURE_2D,
+                               0,
+                               gl::RGBA as gl::types::GLint,
+                               image.get_width() as gl::types::GLint,
+                               image.get_height() as gl::types::GLint,
+                               0,
+                               gl::RGBA,
+                               gl::UNSIGNED_BYTE,
+                               image.as_ptr() as *const _);
+            }
+            self.wallpaper = Some(wallpaper);
+        }
+        Ok(())
+    }
+
+    /// Reads contents of the frame buffer. Returned buffer has rows ordered from top to bottom and
+    /// pixels in RGBA format.
+    pub fn read_pixels(&self) -> Result<Buffer, Illusion> {
+        let _context = self.egl.make_current()?;
+        Ok(Self::read_bound_framebuffer(self.size))
+    }
+
+    /// Reads contents of given render target. Format is the same as in `read_pixels`.
+    pub fn read_target_pixels(&self, target: &RenderTarget) -> Result<Buffer, Illusion> {
+        let _context = self.egl.make_current()?;
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, target.framebuffer);
+        }
+        let buffer = Self::read_bound_framebuffer(target.size);
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+        Ok(buffer)
+    }
+
+    /// Draws passed scene to temporary off-screen target of the size of the renderer (with width
+    /// and height swapped if the screen is rotated by 90 or 270 degrees) and returns its contents.
+    /// Format is the same as in `read_pixels`. Surfaces excluded from captures are blacked out.
+    pub fn capture(&mut self,
+                   surfaces: &Vec<SurfaceContext>,
+                   pointer: Option<SurfaceContext>,
+                   coordinator: &Coordinator)
+                   -> Result<Buffer, Illusion> {
+        let target = self.create_render_target(self.get_upright_size())?;
+        let result = self.draw_to_target(&target, surfaces, pointer, coordinator)
+            .and_then(|_| self.read_target_pixels(&target));
+        self.destroy_render_target(target)?;
+        result
+    }
+
+    /// Swap buffers. If supported, display is informed which regions of the screen changed.
     pub fn swap_buffers(&mut self) -> Result<(), Illusion> {
         let context = self.egl.make_current()?;
-        context.swap_buffers()
+        match self.frame_damage {
+            Some(ref damage) if self.swap_with_damage => {
+                let screen = Area::new(Position::default(), self.size);
+                let mut rects = Vec::with_capacity(4 * damage.len());
+                let damage = damage.iter().map(|area| self.to_screen_area(area));
+                for area in damage.filter_map(|area| area.intersected(&screen)) {
+                    let bottom = area.pos.y as usize + area.size.height;
+                    rects.push(area.pos.x as egl::EGLint);
+                    rects.push((self.size.height - bottom) as egl::EGLint);
+                    rects.push(area.size.width as egl::EGLint);
+                    rects.push(area.size.height as egl::EGLint);
+                }
+                context.swap_buffers_with_damage(&rects)
+            }
+            _ => context.swap_buffers(),
+        }
     }
 }
 
@@ -145,86 +590,541 @@ impl RendererGl {
 
 /// Drawing helpers.
 impl RendererGl {
-    /// Prepare view for drawing.
-    fn prepare_view(&self) {
+    /// Prepare view for drawing. Background color is converted to linear space if the scene is
+    /// drawn to sRGB target.
+    fn prepare_view(&self, linear: bool) {
+        let color = if linear { to_linear(BACKGROUND_COLOR) } else { BACKGROUND_COLOR };
         unsafe {
-            gl::ClearColor(0.0, 0.3, 0.5, 1.0);
+            gl::ClearColor(color[0], color[1], color[2], color[3]);
             gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
 
-            gl::Enable(gl::BLEND);
-            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+            let size = self.get_logical_size();
+            let matrix = get_transform_matrix(self.view_transform);
+            gl::UseProgram(self.program);
+            gl::Uniform2i(self.loc_screen_size, size.width as i32, size.height as i32);
+            gl::UniformMatrix2fv(self.loc_transform, 1, gl::FALSE, matrix.as_ptr());
+        }
+        Self::set_blending(AlphaMode::Straight);
+    }
 
+    /// Selects blend function suitable for content with given alpha mode.
+    fn set_blending(alpha_mode: AlphaMode) {
+        unsafe {
+            match alpha_mode {
+                AlphaMode::Opaque => {
+                    gl::Disable(gl::BLEND);
+                }
+                AlphaMode::Straight => {
+                    gl::Enable(gl::BLEND);
+                    gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+                }
+                AlphaMode::Premultiplied => {
+                    gl::Enable(gl::BLEND);
+                    gl::BlendFunc(gl::ONE, gl::ONE_MINUS_SRC_ALPHA);
+                }
+            }
+        }
+    }
+
+    /// Selects conversion of colors sampled by fragment shader for content with given alpha mode.
+    fn set_transfer(&self, alpha_mode: AlphaMode, linear: bool) {
+        if let Some(loc_transfer) = self.loc_transfer {
+            let transfer = match (linear, alpha_mode) {
+                (false, _) => TRANSFER_NONE,
+                (true, AlphaMode::Premultiplied) => TRANSFER_TO_LINEAR_PREMULTIPLIED,
+                (true, _) => TRANSFER_TO_LINEAR,
+            };
+            unsafe {
+                gl::Uniform1i(loc_transfer, transfer);
+            }
+        }
+    }
+
+    /// Selects if red and blue components of sampled colors have to be swapped. Contents of
+    /// textures are uploaded as they are laid out in memory, so textures with blue byte first
+    /// have to be swizzled when drawn.
+    fn set_swizzle(&self, swizzle: bool) {
+        unsafe {
+            gl::Uniform1i(self.loc_swizzle, if swizzle { 1 } else { 0 });
+        }
+    }
+
+    /// Sets fraction by which brightness and saturation of drawn colors are reduced.
+    fn set_dim(&self, dim: f32) {
+        unsafe {
+            gl::Uniform1f(self.loc_dim, dim);
+        }
+    }
+
+    /// Checks if scene should be blended in linear space.
+    fn is_blending_linear(&self) -> bool {
+        self.linear_blending && self.loc_transfer.is_some()
+    }
+
+    /// Draw background image fitted to the screen according to its mode. `linear` tells if colors
+    /// have to be converted to linear space.
+    fn draw_bg_image(&self, linear: bool) {
+        let wallpaper = match self.wallpaper {
+            Some(ref wallpaper) => wallpaper,
+            None => return,
+        };
+
+        let tiles = wallpaper::layout(wallpaper.mode, wallpaper.size, self.get_logical_size());
+        let mut vertices: Vec<gl::types::GLfloat> = Vec::with_capacity(12 * tiles.len());
+        let mut texcoords: Vec<gl::types::GLfloat> = Vec::with_capacity(12 * tiles.len());
+        for tile in tiles.iter() {
+            let left = tile.area.pos.x as gl::types::GLfloat;
+            let top = tile.area.pos.y as gl::types::GLfloat;
+            let right = left + tile.area.size.width as gl::types::GLfloat;
+            let bottom = top + tile.area.size.height as gl::types::GLfloat;
+            vertices.extend_from_slice(&[left, top, right, top, left, bottom,
+                                         right, top, right, bottom, left, bottom]);
+
+            let (tex_left, tex_top) = (tile.texcoords[0], tile.texcoords[1]);
+            let (tex_right, tex_bottom) = (tile.texcoords[2], tile.texcoords[3]);
+            texcoords.extend_from_slice(&[tex_left, tex_top, tex_right, tex_top,
+                                          tex_left, tex_bottom, tex_right, tex_top,
+                                          tex_right, tex_bottom, tex_left, tex_bottom]);
+        }
+
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, wallpaper.texture);
+            gl::Uniform1i(self.loc_texture, 0);
+        }
+        self.set_transfer(AlphaMode::Opaque, linear);
+        self.set_swizzle(false);
+        self.set_dim(0.0);
+        Self::set_blending(AlphaMode::Opaque);
+        self.draw_textured(&vertices, &texcoords);
+        Self::set_blending(AlphaMode::Straight);
+    }
+
+    /// Draw background, surfaces and pointer to currently bound frame buffer. `concealed` areas
+    /// are covered before drawing pointer. `linear` tells if the frame buffer has sRGB encoding so
+    /// colors have to be converted to linear space.
+    fn draw_scene(&mut self,
+                  surfaces: &Vec<SurfaceContext>,
+                  pointer: Option<SurfaceContext>,
+                  coordinator: &Coordinator,
+                  concealed: &[Area],
+                  linear: bool) {
+        self.textures.begin_frame();
+        self.prepare_view(linear);
+        self.draw_bg_image(linear);
+        self.draw_surfaces(surfaces, coordinator, linear);
+        if concealed.len() > 0 {
+            self.draw_overlay(concealed, CONCEAL_COLOR, linear);
+            unsafe {
+                gl::UseProgram(self.program);
+            }
+        }
+        if let Some(pointer) = pointer {
+            self.draw_pointer(pointer, coordinator, linear);
+        }
+    }
+
+    /// Draws scene to target with sRGB encoding, so GL blends colors in linear space, and then
+    /// copies it to given frame buffer of given size converting colors back to sRGB.
+    fn draw_scene_linear(&mut self,
+                         framebuffer: gl::types::GLuint,
+                         size: Size,
+                         surfaces: &Vec<SurfaceContext>,
+                         pointer: Option<SurfaceContext>,
+                         coordinator: &Coordinator,
+                         concealed: &[Area])
+                         -> Result<(), Illusion> {
+        if self.scene_target.is_none() {
+            self.scene_target = Some(self.create_target(self.size, gl::SRGB8_ALPHA8)?);
+        }
+        let (scene_framebuffer, scene_texture) = match self.scene_target {
+            Some(ref target) => (target.framebuffer, target.texture),
+            None => return Ok(()),
+        };
+
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, scene_framebuffer);
+            gl::Viewport(0,
+                         0,
+                         self.size.width as gl::types::GLint,
+                         self.size.height as gl::types::GLint);
+        }
+        self.draw_scene(surfaces, pointer, coordinator, concealed, true);
+
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, framebuffer);
+            gl::Viewport(0, 0, size.width as gl::types::GLint, size.height as gl::types::GLint);
+        }
+        self.draw_scene_texture(scene_texture);
+        unsafe {
+            gl::Viewport(0,
+                         0,
+                         self.size.width as gl::types::GLint,
+                         self.size.height as gl::types::GLint);
+        }
+        Ok(())
+    }
+
+    /// Draws texture holding scene over the whole bound frame buffer converting its colors from
+    /// linear space to sRGB. Texture rows are ordered from bottom to top as it was drawn by GL.
+    /// Scene in the texture is already rotated, so it is copied without transformation.
+    fn draw_scene_texture(&self, texture: gl::types::GLuint) {
+        let size = self.get_logical_size();
+        let width = size.width as gl::types::GLfloat;
+        let height = size.height as gl::types::GLfloat;
+        let vertices: [gl::types::GLfloat; 12] =
+            [0.0, 0.0, width, 0.0, 0.0, height, width, 0.0, width, height, 0.0, height];
+        let texcoords: [gl::types::GLfloat; 12] =
+            [0.0, 1.0, 1.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0];
+
+        let matrix = get_transform_matrix(Transform::Normal);
+        unsafe {
             gl::UseProgram(self.program);
-            gl::Uniform2i(self.loc_screen_size, self.size.width as i32, self.size.height as i32);
+            gl::UniformMatrix2fv(self.loc_transform, 1, gl::FALSE, matrix.as_ptr());
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, texture);
+            gl::Uniform1i(self.loc_texture, 0);
+            if let Some(loc_transfer) = self.loc_transfer {
+                gl::Uniform1i(loc_transfer, TRANSFER_TO_SRGB);
+            }
         }
+        self.set_swizzle(false);
+        self.set_dim(0.0);
+        Self::set_blending(AlphaMode::Opaque);
+        self.draw_textured(&vertices, &texcoords);
+        Self::set_blending(AlphaMode::Straight);
     }
 
-    /// Draw background image.
-    fn draw_bg_image(&self) {}
+    /// Draws triangles with given positions and texture coordinates using currently bound
+    /// texture and main program.
+    fn draw_textured(&self, vertices: &[gl::types::GLfloat], texcoords: &[gl::types::GLfloat]) {
+        let size = vertices.len() * std::mem::size_of::<gl::types::GLfloat>();
+        let stride = 2 * std::mem::size_of::<gl::types::GLfloat>() as gl::types::GLint;
+
+        unsafe {
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo_vertices);
+            gl::EnableVertexAttribArray(self.loc_vertices as gl::types::GLuint);
+            gl::VertexAttribPointer(self.loc_vertices as gl::types::GLuint,
+                                    2,
+                                    gl::FLOAT,
+                                    gl::FALSE,
+                                    stride,
+                                    std::ptr::null());
+            gl::BufferData(gl::ARRAY_BUFFER,
+                           size as isize,
+                           vertices.as_ptr() as *const _,
+                           gl::DYNAMIC_DRAW);
+
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo_texcoords);
+            gl::EnableVertexAttribArray(self.loc_texcoords as gl::types::GLuint);
+            gl::VertexAttribPointer(self.loc_texcoords as gl::types::GLuint,
+                                    2,
+                                    gl::FLOAT,
+                                    gl::FALSE,
+                                    stride,
+                                    std::ptr::null());
+            gl::BufferData(gl::ARRAY_BUFFER,
+                           size as isize,
+                           texcoords.as_ptr() as *const _,
+                           gl::DYNAMIC_DRAW);
+
+            gl::DrawArrays(gl::TRIANGLES, 0, (vertices.len() / 2) as gl::types::GLint);
+
+            gl::DisableVertexAttribArray(self.loc_texcoords as gl::types::GLuint);
+            gl::DisableVertexAttribArray(self.loc_vertices as gl::types::GLuint);
+        }
+    }
+
+    /// Returns areas of surfaces excluded from captures. Satellites (e.g. pop-ups) of excluded
+    /// surfaces are excluded as well.
+    fn find_concealed_areas(surfaces: &Vec<SurfaceContext>,
+                            coordinator: &Coordinator)
+                            -> Vec<Area> {
+        let mut areas = Vec::new();
+        for context in surfaces.iter() {
+            if let Some(info) = coordinator.get_surface(context.id) {
+                let excluded = info.capture_excluded ||
+                               (info.parent_sid.is_valid() &&
+                                coordinator.get_surface(info.parent_sid)
+                                    .map(|parent| parent.capture_excluded)
+                                    .unwrap_or(false));
+                if !excluded {
+                    continue;
+                }
+
+                if let Some(placement) = coordinator.get_surface_placement(context) {
+                    areas.push(Area::new(context.pos + placement.target.pos,
+                                         placement.target.size));
+                }
+            }
+        }
+        areas
+    }
+
+    /// Reads contents of currently bound frame buffer of given size.
+    fn read_bound_framebuffer(size: Size) -> Buffer {
+        let stride = 4 * size.width;
+        let mut data = vec![0u8; stride * size.height];
+        unsafe {
+            gl::ReadPixels(0,
+                           0,
+                           size.width as gl::types::GLint,
+                           size.height as gl::types::GLint,
+                           gl::RGBA,
+                           gl::UNSIGNED_BYTE,
+                           data.as_mut_ptr() as *mut _);
+        }
+
+        // OpenGL places origin in lower left corner
+        let mut flipped = Vec::with_capacity(data.len());
+        for row in data.chunks(stride).rev() {
+            flipped.extend_from_slice(row);
+        }
+        Buffer::new(size.width, size.height, stride, flipped)
+    }
 
-    /// Load textures and prepare vertices.
-    fn load_texture_and_prepare_vertices(&self,
+    /// Calculates regions of screen changed since last frame basing on damage of surfaces. If
+    /// surfaces were added, removed, moved, resized or dimmed the whole screen is considered
+    /// changed.
+    fn calculate_frame_damage(&mut self,
+                              surfaces: &Vec<SurfaceContext>,
+                              pointer: &Option<SurfaceContext>,
+                              coordinator: &Coordinator)
+                              -> Option<Vec<Area>> {
+        let mut layout = Vec::with_capacity(surfaces.len() + 1);
+        let mut damage = Vec::new();
+        for context in surfaces.iter().chain(pointer.as_ref()) {
+            if let Some(placement) = coordinator.get_surface_placement(context) {
+                let target = Area::new(context.pos + placement.target.pos, placement.target.size);
+                layout.push((context.id, target, context.dim));
+                for region in context.damage.iter() {
+                    if let Some(area) = placement.map_damage(region) {
+                        damage.push(Area::new(context.pos + area.pos, area.size));
+                    }
+                }
+            }
+        }
+
+        let unchanged = layout == self.last_layout && !self.damage_all;
+        self.last_layout = layout;
+        self.damage_all = false;
+        if unchanged { Some(damage) } else { None }
+    }
+
+    /// Returns GL format and type of pixels of given format and tells if red and blue components
+    /// of them have to be swapped when drawn.
+    fn get_pixel_format(format: PixelFormat) -> (gl::types::GLenum, gl::types::GLenum, bool) {
+        match format {
+            PixelFormat::Argb8888 | PixelFormat::Xrgb8888 => (gl::RGBA, gl::UNSIGNED_BYTE, true),
+            PixelFormat::Rgb565 => (gl::RGB, gl::UNSIGNED_SHORT_5_6_5, false),
+        }
+    }
+
+    /// Uploads buffer of surface described by given context to given texture. If the texture
+    /// holds contents of the same size not older than contents damage is relative to only
+    /// damaged regions are uploaded, otherwise whole buffer. Damage is translated from surface to
+    /// buffer coordinates using given buffer transformation.
+    fn upload_buffer(buffer: &MemoryView,
+                     buffer_transform: &BufferTransform,
+                     texture: gl::types::GLuint,
+                     cached: Option<Contents>,
+                     current: Contents,
+                     context: &SurfaceContext) {
+        let damage_applies = cached.map_or(false, |cached| {
+            cached.size == current.size && cached.generation >= context.damage_generation
+        });
+
+        // Rows are assumed to be padded only to the alignment their stride allows
+        let stride = buffer.get_stride();
+        let alignment = if stride % 4 == 0 { 4 } else if stride % 2 == 0 { 2 } else { 1 };
+        let (format, kind, _) = Self::get_pixel_format(buffer.get_format());
+
+        unsafe {
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, texture);
+            gl::PixelStorei(gl::UNPACK_ALIGNMENT, alignment);
+            if damage_applies {
+                let surface_size = buffer_transform.get_surface_size(buffer.get_size());
+                for region in context.damage.iter() {
+                    let region = buffer_transform.to_buffer_area(region, surface_size);
+                    Self::upload_damaged_rows(buffer, &region, format, kind);
+                }
+            } else {
+                gl::TexImage2D(gl::TEXTURE_2D, // target
+                               0, // level, 0 = no mipmap
+                               format as gl::types::GLint, // internal format
+                               buffer.get_width() as gl::types::GLint, // width
+                               buffer.get_height() as gl::types::GLint, // height
+                               0, // always 0 in OpenGL ES
+                               format, // format
+                               kind, // type
+                               buffer.as_ptr() as *const _);
+            }
+            gl::PixelStorei(gl::UNPACK_ALIGNMENT, 4);
+        }
+    }
+
+    /// Uploads rows of buffer covered by damaged region to currently bound texture.
+    ///
+    /// OpenGL ES 2.0 does not allow to specify row length of uploaded data so whole rows are
+    /// uploaded.
+    fn upload_damaged_rows(buffer: &MemoryView,
+                           region: &Area,
+                           format: gl::types::GLenum,
+                           kind: gl::types::GLenum) {
+        let bounds = Area::new(Position::default(), buffer.get_size());
+        if let Some(area) = region.intersected(&bounds) {
+            let offset = area.pos.y as usize * buffer.get_stride();
+            unsafe {
+                gl::TexSubImage2D(gl::TEXTURE_2D, // target
+                                  0, // level, 0 = no mipmap
+                                  0, // x offset
+                                  area.pos.y as gl::types::GLint, // y offset
+                                  buffer.get_width() as gl::types::GLint, // width
+                                  area.size.height as gl::types::GLint, // height
+                                  format, // format
+                                  kind, // type
+                                  buffer.as_ptr().offset(offset as isize) as *const _);
+            }
+        }
+    }
+
+    /// Load texture and prepare vertices. Returns texture, alpha mode the surface should be drawn
+    /// with and whether red and blue components have to be swapped or `None` if the surface can
+    /// not be drawn.
+    ///
+    /// Contents are uploaded only if they were committed since last upload. If the texture holds
+    /// contents damage is relative to only damaged regions are uploaded. Surfaces not matching
+    /// size of space reserved for them are clipped, centered or scaled according to size mismatch
+    /// policy. Texture coordinates account for scale and transformation of the buffer.
+    fn load_texture_and_prepare_vertices(&mut self,
                                          coordinator: &Coordinator,
                                          context: &SurfaceContext,
                                          vertices: &mut [gl::types::GLfloat],
-                                         texcoords: &mut [gl::types::GLfloat],
-                                         index: usize) {
+                                         texcoords: &mut [gl::types::GLfloat])
+                                         -> Option<(gl::types::GLuint, AlphaMode, bool)> {
         if let Some(ref surface) = coordinator.get_surface(context.id) {
-            if let Some(ref buffer) = surface.buffer {
-                unsafe {
-                    gl::ActiveTexture(gl::TEXTURE0 + index as u32);
-                    gl::BindTexture(gl::TEXTURE_2D, self.vbo_texture[index]);
-                    gl::TexImage2D(gl::TEXTURE_2D, // target
-                                   0, // level, 0 = no mipmap
-                                   gl::RGBA as gl::types::GLint, // internal format
-                                   (*buffer).get_width() as gl::types::GLint, // width
-                                   (*buffer).get_height() as gl::types::GLint, // height
-                                   0, // always 0 in OpenGL ES
-                                   gl::RGBA, // format
-                                   gl::UNSIGNED_BYTE, // type
-                                   (*buffer).as_ptr() as *const _);
+            let (texture, contents) = self.textures.get(context.id);
+            let mut swizzle = false;
+            let size = if let Some(ref buffer) = surface.buffer {
+                let current = Contents {
+                    size: Some(buffer.get_size()),
+                    generation: context.generation,
+                };
+                if contents != Some(current) {
+                    Self::upload_buffer(buffer,
+                                        &surface.buffer_transform,
+                                        texture,
+                                        contents,
+                                        current,
+                                        context);
+                    self.textures.set_contents(context.id, Some(current));
                 }
-
-                let left = (context.pos.x - surface.offset.x) as gl::types::GLfloat;
-                let top = (context.pos.y - surface.offset.y) as gl::types::GLfloat;
-                let right = left + (*buffer).get_width() as gl::types::GLfloat;
-                let bottom = top + (*buffer).get_height() as gl::types::GLfloat;
-
-                vertices[0] = left;
-                vertices[1] = top;
-                vertices[2] = right;
-                vertices[3] = top;
-                vertices[4] = left;
-                vertices[5] = bottom;
-                vertices[6] = right;
-                vertices[7] = top;
-                vertices[8] = right;
-                vertices[9] = bottom;
-                vertices[10] = left;
-                vertices[11] = bottom;
-
-                texcoords[0] = 0.0;
-                texcoords[1] = 0.0;
-                texcoords[2] = 1.0;
-                texcoords[3] = 0.0;
-                texcoords[4] = 0.0;
-                texcoords[5] = 1.0;
-                texcoords[6] = 1.0;
-                texcoords[7] = 0.0;
-                texcoords[8] = 1.0;
-                texcoords[9] = 1.0;
-                texcoords[10] = 0.0;
-                texcoords[11] = 1.0;
+                swizzle = Self::get_pixel_format(buffer.get_format()).2;
+                buffer.get_size()
+            } else if let Some(ref hw_image) = surface.hw_image {
+                let current = Contents {
+                    size: None,
+                    generation: context.generation,
+                };
+                if contents != Some(current) {
+                    unsafe {
+                        gl::ActiveTexture(gl::TEXTURE0);
+                        gl::BindTexture(gl::TEXTURE_2D, texture);
+                    }
+                    let result = self.egl.create_image(hw_image).and_then(|i| i.bind_to_texture());
+                    if let Err(err) = result {
+                        log_error!("Renderer: Failed to import image for surface {}: {:?}",
+                                   context.id,
+                                   err);
+                        self.textures.set_contents(context.id, None);
+                        return None;
+                    }
+                    self.textures.set_contents(context.id, Some(current));
+                }
+                hw_image.get_size()
             } else {
                 log_error!("Renderer: No buffer for surface {}", context.id);
-            }
+                return None;
+            };
+
+            let placement = match coordinator.get_surface_placement(context) {
+                Some(placement) => placement,
+                None => return None,
+            };
+            let (target, source) = (placement.target, placement.source);
+
+            let left = (context.pos.x + target.pos.x) as gl::types::GLfloat;
+            let top = (context.pos.y + target.pos.y) as gl::types::GLfloat;
+            let right = left + target.size.width as gl::types::GLfloat;
+            let bottom = top + target.size.height as gl::types::GLfloat;
+
+            // Corners of drawn part of surface are mapped to buffer separately as the buffer may
+            // be rotated or flipped
+            let surface_size = surface.buffer_transform.get_surface_size(size);
+            let source_end = Position::new(source.pos.x + source.size.width as isize,
+                                           source.pos.y + source.size.height as isize);
+            let texcoord = |x: isize, y: isize| {
+                let position = surface.buffer_transform
+                    .to_buffer_position(Position::new(x, y), surface_size);
+                (position.x as gl::types::GLfloat / size.width.max(1) as gl::types::GLfloat,
+                 position.y as gl::types::GLfloat / size.height.max(1) as gl::types::GLfloat)
+            };
+            let top_left = texcoord(source.pos.x, source.pos.y);
+            let top_right = texcoord(source_end.x, source.pos.y);
+            let bottom_left = texcoord(source.pos.x, source_end.y);
+            let bottom_right = texcoord(source_end.x, source_end.y);
+
+            vertices[0] = left;
+            vertices[1] = top;
+            vertices[2] = right;
+            vertices[3] = top;
+            vertices[4] = left;
+            vertices[5] = bottom;
+            vertices[6] = right;
+            vertices[7] = top;
+            vertices[8] = right;
+            vertices[9] = bottom;
+            vertices[10] = left;
+            vertices[11] = bottom;
+
+            texcoords[0] = top_left.0;
+            texcoords[1] = top_left.1;
+            texcoords[2] = top_right.0;
+            texcoords[3] = top_right.1;
+            texcoords[4] = bottom_left.0;
+            texcoords[5] = bottom_left.1;
+            texcoords[6] = top_right.0;
+            texcoords[7] = top_right.1;
+            texcoords[8] = bottom_right.0;
+            texcoords[9] = bottom_right.1;
+            texcoords[10] = bottom_left.0;
+            texcoords[11] = bottom_left.1;
+            Some((texture, surface.alpha_mode, swizzle))
         } else {
             log_error!("Renderer: No info for surface {}", context.id);
+            None
+        }
+    }
+
+    /// Draw surfaces in batches not bigger than capacity of texture cache so textures are not
+    /// reassigned before they are drawn. `linear` tells if colors have to be converted to linear
+    /// space.
+    fn draw_surfaces(&mut self,
+                     surfaces: &[SurfaceContext],
+                     coordinator: &Coordinator,
+                     linear: bool) {
+        let capacity = self.textures.get_capacity();
+        for batch in surfaces.chunks(capacity) {
+            self.draw_surface_batch(batch, coordinator, linear);
         }
     }
 
-    /// Draw surfaces.
-    fn draw_surfaces(&self, surfaces: &Vec<SurfaceContext>, coordinator: &Coordinator) {
+    /// Draw batch of surfaces. Surfaces which can not be drawn are skipped.
+    fn draw_surface_batch(&mut self,
+                          surfaces: &[SurfaceContext],
+                          coordinator: &Coordinator,
+                          linear: bool) {
         if surfaces.len() == 0 {
             return;
         }
@@ -234,13 +1134,15 @@ impl RendererGl {
         let vertices_size = vertices_len * std::mem::size_of::<gl::types::GLfloat>();
         let mut vertices = vec![0.0; vertices_len];
         let mut texcoords = vec![0.0; vertices_len];
+        let mut draws = Vec::with_capacity(surfaces.len());
 
         for i in 0..surfaces.len() {
-            self.load_texture_and_prepare_vertices(coordinator,
-                                                   &surfaces[i],
-                                                   &mut vertices[12 * i..12 * i + 12],
-                                                   &mut texcoords[12 * i..12 * i + 12],
-                                                   i);
+            let draw =
+                self.load_texture_and_prepare_vertices(coordinator,
+                                                       &surfaces[i],
+                                                       &mut vertices[12 * i..12 * i + 12],
+                                                       &mut texcoords[12 * i..12 * i + 12]);
+            draws.push(draw);
         }
 
         unsafe {
@@ -275,10 +1177,19 @@ impl RendererGl {
                            gl::DYNAMIC_DRAW);
 
             // Redraw everything
-            for i in 0..surfaces.len() as i32 {
-                gl::Uniform1i(self.loc_texture, i);
-                gl::DrawArrays(gl::TRIANGLES, 6 * i, 6);
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::Uniform1i(self.loc_texture, 0);
+            for (i, draw) in draws.iter().enumerate() {
+                if let Some((texture, alpha_mode, swizzle)) = *draw {
+                    Self::set_blending(alpha_mode);
+                    self.set_transfer(alpha_mode, linear);
+                    self.set_swizzle(swizzle);
+                    self.set_dim(surfaces[i].dim);
+                    gl::BindTexture(gl::TEXTURE_2D, texture);
+                    gl::DrawArrays(gl::TRIANGLES, 6 * i as i32, 6);
+                }
             }
+            Self::set_blending(AlphaMode::Straight);
 
             // Release resources
             gl::DisableVertexAttribArray(self.loc_texcoords as gl::types::GLuint);
@@ -287,9 +1198,101 @@ impl RendererGl {
     }
 
     /// Draw pointer.
-    fn draw_pointer(&self, pointer: SurfaceContext, coordinator: &Coordinator) {
+    fn draw_pointer(&mut self, pointer: SurfaceContext, coordinator: &Coordinator, linear: bool) {
         let surfaces = vec![pointer];
-        self.draw_surfaces(&surfaces, coordinator);
+        self.draw_surfaces(&surfaces, coordinator, linear);
+    }
+
+    /// Tint regions repainted in last frames. The older the repaint the weaker the tint.
+    ///
+    /// Overlay changes also regions which were not damaged so when it is drawn the whole screen is
+    /// considered changed.
+    fn draw_damage_overlay(&self) {
+        for (age, damage) in self.damage_history.iter().enumerate() {
+            if damage.len() == 0 {
+                continue;
+            }
+
+            let fade = (DAMAGE_FADE_FRAMES - age) as f32 / DAMAGE_FADE_FRAMES as f32;
+            let color = [DAMAGE_TINT[0], DAMAGE_TINT[1], DAMAGE_TINT[2], DAMAGE_TINT[3] * fade];
+            self.draw_overlay(damage, color, false);
+        }
+    }
+
+    /// Fills given areas with given color using overlay program. `linear` tells if the color has
+    /// to be converted to linear space.
+    fn draw_overlay(&self, areas: &[Area], color: [f32; 4], linear: bool) {
+        let color = if linear { to_linear(color) } else { color };
+        let mut vertices: Vec<gl::types::GLfloat> = Vec::with_capacity(12 * areas.len());
+        for area in areas.iter() {
+            let left = area.pos.x as gl::types::GLfloat;
+            let top = area.pos.y as gl::types::GLfloat;
+            let right = left + area.size.width as gl::types::GLfloat;
+            let bottom = top + area.size.height as gl::types::GLfloat;
+            vertices.extend_from_slice(&[left, top, right, top, left, bottom,
+                                         right, top, right, bottom, left, bottom]);
+        }
+
+        let vertices_size = vertices.len() * std::mem::size_of::<gl::types::GLfloat>();
+        let size = self.get_logical_size();
+        let matrix = get_transform_matrix(self.view_transform);
+        unsafe {
+            gl::UseProgram(self.overlay_program);
+            gl::Uniform2i(self.loc_overlay_screen_size, size.width as i32, size.height as i32);
+            gl::UniformMatrix2fv(self.loc_overlay_transform, 1, gl::FALSE, matrix.as_ptr());
+            gl::Uniform4f(self.loc_overlay_color, color[0], color[1], color[2], color[3]);
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo_vertices);
+            gl::EnableVertexAttribArray(self.loc_overlay_vertices as gl::types::GLuint);
+            gl::VertexAttribPointer(self.loc_overlay_vertices as gl::types::GLuint,
+                                    2,
+                                    gl::FLOAT,
+                                    gl::FALSE,
+                                    2 *
+                                    std::mem::size_of::<gl::types::GLfloat>() as gl::types::GLint,
+                                    std::ptr::null());
+            gl::BufferData(gl::ARRAY_BUFFER,
+                           vertices_size as isize,
+                           vertices.as_ptr() as *const _,
+                           gl::DYNAMIC_DRAW);
+            gl::DrawArrays(gl::TRIANGLES, 0, (vertices.len() / 2) as gl::types::GLint);
+            gl::DisableVertexAttribArray(self.loc_overlay_vertices as gl::types::GLuint);
+        }
+    }
+
+    /// Returns size of the screen in logical units in orientation seen by user.
+    fn get_logical_size(&self) -> Size {
+        let size = self.get_upright_size();
+        Size::new((size.width as f32 / self.scale).round() as usize,
+                  (size.height as f32 / self.scale).round() as usize)
+    }
+
+    /// Returns size of the screen in pixels in orientation seen by user.
+    fn get_upright_size(&self) -> Size {
+        if self.transform.is_transposing() {
+            Size::new(self.size.height, self.size.width)
+        } else {
+            self.size
+        }
+    }
+
+    /// Translates area given in logical units to pixels of the screen in its natural orientation.
+    fn to_screen_area(&self, area: &Area) -> Area {
+        let area = area.scaled(self.scale);
+        let upright = self.get_upright_size();
+        let (width, height) = (upright.width as isize, upright.height as isize);
+        let (x, y) = (area.pos.x, area.pos.y);
+        let (w, h) = (area.size.width as isize, area.size.height as isize);
+        let (x, y, w, h) = match self.transform {
+            Transform::Normal => (x, y, w, h),
+            Transform::Rotated90 => (height - y - h, x, h, w),
+            Transform::Rotated180 => (width - x - w, height - y - h, w, h),
+            Transform::Rotated270 => (y, width - x - w, h, w),
+            Transform::Flipped => (width - x - w, y, w, h),
+            Transform::Flipped90 => (height - y - h, width - x - w, h, w),
+            Transform::Flipped180 => (x, height - y - h, w, h),
+            Transform::Flipped270 => (y, x, h, w),
+        };
+        Area::new(Position::new(x, y), Size::new(w as usize, h as usize))
     }
 
     /// Unbind framebuffer and program.
@@ -302,3 +1305,32 @@ impl RendererGl {
 }
 
 // -------------------------------------------------------------------------------------------------
+
+/// Returns column-major matrix rotating normalized device coordinates of scene in orientation seen
+/// by user to orientation of the screen with given transform.
+fn get_transform_matrix(transform: Transform) -> [gl::types::GLfloat; 4] {
+    match transform {
+        Transform::Normal => [1.0, 0.0, 0.0, 1.0],
+        Transform::Rotated90 => [0.0, -1.0, 1.0, 0.0],
+        Transform::Rotated180 => [-1.0, 0.0, 0.0, -1.0],
+        Transform::Rotated270 => [0.0, 1.0, -1.0, 0.0],
+        Transform::Flipped => [-1.0, 0.0, 0.0, 1.0],
+        Transform::Flipped90 => [0.0, 1.0, 1.0, 0.0],
+        Transform::Flipped180 => [1.0, 0.0, 0.0, -1.0],
+        Transform::Flipped270 => [0.0, -1.0, -1.0, 0.0],
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Converts sRGB-encoded color with straight alpha to linear space.
+fn to_linear(color: [f32; 4]) -> [f32; 4] {
+    let decode = |c: f32| if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    };
+    [decode(color[0]), decode(color[1]), decode(color[2]), color[3]]
+}
+
+// -------------------------------------------------------------------------------------------------
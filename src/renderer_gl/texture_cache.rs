@@ -0,0 +1,144 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains cache of textures holding contents of surfaces.
+//!
+//! Every drawn surface gets its own texture which is kept between frames so contents of surfaces
+//! which were not committed again do not have to be uploaded again. Number of textures is limited;
+//! when the limit is reached texture of the surface drawn least recently is given to the new
+//! surface.
+
+// -------------------------------------------------------------------------------------------------
+
+use std::collections::HashMap;
+
+use gl;
+
+use qualia::{Size, SurfaceId};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Describes contents of surface held by texture.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Contents {
+    /// Size of buffer uploaded to the texture. `None` if the texture is bound to hardware image.
+    pub size: Option<Size>,
+
+    /// Generation of surface contents (see `SurfaceContext::generation`).
+    pub generation: u64,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Texture holding contents of one surface.
+struct CachedTexture {
+    /// GL name of the texture.
+    texture: gl::types::GLuint,
+
+    /// Contents the texture holds. `None` if contents are not known (e.g. the texture was just
+    /// assigned).
+    contents: Option<Contents>,
+
+    /// Number of the frame the texture was used in last time.
+    last_used: u64,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Cache of textures of surfaces with least-recently-used eviction. Must be used with current GL
+/// context.
+pub struct TextureCache {
+    /// Textures by ID of surface they are assigned to.
+    textures: HashMap<SurfaceId, CachedTexture>,
+
+    /// Maximal number of textures.
+    capacity: usize,
+
+    /// Number of the current frame.
+    frame: u64,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl TextureCache {
+    /// Constructs new `TextureCache` holding at most `capacity` textures.
+    pub fn new(capacity: usize) -> Self {
+        TextureCache {
+            textures: HashMap::new(),
+            capacity: capacity,
+            frame: 0,
+        }
+    }
+
+    /// Returns maximal number of textures.
+    pub fn get_capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Marks beginning of new frame. Textures used in previous frames may be evicted.
+    pub fn begin_frame(&mut self) {
+        self.frame += 1;
+    }
+
+    /// Returns texture assigned to given surface, assigning one if needed, and description of
+    /// contents the texture holds (`None` if contents have to be uploaded whole).
+    ///
+    /// If the cache is full, texture used least recently is reassigned. Callers drawing more
+    /// surfaces than capacity of the cache in one frame must draw them in batches not bigger than
+    /// the capacity so textures are not reassigned before they are drawn.
+    pub fn get(&mut self, sid: SurfaceId) -> (gl::types::GLuint, Option<Contents>) {
+        let frame = self.frame;
+        if let Some(cached) = self.textures.get_mut(&sid) {
+            cached.last_used = frame;
+            return (cached.texture, cached.contents);
+        }
+
+        let texture = if self.textures.len() < self.capacity {
+            Self::generate_texture()
+        } else {
+            let evicted = self.textures
+                .iter()
+                .min_by_key(|&(_, cached)| cached.last_used)
+                .map(|(sid, _)| *sid)
+                .expect("Texture cache must not be empty when full");
+            self.textures.remove(&evicted).expect("Evicted texture must be cached").texture
+        };
+
+        self.textures.insert(sid,
+                             CachedTexture {
+                                 texture: texture,
+                                 contents: None,
+                                 last_used: frame,
+                             });
+        (texture, None)
+    }
+
+    /// Records that texture of given surface holds given contents or that the contents are not
+    /// known if `None`.
+    pub fn set_contents(&mut self, sid: SurfaceId, contents: Option<Contents>) {
+        if let Some(cached) = self.textures.get_mut(&sid) {
+            cached.contents = contents;
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+// Helper methods.
+impl TextureCache {
+    /// Creates new texture for surface contents.
+    fn generate_texture() -> gl::types::GLuint {
+        let mut texture = 0;
+        unsafe {
+            gl::GenTextures(1, &mut texture);
+            gl::BindTexture(gl::TEXTURE_2D, texture);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+        }
+        texture
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
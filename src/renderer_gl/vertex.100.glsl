@@ -5,6 +5,7 @@
 attribute vec2 vertices;
 attribute vec2 texcoords;
 uniform ivec2 screen_size;
+uniform mat2 transform;
 varying vec2 v_texcoords;
 
 void main(void)
@@ -12,6 +13,7 @@ void main(void)
     mat2 view_matrix = mat2(2.0/float(screen_size.x),          0.0,
                                     0.0,           -2.0/float(screen_size.y));
     vec2 translation_vector = vec2(-1.0, 1.0);
-    gl_Position = vec4(view_matrix * vertices + translation_vector, 0.0, 1.0);
+    vec2 position = view_matrix * vertices + translation_vector;
+    gl_Position = vec4(transform * position, 0.0, 1.0);
     v_texcoords = texcoords;
 }
@@ -22,10 +22,13 @@ use std::collections::HashMap;
 use std::error::Error;
 use std::io::{Cursor, SeekFrom, Seek};
 use std::os::unix::io::RawFd;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use byteorder::{NativeEndian, ReadBytesExt, WriteBytesExt};
 
 use nix;
+use nix::fcntl;
 use nix::sys::socket;
 use nix::sys::uio;
 
@@ -240,6 +243,7 @@ impl ClientSocket {
 pub struct DisplaySocket {
     fd: RawFd,
     path: std::path::PathBuf,
+    kept: Arc<AtomicBool>,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -279,6 +283,7 @@ impl DisplaySocket {
         Ok(DisplaySocket {
                fd: sockfd,
                path: path.to_owned(),
+               kept: Arc::new(AtomicBool::new(false)),
            })
     }
 
@@ -287,14 +292,42 @@ impl DisplaySocket {
     /// Path is created from system variables: `$XDG_RUNTIME_DIR/$WAYLAND_DISPLAY` or
     /// `$XDG_RUNTIME_DIR/wayland-0` if `$WAYLAND_DISPLAY` is not set.
     pub fn new_default() -> Result<Self, SkylaneError> {
+        Self::new(&Self::get_default_path()?)
+    }
+
+    /// Creates `DisplaySocket` from socket already bound to given path and listening, e.g.
+    /// inherited from process which executed this one after calling `keep_for_exec`.
+    pub fn from_fd(fd: RawFd, path: &std::path::Path) -> Result<Self, SkylaneError> {
+        try_sock!("Reusing",
+                  path,
+                  fcntl::fcntl(fd, fcntl::FcntlArg::F_SETFD(fcntl::FD_CLOEXEC)));
+        Ok(DisplaySocket {
+               fd: fd,
+               path: path.to_owned(),
+               kept: Arc::new(AtomicBool::new(false)),
+           })
+    }
+
+    /// Returns default path of display socket as described in `new_default`.
+    pub fn get_default_path() -> Result<std::path::PathBuf, SkylaneError> {
         let mut path = std::path::PathBuf::from(std::env::var("XDG_RUNTIME_DIR")?);
         if let Ok(sock) = std::env::var("WAYLAND_DISPLAY") {
             path.push(sock);
         } else {
             path.push("wayland-0");
         }
+        Ok(path)
+    }
 
-        Self::new(&path)
+    /// Makes socket survive execution of new program: socket file descriptor will be inherited by
+    /// the program and socket path will not be removed when `DisplaySocket` (or any of its clones)
+    /// is dropped.
+    pub fn keep_for_exec(&self) -> Result<RawFd, SkylaneError> {
+        try_sock!("Keeping",
+                  self.path,
+                  fcntl::fcntl(self.fd, fcntl::FcntlArg::F_SETFD(fcntl::FdFlag::empty())));
+        self.kept.store(true, Ordering::SeqCst);
+        Ok(self.fd)
     }
 
     /// Accepts client connection and return new `ClientSocket`.
@@ -313,7 +346,10 @@ impl DisplaySocket {
 
 impl Drop for DisplaySocket {
     fn drop(&mut self) {
-        // Remove socket path. Nothing to do with result.
+        // Remove socket path unless it is meant to be reused. Nothing to do with result.
+        if self.kept.load(Ordering::SeqCst) {
+            return;
+        }
         let _ = nix::unistd::unlink(self.path.as_path());
     }
 }
@@ -26,7 +26,22 @@ fn main() {
     let out_dir = env::var("OUT_DIR").expect("Read OUT_DIR variable");
     let src_dir = env::current_dir().expect("Get current directory");
 
-    for protocol in vec!["wayland", "xdg-shell-unstable-v6"] {
+    for protocol in vec!["wayland",
+                         "wayland-drm",
+                         "xdg-shell",
+                         "xdg-shell-unstable-v6",
+                         "xdg-decoration-unstable-v1",
+                         "linux-dmabuf-unstable-v1",
+                         "wlr-data-control-unstable-v1",
+                         "pointer-gestures-unstable-v1",
+                         "ext-idle-notify-v1",
+                         "ext-session-lock-v1",
+                         "wlr-screencopy-unstable-v1",
+                         "wlr-layer-shell-unstable-v1",
+                         "fractional-scale-v1",
+                         "xdg-output-unstable-v1",
+                         "wlr-output-management-unstable-v1",
+                         "presentation-time"] {
         let mut src_path = src_dir.clone();
         src_path.push("skylane_protocols");
         src_path.set_file_name(protocol);
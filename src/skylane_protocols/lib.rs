@@ -107,9 +107,51 @@ pub mod server {
     pub mod wayland {
         include!(concat!(env!("OUT_DIR"), "/wayland_server.rs"));
     }
+    pub mod drm {
+        include!(concat!(env!("OUT_DIR"), "/drm_server.rs"));
+    }
+    pub mod xdg_shell {
+        include!(concat!(env!("OUT_DIR"), "/xdg_shell_server.rs"));
+    }
     pub mod xdg_shell_unstable_v6 {
         include!(concat!(env!("OUT_DIR"), "/xdg_shell_unstable_v6_server.rs"));
     }
+    pub mod xdg_decoration_unstable_v1 {
+        include!(concat!(env!("OUT_DIR"), "/xdg_decoration_unstable_v1_server.rs"));
+    }
+    pub mod linux_dmabuf_unstable_v1 {
+        include!(concat!(env!("OUT_DIR"), "/linux_dmabuf_unstable_v1_server.rs"));
+    }
+    pub mod wlr_data_control_unstable_v1 {
+        include!(concat!(env!("OUT_DIR"), "/wlr_data_control_unstable_v1_server.rs"));
+    }
+    pub mod pointer_gestures_unstable_v1 {
+        include!(concat!(env!("OUT_DIR"), "/pointer_gestures_unstable_v1_server.rs"));
+    }
+    pub mod ext_idle_notify_v1 {
+        include!(concat!(env!("OUT_DIR"), "/ext_idle_notify_v1_server.rs"));
+    }
+    pub mod ext_session_lock_v1 {
+        include!(concat!(env!("OUT_DIR"), "/ext_session_lock_v1_server.rs"));
+    }
+    pub mod wlr_screencopy_unstable_v1 {
+        include!(concat!(env!("OUT_DIR"), "/wlr_screencopy_unstable_v1_server.rs"));
+    }
+    pub mod wlr_layer_shell_unstable_v1 {
+        include!(concat!(env!("OUT_DIR"), "/wlr_layer_shell_unstable_v1_server.rs"));
+    }
+    pub mod fractional_scale_v1 {
+        include!(concat!(env!("OUT_DIR"), "/fractional_scale_v1_server.rs"));
+    }
+    pub mod xdg_output_unstable_v1 {
+        include!(concat!(env!("OUT_DIR"), "/xdg_output_unstable_v1_server.rs"));
+    }
+    pub mod wlr_output_management_unstable_v1 {
+        include!(concat!(env!("OUT_DIR"), "/wlr_output_management_unstable_v1_server.rs"));
+    }
+    pub mod presentation_time {
+        include!(concat!(env!("OUT_DIR"), "/presentation_time_server.rs"));
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -118,9 +160,51 @@ pub mod client {
     pub mod wayland {
         include!(concat!(env!("OUT_DIR"), "/wayland_client.rs"));
     }
+    pub mod drm {
+        include!(concat!(env!("OUT_DIR"), "/drm_client.rs"));
+    }
+    pub mod xdg_shell {
+        include!(concat!(env!("OUT_DIR"), "/xdg_shell_client.rs"));
+    }
     pub mod xdg_shell_unstable_v6 {
         include!(concat!(env!("OUT_DIR"), "/xdg_shell_unstable_v6_client.rs"));
     }
+    pub mod xdg_decoration_unstable_v1 {
+        include!(concat!(env!("OUT_DIR"), "/xdg_decoration_unstable_v1_client.rs"));
+    }
+    pub mod linux_dmabuf_unstable_v1 {
+        include!(concat!(env!("OUT_DIR"), "/linux_dmabuf_unstable_v1_client.rs"));
+    }
+    pub mod wlr_data_control_unstable_v1 {
+        include!(concat!(env!("OUT_DIR"), "/wlr_data_control_unstable_v1_client.rs"));
+    }
+    pub mod pointer_gestures_unstable_v1 {
+        include!(concat!(env!("OUT_DIR"), "/pointer_gestures_unstable_v1_client.rs"));
+    }
+    pub mod ext_idle_notify_v1 {
+        include!(concat!(env!("OUT_DIR"), "/ext_idle_notify_v1_client.rs"));
+    }
+    pub mod ext_session_lock_v1 {
+        include!(concat!(env!("OUT_DIR"), "/ext_session_lock_v1_client.rs"));
+    }
+    pub mod wlr_screencopy_unstable_v1 {
+        include!(concat!(env!("OUT_DIR"), "/wlr_screencopy_unstable_v1_client.rs"));
+    }
+    pub mod wlr_layer_shell_unstable_v1 {
+        include!(concat!(env!("OUT_DIR"), "/wlr_layer_shell_unstable_v1_client.rs"));
+    }
+    pub mod fractional_scale_v1 {
+        include!(concat!(env!("OUT_DIR"), "/fractional_scale_v1_client.rs"));
+    }
+    pub mod xdg_output_unstable_v1 {
+        include!(concat!(env!("OUT_DIR"), "/xdg_output_unstable_v1_client.rs"));
+    }
+    pub mod wlr_output_management_unstable_v1 {
+        include!(concat!(env!("OUT_DIR"), "/wlr_output_management_unstable_v1_client.rs"));
+    }
+    pub mod presentation_time {
+        include!(concat!(env!("OUT_DIR"), "/presentation_time_client.rs"));
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -5,22 +5,37 @@
 
 // -------------------------------------------------------------------------------------------------
 
+use std;
 use std::collections::HashMap;
+use std::os::unix::io::RawFd;
+use std::path::PathBuf;
 
+use nix;
 use dharma;
 use skylane as wl;
 
 use qualia::{Axis, Button, Key, Milliseconds, OutputInfo, Position, Size, SurfaceId, KeyMods};
 use qualia::{Coordinator, KeyboardState, XkbKeymap, Perceptron, Settings, surface_state};
+use qualia::{Direction, GestureKind, GestureMotion, SecurityConfig, Transform, XkbConfig};
+use qualia::{Buffer, PresentationTime, SeatConfig, SeatId};
+use qualia::seat_capability::SeatCapabilities;
 
 use protocol;
+use protocol::data_device_manager::DataOffer;
+use protocol::data_control::DataControlOffer;
 use gateway::Gateway;
 use proxy::{Proxy, ProxyRef};
 use mediator::{Mediator, MediatorRef};
+use security::SecurityPolicy;
 use event_handlers::{ClientEventHandler, DisplayEventHandler};
 
 // -------------------------------------------------------------------------------------------------
 
+/// Environment variable passing file descriptor of display socket to restarted compositor.
+const DISPLAY_FD_VAR: &'static str = "PERCEPTIA_DISPLAY_FD";
+
+// -------------------------------------------------------------------------------------------------
+
 /// Helper structure for aggregating `Client` with its `Proxy`.
 struct ClientPackage {
     client: wl::server::Client,
@@ -39,26 +54,48 @@ pub struct Engine {
     output_infos: Vec<OutputInfo>,
     coordinator: Coordinator,
     settings: Settings,
+    security_policy: SecurityPolicy,
     dispatcher: dharma::Dispatcher,
     keyboard_state: KeyboardState,
+    seats: Vec<SeatConfig>,
+    seat_keyboard_states: HashMap<SeatId, KeyboardState>,
+    last_transferer: Option<dharma::EventHandlerId>,
+    drag_target: Option<dharma::EventHandlerId>,
 }
 
 // -------------------------------------------------------------------------------------------------
 
 impl Engine {
     /// Creates new `Engine`. Sets display socket up.
-    pub fn new(coordinator: Coordinator, settings: Settings) -> Self {
-        let xkb_keymap = XkbKeymap::default().expect("Creating XKB map");
+    pub fn new(coordinator: Coordinator,
+               settings: Settings,
+               security_config: SecurityConfig,
+               xkb_config: XkbConfig,
+               seats: Vec<SeatConfig>)
+               -> Self {
+        let xkb_keymap = XkbKeymap::new(&xkb_config)
+            .or_else(|| XkbKeymap::default())
+            .expect("Creating XKB map");
+
+        let mut seat_keyboard_states = HashMap::new();
+        for i in 0..seats.len() {
+            seat_keyboard_states.insert(SeatId::new(i + 1), KeyboardState::new(&xkb_keymap.keymap));
+        }
 
         Engine {
-            display: wl::server::DisplaySocket::new_default().expect("Creating display socket"),
+            display: Self::create_display_socket(),
             mediator: MediatorRef::new(Mediator::new()),
             clients: HashMap::new(),
             output_infos: Vec::new(),
             coordinator: coordinator,
             settings: settings,
+            security_policy: SecurityPolicy::new(security_config),
             dispatcher: dharma::Dispatcher::new(),
             keyboard_state: KeyboardState::new(&xkb_keymap.keymap),
+            seats: seats,
+            seat_keyboard_states: seat_keyboard_states,
+            last_transferer: None,
+            drag_target: None,
         }
     }
 
@@ -72,6 +109,15 @@ impl Engine {
     pub fn receive(&mut self) {
         self.dispatcher.wait_and_process(Some(0));
     }
+
+    /// Prepares display socket to be inherited by restarted compositor. Must be called before
+    /// `Engine` is dropped.
+    pub fn prepare_restart(&mut self) {
+        match self.display.keep_for_exec() {
+            Ok(fd) => std::env::set_var(DISPLAY_FD_VAR, fd.to_string()),
+            Err(err) => log_warn1!("Failed to keep display socket for restart: {:?}", err),
+        }
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -93,20 +139,43 @@ impl Engine {
                         dharma::event_kind::READ);
 
         // Prepare proxy.
+        let privileged = self.security_policy.is_privileged(&client_socket);
         let mut proxy = Proxy::new(id,
                                    self.coordinator.clone(),
                                    self.settings.clone(),
+                                   privileged,
                                    self.mediator.clone(),
                                    client_socket.clone());
         proxy.register_global(protocol::shm::get_global());
+        proxy.register_global(protocol::linux_dmabuf_v1::get_global());
+        if let Some(device) = self.get_drm_device() {
+            proxy.register_global(protocol::drm::get_global(device));
+        }
         proxy.register_global(protocol::compositor::get_global());
         proxy.register_global(protocol::shell::get_global());
+        proxy.register_global(protocol::xdg_shell::get_global());
         proxy.register_global(protocol::xdg_shell_v6::get_global());
+        proxy.register_global(protocol::xdg_decoration::get_global());
         proxy.register_global(protocol::data_device_manager::get_global());
-        proxy.register_global(protocol::seat::get_global());
+        proxy.register_global(protocol::data_control::get_global());
+        proxy.register_global(protocol::seat::get_global(SeatId::default_seat(),
+                                                         "seat0".to_owned()));
+        for (i, seat) in self.seats.iter().enumerate() {
+            proxy.register_global(protocol::seat::get_global(SeatId::new(i + 1),
+                                                             seat.name.clone()));
+        }
+        proxy.register_global(protocol::pointer_gestures::get_global());
+        proxy.register_global(protocol::idle_notify::get_global());
+        proxy.register_global(protocol::session_lock::get_global());
+        proxy.register_global(protocol::layer_shell::get_global());
+        proxy.register_global(protocol::screencopy::get_global());
         proxy.register_global(protocol::subcompositor::get_global());
+        proxy.register_global(protocol::fractional_scale::get_global());
+        proxy.register_global(protocol::xdg_output::get_global());
+        proxy.register_global(protocol::output_management::get_global());
+        proxy.register_global(protocol::presentation::get_global());
         for info in self.output_infos.iter() {
-            proxy.register_global(protocol::output::get_global(info.clone()));
+            proxy.on_display_created(info.clone());
         }
         let proxy_ref = ProxyRef::new(proxy);
 
@@ -119,6 +188,7 @@ impl Engine {
             proxy: proxy_ref,
         };
         self.clients.insert(id, pkg);
+        self.coordinator.set_client_count(self.clients.len());
     }
 
     /// Handles termination (socket hung up) of client.
@@ -136,12 +206,22 @@ impl Engine {
             log_warn2!("Proxy not found for client {} on termination", id);
             false
         };
+        self.coordinator.set_client_count(self.clients.len());
 
         if result1 && result2 {
             log_wayl3!("Client {} terminated successfully", id);
         }
     }
 
+    /// Disconnects client owning given surface.
+    pub fn on_surface_kill_requested(&mut self, sid: SurfaceId) {
+        let id = self.mediator.borrow().get_client_for_sid(sid).cloned();
+        if let Some(id) = id {
+            log_info1!("Killing client {} owning surface {}", id, sid);
+            self.terminate_client(id);
+        }
+    }
+
     /// Handles request from client associated with given `id`.
     pub fn process_events(&mut self, id: dharma::EventHandlerId) {
         if let Some(ref mut package) = self.clients.get_mut(&id) {
@@ -153,7 +233,132 @@ impl Engine {
             }
         } else {
             log_warn1!("Wayland Engine: No client: {}", id);
+            return;
+        }
+
+        // Newly created data control devices must be informed about current selection.
+        let new_data_control_device_oids = if let Some(package) = self.clients.get(&id) {
+            package.proxy.borrow_mut().take_new_data_control_device_oids()
+        } else {
+            Vec::new()
+        };
+        if new_data_control_device_oids.len() > 0 {
+            self.offer_control_transfer(id, new_data_control_device_oids);
+        }
+    }
+
+    /// Handles change of current data transfer (selection):
+    /// - informs previous owner its data source was cancelled
+    /// - advertises new selection to keyboard-focused client
+    /// - advertises new selection to all data control devices regardless of focus.
+    pub fn on_transfer_offered(&mut self) {
+        let transferer = self.mediator.borrow().get_transferer();
+        if self.last_transferer != transferer {
+            if let Some(id) = self.last_transferer {
+                if let Some(package) = self.clients.get(&id) {
+                    package.proxy.borrow_mut().cancel_selection();
+                }
+            }
+            self.last_transferer = transferer;
+        }
+
+        let sid = self.coordinator.get_keyboard_focused_sid();
+        let client_id = self.mediator.borrow().get_client_for_sid(sid).cloned();
+        if let Some(id) = client_id {
+            self.offer_transfer(id);
+        }
+
+        let ids: Vec<dharma::EventHandlerId> = self.clients.keys().cloned().collect();
+        for id in ids {
+            let data_control_device_oids = if let Some(package) = self.clients.get(&id) {
+                package.proxy.borrow().get_data_control_device_oids()
+            } else {
+                Vec::new()
+            };
+            self.offer_control_transfer(id, data_control_device_oids);
+        }
+    }
+
+    /// Handles change of surface over which data is dragged: informs previous target the data left
+    /// and advertises dragged data to the new one.
+    pub fn on_drag_focus_changed(&mut self,
+                                 _old_sid: SurfaceId,
+                                 new_sid: SurfaceId,
+                                 position: Position) {
+        if let Some(id) = self.drag_target {
+            if let Some(package) = self.clients.get(&id) {
+                package.proxy.borrow().leave_drag();
+            }
+        }
+
+        self.drag_target = self.mediator.borrow().get_client_for_sid(new_sid).cloned();
+        if let Some(id) = self.drag_target {
+            self.enter_drag(id, new_sid, position);
+        }
+    }
+
+    /// Handles request to switch keyboard layout. Client with keyboard focus is informed about
+    /// new layout.
+    pub fn on_keyboard_layout_switch(&mut self, direction: Direction) {
+        let forward = match direction {
+            Direction::Forward => true,
+            Direction::Backward => false,
+            _ => return,
+        };
+
+        if self.keyboard_state.cycle_layout(forward) {
+            let sid = self.coordinator.get_keyboard_focused_sid();
+            if let Some(id) = self.mediator.borrow().get_client_for_sid(sid) {
+                if let Some(package) = self.clients.get(&id) {
+                    let mods = self.keyboard_state.get_mods();
+                    package.proxy.borrow().send_keyboard_modifiers(SeatId::default_seat(), mods);
+                }
+            }
+        }
+    }
+
+    /// Handles change of security configuration. Clients which lost privileges are informed about
+    /// removal of privileged globals, clients which gained them - about their addition.
+    pub fn on_security_config_changed(&mut self, config: SecurityConfig) {
+        self.security_policy = SecurityPolicy::new(config);
+        for (_, package) in self.clients.iter() {
+            let mut proxy = package.proxy.borrow_mut();
+            let privileged = self.security_policy.is_privileged(&proxy.get_socket());
+            proxy.set_privileged(privileged);
+        }
+    }
+
+    /// Handles drop of dragged data on given surface.
+    pub fn on_drag_dropped(&mut self, sid: SurfaceId) {
+        if let Some(id) = self.mediator.borrow().get_client_for_sid(sid) {
+            if let Some(package) = self.clients.get(&id) {
+                package.proxy.borrow().drop_drag();
+            }
+        }
+
+        if let Some(id) = self.mediator.borrow().get_drag_source() {
+            if let Some(package) = self.clients.get(&id) {
+                package.proxy.borrow().on_drag_drop_performed();
+            }
+        }
+    }
+
+    /// Handles end of drag-and-drop operation.
+    pub fn on_drag_ended(&mut self, performed: bool) {
+        if let Some(id) = self.drag_target {
+            if let Some(package) = self.clients.get(&id) {
+                package.proxy.borrow().leave_drag();
+            }
+        }
+        self.drag_target = None;
+
+        let drag_source = self.mediator.borrow().get_drag_source();
+        if let Some(id) = drag_source {
+            if let Some(package) = self.clients.get(&id) {
+                package.proxy.borrow_mut().on_drag_ended(performed);
+            }
         }
+        self.mediator.borrow_mut().register_drag_source(None);
     }
 }
 
@@ -164,6 +369,102 @@ impl Engine {
     fn logger(s: String) {
         log_wayl4!("Skylane: {}", s);
     }
+
+    /// Creates display socket or takes over one inherited from compositor which restarted.
+    fn create_display_socket() -> wl::server::DisplaySocket {
+        let inherited = std::env::var(DISPLAY_FD_VAR).ok().and_then(|fd| fd.parse::<RawFd>().ok());
+        std::env::remove_var(DISPLAY_FD_VAR);
+        if let Some(fd) = inherited {
+            let path = wl::server::DisplaySocket::get_default_path();
+            match path.and_then(|path| wl::server::DisplaySocket::from_fd(fd, &path)) {
+                Ok(display) => {
+                    log_info1!("Reusing display socket after restart");
+                    return display;
+                }
+                Err(err) => log_warn1!("Failed to reuse display socket: {:?}", err),
+            }
+        }
+        wl::server::DisplaySocket::new_default().expect("Creating display socket")
+    }
+
+    /// Returns path to DRM device used by outputs. Clients should allocate their buffers on this
+    /// device.
+    fn get_drm_device(&self) -> Option<PathBuf> {
+        self.output_infos.iter().filter_map(|info| info.drm_device.clone()).next()
+    }
+
+    /// Advertises current selection on all data devices of given client. Data offers are created
+    /// here because only `Engine` has access to clients objects.
+    fn offer_transfer(&mut self, id: dharma::EventHandlerId) {
+        let transfer = self.coordinator.get_transfer();
+        if let Some(package) = self.clients.get_mut(&id) {
+            let data_device_oids = package.proxy.borrow().get_data_device_oids();
+            for data_device_oid in data_device_oids {
+                let data_offer_oid = if transfer.is_some() {
+                    let oid = package.proxy.borrow_mut().generate_next_server_oid();
+                    let offer = DataOffer::new_object(oid, false, package.proxy.clone());
+                    package.client.add_object(oid, offer);
+                    oid
+                } else {
+                    wl::common::ObjectId::new(0)
+                };
+                package.proxy.borrow().offer_selection(data_device_oid, data_offer_oid, &transfer);
+            }
+        }
+    }
+
+    /// Advertises current selection on given data control devices of given client.
+    fn offer_control_transfer(&mut self,
+                              id: dharma::EventHandlerId,
+                              data_device_oids: Vec<wl::common::ObjectId>) {
+        let transfer = self.coordinator.get_transfer();
+        if let Some(package) = self.clients.get_mut(&id) {
+            for data_device_oid in data_device_oids {
+                let data_offer_oid = if transfer.is_some() {
+                    let oid = package.proxy.borrow_mut().generate_next_server_oid();
+                    let offer = DataControlOffer::new_object(oid, package.proxy.clone());
+                    package.client.add_object(oid, offer);
+                    oid
+                } else {
+                    wl::common::ObjectId::new(0)
+                };
+                package.proxy
+                    .borrow()
+                    .offer_control_selection(data_device_oid, data_offer_oid, &transfer);
+            }
+        }
+    }
+
+    /// Advertises dragged data on all data devices of given client. Drags without data source are
+    /// advertised only to the client which started them.
+    fn enter_drag(&mut self, id: dharma::EventHandlerId, sid: SurfaceId, position: Position) {
+        let drag = if let Some(drag) = self.coordinator.get_drag() {
+            drag
+        } else {
+            return;
+        };
+
+        if drag.transfer.is_none() && self.mediator.borrow().get_drag_source() != Some(id) {
+            return;
+        }
+
+        if let Some(package) = self.clients.get_mut(&id) {
+            let data_device_oids = package.proxy.borrow().get_data_device_oids();
+            for data_device_oid in data_device_oids {
+                let data_offer_oid = if drag.transfer.is_some() {
+                    let oid = package.proxy.borrow_mut().generate_next_server_oid();
+                    let offer = DataOffer::new_object(oid, true, package.proxy.clone());
+                    package.client.add_object(oid, offer);
+                    oid
+                } else {
+                    wl::common::ObjectId::new(0)
+                };
+                package.proxy
+                    .borrow()
+                    .enter_drag(data_device_oid, data_offer_oid, sid, position, &drag.transfer);
+            }
+        }
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -176,14 +477,40 @@ impl Gateway for Engine {
         }
     }
 
+    fn on_display_destroyed(&mut self, output_id: i32) {
+        self.output_infos.retain(|info| info.id != output_id);
+        for (_, package) in self.clients.iter() {
+            package.proxy.borrow_mut().on_display_destroyed(output_id);
+        }
+    }
+
+    fn on_display_changed(&mut self, output_info: OutputInfo) {
+        for info in self.output_infos.iter_mut().filter(|info| info.id == output_info.id) {
+            *info = output_info.clone();
+        }
+        for (_, package) in self.clients.iter() {
+            package.proxy.borrow_mut().on_display_changed(output_info.clone());
+        }
+    }
+
     fn on_keyboard_input(&mut self, key: Key, _mods: Option<KeyMods>) {
-        let mods = if self.keyboard_state.update(key.code, key.value) {
-            Some(self.keyboard_state.get_mods())
-        } else {
-            None
+        let mods = {
+            let keyboard_state = if key.seat.is_default() {
+                &mut self.keyboard_state
+            } else if let Some(keyboard_state) = self.seat_keyboard_states.get_mut(&key.seat) {
+                keyboard_state
+            } else {
+                return;
+            };
+
+            if keyboard_state.update(key.code, key.value) {
+                Some(keyboard_state.get_mods())
+            } else {
+                None
+            }
         };
 
-        let sid = self.coordinator.get_keyboard_focused_sid();
+        let sid = self.coordinator.get_seat_keyboard_focused_sid(key.seat);
         if let Some(id) = self.mediator.borrow().get_client_for_sid(sid) {
             if let Some(package) = self.clients.get(&id) {
                 package.proxy.borrow_mut().on_keyboard_input(key, mods);
@@ -260,10 +587,73 @@ impl Gateway for Engine {
         }
     }
 
+    fn on_touch_down(&self, sid: SurfaceId, id: i32, position: Position, time: Milliseconds) {
+        if let Some(client_id) = self.mediator.borrow().get_client_for_sid(sid) {
+            if let Some(package) = self.clients.get(&client_id) {
+                package.proxy.borrow().on_touch_down(sid, id, position, time);
+            }
+        }
+    }
+
+    fn on_touch_motion(&self, sid: SurfaceId, id: i32, position: Position, time: Milliseconds) {
+        if let Some(client_id) = self.mediator.borrow().get_client_for_sid(sid) {
+            if let Some(package) = self.clients.get(&client_id) {
+                package.proxy.borrow().on_touch_motion(sid, id, position, time);
+            }
+        }
+    }
+
+    fn on_touch_up(&self, sid: SurfaceId, id: i32, time: Milliseconds) {
+        if let Some(client_id) = self.mediator.borrow().get_client_for_sid(sid) {
+            if let Some(package) = self.clients.get(&client_id) {
+                package.proxy.borrow().on_touch_up(sid, id, time);
+            }
+        }
+    }
+
+    fn on_touch_frame(&self, sid: SurfaceId) {
+        if let Some(client_id) = self.mediator.borrow().get_client_for_sid(sid) {
+            if let Some(package) = self.clients.get(&client_id) {
+                package.proxy.borrow().on_touch_frame(sid);
+            }
+        }
+    }
+
+    fn on_gesture_begin(&self,
+                        sid: SurfaceId,
+                        kind: GestureKind,
+                        fingers: u32,
+                        time: Milliseconds) {
+        if let Some(client_id) = self.mediator.borrow().get_client_for_sid(sid) {
+            if let Some(package) = self.clients.get(&client_id) {
+                package.proxy.borrow().on_gesture_begin(sid, kind, fingers, time);
+            }
+        }
+    }
+
+    fn on_gesture_update(&self, sid: SurfaceId, kind: GestureKind, motion: GestureMotion) {
+        if let Some(client_id) = self.mediator.borrow().get_client_for_sid(sid) {
+            if let Some(package) = self.clients.get(&client_id) {
+                package.proxy.borrow().on_gesture_update(sid, kind, motion);
+            }
+        }
+    }
+
+    fn on_gesture_end(&self,
+                      sid: SurfaceId,
+                      kind: GestureKind,
+                      cancelled: bool,
+                      time: Milliseconds) {
+        if let Some(client_id) = self.mediator.borrow().get_client_for_sid(sid) {
+            if let Some(package) = self.clients.get(&client_id) {
+                package.proxy.borrow().on_gesture_end(sid, kind, cancelled, time);
+            }
+        }
+    }
+
     fn on_keyboard_focus_changed(&mut self, old_sid: SurfaceId, new_sid: SurfaceId) {
-        let mediator = self.mediator.borrow();
-        let old_client_id = mediator.get_client_for_sid(old_sid);
-        let new_client_id = mediator.get_client_for_sid(new_sid);
+        let old_client_id = self.mediator.borrow().get_client_for_sid(old_sid).cloned();
+        let new_client_id = self.mediator.borrow().get_client_for_sid(new_sid).cloned();
 
         if new_client_id != old_client_id {
             if let Some(client_id) = old_client_id {
@@ -274,9 +664,12 @@ impl Gateway for Engine {
             }
             if let Some(client_id) = new_client_id {
                 if let Some(package) = self.clients.get(&client_id) {
-                    package.proxy.borrow_mut().on_keyboard_focus_changed(SurfaceId::invalid(),
-                                                                         new_sid);
+                    let mut proxy = package.proxy.borrow_mut();
+                    proxy.on_keyboard_focus_changed(SurfaceId::invalid(), new_sid);
+                    proxy.send_keyboard_modifiers(SeatId::default_seat(),
+                                                  self.keyboard_state.get_mods());
                 }
+                self.offer_transfer(client_id);
             }
         } else {
             if let Some(client_id) = old_client_id {
@@ -287,16 +680,173 @@ impl Gateway for Engine {
         }
     }
 
-    fn on_surface_reconfigured(&self,
+    fn on_seat_keyboard_focus_changed(&mut self,
+                                      seat: SeatId,
+                                      old_sid: SurfaceId,
+                                      new_sid: SurfaceId) {
+        let old_client_id = self.mediator.borrow().get_client_for_sid(old_sid).cloned();
+        let new_client_id = self.mediator.borrow().get_client_for_sid(new_sid).cloned();
+
+        if new_client_id != old_client_id {
+            if let Some(client_id) = old_client_id {
+                if let Some(package) = self.clients.get(&client_id) {
+                    package.proxy
+                        .borrow_mut()
+                        .on_seat_keyboard_focus_changed(seat, old_sid, SurfaceId::invalid());
+                }
+            }
+            if let Some(client_id) = new_client_id {
+                if let Some(package) = self.clients.get(&client_id) {
+                    let mut proxy = package.proxy.borrow_mut();
+                    proxy.on_seat_keyboard_focus_changed(seat, SurfaceId::invalid(), new_sid);
+                    if let Some(keyboard_state) = self.seat_keyboard_states.get(&seat) {
+                        proxy.send_keyboard_modifiers(seat, keyboard_state.get_mods());
+                    }
+                }
+            }
+        } else {
+            if let Some(client_id) = old_client_id {
+                if let Some(package) = self.clients.get(&client_id) {
+                    package.proxy
+                        .borrow_mut()
+                        .on_seat_keyboard_focus_changed(seat, old_sid, new_sid);
+                }
+            }
+        }
+    }
+
+    fn on_seat_capabilities_changed(&mut self, seat: SeatId, capabilities: SeatCapabilities) {
+        for (_, package) in self.clients.iter() {
+            package.proxy.borrow_mut().on_seat_capabilities_changed(seat, capabilities);
+        }
+    }
+
+    fn on_idle(&mut self, timeout: u32) {
+        for (_, package) in self.clients.iter() {
+            package.proxy.borrow_mut().on_idle(timeout);
+        }
+    }
+
+    fn on_resumed(&mut self) {
+        for (_, package) in self.clients.iter() {
+            package.proxy.borrow_mut().on_resumed();
+        }
+    }
+
+    fn on_surface_reconfigured(&mut self,
                                sid: SurfaceId,
                                size: Size,
                                state_flags: surface_state::SurfaceState) {
         if let Some(id) = self.mediator.borrow().get_client_for_sid(sid) {
             if let Some(package) = self.clients.get(&id) {
-                package.proxy.borrow().on_surface_reconfigured(sid, size, state_flags);
+                package.proxy.borrow_mut().on_surface_reconfigured(sid, size, state_flags);
+            }
+        }
+    }
+
+    fn on_surface_close_requested(&self, sid: SurfaceId) {
+        if let Some(id) = self.mediator.borrow().get_client_for_sid(sid) {
+            if let Some(package) = self.clients.get(&id) {
+                package.proxy.borrow().on_surface_close_requested(sid);
             }
         }
     }
+
+    fn on_decoration_mode_changed(&mut self, sid: SurfaceId) {
+        if let Some(id) = self.mediator.borrow().get_client_for_sid(sid) {
+            if let Some(package) = self.clients.get(&id) {
+                package.proxy.borrow_mut().on_decoration_mode_changed(sid);
+            }
+        }
+    }
+
+    fn on_surface_output_entered(&self, sid: SurfaceId, output_id: i32) {
+        if let Some(id) = self.mediator.borrow().get_client_for_sid(sid) {
+            if let Some(package) = self.clients.get(&id) {
+                package.proxy.borrow().on_surface_output_entered(sid, output_id);
+            }
+        }
+    }
+
+    fn on_surface_output_left(&self, sid: SurfaceId, output_id: i32) {
+        if let Some(id) = self.mediator.borrow().get_client_for_sid(sid) {
+            if let Some(package) = self.clients.get(&id) {
+                package.proxy.borrow().on_surface_output_left(sid, output_id);
+            }
+        }
+    }
+
+    fn on_preferred_buffer_changed(&mut self, sid: SurfaceId, scale: f32, transform: Transform) {
+        if let Some(id) = self.mediator.borrow().get_client_for_sid(sid) {
+            if let Some(package) = self.clients.get(&id) {
+                package.proxy.borrow_mut().on_preferred_buffer_changed(sid, scale, transform);
+            }
+        }
+    }
+
+    fn on_transfer_requested(&mut self, mime_type: String, fd: RawFd) {
+        if let Some(id) = self.mediator.borrow().get_transferer() {
+            if let Some(package) = self.clients.get(&id) {
+                package.proxy.borrow_mut().on_transfer_requested(mime_type, fd);
+            }
+        }
+
+        // File descriptor was already passed to the owner of the transfer.
+        if let Err(err) = nix::unistd::close(fd) {
+            log_warn3!("Failed to close transfer file descriptor: {:?}", err);
+        }
+    }
+
+    fn on_drag_motion(&self, sid: SurfaceId, position: Position, milliseconds: Milliseconds) {
+        if let Some(id) = self.mediator.borrow().get_client_for_sid(sid) {
+            if let Some(package) = self.clients.get(&id) {
+                package.proxy.borrow().on_drag_motion(sid, position, milliseconds);
+            }
+        }
+    }
+
+    fn on_output_captured(&mut self, output_id: i32, frame: Buffer) {
+        for (_, package) in self.clients.iter() {
+            package.proxy.borrow_mut().on_output_captured(output_id, frame.clone());
+        }
+    }
+
+    fn on_outputs_configured(&mut self, id: u32, result: Result<(), String>) {
+        for (_, package) in self.clients.iter() {
+            package.proxy.borrow_mut().on_outputs_configured(id, result.clone());
+        }
+    }
+
+    fn on_frame_presented(&mut self, output_id: i32, sids: Vec<SurfaceId>, time: PresentationTime) {
+        let mut client_sids: HashMap<dharma::EventHandlerId, Vec<SurfaceId>> = HashMap::new();
+        {
+            let mediator = self.mediator.borrow();
+            for sid in sids {
+                if let Some(id) = mediator.get_client_for_sid(sid) {
+                    client_sids.entry(*id).or_insert_with(Vec::new).push(sid);
+                }
+            }
+        }
+
+        for (id, sids) in client_sids {
+            if let Some(package) = self.clients.get(&id) {
+                package.proxy.borrow_mut().on_frame_presented(output_id, sids, time);
+            }
+        }
+    }
+
+    fn on_drag_transfer_requested(&mut self, mime_type: String, fd: RawFd) {
+        if let Some(id) = self.mediator.borrow().get_drag_source() {
+            if let Some(package) = self.clients.get(&id) {
+                package.proxy.borrow_mut().on_drag_transfer_requested(mime_type, fd);
+            }
+        }
+
+        // File descriptor was already passed to the owner of the dragged data.
+        if let Err(err) = nix::unistd::close(fd) {
+            log_warn3!("Failed to close transfer file descriptor: {:?}", err);
+        }
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -5,43 +5,29 @@
 
 // -------------------------------------------------------------------------------------------------
 
+use std::os::unix::io::RawFd;
+
 use skylane as wl;
 
 use qualia::{Area, MappedMemory, Size, SurfaceId, Vector, MemoryPoolId, MemoryViewId, show_reason};
+use qualia::{AlphaMode, DecorationMode, HwImage, HwImageId, PixelFormat, Positioner, SeatId};
+use qualia::{Layer, LayerSurface, OutputConfiguration, Transform};
+use qualia::seat_capability::SeatCapabilities;
+
+use protocol::output_management::OutputHead;
 
 // -------------------------------------------------------------------------------------------------
 
 /// Enum describing type of shell and related object IDs.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug)]
 pub enum ShellSurfaceOid {
     Shell(wl::common::ObjectId),
     ZxdgToplevelV6(wl::common::ObjectId, wl::common::ObjectId),
-}
-
-// -------------------------------------------------------------------------------------------------
-
-/// Data realted to positioner object.
-#[derive(Clone, Copy)]
-pub struct PositionerInfo {
-    pub offset: Vector,
-    pub size: Size,
-    pub anchor: Area,
-}
-
-// -------------------------------------------------------------------------------------------------
-
-impl PositionerInfo {
-    pub fn new() -> Self {
-        PositionerInfo {
-            offset: Vector::default(),
-            size: Size::default(),
-            anchor: Area::default(),
-        }
-    }
-
-    pub fn get_area(&self) -> Area {
-        Area::new(self.offset + self.anchor.pos, self.size)
-    }
+    XdgToplevel(wl::common::ObjectId, wl::common::ObjectId),
+    ZxdgPopupV6(wl::common::ObjectId, wl::common::ObjectId),
+    XdgPopup(wl::common::ObjectId, wl::common::ObjectId),
+    SessionLock(wl::common::ObjectId),
+    LayerSurface(wl::common::ObjectId),
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -57,42 +43,245 @@ pub trait Facade {
     /// Requests replacement of mapped memory after resize request afrom client.
     fn replace_memory_pool(&mut self, mpid: MemoryPoolId, memory: MappedMemory);
 
-    /// Requests creation of memory view.
+    /// Requests creation of memory view. `alpha_mode` is derived from format of client buffer.
     fn create_memory_view(&mut self,
                           mpid: MemoryPoolId,
                           buffer_oid: wl::common::ObjectId,
                           offset: usize,
                           width: usize,
                           height: usize,
-                          stride: usize)
+                          stride: usize,
+                          format: PixelFormat,
+                          alpha_mode: AlphaMode)
                           -> Option<MemoryViewId>;
 
     /// Requests destruction of memory view.
     fn destroy_memory_view(&mut self, mvid: MemoryViewId);
 
+    /// Requests creation of hardware image (e.g. imported from DMA-BUF).
+    fn create_hw_image(&mut self, buffer_oid: wl::common::ObjectId, image: HwImage) -> HwImageId;
+
+    /// Requests destruction of hardware image.
+    fn destroy_hw_image(&mut self, hiid: HwImageId);
+
     /// Defines region. Regions may be used to define input area of surface.
     fn define_region(&mut self, region_oid: wl::common::ObjectId, region: Area);
 
     /// Undefines region.
     fn undefine_region(&mut self, region_oid: wl::common::ObjectId);
 
+    /// Remembers version in which client bound global object.
+    fn set_object_version(&mut self, oid: wl::common::ObjectId, version: u32);
+
+    /// Makes object inherit version of object it was created from.
+    fn inherit_object_version(&mut self,
+                              oid: wl::common::ObjectId,
+                              parent_oid: wl::common::ObjectId);
+
+    /// Returns version of object. Objects with unknown version are assumed to have version 1.
+    fn get_object_version(&self, oid: wl::common::ObjectId) -> u32;
+
+    /// Adds registry OID. Registries are informed about globals added and removed later.
+    fn add_registry_oid(&mut self, registry_oid: wl::common::ObjectId);
+
+    /// Adds seat OID bound to given seat.
+    fn add_seat_oid(&mut self, seat_oid: wl::common::ObjectId, seat: SeatId);
+
+    /// Removes seat OID.
+    fn remove_seat_oid(&mut self, seat_oid: wl::common::ObjectId);
+
+    /// Returns kinds of input devices currently available in given seat.
+    fn get_seat_capabilities(&self, seat: SeatId) -> SeatCapabilities;
+
     /// Adds pointer OID.
     fn add_pointer_oid(&mut self, pointer_oid: wl::common::ObjectId);
 
     /// Removes pointer OID.
     fn remove_pointer_oid(&mut self, pointer_oid: wl::common::ObjectId);
 
-    /// Adds keyboard OID.
-    fn add_keyboard_oid(&mut self, keyboard_oid: wl::common::ObjectId);
+    /// Adds keyboard OID bound to given seat.
+    fn add_keyboard_oid(&mut self, keyboard_oid: wl::common::ObjectId, seat: SeatId);
 
     /// Removes keyboard OID.
     fn remove_keyboard_oid(&mut self, keyboard_oid: wl::common::ObjectId);
 
+    /// Adds touch OID.
+    fn add_touch_oid(&mut self, touch_oid: wl::common::ObjectId);
+
+    /// Removes touch OID.
+    fn remove_touch_oid(&mut self, touch_oid: wl::common::ObjectId);
+
+    /// Adds swipe gesture OID.
+    fn add_swipe_gesture_oid(&mut self, gesture_oid: wl::common::ObjectId);
+
+    /// Removes swipe gesture OID.
+    fn remove_swipe_gesture_oid(&mut self, gesture_oid: wl::common::ObjectId);
+
+    /// Adds pinch gesture OID.
+    fn add_pinch_gesture_oid(&mut self, gesture_oid: wl::common::ObjectId);
+
+    /// Removes pinch gesture OID.
+    fn remove_pinch_gesture_oid(&mut self, gesture_oid: wl::common::ObjectId);
+
+    /// Adds idle notification OID with its timeout in milliseconds.
+    fn add_idle_notification_oid(&mut self, notification_oid: wl::common::ObjectId, timeout: u32);
+
+    /// Removes idle notification OID.
+    fn remove_idle_notification_oid(&mut self, notification_oid: wl::common::ObjectId);
+
+    /// Requests locking the session. Informs session lock object if it succeeded.
+    fn lock_session(&mut self, lock_oid: wl::common::ObjectId);
+
+    /// Unlocks session locked with given session lock object.
+    fn unlock_session(&mut self, lock_oid: wl::common::ObjectId);
+
+    /// Handles destruction of session lock object. Session locked by destroyed object stays locked.
+    fn destroy_session_lock(&mut self, lock_oid: wl::common::ObjectId);
+
+    /// Makes surface cover output while session is locked.
+    fn add_lock_surface(&mut self,
+                        lock_surface_oid: wl::common::ObjectId,
+                        surface_oid: wl::common::ObjectId,
+                        output_oid: wl::common::ObjectId);
+
+    /// Stops using surface as lock surface.
+    fn remove_lock_surface(&mut self, surface_oid: wl::common::ObjectId);
+
+    /// Makes surface placed in layer on given output (or output chosen by compositor if null).
+    /// Returns initial state of the layer surface or `None` if the surface is not known.
+    fn add_layer_surface(&mut self,
+                         layer_surface_oid: wl::common::ObjectId,
+                         surface_oid: wl::common::ObjectId,
+                         output_oid: wl::common::ObjectId,
+                         layer: Layer,
+                         namespace: String)
+                         -> Option<LayerSurface>;
+
+    /// Sets pending state of layer surface. The state will be applied on next commit.
+    fn set_pending_layer_surface(&mut self, state: LayerSurface);
+
+    /// Stops placing surface in layer.
+    fn remove_layer_surface(&mut self, surface_oid: wl::common::ObjectId);
+
+    /// Adds OID of `wl_output` object representing output with given ID.
+    fn add_output_oid(&mut self, output_id: i32, output_oid: wl::common::ObjectId);
+
+    /// Removes output OID.
+    fn remove_output_oid(&mut self, output_oid: wl::common::ObjectId);
+
+    /// Adds OID of `zxdg_output_v1` object describing output represented by given `wl_output`
+    /// object and sends logical geometry of the output.
+    fn add_xdg_output_oid(&mut self,
+                          xdg_output_oid: wl::common::ObjectId,
+                          output_oid: wl::common::ObjectId);
+
+    /// Removes OID of `zxdg_output_v1` object.
+    fn remove_xdg_output_oid(&mut self, xdg_output_oid: wl::common::ObjectId);
+
+    /// Adds OID of `zwlr_output_manager_v1` object and advertises current outputs to it.
+    fn add_output_manager_oid(&mut self, manager_oid: wl::common::ObjectId);
+
+    /// Removes OID of `zwlr_output_manager_v1` object and finishes heads advertised to it.
+    fn remove_output_manager_oid(&mut self, manager_oid: wl::common::ObjectId);
+
+    /// Returns heads advertised to given `zwlr_output_manager_v1` object.
+    fn get_output_heads(&self, manager_oid: wl::common::ObjectId) -> Vec<OutputHead>;
+
+    /// Requests applying or testing configuration of outputs. Configuration based on outdated
+    /// serial is cancelled. Result will be sent to `zwlr_output_configuration_v1` object.
+    fn request_output_configuration(&mut self,
+                                    configuration_oid: wl::common::ObjectId,
+                                    serial: u32,
+                                    configurations: Vec<OutputConfiguration>,
+                                    test: bool);
+
+    /// Removes OID of `zwlr_output_configuration_v1` object.
+    fn remove_output_configuration_oid(&mut self, configuration_oid: wl::common::ObjectId);
+
+    /// Adds screencopy frame capturing given region of output or the whole output. Informs the
+    /// frame about required buffer parameters or about failure if output is not known.
+    fn add_screencopy_frame(&mut self,
+                            frame_oid: wl::common::ObjectId,
+                            output_oid: wl::common::ObjectId,
+                            region: Option<Area>);
+
+    /// Requests copying output contents to given buffer. Frame is informed when copy is ready or
+    /// failed.
+    fn copy_screencopy_frame(&mut self,
+                             frame_oid: wl::common::ObjectId,
+                             buffer_oid: wl::common::ObjectId,
+                             with_damage: bool);
+
+    /// Removes screencopy frame.
+    fn remove_screencopy_frame(&mut self, frame_oid: wl::common::ObjectId);
+
+    /// Adds data device OID.
+    fn add_data_device_oid(&mut self, data_device_oid: wl::common::ObjectId);
+
+    /// Removes data device OID.
+    fn remove_data_device_oid(&mut self, data_device_oid: wl::common::ObjectId);
+
+    /// Adds data control device OID.
+    fn add_data_control_device_oid(&mut self, data_device_oid: wl::common::ObjectId);
+
+    /// Removes data control device OID.
+    fn remove_data_control_device_oid(&mut self, data_device_oid: wl::common::ObjectId);
+
+    /// Requests creation of data source.
+    fn create_data_source(&mut self, source_oid: wl::common::ObjectId);
+
+    /// Requests creation of data source controlled by clipboard manager.
+    fn create_data_control_source(&mut self, source_oid: wl::common::ObjectId);
+
+    /// Requests destruction of data source. If the source is current selection, the selection is
+    /// cleared.
+    fn destroy_data_source(&mut self, source_oid: wl::common::ObjectId);
+
+    /// Adds MIME type to list of types offered by data source.
+    fn add_mime_type(&mut self, source_oid: wl::common::ObjectId, mime_type: String);
+
+    /// Sets given data source as current selection. Null object ID clears the selection.
+    fn set_selection(&mut self, source_oid: wl::common::ObjectId);
+
+    /// Requests data of current selection in given MIME type to be written to given file
+    /// descriptor.
+    fn request_transfer(&mut self, mime_type: String, fd: RawFd);
+
+    /// Starts drag-and-drop operation with data from given data source. Null data source means
+    /// drag is internal to the client.
+    fn start_drag(&mut self,
+                  source_oid: wl::common::ObjectId,
+                  origin_surface_oid: wl::common::ObjectId,
+                  icon_surface_oid: wl::common::ObjectId);
+
+    /// Requests dragged data in given MIME type to be written to given file descriptor.
+    fn request_drag_transfer(&mut self, mime_type: String, fd: RawFd);
+
+    /// Informs that target client finished receiving dropped data.
+    fn finish_drag(&mut self);
+
+    /// Requests interactive move of given surface.
+    fn request_move(&mut self, surface_oid: wl::common::ObjectId);
+
+    /// Requests making given surface fullscreen or restoring it. Null output object ID lets
+    /// compositor choose the output.
+    fn request_fullscreen(&mut self,
+                          surface_oid: wl::common::ObjectId,
+                          fullscreen: bool,
+                          output_oid: wl::common::ObjectId);
+
+    /// Requests maximizing given surface or restoring it.
+    fn request_maximized(&mut self, surface_oid: wl::common::ObjectId, maximized: bool);
+
+    /// Requests size the surface will have when mapped. It will be sent to client in configure
+    /// event.
+    fn request_initial_size(&mut self, surface_oid: wl::common::ObjectId);
+
     /// Sets positioner info.
-    fn set_positioner(&mut self, wl::common::ObjectId, positioner: PositionerInfo);
+    fn set_positioner(&mut self, wl::common::ObjectId, positioner: Positioner);
 
     /// Gets positioner info.
-    fn get_positioner(&mut self, oid: wl::common::ObjectId) -> Option<PositionerInfo>;
+    fn get_positioner(&mut self, oid: wl::common::ObjectId) -> Option<Positioner>;
 
     /// Removes positioner info.
     fn remove_positioner(&mut self, oid: wl::common::ObjectId);
@@ -100,17 +289,33 @@ pub trait Facade {
     /// Sets given region as input region of surface.
     fn set_input_region(&self, sid: SurfaceId, region_oid: wl::common::ObjectId);
 
-    /// Requests creation of surface. Return ID of newly created surface.
-    fn create_surface(&mut self, surface_oid: wl::common::ObjectId) -> SurfaceId;
+    /// Sets given region as opaque region of surface. Invalid `region_oid` resets it.
+    fn set_opaque_region(&self, sid: SurfaceId, region_oid: wl::common::ObjectId);
+
+    /// Requests creation of surface. Return ID of newly created surface. `version` is version of
+    /// the surface object used to decide which events may be sent to client.
+    fn create_surface(&mut self, surface_oid: wl::common::ObjectId, version: u32) -> SurfaceId;
 
     /// Requests destruction of surface.
-    fn destroy_surface(&self, sid: SurfaceId);
+    fn destroy_surface(&mut self, sid: SurfaceId);
 
     /// Attaches memory view to surface. This will take effect after `commit`.
     fn attach(&mut self, buffer_oid: wl::common::ObjectId, sid: SurfaceId, x: i32, y: i32);
 
+    /// Adds given region to damage of surface to be applied on next commit.
+    fn damage(&self, sid: SurfaceId, area: Area);
+
+    /// Adds given region in buffer coordinates to damage of surface to be applied on next commit.
+    fn damage_buffer(&self, sid: SurfaceId, area: Area);
+
+    /// Sets scale of buffer of surface to be applied on next commit.
+    fn set_buffer_scale(&self, sid: SurfaceId, scale: u32);
+
+    /// Sets transformation of buffer of surface to be applied on next commit.
+    fn set_buffer_transform(&self, sid: SurfaceId, transform: Transform);
+
     /// Commits all requests to surface.
-    fn commit(&self, sid: SurfaceId);
+    fn commit(&mut self, sid: SurfaceId);
 
     /// Requests (one-shot) notification about redrawing of given surface.
     fn set_frame(&mut self, sid: SurfaceId, frame_oid: wl::common::ObjectId);
@@ -132,6 +337,56 @@ pub trait Facade {
     /// Request setting size of surface.
     fn set_requested_size(&self, sid: SurfaceId, size: Size);
 
+    /// Sets minimal and maximal size of window. Zero means no limit.
+    fn set_size_limits(&self, surface_oid: wl::common::ObjectId, min_size: Size, max_size: Size);
+
+    /// Sets identifier of application owning the surface.
+    fn set_app_id(&self, surface_oid: wl::common::ObjectId, app_id: String);
+
+    /// Sets title of window of the surface.
+    fn set_title(&self, surface_oid: wl::common::ObjectId, title: String);
+
+    /// Adds OID of decoration object of given surface. Returns `false` if the surface already has
+    /// decoration object.
+    fn add_decoration_oid(&mut self,
+                          surface_oid: wl::common::ObjectId,
+                          decoration_oid: wl::common::ObjectId)
+                          -> bool;
+
+    /// Removes OID of decoration object of given surface.
+    fn remove_decoration_oid(&mut self, surface_oid: wl::common::ObjectId);
+
+    /// Adds OID of fractional scale object of given surface and sends scale preferred for the
+    /// surface. Returns `false` if the surface already has fractional scale object.
+    fn add_fractional_scale_oid(&mut self,
+                                surface_oid: wl::common::ObjectId,
+                                fractional_scale_oid: wl::common::ObjectId)
+                                -> bool;
+
+    /// Removes OID of fractional scale object of given surface.
+    fn remove_fractional_scale_oid(&mut self, surface_oid: wl::common::ObjectId);
+
+    /// Adds OID of presentation feedback object for content which will be submitted in next
+    /// `commit` of given surface. Feedback for unknown surface is discarded immediately.
+    fn add_presentation_feedback(&mut self,
+                                 surface_oid: wl::common::ObjectId,
+                                 feedback_oid: wl::common::ObjectId);
+
+    /// Requests given decoration mode for surface. `None` means client has no preference.
+    fn request_decoration_mode(&self,
+                               surface_oid: wl::common::ObjectId,
+                               mode: Option<DecorationMode>);
+
+    /// Sets part of the surface being the window itself. It will take effect after `commit`.
+    fn set_window_geometry(&self, surface_oid: wl::common::ObjectId, geometry: Area);
+
+    /// Remembers serial of configuration sent to the surface to be later acknowledged by client.
+    fn add_configure_serial(&mut self, surface_oid: wl::common::ObjectId, serial: u32);
+
+    /// Acknowledges configuration with given serial and all sent before it. Returns `false` if
+    /// no configuration with this serial is awaiting acknowledgement.
+    fn ack_configure(&mut self, surface_oid: wl::common::ObjectId, serial: u32) -> bool;
+
     /// Requests setting relation (child-parent) between two surfaces.
     fn relate(&self, surface_oid: wl::common::ObjectId, parent_surface_oid: wl::common::ObjectId);
 
@@ -141,6 +396,13 @@ pub trait Facade {
     /// Requests to set offset between related surfaces.
     fn set_relative_position(&self, surface_oid: wl::common::ObjectId, x: isize, y: isize);
 
+    /// Requests placing pop-up relative to its parent according to given positioner. The pop-up
+    /// will be configured when placement is decided.
+    fn place_popup(&mut self,
+                   surface_oid: wl::common::ObjectId,
+                   shell_surface_oid: ShellSurfaceOid,
+                   positioner: Positioner);
+
     /// Requests to use given surface for drawing cursor.
     fn set_as_cursor(&self, surface_oid: wl::common::ObjectId, hotspot_x: isize, hotspot_x: isize);
 }
@@ -5,8 +5,12 @@
 
 // -------------------------------------------------------------------------------------------------
 
+use std::os::unix::io::RawFd;
+
 use qualia::{Axis, Button, Key, KeyMods, Milliseconds, OutputInfo, Position, Size, SurfaceId};
-use qualia::surface_state;
+use qualia::{Buffer, PresentationTime};
+use qualia::{surface_state, GestureKind, GestureMotion, SeatId, Transform};
+use qualia::seat_capability::SeatCapabilities;
 
 // -------------------------------------------------------------------------------------------------
 
@@ -14,6 +18,12 @@ pub trait Gateway {
     /// Notifies output was found.
     fn on_display_created(&mut self, output_info: OutputInfo);
 
+    /// Notifies that display was destroyed.
+    fn on_display_destroyed(&mut self, output_id: i32);
+
+    /// Notifies about change of mode or other properties of display.
+    fn on_display_changed(&mut self, output_info: OutputInfo);
+
     /// Notifies keyboard key was pressed.
     fn on_keyboard_input(&mut self, key: Key, mods: Option<KeyMods>);
 
@@ -35,14 +45,90 @@ pub trait Gateway {
     /// Notifies about pointer move.
     fn on_pointer_axis(&self, axis: Axis);
 
+    /// Notifies that touch point with given ID touched down on surface at given position.
+    fn on_touch_down(&self, sid: SurfaceId, id: i32, position: Position, time: Milliseconds);
+
+    /// Notifies that touch point with given ID moved to given position relative to surface.
+    fn on_touch_motion(&self, sid: SurfaceId, id: i32, position: Position, time: Milliseconds);
+
+    /// Notifies that touch point with given ID was lifted.
+    fn on_touch_up(&self, sid: SurfaceId, id: i32, time: Milliseconds);
+
+    /// Notifies about end of set of touch events sent to surface.
+    fn on_touch_frame(&self, sid: SurfaceId);
+
+    /// Notifies that touchpad gesture performed with given number of fingers began over surface.
+    fn on_gesture_begin(&self, sid: SurfaceId, kind: GestureKind, fingers: u32, time: Milliseconds);
+
+    /// Notifies about progress of touchpad gesture.
+    fn on_gesture_update(&self, sid: SurfaceId, kind: GestureKind, motion: GestureMotion);
+
+    /// Notifies that touchpad gesture ended or was cancelled.
+    fn on_gesture_end(&self,
+                      sid: SurfaceId,
+                      kind: GestureKind,
+                      cancelled: bool,
+                      time: Milliseconds);
+
     /// Notifies about keyboard focus change.
     fn on_keyboard_focus_changed(&mut self, old_sid: SurfaceId, new_sid: SurfaceId);
 
+    /// Notifies about keyboard focus change in seat other than default one.
+    fn on_seat_keyboard_focus_changed(&mut self,
+                                      seat: SeatId,
+                                      old_sid: SurfaceId,
+                                      new_sid: SurfaceId);
+
+    /// Notifies that kinds of input devices available in seat changed.
+    fn on_seat_capabilities_changed(&mut self, seat: SeatId, capabilities: SeatCapabilities);
+
+    /// Notifies that user was inactive for given time in milliseconds.
+    fn on_idle(&mut self, timeout: u32);
+
+    /// Notifies that user became active after being idle.
+    fn on_resumed(&mut self);
+
     /// Notifies about change of size or state of surface.
-    fn on_surface_reconfigured(&self,
+    fn on_surface_reconfigured(&mut self,
                                sid: SurfaceId,
                                size: Size,
                                state_flags: surface_state::SurfaceState);
+
+    /// Notifies that compositor requested closing of surface (e.g. from its decoration).
+    fn on_surface_close_requested(&self, sid: SurfaceId);
+
+    /// Notifies that compositor chose decoration mode for surface.
+    fn on_decoration_mode_changed(&mut self, sid: SurfaceId);
+
+    /// Notifies that surface became visible on output with given ID.
+    fn on_surface_output_entered(&self, sid: SurfaceId, output_id: i32);
+
+    /// Notifies that surface is no longer visible on output with given ID.
+    fn on_surface_output_left(&self, sid: SurfaceId, output_id: i32);
+
+    /// Notifies about change of buffer scale (possibly fractional) and transform preferred for
+    /// surface.
+    fn on_preferred_buffer_changed(&mut self, sid: SurfaceId, scale: f32, transform: Transform);
+
+    /// Notifies that client requested data of current transfer in given MIME type to be written
+    /// to given file descriptor.
+    fn on_transfer_requested(&mut self, mime_type: String, fd: RawFd);
+
+    /// Notifies that dragged data was moved over surface.
+    fn on_drag_motion(&self, sid: SurfaceId, position: Position, milliseconds: Milliseconds);
+
+    /// Notifies that client requested dragged data in given MIME type to be written to given file
+    /// descriptor.
+    fn on_drag_transfer_requested(&mut self, mime_type: String, fd: RawFd);
+
+    /// Notifies that contents of output with given ID were captured.
+    fn on_output_captured(&mut self, output_id: i32, frame: Buffer);
+
+    /// Notifies that configuration of outputs requested by client was applied, tested or rejected.
+    fn on_outputs_configured(&mut self, id: u32, result: Result<(), String>);
+
+    /// Notifies that frame containing given surfaces was presented on output with given ID.
+    fn on_frame_presented(&mut self, output_id: i32, sids: Vec<SurfaceId>, time: PresentationTime);
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -5,24 +5,31 @@
 
 // -------------------------------------------------------------------------------------------------
 
+use std::rc::Rc;
+
 use skylane as wl;
 
 use proxy::ProxyRef;
 
 // -------------------------------------------------------------------------------------------------
 
-/// Type alias for constructor of Wayland global objects.
-type GlobalContructor = Fn(wl::common::ObjectId, ProxyRef) -> Box<wl::server::Object>;
+/// Type alias for constructor of Wayland global objects. Constructor receives version of the
+/// global bound by client.
+type GlobalContructor = Fn(wl::common::ObjectId, u32, ProxyRef) -> Box<wl::server::Object>;
 
 // -------------------------------------------------------------------------------------------------
 
 /// Structure representing global Wayland object.
 // TODO: Define new type for name.
+#[derive(Clone)]
 pub struct Global {
     pub name: u32,
     pub interface: &'static str,
     pub version: u32,
-    constructor: Box<GlobalContructor>,
+
+    /// Tells if the global may be advertised only to privileged clients.
+    pub privileged: bool,
+    constructor: Rc<Box<GlobalContructor>>,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -33,12 +40,27 @@ impl Global {
             name: 0,
             interface: interface,
             version: version,
-            constructor: constructor,
+            privileged: false,
+            constructor: Rc::new(constructor),
         }
     }
 
-    pub fn construct(&self, id: wl::common::ObjectId, proxy: ProxyRef) -> Box<wl::server::Object> {
-        (self.constructor)(id, proxy)
+    /// Creates global which will be advertised only to privileged clients.
+    pub fn new_privileged(interface: &'static str,
+                          version: u32,
+                          constructor: Box<GlobalContructor>)
+                          -> Self {
+        let mut global = Self::new(interface, version, constructor);
+        global.privileged = true;
+        global
+    }
+
+    pub fn construct(&self,
+                     id: wl::common::ObjectId,
+                     version: u32,
+                     proxy: ProxyRef)
+                     -> Box<wl::server::Object> {
+        (self.constructor)(id, version, proxy)
     }
 }
 
@@ -31,6 +31,9 @@
 //! new client can not be done in `DisplayEventHandler` and handling requests can not be done in
 //! `ClientEventHandler` as it may require mutating `dharma::Dispatcher`, so handling is decoupled
 //! from processing using `dharma::DirectSender`.
+//!
+//! For unit tests of handlers `mocks` module provides implementations of `Facade` and `Gateway`
+//! recording invocations instead of passing them to the rest of application.
 
 extern crate nix;
 
@@ -53,10 +56,15 @@ mod global;
 mod facade;
 mod gateway;
 mod proxy;
+mod security;
 mod event_handlers;
+mod version;
 
 mod protocol;
 
+#[cfg(test)]
+mod mocks;
+
 pub mod engine;
 
 pub use engine::Engine;
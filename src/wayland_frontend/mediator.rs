@@ -13,11 +13,14 @@ use qualia::SurfaceId;
 
 // -------------------------------------------------------------------------------------------------
 
-/// `Mediator` stores information about which surface was created by which client.
+/// `Mediator` stores information about which surface was created by which client and which clients
+/// own current data transfer and current drag-and-drop operation.
 ///
 /// For information about its place among other structures see crate-level documentation.
 pub struct Mediator {
     sid_to_cid_dictionary: HashMap<SurfaceId, dharma::EventHandlerId>,
+    transferer: Option<dharma::EventHandlerId>,
+    drag_source: Option<dharma::EventHandlerId>,
 }
 
 define_ref!(Mediator, MediatorRef);
@@ -26,7 +29,11 @@ define_ref!(Mediator, MediatorRef);
 
 impl Mediator {
     pub fn new() -> Self {
-        Mediator { sid_to_cid_dictionary: HashMap::new() }
+        Mediator {
+            sid_to_cid_dictionary: HashMap::new(),
+            transferer: None,
+            drag_source: None,
+        }
     }
 }
 
@@ -44,6 +51,22 @@ impl Mediator {
     pub fn remove(&mut self, sid: SurfaceId) {
         self.sid_to_cid_dictionary.remove(&sid);
     }
+
+    pub fn register_transferer(&mut self, hid: Option<dharma::EventHandlerId>) {
+        self.transferer = hid;
+    }
+
+    pub fn get_transferer(&self) -> Option<dharma::EventHandlerId> {
+        self.transferer
+    }
+
+    pub fn register_drag_source(&mut self, hid: Option<dharma::EventHandlerId>) {
+        self.drag_source = hid;
+    }
+
+    pub fn get_drag_source(&self) -> Option<dharma::EventHandlerId> {
+        self.drag_source
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
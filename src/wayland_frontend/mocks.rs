@@ -0,0 +1,808 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains mocks of `Facade` and `Gateway` for testing protocol handlers without the
+//! rest of application.
+//!
+//! Mocks record every invocation as text consisting of method name and arguments formatted with
+//! `Debug`, e.g. `set_title(5, "terminal")`. Values returned by methods are scripted with public
+//! fields of the mocks.
+
+#![allow(dead_code)]
+
+// -------------------------------------------------------------------------------------------------
+
+use std::cell::RefCell;
+use std::os::unix::io::RawFd;
+
+use skylane as wl;
+
+use qualia::{Area, Axis, Button, Key, KeyMods, Milliseconds, OutputInfo, Position, Size, Vector};
+use qualia::{AlphaMode, HwImage, HwImageId, MappedMemory, MemoryPoolId, MemoryViewId};
+use qualia::{Buffer, PixelFormat, Pixmap, PresentationTime};
+use qualia::Positioner;
+use qualia::{show_reason, surface_state, DecorationMode, SurfaceId};
+use qualia::{GestureKind, GestureMotion, SeatId, Transform};
+use qualia::{Layer, LayerSurface, OutputConfiguration};
+use qualia::seat_capability::{self, SeatCapabilities};
+
+use protocol::output_management::OutputHead;
+
+use facade::{Facade, ShellSurfaceOid};
+use gateway::Gateway;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Records invocation of method with given name and arguments.
+macro_rules! record {
+    ($mock:expr, $method:expr) => {
+        $mock.invocations.record(format!("{}()", $method))
+    };
+    ($mock:expr, $method:expr, $($arg:expr),+) => {{
+        let args: Vec<String> = vec![$(format!("{:?}", $arg)),+];
+        $mock.invocations.record(format!("{}({})", $method, args.join(", ")))
+    }};
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// List of recorded invocations.
+pub struct Invocations {
+    list: RefCell<Vec<String>>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Invocations {
+    /// Constructs new empty `Invocations`.
+    pub fn new() -> Self {
+        Invocations { list: RefCell::new(Vec::new()) }
+    }
+
+    /// Adds invocation to the list.
+    fn record(&self, invocation: String) {
+        self.list.borrow_mut().push(invocation);
+    }
+
+    /// Returns all invocations in order in which they were made.
+    pub fn get(&self) -> Vec<String> {
+        self.list.borrow().clone()
+    }
+
+    /// Returns all invocations and clears the list.
+    pub fn take(&self) -> Vec<String> {
+        self.list.borrow_mut().drain(..).collect()
+    }
+
+    /// Checks if exactly given invocation was made.
+    pub fn contains(&self, invocation: &str) -> bool {
+        self.list.borrow().iter().any(|i| i == invocation)
+    }
+
+    /// Returns number of invocations of method with given name.
+    pub fn count(&self, method: &str) -> usize {
+        let prefix = format!("{}(", method);
+        self.list.borrow().iter().filter(|i| i.starts_with(&prefix)).count()
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Mock of `Facade`.
+pub struct FacadeMock {
+    /// Recorded invocations.
+    pub invocations: Invocations,
+
+    /// ID returned by `create_memory_pool`.
+    pub memory_pool_id: MemoryPoolId,
+
+    /// ID returned by `create_memory_view`.
+    pub memory_view_id: Option<MemoryViewId>,
+
+    /// ID returned by `create_hw_image`.
+    pub hw_image_id: HwImageId,
+
+    /// Version returned by `get_object_version`.
+    pub object_version: u32,
+
+    /// Capabilities returned by `get_seat_capabilities`.
+    pub seat_capabilities: SeatCapabilities,
+
+    /// Positioner returned by `get_positioner`.
+    pub positioner: Option<Positioner>,
+
+    /// ID returned by `create_surface`.
+    pub surface_id: SurfaceId,
+
+    /// Result of `add_decoration_oid`.
+    pub decoration_added: bool,
+
+    /// Result of `add_fractional_scale_oid`.
+    pub fractional_scale_added: bool,
+
+    /// Result of `ack_configure`.
+    pub configure_acked: bool,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl FacadeMock {
+    /// Constructs new `FacadeMock` returning valid IDs and successful results.
+    pub fn new() -> Self {
+        FacadeMock {
+            invocations: Invocations::new(),
+            memory_pool_id: MemoryPoolId::initial(),
+            memory_view_id: Some(MemoryViewId::initial()),
+            hw_image_id: HwImageId::initial(),
+            object_version: 1,
+            seat_capabilities: seat_capability::POINTER | seat_capability::KEYBOARD,
+            positioner: None,
+            surface_id: SurfaceId::new(1),
+            decoration_added: true,
+            fractional_scale_added: true,
+            configure_acked: true,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Facade for FacadeMock {
+    fn create_memory_pool(&mut self, _memory: MappedMemory) -> MemoryPoolId {
+        record!(self, "create_memory_pool");
+        self.memory_pool_id
+    }
+
+    fn destroy_memory_pool(&mut self, mpid: MemoryPoolId) {
+        record!(self, "destroy_memory_pool", mpid);
+    }
+
+    fn replace_memory_pool(&mut self, mpid: MemoryPoolId, _memory: MappedMemory) {
+        record!(self, "replace_memory_pool", mpid);
+    }
+
+    fn create_memory_view(&mut self,
+                          mpid: MemoryPoolId,
+                          buffer_oid: wl::common::ObjectId,
+                          offset: usize,
+                          width: usize,
+                          height: usize,
+                          stride: usize,
+                          format: PixelFormat,
+                          alpha_mode: AlphaMode)
+                          -> Option<MemoryViewId> {
+        record!(self,
+                "create_memory_view",
+                mpid,
+                buffer_oid,
+                offset,
+                width,
+                height,
+                stride,
+                format,
+                alpha_mode);
+        self.memory_view_id
+    }
+
+    fn destroy_memory_view(&mut self, mvid: MemoryViewId) {
+        record!(self, "destroy_memory_view", mvid);
+    }
+
+    fn create_hw_image(&mut self, buffer_oid: wl::common::ObjectId, image: HwImage) -> HwImageId {
+        record!(self, "create_hw_image", buffer_oid, image);
+        self.hw_image_id
+    }
+
+    fn destroy_hw_image(&mut self, hiid: HwImageId) {
+        record!(self, "destroy_hw_image", hiid);
+    }
+
+    fn define_region(&mut self, region_oid: wl::common::ObjectId, region: Area) {
+        record!(self, "define_region", region_oid, region);
+    }
+
+    fn undefine_region(&mut self, region_oid: wl::common::ObjectId) {
+        record!(self, "undefine_region", region_oid);
+    }
+
+    fn set_object_version(&mut self, oid: wl::common::ObjectId, version: u32) {
+        record!(self, "set_object_version", oid, version);
+    }
+
+    fn inherit_object_version(&mut self,
+                              oid: wl::common::ObjectId,
+                              parent_oid: wl::common::ObjectId) {
+        record!(self, "inherit_object_version", oid, parent_oid);
+    }
+
+    fn get_object_version(&self, oid: wl::common::ObjectId) -> u32 {
+        record!(self, "get_object_version", oid);
+        self.object_version
+    }
+
+    fn add_registry_oid(&mut self, registry_oid: wl::common::ObjectId) {
+        record!(self, "add_registry_oid", registry_oid);
+    }
+
+    fn add_seat_oid(&mut self, seat_oid: wl::common::ObjectId, seat: SeatId) {
+        record!(self, "add_seat_oid", seat_oid, seat);
+    }
+
+    fn remove_seat_oid(&mut self, seat_oid: wl::common::ObjectId) {
+        record!(self, "remove_seat_oid", seat_oid);
+    }
+
+    fn get_seat_capabilities(&self, seat: SeatId) -> SeatCapabilities {
+        record!(self, "get_seat_capabilities", seat);
+        self.seat_capabilities
+    }
+
+    fn add_pointer_oid(&mut self, pointer_oid: wl::common::ObjectId) {
+        record!(self, "add_pointer_oid", pointer_oid);
+    }
+
+    fn remove_pointer_oid(&mut self, pointer_oid: wl::common::ObjectId) {
+        record!(self, "remove_pointer_oid", pointer_oid);
+    }
+
+    fn add_keyboard_oid(&mut self, keyboard_oid: wl::common::ObjectId, seat: SeatId) {
+        record!(self, "add_keyboard_oid", keyboard_oid, seat);
+    }
+
+    fn remove_keyboard_oid(&mut self, keyboard_oid: wl::common::ObjectId) {
+        record!(self, "remove_keyboard_oid", keyboard_oid);
+    }
+
+    fn add_touch_oid(&mut self, touch_oid: wl::common::ObjectId) {
+        record!(self, "add_touch_oid", touch_oid);
+    }
+
+    fn remove_touch_oid(&mut self, touch_oid: wl::common::ObjectId) {
+        record!(self, "remove_touch_oid", touch_oid);
+    }
+
+    fn add_swipe_gesture_oid(&mut self, gesture_oid: wl::common::ObjectId) {
+        record!(self, "add_swipe_gesture_oid", gesture_oid);
+    }
+
+    fn remove_swipe_gesture_oid(&mut self, gesture_oid: wl::common::ObjectId) {
+        record!(self, "remove_swipe_gesture_oid", gesture_oid);
+    }
+
+    fn add_pinch_gesture_oid(&mut self, gesture_oid: wl::common::ObjectId) {
+        record!(self, "add_pinch_gesture_oid", gesture_oid);
+    }
+
+    fn remove_pinch_gesture_oid(&mut self, gesture_oid: wl::common::ObjectId) {
+        record!(self, "remove_pinch_gesture_oid", gesture_oid);
+    }
+
+    fn add_idle_notification_oid(&mut self, notification_oid: wl::common::ObjectId, timeout: u32) {
+        record!(self, "add_idle_notification_oid", notification_oid, timeout);
+    }
+
+    fn remove_idle_notification_oid(&mut self, notification_oid: wl::common::ObjectId) {
+        record!(self, "remove_idle_notification_oid", notification_oid);
+    }
+
+    fn lock_session(&mut self, lock_oid: wl::common::ObjectId) {
+        record!(self, "lock_session", lock_oid);
+    }
+
+    fn unlock_session(&mut self, lock_oid: wl::common::ObjectId) {
+        record!(self, "unlock_session", lock_oid);
+    }
+
+    fn destroy_session_lock(&mut self, lock_oid: wl::common::ObjectId) {
+        record!(self, "destroy_session_lock", lock_oid);
+    }
+
+    fn add_lock_surface(&mut self,
+                        lock_surface_oid: wl::common::ObjectId,
+                        surface_oid: wl::common::ObjectId,
+                        output_oid: wl::common::ObjectId) {
+        record!(self, "add_lock_surface", lock_surface_oid, surface_oid, output_oid);
+    }
+
+    fn remove_lock_surface(&mut self, surface_oid: wl::common::ObjectId) {
+        record!(self, "remove_lock_surface", surface_oid);
+    }
+
+    fn add_layer_surface(&mut self,
+                         layer_surface_oid: wl::common::ObjectId,
+                         surface_oid: wl::common::ObjectId,
+                         output_oid: wl::common::ObjectId,
+                         layer: Layer,
+                         namespace: String)
+                         -> Option<LayerSurface> {
+        record!(self,
+                "add_layer_surface",
+                layer_surface_oid,
+                surface_oid,
+                output_oid,
+                layer,
+                namespace);
+        None
+    }
+
+    fn set_pending_layer_surface(&mut self, state: LayerSurface) {
+        record!(self, "set_pending_layer_surface", state);
+    }
+
+    fn remove_layer_surface(&mut self, surface_oid: wl::common::ObjectId) {
+        record!(self, "remove_layer_surface", surface_oid);
+    }
+
+    fn add_output_oid(&mut self, output_id: i32, output_oid: wl::common::ObjectId) {
+        record!(self, "add_output_oid", output_id, output_oid);
+    }
+
+    fn remove_output_oid(&mut self, output_oid: wl::common::ObjectId) {
+        record!(self, "remove_output_oid", output_oid);
+    }
+
+    fn add_xdg_output_oid(&mut self,
+                          xdg_output_oid: wl::common::ObjectId,
+                          output_oid: wl::common::ObjectId) {
+        record!(self, "add_xdg_output_oid", xdg_output_oid, output_oid);
+    }
+
+    fn remove_xdg_output_oid(&mut self, xdg_output_oid: wl::common::ObjectId) {
+        record!(self, "remove_xdg_output_oid", xdg_output_oid);
+    }
+
+    fn add_output_manager_oid(&mut self, manager_oid: wl::common::ObjectId) {
+        record!(self, "add_output_manager_oid", manager_oid);
+    }
+
+    fn remove_output_manager_oid(&mut self, manager_oid: wl::common::ObjectId) {
+        record!(self, "remove_output_manager_oid", manager_oid);
+    }
+
+    fn get_output_heads(&self, manager_oid: wl::common::ObjectId) -> Vec<OutputHead> {
+        record!(self, "get_output_heads", manager_oid);
+        Vec::new()
+    }
+
+    fn request_output_configuration(&mut self,
+                                    configuration_oid: wl::common::ObjectId,
+                                    serial: u32,
+                                    configurations: Vec<OutputConfiguration>,
+                                    test: bool) {
+        record!(self,
+                "request_output_configuration",
+                configuration_oid,
+                serial,
+                configurations,
+                test);
+    }
+
+    fn remove_output_configuration_oid(&mut self, configuration_oid: wl::common::ObjectId) {
+        record!(self, "remove_output_configuration_oid", configuration_oid);
+    }
+
+    fn add_screencopy_frame(&mut self,
+                            frame_oid: wl::common::ObjectId,
+                            output_oid: wl::common::ObjectId,
+                            region: Option<Area>) {
+        record!(self, "add_screencopy_frame", frame_oid, output_oid, region);
+    }
+
+    fn copy_screencopy_frame(&mut self,
+                             frame_oid: wl::common::ObjectId,
+                             buffer_oid: wl::common::ObjectId,
+                             with_damage: bool) {
+        record!(self, "copy_screencopy_frame", frame_oid, buffer_oid, with_damage);
+    }
+
+    fn remove_screencopy_frame(&mut self, frame_oid: wl::common::ObjectId) {
+        record!(self, "remove_screencopy_frame", frame_oid);
+    }
+
+    fn add_data_device_oid(&mut self, data_device_oid: wl::common::ObjectId) {
+        record!(self, "add_data_device_oid", data_device_oid);
+    }
+
+    fn remove_data_device_oid(&mut self, data_device_oid: wl::common::ObjectId) {
+        record!(self, "remove_data_device_oid", data_device_oid);
+    }
+
+    fn add_data_control_device_oid(&mut self, data_device_oid: wl::common::ObjectId) {
+        record!(self, "add_data_control_device_oid", data_device_oid);
+    }
+
+    fn remove_data_control_device_oid(&mut self, data_device_oid: wl::common::ObjectId) {
+        record!(self, "remove_data_control_device_oid", data_device_oid);
+    }
+
+    fn create_data_source(&mut self, source_oid: wl::common::ObjectId) {
+        record!(self, "create_data_source", source_oid);
+    }
+
+    fn create_data_control_source(&mut self, source_oid: wl::common::ObjectId) {
+        record!(self, "create_data_control_source", source_oid);
+    }
+
+    fn destroy_data_source(&mut self, source_oid: wl::common::ObjectId) {
+        record!(self, "destroy_data_source", source_oid);
+    }
+
+    fn add_mime_type(&mut self, source_oid: wl::common::ObjectId, mime_type: String) {
+        record!(self, "add_mime_type", source_oid, mime_type);
+    }
+
+    fn set_selection(&mut self, source_oid: wl::common::ObjectId) {
+        record!(self, "set_selection", source_oid);
+    }
+
+    fn request_transfer(&mut self, mime_type: String, fd: RawFd) {
+        record!(self, "request_transfer", mime_type, fd);
+    }
+
+    fn start_drag(&mut self,
+                  source_oid: wl::common::ObjectId,
+                  origin_surface_oid: wl::common::ObjectId,
+                  icon_surface_oid: wl::common::ObjectId) {
+        record!(self, "start_drag", source_oid, origin_surface_oid, icon_surface_oid);
+    }
+
+    fn request_drag_transfer(&mut self, mime_type: String, fd: RawFd) {
+        record!(self, "request_drag_transfer", mime_type, fd);
+    }
+
+    fn finish_drag(&mut self) {
+        record!(self, "finish_drag");
+    }
+
+    fn request_move(&mut self, surface_oid: wl::common::ObjectId) {
+        record!(self, "request_move", surface_oid);
+    }
+
+    fn request_fullscreen(&mut self,
+                          surface_oid: wl::common::ObjectId,
+                          fullscreen: bool,
+                          output_oid: wl::common::ObjectId) {
+        record!(self, "request_fullscreen", surface_oid, fullscreen, output_oid);
+    }
+
+    fn request_maximized(&mut self, surface_oid: wl::common::ObjectId, maximized: bool) {
+        record!(self, "request_maximized", surface_oid, maximized);
+    }
+
+    fn request_initial_size(&mut self, surface_oid: wl::common::ObjectId) {
+        record!(self, "request_initial_size", surface_oid);
+    }
+
+    fn set_positioner(&mut self, oid: wl::common::ObjectId, positioner: Positioner) {
+        record!(self, "set_positioner", oid, positioner);
+    }
+
+    fn get_positioner(&mut self, oid: wl::common::ObjectId) -> Option<Positioner> {
+        record!(self, "get_positioner", oid);
+        self.positioner
+    }
+
+    fn remove_positioner(&mut self, oid: wl::common::ObjectId) {
+        record!(self, "remove_positioner", oid);
+    }
+
+    fn set_input_region(&self, sid: SurfaceId, region_oid: wl::common::ObjectId) {
+        record!(self, "set_input_region", sid, region_oid);
+    }
+
+    fn set_opaque_region(&self, sid: SurfaceId, region_oid: wl::common::ObjectId) {
+        record!(self, "set_opaque_region", sid, region_oid);
+    }
+
+    fn create_surface(&mut self, surface_oid: wl::common::ObjectId, version: u32) -> SurfaceId {
+        record!(self, "create_surface", surface_oid, version);
+        self.surface_id
+    }
+
+    fn destroy_surface(&mut self, sid: SurfaceId) {
+        record!(self, "destroy_surface", sid);
+    }
+
+    fn attach(&mut self, buffer_oid: wl::common::ObjectId, sid: SurfaceId, x: i32, y: i32) {
+        record!(self, "attach", buffer_oid, sid, x, y);
+    }
+
+    fn damage(&self, sid: SurfaceId, area: Area) {
+        record!(self, "damage", sid, area);
+    }
+
+    fn damage_buffer(&self, sid: SurfaceId, area: Area) {
+        record!(self, "damage_buffer", sid, area);
+    }
+
+    fn set_buffer_scale(&self, sid: SurfaceId, scale: u32) {
+        record!(self, "set_buffer_scale", sid, scale);
+    }
+
+    fn set_buffer_transform(&self, sid: SurfaceId, transform: Transform) {
+        record!(self, "set_buffer_transform", sid, transform);
+    }
+
+    fn commit(&mut self, sid: SurfaceId) {
+        record!(self, "commit", sid);
+    }
+
+    fn set_frame(&mut self, sid: SurfaceId, frame_oid: wl::common::ObjectId) {
+        record!(self, "set_frame", sid, frame_oid);
+    }
+
+    fn show(&mut self,
+            surface_oid: wl::common::ObjectId,
+            shell_surface_oid: ShellSurfaceOid,
+            reason: show_reason::ShowReason) {
+        record!(self, "show", surface_oid, shell_surface_oid, reason);
+    }
+
+    fn hide(&mut self, surface_oid: wl::common::ObjectId, reason: show_reason::ShowReason) {
+        record!(self, "hide", surface_oid, reason);
+    }
+
+    fn set_offset(&self, sid: SurfaceId, offset: Vector) {
+        record!(self, "set_offset", sid, offset);
+    }
+
+    fn set_requested_size(&self, sid: SurfaceId, size: Size) {
+        record!(self, "set_requested_size", sid, size);
+    }
+
+    fn set_size_limits(&self, surface_oid: wl::common::ObjectId, min_size: Size, max_size: Size) {
+        record!(self, "set_size_limits", surface_oid, min_size, max_size);
+    }
+
+    fn set_app_id(&self, surface_oid: wl::common::ObjectId, app_id: String) {
+        record!(self, "set_app_id", surface_oid, app_id);
+    }
+
+    fn set_title(&self, surface_oid: wl::common::ObjectId, title: String) {
+        record!(self, "set_title", surface_oid, title);
+    }
+
+    fn add_decoration_oid(&mut self,
+                          surface_oid: wl::common::ObjectId,
+                          decoration_oid: wl::common::ObjectId)
+                          -> bool {
+        record!(self, "add_decoration_oid", surface_oid, decoration_oid);
+        self.decoration_added
+    }
+
+    fn remove_decoration_oid(&mut self, surface_oid: wl::common::ObjectId) {
+        record!(self, "remove_decoration_oid", surface_oid);
+    }
+
+    fn add_fractional_scale_oid(&mut self,
+                                surface_oid: wl::common::ObjectId,
+                                fractional_scale_oid: wl::common::ObjectId)
+                                -> bool {
+        record!(self, "add_fractional_scale_oid", surface_oid, fractional_scale_oid);
+        self.fractional_scale_added
+    }
+
+    fn remove_fractional_scale_oid(&mut self, surface_oid: wl::common::ObjectId) {
+        record!(self, "remove_fractional_scale_oid", surface_oid);
+    }
+
+    fn add_presentation_feedback(&mut self,
+                                 surface_oid: wl::common::ObjectId,
+                                 feedback_oid: wl::common::ObjectId) {
+        record!(self, "add_presentation_feedback", surface_oid, feedback_oid);
+    }
+
+    fn request_decoration_mode(&self,
+                               surface_oid: wl::common::ObjectId,
+                               mode: Option<DecorationMode>) {
+        record!(self, "request_decoration_mode", surface_oid, mode);
+    }
+
+    fn set_window_geometry(&self, surface_oid: wl::common::ObjectId, geometry: Area) {
+        record!(self, "set_window_geometry", surface_oid, geometry);
+    }
+
+    fn add_configure_serial(&mut self, surface_oid: wl::common::ObjectId, serial: u32) {
+        record!(self, "add_configure_serial", surface_oid, serial);
+    }
+
+    fn ack_configure(&mut self, surface_oid: wl::common::ObjectId, serial: u32) -> bool {
+        record!(self, "ack_configure", surface_oid, serial);
+        self.configure_acked
+    }
+
+    fn relate(&self, surface_oid: wl::common::ObjectId, parent_surface_oid: wl::common::ObjectId) {
+        record!(self, "relate", surface_oid, parent_surface_oid);
+    }
+
+    fn unrelate(&self, surface_oid: wl::common::ObjectId) {
+        record!(self, "unrelate", surface_oid);
+    }
+
+    fn set_relative_position(&self, surface_oid: wl::common::ObjectId, x: isize, y: isize) {
+        record!(self, "set_relative_position", surface_oid, x, y);
+    }
+
+    fn place_popup(&mut self,
+                   surface_oid: wl::common::ObjectId,
+                   shell_surface_oid: ShellSurfaceOid,
+                   positioner: Positioner) {
+        record!(self, "place_popup", surface_oid, shell_surface_oid, positioner);
+    }
+
+    fn set_as_cursor(&self, surface_oid: wl::common::ObjectId, hotspot_x: isize, hotspot_y: isize) {
+        record!(self, "set_as_cursor", surface_oid, hotspot_x, hotspot_y);
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Mock of `Gateway`.
+pub struct GatewayMock {
+    /// Recorded invocations.
+    pub invocations: Invocations,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl GatewayMock {
+    /// Constructs new `GatewayMock`.
+    pub fn new() -> Self {
+        GatewayMock { invocations: Invocations::new() }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Gateway for GatewayMock {
+    fn on_display_created(&mut self, output_info: OutputInfo) {
+        record!(self, "on_display_created", output_info.id);
+    }
+
+    fn on_display_destroyed(&mut self, output_id: i32) {
+        record!(self, "on_display_destroyed", output_id);
+    }
+
+    fn on_display_changed(&mut self, output_info: OutputInfo) {
+        record!(self, "on_display_changed", output_info.id);
+    }
+
+    fn on_keyboard_input(&mut self, key: Key, mods: Option<KeyMods>) {
+        record!(self, "on_keyboard_input", key, mods);
+    }
+
+    fn on_surface_frame(&mut self, sid: SurfaceId, milliseconds: Milliseconds) {
+        record!(self, "on_surface_frame", sid, milliseconds);
+    }
+
+    fn on_pointer_focus_changed(&self, old_sid: SurfaceId, new_sid: SurfaceId, position: Position) {
+        record!(self, "on_pointer_focus_changed", old_sid, new_sid, position);
+    }
+
+    fn on_pointer_relative_motion(&self,
+                                  sid: SurfaceId,
+                                  position: Position,
+                                  milliseconds: Milliseconds) {
+        record!(self, "on_pointer_relative_motion", sid, position, milliseconds);
+    }
+
+    fn on_pointer_button(&self, btn: Button) {
+        record!(self, "on_pointer_button", btn);
+    }
+
+    fn on_pointer_axis(&self, axis: Axis) {
+        record!(self, "on_pointer_axis", axis);
+    }
+
+    fn on_touch_down(&self, sid: SurfaceId, id: i32, position: Position, time: Milliseconds) {
+        record!(self, "on_touch_down", sid, id, position, time);
+    }
+
+    fn on_touch_motion(&self, sid: SurfaceId, id: i32, position: Position, time: Milliseconds) {
+        record!(self, "on_touch_motion", sid, id, position, time);
+    }
+
+    fn on_touch_up(&self, sid: SurfaceId, id: i32, time: Milliseconds) {
+        record!(self, "on_touch_up", sid, id, time);
+    }
+
+    fn on_touch_frame(&self, sid: SurfaceId) {
+        record!(self, "on_touch_frame", sid);
+    }
+
+    fn on_gesture_begin(&self,
+                        sid: SurfaceId,
+                        kind: GestureKind,
+                        fingers: u32,
+                        time: Milliseconds) {
+        record!(self, "on_gesture_begin", sid, kind, fingers, time);
+    }
+
+    fn on_gesture_update(&self, sid: SurfaceId, kind: GestureKind, motion: GestureMotion) {
+        record!(self, "on_gesture_update", sid, kind, motion);
+    }
+
+    fn on_gesture_end(&self,
+                      sid: SurfaceId,
+                      kind: GestureKind,
+                      cancelled: bool,
+                      time: Milliseconds) {
+        record!(self, "on_gesture_end", sid, kind, cancelled, time);
+    }
+
+    fn on_keyboard_focus_changed(&mut self, old_sid: SurfaceId, new_sid: SurfaceId) {
+        record!(self, "on_keyboard_focus_changed", old_sid, new_sid);
+    }
+
+    fn on_seat_keyboard_focus_changed(&mut self,
+                                      seat: SeatId,
+                                      old_sid: SurfaceId,
+                                      new_sid: SurfaceId) {
+        record!(self, "on_seat_keyboard_focus_changed", seat, old_sid, new_sid);
+    }
+
+    fn on_seat_capabilities_changed(&mut self, seat: SeatId, capabilities: SeatCapabilities) {
+        record!(self, "on_seat_capabilities_changed", seat, capabilities);
+    }
+
+    fn on_idle(&mut self, timeout: u32) {
+        record!(self, "on_idle", timeout);
+    }
+
+    fn on_resumed(&mut self) {
+        record!(self, "on_resumed");
+    }
+
+    fn on_surface_reconfigured(&mut self,
+                               sid: SurfaceId,
+                               size: Size,
+                               state_flags: surface_state::SurfaceState) {
+        record!(self, "on_surface_reconfigured", sid, size, state_flags);
+    }
+
+    fn on_surface_close_requested(&self, sid: SurfaceId) {
+        record!(self, "on_surface_close_requested", sid);
+    }
+
+    fn on_decoration_mode_changed(&mut self, sid: SurfaceId) {
+        record!(self, "on_decoration_mode_changed", sid);
+    }
+
+    fn on_surface_output_entered(&self, sid: SurfaceId, output_id: i32) {
+        record!(self, "on_surface_output_entered", sid, output_id);
+    }
+
+    fn on_surface_output_left(&self, sid: SurfaceId, output_id: i32) {
+        record!(self, "on_surface_output_left", sid, output_id);
+    }
+
+    fn on_preferred_buffer_changed(&mut self, sid: SurfaceId, scale: f32, transform: Transform) {
+        record!(self, "on_preferred_buffer_changed", sid, scale, transform);
+    }
+
+    fn on_transfer_requested(&mut self, mime_type: String, fd: RawFd) {
+        record!(self, "on_transfer_requested", mime_type, fd);
+    }
+
+    fn on_drag_motion(&self, sid: SurfaceId, position: Position, milliseconds: Milliseconds) {
+        record!(self, "on_drag_motion", sid, position, milliseconds);
+    }
+
+    fn on_drag_transfer_requested(&mut self, mime_type: String, fd: RawFd) {
+        record!(self, "on_drag_transfer_requested", mime_type, fd);
+    }
+
+    fn on_output_captured(&mut self, output_id: i32, frame: Buffer) {
+        record!(self, "on_output_captured", output_id, frame.get_size());
+    }
+
+    fn on_outputs_configured(&mut self, id: u32, result: Result<(), String>) {
+        record!(self, "on_outputs_configured", id, result);
+    }
+
+    fn on_frame_presented(&mut self, output_id: i32, sids: Vec<SurfaceId>, time: PresentationTime) {
+        record!(self, "on_frame_presented", output_id, sids, time);
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -5,6 +5,7 @@
 
 use skylane as wl;
 use skylane_protocols::server::Handler;
+use skylane_protocols::server::wayland::wl_display;
 use skylane_protocols::server::wayland::wl_compositor;
 use skylane_protocols::server::wayland::wl_surface;
 use skylane_protocols::server::wayland::wl_region;
@@ -13,12 +14,13 @@ use qualia::{Area, SurfaceId};
 
 use global::Global;
 use facade::Facade;
-use proxy::ProxyRef;
+use proxy::{translate_wl_transform, ProxyRef};
 
 // -------------------------------------------------------------------------------------------------
 
 /// Wayland `wl_compositor` object.
 struct Compositor {
+    version: u32,
     proxy: ProxyRef,
 }
 
@@ -31,12 +33,18 @@ pub fn get_global() -> Global {
 // -------------------------------------------------------------------------------------------------
 
 impl Compositor {
-    fn new(proxy_ref: ProxyRef) -> Self {
-        Compositor { proxy: proxy_ref }
+    fn new(version: u32, proxy_ref: ProxyRef) -> Self {
+        Compositor {
+            version: version,
+            proxy: proxy_ref,
+        }
     }
 
-    fn new_object(_oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Box<wl::server::Object> {
-        Box::new(Handler::<_, wl_compositor::Dispatcher>::new(Self::new(proxy_ref)))
+    fn new_object(_oid: wl::common::ObjectId,
+                  version: u32,
+                  proxy_ref: ProxyRef)
+                  -> Box<wl::server::Object> {
+        Box::new(Handler::<_, wl_compositor::Dispatcher>::new(Self::new(version, proxy_ref)))
     }
 }
 
@@ -48,7 +56,7 @@ impl wl_compositor::Interface for Compositor {
                       _socket: &mut wl::server::ClientSocket,
                       new_surface_id: wl::common::ObjectId)
                       -> wl::server::Task {
-        let surface = Surface::new_object(new_surface_id, self.proxy.clone());
+        let surface = Surface::new_object(new_surface_id, self.version, self.proxy.clone());
         wl::server::Task::Create {
             id: new_surface_id,
             object: surface,
@@ -79,10 +87,10 @@ struct Surface {
 // -------------------------------------------------------------------------------------------------
 
 impl Surface {
-    fn new(oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Self {
+    fn new(oid: wl::common::ObjectId, version: u32, proxy_ref: ProxyRef) -> Self {
         let sid = {
             let mut proxy = proxy_ref.borrow_mut();
-            proxy.create_surface(oid)
+            proxy.create_surface(oid, version)
         };
 
         Surface {
@@ -91,8 +99,11 @@ impl Surface {
         }
     }
 
-    fn new_object(oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Box<wl::server::Object> {
-        Box::new(Handler::<_, wl_surface::Dispatcher>::new(Self::new(oid, proxy_ref)))
+    fn new_object(oid: wl::common::ObjectId,
+                  version: u32,
+                  proxy_ref: ProxyRef)
+                  -> Box<wl::server::Object> {
+        Box::new(Handler::<_, wl_surface::Dispatcher>::new(Self::new(oid, version, proxy_ref)))
     }
 }
 
@@ -104,7 +115,7 @@ impl wl_surface::Interface for Surface {
                this_object_id: wl::common::ObjectId,
                socket: &mut wl::server::ClientSocket)
                -> wl::server::Task {
-        let proxy = self.proxy.borrow();
+        let mut proxy = self.proxy.borrow_mut();
         proxy.destroy_surface(self.sid);
         wl::server::Task::Destroy { id: this_object_id }
     }
@@ -129,6 +140,10 @@ impl wl_surface::Interface for Surface {
               width: i32,
               height: i32)
               -> wl::server::Task {
+        if (width > 0) && (height > 0) {
+            let area = Area::create(x as isize, y as isize, width as usize, height as usize);
+            self.proxy.borrow().damage(self.sid, area);
+        }
         wl::server::Task::None
     }
 
@@ -148,7 +163,7 @@ impl wl_surface::Interface for Surface {
                          region_oid: wl::common::ObjectId)
                          -> wl::server::Task {
         let proxy = self.proxy.borrow_mut();
-        proxy.set_input_region(self.sid, region_oid);
+        proxy.set_opaque_region(self.sid, region_oid);
         wl::server::Task::None
     }
 
@@ -166,7 +181,7 @@ impl wl_surface::Interface for Surface {
               this_object_id: wl::common::ObjectId,
               socket: &mut wl::server::ClientSocket)
               -> wl::server::Task {
-        let proxy = self.proxy.borrow_mut();
+        let mut proxy = self.proxy.borrow_mut();
         proxy.commit(self.sid);
         wl::server::Task::None
     }
@@ -176,6 +191,16 @@ impl wl_surface::Interface for Surface {
                             socket: &mut wl::server::ClientSocket,
                             transform: i32)
                             -> wl::server::Task {
+        match translate_wl_transform(transform) {
+            Some(transform) => self.proxy.borrow().set_buffer_transform(self.sid, transform),
+            None => {
+                send!(wl_display::error(socket,
+                                        wl::common::DISPLAY_ID,
+                                        this_object_id,
+                                        wl_surface::error::INVALID_TRANSFORM,
+                                        &format!("Invalid buffer transform {}", transform)));
+            }
+        }
         wl::server::Task::None
     }
 
@@ -184,6 +209,15 @@ impl wl_surface::Interface for Surface {
                         socket: &mut wl::server::ClientSocket,
                         scale: i32)
                         -> wl::server::Task {
+        if scale > 0 {
+            self.proxy.borrow().set_buffer_scale(self.sid, scale as u32);
+        } else {
+            send!(wl_display::error(socket,
+                                    wl::common::DISPLAY_ID,
+                                    this_object_id,
+                                    wl_surface::error::INVALID_SCALE,
+                                    &format!("Invalid buffer scale {}", scale)));
+        }
         wl::server::Task::None
     }
 
@@ -195,6 +229,19 @@ impl wl_surface::Interface for Surface {
                      width: i32,
                      height: i32)
                      -> wl::server::Task {
+        if (width > 0) && (height > 0) {
+            let area = Area::create(x as isize, y as isize, width as usize, height as usize);
+            self.proxy.borrow().damage_buffer(self.sid, area);
+        }
+        wl::server::Task::None
+    }
+
+    fn offset(&mut self,
+              this_object_id: wl::common::ObjectId,
+              socket: &mut wl::server::ClientSocket,
+              x: i32,
+              y: i32)
+              -> wl::server::Task {
         wl::server::Task::None
     }
 }
@@ -274,3 +321,4 @@ impl wl_region::Interface for Region {
 }
 
 // -------------------------------------------------------------------------------------------------
+
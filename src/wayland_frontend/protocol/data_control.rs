@@ -0,0 +1,234 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementations of Wayland `zwlr_data_control_manager_v1`, `zwlr_data_control_device_v1`,
+//! `zwlr_data_control_source_v1` and `zwlr_data_control_offer_v1` objects.
+//!
+//! These objects let clipboard managers read and set selection without having keyboard focus. They
+//! share state with `wl_data_device` objects so the selection is the same for both. The global is
+//! privileged and advertised only to clients allowed by security policy. When client loses
+//! privileges its data devices are finished and new ones are finished right after creation.
+
+use skylane as wl;
+use skylane_protocols::server::Handler;
+use skylane_protocols::server::wlr_data_control_unstable_v1::zwlr_data_control_manager_v1;
+use skylane_protocols::server::wlr_data_control_unstable_v1::zwlr_data_control_device_v1;
+use skylane_protocols::server::wlr_data_control_unstable_v1::zwlr_data_control_source_v1;
+use skylane_protocols::server::wlr_data_control_unstable_v1::zwlr_data_control_offer_v1;
+
+use global::Global;
+use facade::Facade;
+use proxy::ProxyRef;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwlr_data_control_manager_v1` object.
+struct DataControlManager {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub fn get_global() -> Global {
+    Global::new_privileged(zwlr_data_control_manager_v1::NAME,
+                           zwlr_data_control_manager_v1::VERSION,
+                           Box::new(DataControlManager::new_object))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl DataControlManager {
+    /// Creates new `DataControlManager`.
+    fn new(_oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Self {
+        DataControlManager { proxy: proxy_ref }
+    }
+
+    fn new_object(oid: wl::common::ObjectId,
+                  _version: u32,
+                  proxy_ref: ProxyRef)
+                  -> Box<wl::server::Object> {
+        Box::new(Handler::<_, zwlr_data_control_manager_v1::Dispatcher>::new(Self::new(oid,
+                                                                                       proxy_ref)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+#[allow(unused_variables)]
+impl zwlr_data_control_manager_v1::Interface for DataControlManager {
+    fn create_data_source(&mut self,
+                          this_object_id: wl::common::ObjectId,
+                          socket: &mut wl::server::ClientSocket,
+                          id: wl::common::ObjectId)
+                          -> wl::server::Task {
+        wl::server::Task::Create {
+            id: id,
+            object: DataControlSource::new_object(id, self.proxy.clone()),
+        }
+    }
+
+    fn get_data_device(&mut self,
+                       this_object_id: wl::common::ObjectId,
+                       socket: &mut wl::server::ClientSocket,
+                       id: wl::common::ObjectId,
+                       seat: wl::common::ObjectId)
+                       -> wl::server::Task {
+        wl::server::Task::Create {
+            id: id,
+            object: DataControlDevice::new_object(id, self.proxy.clone()),
+        }
+    }
+
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwlr_data_control_device_v1` object.
+struct DataControlDevice {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl DataControlDevice {
+    /// Creates new `DataControlDevice`. Current selection will be advertised to it by `Engine`
+    /// after processing client requests.
+    fn new(oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Self {
+        {
+            let mut proxy = proxy_ref.borrow_mut();
+            if proxy.is_privileged() {
+                proxy.add_data_control_device_oid(oid);
+            } else {
+                send!(zwlr_data_control_device_v1::finished(&proxy.get_socket(), oid));
+            }
+        }
+        DataControlDevice { proxy: proxy_ref }
+    }
+
+    fn new_object(oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Box<wl::server::Object> {
+        Box::new(Handler::<_, zwlr_data_control_device_v1::Dispatcher>::new(Self::new(oid,
+                                                                                      proxy_ref)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+#[allow(unused_variables)]
+impl zwlr_data_control_device_v1::Interface for DataControlDevice {
+    fn set_selection(&mut self,
+                     this_object_id: wl::common::ObjectId,
+                     socket: &mut wl::server::ClientSocket,
+                     source: wl::common::ObjectId)
+                     -> wl::server::Task {
+        // Finished devices may not change selection
+        let mut proxy = self.proxy.borrow_mut();
+        if proxy.get_data_control_device_oids().contains(&this_object_id) {
+            proxy.set_selection(source);
+        }
+        wl::server::Task::None
+    }
+
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        self.proxy.borrow_mut().remove_data_control_device_oid(this_object_id);
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwlr_data_control_source_v1` object.
+struct DataControlSource {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl DataControlSource {
+    /// Creates new `DataControlSource`.
+    fn new(oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Self {
+        proxy_ref.borrow_mut().create_data_control_source(oid);
+        DataControlSource { proxy: proxy_ref }
+    }
+
+    fn new_object(oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Box<wl::server::Object> {
+        Box::new(Handler::<_, zwlr_data_control_source_v1::Dispatcher>::new(Self::new(oid,
+                                                                                      proxy_ref)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+#[allow(unused_variables)]
+impl zwlr_data_control_source_v1::Interface for DataControlSource {
+    fn offer(&mut self,
+             this_object_id: wl::common::ObjectId,
+             socket: &mut wl::server::ClientSocket,
+             mime_type: String)
+             -> wl::server::Task {
+        self.proxy.borrow_mut().add_mime_type(this_object_id, mime_type);
+        wl::server::Task::None
+    }
+
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        self.proxy.borrow_mut().destroy_data_source(this_object_id);
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwlr_data_control_offer_v1` object.
+pub struct DataControlOffer {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl DataControlOffer {
+    /// Creates new `DataControlOffer`.
+    fn new(proxy_ref: ProxyRef) -> Self {
+        DataControlOffer { proxy: proxy_ref }
+    }
+
+    /// Creates new `DataControlOffer` wrapped in handler. Like data offers, data control offers are
+    /// created on server side.
+    pub fn new_object(_oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Box<wl::server::Object> {
+        Box::new(Handler::<_, zwlr_data_control_offer_v1::Dispatcher>::new(Self::new(proxy_ref)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+#[allow(unused_variables)]
+impl zwlr_data_control_offer_v1::Interface for DataControlOffer {
+    fn receive(&mut self,
+               this_object_id: wl::common::ObjectId,
+               socket: &mut wl::server::ClientSocket,
+               mime_type: String,
+               fd: i32)
+               -> wl::server::Task {
+        self.proxy.borrow_mut().request_transfer(mime_type, fd);
+        wl::server::Task::None
+    }
+
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -1,19 +1,26 @@
 // This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
 // the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
 
-//! Implementations of Wayland `wl_data_device_manager` object.
+//! Implementations of Wayland `wl_data_device_manager`, `wl_data_device`, `wl_data_source` and
+//! `wl_data_offer` objects.
 
 use skylane as wl;
 use skylane_protocols::server::Handler;
 use skylane_protocols::server::wayland::wl_data_device_manager;
+use skylane_protocols::server::wayland::wl_data_device;
+use skylane_protocols::server::wayland::wl_data_source;
+use skylane_protocols::server::wayland::wl_data_offer;
 
 use global::Global;
+use facade::Facade;
 use proxy::ProxyRef;
 
 // -------------------------------------------------------------------------------------------------
 
 /// Wayland `wl_data_device_manager` object.
-struct DataDeviceManager {}
+struct DataDeviceManager {
+    proxy: ProxyRef,
+}
 
 // -------------------------------------------------------------------------------------------------
 
@@ -27,11 +34,14 @@ pub fn get_global() -> Global {
 
 impl DataDeviceManager {
     /// Creates new `DataDeviceManager`.
-    fn new(_oid: wl::common::ObjectId, _proxy_ref: ProxyRef) -> Self {
-        DataDeviceManager {}
+    fn new(_oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Self {
+        DataDeviceManager { proxy: proxy_ref }
     }
 
-    fn new_object(oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Box<wl::server::Object> {
+    fn new_object(oid: wl::common::ObjectId,
+                  _version: u32,
+                  proxy_ref: ProxyRef)
+                  -> Box<wl::server::Object> {
         Box::new(Handler::<_, wl_data_device_manager::Dispatcher>::new(Self::new(oid, proxy_ref)))
     }
 }
@@ -45,8 +55,10 @@ impl wl_data_device_manager::Interface for DataDeviceManager {
                           socket: &mut wl::server::ClientSocket,
                           id: wl::common::ObjectId)
                           -> wl::server::Task {
-        // FIXME: Finish implementation of `create_data_source`.
-        wl::server::Task::None
+        wl::server::Task::Create {
+            id: id,
+            object: DataSource::new_object(id, self.proxy.clone()),
+        }
     }
 
     fn get_data_device(&mut self,
@@ -55,7 +67,213 @@ impl wl_data_device_manager::Interface for DataDeviceManager {
                        id: wl::common::ObjectId,
                        seat: wl::common::ObjectId)
                        -> wl::server::Task {
-        // FIXME: Finish implementation of `get_data_device`.
+        self.proxy.borrow_mut().inherit_object_version(id, this_object_id);
+        wl::server::Task::Create {
+            id: id,
+            object: DataDevice::new_object(id, self.proxy.clone()),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `wl_data_device` object.
+struct DataDevice {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl DataDevice {
+    /// Creates new `DataDevice`.
+    fn new(oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Self {
+        proxy_ref.borrow_mut().add_data_device_oid(oid);
+        DataDevice { proxy: proxy_ref }
+    }
+
+    fn new_object(oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Box<wl::server::Object> {
+        Box::new(Handler::<_, wl_data_device::Dispatcher>::new(Self::new(oid, proxy_ref)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+#[allow(unused_variables)]
+impl wl_data_device::Interface for DataDevice {
+    fn start_drag(&mut self,
+                  this_object_id: wl::common::ObjectId,
+                  socket: &mut wl::server::ClientSocket,
+                  source: wl::common::ObjectId,
+                  origin: wl::common::ObjectId,
+                  icon: wl::common::ObjectId,
+                  serial: u32)
+                  -> wl::server::Task {
+        self.proxy.borrow_mut().start_drag(source, origin, icon);
+        wl::server::Task::None
+    }
+
+    fn set_selection(&mut self,
+                     this_object_id: wl::common::ObjectId,
+                     socket: &mut wl::server::ClientSocket,
+                     source: wl::common::ObjectId,
+                     serial: u32)
+                     -> wl::server::Task {
+        self.proxy.borrow_mut().set_selection(source);
+        wl::server::Task::None
+    }
+
+    fn release(&mut self,
+               this_object_id: wl::common::ObjectId,
+               socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        self.proxy.borrow_mut().remove_data_device_oid(this_object_id);
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `wl_data_source` object.
+struct DataSource {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl DataSource {
+    /// Creates new `DataSource`.
+    fn new(oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Self {
+        proxy_ref.borrow_mut().create_data_source(oid);
+        DataSource { proxy: proxy_ref }
+    }
+
+    fn new_object(oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Box<wl::server::Object> {
+        Box::new(Handler::<_, wl_data_source::Dispatcher>::new(Self::new(oid, proxy_ref)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+#[allow(unused_variables)]
+impl wl_data_source::Interface for DataSource {
+    fn offer(&mut self,
+             this_object_id: wl::common::ObjectId,
+             socket: &mut wl::server::ClientSocket,
+             mime_type: String)
+             -> wl::server::Task {
+        self.proxy.borrow_mut().add_mime_type(this_object_id, mime_type);
+        wl::server::Task::None
+    }
+
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        self.proxy.borrow_mut().destroy_data_source(this_object_id);
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+
+    fn set_actions(&mut self,
+                   this_object_id: wl::common::ObjectId,
+                   socket: &mut wl::server::ClientSocket,
+                   dnd_actions: u32)
+                   -> wl::server::Task {
+        // Only copying is supported so there is no need to store the actions.
+        wl::server::Task::None
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `wl_data_offer` object.
+pub struct DataOffer {
+    proxy: ProxyRef,
+
+    /// Tells if the offer was created for drag-and-drop or for selection.
+    for_drag: bool,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl DataOffer {
+    /// Creates new `DataOffer`.
+    fn new(for_drag: bool, proxy_ref: ProxyRef) -> Self {
+        DataOffer {
+            proxy: proxy_ref,
+            for_drag: for_drag,
+        }
+    }
+
+    /// Creates new `DataOffer` wrapped in handler. Unlike most of the objects data offers are
+    /// created on server side.
+    pub fn new_object(_oid: wl::common::ObjectId,
+                      for_drag: bool,
+                      proxy_ref: ProxyRef)
+                      -> Box<wl::server::Object> {
+        Box::new(Handler::<_, wl_data_offer::Dispatcher>::new(Self::new(for_drag, proxy_ref)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+#[allow(unused_variables)]
+impl wl_data_offer::Interface for DataOffer {
+    fn accept(&mut self,
+              this_object_id: wl::common::ObjectId,
+              socket: &mut wl::server::ClientSocket,
+              serial: u32,
+              mime_type: String)
+              -> wl::server::Task {
+        wl::server::Task::None
+    }
+
+    fn receive(&mut self,
+               this_object_id: wl::common::ObjectId,
+               socket: &mut wl::server::ClientSocket,
+               mime_type: String,
+               fd: i32)
+               -> wl::server::Task {
+        if self.for_drag {
+            self.proxy.borrow_mut().request_drag_transfer(mime_type, fd);
+        } else {
+            self.proxy.borrow_mut().request_transfer(mime_type, fd);
+        }
+        wl::server::Task::None
+    }
+
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        // Clients not supporting `finish` request destroy the offer when they are done.
+        if self.for_drag {
+            self.proxy.borrow_mut().finish_drag();
+        }
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+
+    fn finish(&mut self,
+              this_object_id: wl::common::ObjectId,
+              socket: &mut wl::server::ClientSocket)
+              -> wl::server::Task {
+        if self.for_drag {
+            self.proxy.borrow_mut().finish_drag();
+        }
+        wl::server::Task::None
+    }
+
+    fn set_actions(&mut self,
+                   this_object_id: wl::common::ObjectId,
+                   socket: &mut wl::server::ClientSocket,
+                   dnd_actions: u32,
+                   preferred_action: u32)
+                   -> wl::server::Task {
+        let action = if (dnd_actions & wl_data_device_manager::dnd_action::COPY) != 0 {
+            wl_data_device_manager::dnd_action::COPY
+        } else {
+            wl_data_device_manager::dnd_action::NONE
+        };
+        send!(wl_data_offer::action(socket, this_object_id, action));
         wl::server::Task::None
     }
 }
@@ -0,0 +1,182 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementation of Mesa-specific Wayland `wl_drm` object.
+//!
+//! Only buffers shared via PRIME file descriptors are supported. Buffers are imported the same way
+//! as buffers created with `zwp_linux_dmabuf_v1`. `eglBindWaylandDisplayWL` is not used as it
+//! requires display from `libwayland-server` while this crate implements protocol on its own.
+
+use std::path::PathBuf;
+
+use skylane as wl;
+use skylane_protocols::server::Handler;
+use skylane_protocols::server::wayland::wl_display;
+use skylane_protocols::server::drm::wl_drm;
+
+use qualia::{Dmabuf, DmabufPlane, HwImage};
+
+use global::Global;
+use facade::Facade;
+use proxy::ProxyRef;
+use version;
+use protocol::linux_dmabuf_v1::DmabufBuffer;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Formats advertised to clients.
+const SUPPORTED_FORMATS: [u32; 2] = [wl_drm::format::ARGB8888, wl_drm::format::XRGB8888];
+
+/// Modifier meaning the buffer layout is defined implicitly by driver.
+const DRM_FORMAT_MOD_INVALID: u64 = 0x00ffffffffffffff;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `wl_drm` object.
+struct Drm {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Creates `wl_drm` global advertising DRM device with given path.
+pub fn get_global(device: PathBuf) -> Global {
+    Global::new(wl_drm::NAME,
+                wl_drm::VERSION,
+                Box::new(move |oid, version, proxy| Drm::new_object(oid, version, proxy, &device)))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Drm {
+    /// Creates new `Drm` and posts device path, supported formats and capabilities.
+    fn new(oid: wl::common::ObjectId, version: u32, proxy_ref: ProxyRef, device: &PathBuf) -> Self {
+        {
+            let proxy = proxy_ref.borrow();
+            let socket = proxy.get_socket();
+            send!(wl_drm::device(&socket, oid, &device.to_string_lossy().into_owned()));
+            for format in SUPPORTED_FORMATS.iter() {
+                send!(wl_drm::format(&socket, oid, *format));
+            }
+            if version >= version::WL_DRM_PRIME_SINCE {
+                send!(wl_drm::capabilities(&socket, oid, wl_drm::capability::PRIME));
+            }
+        }
+
+        Drm { proxy: proxy_ref }
+    }
+
+    fn new_object(oid: wl::common::ObjectId,
+                  version: u32,
+                  proxy_ref: ProxyRef,
+                  device: &PathBuf)
+                  -> Box<wl::server::Object> {
+        Box::new(Handler::<_, wl_drm::Dispatcher>::new(Self::new(oid, version, proxy_ref, device)))
+    }
+
+    /// Sends error informing client that buffers shared by name are not supported.
+    fn send_invalid_name(&self, socket: &mut wl::server::ClientSocket, oid: wl::common::ObjectId) {
+        send!(wl_display::error(socket,
+                                wl::common::DISPLAY_ID,
+                                oid,
+                                wl_drm::error::INVALID_NAME,
+                                "Only PRIME buffers are supported"));
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+#[allow(unused_variables)]
+impl wl_drm::Interface for Drm {
+    fn authenticate(&mut self,
+                    this_object_id: wl::common::ObjectId,
+                    socket: &mut wl::server::ClientSocket,
+                    id: u32)
+                    -> wl::server::Task {
+        // Buffers are shared only via PRIME file descriptors which do not require client to be
+        // authenticated by DRM master so authentication is always reported as successful.
+        send!(wl_drm::authenticated(socket, this_object_id));
+        wl::server::Task::None
+    }
+
+    fn create_buffer(&mut self,
+                     this_object_id: wl::common::ObjectId,
+                     socket: &mut wl::server::ClientSocket,
+                     id: wl::common::ObjectId,
+                     name: u32,
+                     width: i32,
+                     height: i32,
+                     stride: u32,
+                     format: u32)
+                     -> wl::server::Task {
+        self.send_invalid_name(socket, this_object_id);
+        wl::server::Task::None
+    }
+
+    fn create_planar_buffer(&mut self,
+                            this_object_id: wl::common::ObjectId,
+                            socket: &mut wl::server::ClientSocket,
+                            id: wl::common::ObjectId,
+                            name: u32,
+                            width: i32,
+                            height: i32,
+                            format: u32,
+                            offset0: i32,
+                            stride0: i32,
+                            offset1: i32,
+                            stride1: i32,
+                            offset2: i32,
+                            stride2: i32)
+                            -> wl::server::Task {
+        self.send_invalid_name(socket, this_object_id);
+        wl::server::Task::None
+    }
+
+    fn create_prime_buffer(&mut self,
+                           this_object_id: wl::common::ObjectId,
+                           socket: &mut wl::server::ClientSocket,
+                           id: wl::common::ObjectId,
+                           name: i32,
+                           width: i32,
+                           height: i32,
+                           format: u32,
+                           offset0: i32,
+                           stride0: i32,
+                           offset1: i32,
+                           stride1: i32,
+                           offset2: i32,
+                           stride2: i32)
+                           -> wl::server::Task {
+        if !SUPPORTED_FORMATS.contains(&format) {
+            send!(wl_display::error(socket,
+                                    wl::common::DISPLAY_ID,
+                                    this_object_id,
+                                    wl_drm::error::INVALID_FORMAT,
+                                    &format!("Format 0x{:x} is not supported", format)));
+            return wl::server::Task::None;
+        }
+
+        // All planes of PRIME buffer share one file descriptor. Supported formats have only one
+        // plane so offsets and strides of other planes are ignored.
+        let dmabuf = Dmabuf {
+            width: width as usize,
+            height: height as usize,
+            format: format,
+            planes: vec![DmabufPlane {
+                             fd: name,
+                             offset: offset0 as u32,
+                             stride: stride0 as u32,
+                             modifier: DRM_FORMAT_MOD_INVALID,
+                         }],
+        };
+
+        let image = HwImage::new_from_dmabuf(dmabuf);
+        let hiid = self.proxy.borrow_mut().create_hw_image(id, image);
+        wl::server::Task::Create {
+            id: id,
+            object: DmabufBuffer::new_object(self.proxy.clone(), hiid),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
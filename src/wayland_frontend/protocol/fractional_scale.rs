@@ -0,0 +1,124 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementations of Wayland `wp_fractional_scale_manager_v1` and `wp_fractional_scale_v1`
+//! objects.
+//!
+//! These objects inform clients about scale (possibly fractional) they should prefer when drawing
+//! buffers of their surfaces. The scale is chosen the same way as preferred buffer scale of
+//! `wl_surface`.
+
+use skylane as wl;
+use skylane_protocols::server::Handler;
+use skylane_protocols::server::wayland::wl_display;
+use skylane_protocols::server::fractional_scale_v1::wp_fractional_scale_manager_v1;
+use skylane_protocols::server::fractional_scale_v1::wp_fractional_scale_v1;
+
+use global::Global;
+use facade::Facade;
+use proxy::ProxyRef;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `wp_fractional_scale_manager_v1` object.
+struct FractionalScaleManager {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub fn get_global() -> Global {
+    Global::new(wp_fractional_scale_manager_v1::NAME,
+                wp_fractional_scale_manager_v1::VERSION,
+                Box::new(FractionalScaleManager::new_object))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl FractionalScaleManager {
+    /// Creates new `FractionalScaleManager`.
+    fn new(_oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Self {
+        FractionalScaleManager { proxy: proxy_ref }
+    }
+
+    fn new_object(oid: wl::common::ObjectId,
+                  _version: u32,
+                  proxy_ref: ProxyRef)
+                  -> Box<wl::server::Object> {
+        let manager = Self::new(oid, proxy_ref);
+        Box::new(Handler::<_, wp_fractional_scale_manager_v1::Dispatcher>::new(manager))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl wp_fractional_scale_manager_v1::Interface for FractionalScaleManager {
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               _socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+
+    fn get_fractional_scale(&mut self,
+                            this_object_id: wl::common::ObjectId,
+                            socket: &mut wl::server::ClientSocket,
+                            id: wl::common::ObjectId,
+                            surface: wl::common::ObjectId)
+                            -> wl::server::Task {
+        if !self.proxy.borrow_mut().add_fractional_scale_oid(surface, id) {
+            send!(wl_display::error(socket,
+                                    wl::common::DISPLAY_ID,
+                                    this_object_id,
+                                    wp_fractional_scale_manager_v1::error::FRACTIONAL_SCALE_EXISTS,
+                                    "Surface already has fractional scale object"));
+            return wl::server::Task::None;
+        }
+
+        wl::server::Task::Create {
+            id: id,
+            object: FractionalScale::new_object(surface, self.proxy.clone()),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `wp_fractional_scale_v1` object.
+struct FractionalScale {
+    surface_oid: wl::common::ObjectId,
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl FractionalScale {
+    /// Creates new `FractionalScale`.
+    fn new(surface_oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Self {
+        FractionalScale {
+            surface_oid: surface_oid,
+            proxy: proxy_ref,
+        }
+    }
+
+    fn new_object(surface_oid: wl::common::ObjectId,
+                  proxy_ref: ProxyRef)
+                  -> Box<wl::server::Object> {
+        let fractional_scale = Self::new(surface_oid, proxy_ref);
+        Box::new(Handler::<_, wp_fractional_scale_v1::Dispatcher>::new(fractional_scale))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl wp_fractional_scale_v1::Interface for FractionalScale {
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               _socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        self.proxy.borrow_mut().remove_fractional_scale_oid(self.surface_oid);
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,109 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementations of Wayland `ext_idle_notifier_v1` and `ext_idle_notification_v1` objects.
+//!
+//! Inactivity is tracked for all seats together. Timeout of notification is counted from the last
+//! input, so notification created while user is already idle long enough becomes idle right away.
+
+use skylane as wl;
+use skylane_protocols::server::Handler;
+use skylane_protocols::server::ext_idle_notify_v1::ext_idle_notifier_v1;
+use skylane_protocols::server::ext_idle_notify_v1::ext_idle_notification_v1;
+
+use global::Global;
+use facade::Facade;
+use proxy::ProxyRef;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `ext_idle_notifier_v1` object.
+struct IdleNotifier {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub fn get_global() -> Global {
+    Global::new(ext_idle_notifier_v1::NAME,
+                ext_idle_notifier_v1::VERSION,
+                Box::new(IdleNotifier::new_object))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl IdleNotifier {
+    /// Creates new `IdleNotifier`.
+    fn new(_oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Self {
+        IdleNotifier { proxy: proxy_ref }
+    }
+
+    fn new_object(oid: wl::common::ObjectId,
+                  _version: u32,
+                  proxy_ref: ProxyRef)
+                  -> Box<wl::server::Object> {
+        Box::new(Handler::<_, ext_idle_notifier_v1::Dispatcher>::new(Self::new(oid, proxy_ref)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl ext_idle_notifier_v1::Interface for IdleNotifier {
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               _socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+
+    fn get_idle_notification(&mut self,
+                             _this_object_id: wl::common::ObjectId,
+                             _socket: &mut wl::server::ClientSocket,
+                             id: wl::common::ObjectId,
+                             timeout: u32,
+                             _seat: wl::common::ObjectId)
+                             -> wl::server::Task {
+        wl::server::Task::Create {
+            id: id,
+            object: IdleNotification::new_object(id, timeout, self.proxy.clone()),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `ext_idle_notification_v1` object.
+struct IdleNotification {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl IdleNotification {
+    fn new(oid: wl::common::ObjectId, timeout: u32, proxy_ref: ProxyRef) -> Self {
+        proxy_ref.borrow_mut().add_idle_notification_oid(oid, timeout);
+        IdleNotification { proxy: proxy_ref }
+    }
+
+    fn new_object(oid: wl::common::ObjectId,
+                  timeout: u32,
+                  proxy_ref: ProxyRef)
+                  -> Box<wl::server::Object> {
+        let notification = Self::new(oid, timeout, proxy_ref);
+        Box::new(Handler::<_, ext_idle_notification_v1::Dispatcher>::new(notification))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl ext_idle_notification_v1::Interface for IdleNotification {
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               _socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        self.proxy.borrow_mut().remove_idle_notification_oid(this_object_id);
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,290 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementations of Wayland `zwlr_layer_shell_v1` and `zwlr_layer_surface_v1` objects.
+//!
+//! Layer surfaces are not managed as windows. They are placed on output according to their anchors
+//! and margins and drawn below or above windows depending on their layer. State of layer surface
+//! is gathered here and passed further when the surface is committed.
+
+use skylane as wl;
+use skylane_protocols::server::Handler;
+use skylane_protocols::server::wayland::wl_display;
+use skylane_protocols::server::wlr_layer_shell_unstable_v1::zwlr_layer_shell_v1;
+use skylane_protocols::server::wlr_layer_shell_unstable_v1::zwlr_layer_surface_v1;
+
+use qualia::{KeyboardInteractivity, Layer, LayerSurface as LayerSurfaceState, Margins, Size};
+use qualia::positioner::edges;
+
+use global::Global;
+use facade::Facade;
+use proxy::ProxyRef;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwlr_layer_shell_v1` object.
+struct LayerShell {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub fn get_global() -> Global {
+    Global::new(zwlr_layer_shell_v1::NAME,
+                zwlr_layer_shell_v1::VERSION,
+                Box::new(LayerShell::new_object))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl LayerShell {
+    /// Creates new `LayerShell`.
+    fn new(_oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Self {
+        LayerShell { proxy: proxy_ref }
+    }
+
+    fn new_object(oid: wl::common::ObjectId,
+                  _version: u32,
+                  proxy_ref: ProxyRef)
+                  -> Box<wl::server::Object> {
+        let shell = Self::new(oid, proxy_ref);
+        Box::new(Handler::<_, zwlr_layer_shell_v1::Dispatcher>::new(shell))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zwlr_layer_shell_v1::Interface for LayerShell {
+    fn get_layer_surface(&mut self,
+                         this_object_id: wl::common::ObjectId,
+                         socket: &mut wl::server::ClientSocket,
+                         id: wl::common::ObjectId,
+                         surface: wl::common::ObjectId,
+                         output: wl::common::ObjectId,
+                         layer: u32,
+                         namespace: String)
+                         -> wl::server::Task {
+        let layer = match translate_layer(layer) {
+            Some(layer) => layer,
+            None => {
+                send!(wl_display::error(socket,
+                                        wl::common::DISPLAY_ID,
+                                        this_object_id,
+                                        zwlr_layer_shell_v1::error::INVALID_LAYER,
+                                        &format!("Invalid layer {}", layer)));
+                return wl::server::Task::None;
+            }
+        };
+
+        let state = self.proxy
+            .borrow_mut()
+            .add_layer_surface(id, surface, output, layer, namespace);
+        wl::server::Task::Create {
+            id: id,
+            object: LayerSurface::new_object(surface, state, self.proxy.clone()),
+        }
+    }
+
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               _socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwlr_layer_surface_v1` object.
+struct LayerSurface {
+    proxy: ProxyRef,
+    surface_oid: wl::common::ObjectId,
+
+    /// Pending state of the surface. `None` if the surface or output is not known.
+    state: Option<LayerSurfaceState>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl LayerSurface {
+    fn new(surface_oid: wl::common::ObjectId,
+           state: Option<LayerSurfaceState>,
+           proxy_ref: ProxyRef)
+           -> Self {
+        LayerSurface {
+            proxy: proxy_ref,
+            surface_oid: surface_oid,
+            state: state,
+        }
+    }
+
+    fn new_object(surface_oid: wl::common::ObjectId,
+                  state: Option<LayerSurfaceState>,
+                  proxy_ref: ProxyRef)
+                  -> Box<wl::server::Object> {
+        let surface = Self::new(surface_oid, state, proxy_ref);
+        Box::new(Handler::<_, zwlr_layer_surface_v1::Dispatcher>::new(surface))
+    }
+
+    /// Applies given change to pending state and passes the state further. It will be used when
+    /// the surface is committed.
+    fn update<F>(&mut self, change: F)
+        where F: FnOnce(&mut LayerSurfaceState)
+    {
+        if let Some(ref mut state) = self.state {
+            change(state);
+            self.proxy.borrow_mut().set_pending_layer_surface(state.clone());
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zwlr_layer_surface_v1::Interface for LayerSurface {
+    fn set_size(&mut self,
+                _this_object_id: wl::common::ObjectId,
+                _socket: &mut wl::server::ClientSocket,
+                width: u32,
+                height: u32)
+                -> wl::server::Task {
+        self.update(|state| state.size = Size::new(width as usize, height as usize));
+        wl::server::Task::None
+    }
+
+    fn set_anchor(&mut self,
+                  this_object_id: wl::common::ObjectId,
+                  socket: &mut wl::server::ClientSocket,
+                  anchor: u32)
+                  -> wl::server::Task {
+        match edges::Edges::from_bits(anchor) {
+            Some(anchor) => self.update(|state| state.anchor = anchor),
+            None => {
+                send!(wl_display::error(socket,
+                                        wl::common::DISPLAY_ID,
+                                        this_object_id,
+                                        zwlr_layer_surface_v1::error::INVALID_ANCHOR,
+                                        &format!("Invalid anchor {}", anchor)));
+            }
+        }
+        wl::server::Task::None
+    }
+
+    fn set_exclusive_zone(&mut self,
+                          _this_object_id: wl::common::ObjectId,
+                          _socket: &mut wl::server::ClientSocket,
+                          zone: i32)
+                          -> wl::server::Task {
+        self.update(|state| state.exclusive_zone = zone);
+        wl::server::Task::None
+    }
+
+    fn set_margin(&mut self,
+                  _this_object_id: wl::common::ObjectId,
+                  _socket: &mut wl::server::ClientSocket,
+                  top: i32,
+                  right: i32,
+                  bottom: i32,
+                  left: i32)
+                  -> wl::server::Task {
+        // Negative margins are not supported; such surfaces are placed at the edges.
+        let margins = Margins::new(top.max(0) as usize,
+                                   right.max(0) as usize,
+                                   bottom.max(0) as usize,
+                                   left.max(0) as usize);
+        self.update(|state| state.margins = margins);
+        wl::server::Task::None
+    }
+
+    fn set_keyboard_interactivity(&mut self,
+                                  this_object_id: wl::common::ObjectId,
+                                  socket: &mut wl::server::ClientSocket,
+                                  keyboard_interactivity: u32)
+                                  -> wl::server::Task {
+        use self::zwlr_layer_surface_v1::error::INVALID_KEYBOARD_INTERACTIVITY;
+        let interactivity = match keyboard_interactivity {
+            zwlr_layer_surface_v1::keyboard_interactivity::NONE => KeyboardInteractivity::None,
+            zwlr_layer_surface_v1::keyboard_interactivity::EXCLUSIVE => {
+                KeyboardInteractivity::Exclusive
+            }
+            zwlr_layer_surface_v1::keyboard_interactivity::ON_DEMAND => {
+                KeyboardInteractivity::OnDemand
+            }
+            _ => {
+                send!(wl_display::error(socket,
+                                        wl::common::DISPLAY_ID,
+                                        this_object_id,
+                                        INVALID_KEYBOARD_INTERACTIVITY,
+                                        &format!("Invalid keyboard interactivity {}",
+                                                 keyboard_interactivity)));
+                return wl::server::Task::None;
+            }
+        };
+        self.update(|state| state.keyboard_interactivity = interactivity);
+        wl::server::Task::None
+    }
+
+    fn get_popup(&mut self,
+                 _this_object_id: wl::common::ObjectId,
+                 _socket: &mut wl::server::ClientSocket,
+                 _popup: wl::common::ObjectId)
+                 -> wl::server::Task {
+        log_warn3!("Pop-ups of layer surfaces are not supported");
+        wl::server::Task::None
+    }
+
+    fn ack_configure(&mut self,
+                     this_object_id: wl::common::ObjectId,
+                     socket: &mut wl::server::ClientSocket,
+                     serial: u32)
+                     -> wl::server::Task {
+        if !self.proxy.borrow_mut().ack_configure(self.surface_oid, serial) {
+            send!(wl_display::error(socket,
+                                    wl::common::DISPLAY_ID,
+                                    this_object_id,
+                                    zwlr_layer_surface_v1::error::INVALID_SURFACE_STATE,
+                                    &format!("Serial {} was not sent in configure", serial)));
+        }
+        wl::server::Task::None
+    }
+
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               _socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        self.proxy.borrow_mut().remove_layer_surface(self.surface_oid);
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+
+    fn set_layer(&mut self,
+                 this_object_id: wl::common::ObjectId,
+                 socket: &mut wl::server::ClientSocket,
+                 layer: u32)
+                 -> wl::server::Task {
+        match translate_layer(layer) {
+            Some(layer) => self.update(|state| state.layer = layer),
+            None => {
+                send!(wl_display::error(socket,
+                                        wl::common::DISPLAY_ID,
+                                        this_object_id,
+                                        zwlr_layer_shell_v1::error::INVALID_LAYER,
+                                        &format!("Invalid layer {}", layer)));
+            }
+        }
+        wl::server::Task::None
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Translates layer from protocol value.
+fn translate_layer(layer: u32) -> Option<Layer> {
+    match layer {
+        zwlr_layer_shell_v1::layer::BACKGROUND => Some(Layer::Background),
+        zwlr_layer_shell_v1::layer::BOTTOM => Some(Layer::Bottom),
+        zwlr_layer_shell_v1::layer::TOP => Some(Layer::Top),
+        zwlr_layer_shell_v1::layer::OVERLAY => Some(Layer::Overlay),
+        _ => None,
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
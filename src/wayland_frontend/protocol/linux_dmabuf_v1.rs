@@ -0,0 +1,335 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementations of Wayland `zwp_linux_dmabuf_v1`, `zwp_linux_buffer_params_v1` and
+//! DMA-BUF-based `wl_buffer` objects.
+
+use nix::unistd;
+
+use skylane as wl;
+use skylane_protocols::server::Handler;
+use skylane_protocols::server::wayland::wl_display;
+use skylane_protocols::server::wayland::wl_buffer;
+use skylane_protocols::server::linux_dmabuf_unstable_v1::zwp_linux_dmabuf_v1;
+use skylane_protocols::server::linux_dmabuf_unstable_v1::zwp_linux_buffer_params_v1;
+
+use qualia::{Dmabuf, DmabufPlane, HwImage, HwImageId};
+
+use global::Global;
+use facade::Facade;
+use proxy::ProxyRef;
+use version;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Maximal number of planes a buffer can consist of.
+const MAX_PLANES: usize = 4;
+
+/// DRM fourcc codes of supported formats.
+const SUPPORTED_FORMATS: [u32; 2] = [0x34325241 /* ARGB8888 */, 0x34325258 /* XRGB8888 */];
+
+/// Modifier meaning the buffer layout is defined implicitly by driver.
+const DRM_FORMAT_MOD_INVALID: u64 = 0x00ffffffffffffff;
+
+/// Modifier meaning the buffer is not tiled.
+const DRM_FORMAT_MOD_LINEAR: u64 = 0;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwp_linux_dmabuf_v1` object.
+struct LinuxDmabuf {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub fn get_global() -> Global {
+    Global::new(zwp_linux_dmabuf_v1::NAME,
+                zwp_linux_dmabuf_v1::VERSION,
+                Box::new(LinuxDmabuf::new_object))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl LinuxDmabuf {
+    /// Creates new `LinuxDmabuf` and posts supported formats and modifiers.
+    fn new(oid: wl::common::ObjectId, version: u32, proxy_ref: ProxyRef) -> Self {
+        {
+            let proxy = proxy_ref.borrow();
+            let socket = proxy.get_socket();
+            for format in SUPPORTED_FORMATS.iter() {
+                send!(zwp_linux_dmabuf_v1::format(&socket, oid, *format));
+                if version >= version::ZWP_LINUX_DMABUF_MODIFIER_SINCE {
+                    for modifier in [DRM_FORMAT_MOD_INVALID, DRM_FORMAT_MOD_LINEAR].iter() {
+                        send!(zwp_linux_dmabuf_v1::modifier(&socket,
+                                                            oid,
+                                                            *format,
+                                                            (*modifier >> 32) as u32,
+                                                            (*modifier & 0xFFFFFFFF) as u32));
+                    }
+                }
+            }
+        }
+
+        LinuxDmabuf { proxy: proxy_ref }
+    }
+
+    fn new_object(oid: wl::common::ObjectId,
+                  version: u32,
+                  proxy_ref: ProxyRef)
+                  -> Box<wl::server::Object> {
+        Box::new(Handler::<_, zwp_linux_dmabuf_v1::Dispatcher>::new(Self::new(oid,
+                                                                              version,
+                                                                              proxy_ref)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+#[allow(unused_variables)]
+impl zwp_linux_dmabuf_v1::Interface for LinuxDmabuf {
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+
+    fn create_params(&mut self,
+                     this_object_id: wl::common::ObjectId,
+                     socket: &mut wl::server::ClientSocket,
+                     params_id: wl::common::ObjectId)
+                     -> wl::server::Task {
+        wl::server::Task::Create {
+            id: params_id,
+            object: LinuxBufferParams::new_object(self.proxy.clone()),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwp_linux_buffer_params_v1` object.
+struct LinuxBufferParams {
+    proxy: ProxyRef,
+
+    /// Planes added by client. Their file descriptors are owned by this object until buffer is
+    /// created.
+    planes: [Option<DmabufPlane>; MAX_PLANES],
+
+    /// Tells if the parameters were already used to create a buffer.
+    used: bool,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl LinuxBufferParams {
+    /// Creates new `LinuxBufferParams`.
+    fn new(proxy_ref: ProxyRef) -> Self {
+        LinuxBufferParams {
+            proxy: proxy_ref,
+            planes: [None; MAX_PLANES],
+            used: false,
+        }
+    }
+
+    fn new_object(proxy_ref: ProxyRef) -> Box<wl::server::Object> {
+        Box::new(Handler::<_, zwp_linux_buffer_params_v1::Dispatcher>::new(Self::new(proxy_ref)))
+    }
+
+    /// Checks if parameters describe valid buffer and if so constructs `Dmabuf` taking ownership
+    /// of planes. Returns error code and message otherwise.
+    fn take_dmabuf(&mut self,
+                   width: i32,
+                   height: i32,
+                   format: u32)
+                   -> Result<Dmabuf, (u32, String)> {
+        if self.used {
+            return Err((zwp_linux_buffer_params_v1::error::ALREADY_USED,
+                        format!("Parameters were already used")));
+        }
+
+        let num_planes = self.planes.iter().take_while(|plane| plane.is_some()).count();
+        if (num_planes == 0) || self.planes[num_planes..].iter().any(|plane| plane.is_some()) {
+            return Err((zwp_linux_buffer_params_v1::error::INCOMPLETE,
+                        format!("Missing planes")));
+        }
+
+        if !SUPPORTED_FORMATS.contains(&format) {
+            return Err((zwp_linux_buffer_params_v1::error::INVALID_FORMAT,
+                        format!("Format 0x{:x} is not supported", format)));
+        }
+
+        if (width <= 0) || (height <= 0) {
+            return Err((zwp_linux_buffer_params_v1::error::INVALID_DIMENSIONS,
+                        format!("Invalid size {}x{}", width, height)));
+        }
+
+        self.used = true;
+        let planes = self.planes.iter_mut().filter_map(|plane| plane.take()).collect();
+        Ok(Dmabuf {
+               width: width as usize,
+               height: height as usize,
+               format: format,
+               planes: planes,
+           })
+    }
+
+    /// Registers new hardware image and creates `wl_buffer` object for it.
+    fn create_buffer(&mut self,
+                     buffer_oid: wl::common::ObjectId,
+                     dmabuf: Dmabuf)
+                     -> wl::server::Task {
+        let image = HwImage::new_from_dmabuf(dmabuf);
+        let hiid = self.proxy.borrow_mut().create_hw_image(buffer_oid, image);
+        wl::server::Task::Create {
+            id: buffer_oid,
+            object: DmabufBuffer::new_object(self.proxy.clone(), hiid),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+#[allow(unused_variables)]
+impl zwp_linux_buffer_params_v1::Interface for LinuxBufferParams {
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+
+    fn add(&mut self,
+           this_object_id: wl::common::ObjectId,
+           socket: &mut wl::server::ClientSocket,
+           fd: i32,
+           plane_idx: u32,
+           offset: u32,
+           stride: u32,
+           modifier_hi: u32,
+           modifier_lo: u32)
+           -> wl::server::Task {
+        let index = plane_idx as usize;
+        let error = if self.used {
+            Some((zwp_linux_buffer_params_v1::error::ALREADY_USED,
+                  format!("Parameters were already used")))
+        } else if index >= MAX_PLANES {
+            Some((zwp_linux_buffer_params_v1::error::PLANE_IDX,
+                  format!("Plane index {} out of bounds", plane_idx)))
+        } else if self.planes[index].is_some() {
+            Some((zwp_linux_buffer_params_v1::error::PLANE_SET,
+                  format!("Plane {} was already set", plane_idx)))
+        } else {
+            None
+        };
+
+        if let Some((code, msg)) = error {
+            unistd::close(fd).ok();
+            send!(wl_display::error(socket, wl::common::DISPLAY_ID, this_object_id, code, &msg));
+        } else {
+            self.planes[index] = Some(DmabufPlane {
+                                          fd: fd,
+                                          offset: offset,
+                                          stride: stride,
+                                          modifier: ((modifier_hi as u64) << 32) |
+                                                    (modifier_lo as u64),
+                                      });
+        }
+        wl::server::Task::None
+    }
+
+    fn create(&mut self,
+              this_object_id: wl::common::ObjectId,
+              socket: &mut wl::server::ClientSocket,
+              width: i32,
+              height: i32,
+              format: u32,
+              flags: u32)
+              -> wl::server::Task {
+        match self.take_dmabuf(width, height, format) {
+            Ok(dmabuf) => {
+                let buffer_oid = self.proxy.borrow_mut().generate_next_server_oid();
+                send!(zwp_linux_buffer_params_v1::created(socket, this_object_id, buffer_oid));
+                self.create_buffer(buffer_oid, dmabuf)
+            }
+            Err((_, msg)) => {
+                log_warn2!("Failed to create DMA-BUF buffer: {}", msg);
+                send!(zwp_linux_buffer_params_v1::failed(socket, this_object_id));
+                wl::server::Task::None
+            }
+        }
+    }
+
+    fn create_immed(&mut self,
+                    this_object_id: wl::common::ObjectId,
+                    socket: &mut wl::server::ClientSocket,
+                    buffer_id: wl::common::ObjectId,
+                    width: i32,
+                    height: i32,
+                    format: u32,
+                    flags: u32)
+                    -> wl::server::Task {
+        match self.take_dmabuf(width, height, format) {
+            Ok(dmabuf) => self.create_buffer(buffer_id, dmabuf),
+            Err((code, msg)) => {
+                send!(wl_display::error(socket,
+                                        wl::common::DISPLAY_ID,
+                                        this_object_id,
+                                        code,
+                                        &msg));
+                wl::server::Task::None
+            }
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Drop for LinuxBufferParams {
+    fn drop(&mut self) {
+        // Close file descriptors of planes which were not used to create buffer.
+        for plane in self.planes.iter().filter_map(|plane| *plane) {
+            unistd::close(plane.fd).ok();
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `wl_buffer` object backed by DMA-BUF.
+pub struct DmabufBuffer {
+    proxy: ProxyRef,
+    hiid: HwImageId,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl DmabufBuffer {
+    fn new(proxy_ref: ProxyRef, hiid: HwImageId) -> Self {
+        DmabufBuffer {
+            proxy: proxy_ref,
+            hiid: hiid,
+        }
+    }
+
+    /// Creates new `DmabufBuffer` wrapped in handler.
+    pub fn new_object(proxy_ref: ProxyRef, hiid: HwImageId) -> Box<wl::server::Object> {
+        Box::new(Handler::<_, wl_buffer::Dispatcher>::new(Self::new(proxy_ref, hiid)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+#[allow(unused_variables)]
+impl wl_buffer::Interface for DmabufBuffer {
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        self.proxy.borrow_mut().destroy_hw_image(self.hiid);
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
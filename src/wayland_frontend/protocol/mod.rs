@@ -6,12 +6,26 @@
 pub mod display;
 pub mod registry;
 pub mod shm;
+pub mod linux_dmabuf_v1;
+pub mod drm;
 
 pub mod compositor;
 pub mod shell;
+pub mod xdg_shell;
 pub mod xdg_shell_v6;
+pub mod xdg_decoration;
 
 pub mod data_device_manager;
+pub mod data_control;
 pub mod seat;
+pub mod pointer_gestures;
+pub mod idle_notify;
+pub mod session_lock;
+pub mod layer_shell;
+pub mod fractional_scale;
+pub mod screencopy;
 pub mod subcompositor;
 pub mod output;
+pub mod xdg_output;
+pub mod output_management;
+pub mod presentation;
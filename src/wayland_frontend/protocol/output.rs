@@ -2,66 +2,106 @@
 // the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
 
 //! Implementations of Wayland `wl_output` object.
+//!
+//! All modes supported by the output are advertised, the current one last. Information is sent
+//! again to bound objects when the output changes.
 
 use skylane as wl;
 use skylane_protocols::server::Handler;
 use skylane_protocols::server::wayland::wl_output;
 
-use qualia::OutputInfo;
+use qualia::{OutputInfo, OutputMode};
 
 use global::Global;
-use proxy::ProxyRef;
+use facade::Facade;
+use proxy::{translate_transform, ProxyRef};
+use version;
 
 // -------------------------------------------------------------------------------------------------
 
 /// Wayland `wl_output` object.
-struct Output {}
+struct Output {
+    proxy: ProxyRef,
+}
 
 // -------------------------------------------------------------------------------------------------
 
 pub fn get_global(info: OutputInfo) -> Global {
     Global::new(wl_output::NAME,
                 wl_output::VERSION,
-                Box::new(move |oid, proxy| Output::new_object(oid, proxy, info.clone())))
+                Box::new(move |oid, version, proxy| {
+                             Output::new_object(oid, version, proxy, info.clone())
+                         }))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Sends information about output to `wl_output` object of given version.
+pub fn send_info(socket: &wl::server::ClientSocket,
+                 oid: wl::common::ObjectId,
+                 version: u32,
+                 info: &OutputInfo) {
+    send!(wl_output::geometry(socket,
+                              oid,
+                              info.area.pos.x as i32,
+                              info.area.pos.y as i32,
+                              info.physical_size.width as i32,
+                              info.physical_size.height as i32,
+                              wl_output::subpixel::UNKNOWN as i32,
+                              &info.make,
+                              &info.model,
+                              translate_transform(info.transform) as i32));
+
+    // Refresh rate is sent in mHz; the first mode of output is the preferred one
+    let current = OutputMode::new(info.pixel_size, info.refresh_rate);
+    for (i, mode) in info.modes.iter().enumerate().filter(|&(_, mode)| *mode != current) {
+        let flags = if i == 0 { wl_output::mode::PREFERRED as u32 } else { 0 };
+        send!(wl_output::mode(socket,
+                              oid,
+                              flags,
+                              mode.size.width as i32,
+                              mode.size.height as i32,
+                              1000 * mode.refresh_rate as i32));
+    }
+
+    let mut flags = wl_output::mode::CURRENT as u32;
+    if info.modes.first() == Some(&current) {
+        flags |= wl_output::mode::PREFERRED as u32;
+    }
+    send!(wl_output::mode(socket,
+                          oid,
+                          flags,
+                          info.pixel_size.width as i32,
+                          info.pixel_size.height as i32,
+                          1000 * info.refresh_rate as i32));
+
+    if version >= version::WL_OUTPUT_DONE_SINCE {
+        // Integer scale is rounded up so clients rather downscale than upscale
+        send!(wl_output::scale(socket, oid, info.scale.ceil() as i32));
+        send!(wl_output::done(socket, oid));
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
 
 impl Output {
-    fn new(oid: wl::common::ObjectId, proxy_ref: ProxyRef, info: OutputInfo) -> Self {
+    fn new(oid: wl::common::ObjectId, version: u32, proxy_ref: ProxyRef, info: OutputInfo) -> Self {
         {
-            let proxy = proxy_ref.borrow();
-            let socket = proxy.get_socket();
-            send!(wl_output::geometry(&socket,
-                                      oid,
-                                      info.area.pos.x as i32,
-                                      info.area.pos.y as i32,
-                                      info.physical_size.width as i32,
-                                      info.physical_size.height as i32,
-                                      wl_output::subpixel::UNKNOWN as i32,
-                                      &info.make,
-                                      &info.model,
-                                      wl_output::transform::NORMAL as i32));
-
-            send!(wl_output::mode(&socket,
-                                  oid,
-                                  wl_output::mode::CURRENT as u32,
-                                  info.area.size.width as i32,
-                                  info.area.size.height as i32,
-                                  info.refresh_rate as i32));
-
-            send!(wl_output::scale(&socket, oid, 1));
-            send!(wl_output::done(&socket, oid));
+            let mut proxy = proxy_ref.borrow_mut();
+            send_info(&proxy.get_socket(), oid, version, &info);
+            proxy.set_object_version(oid, version);
+            proxy.add_output_oid(info.id, oid);
         }
-
-        Output {}
+        Output { proxy: proxy_ref }
     }
 
     fn new_object(oid: wl::common::ObjectId,
+                  version: u32,
                   proxy_ref: ProxyRef,
                   info: OutputInfo)
                   -> Box<wl::server::Object> {
-        Box::new(Handler::<_, wl_output::Dispatcher>::new(Self::new(oid, proxy_ref, info)))
+        let output = Self::new(oid, version, proxy_ref, info);
+        Box::new(Handler::<_, wl_output::Dispatcher>::new(output))
     }
 }
 
@@ -72,6 +112,7 @@ impl wl_output::Interface for Output {
                this_object_id: wl::common::ObjectId,
                _socket: &mut wl::server::ClientSocket)
                -> wl::server::Task {
+        self.proxy.borrow_mut().remove_output_oid(this_object_id);
         wl::server::Task::Destroy { id: this_object_id }
     }
 }
@@ -0,0 +1,523 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementations of Wayland `zwlr_output_manager_v1`, `zwlr_output_head_v1`,
+//! `zwlr_output_mode_v1`, `zwlr_output_configuration_v1` and `zwlr_output_configuration_head_v1`
+//! objects.
+//!
+//! These objects let tools like `wlr-randr` or `kanshi` enable and disable outputs and change their
+//! modes, positions, transforms and scales. Heads and modes are created by server and have no
+//! requests so they do not need handlers. Configurations are sent to `Exhibitor` as perceptrons
+//! and client is informed about the result when `Engine` receives it. The global is privileged
+//! and advertised only to clients allowed by security policy.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use skylane as wl;
+use skylane_protocols::server::Handler;
+use skylane_protocols::server::wayland::wl_display;
+use skylane_protocols::server::wlr_output_management_unstable_v1::zwlr_output_manager_v1;
+use skylane_protocols::server::wlr_output_management_unstable_v1::zwlr_output_head_v1;
+use skylane_protocols::server::wlr_output_management_unstable_v1::zwlr_output_mode_v1;
+use skylane_protocols::server::wlr_output_management_unstable_v1::zwlr_output_configuration_v1;
+use skylane_protocols::server::wlr_output_management_unstable_v1::zwlr_output_configuration_head_v1;
+
+use qualia::{OutputConfiguration, OutputInfo, OutputMode, Position, Size};
+
+use global::Global;
+use facade::Facade;
+use proxy::{translate_transform, translate_wl_transform, ProxyRef};
+use protocol::xdg_output;
+use version;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Head advertised to `zwlr_output_manager_v1` object together with its modes.
+#[derive(Clone, Debug)]
+pub struct OutputHead {
+    /// ID of the output represented by the head.
+    pub output_id: i32,
+
+    /// OID of `zwlr_output_head_v1` object.
+    pub oid: wl::common::ObjectId,
+
+    /// Modes of the output and OIDs of `zwlr_output_mode_v1` objects representing them. The first
+    /// mode is the preferred one.
+    pub modes: Vec<(wl::common::ObjectId, OutputMode)>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Advertises head to `zwlr_output_manager_v1` object of given version and sends its description,
+/// modes and current state. `done` event has to be sent by the caller.
+pub fn send_head(socket: &wl::server::ClientSocket,
+                 manager_oid: wl::common::ObjectId,
+                 version: u32,
+                 head: &OutputHead,
+                 info: &OutputInfo) {
+    send!(zwlr_output_manager_v1::head(socket, manager_oid, head.oid));
+    send!(zwlr_output_head_v1::name(socket, head.oid, &info.model));
+    send!(zwlr_output_head_v1::description(socket, head.oid, &xdg_output::get_description(info)));
+    send!(zwlr_output_head_v1::physical_size(socket,
+                                             head.oid,
+                                             info.physical_size.width as i32,
+                                             info.physical_size.height as i32));
+    if version >= version::ZWLR_OUTPUT_HEAD_MAKE_SINCE {
+        send!(zwlr_output_head_v1::make(socket, head.oid, &info.make));
+        send!(zwlr_output_head_v1::model(socket, head.oid, &info.model));
+    }
+
+    for (i, &(mode_oid, ref mode)) in head.modes.iter().enumerate() {
+        send!(zwlr_output_head_v1::mode(socket, head.oid, mode_oid));
+        send!(zwlr_output_mode_v1::size(socket,
+                                        mode_oid,
+                                        mode.size.width as i32,
+                                        mode.size.height as i32));
+        send!(zwlr_output_mode_v1::refresh(socket, mode_oid, 1000 * mode.refresh_rate as i32));
+        if i == 0 {
+            send!(zwlr_output_mode_v1::preferred(socket, mode_oid));
+        }
+    }
+
+    send_head_state(socket, head, info);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Sends current state of output to `zwlr_output_head_v1` object. Properties of disabled outputs
+/// are not sent. `done` event has to be sent by the caller.
+pub fn send_head_state(socket: &wl::server::ClientSocket, head: &OutputHead, info: &OutputInfo) {
+    send!(zwlr_output_head_v1::enabled(socket, head.oid, info.enabled as i32));
+    if !info.enabled {
+        return;
+    }
+
+    let current = OutputMode::new(info.pixel_size, info.refresh_rate);
+    if let Some(&(mode_oid, _)) = head.modes.iter().find(|&&(_, ref mode)| *mode == current) {
+        send!(zwlr_output_head_v1::current_mode(socket, head.oid, mode_oid));
+    }
+    send!(zwlr_output_head_v1::position(socket,
+                                        head.oid,
+                                        info.area.pos.x as i32,
+                                        info.area.pos.y as i32));
+    send!(zwlr_output_head_v1::transform(socket,
+                                         head.oid,
+                                         translate_transform(info.transform) as i32));
+    send!(zwlr_output_head_v1::scale(socket, head.oid, info.scale));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Informs `zwlr_output_head_v1` object and its modes that they will not be used any more.
+pub fn send_head_finished(socket: &wl::server::ClientSocket, head: &OutputHead) {
+    for &(mode_oid, _) in head.modes.iter() {
+        send!(zwlr_output_mode_v1::finished(socket, mode_oid));
+    }
+    send!(zwlr_output_head_v1::finished(socket, head.oid));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwlr_output_manager_v1` object.
+struct OutputManager {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub fn get_global() -> Global {
+    Global::new_privileged(zwlr_output_manager_v1::NAME,
+                           zwlr_output_manager_v1::VERSION,
+                           Box::new(OutputManager::new_object))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl OutputManager {
+    /// Creates new `OutputManager` and advertises current outputs to it.
+    fn new(oid: wl::common::ObjectId, version: u32, proxy_ref: ProxyRef) -> Self {
+        {
+            let mut proxy = proxy_ref.borrow_mut();
+            proxy.set_object_version(oid, version);
+            proxy.add_output_manager_oid(oid);
+        }
+        OutputManager { proxy: proxy_ref }
+    }
+
+    fn new_object(oid: wl::common::ObjectId,
+                  version: u32,
+                  proxy_ref: ProxyRef)
+                  -> Box<wl::server::Object> {
+        let manager = Self::new(oid, version, proxy_ref);
+        Box::new(Handler::<_, zwlr_output_manager_v1::Dispatcher>::new(manager))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zwlr_output_manager_v1::Interface for OutputManager {
+    fn create_configuration(&mut self,
+                            this_object_id: wl::common::ObjectId,
+                            _socket: &mut wl::server::ClientSocket,
+                            id: wl::common::ObjectId,
+                            serial: u32)
+                            -> wl::server::Task {
+        let heads = self.proxy.borrow().get_output_heads(this_object_id);
+        wl::server::Task::Create {
+            id: id,
+            object: OutputConfigurationObject::new_object(serial, heads, self.proxy.clone()),
+        }
+    }
+
+    fn stop(&mut self,
+            this_object_id: wl::common::ObjectId,
+            _socket: &mut wl::server::ClientSocket)
+            -> wl::server::Task {
+        self.proxy.borrow_mut().remove_output_manager_oid(this_object_id);
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwlr_output_configuration_v1` object.
+///
+/// Keeps heads advertised to manager at the moment of creation. If outputs change in meantime the
+/// serial becomes outdated and the configuration is cancelled anyway.
+struct OutputConfigurationObject {
+    proxy: ProxyRef,
+
+    /// Serial of output configuration the client based this configuration on.
+    serial: u32,
+
+    /// Heads known to the manager which created this configuration.
+    heads: Vec<OutputHead>,
+
+    /// Configured heads and their settings. Settings of disabled heads are `None`.
+    configured: Vec<(wl::common::ObjectId, Option<Rc<RefCell<OutputConfiguration>>>)>,
+
+    /// Tells if the configuration was already applied or tested.
+    used: bool,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl OutputConfigurationObject {
+    /// Creates new `OutputConfigurationObject`.
+    fn new(serial: u32, heads: Vec<OutputHead>, proxy_ref: ProxyRef) -> Self {
+        OutputConfigurationObject {
+            proxy: proxy_ref,
+            serial: serial,
+            heads: heads,
+            configured: Vec::new(),
+            used: false,
+        }
+    }
+
+    fn new_object(serial: u32,
+                  heads: Vec<OutputHead>,
+                  proxy_ref: ProxyRef)
+                  -> Box<wl::server::Object> {
+        let configuration = Self::new(serial, heads, proxy_ref);
+        Box::new(Handler::<_, zwlr_output_configuration_v1::Dispatcher>::new(configuration))
+    }
+
+    /// Sends protocol error.
+    fn send_error(this_object_id: wl::common::ObjectId,
+                  socket: &mut wl::server::ClientSocket,
+                  code: u32,
+                  msg: &str) {
+        send!(wl_display::error(socket,
+                                wl::common::DISPLAY_ID,
+                                this_object_id,
+                                code,
+                                &msg.to_owned()));
+    }
+
+    /// Checks if head can be configured. Sends error and returns `false` otherwise.
+    fn check_head(&self,
+                  this_object_id: wl::common::ObjectId,
+                  socket: &mut wl::server::ClientSocket,
+                  head_oid: wl::common::ObjectId)
+                  -> bool {
+        if self.used {
+            Self::send_error(this_object_id,
+                             socket,
+                             zwlr_output_configuration_v1::error::ALREADY_USED,
+                             "Configuration was already used");
+            false
+        } else if self.configured.iter().any(|&(oid, _)| oid == head_oid) {
+            Self::send_error(this_object_id,
+                             socket,
+                             zwlr_output_configuration_v1::error::ALREADY_CONFIGURED_HEAD,
+                             "Head was already configured");
+            false
+        } else {
+            true
+        }
+    }
+
+    /// Checks configuration and passes it to `Exhibitor` to be applied or tested.
+    fn request(&mut self,
+               this_object_id: wl::common::ObjectId,
+               socket: &mut wl::server::ClientSocket,
+               test: bool) {
+        if self.used {
+            Self::send_error(this_object_id,
+                             socket,
+                             zwlr_output_configuration_v1::error::ALREADY_USED,
+                             "Configuration was already used");
+            return;
+        }
+        self.used = true;
+
+        if self.heads.iter().any(|head| self.configured.iter().all(|&(oid, _)| oid != head.oid)) {
+            Self::send_error(this_object_id,
+                             socket,
+                             zwlr_output_configuration_v1::error::UNCONFIGURED_HEAD,
+                             "Not all heads were configured");
+            return;
+        }
+
+        let mut configurations = Vec::new();
+        for &(head_oid, ref settings) in self.configured.iter() {
+            if let Some(ref settings) = *settings {
+                configurations.push(settings.borrow().clone());
+            } else if let Some(head) = self.heads.iter().find(|head| head.oid == head_oid) {
+                configurations.push(OutputConfiguration::new(head.output_id, false));
+            }
+        }
+
+        self.proxy
+            .borrow_mut()
+            .request_output_configuration(this_object_id, self.serial, configurations, test);
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zwlr_output_configuration_v1::Interface for OutputConfigurationObject {
+    fn enable_head(&mut self,
+                   this_object_id: wl::common::ObjectId,
+                   socket: &mut wl::server::ClientSocket,
+                   id: wl::common::ObjectId,
+                   head: wl::common::ObjectId)
+                   -> wl::server::Task {
+        if !self.check_head(this_object_id, socket, head) {
+            return wl::server::Task::None;
+        }
+
+        // Heads of other managers or finished heads are not known to this configuration so they
+        // are configured, but ignored
+        let mut modes = Vec::new();
+        let settings = Rc::new(RefCell::new(OutputConfiguration::new(0, true)));
+        if let Some(output_head) = self.heads.iter().find(|output_head| output_head.oid == head) {
+            settings.borrow_mut().id = output_head.output_id;
+            modes = output_head.modes.clone();
+            self.configured.push((head, Some(settings.clone())));
+        } else {
+            log_warn2!("Client enabled unknown output head {}", head);
+            self.configured.push((head, None));
+        }
+
+        wl::server::Task::Create {
+            id: id,
+            object: OutputConfigurationHead::new_object(modes, settings),
+        }
+    }
+
+    fn disable_head(&mut self,
+                    this_object_id: wl::common::ObjectId,
+                    socket: &mut wl::server::ClientSocket,
+                    head: wl::common::ObjectId)
+                    -> wl::server::Task {
+        if self.check_head(this_object_id, socket, head) {
+            self.configured.push((head, None));
+        }
+        wl::server::Task::None
+    }
+
+    fn apply(&mut self,
+             this_object_id: wl::common::ObjectId,
+             socket: &mut wl::server::ClientSocket)
+             -> wl::server::Task {
+        self.request(this_object_id, socket, false);
+        wl::server::Task::None
+    }
+
+    fn test(&mut self,
+            this_object_id: wl::common::ObjectId,
+            socket: &mut wl::server::ClientSocket)
+            -> wl::server::Task {
+        self.request(this_object_id, socket, true);
+        wl::server::Task::None
+    }
+
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               _socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        self.proxy.borrow_mut().remove_output_configuration_oid(this_object_id);
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwlr_output_configuration_head_v1` object.
+struct OutputConfigurationHead {
+    /// Modes of the configured head.
+    modes: Vec<(wl::common::ObjectId, OutputMode)>,
+
+    /// Settings shared with configuration object.
+    settings: Rc<RefCell<OutputConfiguration>>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl OutputConfigurationHead {
+    /// Creates new `OutputConfigurationHead`.
+    fn new(modes: Vec<(wl::common::ObjectId, OutputMode)>,
+           settings: Rc<RefCell<OutputConfiguration>>)
+           -> Self {
+        OutputConfigurationHead {
+            modes: modes,
+            settings: settings,
+        }
+    }
+
+    fn new_object(modes: Vec<(wl::common::ObjectId, OutputMode)>,
+                  settings: Rc<RefCell<OutputConfiguration>>)
+                  -> Box<wl::server::Object> {
+        let head = Self::new(modes, settings);
+        Box::new(Handler::<_, zwlr_output_configuration_head_v1::Dispatcher>::new(head))
+    }
+
+    /// Sends protocol error.
+    fn send_error(this_object_id: wl::common::ObjectId,
+                  socket: &mut wl::server::ClientSocket,
+                  code: u32,
+                  msg: &str) {
+        send!(wl_display::error(socket,
+                                wl::common::DISPLAY_ID,
+                                this_object_id,
+                                code,
+                                &msg.to_owned()));
+    }
+
+    /// Sends `already_set` error if property was already set. Returns `true` if it was not.
+    fn check_unset<T>(this_object_id: wl::common::ObjectId,
+                      socket: &mut wl::server::ClientSocket,
+                      property: &Option<T>)
+                      -> bool {
+        if property.is_some() {
+            Self::send_error(this_object_id,
+                             socket,
+                             zwlr_output_configuration_head_v1::error::ALREADY_SET,
+                             "Property was already set");
+            false
+        } else {
+            true
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zwlr_output_configuration_head_v1::Interface for OutputConfigurationHead {
+    fn set_mode(&mut self,
+                this_object_id: wl::common::ObjectId,
+                socket: &mut wl::server::ClientSocket,
+                mode: wl::common::ObjectId)
+                -> wl::server::Task {
+        let mut settings = self.settings.borrow_mut();
+        if Self::check_unset(this_object_id, socket, &settings.mode) {
+            if let Some(&(_, output_mode)) = self.modes.iter().find(|&&(oid, _)| oid == mode) {
+                settings.mode = Some(output_mode);
+            } else {
+                Self::send_error(this_object_id,
+                                 socket,
+                                 zwlr_output_configuration_head_v1::error::INVALID_MODE,
+                                 "Mode does not belong to head");
+            }
+        }
+        wl::server::Task::None
+    }
+
+    fn set_custom_mode(&mut self,
+                       this_object_id: wl::common::ObjectId,
+                       socket: &mut wl::server::ClientSocket,
+                       width: i32,
+                       height: i32,
+                       refresh: i32)
+                       -> wl::server::Task {
+        let mut settings = self.settings.borrow_mut();
+        if Self::check_unset(this_object_id, socket, &settings.mode) {
+            if (width > 0) && (height > 0) && (refresh >= 0) {
+                let refresh_rate = (refresh as f32 / 1000.0).round() as usize;
+                let size = Size::new(width as usize, height as usize);
+                settings.mode = Some(OutputMode::new(size, refresh_rate));
+            } else {
+                Self::send_error(this_object_id,
+                                 socket,
+                                 zwlr_output_configuration_head_v1::error::INVALID_CUSTOM_MODE,
+                                 "Size must be positive and refresh rate not negative");
+            }
+        }
+        wl::server::Task::None
+    }
+
+    fn set_position(&mut self,
+                    this_object_id: wl::common::ObjectId,
+                    socket: &mut wl::server::ClientSocket,
+                    x: i32,
+                    y: i32)
+                    -> wl::server::Task {
+        let mut settings = self.settings.borrow_mut();
+        if Self::check_unset(this_object_id, socket, &settings.position) {
+            settings.position = Some(Position::new(x as isize, y as isize));
+        }
+        wl::server::Task::None
+    }
+
+    fn set_transform(&mut self,
+                     this_object_id: wl::common::ObjectId,
+                     socket: &mut wl::server::ClientSocket,
+                     transform: i32)
+                     -> wl::server::Task {
+        let mut settings = self.settings.borrow_mut();
+        if Self::check_unset(this_object_id, socket, &settings.transform) {
+            if let Some(transform) = translate_wl_transform(transform) {
+                settings.transform = Some(transform);
+            } else {
+                Self::send_error(this_object_id,
+                                 socket,
+                                 zwlr_output_configuration_head_v1::error::INVALID_TRANSFORM,
+                                 "Unknown transform");
+            }
+        }
+        wl::server::Task::None
+    }
+
+    fn set_scale(&mut self,
+                 this_object_id: wl::common::ObjectId,
+                 socket: &mut wl::server::ClientSocket,
+                 scale: f32)
+                 -> wl::server::Task {
+        let mut settings = self.settings.borrow_mut();
+        if Self::check_unset(this_object_id, socket, &settings.scale) {
+            if scale > 0.0 {
+                settings.scale = Some(scale);
+            } else {
+                Self::send_error(this_object_id,
+                                 socket,
+                                 zwlr_output_configuration_head_v1::error::INVALID_SCALE,
+                                 "Scale must be positive");
+            }
+        }
+        wl::server::Task::None
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
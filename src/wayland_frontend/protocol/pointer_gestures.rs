@@ -0,0 +1,144 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementations of Wayland `zwp_pointer_gestures_v1`, `zwp_pointer_gesture_swipe_v1` and
+//! `zwp_pointer_gesture_pinch_v1` objects.
+//!
+//! Only touchpad gestures not consumed by compositor are forwarded to clients.
+
+use skylane as wl;
+use skylane_protocols::server::Handler;
+use skylane_protocols::server::pointer_gestures_unstable_v1::zwp_pointer_gestures_v1;
+use skylane_protocols::server::pointer_gestures_unstable_v1::zwp_pointer_gesture_swipe_v1;
+use skylane_protocols::server::pointer_gestures_unstable_v1::zwp_pointer_gesture_pinch_v1;
+
+use global::Global;
+use facade::Facade;
+use proxy::ProxyRef;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwp_pointer_gestures_v1` object.
+struct PointerGestures {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub fn get_global() -> Global {
+    Global::new(zwp_pointer_gestures_v1::NAME,
+                zwp_pointer_gestures_v1::VERSION,
+                Box::new(PointerGestures::new_object))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl PointerGestures {
+    /// Creates new `PointerGestures`.
+    fn new(_oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Self {
+        PointerGestures { proxy: proxy_ref }
+    }
+
+    fn new_object(oid: wl::common::ObjectId,
+                  _version: u32,
+                  proxy_ref: ProxyRef)
+                  -> Box<wl::server::Object> {
+        Box::new(Handler::<_, zwp_pointer_gestures_v1::Dispatcher>::new(Self::new(oid, proxy_ref)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zwp_pointer_gestures_v1::Interface for PointerGestures {
+    fn get_swipe_gesture(&mut self,
+                         _this_object_id: wl::common::ObjectId,
+                         _socket: &mut wl::server::ClientSocket,
+                         id: wl::common::ObjectId,
+                         _pointer: wl::common::ObjectId)
+                         -> wl::server::Task {
+        wl::server::Task::Create {
+            id: id,
+            object: SwipeGesture::new_object(id, self.proxy.clone()),
+        }
+    }
+
+    fn get_pinch_gesture(&mut self,
+                         _this_object_id: wl::common::ObjectId,
+                         _socket: &mut wl::server::ClientSocket,
+                         id: wl::common::ObjectId,
+                         _pointer: wl::common::ObjectId)
+                         -> wl::server::Task {
+        wl::server::Task::Create {
+            id: id,
+            object: PinchGesture::new_object(id, self.proxy.clone()),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwp_pointer_gesture_swipe_v1` object.
+struct SwipeGesture {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl SwipeGesture {
+    fn new(oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Self {
+        proxy_ref.borrow_mut().add_swipe_gesture_oid(oid);
+        SwipeGesture { proxy: proxy_ref }
+    }
+
+    fn new_object(oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Box<wl::server::Object> {
+        let gesture = Self::new(oid, proxy_ref);
+        Box::new(Handler::<_, zwp_pointer_gesture_swipe_v1::Dispatcher>::new(gesture))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zwp_pointer_gesture_swipe_v1::Interface for SwipeGesture {
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               _socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        self.proxy.borrow_mut().remove_swipe_gesture_oid(this_object_id);
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwp_pointer_gesture_pinch_v1` object.
+struct PinchGesture {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl PinchGesture {
+    fn new(oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Self {
+        proxy_ref.borrow_mut().add_pinch_gesture_oid(oid);
+        PinchGesture { proxy: proxy_ref }
+    }
+
+    fn new_object(oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Box<wl::server::Object> {
+        let gesture = Self::new(oid, proxy_ref);
+        Box::new(Handler::<_, zwp_pointer_gesture_pinch_v1::Dispatcher>::new(gesture))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zwp_pointer_gesture_pinch_v1::Interface for PinchGesture {
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               _socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        self.proxy.borrow_mut().remove_pinch_gesture_oid(this_object_id);
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,79 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementations of Wayland `wp_presentation` object.
+//!
+//! `wp_presentation_feedback` objects do not handle any requests so no server objects are created
+//! for them. They are stored in `Proxy` and fed with timestamps of page flips in which surfaces
+//! were presented. Timestamps come from DRM which uses monotonic clock.
+
+use nix::libc;
+
+use skylane as wl;
+use skylane_protocols::server::Handler;
+use skylane_protocols::server::presentation_time::wp_presentation;
+
+use global::Global;
+use facade::Facade;
+use proxy::ProxyRef;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `wp_presentation` object.
+struct Presentation {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub fn get_global() -> Global {
+    Global::new(wp_presentation::NAME,
+                wp_presentation::VERSION,
+                Box::new(Presentation::new_object))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Presentation {
+    /// Creates new `Presentation` and sends clock ID.
+    fn new(oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Self {
+        {
+            let proxy = proxy_ref.borrow();
+            let socket = proxy.get_socket();
+            send!(wp_presentation::clock_id(&socket, oid, libc::CLOCK_MONOTONIC as u32));
+        }
+
+        Presentation { proxy: proxy_ref }
+    }
+
+    fn new_object(oid: wl::common::ObjectId,
+                  _version: u32,
+                  proxy_ref: ProxyRef)
+                  -> Box<wl::server::Object> {
+        let presentation = Self::new(oid, proxy_ref);
+        Box::new(Handler::<_, wp_presentation::Dispatcher>::new(presentation))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl wp_presentation::Interface for Presentation {
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               _socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+
+    fn feedback(&mut self,
+                _this_object_id: wl::common::ObjectId,
+                _socket: &mut wl::server::ClientSocket,
+                surface: wl::common::ObjectId,
+                callback: wl::common::ObjectId)
+                -> wl::server::Task {
+        self.proxy.borrow_mut().add_presentation_feedback(surface, callback);
+        wl::server::Task::None
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -8,7 +8,9 @@ use skylane_protocols::server::Handler;
 use skylane_protocols::server::wayland::wl_display;
 use skylane_protocols::server::wayland::wl_registry;
 
+use facade::Facade;
 use proxy::ProxyRef;
+use version;
 
 // -------------------------------------------------------------------------------------------------
 
@@ -20,14 +22,20 @@ pub struct Registry {
 // -------------------------------------------------------------------------------------------------
 
 impl Registry {
-    /// Creates new `Registry` and posts curretn globals.
+    /// Creates new `Registry` and posts current globals. Globals added or removed later are
+    /// announced by `Proxy`.
     fn new(oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Self {
         {
-            let proxy = proxy_ref.borrow();
+            let mut proxy = proxy_ref.borrow_mut();
             let socket = proxy.get_socket();
-            for (name, global) in proxy.get_globals() {
-                send!(wl_registry::global(&socket, oid, *name, global.interface, global.version));
+            for global in proxy.get_globals() {
+                send!(wl_registry::global(&socket,
+                                          oid,
+                                          global.name,
+                                          global.interface,
+                                          global.version));
             }
+            proxy.add_registry_oid(oid);
         }
 
         Registry { proxy: proxy_ref }
@@ -49,32 +57,29 @@ impl wl_registry::Interface for Registry {
             version: u32,
             new_object_id: wl::common::ObjectId)
             -> wl::server::Task {
-        let proxy = self.proxy.borrow();
-        match {
-            if let Some(global) = proxy.get_globals().get(&name) {
+        // Proxy must not be borrowed while constructing the object as constructors may modify it.
+        // Globals removed recently may still be bound as client could not know about removal.
+        let result = {
+            let proxy = self.proxy.borrow();
+            if let Some(global) = proxy.find_global(name) {
                 if global.interface != interface {
                     Err(format!("Interface names do not match. Expected '{}', received: '{}'.",
                                 global.interface,
                                 interface))
-                } else if version == 0 {
-                    Err(format!("Invalid version for global '{}': 0 is not valid version.",
-                                interface))
-                } else if global.version < version {
-                    Err(format!("Invalid version for global '{}': server has: {}, client wanted: {}.",
-                                interface,
-                                global.version,
-                                version))
                 } else {
-                    Ok(global)
+                    version::check_bind(&interface, global.version, version).map(|_| global)
                 }
             } else {
                 Err(format!("Requested for not registered global '{}' ({})",
                             interface,
                             name))
             }
-        } {
+        };
+
+        match result {
             Ok(global) => {
-                let object = global.construct(new_object_id, self.proxy.clone());
+                self.proxy.borrow_mut().set_object_version(new_object_id, version);
+                let object = global.construct(new_object_id, version, self.proxy.clone());
                 wl::server::Task::Create {
                     id: new_object_id,
                     object: object,
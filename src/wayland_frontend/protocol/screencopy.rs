@@ -0,0 +1,164 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementations of Wayland `zwlr_screencopy_manager_v1` and `zwlr_screencopy_frame_v1` objects.
+//!
+//! These objects let screenshot and screen recording tools copy contents of outputs to their own
+//! shared memory buffers. The global is privileged and advertised only to clients allowed by
+//! security policy. Cursor is always drawn on captured frames, so `overlay_cursor` argument is
+//! ignored.
+
+use skylane as wl;
+use skylane_protocols::server::Handler;
+use skylane_protocols::server::wlr_screencopy_unstable_v1::zwlr_screencopy_manager_v1;
+use skylane_protocols::server::wlr_screencopy_unstable_v1::zwlr_screencopy_frame_v1;
+
+use qualia::{Area, Position, Size};
+
+use global::Global;
+use facade::Facade;
+use proxy::ProxyRef;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwlr_screencopy_manager_v1` object.
+struct ScreencopyManager {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub fn get_global() -> Global {
+    Global::new_privileged(zwlr_screencopy_manager_v1::NAME,
+                           zwlr_screencopy_manager_v1::VERSION,
+                           Box::new(ScreencopyManager::new_object))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl ScreencopyManager {
+    /// Creates new `ScreencopyManager`.
+    fn new(_oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Self {
+        ScreencopyManager { proxy: proxy_ref }
+    }
+
+    fn new_object(oid: wl::common::ObjectId,
+                  _version: u32,
+                  proxy_ref: ProxyRef)
+                  -> Box<wl::server::Object> {
+        Box::new(Handler::<_, zwlr_screencopy_manager_v1::Dispatcher>::new(Self::new(oid,
+                                                                                     proxy_ref)))
+    }
+
+    /// Creates new frame capturing given region of output or whole output if region is not given.
+    fn create_frame(&mut self,
+                    this_object_id: wl::common::ObjectId,
+                    frame: wl::common::ObjectId,
+                    output: wl::common::ObjectId,
+                    region: Option<Area>)
+                    -> wl::server::Task {
+        {
+            let mut proxy = self.proxy.borrow_mut();
+            proxy.inherit_object_version(frame, this_object_id);
+            proxy.add_screencopy_frame(frame, output, region);
+        }
+
+        wl::server::Task::Create {
+            id: frame,
+            object: ScreencopyFrame::new_object(frame, self.proxy.clone()),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+#[allow(unused_variables)]
+impl zwlr_screencopy_manager_v1::Interface for ScreencopyManager {
+    fn capture_output(&mut self,
+                      this_object_id: wl::common::ObjectId,
+                      socket: &mut wl::server::ClientSocket,
+                      frame: wl::common::ObjectId,
+                      overlay_cursor: i32,
+                      output: wl::common::ObjectId)
+                      -> wl::server::Task {
+        self.create_frame(this_object_id, frame, output, None)
+    }
+
+    fn capture_output_region(&mut self,
+                             this_object_id: wl::common::ObjectId,
+                             socket: &mut wl::server::ClientSocket,
+                             frame: wl::common::ObjectId,
+                             overlay_cursor: i32,
+                             output: wl::common::ObjectId,
+                             x: i32,
+                             y: i32,
+                             width: i32,
+                             height: i32)
+                             -> wl::server::Task {
+        let region = Area::new(Position::new(x as isize, y as isize),
+                               Size::new(width.max(0) as usize, height.max(0) as usize));
+        self.create_frame(this_object_id, frame, output, Some(region))
+    }
+
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwlr_screencopy_frame_v1` object.
+struct ScreencopyFrame {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl ScreencopyFrame {
+    /// Creates new `ScreencopyFrame`. Buffer parameters are advertised by `Proxy` when adding the
+    /// frame.
+    fn new(_oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Self {
+        ScreencopyFrame { proxy: proxy_ref }
+    }
+
+    fn new_object(oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Box<wl::server::Object> {
+        Box::new(Handler::<_, zwlr_screencopy_frame_v1::Dispatcher>::new(Self::new(oid,
+                                                                                   proxy_ref)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+#[allow(unused_variables)]
+impl zwlr_screencopy_frame_v1::Interface for ScreencopyFrame {
+    fn copy(&mut self,
+            this_object_id: wl::common::ObjectId,
+            socket: &mut wl::server::ClientSocket,
+            buffer: wl::common::ObjectId)
+            -> wl::server::Task {
+        self.proxy.borrow_mut().copy_screencopy_frame(this_object_id, buffer, false);
+        wl::server::Task::None
+    }
+
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        self.proxy.borrow_mut().remove_screencopy_frame(this_object_id);
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+
+    fn copy_with_damage(&mut self,
+                        this_object_id: wl::common::ObjectId,
+                        socket: &mut wl::server::ClientSocket,
+                        buffer: wl::common::ObjectId)
+                        -> wl::server::Task {
+        self.proxy.borrow_mut().copy_screencopy_frame(this_object_id, buffer, true);
+        wl::server::Task::None
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
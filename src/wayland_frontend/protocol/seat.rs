@@ -2,6 +2,10 @@
 // the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
 
 //! Implementations of Wayland `wl_seat` and `wl_keyboard` objects.
+//!
+//! Every seat has its own `wl_seat` global. Pointer and touch are shared by all seats so only the
+//! default seat advertises them. Capabilities reflect input devices currently present in the seat
+//! and are sent again whenever devices appear or disappear.
 
 use skylane as wl;
 use skylane_protocols::server::Handler;
@@ -10,40 +14,84 @@ use skylane_protocols::server::wayland::wl_pointer;
 use skylane_protocols::server::wayland::wl_keyboard;
 use skylane_protocols::server::wayland::wl_touch;
 
+use qualia::SeatId;
+use qualia::seat_capability::{self, SeatCapabilities};
+
 use global::Global;
 use facade::Facade;
 use proxy::ProxyRef;
+use version;
+
 
 // -------------------------------------------------------------------------------------------------
 
 /// Wayland `wl_seat` object.
 struct Seat {
+    seat: SeatId,
     proxy: ProxyRef,
 }
 
 // -------------------------------------------------------------------------------------------------
 
-pub fn get_global() -> Global {
-    Global::new(wl_seat::NAME, wl_seat::VERSION, Box::new(Seat::new_object))
+pub fn get_global(seat: SeatId, name: String) -> Global {
+    Global::new(wl_seat::NAME,
+                wl_seat::VERSION,
+                Box::new(move |oid, version, proxy| {
+                             Seat::new_object(oid, version, seat, &name, proxy)
+                         }))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Sends `capabilities` event to `wl_seat` object with given ID.
+pub fn send_capabilities(socket: &wl::server::ClientSocket,
+                         oid: wl::common::ObjectId,
+                         capabilities: SeatCapabilities) {
+    let mut caps = 0;
+    if capabilities.contains(seat_capability::POINTER) {
+        caps |= wl_seat::capability::POINTER;
+    }
+    if capabilities.contains(seat_capability::KEYBOARD) {
+        caps |= wl_seat::capability::KEYBOARD;
+    }
+    if capabilities.contains(seat_capability::TOUCH) {
+        caps |= wl_seat::capability::TOUCH;
+    }
+    send!(wl_seat::capabilities(socket, oid, caps));
 }
 
 // -------------------------------------------------------------------------------------------------
 
 impl Seat {
-    fn new(oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Self {
+    fn new(oid: wl::common::ObjectId,
+           version: u32,
+           seat: SeatId,
+           name: &str,
+           proxy_ref: ProxyRef)
+           -> Self {
         {
-            // TODO: Add more capabilities
-            let proxy = proxy_ref.borrow();
+            let mut proxy = proxy_ref.borrow_mut();
             let socket = proxy.get_socket();
-            let caps = wl_seat::capability::POINTER | wl_seat::capability::KEYBOARD;
-            send!(wl_seat::capabilities(&socket, oid, caps));
-            send!(wl_seat::name(&socket, oid, "seat0"));
+            proxy.add_seat_oid(oid, seat);
+            send_capabilities(&socket, oid, proxy.get_seat_capabilities(seat));
+            if version >= version::WL_SEAT_NAME_SINCE {
+                send!(wl_seat::name(&socket, oid, name));
+            }
+        }
+        Seat {
+            seat: seat,
+            proxy: proxy_ref,
         }
-        Seat { proxy: proxy_ref }
     }
 
-    fn new_object(oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Box<wl::server::Object> {
-        Box::new(Handler::<_, wl_seat::Dispatcher>::new(Self::new(oid, proxy_ref)))
+    fn new_object(oid: wl::common::ObjectId,
+                  version: u32,
+                  seat: SeatId,
+                  name: &str,
+                  proxy_ref: ProxyRef)
+                  -> Box<wl::server::Object> {
+        let seat = Self::new(oid, version, seat, name, proxy_ref);
+        Box::new(Handler::<_, wl_seat::Dispatcher>::new(seat))
     }
 }
 
@@ -51,10 +99,11 @@ impl Seat {
 
 impl wl_seat::Interface for Seat {
     fn get_pointer(&mut self,
-                   _this_object_id: wl::common::ObjectId,
+                   this_object_id: wl::common::ObjectId,
                    _socket: &mut wl::server::ClientSocket,
                    new_pointer_id: wl::common::ObjectId)
                    -> wl::server::Task {
+        self.proxy.borrow_mut().inherit_object_version(new_pointer_id, this_object_id);
         wl::server::Task::Create {
             id: new_pointer_id,
             object: Pointer::new_object(new_pointer_id, self.proxy.clone()),
@@ -62,21 +111,23 @@ impl wl_seat::Interface for Seat {
     }
 
     fn get_keyboard(&mut self,
-                    _this_object_id: wl::common::ObjectId,
+                    this_object_id: wl::common::ObjectId,
                     _socket: &mut wl::server::ClientSocket,
                     new_keyboard_id: wl::common::ObjectId)
                     -> wl::server::Task {
+        self.proxy.borrow_mut().inherit_object_version(new_keyboard_id, this_object_id);
         wl::server::Task::Create {
             id: new_keyboard_id,
-            object: Keyboard::new_object(new_keyboard_id, self.proxy.clone()),
+            object: Keyboard::new_object(new_keyboard_id, self.seat, self.proxy.clone()),
         }
     }
 
     fn get_touch(&mut self,
-                 _this_object_id: wl::common::ObjectId,
+                 this_object_id: wl::common::ObjectId,
                  _socket: &mut wl::server::ClientSocket,
                  new_touch_id: wl::common::ObjectId)
                  -> wl::server::Task {
+        self.proxy.borrow_mut().inherit_object_version(new_touch_id, this_object_id);
         wl::server::Task::Create {
             id: new_touch_id,
             object: Touch::new_object(new_touch_id, self.proxy.clone()),
@@ -87,6 +138,7 @@ impl wl_seat::Interface for Seat {
                this_object_id: wl::common::ObjectId,
                _socket: &mut wl::server::ClientSocket)
                -> wl::server::Task {
+        self.proxy.borrow_mut().remove_seat_oid(this_object_id);
         wl::server::Task::Destroy { id: this_object_id }
     }
 }
@@ -145,20 +197,33 @@ struct Keyboard {
 // -------------------------------------------------------------------------------------------------
 
 impl Keyboard {
-    fn new(oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Self {
+    fn new(oid: wl::common::ObjectId, seat: SeatId, proxy_ref: ProxyRef) -> Self {
         {
             let mut proxy = proxy_ref.borrow_mut();
             let socket = proxy.get_socket();
-            let keymap = proxy.get_settings().get_keymap();
-            proxy.add_keyboard_oid(oid);
+            let settings = proxy.get_settings();
+            let keymap = settings.get_keymap();
+            proxy.add_keyboard_oid(oid, seat);
             send!(wl_keyboard::keymap(&socket, oid, keymap.format, keymap.fd, keymap.size as u32));
+
+            if proxy.get_object_version(oid) >= version::WL_KEYBOARD_REPEAT_INFO_SINCE {
+                let repeat = settings.get_key_repeat();
+                send!(wl_keyboard::repeat_info(&socket,
+                                               oid,
+                                               repeat.rate as i32,
+                                               repeat.delay as i32));
+            }
         }
 
         Keyboard { proxy: proxy_ref }
     }
 
-    fn new_object(oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Box<wl::server::Object> {
-        Box::new(Handler::<_, wl_keyboard::Dispatcher>::new(Self::new(oid, proxy_ref)))
+    fn new_object(oid: wl::common::ObjectId,
+                  seat: SeatId,
+                  proxy_ref: ProxyRef)
+                  -> Box<wl::server::Object> {
+        let keyboard = Self::new(oid, seat, proxy_ref);
+        Box::new(Handler::<_, wl_keyboard::Dispatcher>::new(keyboard))
     }
 }
 
@@ -177,7 +242,6 @@ impl wl_keyboard::Interface for Keyboard {
 // -------------------------------------------------------------------------------------------------
 
 /// Wayland `wl_touch` object.
-#[allow(dead_code)]
 struct Touch {
     proxy: ProxyRef,
 }
@@ -185,12 +249,13 @@ struct Touch {
 // -------------------------------------------------------------------------------------------------
 
 impl Touch {
-    fn new(proxy_ref: ProxyRef) -> Self {
+    fn new(oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Self {
+        proxy_ref.borrow_mut().add_touch_oid(oid);
         Touch { proxy: proxy_ref }
     }
 
-    fn new_object(_oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Box<wl::server::Object> {
-        Box::new(Handler::<_, wl_touch::Dispatcher>::new(Self::new(proxy_ref)))
+    fn new_object(oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Box<wl::server::Object> {
+        Box::new(Handler::<_, wl_touch::Dispatcher>::new(Self::new(oid, proxy_ref)))
     }
 }
 
@@ -201,6 +266,7 @@ impl wl_touch::Interface for Touch {
                this_object_id: wl::common::ObjectId,
                _socket: &mut wl::server::ClientSocket)
                -> wl::server::Task {
+        self.proxy.borrow_mut().remove_touch_oid(this_object_id);
         wl::server::Task::Destroy { id: this_object_id }
     }
 }
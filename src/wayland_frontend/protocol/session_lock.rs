@@ -0,0 +1,188 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementations of Wayland `ext_session_lock_manager_v1`, `ext_session_lock_v1` and
+//! `ext_session_lock_surface_v1` objects.
+//!
+//! While session is locked only lock surfaces are drawn and receive input. Session stays locked
+//! until client holding the lock unlocks it, even if the client crashes.
+
+use skylane as wl;
+use skylane_protocols::server::Handler;
+use skylane_protocols::server::wayland::wl_display;
+use skylane_protocols::server::ext_session_lock_v1::ext_session_lock_manager_v1;
+use skylane_protocols::server::ext_session_lock_v1::ext_session_lock_v1;
+use skylane_protocols::server::ext_session_lock_v1::ext_session_lock_surface_v1;
+
+use global::Global;
+use facade::Facade;
+use proxy::ProxyRef;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `ext_session_lock_manager_v1` object.
+struct SessionLockManager {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub fn get_global() -> Global {
+    Global::new(ext_session_lock_manager_v1::NAME,
+                ext_session_lock_manager_v1::VERSION,
+                Box::new(SessionLockManager::new_object))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl SessionLockManager {
+    /// Creates new `SessionLockManager`.
+    fn new(_oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Self {
+        SessionLockManager { proxy: proxy_ref }
+    }
+
+    fn new_object(oid: wl::common::ObjectId,
+                  _version: u32,
+                  proxy_ref: ProxyRef)
+                  -> Box<wl::server::Object> {
+        let manager = Self::new(oid, proxy_ref);
+        Box::new(Handler::<_, ext_session_lock_manager_v1::Dispatcher>::new(manager))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl ext_session_lock_manager_v1::Interface for SessionLockManager {
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               _socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+
+    fn lock(&mut self,
+            _this_object_id: wl::common::ObjectId,
+            _socket: &mut wl::server::ClientSocket,
+            id: wl::common::ObjectId)
+            -> wl::server::Task {
+        wl::server::Task::Create {
+            id: id,
+            object: SessionLock::new_object(id, self.proxy.clone()),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `ext_session_lock_v1` object.
+struct SessionLock {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl SessionLock {
+    fn new(oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Self {
+        proxy_ref.borrow_mut().lock_session(oid);
+        SessionLock { proxy: proxy_ref }
+    }
+
+    fn new_object(oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Box<wl::server::Object> {
+        Box::new(Handler::<_, ext_session_lock_v1::Dispatcher>::new(Self::new(oid, proxy_ref)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl ext_session_lock_v1::Interface for SessionLock {
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               _socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        self.proxy.borrow_mut().destroy_session_lock(this_object_id);
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+
+    fn get_lock_surface(&mut self,
+                        _this_object_id: wl::common::ObjectId,
+                        _socket: &mut wl::server::ClientSocket,
+                        id: wl::common::ObjectId,
+                        surface: wl::common::ObjectId,
+                        output: wl::common::ObjectId)
+                        -> wl::server::Task {
+        wl::server::Task::Create {
+            id: id,
+            object: SessionLockSurface::new_object(id, surface, output, self.proxy.clone()),
+        }
+    }
+
+    fn unlock_and_destroy(&mut self,
+                          this_object_id: wl::common::ObjectId,
+                          _socket: &mut wl::server::ClientSocket)
+                          -> wl::server::Task {
+        self.proxy.borrow_mut().unlock_session(this_object_id);
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `ext_session_lock_surface_v1` object.
+struct SessionLockSurface {
+    proxy: ProxyRef,
+    surface_oid: wl::common::ObjectId,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl SessionLockSurface {
+    fn new(oid: wl::common::ObjectId,
+           surface_oid: wl::common::ObjectId,
+           output_oid: wl::common::ObjectId,
+           proxy_ref: ProxyRef)
+           -> Self {
+        proxy_ref.borrow_mut().add_lock_surface(oid, surface_oid, output_oid);
+        SessionLockSurface {
+            proxy: proxy_ref,
+            surface_oid: surface_oid,
+        }
+    }
+
+    fn new_object(oid: wl::common::ObjectId,
+                  surface_oid: wl::common::ObjectId,
+                  output_oid: wl::common::ObjectId,
+                  proxy_ref: ProxyRef)
+                  -> Box<wl::server::Object> {
+        let surface = Self::new(oid, surface_oid, output_oid, proxy_ref);
+        Box::new(Handler::<_, ext_session_lock_surface_v1::Dispatcher>::new(surface))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl ext_session_lock_surface_v1::Interface for SessionLockSurface {
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               _socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        self.proxy.borrow_mut().remove_lock_surface(self.surface_oid);
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+
+    fn ack_configure(&mut self,
+                     this_object_id: wl::common::ObjectId,
+                     socket: &mut wl::server::ClientSocket,
+                     serial: u32)
+                     -> wl::server::Task {
+        if !self.proxy.borrow_mut().ack_configure(self.surface_oid, serial) {
+            send!(wl_display::error(socket,
+                                    wl::common::DISPLAY_ID,
+                                    this_object_id,
+                                    ext_session_lock_surface_v1::error::INVALID_SERIAL,
+                                    &format!("Serial {} was not sent in configure", serial)));
+        }
+        wl::server::Task::None
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
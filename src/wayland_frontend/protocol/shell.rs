@@ -42,7 +42,10 @@ impl Shell {
         Shell { proxy: proxy_ref }
     }
 
-    fn new_object(_oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Box<wl::server::Object> {
+    fn new_object(_oid: wl::common::ObjectId,
+                  _version: u32,
+                  proxy_ref: ProxyRef)
+                  -> Box<wl::server::Object> {
         Box::new(Handler::<_, wl_shell::Dispatcher>::new(Self::new(proxy_ref)))
     }
 }
@@ -115,6 +118,7 @@ impl wl_shell_surface::Interface for Surface {
              seat: wl::common::ObjectId,
              serial: u32)
              -> wl::server::Task {
+        self.proxy.borrow_mut().request_move(self.surface_oid);
         wl::server::Task::None
     }
 
@@ -143,6 +147,10 @@ impl wl_shell_surface::Interface for Surface {
         self.surface_type = SurfaceType::Toplevel;
 
         proxy.show(self.surface_oid, ShellSurfaceOid::Shell(this_object_id), show_reason::IN_SHELL);
+
+        // Setting surface as toplevel ends fullscreen and maximized state.
+        proxy.request_fullscreen(self.surface_oid, false, wl::common::ObjectId::new(0));
+        proxy.request_maximized(self.surface_oid, false);
         wl::server::Task::None
     }
 
@@ -177,6 +185,17 @@ impl wl_shell_surface::Interface for Surface {
                       framerate: u32,
                       output: wl::common::ObjectId)
                       -> wl::server::Task {
+        let mut proxy = self.proxy.borrow_mut();
+
+        // Fullscreen surface is toplevel covering whole output.
+        match self.surface_type {
+            SurfaceType::Popup => proxy.unrelate(self.surface_oid),
+            _ => {}
+        }
+        self.surface_type = SurfaceType::Toplevel;
+
+        proxy.show(self.surface_oid, ShellSurfaceOid::Shell(this_object_id), show_reason::IN_SHELL);
+        proxy.request_fullscreen(self.surface_oid, true, output);
         wl::server::Task::None
     }
 
@@ -209,6 +228,17 @@ impl wl_shell_surface::Interface for Surface {
                      socket: &mut wl::server::ClientSocket,
                      output: wl::common::ObjectId)
                      -> wl::server::Task {
+        let mut proxy = self.proxy.borrow_mut();
+
+        // Maximized surface is toplevel filling its place in layout.
+        match self.surface_type {
+            SurfaceType::Popup => proxy.unrelate(self.surface_oid),
+            _ => {}
+        }
+        self.surface_type = SurfaceType::Toplevel;
+
+        proxy.show(self.surface_oid, ShellSurfaceOid::Shell(this_object_id), show_reason::IN_SHELL);
+        proxy.request_maximized(self.surface_oid, true);
         wl::server::Task::None
     }
 
@@ -12,7 +12,7 @@ use skylane_protocols::server::wayland::wl_shm;
 use skylane_protocols::server::wayland::wl_shm_pool;
 use skylane_protocols::server::wayland::wl_buffer;
 
-use qualia::{MappedMemory, MemoryPoolId, MemoryViewId};
+use qualia::{AlphaMode, MappedMemory, MemoryPoolId, MemoryViewId, PixelFormat};
 
 use global::Global;
 use proxy::ProxyRef;
@@ -42,6 +42,7 @@ impl Shm {
             let mut socket = proxy_ref.borrow().get_socket();
             send!(wl_shm::format(&mut socket, oid, wl_shm::format::XRGB8888));
             send!(wl_shm::format(&mut socket, oid, wl_shm::format::ARGB8888));
+            send!(wl_shm::format(&mut socket, oid, wl_shm::format::RGB565));
         }
 
         Shm {
@@ -50,7 +51,10 @@ impl Shm {
         }
     }
 
-    fn new_object(oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Box<wl::server::Object> {
+    fn new_object(oid: wl::common::ObjectId,
+                  _version: u32,
+                  proxy_ref: ProxyRef)
+                  -> Box<wl::server::Object> {
         Box::new(Handler::<_, wl_shm::Dispatcher>::new(Self::new(oid, proxy_ref)))
     }
 }
@@ -129,13 +133,30 @@ impl wl_shm_pool::Interface for ShmPool {
                      stride: i32,
                      format: u32)
                      -> wl::server::Task {
+        // Padding byte of XRGB8888 is undefined so such buffers have to be treated as opaque.
+        let (format, alpha_mode) = match format {
+            wl_shm::format::ARGB8888 => (PixelFormat::Argb8888, AlphaMode::Premultiplied),
+            wl_shm::format::XRGB8888 => (PixelFormat::Xrgb8888, AlphaMode::Opaque),
+            wl_shm::format::RGB565 => (PixelFormat::Rgb565, AlphaMode::Opaque),
+            _ => {
+                send!(wl_display::error(socket,
+                                        wl::common::DISPLAY_ID,
+                                        this_object_id,
+                                        wl_shm::error::INVALID_FORMAT,
+                                        &format!("Format {:#x} is not supported", format)));
+                return wl::server::Task::None;
+            }
+        };
+
         let mut proxy = self.proxy.borrow_mut();
         if let Some(mvid) = proxy.create_memory_view(self.mpid,
                                                      new_buffer_id,
                                                      offset as usize,
                                                      width as usize,
                                                      height as usize,
-                                                     stride as usize) {
+                                                     stride as usize,
+                                                     format,
+                                                     alpha_mode) {
             let buffer = ShmBuffer::new_object(self.proxy.clone(), mvid);
             wl::server::Task::Create {
                 id: new_buffer_id,
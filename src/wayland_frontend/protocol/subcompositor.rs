@@ -36,7 +36,10 @@ impl Subcompositor {
         Subcompositor { proxy: proxy_ref }
     }
 
-    fn new_object(_oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Box<wl::server::Object> {
+    fn new_object(_oid: wl::common::ObjectId,
+                  _version: u32,
+                  proxy_ref: ProxyRef)
+                  -> Box<wl::server::Object> {
         Box::new(Handler::<_, wl_subcompositor::Dispatcher>::new(Self::new(proxy_ref)))
     }
 }
@@ -0,0 +1,172 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementations of Wayland `zxdg_decoration_manager_v1` and `zxdg_toplevel_decoration_v1`
+//! objects.
+//!
+//! These objects let clients negotiate if decorations of their toplevel surfaces are drawn by
+//! client or by compositor. The final decision belongs to compositor and is announced to client in
+//! `configure` event followed by reconfiguration of the surface.
+
+use skylane as wl;
+use skylane_protocols::server::Handler;
+use skylane_protocols::server::wayland::wl_display;
+use skylane_protocols::server::xdg_decoration_unstable_v1::zxdg_decoration_manager_v1;
+use skylane_protocols::server::xdg_decoration_unstable_v1::zxdg_toplevel_decoration_v1;
+
+use qualia::DecorationMode;
+
+use global::Global;
+use facade::Facade;
+use proxy::ProxyRef;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zxdg_decoration_manager_v1` object.
+struct DecorationManager {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub fn get_global() -> Global {
+    Global::new(zxdg_decoration_manager_v1::NAME,
+                zxdg_decoration_manager_v1::VERSION,
+                Box::new(DecorationManager::new_object))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl DecorationManager {
+    /// Creates new `DecorationManager`.
+    fn new(_oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Self {
+        DecorationManager { proxy: proxy_ref }
+    }
+
+    fn new_object(oid: wl::common::ObjectId,
+                  _version: u32,
+                  proxy_ref: ProxyRef)
+                  -> Box<wl::server::Object> {
+        Box::new(Handler::<_, zxdg_decoration_manager_v1::Dispatcher>::new(Self::new(oid,
+                                                                                     proxy_ref)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+#[allow(unused_variables)]
+impl zxdg_decoration_manager_v1::Interface for DecorationManager {
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+
+    fn get_toplevel_decoration(&mut self,
+                               this_object_id: wl::common::ObjectId,
+                               socket: &mut wl::server::ClientSocket,
+                               id: wl::common::ObjectId,
+                               toplevel: wl::common::ObjectId)
+                               -> wl::server::Task {
+        let surface_oid = self.proxy.borrow().get_surface_oid_for_toplevel(toplevel);
+        if let Some(surface_oid) = surface_oid {
+            if !self.proxy.borrow_mut().add_decoration_oid(surface_oid, id) {
+                send!(wl_display::error(socket,
+                                        wl::common::DISPLAY_ID,
+                                        this_object_id,
+                                        zxdg_toplevel_decoration_v1::error::ALREADY_CONSTRUCTED,
+                                        "Toplevel already has decoration object"));
+                return wl::server::Task::None;
+            }
+        } else {
+            log_warn3!("Requested decoration for unknown toplevel {}", toplevel);
+        }
+
+        wl::server::Task::Create {
+            id: id,
+            object: ToplevelDecoration::new_object(surface_oid, self.proxy.clone()),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zxdg_toplevel_decoration_v1` object.
+struct ToplevelDecoration {
+    surface_oid: Option<wl::common::ObjectId>,
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl ToplevelDecoration {
+    /// Creates new `ToplevelDecoration`. Client did not express its preference yet so compositor
+    /// is asked to choose the mode.
+    fn new(surface_oid: Option<wl::common::ObjectId>, proxy_ref: ProxyRef) -> Self {
+        if let Some(surface_oid) = surface_oid {
+            proxy_ref.borrow().request_decoration_mode(surface_oid, None);
+        }
+
+        ToplevelDecoration {
+            surface_oid: surface_oid,
+            proxy: proxy_ref,
+        }
+    }
+
+    fn new_object(surface_oid: Option<wl::common::ObjectId>,
+                  proxy_ref: ProxyRef)
+                  -> Box<wl::server::Object> {
+        let decoration = Self::new(surface_oid, proxy_ref);
+        Box::new(Handler::<_, zxdg_toplevel_decoration_v1::Dispatcher>::new(decoration))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+#[allow(unused_variables)]
+impl zxdg_toplevel_decoration_v1::Interface for ToplevelDecoration {
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        if let Some(surface_oid) = self.surface_oid {
+            let mut proxy = self.proxy.borrow_mut();
+            proxy.remove_decoration_oid(surface_oid);
+            proxy.request_decoration_mode(surface_oid, Some(DecorationMode::ClientSide));
+        }
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+
+    fn set_mode(&mut self,
+                this_object_id: wl::common::ObjectId,
+                socket: &mut wl::server::ClientSocket,
+                mode: u32)
+                -> wl::server::Task {
+        let mode = match mode {
+            zxdg_toplevel_decoration_v1::mode::CLIENT_SIDE => Some(DecorationMode::ClientSide),
+            zxdg_toplevel_decoration_v1::mode::SERVER_SIDE => Some(DecorationMode::ServerSide),
+            _ => {
+                log_warn3!("Requested unknown decoration mode {}", mode);
+                None
+            }
+        };
+
+        if let Some(surface_oid) = self.surface_oid {
+            self.proxy.borrow().request_decoration_mode(surface_oid, mode);
+        }
+        wl::server::Task::None
+    }
+
+    fn unset_mode(&mut self,
+                  this_object_id: wl::common::ObjectId,
+                  socket: &mut wl::server::ClientSocket)
+                  -> wl::server::Task {
+        if let Some(surface_oid) = self.surface_oid {
+            self.proxy.borrow().request_decoration_mode(surface_oid, None);
+        }
+        wl::server::Task::None
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,167 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementations of Wayland `zxdg_output_manager_v1` and `zxdg_output_v1` objects.
+//!
+//! These objects describe outputs in logical coordinates (i.e. after applying scale and transform)
+//! used by tools placing windows or mapping input devices. Information is sent again when the
+//! output changes.
+
+use skylane as wl;
+use skylane_protocols::server::Handler;
+use skylane_protocols::server::xdg_output_unstable_v1::zxdg_output_manager_v1;
+use skylane_protocols::server::xdg_output_unstable_v1::zxdg_output_v1;
+
+use qualia::OutputInfo;
+
+use global::Global;
+use facade::Facade;
+use proxy::ProxyRef;
+use version;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zxdg_output_manager_v1` object.
+struct XdgOutputManager {
+    version: u32,
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub fn get_global() -> Global {
+    Global::new(zxdg_output_manager_v1::NAME,
+                zxdg_output_manager_v1::VERSION,
+                Box::new(XdgOutputManager::new_object))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Sends logical geometry of output to `zxdg_output_v1` object of given version. Name of the
+/// output must not change so it is sent only if `with_name` is set. Objects since version 3 are
+/// not sent `done` event; it is replaced by `wl_output.done` which has to be sent by the caller.
+pub fn send_info(socket: &wl::server::ClientSocket,
+                 oid: wl::common::ObjectId,
+                 version: u32,
+                 info: &OutputInfo,
+                 with_name: bool) {
+    send!(zxdg_output_v1::logical_position(socket,
+                                           oid,
+                                           info.area.pos.x as i32,
+                                           info.area.pos.y as i32));
+    send!(zxdg_output_v1::logical_size(socket,
+                                       oid,
+                                       info.area.size.width as i32,
+                                       info.area.size.height as i32));
+
+    if version >= version::ZXDG_OUTPUT_NAME_SINCE {
+        if with_name {
+            send!(zxdg_output_v1::name(socket, oid, &info.model));
+        }
+        send!(zxdg_output_v1::description(socket, oid, &get_description(info)));
+    }
+
+    if version < version::ZXDG_OUTPUT_WL_OUTPUT_DONE_SINCE {
+        send!(zxdg_output_v1::done(socket, oid));
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Returns human-readable description of output.
+pub fn get_description(info: &OutputInfo) -> String {
+    let name = if info.make == info.model {
+        info.model.clone()
+    } else {
+        format!("{} {}", info.make, info.model)
+    };
+    format!("{} ({}x{}@{})",
+            name,
+            info.pixel_size.width,
+            info.pixel_size.height,
+            info.refresh_rate)
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl XdgOutputManager {
+    /// Creates new `XdgOutputManager`.
+    fn new(_oid: wl::common::ObjectId, version: u32, proxy_ref: ProxyRef) -> Self {
+        XdgOutputManager {
+            version: version,
+            proxy: proxy_ref,
+        }
+    }
+
+    fn new_object(oid: wl::common::ObjectId,
+                  version: u32,
+                  proxy_ref: ProxyRef)
+                  -> Box<wl::server::Object> {
+        let manager = Self::new(oid, version, proxy_ref);
+        Box::new(Handler::<_, zxdg_output_manager_v1::Dispatcher>::new(manager))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zxdg_output_manager_v1::Interface for XdgOutputManager {
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               _socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+
+    fn get_xdg_output(&mut self,
+                      _this_object_id: wl::common::ObjectId,
+                      _socket: &mut wl::server::ClientSocket,
+                      id: wl::common::ObjectId,
+                      output: wl::common::ObjectId)
+                      -> wl::server::Task {
+        {
+            let mut proxy = self.proxy.borrow_mut();
+            proxy.set_object_version(id, self.version);
+            proxy.add_xdg_output_oid(id, output);
+        }
+
+        wl::server::Task::Create {
+            id: id,
+            object: XdgOutput::new_object(self.proxy.clone()),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zxdg_output_v1` object.
+struct XdgOutput {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl XdgOutput {
+    /// Creates new `XdgOutput`.
+    fn new(proxy_ref: ProxyRef) -> Self {
+        XdgOutput { proxy: proxy_ref }
+    }
+
+    fn new_object(proxy_ref: ProxyRef) -> Box<wl::server::Object> {
+        let xdg_output = Self::new(proxy_ref);
+        Box::new(Handler::<_, zxdg_output_v1::Dispatcher>::new(xdg_output))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zxdg_output_v1::Interface for XdgOutput {
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               _socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        self.proxy.borrow_mut().remove_xdg_output_oid(this_object_id);
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,719 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementations of Wayland `xdg_wm_base`, `xdg_positioner`, `xdg_surface`, `xdg_toplevel` and
+//! `xdg_popup` objects.
+
+use skylane as wl;
+use skylane_protocols::server::Handler;
+use skylane_protocols::server::wayland::wl_display;
+use skylane_protocols::server::xdg_shell::xdg_wm_base;
+use skylane_protocols::server::xdg_shell::xdg_positioner;
+use skylane_protocols::server::xdg_shell::xdg_surface;
+use skylane_protocols::server::xdg_shell::xdg_toplevel;
+use skylane_protocols::server::xdg_shell::xdg_popup;
+
+use qualia::{show_reason, Area, Position, Positioner, Size};
+use qualia::positioner::edges;
+use qualia::positioner::constraint_adjustment::ConstraintAdjustment;
+
+use facade::{Facade, ShellSurfaceOid};
+use global::Global;
+use proxy::ProxyRef;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `xdg_wm_base` object.
+#[allow(dead_code)]
+struct XdgWmBase {
+    oid: wl::common::ObjectId,
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub fn get_global() -> Global {
+    Global::new(xdg_wm_base::NAME, xdg_wm_base::VERSION, Box::new(XdgWmBase::new_object))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl XdgWmBase {
+    fn new(oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Self {
+        XdgWmBase {
+            oid: oid,
+            proxy: proxy_ref,
+        }
+    }
+
+    fn new_object(oid: wl::common::ObjectId,
+                  _version: u32,
+                  proxy_ref: ProxyRef)
+                  -> Box<wl::server::Object> {
+        Box::new(Handler::<_, xdg_wm_base::Dispatcher>::new(Self::new(oid, proxy_ref)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+#[allow(unused_variables)]
+impl xdg_wm_base::Interface for XdgWmBase {
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+
+    fn create_positioner(&mut self,
+                         this_object_id: wl::common::ObjectId,
+                         socket: &mut wl::server::ClientSocket,
+                         new_positioner_oid: wl::common::ObjectId)
+                         -> wl::server::Task {
+        let positioner = XdgPositioner::new_object(new_positioner_oid, self.proxy.clone());
+        wl::server::Task::Create {
+            id: new_positioner_oid,
+            object: positioner,
+        }
+    }
+
+    fn get_xdg_surface(&mut self,
+                       this_object_id: wl::common::ObjectId,
+                       socket: &mut wl::server::ClientSocket,
+                       new_surface_oid: wl::common::ObjectId,
+                       surface: wl::common::ObjectId)
+                       -> wl::server::Task {
+        let surface = XdgSurface::new_object(new_surface_oid, surface, self.proxy.clone());
+        wl::server::Task::Create {
+            id: new_surface_oid,
+            object: surface,
+        }
+    }
+
+    fn pong(&mut self,
+            this_object_id: wl::common::ObjectId,
+            socket: &mut wl::server::ClientSocket,
+            serial: u32)
+            -> wl::server::Task {
+        wl::server::Task::None
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `xdg_positioner` object.
+struct XdgPositioner {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl XdgPositioner {
+    fn new(oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Self {
+        {
+            let mut proxy = proxy_ref.borrow_mut();
+            proxy.set_positioner(oid, Positioner::new());
+        }
+        XdgPositioner { proxy: proxy_ref }
+    }
+
+    fn new_object(oid: wl::common::ObjectId, proxy: ProxyRef) -> Box<wl::server::Object> {
+        Box::new(Handler::<_, xdg_positioner::Dispatcher>::new(Self::new(oid, proxy)))
+    }
+
+    /// Translates value of `anchor` or `gravity` enum (both have the same values) to edges.
+    fn translate_edges(value: u32) -> Option<edges::Edges> {
+        match value {
+            xdg_positioner::anchor::NONE => Some(edges::NONE),
+            xdg_positioner::anchor::TOP => Some(edges::TOP),
+            xdg_positioner::anchor::BOTTOM => Some(edges::BOTTOM),
+            xdg_positioner::anchor::LEFT => Some(edges::LEFT),
+            xdg_positioner::anchor::RIGHT => Some(edges::RIGHT),
+            xdg_positioner::anchor::TOP_LEFT => Some(edges::TOP | edges::LEFT),
+            xdg_positioner::anchor::BOTTOM_LEFT => Some(edges::BOTTOM | edges::LEFT),
+            xdg_positioner::anchor::TOP_RIGHT => Some(edges::TOP | edges::RIGHT),
+            xdg_positioner::anchor::BOTTOM_RIGHT => Some(edges::BOTTOM | edges::RIGHT),
+            _ => None,
+        }
+    }
+
+    /// Sends `invalid_input` error.
+    fn send_invalid_input(this_object_id: wl::common::ObjectId,
+                          socket: &mut wl::server::ClientSocket,
+                          msg: &str) {
+        send!(wl_display::error(socket,
+                                wl::common::DISPLAY_ID,
+                                this_object_id,
+                                xdg_positioner::error::INVALID_INPUT,
+                                &msg.to_owned()));
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+#[allow(unused_variables)]
+impl xdg_positioner::Interface for XdgPositioner {
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        let mut proxy = self.proxy.borrow_mut();
+        proxy.remove_positioner(this_object_id);
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+
+    fn set_size(&mut self,
+                this_object_id: wl::common::ObjectId,
+                socket: &mut wl::server::ClientSocket,
+                width: i32,
+                height: i32)
+                -> wl::server::Task {
+        if (width <= 0) || (height <= 0) {
+            Self::send_invalid_input(this_object_id, socket, "Pop-up size must be positive");
+            return wl::server::Task::None;
+        }
+
+        let mut proxy = self.proxy.borrow_mut();
+        if let Some(mut positioner) = proxy.get_positioner(this_object_id) {
+            positioner.size.width = width as usize;
+            positioner.size.height = height as usize;
+            proxy.set_positioner(this_object_id, positioner);
+        }
+        wl::server::Task::None
+    }
+
+    fn set_anchor_rect(&mut self,
+                       this_object_id: wl::common::ObjectId,
+                       socket: &mut wl::server::ClientSocket,
+                       x: i32,
+                       y: i32,
+                       width: i32,
+                       height: i32)
+                       -> wl::server::Task {
+        if (width < 0) || (height < 0) {
+            Self::send_invalid_input(this_object_id, socket, "Anchor size must not be negative");
+            return wl::server::Task::None;
+        }
+
+        let mut proxy = self.proxy.borrow_mut();
+        if let Some(mut positioner) = proxy.get_positioner(this_object_id) {
+            positioner.anchor_rect.pos.x = x as isize;
+            positioner.anchor_rect.pos.y = y as isize;
+            positioner.anchor_rect.size.width = width as usize;
+            positioner.anchor_rect.size.height = height as usize;
+            proxy.set_positioner(this_object_id, positioner);
+        }
+        wl::server::Task::None
+    }
+
+    fn set_anchor(&mut self,
+                  this_object_id: wl::common::ObjectId,
+                  socket: &mut wl::server::ClientSocket,
+                  anchor: u32)
+                  -> wl::server::Task {
+        let anchor = if let Some(anchor) = Self::translate_edges(anchor) {
+            anchor
+        } else {
+            Self::send_invalid_input(this_object_id, socket, "Unknown anchor");
+            return wl::server::Task::None;
+        };
+
+        let mut proxy = self.proxy.borrow_mut();
+        if let Some(mut positioner) = proxy.get_positioner(this_object_id) {
+            positioner.anchor = anchor;
+            proxy.set_positioner(this_object_id, positioner);
+        }
+        wl::server::Task::None
+    }
+
+    fn set_gravity(&mut self,
+                   this_object_id: wl::common::ObjectId,
+                   socket: &mut wl::server::ClientSocket,
+                   gravity: u32)
+                   -> wl::server::Task {
+        let gravity = if let Some(gravity) = Self::translate_edges(gravity) {
+            gravity
+        } else {
+            Self::send_invalid_input(this_object_id, socket, "Unknown gravity");
+            return wl::server::Task::None;
+        };
+
+        let mut proxy = self.proxy.borrow_mut();
+        if let Some(mut positioner) = proxy.get_positioner(this_object_id) {
+            positioner.gravity = gravity;
+            proxy.set_positioner(this_object_id, positioner);
+        }
+        wl::server::Task::None
+    }
+
+    fn set_constraint_adjustment(&mut self,
+                                 this_object_id: wl::common::ObjectId,
+                                 socket: &mut wl::server::ClientSocket,
+                                 constraint_adjustment: u32)
+                                 -> wl::server::Task {
+        let mut proxy = self.proxy.borrow_mut();
+        if let Some(mut positioner) = proxy.get_positioner(this_object_id) {
+            positioner.constraint_adjustment =
+                ConstraintAdjustment::from_bits_truncate(constraint_adjustment);
+            proxy.set_positioner(this_object_id, positioner);
+        }
+        wl::server::Task::None
+    }
+
+    fn set_offset(&mut self,
+                  this_object_id: wl::common::ObjectId,
+                  _socket: &mut wl::server::ClientSocket,
+                  x: i32,
+                  y: i32)
+                  -> wl::server::Task {
+        let mut proxy = self.proxy.borrow_mut();
+        if let Some(mut positioner) = proxy.get_positioner(this_object_id) {
+            positioner.offset.x = x as isize;
+            positioner.offset.y = y as isize;
+            proxy.set_positioner(this_object_id, positioner);
+        }
+        wl::server::Task::None
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `xdg_surface` object.
+struct XdgSurface {
+    oid: wl::common::ObjectId,
+    surface_oid: wl::common::ObjectId,
+    proxy: ProxyRef,
+
+    /// Tells if role (toplevel or pop-up) was already assigned.
+    constructed: bool,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl XdgSurface {
+    fn new(oid: wl::common::ObjectId,
+           surface_oid: wl::common::ObjectId,
+           proxy_ref: ProxyRef)
+           -> Self {
+        XdgSurface {
+            oid: oid,
+            surface_oid: surface_oid,
+            proxy: proxy_ref,
+            constructed: false,
+        }
+    }
+
+    fn new_object(oid: wl::common::ObjectId,
+                  surface_oid: wl::common::ObjectId,
+                  proxy: ProxyRef)
+                  -> Box<wl::server::Object> {
+        Box::new(Handler::<_, xdg_surface::Dispatcher>::new(Self::new(oid, surface_oid, proxy)))
+    }
+
+    /// Marks role as assigned. Sends protocol error and returns `false` if it already was.
+    fn construct(&mut self,
+                 this_object_id: wl::common::ObjectId,
+                 socket: &mut wl::server::ClientSocket)
+                 -> bool {
+        if self.constructed {
+            send!(wl_display::error(socket,
+                                    wl::common::DISPLAY_ID,
+                                    this_object_id,
+                                    xdg_surface::error::ALREADY_CONSTRUCTED,
+                                    &"Role was already assigned to the surface".to_owned()));
+            false
+        } else {
+            self.constructed = true;
+            true
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+#[allow(unused_variables)]
+impl xdg_surface::Interface for XdgSurface {
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        let mut proxy = self.proxy.borrow_mut();
+        proxy.hide(self.surface_oid, show_reason::IN_SHELL);
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+
+    fn get_toplevel(&mut self,
+                    this_object_id: wl::common::ObjectId,
+                    socket: &mut wl::server::ClientSocket,
+                    new_toplevel_id: wl::common::ObjectId)
+                    -> wl::server::Task {
+        if !self.construct(this_object_id, socket) {
+            return wl::server::Task::None;
+        }
+
+        let toplevel = XdgToplevel::new_object(new_toplevel_id,
+                                               self.surface_oid,
+                                               self.oid,
+                                               self.proxy.clone());
+
+        // Clients do not attach buffers before receiving initial configuration. It is sent when
+        // compositor decides where the surface will be placed so the first buffer already has
+        // proper size.
+        self.proxy.borrow_mut().request_initial_size(self.surface_oid);
+
+        wl::server::Task::Create {
+            id: new_toplevel_id,
+            object: toplevel,
+        }
+    }
+
+    fn get_popup(&mut self,
+                 this_object_id: wl::common::ObjectId,
+                 socket: &mut wl::server::ClientSocket,
+                 new_popup_oid: wl::common::ObjectId,
+                 parent_shell_surface_oid: wl::common::ObjectId,
+                 positioner_oid: wl::common::ObjectId)
+                 -> wl::server::Task {
+        if !self.construct(this_object_id, socket) {
+            return wl::server::Task::None;
+        }
+
+        let positioner = self.proxy.borrow_mut().get_positioner(positioner_oid);
+        let positioner = match positioner {
+            Some(ref positioner) if positioner.is_complete() => *positioner,
+            _ => {
+                send!(wl_display::error(socket,
+                                        wl::common::DISPLAY_ID,
+                                        this_object_id,
+                                        xdg_wm_base::error::INVALID_POSITIONER,
+                                        &"Positioner is incomplete".to_owned()));
+                return wl::server::Task::None;
+            }
+        };
+
+        // Pop-up will be configured when its placement is decided.
+        let popup = XdgPopup::new_object(new_popup_oid,
+                                         self.surface_oid,
+                                         this_object_id,
+                                         parent_shell_surface_oid,
+                                         positioner,
+                                         self.proxy.clone());
+
+        wl::server::Task::Create {
+            id: new_popup_oid,
+            object: popup,
+        }
+    }
+
+    fn set_window_geometry(&mut self,
+                           this_object_id: wl::common::ObjectId,
+                           socket: &mut wl::server::ClientSocket,
+                           x: i32,
+                           y: i32,
+                           width: i32,
+                           height: i32)
+                           -> wl::server::Task {
+        if (width <= 0) || (height <= 0) {
+            send!(wl_display::error(socket,
+                                    wl::common::DISPLAY_ID,
+                                    this_object_id,
+                                    xdg_wm_base::error::INVALID_SURFACE_STATE,
+                                    &"Window geometry must have positive size".to_owned()));
+        } else {
+            let geometry = Area::new(Position::new(x as isize, y as isize),
+                                     Size::new(width as usize, height as usize));
+            self.proxy.borrow().set_window_geometry(self.surface_oid, geometry);
+        }
+        wl::server::Task::None
+    }
+
+    fn ack_configure(&mut self,
+                     this_object_id: wl::common::ObjectId,
+                     socket: &mut wl::server::ClientSocket,
+                     serial: u32)
+                     -> wl::server::Task {
+        if !self.proxy.borrow_mut().ack_configure(self.surface_oid, serial) {
+            send!(wl_display::error(socket,
+                                    wl::common::DISPLAY_ID,
+                                    this_object_id,
+                                    xdg_wm_base::error::INVALID_SURFACE_STATE,
+                                    &format!("Serial {} was not sent in configure", serial)));
+        }
+        wl::server::Task::None
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `xdg_toplevel` object.
+struct XdgToplevel {
+    surface_oid: wl::common::ObjectId,
+    proxy: ProxyRef,
+    min_size: Size,
+    max_size: Size,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl XdgToplevel {
+    fn new(oid: wl::common::ObjectId,
+           surface_oid: wl::common::ObjectId,
+           shell_surface_oid: wl::common::ObjectId,
+           proxy_ref: ProxyRef)
+           -> Self {
+        {
+            let mut proxy = proxy_ref.borrow_mut();
+            proxy.show(surface_oid,
+                       ShellSurfaceOid::XdgToplevel(shell_surface_oid, oid),
+                       show_reason::IN_SHELL);
+        }
+
+        XdgToplevel {
+            surface_oid: surface_oid,
+            proxy: proxy_ref,
+            min_size: Size::default(),
+            max_size: Size::default(),
+        }
+    }
+
+    fn new_object(oid: wl::common::ObjectId,
+                  surface_oid: wl::common::ObjectId,
+                  shell_surface_oid: wl::common::ObjectId,
+                  proxy_ref: ProxyRef)
+                  -> Box<wl::server::Object> {
+        let toplevel = Self::new(oid, surface_oid, shell_surface_oid, proxy_ref);
+        Box::new(Handler::<_, xdg_toplevel::Dispatcher>::new(toplevel))
+    }
+
+    /// Passes minimal and maximal size to the rest of application.
+    fn update_size_limits(&self) {
+        self.proxy.borrow().set_size_limits(self.surface_oid, self.min_size, self.max_size);
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+#[allow(unused_variables)]
+impl xdg_toplevel::Interface for XdgToplevel {
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+
+    fn set_parent(&mut self,
+                  this_object_id: wl::common::ObjectId,
+                  socket: &mut wl::server::ClientSocket,
+                  parent: wl::common::ObjectId)
+                  -> wl::server::Task {
+        wl::server::Task::None
+    }
+
+    fn set_title(&mut self,
+                 this_object_id: wl::common::ObjectId,
+                 socket: &mut wl::server::ClientSocket,
+                 title: String)
+                 -> wl::server::Task {
+        self.proxy.borrow().set_title(self.surface_oid, title);
+        wl::server::Task::None
+    }
+
+    fn set_app_id(&mut self,
+                  this_object_id: wl::common::ObjectId,
+                  socket: &mut wl::server::ClientSocket,
+                  app_id: String)
+                  -> wl::server::Task {
+        self.proxy.borrow().set_app_id(self.surface_oid, app_id);
+        wl::server::Task::None
+    }
+
+    fn show_window_menu(&mut self,
+                        this_object_id: wl::common::ObjectId,
+                        socket: &mut wl::server::ClientSocket,
+                        seat: wl::common::ObjectId,
+                        serial: u32,
+                        x: i32,
+                        y: i32)
+                        -> wl::server::Task {
+        wl::server::Task::None
+    }
+
+    fn move_(&mut self,
+             this_object_id: wl::common::ObjectId,
+             socket: &mut wl::server::ClientSocket,
+             seat: wl::common::ObjectId,
+             serial: u32)
+             -> wl::server::Task {
+        self.proxy.borrow_mut().request_move(self.surface_oid);
+        wl::server::Task::None
+    }
+
+    fn resize(&mut self,
+              this_object_id: wl::common::ObjectId,
+              socket: &mut wl::server::ClientSocket,
+              seat: wl::common::ObjectId,
+              serial: u32,
+              edges: u32)
+              -> wl::server::Task {
+        wl::server::Task::None
+    }
+
+    fn set_max_size(&mut self,
+                    this_object_id: wl::common::ObjectId,
+                    socket: &mut wl::server::ClientSocket,
+                    width: i32,
+                    height: i32)
+                    -> wl::server::Task {
+        if (width < 0) || (height < 0) {
+            send!(wl_display::error(socket,
+                                    wl::common::DISPLAY_ID,
+                                    this_object_id,
+                                    xdg_wm_base::error::INVALID_SURFACE_STATE,
+                                    &"Maximal size must not be negative".to_owned()));
+        } else {
+            self.max_size = Size::new(width as usize, height as usize);
+            self.update_size_limits();
+        }
+        wl::server::Task::None
+    }
+
+    fn set_min_size(&mut self,
+                    this_object_id: wl::common::ObjectId,
+                    socket: &mut wl::server::ClientSocket,
+                    width: i32,
+                    height: i32)
+                    -> wl::server::Task {
+        if (width < 0) || (height < 0) {
+            send!(wl_display::error(socket,
+                                    wl::common::DISPLAY_ID,
+                                    this_object_id,
+                                    xdg_wm_base::error::INVALID_SURFACE_STATE,
+                                    &"Minimal size must not be negative".to_owned()));
+        } else {
+            self.min_size = Size::new(width as usize, height as usize);
+            self.update_size_limits();
+        }
+        wl::server::Task::None
+    }
+
+    fn set_maximized(&mut self,
+                     this_object_id: wl::common::ObjectId,
+                     socket: &mut wl::server::ClientSocket)
+                     -> wl::server::Task {
+        self.proxy.borrow_mut().request_maximized(self.surface_oid, true);
+        wl::server::Task::None
+    }
+
+    fn unset_maximized(&mut self,
+                       this_object_id: wl::common::ObjectId,
+                       socket: &mut wl::server::ClientSocket)
+                       -> wl::server::Task {
+        self.proxy.borrow_mut().request_maximized(self.surface_oid, false);
+        wl::server::Task::None
+    }
+
+    fn set_fullscreen(&mut self,
+                      this_object_id: wl::common::ObjectId,
+                      socket: &mut wl::server::ClientSocket,
+                      output: wl::common::ObjectId)
+                      -> wl::server::Task {
+        self.proxy.borrow_mut().request_fullscreen(self.surface_oid, true, output);
+        wl::server::Task::None
+    }
+
+    fn unset_fullscreen(&mut self,
+                        this_object_id: wl::common::ObjectId,
+                        socket: &mut wl::server::ClientSocket)
+                        -> wl::server::Task {
+        let null = wl::common::ObjectId::new(0);
+        self.proxy.borrow_mut().request_fullscreen(self.surface_oid, false, null);
+        wl::server::Task::None
+    }
+
+    fn set_minimized(&mut self,
+                     this_object_id: wl::common::ObjectId,
+                     socket: &mut wl::server::ClientSocket)
+                     -> wl::server::Task {
+        wl::server::Task::None
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `xdg_popup` object.
+struct XdgPopup {
+    surface_oid: wl::common::ObjectId,
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl XdgPopup {
+    fn new(oid: wl::common::ObjectId,
+           surface_oid: wl::common::ObjectId,
+           shell_surface_oid: wl::common::ObjectId,
+           parent_shell_surface_oid: wl::common::ObjectId,
+           positioner: Positioner,
+           proxy_ref: ProxyRef)
+           -> Self {
+        {
+            let mut proxy = proxy_ref.borrow_mut();
+            let parent_surface_oid = proxy.get_surface_oid_for_shell(parent_shell_surface_oid);
+            if let Some(parent_surface_oid) = parent_surface_oid {
+                let area = positioner.get_area();
+                proxy.relate(surface_oid, parent_surface_oid);
+                proxy.set_relative_position(surface_oid, area.pos.x, area.pos.y);
+            }
+            proxy.place_popup(surface_oid,
+                              ShellSurfaceOid::XdgPopup(shell_surface_oid, oid),
+                              positioner);
+        }
+
+        XdgPopup {
+            surface_oid: surface_oid,
+            proxy: proxy_ref,
+        }
+    }
+
+    fn new_object(oid: wl::common::ObjectId,
+                  surface_oid: wl::common::ObjectId,
+                  shell_surface_oid: wl::common::ObjectId,
+                  parent_shell_surface_oid: wl::common::ObjectId,
+                  positioner: Positioner,
+                  proxy_ref: ProxyRef)
+                  -> Box<wl::server::Object> {
+        let popup = Self::new(oid,
+                              surface_oid,
+                              shell_surface_oid,
+                              parent_shell_surface_oid,
+                              positioner,
+                              proxy_ref);
+        Box::new(Handler::<_, xdg_popup::Dispatcher>::new(popup))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+#[allow(unused_variables)]
+impl xdg_popup::Interface for XdgPopup {
+    fn destroy(&mut self,
+               this_object_id: wl::common::ObjectId,
+               socket: &mut wl::server::ClientSocket)
+               -> wl::server::Task {
+        let proxy = self.proxy.borrow();
+        proxy.unrelate(self.surface_oid);
+        wl::server::Task::Destroy { id: this_object_id }
+    }
+
+    fn grab(&mut self,
+            this_object_id: wl::common::ObjectId,
+            socket: &mut wl::server::ClientSocket,
+            seat: wl::common::ObjectId,
+            serial: u32)
+            -> wl::server::Task {
+        wl::server::Task::None
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -4,8 +4,6 @@
 //! Implementations of Wayland `zxdg_shell_v6`, `zxdg_positioner_v6`, `zxdg_surface_v6`,
 //! `zxdg_toplevel_v6` and `zxdg_popup_v6` objects.
 
-// FIXME: Finish implementation of XDG pop-up positioning.
-
 use skylane as wl;
 use skylane_protocols::server::Handler;
 use skylane_protocols::server::xdg_shell_unstable_v6::zxdg_shell_v6;
@@ -14,9 +12,11 @@ use skylane_protocols::server::xdg_shell_unstable_v6::zxdg_surface_v6;
 use skylane_protocols::server::xdg_shell_unstable_v6::zxdg_toplevel_v6;
 use skylane_protocols::server::xdg_shell_unstable_v6::zxdg_popup_v6;
 
-use qualia::{show_reason, Area};
+use qualia::{show_reason, Positioner};
+use qualia::positioner::edges::Edges;
+use qualia::positioner::constraint_adjustment::ConstraintAdjustment;
 
-use facade::{Facade, PositionerInfo, ShellSurfaceOid};
+use facade::{Facade, ShellSurfaceOid};
 use global::Global;
 use proxy::ProxyRef;
 
@@ -45,7 +45,10 @@ impl ZxdgShellV6 {
         }
     }
 
-    fn new_object(oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Box<wl::server::Object> {
+    fn new_object(oid: wl::common::ObjectId,
+                  _version: u32,
+                  proxy_ref: ProxyRef)
+                  -> Box<wl::server::Object> {
         Box::new(Handler::<_, zxdg_shell_v6::Dispatcher>::new(Self::new(oid, proxy_ref)))
     }
 }
@@ -108,7 +111,7 @@ impl ZxdgPositionerV6 {
     fn new(oid: wl::common::ObjectId, proxy_ref: ProxyRef) -> Self {
         {
             let mut proxy = proxy_ref.borrow_mut();
-            proxy.set_positioner(oid, PositionerInfo::new());
+            proxy.set_positioner(oid, Positioner::new());
         }
         ZxdgPositionerV6 { proxy: proxy_ref }
     }
@@ -156,10 +159,10 @@ impl zxdg_positioner_v6::Interface for ZxdgPositionerV6 {
                        -> wl::server::Task {
         let mut proxy = self.proxy.borrow_mut();
         if let Some(mut positioner) = proxy.get_positioner(this_object_id) {
-            positioner.anchor.pos.x = x as isize;
-            positioner.anchor.pos.y = y as isize;
-            positioner.anchor.size.width = width as usize;
-            positioner.anchor.size.height = height as usize;
+            positioner.anchor_rect.pos.x = x as isize;
+            positioner.anchor_rect.pos.y = y as isize;
+            positioner.anchor_rect.size.width = width as usize;
+            positioner.anchor_rect.size.height = height as usize;
             proxy.set_positioner(this_object_id, positioner);
         }
         wl::server::Task::None
@@ -170,6 +173,11 @@ impl zxdg_positioner_v6::Interface for ZxdgPositionerV6 {
                   socket: &mut wl::server::ClientSocket,
                   anchor: u32)
                   -> wl::server::Task {
+        let mut proxy = self.proxy.borrow_mut();
+        if let Some(mut positioner) = proxy.get_positioner(this_object_id) {
+            positioner.anchor = Edges::from_bits_truncate(anchor);
+            proxy.set_positioner(this_object_id, positioner);
+        }
         wl::server::Task::None
     }
 
@@ -178,6 +186,11 @@ impl zxdg_positioner_v6::Interface for ZxdgPositionerV6 {
                    socket: &mut wl::server::ClientSocket,
                    gravity: u32)
                    -> wl::server::Task {
+        let mut proxy = self.proxy.borrow_mut();
+        if let Some(mut positioner) = proxy.get_positioner(this_object_id) {
+            positioner.gravity = Edges::from_bits_truncate(gravity);
+            proxy.set_positioner(this_object_id, positioner);
+        }
         wl::server::Task::None
     }
 
@@ -186,6 +199,12 @@ impl zxdg_positioner_v6::Interface for ZxdgPositionerV6 {
                                  socket: &mut wl::server::ClientSocket,
                                  constraint_adjustment: u32)
                                  -> wl::server::Task {
+        let mut proxy = self.proxy.borrow_mut();
+        if let Some(mut positioner) = proxy.get_positioner(this_object_id) {
+            positioner.constraint_adjustment =
+                ConstraintAdjustment::from_bits_truncate(constraint_adjustment);
+            proxy.set_positioner(this_object_id, positioner);
+        }
         wl::server::Task::None
     }
 
@@ -258,6 +277,10 @@ impl zxdg_surface_v6::Interface for ZxdgSurfaceV6 {
                                                   self.surface_oid,
                                                   self.oid,
                                                   self.proxy.clone());
+
+        // Configure the surface with size it will have when mapped.
+        self.proxy.borrow_mut().request_initial_size(self.surface_oid);
+
         wl::server::Task::Create {
             id: new_toplevel_id,
             object: toplevel,
@@ -271,30 +294,20 @@ impl zxdg_surface_v6::Interface for ZxdgSurfaceV6 {
                  parent_shell_surface_oid: wl::common::ObjectId,
                  positioner_oid: wl::common::ObjectId)
                  -> wl::server::Task {
-        let area = {
+        let positioner = {
             let mut proxy = self.proxy.borrow_mut();
-            if let Some(positioner) = proxy.get_positioner(positioner_oid) {
-                positioner.get_area()
-            } else {
-                Area::default()
-            }
+            proxy.get_positioner(positioner_oid).unwrap_or_else(Positioner::new)
         };
 
-        let popup = ZxdgPopupV6::new_object(self.surface_oid,
+        // Pop-up will be configured when its placement is decided. GTK does not map surface
+        // without configuring it.
+        let popup = ZxdgPopupV6::new_object(new_popup_oid,
+                                            self.surface_oid,
+                                            this_object_id,
                                             parent_shell_surface_oid,
-                                            area,
+                                            positioner,
                                             self.proxy.clone());
 
-        // GTK does not map surface without configuring it.
-        let serial = socket.get_next_serial();
-        send!(zxdg_popup_v6::configure(socket,
-                                       new_popup_oid,
-                                       area.pos.x as i32,
-                                       area.pos.y as i32,
-                                       area.size.width as i32,
-                                       area.size.height as i32));
-        send!(zxdg_surface_v6::configure(socket, this_object_id, serial));
-
         wl::server::Task::Create {
             id: new_popup_oid,
             object: popup,
@@ -324,7 +337,10 @@ impl zxdg_surface_v6::Interface for ZxdgSurfaceV6 {
 // -------------------------------------------------------------------------------------------------
 
 /// Wayland `zxdg_toplevel_v6` object.
-struct ZxdgToplevelV6 {}
+struct ZxdgToplevelV6 {
+    surface_oid: wl::common::ObjectId,
+    proxy: ProxyRef,
+}
 
 // -------------------------------------------------------------------------------------------------
 
@@ -341,7 +357,10 @@ impl ZxdgToplevelV6 {
                        show_reason::IN_SHELL);
         }
 
-        ZxdgToplevelV6 {}
+        ZxdgToplevelV6 {
+            surface_oid: surface_oid,
+            proxy: proxy_ref,
+        }
     }
 
     fn new_object(oid: wl::common::ObjectId,
@@ -378,6 +397,7 @@ impl zxdg_toplevel_v6::Interface for ZxdgToplevelV6 {
                  socket: &mut wl::server::ClientSocket,
                  title: String)
                  -> wl::server::Task {
+        self.proxy.borrow().set_title(self.surface_oid, title);
         wl::server::Task::None
     }
 
@@ -386,6 +406,7 @@ impl zxdg_toplevel_v6::Interface for ZxdgToplevelV6 {
                   socket: &mut wl::server::ClientSocket,
                   app_id: String)
                   -> wl::server::Task {
+        self.proxy.borrow().set_app_id(self.surface_oid, app_id);
         wl::server::Task::None
     }
 
@@ -406,6 +427,7 @@ impl zxdg_toplevel_v6::Interface for ZxdgToplevelV6 {
              seat: wl::common::ObjectId,
              serial: u32)
              -> wl::server::Task {
+        self.proxy.borrow_mut().request_move(self.surface_oid);
         wl::server::Task::None
     }
 
@@ -441,6 +463,7 @@ impl zxdg_toplevel_v6::Interface for ZxdgToplevelV6 {
                      this_object_id: wl::common::ObjectId,
                      socket: &mut wl::server::ClientSocket)
                      -> wl::server::Task {
+        self.proxy.borrow_mut().request_maximized(self.surface_oid, true);
         wl::server::Task::None
     }
 
@@ -448,6 +471,7 @@ impl zxdg_toplevel_v6::Interface for ZxdgToplevelV6 {
                        this_object_id: wl::common::ObjectId,
                        socket: &mut wl::server::ClientSocket)
                        -> wl::server::Task {
+        self.proxy.borrow_mut().request_maximized(self.surface_oid, false);
         wl::server::Task::None
     }
 
@@ -456,6 +480,7 @@ impl zxdg_toplevel_v6::Interface for ZxdgToplevelV6 {
                       socket: &mut wl::server::ClientSocket,
                       output: wl::common::ObjectId)
                       -> wl::server::Task {
+        self.proxy.borrow_mut().request_fullscreen(self.surface_oid, true, output);
         wl::server::Task::None
     }
 
@@ -463,6 +488,8 @@ impl zxdg_toplevel_v6::Interface for ZxdgToplevelV6 {
                         this_object_id: wl::common::ObjectId,
                         socket: &mut wl::server::ClientSocket)
                         -> wl::server::Task {
+        let null = wl::common::ObjectId::new(0);
+        self.proxy.borrow_mut().request_fullscreen(self.surface_oid, false, null);
         wl::server::Task::None
     }
 
@@ -485,18 +512,24 @@ struct ZxdgPopupV6 {
 // -------------------------------------------------------------------------------------------------
 
 impl ZxdgPopupV6 {
-    fn new(surface_oid: wl::common::ObjectId,
+    fn new(oid: wl::common::ObjectId,
+           surface_oid: wl::common::ObjectId,
+           shell_surface_oid: wl::common::ObjectId,
            parent_shell_surface_oid: wl::common::ObjectId,
-           area: Area,
+           positioner: Positioner,
            proxy_ref: ProxyRef)
            -> Self {
         {
-            let proxy = proxy_ref.borrow();
+            let mut proxy = proxy_ref.borrow_mut();
             let parent_surface_oid = proxy.get_surface_oid_for_shell(parent_shell_surface_oid);
             if let Some(parent_surface_oid) = parent_surface_oid {
+                let area = positioner.get_area();
                 proxy.relate(surface_oid, parent_surface_oid);
                 proxy.set_relative_position(surface_oid, area.pos.x, area.pos.y);
             }
+            proxy.place_popup(surface_oid,
+                              ShellSurfaceOid::ZxdgPopupV6(shell_surface_oid, oid),
+                              positioner);
         }
 
         ZxdgPopupV6 {
@@ -505,12 +538,19 @@ impl ZxdgPopupV6 {
         }
     }
 
-    fn new_object(surface_oid: wl::common::ObjectId,
+    fn new_object(oid: wl::common::ObjectId,
+                  surface_oid: wl::common::ObjectId,
+                  shell_surface_oid: wl::common::ObjectId,
                   parent_shell_surface_oid: wl::common::ObjectId,
-                  area: Area,
+                  positioner: Positioner,
                   proxy_ref: ProxyRef)
                   -> Box<wl::server::Object> {
-        let popup = Self::new(surface_oid, parent_shell_surface_oid, area, proxy_ref);
+        let popup = Self::new(oid,
+                              surface_oid,
+                              shell_surface_oid,
+                              parent_shell_surface_oid,
+                              positioner,
+                              proxy_ref);
         Box::new(Handler::<_, zxdg_popup_v6::Dispatcher>::new(popup))
     }
 }
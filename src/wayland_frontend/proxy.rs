@@ -7,27 +7,61 @@
 
 use std;
 use std::collections::{BTreeMap, HashMap, HashSet};
+use std::os::unix::io::RawFd;
 
 use dharma;
 use skylane as wl;
-use skylane_protocols::server::wayland::{wl_display, wl_callback, wl_buffer};
-use skylane_protocols::server::wayland::{wl_keyboard, wl_pointer};
+use skylane_protocols::server::wayland::{wl_display, wl_callback, wl_buffer, wl_output, wl_surface};
+use skylane_protocols::server::wayland::wl_registry;
+use skylane_protocols::server::wayland::{wl_keyboard, wl_pointer, wl_touch};
 use skylane_protocols::server::wayland::wl_shell_surface;
+use skylane_protocols::server::wayland::{wl_data_device, wl_data_offer, wl_data_source};
+use skylane_protocols::server::wayland::wl_data_device_manager;
+use skylane_protocols::server::ext_idle_notify_v1::ext_idle_notification_v1;
+use skylane_protocols::server::ext_session_lock_v1::{ext_session_lock_v1,
+                                                     ext_session_lock_surface_v1};
+use skylane_protocols::server::wlr_layer_shell_unstable_v1::zwlr_layer_surface_v1;
+use skylane_protocols::server::xdg_shell::{xdg_toplevel, xdg_surface, xdg_popup};
 use skylane_protocols::server::xdg_shell_unstable_v6::{zxdg_toplevel_v6, zxdg_surface_v6};
+use skylane_protocols::server::xdg_shell_unstable_v6::zxdg_popup_v6;
+use skylane_protocols::server::xdg_decoration_unstable_v1::zxdg_toplevel_decoration_v1;
+use skylane_protocols::server::wlr_data_control_unstable_v1::{zwlr_data_control_device_v1,
+                                                               zwlr_data_control_offer_v1,
+                                                               zwlr_data_control_source_v1};
+use skylane_protocols::server::pointer_gestures_unstable_v1::{zwp_pointer_gesture_swipe_v1,
+                                                               zwp_pointer_gesture_pinch_v1};
+use skylane_protocols::server::wlr_screencopy_unstable_v1::zwlr_screencopy_frame_v1;
+use skylane_protocols::server::wlr_output_management_unstable_v1::{zwlr_output_manager_v1,
+                                                                    zwlr_output_configuration_v1};
+use skylane_protocols::server::wayland::wl_shm;
+use skylane_protocols::server::fractional_scale_v1::wp_fractional_scale_v1;
+use skylane_protocols::server::presentation_time::wp_presentation_feedback;
 
 use qualia::{Coordinator, Settings};
 use qualia::{Area, Axis, Button, Key, KeyMods, Milliseconds, OutputInfo, Position, Size, Vector};
-use qualia::{MappedMemory, MemoryPoolId, MemoryViewId};
-use qualia::{show_reason, surface_state, SurfaceId};
+use qualia::{OutputConfiguration, OutputMode, PresentationTime};
+use qualia::{AlphaMode, HwImage, HwImageId, MappedMemory, MemoryPoolId, MemoryViewId};
+use qualia::{Buffer, PixelFormat, Pixmap};
+use qualia::Positioner;
+use qualia::{show_reason, surface_state, DecorationMode, DragState, SurfaceId, Transfer};
+use qualia::{GestureKind, GestureMotion, SeatId, Transform};
+use qualia::{Layer, LayerSurface};
+use qualia::seat_capability::SeatCapabilities;
 
 use protocol;
-use facade::{Facade, PositionerInfo, ShellSurfaceOid};
+use protocol::output_management::OutputHead;
+use facade::{Facade, ShellSurfaceOid};
 use gateway::Gateway;
 use global::Global;
 use mediator::MediatorRef;
+use version;
 
 // -------------------------------------------------------------------------------------------------
 
+/// First object ID from range reserved for objects created by server.
+const FIRST_SERVER_OID: u32 = 0xFF000000;
+
+
 /// Helper macro for relating surface information with surface ID
 macro_rules! relate_sid_with {
     ($member:ident, $dict:expr, $sid:ident, $obj:ident) => {
@@ -75,6 +109,30 @@ struct SurfaceInfo {
 
     // For send reconfiguration events in `on_surface_reconfigured`
     shell_surface_oid: Option<ShellSurfaceOid>,
+
+    // For sending chosen decoration mode in `on_decoration_mode_changed`
+    decoration_oid: Option<wl::common::ObjectId>,
+
+    // For sending preferred fractional scale in `on_preferred_buffer_changed`
+    fractional_scale_oid: Option<wl::common::ObjectId>,
+
+    // For sending preferred fractional scale when fractional scale object is created
+    preferred_scale: f32,
+
+    // For checking if preferred buffer events can be sent in `on_preferred_buffer_changed`
+    surface_version: u32,
+
+    // For validating `ack_configure` requests
+    configure_serials: Vec<u32>,
+
+    // For sending presentation feedback for content which will be submitted in next `commit`
+    feedback_oids: Vec<wl::common::ObjectId>,
+
+    // For sending presentation feedback for committed content which was not drawn yet
+    committed_feedback_oids: Vec<wl::common::ObjectId>,
+
+    // For sending presentation feedback in `on_frame_presented`
+    drawn_feedback_oids: Vec<wl::common::ObjectId>,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -84,26 +142,46 @@ impl SurfaceInfo {
         SurfaceInfo {
             surface_oid: None,
             shell_surface_oid: None,
+            decoration_oid: None,
+            fractional_scale_oid: None,
+            preferred_scale: 1.0,
             buffer_oid: None,
             frame_oid: None,
+            surface_version: 1,
+            configure_serials: Vec::new(),
+            feedback_oids: Vec::new(),
+            committed_feedback_oids: Vec::new(),
+            drawn_feedback_oids: Vec::new(),
         }
     }
 }
 
 // -------------------------------------------------------------------------------------------------
 
-/// Helper structure for aggregating information about buffers.
+/// Helper enumeration for aggregating information about buffers. Buffers may be backed by shared
+/// memory or by hardware images.
 #[derive(Clone, Copy)]
-struct BufferInfo {
-    mvid: MemoryViewId,
+enum BufferInfo {
+    MemoryView(MemoryViewId),
+    HwImage(HwImageId),
 }
 
 // -------------------------------------------------------------------------------------------------
 
-impl BufferInfo {
-    pub fn new(mvid: MemoryViewId) -> Self {
-        BufferInfo { mvid: mvid }
-    }
+/// Helper structure for aggregating information about screencopy frames.
+#[derive(Clone, Copy)]
+struct ScreencopyInfo {
+    /// ID of captured output.
+    output_id: i32,
+
+    /// Captured region in output coordinates.
+    area: Area,
+
+    /// Buffer to copy the frame to. `None` until client requests the copy.
+    buffer_oid: Option<wl::common::ObjectId>,
+
+    /// Tells if client requested damage to be reported.
+    with_damage: bool,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -117,6 +195,9 @@ pub struct Proxy {
     coordinator: Coordinator,
     settings: Settings,
 
+    /// Tells if client is allowed to bind privileged globals.
+    privileged: bool,
+
     mediator: MediatorRef,
     socket: wl::server::ClientSocket,
 
@@ -126,15 +207,60 @@ pub struct Proxy {
     /// globals in wrong order may crash clients
     globals: BTreeMap<u32, Global>,
 
+    /// Globals removed while client was connected. Client may still bind them until it processes
+    /// `global_remove` event.
+    removed_globals: HashMap<u32, Global>,
+
+    /// Names of `wl_output` globals of outputs.
+    output_globals: HashMap<i32, u32>,
+
+    /// Sizes of frames captured from outputs in pixels and scales of the outputs.
+    output_sizes: HashMap<i32, (Size, f32)>,
+
+    /// Information about outputs sent to `zxdg_output_v1` objects.
+    output_infos: HashMap<i32, OutputInfo>,
+
+    /// OIDs of `wl_output` objects described by `zxdg_output_v1` objects.
+    xdg_output_oids: HashMap<wl::common::ObjectId, wl::common::ObjectId>,
+
+    /// Heads advertised to `zwlr_output_manager_v1` objects.
+    output_heads: HashMap<wl::common::ObjectId, Vec<OutputHead>>,
+
+    /// Serial of output configuration advertised to `zwlr_output_manager_v1` objects.
+    output_configuration_serial: u32,
+
+    /// OIDs of `zwlr_output_configuration_v1` objects waiting for result by ID of request.
+    output_configuration_oids: HashMap<u32, wl::common::ObjectId>,
+
+    registry_oids: HashSet<wl::common::ObjectId>,
     regions: HashMap<wl::common::ObjectId, Area>,
-    positioners: HashMap<wl::common::ObjectId, PositionerInfo>,
+    positioners: HashMap<wl::common::ObjectId, Positioner>,
+    seat_oids: HashMap<wl::common::ObjectId, SeatId>,
     pointer_oids: HashSet<wl::common::ObjectId>,
-    keyboard_oids: HashSet<wl::common::ObjectId>,
+    keyboard_oids: HashMap<wl::common::ObjectId, SeatId>,
+    touch_oids: HashSet<wl::common::ObjectId>,
+    swipe_gesture_oids: HashSet<wl::common::ObjectId>,
+    pinch_gesture_oids: HashSet<wl::common::ObjectId>,
+    idle_notification_oids: HashMap<wl::common::ObjectId, u32>,
+    idled_notification_oids: HashSet<wl::common::ObjectId>,
+    session_lock_oid: Option<wl::common::ObjectId>,
+    layer_surfaces: HashMap<SurfaceId, LayerSurface>,
+    output_oids: HashMap<wl::common::ObjectId, i32>,
+    screencopy_frames: HashMap<wl::common::ObjectId, ScreencopyInfo>,
+    object_versions: HashMap<wl::common::ObjectId, u32>,
+    data_device_oids: HashSet<wl::common::ObjectId>,
+    data_sources: HashMap<wl::common::ObjectId, Transfer>,
+    data_control_device_oids: HashSet<wl::common::ObjectId>,
+    new_data_control_device_oids: Vec<wl::common::ObjectId>,
+    data_control_source_oids: HashSet<wl::common::ObjectId>,
+    selection_source_oid: Option<wl::common::ObjectId>,
+    drag_source_oid: Option<wl::common::ObjectId>,
     memory_pools: HashSet<MemoryPoolId>,
     surface_oid_to_sid_dictionary: HashMap<wl::common::ObjectId, SurfaceId>,
     sid_to_surface_info_dictionary: HashMap<SurfaceId, SurfaceInfo>,
     buffer_oid_to_buffer_info_dictionary: HashMap<wl::common::ObjectId, BufferInfo>,
     last_global_id: u32,
+    last_server_oid: u32,
 }
 
 define_ref!(Proxy, ProxyRef);
@@ -146,6 +272,7 @@ impl Proxy {
     pub fn new(id: dharma::EventHandlerId,
                coordinator: Coordinator,
                settings: Settings,
+               privileged: bool,
                mediator: MediatorRef,
                socket: wl::server::ClientSocket)
                -> Self {
@@ -154,17 +281,46 @@ impl Proxy {
             coordinator: coordinator,
             mediator: mediator,
             settings: settings,
+            privileged: privileged,
             socket: socket,
             globals: BTreeMap::new(),
+            removed_globals: HashMap::new(),
+            output_globals: HashMap::new(),
+            output_sizes: HashMap::new(),
+            output_infos: HashMap::new(),
+            xdg_output_oids: HashMap::new(),
+            output_heads: HashMap::new(),
+            output_configuration_serial: 0,
+            output_configuration_oids: HashMap::new(),
+            registry_oids: HashSet::new(),
             regions: HashMap::new(),
             positioners: HashMap::new(),
+            seat_oids: HashMap::new(),
             pointer_oids: HashSet::new(),
-            keyboard_oids: HashSet::new(),
+            keyboard_oids: HashMap::new(),
+            touch_oids: HashSet::new(),
+            swipe_gesture_oids: HashSet::new(),
+            pinch_gesture_oids: HashSet::new(),
+            idle_notification_oids: HashMap::new(),
+            idled_notification_oids: HashSet::new(),
+            session_lock_oid: None,
+            layer_surfaces: HashMap::new(),
+            output_oids: HashMap::new(),
+            screencopy_frames: HashMap::new(),
+            object_versions: HashMap::new(),
+            data_device_oids: HashSet::new(),
+            data_sources: HashMap::new(),
+            data_control_device_oids: HashSet::new(),
+            new_data_control_device_oids: Vec::new(),
+            data_control_source_oids: HashSet::new(),
+            selection_source_oid: None,
+            drag_source_oid: None,
             memory_pools: HashSet::new(),
             surface_oid_to_sid_dictionary: HashMap::new(),
             sid_to_surface_info_dictionary: HashMap::new(),
             buffer_oid_to_buffer_info_dictionary: HashMap::new(),
             last_global_id: 0,
+            last_server_oid: FIRST_SERVER_OID - 1,
         }
     }
 
@@ -178,16 +334,115 @@ impl Proxy {
         self.socket.clone()
     }
 
-    /// Return list of current globals.
-    pub fn get_globals(&self) -> &BTreeMap<u32, Global> {
-        &self.globals
+    /// Tells if client is allowed to bind privileged globals.
+    pub fn is_privileged(&self) -> bool {
+        self.privileged
+    }
+
+    /// Return list of current globals advertised to the client in order of registering.
+    pub fn get_globals(&self) -> Vec<&Global> {
+        self.globals.values().filter(|global| self.is_advertised(global)).collect()
+    }
+
+    /// Returns global with given name if it is advertised to the client or was removed after
+    /// being advertised.
+    pub fn find_global(&self, name: u32) -> Option<Global> {
+        match self.globals.get(&name) {
+            Some(global) if self.is_advertised(global) => Some(global.clone()),
+            Some(_) => None,
+            None => self.removed_globals.get(&name).cloned(),
+        }
+    }
+
+    /// Informs all keyboards of the client bound to given seat about state of modifiers and active
+    /// layout.
+    pub fn send_keyboard_modifiers(&self, seat: SeatId, mods: KeyMods) {
+        for keyboard_oid in self.get_keyboard_oids(seat) {
+            let serial = self.socket.get_next_serial();
+            send!(wl_keyboard::modifiers(&self.socket,
+                                         keyboard_oid,
+                                         serial,
+                                         mods.depressed,
+                                         mods.latched,
+                                         mods.locked,
+                                         mods.layout));
+        }
     }
 
-    /// Registers new global.
-    pub fn register_global(&mut self, mut global: Global) {
+    /// Registers new global and announces it to bound registries. Privileged globals are
+    /// advertised only to privileged clients. Returns name of the global.
+    pub fn register_global(&mut self, mut global: Global) -> u32 {
         self.last_global_id += 1;
         global.name = self.last_global_id;
+        if self.is_advertised(&global) {
+            self.send_global(&global);
+        }
         self.globals.insert(self.last_global_id, global);
+        self.last_global_id
+    }
+
+    /// Replaces global with given name without announcing it to registries. Clients binding the
+    /// global from now on get objects created by the new one.
+    pub fn replace_global(&mut self, name: u32, mut global: Global) {
+        if self.globals.contains_key(&name) {
+            global.name = name;
+            self.globals.insert(name, global);
+        }
+    }
+
+    /// Unregisters global and announces its removal to bound registries. Objects already bound
+    /// stay valid until client destroys them.
+    pub fn unregister_global(&mut self, name: u32) {
+        if let Some(global) = self.globals.remove(&name) {
+            if self.is_advertised(&global) {
+                self.send_global_remove(name);
+                self.removed_globals.insert(name, global);
+            }
+        }
+    }
+
+    /// Grants or revokes client privileges. Privileged globals are announced or removed
+    /// accordingly. When privileges are revoked objects giving access to privileged
+    /// functionality are finished.
+    pub fn set_privileged(&mut self, privileged: bool) {
+        if self.privileged == privileged {
+            return;
+        }
+
+        let names: Vec<u32> = self.globals
+            .values()
+            .filter(|global| global.privileged)
+            .map(|global| global.name)
+            .collect();
+        if privileged {
+            self.privileged = true;
+            for name in names {
+                if let Some(global) = self.globals.get(&name) {
+                    self.send_global(global);
+                }
+            }
+        } else {
+            for name in names.iter() {
+                self.send_global_remove(*name);
+            }
+            for name in names {
+                if let Some(global) = self.globals.get(&name) {
+                    self.removed_globals.insert(name, global.clone());
+                }
+            }
+            self.privileged = false;
+
+            for oid in self.data_control_device_oids.drain() {
+                send!(zwlr_data_control_device_v1::finished(&self.socket, oid));
+            }
+            self.new_data_control_device_oids.clear();
+
+            let manager_oids: Vec<wl::common::ObjectId> =
+                self.output_heads.keys().cloned().collect();
+            for manager_oid in manager_oids {
+                self.remove_output_manager_oid(manager_oid);
+            }
+        }
     }
 
     /// Handles termination of client by destroying its resources.
@@ -200,6 +455,162 @@ impl Proxy {
             self.mediator.borrow_mut().remove(*sid);
             self.coordinator.destroy_surface(*sid);
         }
+
+        if self.mediator.borrow().get_transferer() == Some(self.id) {
+            self.mediator.borrow_mut().register_transferer(None);
+            self.coordinator.set_transfer(None);
+        }
+
+        if self.mediator.borrow().get_drag_source() == Some(self.id) {
+            self.mediator.borrow_mut().register_drag_source(None);
+            self.coordinator.end_drag(false);
+        }
+
+        for (_, timeout) in self.idle_notification_oids.drain() {
+            self.coordinator.remove_idle_timeout(timeout);
+        }
+
+        if self.session_lock_oid.take().is_some() {
+            self.coordinator.abandon_session_lock();
+        }
+    }
+
+    /// Generates ID for new object created on server side.
+    pub fn generate_next_server_oid(&mut self) -> wl::common::ObjectId {
+        self.last_server_oid += 1;
+        wl::common::ObjectId::new(self.last_server_oid)
+    }
+
+    /// Returns list of data device object IDs.
+    pub fn get_data_device_oids(&self) -> Vec<wl::common::ObjectId> {
+        self.data_device_oids.iter().cloned().collect()
+    }
+
+    /// Returns IDs of all data control devices of this client.
+    pub fn get_data_control_device_oids(&self) -> Vec<wl::common::ObjectId> {
+        self.data_control_device_oids.iter().cloned().collect()
+    }
+
+    /// Returns IDs of data control devices created since last call. These devices were not yet
+    /// informed about current selection.
+    pub fn take_new_data_control_device_oids(&mut self) -> Vec<wl::common::ObjectId> {
+        std::mem::replace(&mut self.new_data_control_device_oids, Vec::new())
+    }
+
+    /// Advertises given data offer as current selection on given data device. Null data offer
+    /// object ID means there is no selection.
+    pub fn offer_selection(&self,
+                           data_device_oid: wl::common::ObjectId,
+                           data_offer_oid: wl::common::ObjectId,
+                           transfer: &Option<Transfer>) {
+        if let Some(ref transfer) = *transfer {
+            send!(wl_data_device::data_offer(&self.socket, data_device_oid, data_offer_oid));
+            for mime_type in transfer.get_mime_types() {
+                send!(wl_data_offer::offer(&self.socket, data_offer_oid, mime_type));
+            }
+        }
+        send!(wl_data_device::selection(&self.socket, data_device_oid, data_offer_oid));
+    }
+
+    /// Advertises given data control offer as current selection on given data control device.
+    /// Null data offer object ID means there is no selection.
+    pub fn offer_control_selection(&self,
+                                   data_device_oid: wl::common::ObjectId,
+                                   data_offer_oid: wl::common::ObjectId,
+                                   transfer: &Option<Transfer>) {
+        if let Some(ref transfer) = *transfer {
+            send!(zwlr_data_control_device_v1::data_offer(&self.socket,
+                                                          data_device_oid,
+                                                          data_offer_oid));
+            for mime_type in transfer.get_mime_types() {
+                send!(zwlr_data_control_offer_v1::offer(&self.socket, data_offer_oid, mime_type));
+            }
+        }
+        send!(zwlr_data_control_device_v1::selection(&self.socket,
+                                                     data_device_oid,
+                                                     data_offer_oid));
+    }
+
+    /// Informs client its data source is no longer used as selection.
+    pub fn cancel_selection(&mut self) {
+        if let Some(source_oid) = self.selection_source_oid {
+            self.send_source_cancelled(source_oid);
+        }
+        self.selection_source_oid = None;
+    }
+
+    /// Informs client dragged data entered its surface. Null data offer object ID means the drag
+    /// has no data source.
+    pub fn enter_drag(&self,
+                      data_device_oid: wl::common::ObjectId,
+                      data_offer_oid: wl::common::ObjectId,
+                      sid: SurfaceId,
+                      position: Position,
+                      transfer: &Option<Transfer>) {
+        let surface_oid = if let Some(info) = self.sid_to_surface_info_dictionary.get(&sid) {
+            if let Some(surface_oid) = info.surface_oid {
+                surface_oid
+            } else {
+                return;
+            }
+        } else {
+            return;
+        };
+
+        if let Some(ref transfer) = *transfer {
+            send!(wl_data_device::data_offer(&self.socket, data_device_oid, data_offer_oid));
+            for mime_type in transfer.get_mime_types() {
+                send!(wl_data_offer::offer(&self.socket, data_offer_oid, mime_type));
+            }
+            if self.supports(data_device_oid, version::WL_DATA_DEVICE_ACTIONS_SINCE) {
+                send!(wl_data_offer::source_actions(&self.socket,
+                                                    data_offer_oid,
+                                                    wl_data_device_manager::dnd_action::COPY));
+            }
+        }
+
+        let serial = self.socket.get_next_serial();
+        send!(wl_data_device::enter(&self.socket,
+                                    data_device_oid,
+                                    serial,
+                                    surface_oid,
+                                    position.x as f32,
+                                    position.y as f32,
+                                    data_offer_oid));
+    }
+
+    /// Informs client dragged data left its surface.
+    pub fn leave_drag(&self) {
+        for data_device_oid in self.data_device_oids.iter() {
+            send!(wl_data_device::leave(&self.socket, *data_device_oid));
+        }
+    }
+
+    /// Informs client dragged data was dropped on its surface.
+    pub fn drop_drag(&self) {
+        for data_device_oid in self.data_device_oids.iter() {
+            send!(wl_data_device::drop(&self.socket, *data_device_oid));
+        }
+    }
+
+    /// Informs client owning dragged data it was dropped on target surface.
+    pub fn on_drag_drop_performed(&self) {
+        if let Some(source_oid) = self.drag_source_oid {
+            send!(wl_data_source::dnd_drop_performed(&self.socket, source_oid));
+        }
+    }
+
+    /// Informs client owning dragged data the drag ended. If the drop was performed the data
+    /// source is finished, otherwise it is cancelled.
+    pub fn on_drag_ended(&mut self, performed: bool) {
+        if let Some(source_oid) = self.drag_source_oid {
+            if performed {
+                send!(wl_data_source::dnd_finished(&self.socket, source_oid));
+            } else {
+                send!(wl_data_source::cancelled(&self.socket, source_oid));
+            }
+        }
+        self.drag_source_oid = None;
     }
 }
 
@@ -231,6 +642,224 @@ impl Proxy {
 // -------------------------------------------------------------------------------------------------
 
 impl Proxy {
+    /// Helper method for informing data source it was cancelled. Data sources may come from
+    /// `wl_data_device_manager` or `zwlr_data_control_manager_v1`.
+    fn send_source_cancelled(&self, source_oid: wl::common::ObjectId) {
+        if self.data_control_source_oids.contains(&source_oid) {
+            send!(zwlr_data_control_source_v1::cancelled(&self.socket, source_oid));
+        } else {
+            send!(wl_data_source::cancelled(&self.socket, source_oid));
+        }
+    }
+
+    /// Helper method for requesting data source to write its data to given file descriptor.
+    fn send_source_send(&self, source_oid: wl::common::ObjectId, mime_type: &String, fd: RawFd) {
+        if self.data_control_source_oids.contains(&source_oid) {
+            send!(zwlr_data_control_source_v1::send(&self.socket, source_oid, mime_type, fd));
+        } else {
+            send!(wl_data_source::send(&self.socket, source_oid, mime_type, fd));
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Proxy {
+    /// Helper method copying captured region of output to client buffer of screencopy frame.
+    /// Captured frame is in RGBA format and client buffer in XRGB8888. Returns `false` if buffer is
+    /// not backed by shared memory or does not match the region.
+    fn copy_captured_frame(&self, info: &ScreencopyInfo, frame: &Buffer) -> bool {
+        let view = match info.buffer_oid
+            .and_then(|oid| self.buffer_oid_to_buffer_info_dictionary.get(&oid).cloned()) {
+            Some(BufferInfo::MemoryView(mvid)) => self.coordinator.get_memory_view(mvid),
+            _ => None,
+        };
+
+        let view = match view {
+            Some(view) => view,
+            None => return false,
+        };
+
+        let width = info.area.size.width;
+        let height = info.area.size.height;
+        let frame_area = Area::new(Position::default(), frame.get_size());
+        if view.get_format().get_bytes_per_pixel() != 4 || view.get_size() != info.area.size ||
+           view.get_stride() < 4 * width ||
+           info.area.intersected(&frame_area) != Some(info.area) {
+            return false;
+        }
+
+        let destination = match unsafe { view.as_mut_slice() } {
+            Some(destination) => destination,
+            None => return false,
+        };
+
+        let source = frame.as_slice();
+        let (x, y) = (info.area.pos.x as usize, info.area.pos.y as usize);
+        for row in 0..height {
+            let src = (y + row) * frame.get_stride() + 4 * x;
+            let dst = row * view.get_stride();
+            for col in 0..width {
+                let (s, d) = (src + 4 * col, dst + 4 * col);
+                destination[d + 0] = source[s + 2];
+                destination[d + 1] = source[s + 1];
+                destination[d + 2] = source[s + 0];
+                destination[d + 3] = 0xFF;
+            }
+        }
+        true
+    }
+
+    /// Helper method informing screencopy frame its copy is ready.
+    fn send_screencopy_ready(&self, frame_oid: wl::common::ObjectId, info: &ScreencopyInfo) {
+        send!(zwlr_screencopy_frame_v1::flags(&self.socket, frame_oid, 0));
+        if info.with_damage &&
+           self.supports(frame_oid, version::ZWLR_SCREENCOPY_FRAME_DAMAGE_SINCE) {
+            send!(zwlr_screencopy_frame_v1::damage(&self.socket,
+                                                   frame_oid,
+                                                   0,
+                                                   0,
+                                                   info.area.size.width as u32,
+                                                   info.area.size.height as u32));
+        }
+
+        let time = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or(std::time::Duration::from_secs(0));
+        let secs = time.as_secs();
+        send!(zwlr_screencopy_frame_v1::ready(&self.socket,
+                                              frame_oid,
+                                              (secs >> 32) as u32,
+                                              secs as u32,
+                                              time.subsec_nanos()));
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Proxy {
+    /// Helper method checking if object has version at least `since`.
+    fn supports(&self, oid: wl::common::ObjectId, since: u32) -> bool {
+        self.get_object_version(oid) >= since
+    }
+
+    /// Helper method checking if global may be advertised to the client.
+    fn is_advertised(&self, global: &Global) -> bool {
+        !global.privileged || self.privileged
+    }
+
+    /// Helper method announcing global to all bound registries.
+    fn send_global(&self, global: &Global) {
+        for registry_oid in self.registry_oids.iter() {
+            send!(wl_registry::global(&self.socket,
+                                      *registry_oid,
+                                      global.name,
+                                      global.interface,
+                                      global.version));
+        }
+    }
+
+    /// Helper method remembering size of output in pixels and its scale. Frames are captured in
+    /// orientation seen by user.
+    fn update_output_size(&mut self, output_info: &OutputInfo) {
+        let size = if output_info.transform.is_transposing() {
+            Size::new(output_info.pixel_size.height, output_info.pixel_size.width)
+        } else {
+            output_info.pixel_size
+        };
+        self.output_sizes.insert(output_info.id, (size, output_info.scale));
+    }
+
+    /// Helper method creating head describing output to `zwlr_output_manager_v1` object.
+    fn create_output_head(&mut self, output_info: &OutputInfo) -> OutputHead {
+        let mut modes = output_info.modes.clone();
+        if modes.is_empty() {
+            modes.push(OutputMode::new(output_info.pixel_size, output_info.refresh_rate));
+        }
+
+        let oid = self.generate_next_server_oid();
+        let modes = modes.into_iter().map(|mode| (self.generate_next_server_oid(), mode)).collect();
+        OutputHead {
+            output_id: output_info.id,
+            oid: oid,
+            modes: modes,
+        }
+    }
+
+    /// Helper method informing all `zwlr_output_manager_v1` objects that configuration of outputs
+    /// changed.
+    fn send_output_managers_done(&mut self) {
+        self.output_configuration_serial += 1;
+        for manager_oid in self.output_heads.keys() {
+            send!(zwlr_output_manager_v1::done(&self.socket,
+                                               *manager_oid,
+                                               self.output_configuration_serial));
+        }
+    }
+
+    /// Helper method announcing removal of global to all bound registries.
+    fn send_global_remove(&self, name: u32) {
+        for registry_oid in self.registry_oids.iter() {
+            send!(wl_registry::global_remove(&self.socket, *registry_oid, name));
+        }
+    }
+
+    /// Helper method returning IDs of keyboard objects bound to given seat.
+    fn get_keyboard_oids(&self, seat: SeatId) -> Vec<wl::common::ObjectId> {
+        self.keyboard_oids
+            .iter()
+            .filter(|&(_, keyboard_seat)| *keyboard_seat == seat)
+            .map(|(oid, _)| *oid)
+            .collect()
+    }
+
+    /// Helper method for sending `leave` and `enter` events to keyboards bound to given seat.
+    /// Returns IDs of surfaces which lost or gained focus of any of these keyboards.
+    fn send_keyboard_focus_change(&self,
+                                  seat: SeatId,
+                                  old_sid: SurfaceId,
+                                  new_sid: SurfaceId)
+                                  -> Vec<SurfaceId> {
+        let mut changed = Vec::new();
+        let keyboard_oids = self.get_keyboard_oids(seat);
+        if keyboard_oids.is_empty() {
+            return changed;
+        }
+
+        if old_sid != SurfaceId::invalid() {
+            if let Some(surface_info) = self.sid_to_surface_info_dictionary.get(&old_sid) {
+                if let Some(surface_oid) = surface_info.surface_oid {
+                    for keyboard_oid in keyboard_oids.iter() {
+                        let serial = self.socket.get_next_serial();
+                        send!(wl_keyboard::leave(&self.socket, *keyboard_oid, serial, surface_oid));
+                    }
+                    changed.push(old_sid);
+                }
+            }
+        }
+
+        if new_sid != SurfaceId::invalid() {
+            if let Some(surface_info) = self.sid_to_surface_info_dictionary.get(&new_sid) {
+                if let Some(surface_oid) = surface_info.surface_oid {
+                    for keyboard_oid in keyboard_oids.iter() {
+                        let serial = self.socket.get_next_serial();
+
+                        // TODO: Pass correct keys on keyboard enter.
+                        let keys: [u32; 0] = [0; 0];
+
+                        send!(wl_keyboard::enter(&self.socket,
+                                                 *keyboard_oid,
+                                                 serial,
+                                                 surface_oid,
+                                                 &keys[..]));
+                    }
+                    changed.push(new_sid);
+                }
+            }
+        }
+        changed
+    }
+
     /// Helper method for unsetting shell information for surface.
     fn unrelate_sid_with_shell_surface(&mut self, sid: SurfaceId) {
         unrelate_sid_with!(shell_surface_oid, self.sid_to_surface_info_dictionary, sid);
@@ -239,6 +868,55 @@ impl Proxy {
 
 // -------------------------------------------------------------------------------------------------
 
+// Helper methods for presentation feedback.
+impl Proxy {
+    /// Informs client that content updates related to given feedback objects were never presented.
+    fn discard_feedbacks(&self, feedback_oids: Vec<wl::common::ObjectId>) {
+        for feedback_oid in feedback_oids {
+            send!(wp_presentation_feedback::discarded(&self.socket, feedback_oid));
+            send!(wl_display::delete_id(&self.socket,
+                                        wl::common::DISPLAY_ID,
+                                        feedback_oid.get_value()));
+        }
+    }
+
+    /// Sends presentation time to given feedback objects.
+    fn present_feedbacks(&self,
+                         feedback_oids: Vec<wl::common::ObjectId>,
+                         output_id: i32,
+                         time: PresentationTime) {
+        let refresh = match self.output_infos.get(&output_id) {
+            Some(info) if info.refresh_rate != 0 => 1_000_000_000 / info.refresh_rate as u32,
+            _ => 0,
+        };
+        let flags = wp_presentation_feedback::kind::VSYNC |
+                    wp_presentation_feedback::kind::HW_CLOCK |
+                    wp_presentation_feedback::kind::HW_COMPLETION;
+
+        for feedback_oid in feedback_oids {
+            for (output_oid, _) in self.output_oids.iter().filter(|&(_, id)| *id == output_id) {
+                send!(wp_presentation_feedback::sync_output(&self.socket,
+                                                            feedback_oid,
+                                                            *output_oid));
+            }
+            send!(wp_presentation_feedback::presented(&self.socket,
+                                                      feedback_oid,
+                                                      (time.sec >> 32) as u32,
+                                                      time.sec as u32,
+                                                      time.nsec,
+                                                      refresh,
+                                                      (time.sequence >> 32) as u32,
+                                                      time.sequence as u32,
+                                                      flags));
+            send!(wl_display::delete_id(&self.socket,
+                                        wl::common::DISPLAY_ID,
+                                        feedback_oid.get_value()));
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
 // Other functions (which should be probably refactored).
 impl Proxy {
     pub fn get_surface_oid_for_shell(&self,
@@ -247,7 +925,10 @@ impl Proxy {
         for info in self.sid_to_surface_info_dictionary.values() {
             if let Some(shell_surface_oid) = info.shell_surface_oid {
                 match shell_surface_oid {
-                    ShellSurfaceOid::ZxdgToplevelV6(shell_surface_oid, _) => {
+                    ShellSurfaceOid::ZxdgToplevelV6(shell_surface_oid, _) |
+                    ShellSurfaceOid::XdgToplevel(shell_surface_oid, _) |
+                    ShellSurfaceOid::ZxdgPopupV6(shell_surface_oid, _) |
+                    ShellSurfaceOid::XdgPopup(shell_surface_oid, _) => {
                         if shell_surface_oid == parent_shell_surface_oid {
                             return info.surface_oid;
                         }
@@ -258,6 +939,20 @@ impl Proxy {
         }
         None
     }
+
+    pub fn get_surface_oid_for_toplevel(&self,
+                                        toplevel_oid: wl::common::ObjectId)
+                                        -> Option<wl::common::ObjectId> {
+        for info in self.sid_to_surface_info_dictionary.values() {
+            if let Some(ShellSurfaceOid::XdgToplevel(_, shell_toplevel_oid)) =
+                info.shell_surface_oid {
+                if shell_toplevel_oid == toplevel_oid {
+                    return info.surface_oid;
+                }
+            }
+        }
+        None
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -285,11 +980,15 @@ impl Facade for Proxy {
                           offset: usize,
                           width: usize,
                           height: usize,
-                          stride: usize)
+                          stride: usize,
+                          format: PixelFormat,
+                          alpha_mode: AlphaMode)
                           -> Option<MemoryViewId> {
-        let result = self.coordinator.create_memory_view(mpid, offset, width, height, stride);
+        let result = self.coordinator
+            .create_memory_view(mpid, offset, width, height, stride, format, alpha_mode);
         if let Some(mvid) = result {
-            self.buffer_oid_to_buffer_info_dictionary.insert(buffer_oid, BufferInfo::new(mvid));
+            let info = BufferInfo::MemoryView(mvid);
+            self.buffer_oid_to_buffer_info_dictionary.insert(buffer_oid, info);
         }
         result
     }
@@ -298,6 +997,16 @@ impl Facade for Proxy {
         self.coordinator.destroy_memory_view(mvid);
     }
 
+    fn create_hw_image(&mut self, buffer_oid: wl::common::ObjectId, image: HwImage) -> HwImageId {
+        let hiid = self.coordinator.create_hw_image(image);
+        self.buffer_oid_to_buffer_info_dictionary.insert(buffer_oid, BufferInfo::HwImage(hiid));
+        hiid
+    }
+
+    fn destroy_hw_image(&mut self, hiid: HwImageId) {
+        self.coordinator.destroy_hw_image(hiid);
+    }
+
     fn define_region(&mut self, region_oid: wl::common::ObjectId, region: Area) {
         self.regions.insert(region_oid, region);
     }
@@ -306,73 +1015,578 @@ impl Facade for Proxy {
         self.regions.remove(&region_oid);
     }
 
+    fn set_object_version(&mut self, oid: wl::common::ObjectId, version: u32) {
+        self.object_versions.insert(oid, version);
+    }
+
+    fn inherit_object_version(&mut self,
+                              oid: wl::common::ObjectId,
+                              parent_oid: wl::common::ObjectId) {
+        let version = self.get_object_version(parent_oid);
+        self.object_versions.insert(oid, version);
+    }
+
+    fn get_object_version(&self, oid: wl::common::ObjectId) -> u32 {
+        self.object_versions.get(&oid).cloned().unwrap_or(1)
+    }
+
+    fn add_registry_oid(&mut self, registry_oid: wl::common::ObjectId) {
+        self.registry_oids.insert(registry_oid);
+    }
+
+    fn add_seat_oid(&mut self, seat_oid: wl::common::ObjectId, seat: SeatId) {
+        self.seat_oids.insert(seat_oid, seat);
+    }
+
+    fn remove_seat_oid(&mut self, seat_oid: wl::common::ObjectId) {
+        self.seat_oids.remove(&seat_oid);
+        self.object_versions.remove(&seat_oid);
+    }
+
+    fn get_seat_capabilities(&self, seat: SeatId) -> SeatCapabilities {
+        self.coordinator.get_seat_capabilities(seat)
+    }
+
     fn add_pointer_oid(&mut self, pointer_oid: wl::common::ObjectId) {
         self.pointer_oids.insert(pointer_oid);
     }
 
     fn remove_pointer_oid(&mut self, pointer_oid: wl::common::ObjectId) {
         self.pointer_oids.remove(&pointer_oid);
+        self.object_versions.remove(&pointer_oid);
     }
 
-    fn add_keyboard_oid(&mut self, keyboard_oid: wl::common::ObjectId) {
-        self.keyboard_oids.insert(keyboard_oid);
+    fn add_keyboard_oid(&mut self, keyboard_oid: wl::common::ObjectId, seat: SeatId) {
+        self.keyboard_oids.insert(keyboard_oid, seat);
     }
 
     fn remove_keyboard_oid(&mut self, keyboard_oid: wl::common::ObjectId) {
         self.keyboard_oids.remove(&keyboard_oid);
+        self.object_versions.remove(&keyboard_oid);
     }
 
-    fn set_positioner(&mut self, oid: wl::common::ObjectId, positioner: PositionerInfo) {
-        self.positioners.insert(oid, positioner);
+    fn add_touch_oid(&mut self, touch_oid: wl::common::ObjectId) {
+        self.touch_oids.insert(touch_oid);
     }
 
-    fn get_positioner(&mut self, oid: wl::common::ObjectId) -> Option<PositionerInfo> {
-        if let Some(positioner) = self.positioners.get(&oid) {
-            Some(*positioner)
-        } else {
-            None
-        }
+    fn remove_touch_oid(&mut self, touch_oid: wl::common::ObjectId) {
+        self.touch_oids.remove(&touch_oid);
+        self.object_versions.remove(&touch_oid);
     }
 
-    fn remove_positioner(&mut self, oid: wl::common::ObjectId) {
-        self.positioners.remove(&oid);
+    fn add_swipe_gesture_oid(&mut self, gesture_oid: wl::common::ObjectId) {
+        self.swipe_gesture_oids.insert(gesture_oid);
     }
 
-    fn set_input_region(&self, sid: SurfaceId, region_oid: wl::common::ObjectId) {
-        if let Some(region) = self.regions.get(&region_oid) {
-            self.coordinator.set_surface_offset(sid, region.pos);
-            self.coordinator.set_surface_requested_size(sid, region.size);
-        } else {
-            // TODO: Implement reseting input region.
-        }
+    fn remove_swipe_gesture_oid(&mut self, gesture_oid: wl::common::ObjectId) {
+        self.swipe_gesture_oids.remove(&gesture_oid);
     }
 
-    fn create_surface(&mut self, oid: wl::common::ObjectId) -> SurfaceId {
-        let sid = self.coordinator.create_surface();
-        self.relate_sid_with_surface(sid, oid);
-        self.mediator.borrow_mut().relate_sid_to_client(sid, self.id);
-        sid
+    fn add_pinch_gesture_oid(&mut self, gesture_oid: wl::common::ObjectId) {
+        self.pinch_gesture_oids.insert(gesture_oid);
     }
 
-    fn destroy_surface(&self, sid: SurfaceId) {
-        self.coordinator.destroy_surface(sid)
+    fn remove_pinch_gesture_oid(&mut self, gesture_oid: wl::common::ObjectId) {
+        self.pinch_gesture_oids.remove(&gesture_oid);
     }
 
-    fn attach(&mut self, buffer_oid: wl::common::ObjectId, sid: SurfaceId, x: i32, y: i32) {
-        if buffer_oid.is_null() {
-            // Client wants to unmap this surface
+    fn add_idle_notification_oid(&mut self, notification_oid: wl::common::ObjectId, timeout: u32) {
+        self.idle_notification_oids.insert(notification_oid, timeout);
+        self.coordinator.add_idle_timeout(timeout);
+    }
+
+    fn remove_idle_notification_oid(&mut self, notification_oid: wl::common::ObjectId) {
+        if let Some(timeout) = self.idle_notification_oids.remove(&notification_oid) {
+            self.coordinator.remove_idle_timeout(timeout);
+        }
+        self.idled_notification_oids.remove(&notification_oid);
+    }
+
+    fn lock_session(&mut self, lock_oid: wl::common::ObjectId) {
+        if self.coordinator.lock_session() {
+            self.session_lock_oid = Some(lock_oid);
+            send!(ext_session_lock_v1::locked(&self.socket, lock_oid));
+        } else {
+            send!(ext_session_lock_v1::finished(&self.socket, lock_oid));
+        }
+    }
+
+    fn unlock_session(&mut self, lock_oid: wl::common::ObjectId) {
+        if self.session_lock_oid == Some(lock_oid) {
+            self.session_lock_oid = None;
+            self.coordinator.unlock_session();
+        }
+    }
+
+    fn destroy_session_lock(&mut self, lock_oid: wl::common::ObjectId) {
+        if self.session_lock_oid == Some(lock_oid) {
+            log_warn1!("Session lock destroyed without unlocking; session stays locked");
+            self.session_lock_oid = None;
+            self.coordinator.abandon_session_lock();
+        }
+    }
+
+    fn add_lock_surface(&mut self,
+                        lock_surface_oid: wl::common::ObjectId,
+                        surface_oid: wl::common::ObjectId,
+                        output_oid: wl::common::ObjectId) {
+        let sid = self.surface_oid_to_sid_dictionary.get(&surface_oid).cloned();
+        let output_id = self.output_oids.get(&output_oid).cloned();
+        if let (Some(sid), Some(output_id)) = (sid, output_id) {
+            self.relate_sid_with_shell_surface(sid, ShellSurfaceOid::SessionLock(lock_surface_oid));
+            if !self.coordinator.add_lock_surface(sid, output_id) {
+                log_warn2!("Surface {} can not cover output {} of locked session", sid, output_id);
+            }
+        }
+    }
+
+    fn remove_lock_surface(&mut self, surface_oid: wl::common::ObjectId) {
+        if let Some(&sid) = self.surface_oid_to_sid_dictionary.get(&surface_oid) {
+            self.unrelate_sid_with_shell_surface(sid);
+            self.coordinator.remove_lock_surface(sid);
+        }
+    }
+
+    fn add_layer_surface(&mut self,
+                         layer_surface_oid: wl::common::ObjectId,
+                         surface_oid: wl::common::ObjectId,
+                         output_oid: wl::common::ObjectId,
+                         layer: Layer,
+                         namespace: String)
+                         -> Option<LayerSurface> {
+        if let Some(&sid) = self.surface_oid_to_sid_dictionary.get(&surface_oid) {
+            let output_id = self.output_oids.get(&output_oid).cloned();
+            let state = LayerSurface::new(sid, output_id, layer, namespace);
+            let shell_surface_oid = ShellSurfaceOid::LayerSurface(layer_surface_oid);
+            self.relate_sid_with_shell_surface(sid, shell_surface_oid);
+            self.layer_surfaces.insert(sid, state.clone());
+            Some(state)
+        } else {
+            log_error!("Unknown surface object ID: {}", surface_oid);
+            None
+        }
+    }
+
+    fn set_pending_layer_surface(&mut self, state: LayerSurface) {
+        self.layer_surfaces.insert(state.sid, state);
+    }
+
+    fn remove_layer_surface(&mut self, surface_oid: wl::common::ObjectId) {
+        if let Some(&sid) = self.surface_oid_to_sid_dictionary.get(&surface_oid) {
+            self.unrelate_sid_with_shell_surface(sid);
+            self.layer_surfaces.remove(&sid);
+            self.coordinator.remove_layer_surface(sid);
+        }
+    }
+
+    fn add_output_oid(&mut self, output_id: i32, output_oid: wl::common::ObjectId) {
+        self.output_oids.insert(output_oid, output_id);
+    }
+
+    fn remove_output_oid(&mut self, output_oid: wl::common::ObjectId) {
+        self.output_oids.remove(&output_oid);
+        self.object_versions.remove(&output_oid);
+    }
+
+    fn add_xdg_output_oid(&mut self,
+                          xdg_output_oid: wl::common::ObjectId,
+                          output_oid: wl::common::ObjectId) {
+        self.xdg_output_oids.insert(xdg_output_oid, output_oid);
+        let info = self.output_oids.get(&output_oid).and_then(|id| self.output_infos.get(id));
+        if let Some(info) = info {
+            let version = self.get_object_version(xdg_output_oid);
+            protocol::xdg_output::send_info(&self.socket, xdg_output_oid, version, info, true);
+            if (version >= version::ZXDG_OUTPUT_WL_OUTPUT_DONE_SINCE) &&
+               (self.get_object_version(output_oid) >= version::WL_OUTPUT_DONE_SINCE) {
+                send!(wl_output::done(&self.socket, output_oid));
+            }
+        }
+    }
+
+    fn remove_xdg_output_oid(&mut self, xdg_output_oid: wl::common::ObjectId) {
+        self.xdg_output_oids.remove(&xdg_output_oid);
+        self.object_versions.remove(&xdg_output_oid);
+    }
+
+    fn add_output_manager_oid(&mut self, manager_oid: wl::common::ObjectId) {
+        if !self.privileged {
+            send!(zwlr_output_manager_v1::finished(&self.socket, manager_oid));
+            return;
+        }
+
+        let version = self.get_object_version(manager_oid);
+        let mut output_infos: Vec<OutputInfo> = self.output_infos.values().cloned().collect();
+        output_infos.sort_by_key(|info| info.id);
+
+        let mut heads = Vec::new();
+        for output_info in output_infos {
+            let head = self.create_output_head(&output_info);
+            protocol::output_management::send_head(&self.socket,
+                                                   manager_oid,
+                                                   version,
+                                                   &head,
+                                                   &output_info);
+            heads.push(head);
+        }
+        self.output_heads.insert(manager_oid, heads);
+        send!(zwlr_output_manager_v1::done(&self.socket,
+                                           manager_oid,
+                                           self.output_configuration_serial));
+    }
+
+    fn remove_output_manager_oid(&mut self, manager_oid: wl::common::ObjectId) {
+        if let Some(heads) = self.output_heads.remove(&manager_oid) {
+            for head in heads.iter() {
+                protocol::output_management::send_head_finished(&self.socket, head);
+            }
+            send!(zwlr_output_manager_v1::finished(&self.socket, manager_oid));
+        }
+        self.object_versions.remove(&manager_oid);
+    }
+
+    fn get_output_heads(&self, manager_oid: wl::common::ObjectId) -> Vec<OutputHead> {
+        self.output_heads.get(&manager_oid).cloned().unwrap_or_else(Vec::new)
+    }
+
+    fn request_output_configuration(&mut self,
+                                    configuration_oid: wl::common::ObjectId,
+                                    serial: u32,
+                                    configurations: Vec<OutputConfiguration>,
+                                    test: bool) {
+        if self.privileged && (serial == self.output_configuration_serial) {
+            let id = self.coordinator.request_output_configuration(configurations, test);
+            self.output_configuration_oids.insert(id, configuration_oid);
+        } else {
+            send!(zwlr_output_configuration_v1::cancelled(&self.socket, configuration_oid));
+        }
+    }
+
+    fn remove_output_configuration_oid(&mut self, configuration_oid: wl::common::ObjectId) {
+        self.output_configuration_oids.retain(|_, oid| *oid != configuration_oid);
+    }
+
+    fn add_screencopy_frame(&mut self,
+                            frame_oid: wl::common::ObjectId,
+                            output_oid: wl::common::ObjectId,
+                            region: Option<Area>) {
+        let output = self.output_oids
+            .get(&output_oid)
+            .and_then(|id| self.output_sizes.get(id).map(|&(size, scale)| (*id, size, scale)));
+        if let Some((output_id, size, scale)) = output {
+            // Region is given in logical coordinates while frames are captured in pixels
+            let screen = Area::new(Position::default(), size);
+            let area = match region {
+                Some(region) => region.scaled(scale).intersected(&screen),
+                None => Some(screen),
+            };
+
+            if let Some(area) = area {
+                send!(zwlr_screencopy_frame_v1::buffer(&self.socket,
+                                                       frame_oid,
+                                                       wl_shm::format::XRGB8888,
+                                                       area.size.width as u32,
+                                                       area.size.height as u32,
+                                                       4 * area.size.width as u32));
+                if self.supports(frame_oid, version::ZWLR_SCREENCOPY_FRAME_BUFFER_DONE_SINCE) {
+                    send!(zwlr_screencopy_frame_v1::buffer_done(&self.socket, frame_oid));
+                }
+
+                let info = ScreencopyInfo {
+                    output_id: output_id,
+                    area: area,
+                    buffer_oid: None,
+                    with_damage: false,
+                };
+                self.screencopy_frames.insert(frame_oid, info);
+                return;
+            }
+        }
+        send!(zwlr_screencopy_frame_v1::failed(&self.socket, frame_oid));
+    }
+
+    fn copy_screencopy_frame(&mut self,
+                             frame_oid: wl::common::ObjectId,
+                             buffer_oid: wl::common::ObjectId,
+                             with_damage: bool) {
+        let output_id = if let Some(info) = self.screencopy_frames.get_mut(&frame_oid) {
+            if info.buffer_oid.is_none() {
+                info.buffer_oid = Some(buffer_oid);
+                info.with_damage = with_damage;
+                Some(info.output_id)
+            } else {
+                log_warn2!("Screencopy frame {} was already used", frame_oid);
+                None
+            }
+        } else {
+            None
+        };
+
+        if let Some(output_id) = output_id {
+            self.coordinator.request_capture(output_id);
+        }
+    }
+
+    fn remove_screencopy_frame(&mut self, frame_oid: wl::common::ObjectId) {
+        self.screencopy_frames.remove(&frame_oid);
+        self.object_versions.remove(&frame_oid);
+    }
+
+    fn add_data_device_oid(&mut self, data_device_oid: wl::common::ObjectId) {
+        self.data_device_oids.insert(data_device_oid);
+    }
+
+    fn remove_data_device_oid(&mut self, data_device_oid: wl::common::ObjectId) {
+        self.data_device_oids.remove(&data_device_oid);
+        self.object_versions.remove(&data_device_oid);
+    }
+
+    fn add_data_control_device_oid(&mut self, data_device_oid: wl::common::ObjectId) {
+        self.data_control_device_oids.insert(data_device_oid);
+        self.new_data_control_device_oids.push(data_device_oid);
+    }
+
+    fn remove_data_control_device_oid(&mut self, data_device_oid: wl::common::ObjectId) {
+        self.data_control_device_oids.remove(&data_device_oid);
+        self.new_data_control_device_oids.retain(|oid| *oid != data_device_oid);
+    }
+
+    fn create_data_source(&mut self, source_oid: wl::common::ObjectId) {
+        self.data_sources.insert(source_oid, Transfer::new());
+    }
+
+    fn create_data_control_source(&mut self, source_oid: wl::common::ObjectId) {
+        self.data_sources.insert(source_oid, Transfer::new());
+        self.data_control_source_oids.insert(source_oid);
+    }
+
+    fn destroy_data_source(&mut self, source_oid: wl::common::ObjectId) {
+        self.data_sources.remove(&source_oid);
+        self.data_control_source_oids.remove(&source_oid);
+        if self.selection_source_oid == Some(source_oid) {
+            self.selection_source_oid = None;
+            self.mediator.borrow_mut().register_transferer(None);
+            self.coordinator.set_transfer(None);
+        }
+        if self.drag_source_oid == Some(source_oid) {
+            self.drag_source_oid = None;
+            self.mediator.borrow_mut().register_drag_source(None);
+            self.coordinator.end_drag(false);
+        }
+    }
+
+    fn add_mime_type(&mut self, source_oid: wl::common::ObjectId, mime_type: String) {
+        if let Some(transfer) = self.data_sources.get_mut(&source_oid) {
+            transfer.add_mime_type(mime_type);
+        } else {
+            log_warn3!("Unknown data source object ID: {}", source_oid);
+        }
+    }
+
+    fn set_selection(&mut self, source_oid: wl::common::ObjectId) {
+        if let Some(old_source_oid) = self.selection_source_oid {
+            if old_source_oid != source_oid {
+                self.send_source_cancelled(old_source_oid);
+            }
+        }
+
+        if source_oid.is_null() {
+            self.selection_source_oid = None;
+            self.mediator.borrow_mut().register_transferer(None);
+            self.coordinator.set_transfer(None);
+        } else if let Some(transfer) = self.data_sources.get(&source_oid) {
+            self.selection_source_oid = Some(source_oid);
+            self.mediator.borrow_mut().register_transferer(Some(self.id));
+            self.coordinator.set_transfer(Some(transfer.clone()));
+        } else {
+            log_warn3!("Unknown data source object ID: {}", source_oid);
+        }
+    }
+
+    fn request_transfer(&mut self, mime_type: String, fd: RawFd) {
+        self.coordinator.request_transfer(mime_type, fd);
+    }
+
+    fn start_drag(&mut self,
+                  source_oid: wl::common::ObjectId,
+                  origin_surface_oid: wl::common::ObjectId,
+                  icon_surface_oid: wl::common::ObjectId) {
+        if self.coordinator.get_drag().is_some() {
+            log_warn3!("Drag already in progress");
+            return;
+        }
+
+        let origin_sid = match self.surface_oid_to_sid_dictionary.get(&origin_surface_oid) {
+            Some(&sid) => sid,
+            None => {
+                log_error!("Unknown surface object ID: {}", origin_surface_oid);
+                return;
+            }
+        };
+
+        let icon_sid = self.surface_oid_to_sid_dictionary
+            .get(&icon_surface_oid)
+            .cloned()
+            .unwrap_or(SurfaceId::invalid());
+
+        let transfer = if source_oid.is_null() {
+            None
+        } else if let Some(transfer) = self.data_sources.get(&source_oid) {
+            self.drag_source_oid = Some(source_oid);
+            Some(transfer.clone())
+        } else {
+            log_warn3!("Unknown data source object ID: {}", source_oid);
+            return;
+        };
+
+        self.mediator.borrow_mut().register_drag_source(Some(self.id));
+        self.coordinator.start_drag(transfer, origin_sid, icon_sid);
+    }
+
+    fn request_drag_transfer(&mut self, mime_type: String, fd: RawFd) {
+        self.coordinator.request_drag_transfer(mime_type, fd);
+    }
+
+    fn request_move(&mut self, surface_oid: wl::common::ObjectId) {
+        if let Some(&sid) = self.surface_oid_to_sid_dictionary.get(&surface_oid) {
+            self.coordinator.request_move(sid);
+        } else {
+            log_error!("Unknown surface object ID: {}", surface_oid);
+        }
+    }
+
+    fn request_fullscreen(&mut self,
+                          surface_oid: wl::common::ObjectId,
+                          fullscreen: bool,
+                          output_oid: wl::common::ObjectId) {
+        if let Some(&sid) = self.surface_oid_to_sid_dictionary.get(&surface_oid) {
+            let output_id = self.output_oids.get(&output_oid).cloned();
+            self.coordinator.request_fullscreen(sid, fullscreen, output_id);
+        } else {
+            log_error!("Unknown surface object ID: {}", surface_oid);
+        }
+    }
+
+    fn request_maximized(&mut self, surface_oid: wl::common::ObjectId, maximized: bool) {
+        if let Some(&sid) = self.surface_oid_to_sid_dictionary.get(&surface_oid) {
+            self.coordinator.request_maximized(sid, maximized);
+        } else {
+            log_error!("Unknown surface object ID: {}", surface_oid);
+        }
+    }
+
+    fn request_initial_size(&mut self, surface_oid: wl::common::ObjectId) {
+        if let Some(&sid) = self.surface_oid_to_sid_dictionary.get(&surface_oid) {
+            self.coordinator.request_initial_size(sid);
+        } else {
+            log_error!("Unknown surface object ID: {}", surface_oid);
+        }
+    }
+
+    fn finish_drag(&mut self) {
+        if let Some(drag) = self.coordinator.get_drag() {
+            if drag.state == DragState::Dropped {
+                self.coordinator.end_drag(true);
+            }
+        }
+    }
+
+    fn set_positioner(&mut self, oid: wl::common::ObjectId, positioner: Positioner) {
+        self.positioners.insert(oid, positioner);
+    }
+
+    fn get_positioner(&mut self, oid: wl::common::ObjectId) -> Option<Positioner> {
+        if let Some(positioner) = self.positioners.get(&oid) {
+            Some(*positioner)
+        } else {
+            None
+        }
+    }
+
+    fn remove_positioner(&mut self, oid: wl::common::ObjectId) {
+        self.positioners.remove(&oid);
+    }
+
+    fn set_input_region(&self, sid: SurfaceId, region_oid: wl::common::ObjectId) {
+        if let Some(region) = self.regions.get(&region_oid) {
+            self.coordinator.set_surface_offset(sid, region.pos);
+            self.coordinator.set_surface_requested_size(sid, region.size);
+        } else {
+            // TODO: Implement reseting input region.
+        }
+    }
+
+    fn set_opaque_region(&self, sid: SurfaceId, region_oid: wl::common::ObjectId) {
+        let region = self.regions.get(&region_oid).cloned();
+        self.coordinator.set_surface_opaque_region(sid, region);
+    }
+
+    fn create_surface(&mut self, oid: wl::common::ObjectId, version: u32) -> SurfaceId {
+        let sid = self.coordinator.create_surface();
+        self.relate_sid_with_surface(sid, oid);
+        if let Some(info) = self.sid_to_surface_info_dictionary.get_mut(&sid) {
+            info.surface_version = version;
+        }
+        self.mediator.borrow_mut().relate_sid_to_client(sid, self.id);
+        sid
+    }
+
+    fn destroy_surface(&mut self, sid: SurfaceId) {
+        let mut feedback_oids = Vec::new();
+        if let Some(info) = self.sid_to_surface_info_dictionary.get_mut(&sid) {
+            feedback_oids.extend(info.feedback_oids.drain(..));
+            feedback_oids.extend(info.committed_feedback_oids.drain(..));
+            feedback_oids.extend(info.drawn_feedback_oids.drain(..));
+        }
+        self.discard_feedbacks(feedback_oids);
+        self.coordinator.destroy_surface(sid)
+    }
+
+    fn attach(&mut self, buffer_oid: wl::common::ObjectId, sid: SurfaceId, x: i32, y: i32) {
+        if buffer_oid.is_null() {
+            // Client wants to unmap this surface
             // TODO: This should be done on commit
             self.coordinator.unrelate_surface(sid);
             self.coordinator.detach_surface(sid)
         } else if let Some(&info) = self.buffer_oid_to_buffer_info_dictionary.get(&buffer_oid) {
             self.relate_sid_with_buffer(sid, buffer_oid);
-            self.coordinator.attach(info.mvid, sid);
+            match info {
+                BufferInfo::MemoryView(mvid) => self.coordinator.attach(mvid, sid),
+                BufferInfo::HwImage(hiid) => self.coordinator.attach_hw_image(hiid, sid),
+            }
         } else {
             log_error!("Unknown buffer object ID: {}", buffer_oid);
         }
     }
 
-    fn commit(&self, sid: SurfaceId) {
+    fn damage(&self, sid: SurfaceId, area: Area) {
+        self.coordinator.damage_surface(sid, area);
+    }
+
+    fn damage_buffer(&self, sid: SurfaceId, area: Area) {
+        self.coordinator.damage_surface_buffer(sid, area);
+    }
+
+    fn set_buffer_scale(&self, sid: SurfaceId, scale: u32) {
+        self.coordinator.set_surface_buffer_scale(sid, scale);
+    }
+
+    fn set_buffer_transform(&self, sid: SurfaceId, transform: Transform) {
+        self.coordinator.set_surface_buffer_transform(sid, transform);
+    }
+
+    fn commit(&mut self, sid: SurfaceId) {
+        if let Some(state) = self.layer_surfaces.get(&sid) {
+            self.coordinator.set_layer_surface(state.clone());
+        }
+
+        // Content committed earlier but not drawn yet is superseded by the new one.
+        let mut superseded_oids = Vec::new();
+        if let Some(info) = self.sid_to_surface_info_dictionary.get_mut(&sid) {
+            superseded_oids = std::mem::replace(&mut info.committed_feedback_oids,
+                                                info.feedback_oids.drain(..).collect());
+        }
+        self.discard_feedbacks(superseded_oids);
+
         self.coordinator.commit_surface(sid);
     }
 
@@ -409,6 +1623,116 @@ impl Facade for Proxy {
         self.coordinator.set_surface_requested_size(sid, size);
     }
 
+    fn set_size_limits(&self, surface_oid: wl::common::ObjectId, min_size: Size, max_size: Size) {
+        if let Some(&sid) = self.surface_oid_to_sid_dictionary.get(&surface_oid) {
+            self.coordinator.set_surface_size_limits(sid, min_size, max_size);
+        }
+    }
+
+    fn set_app_id(&self, surface_oid: wl::common::ObjectId, app_id: String) {
+        if let Some(&sid) = self.surface_oid_to_sid_dictionary.get(&surface_oid) {
+            self.coordinator.set_surface_app_id(sid, app_id);
+        }
+    }
+
+    fn set_title(&self, surface_oid: wl::common::ObjectId, title: String) {
+        if let Some(&sid) = self.surface_oid_to_sid_dictionary.get(&surface_oid) {
+            self.coordinator.set_surface_title(sid, title);
+        }
+    }
+
+    fn add_decoration_oid(&mut self,
+                          surface_oid: wl::common::ObjectId,
+                          decoration_oid: wl::common::ObjectId)
+                          -> bool {
+        if let Some(&sid) = self.surface_oid_to_sid_dictionary.get(&surface_oid) {
+            if let Some(info) = self.sid_to_surface_info_dictionary.get_mut(&sid) {
+                if info.decoration_oid.is_some() {
+                    return false;
+                }
+                info.decoration_oid = Some(decoration_oid);
+            }
+        }
+        true
+    }
+
+    fn remove_decoration_oid(&mut self, surface_oid: wl::common::ObjectId) {
+        if let Some(&sid) = self.surface_oid_to_sid_dictionary.get(&surface_oid) {
+            unrelate_sid_with!(decoration_oid, self.sid_to_surface_info_dictionary, sid);
+        }
+    }
+
+    fn add_fractional_scale_oid(&mut self,
+                                surface_oid: wl::common::ObjectId,
+                                fractional_scale_oid: wl::common::ObjectId)
+                                -> bool {
+        if let Some(&sid) = self.surface_oid_to_sid_dictionary.get(&surface_oid) {
+            if let Some(info) = self.sid_to_surface_info_dictionary.get_mut(&sid) {
+                if info.fractional_scale_oid.is_some() {
+                    return false;
+                }
+                info.fractional_scale_oid = Some(fractional_scale_oid);
+                send!(wp_fractional_scale_v1::preferred_scale(&self.socket,
+                                                              fractional_scale_oid,
+                                                              to_fractional(info.preferred_scale)));
+            }
+        }
+        true
+    }
+
+    fn remove_fractional_scale_oid(&mut self, surface_oid: wl::common::ObjectId) {
+        if let Some(&sid) = self.surface_oid_to_sid_dictionary.get(&surface_oid) {
+            unrelate_sid_with!(fractional_scale_oid, self.sid_to_surface_info_dictionary, sid);
+        }
+    }
+
+    fn add_presentation_feedback(&mut self,
+                                 surface_oid: wl::common::ObjectId,
+                                 feedback_oid: wl::common::ObjectId) {
+        if let Some(&sid) = self.surface_oid_to_sid_dictionary.get(&surface_oid) {
+            if let Some(info) = self.sid_to_surface_info_dictionary.get_mut(&sid) {
+                info.feedback_oids.push(feedback_oid);
+                return;
+            }
+        }
+        self.discard_feedbacks(vec![feedback_oid]);
+    }
+
+    fn request_decoration_mode(&self,
+                               surface_oid: wl::common::ObjectId,
+                               mode: Option<DecorationMode>) {
+        if let Some(&sid) = self.surface_oid_to_sid_dictionary.get(&surface_oid) {
+            self.coordinator.request_decoration_mode(sid, mode);
+        }
+    }
+
+    fn set_window_geometry(&self, surface_oid: wl::common::ObjectId, geometry: Area) {
+        if let Some(&sid) = self.surface_oid_to_sid_dictionary.get(&surface_oid) {
+            self.coordinator.set_surface_window_geometry(sid, geometry);
+        }
+    }
+
+    fn add_configure_serial(&mut self, surface_oid: wl::common::ObjectId, serial: u32) {
+        if let Some(sid) = self.surface_oid_to_sid_dictionary.get(&surface_oid) {
+            if let Some(info) = self.sid_to_surface_info_dictionary.get_mut(sid) {
+                info.configure_serials.push(serial);
+            }
+        }
+    }
+
+    fn ack_configure(&mut self, surface_oid: wl::common::ObjectId, serial: u32) -> bool {
+        if let Some(sid) = self.surface_oid_to_sid_dictionary.get(&surface_oid) {
+            if let Some(info) = self.sid_to_surface_info_dictionary.get_mut(sid) {
+                let position = info.configure_serials.iter().position(|s| *s == serial);
+                if let Some(position) = position {
+                    info.configure_serials.drain(0..(position + 1));
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
     fn set_relative_position(&self, surface_oid: wl::common::ObjectId, x: isize, y: isize) {
         if let Some(&sid) = self.surface_oid_to_sid_dictionary.get(&surface_oid) {
             let position = Position::new(x, y);
@@ -416,6 +1740,16 @@ impl Facade for Proxy {
         }
     }
 
+    fn place_popup(&mut self,
+                   surface_oid: wl::common::ObjectId,
+                   shell_surface_oid: ShellSurfaceOid,
+                   positioner: Positioner) {
+        if let Some(&sid) = self.surface_oid_to_sid_dictionary.get(&surface_oid) {
+            self.relate_sid_with_shell_surface(sid, shell_surface_oid);
+            self.coordinator.set_surface_positioner(sid, positioner);
+        }
+    }
+
     fn relate(&self, surface_oid: wl::common::ObjectId, parent_surface_oid: wl::common::ObjectId) {
         if let Some(&sid) = self.surface_oid_to_sid_dictionary.get(&surface_oid) {
             if let Some(&parent_sid) = self.surface_oid_to_sid_dictionary.get(&parent_surface_oid) {
@@ -444,11 +1778,106 @@ impl Facade for Proxy {
 #[allow(unused_variables)]
 impl Gateway for Proxy {
     fn on_display_created(&mut self, output_info: OutputInfo) {
-        self.register_global(protocol::output::get_global(output_info));
+        let output_id = output_info.id;
+        self.update_output_size(&output_info);
+        self.output_infos.insert(output_id, output_info.clone());
+
+        let manager_oids: Vec<wl::common::ObjectId> = self.output_heads.keys().cloned().collect();
+        for manager_oid in manager_oids {
+            let head = self.create_output_head(&output_info);
+            let version = self.get_object_version(manager_oid);
+            protocol::output_management::send_head(&self.socket,
+                                                   manager_oid,
+                                                   version,
+                                                   &head,
+                                                   &output_info);
+            if let Some(heads) = self.output_heads.get_mut(&manager_oid) {
+                heads.push(head);
+            }
+        }
+        if !self.output_heads.is_empty() {
+            self.send_output_managers_done();
+        }
+
+        let name = self.register_global(protocol::output::get_global(output_info));
+        self.output_globals.insert(output_id, name);
+    }
+
+    fn on_display_changed(&mut self, output_info: OutputInfo) {
+        let output_id = output_info.id;
+        self.update_output_size(&output_info);
+        if let Some(&name) = self.output_globals.get(&output_id) {
+            self.replace_global(name, protocol::output::get_global(output_info.clone()));
+        }
+
+        let output_oids: Vec<wl::common::ObjectId> = self.output_oids
+            .iter()
+            .filter(|&(_, id)| *id == output_id)
+            .map(|(oid, _)| *oid)
+            .collect();
+        for output_oid in output_oids.iter() {
+            // Logical geometry has to be sent before `wl_output.done`
+            for (xdg_output_oid, _) in self.xdg_output_oids
+                .iter()
+                .filter(|&(_, oid)| oid == output_oid) {
+                let version = self.get_object_version(*xdg_output_oid);
+                protocol::xdg_output::send_info(&self.socket,
+                                                *xdg_output_oid,
+                                                version,
+                                                &output_info,
+                                                false);
+            }
+
+            let version = self.get_object_version(*output_oid);
+            protocol::output::send_info(&self.socket, *output_oid, version, &output_info);
+        }
+
+        for heads in self.output_heads.values() {
+            for head in heads.iter().filter(|head| head.output_id == output_id) {
+                protocol::output_management::send_head_state(&self.socket, head, &output_info);
+            }
+        }
+        if !self.output_heads.is_empty() {
+            self.send_output_managers_done();
+        }
+
+        self.output_infos.insert(output_id, output_info);
+    }
+
+    fn on_display_destroyed(&mut self, output_id: i32) {
+        if let Some(name) = self.output_globals.remove(&output_id) {
+            self.unregister_global(name);
+        }
+
+        // Bound objects stay valid, but are not related to any output any more
+        self.output_oids.retain(|_, id| *id != output_id);
+        self.output_sizes.remove(&output_id);
+        self.output_infos.remove(&output_id);
+
+        for heads in self.output_heads.values_mut() {
+            for head in heads.iter().filter(|head| head.output_id == output_id) {
+                protocol::output_management::send_head_finished(&self.socket, head);
+            }
+            heads.retain(|head| head.output_id != output_id);
+        }
+        if !self.output_heads.is_empty() {
+            self.send_output_managers_done();
+        }
+
+        // Pending captures of the output will never be ready
+        let frame_oids: Vec<wl::common::ObjectId> = self.screencopy_frames
+            .iter()
+            .filter(|&(_, info)| info.output_id == output_id)
+            .map(|(oid, _)| *oid)
+            .collect();
+        for frame_oid in frame_oids {
+            self.screencopy_frames.remove(&frame_oid);
+            send!(zwlr_screencopy_frame_v1::failed(&self.socket, frame_oid));
+        }
     }
 
     fn on_keyboard_input(&mut self, key: Key, mods: Option<KeyMods>) {
-        for &keyboard_oid in self.keyboard_oids.iter() {
+        for keyboard_oid in self.get_keyboard_oids(key.seat) {
             let mut serial = self.socket.get_next_serial();
             send!(wl_keyboard::key(&self.socket,
                                    keyboard_oid,
@@ -465,7 +1894,7 @@ impl Gateway for Proxy {
                                              mods.depressed,
                                              mods.latched,
                                              mods.locked,
-                                             mods.effective));
+                                             mods.layout));
             }
         }
     }
@@ -484,6 +1913,9 @@ impl Gateway for Proxy {
                 send!(wl_buffer::release(&self.socket, buffer_oid));
             }
             info.buffer_oid = None;
+
+            let committed_feedback_oids: Vec<_> = info.committed_feedback_oids.drain(..).collect();
+            info.drawn_feedback_oids.extend(committed_feedback_oids);
         }
     }
 
@@ -552,10 +1984,13 @@ impl Gateway for Proxy {
         axis.continuous.y = -1.0 * axis.continuous.y;
 
         for pointer_oid in self.pointer_oids.iter() {
+            // `axis_discrete`, `axis_stop` and `frame` are not known to older clients
+            let framed = self.supports(*pointer_oid, version::WL_POINTER_FRAME_SINCE);
+
             // vertical scroll
             let axis_type = wl_pointer::axis::VERTICAL_SCROLL;
 
-            if axis.discrete.y != 0 {
+            if framed && (axis.discrete.y != 0) {
                 send!(wl_pointer::axis_discrete(&self.socket,
                                                 *pointer_oid,
                                                 axis_type,
@@ -568,7 +2003,7 @@ impl Gateway for Proxy {
                                        axis.time.get_value() as u32,
                                        axis_type,
                                        axis.continuous.y));
-            } else {
+            } else if framed {
                 send!(wl_pointer::axis_stop(&self.socket,
                                             *pointer_oid,
                                             axis.time.get_value() as u32,
@@ -578,7 +2013,7 @@ impl Gateway for Proxy {
             // horizontal scroll
             let axis_type = wl_pointer::axis::HORIZONTAL_SCROLL;
 
-            if axis.discrete.x != 0 {
+            if framed && (axis.discrete.x != 0) {
                 send!(wl_pointer::axis_discrete(&self.socket,
                                                 *pointer_oid,
                                                 axis_type,
@@ -591,7 +2026,7 @@ impl Gateway for Proxy {
                                        axis.time.get_value() as u32,
                                        axis_type,
                                        axis.continuous.x));
-            } else {
+            } else if framed {
                 send!(wl_pointer::axis_stop(&self.socket,
                                             *pointer_oid,
                                             axis.time.get_value() as u32,
@@ -599,59 +2034,197 @@ impl Gateway for Proxy {
             }
 
             // send frame
-            send!(wl_pointer::frame(&self.socket, *pointer_oid));
+            if framed {
+                send!(wl_pointer::frame(&self.socket, *pointer_oid));
+            }
         }
     }
 
-    fn on_keyboard_focus_changed(&mut self, old_sid: SurfaceId, new_sid: SurfaceId) {
-        if old_sid != SurfaceId::invalid() {
-            if let Some(surface_info) = self.sid_to_surface_info_dictionary.get(&old_sid) {
-                if let Some(surface_oid) = surface_info.surface_oid {
-                    for keyboard_oid in self.keyboard_oids.iter() {
-                        let serial = self.socket.get_next_serial();
-                        send!(wl_keyboard::leave(&self.socket, *keyboard_oid, serial, surface_oid));
+    fn on_touch_down(&self, sid: SurfaceId, id: i32, position: Position, time: Milliseconds) {
+        let surface_oid = match self.sid_to_surface_info_dictionary
+            .get(&sid)
+            .and_then(|info| info.surface_oid) {
+            Some(surface_oid) => surface_oid,
+            None => return,
+        };
 
-                        if let Some(window_info) = self.coordinator.get_surface(old_sid) {
-                            self.on_surface_reconfigured(old_sid,
-                                                         window_info.desired_size,
-                                                         window_info.state_flags);
-                        }
-                    }
+        let serial = self.socket.get_next_serial();
+        for touch_oid in self.touch_oids.iter() {
+            send!(wl_touch::down(&self.socket,
+                                 *touch_oid,
+                                 serial,
+                                 time.get_value() as u32,
+                                 surface_oid,
+                                 id,
+                                 position.x as f32,
+                                 position.y as f32));
+        }
+    }
+
+    fn on_touch_motion(&self, _sid: SurfaceId, id: i32, position: Position, time: Milliseconds) {
+        for touch_oid in self.touch_oids.iter() {
+            send!(wl_touch::motion(&self.socket,
+                                   *touch_oid,
+                                   time.get_value() as u32,
+                                   id,
+                                   position.x as f32,
+                                   position.y as f32));
+        }
+    }
+
+    fn on_touch_up(&self, _sid: SurfaceId, id: i32, time: Milliseconds) {
+        let serial = self.socket.get_next_serial();
+        for touch_oid in self.touch_oids.iter() {
+            send!(wl_touch::up(&self.socket, *touch_oid, serial, time.get_value() as u32, id));
+        }
+    }
+
+    fn on_touch_frame(&self, _sid: SurfaceId) {
+        for touch_oid in self.touch_oids.iter() {
+            send!(wl_touch::frame(&self.socket, *touch_oid));
+        }
+    }
+
+    fn on_gesture_begin(&self,
+                        sid: SurfaceId,
+                        kind: GestureKind,
+                        fingers: u32,
+                        time: Milliseconds) {
+        let surface_oid = match self.sid_to_surface_info_dictionary
+            .get(&sid)
+            .and_then(|info| info.surface_oid) {
+            Some(surface_oid) => surface_oid,
+            None => return,
+        };
+
+        let serial = self.socket.get_next_serial();
+        let time = time.get_value() as u32;
+        match kind {
+            GestureKind::Swipe => {
+                for oid in self.swipe_gesture_oids.iter() {
+                    send!(zwp_pointer_gesture_swipe_v1::begin(&self.socket,
+                                                              *oid,
+                                                              serial,
+                                                              time,
+                                                              surface_oid,
+                                                              fingers));
+                }
+            }
+            GestureKind::Pinch => {
+                for oid in self.pinch_gesture_oids.iter() {
+                    send!(zwp_pointer_gesture_pinch_v1::begin(&self.socket,
+                                                              *oid,
+                                                              serial,
+                                                              time,
+                                                              surface_oid,
+                                                              fingers));
                 }
             }
         }
+    }
 
-        if new_sid != SurfaceId::invalid() {
-            if let Some(surface_info) = self.sid_to_surface_info_dictionary.get(&new_sid) {
-                if let Some(surface_oid) = surface_info.surface_oid {
-                    for keyboard_oid in self.keyboard_oids.iter() {
-                        let serial = self.socket.get_next_serial();
+    fn on_gesture_update(&self, _sid: SurfaceId, kind: GestureKind, motion: GestureMotion) {
+        let time = motion.time.get_value() as u32;
+        match kind {
+            GestureKind::Swipe => {
+                for oid in self.swipe_gesture_oids.iter() {
+                    send!(zwp_pointer_gesture_swipe_v1::update(&self.socket,
+                                                               *oid,
+                                                               time,
+                                                               motion.delta.x,
+                                                               motion.delta.y));
+                }
+            }
+            GestureKind::Pinch => {
+                for oid in self.pinch_gesture_oids.iter() {
+                    send!(zwp_pointer_gesture_pinch_v1::update(&self.socket,
+                                                               *oid,
+                                                               time,
+                                                               motion.delta.x,
+                                                               motion.delta.y,
+                                                               motion.scale,
+                                                               motion.rotation));
+                }
+            }
+        }
+    }
 
-                        // TODO: Pass correct keys on keyboard enter.
-                        let keys: [u32; 0] = [0; 0];
+    fn on_gesture_end(&self,
+                      _sid: SurfaceId,
+                      kind: GestureKind,
+                      cancelled: bool,
+                      time: Milliseconds) {
+        let serial = self.socket.get_next_serial();
+        let time = time.get_value() as u32;
+        let cancelled = if cancelled { 1 } else { 0 };
+        match kind {
+            GestureKind::Swipe => {
+                for oid in self.swipe_gesture_oids.iter() {
+                    send!(zwp_pointer_gesture_swipe_v1::end(&self.socket,
+                                                            *oid,
+                                                            serial,
+                                                            time,
+                                                            cancelled));
+                }
+            }
+            GestureKind::Pinch => {
+                for oid in self.pinch_gesture_oids.iter() {
+                    send!(zwp_pointer_gesture_pinch_v1::end(&self.socket,
+                                                            *oid,
+                                                            serial,
+                                                            time,
+                                                            cancelled));
+                }
+            }
+        }
+    }
 
-                        send!(wl_keyboard::enter(&self.socket,
-                                                 *keyboard_oid,
-                                                 serial,
-                                                 surface_oid,
-                                                 &keys[..]));
+    fn on_keyboard_focus_changed(&mut self, old_sid: SurfaceId, new_sid: SurfaceId) {
+        let seat = SeatId::default_seat();
+        let reconfigured = self.send_keyboard_focus_change(seat, old_sid, new_sid);
 
-                        if let Some(window_info) = self.coordinator.get_surface(new_sid) {
-                            self.on_surface_reconfigured(new_sid,
-                                                         window_info.desired_size,
-                                                         window_info.state_flags);
-                        }
-                    }
-                }
+        // Activated state changed
+        for sid in reconfigured {
+            if let Some(info) = self.coordinator.get_surface(sid) {
+                self.on_surface_reconfigured(sid, info.desired_size, info.state_flags);
             }
         }
     }
 
-    fn on_surface_reconfigured(&self,
+    fn on_seat_keyboard_focus_changed(&mut self,
+                                      seat: SeatId,
+                                      old_sid: SurfaceId,
+                                      new_sid: SurfaceId) {
+        self.send_keyboard_focus_change(seat, old_sid, new_sid);
+    }
+
+    fn on_seat_capabilities_changed(&mut self, seat: SeatId, capabilities: SeatCapabilities) {
+        for (oid, oid_seat) in self.seat_oids.iter() {
+            if *oid_seat == seat {
+                protocol::seat::send_capabilities(&self.socket, *oid, capabilities);
+            }
+        }
+    }
+
+    fn on_idle(&mut self, timeout: u32) {
+        for (oid, notification_timeout) in self.idle_notification_oids.iter() {
+            if (*notification_timeout <= timeout) && self.idled_notification_oids.insert(*oid) {
+                send!(ext_idle_notification_v1::idled(&self.socket, *oid));
+            }
+        }
+    }
+
+    fn on_resumed(&mut self) {
+        for oid in self.idled_notification_oids.drain() {
+            send!(ext_idle_notification_v1::resumed(&self.socket, oid));
+        }
+    }
+
+    fn on_surface_reconfigured(&mut self,
                                sid: SurfaceId,
                                size: Size,
                                state_flags: surface_state::SurfaceState) {
-        if let Some(info) = self.sid_to_surface_info_dictionary.get(&sid) {
+        if let Some(info) = self.sid_to_surface_info_dictionary.get_mut(&sid) {
             if let Some(shell_surface) = info.shell_surface_oid {
                 match shell_surface {
                     ShellSurfaceOid::Shell(shell_surface_oid) => {
@@ -662,9 +2235,13 @@ impl Gateway for Proxy {
                                                           size.height as i32));
                     }
                     ShellSurfaceOid::ZxdgToplevelV6(shell_surface_oid, shell_toplevel_oid) => {
+                        // `zxdg_toplevel_v6` has no `suspended` state. Suspended clients are
+                        // throttled only by not receiving frame callbacks. Neither protocol has
+                        // tiled states so tiled surfaces are told to be maximized to make them
+                        // fill their frames and not draw shadows.
                         let mut pos = 0;
                         let mut states: [u32; 2] = [0; 2];
-                        if state_flags.intersects(surface_state::MAXIMIZED) {
+                        if state_flags.intersects(surface_state::MAXIMIZED | surface_state::TILED) {
                             states[pos] = zxdg_toplevel_v6::state::MAXIMIZED;
                             pos += 1;
                         }
@@ -680,6 +2257,81 @@ impl Gateway for Proxy {
                         let serial = self.socket.get_next_serial();
                         send!(zxdg_surface_v6::configure(&self.socket, shell_surface_oid, serial));
                     }
+                    ShellSurfaceOid::XdgToplevel(shell_surface_oid, shell_toplevel_oid) => {
+                        let mut pos = 0;
+                        let mut states: [u32; 4] = [0; 4];
+                        if state_flags.intersects(surface_state::MAXIMIZED | surface_state::TILED) {
+                            states[pos] = xdg_toplevel::state::MAXIMIZED;
+                            pos += 1;
+                        }
+                        if state_flags.intersects(surface_state::FULLSCREEN) {
+                            states[pos] = xdg_toplevel::state::FULLSCREEN;
+                            pos += 1;
+                        }
+                        if state_flags.intersects(surface_state::RESIZING) {
+                            states[pos] = xdg_toplevel::state::RESIZING;
+                            pos += 1;
+                        }
+                        if sid == self.coordinator.get_keyboard_focused_sid() {
+                            states[pos] = xdg_toplevel::state::ACTIVATED;
+                            pos += 1;
+                        }
+                        send!(xdg_toplevel::configure(&self.socket,
+                                                      shell_toplevel_oid,
+                                                      size.width as i32,
+                                                      size.height as i32,
+                                                      &states[0..pos]));
+                        let serial = self.socket.get_next_serial();
+                        send!(xdg_surface::configure(&self.socket, shell_surface_oid, serial));
+                        info.configure_serials.push(serial);
+                    }
+                    ShellSurfaceOid::ZxdgPopupV6(shell_surface_oid, shell_popup_oid) => {
+                        let position = self.coordinator
+                            .get_surface(sid)
+                            .map(|surface| surface.relative_position)
+                            .unwrap_or_default();
+                        send!(zxdg_popup_v6::configure(&self.socket,
+                                                       shell_popup_oid,
+                                                       position.x as i32,
+                                                       position.y as i32,
+                                                       size.width as i32,
+                                                       size.height as i32));
+                        let serial = self.socket.get_next_serial();
+                        send!(zxdg_surface_v6::configure(&self.socket, shell_surface_oid, serial));
+                    }
+                    ShellSurfaceOid::XdgPopup(shell_surface_oid, shell_popup_oid) => {
+                        let position = self.coordinator
+                            .get_surface(sid)
+                            .map(|surface| surface.relative_position)
+                            .unwrap_or_default();
+                        send!(xdg_popup::configure(&self.socket,
+                                                   shell_popup_oid,
+                                                   position.x as i32,
+                                                   position.y as i32,
+                                                   size.width as i32,
+                                                   size.height as i32));
+                        let serial = self.socket.get_next_serial();
+                        send!(xdg_surface::configure(&self.socket, shell_surface_oid, serial));
+                        info.configure_serials.push(serial);
+                    }
+                    ShellSurfaceOid::SessionLock(lock_surface_oid) => {
+                        let serial = self.socket.get_next_serial();
+                        send!(ext_session_lock_surface_v1::configure(&self.socket,
+                                                                     lock_surface_oid,
+                                                                     serial,
+                                                                     size.width as u32,
+                                                                     size.height as u32));
+                        info.configure_serials.push(serial);
+                    }
+                    ShellSurfaceOid::LayerSurface(layer_surface_oid) => {
+                        let serial = self.socket.get_next_serial();
+                        send!(zwlr_layer_surface_v1::configure(&self.socket,
+                                                               layer_surface_oid,
+                                                               serial,
+                                                               size.width as u32,
+                                                               size.height as u32));
+                        info.configure_serials.push(serial);
+                    }
                 }
             } else {
                 log_warn3!("Received reconfiguration request for surface {:?} \
@@ -688,6 +2340,144 @@ impl Gateway for Proxy {
             }
         }
     }
+
+    fn on_surface_close_requested(&self, sid: SurfaceId) {
+        if let Some(info) = self.sid_to_surface_info_dictionary.get(&sid) {
+            match info.shell_surface_oid {
+                Some(ShellSurfaceOid::ZxdgToplevelV6(_, shell_toplevel_oid)) => {
+                    send!(zxdg_toplevel_v6::close(&self.socket, shell_toplevel_oid));
+                }
+                Some(ShellSurfaceOid::XdgToplevel(_, shell_toplevel_oid)) => {
+                    send!(xdg_toplevel::close(&self.socket, shell_toplevel_oid));
+                }
+                _ => log_warn3!("Surface {} can not be asked to close", sid),
+            }
+        }
+    }
+
+    fn on_decoration_mode_changed(&mut self, sid: SurfaceId) {
+        let decoration_oid = if let Some(info) = self.sid_to_surface_info_dictionary.get(&sid) {
+            info.decoration_oid
+        } else {
+            None
+        };
+
+        if let Some(decoration_oid) = decoration_oid {
+            if let Some(surface) = self.coordinator.get_surface(sid) {
+                let mode = match surface.decoration_mode {
+                    DecorationMode::ClientSide => zxdg_toplevel_decoration_v1::mode::CLIENT_SIDE,
+                    DecorationMode::ServerSide => zxdg_toplevel_decoration_v1::mode::SERVER_SIDE,
+                };
+                send!(zxdg_toplevel_decoration_v1::configure(&self.socket, decoration_oid, mode));
+
+                // Decoration configuration has to be followed by surface configuration.
+                self.on_surface_reconfigured(sid, surface.desired_size, surface.state_flags);
+            }
+        }
+    }
+
+    fn on_surface_output_entered(&self, sid: SurfaceId, output_id: i32) {
+        if let Some(info) = self.sid_to_surface_info_dictionary.get(&sid) {
+            if let Some(surface_oid) = info.surface_oid {
+                for (output_oid, _) in self.output_oids.iter().filter(|&(_, id)| *id == output_id) {
+                    send!(wl_surface::enter(&self.socket, surface_oid, *output_oid));
+                }
+            }
+        }
+    }
+
+    fn on_surface_output_left(&self, sid: SurfaceId, output_id: i32) {
+        if let Some(info) = self.sid_to_surface_info_dictionary.get(&sid) {
+            if let Some(surface_oid) = info.surface_oid {
+                for (output_oid, _) in self.output_oids.iter().filter(|&(_, id)| *id == output_id) {
+                    send!(wl_surface::leave(&self.socket, surface_oid, *output_oid));
+                }
+            }
+        }
+    }
+
+    fn on_preferred_buffer_changed(&mut self, sid: SurfaceId, scale: f32, transform: Transform) {
+        if let Some(info) = self.sid_to_surface_info_dictionary.get_mut(&sid) {
+            info.preferred_scale = scale;
+            if let Some(surface_oid) = info.surface_oid {
+                if info.surface_version >= version::WL_SURFACE_PREFERRED_BUFFER_SINCE {
+                    // Integer scale is rounded up so clients rather downscale than upscale
+                    send!(wl_surface::preferred_buffer_scale(&self.socket,
+                                                             surface_oid,
+                                                             scale.ceil() as i32));
+                    send!(wl_surface::preferred_buffer_transform(&self.socket,
+                                                                 surface_oid,
+                                                                 translate_transform(transform)));
+                }
+            }
+            if let Some(fractional_scale_oid) = info.fractional_scale_oid {
+                send!(wp_fractional_scale_v1::preferred_scale(&self.socket,
+                                                              fractional_scale_oid,
+                                                              to_fractional(scale)));
+            }
+        }
+    }
+
+    fn on_transfer_requested(&mut self, mime_type: String, fd: RawFd) {
+        if let Some(source_oid) = self.selection_source_oid {
+            self.send_source_send(source_oid, &mime_type, fd);
+        }
+    }
+
+    fn on_drag_motion(&self, _sid: SurfaceId, position: Position, milliseconds: Milliseconds) {
+        for data_device_oid in self.data_device_oids.iter() {
+            send!(wl_data_device::motion(&self.socket,
+                                         *data_device_oid,
+                                         milliseconds.get_value() as u32,
+                                         position.x as f32,
+                                         position.y as f32));
+        }
+    }
+
+    fn on_drag_transfer_requested(&mut self, mime_type: String, fd: RawFd) {
+        if let Some(source_oid) = self.drag_source_oid {
+            send!(wl_data_source::send(&self.socket, source_oid, &mime_type, fd));
+        }
+    }
+
+    fn on_output_captured(&mut self, output_id: i32, frame: Buffer) {
+        let frames: Vec<(wl::common::ObjectId, ScreencopyInfo)> = self.screencopy_frames
+            .iter()
+            .filter(|&(_, info)| info.output_id == output_id && info.buffer_oid.is_some())
+            .map(|(oid, info)| (*oid, *info))
+            .collect();
+
+        for (frame_oid, info) in frames {
+            // Frame may be used only once
+            self.screencopy_frames.remove(&frame_oid);
+            if self.copy_captured_frame(&info, &frame) {
+                self.send_screencopy_ready(frame_oid, &info);
+            } else {
+                log_warn2!("Buffer of screencopy frame {} is not valid", frame_oid);
+                send!(zwlr_screencopy_frame_v1::failed(&self.socket, frame_oid));
+            }
+        }
+    }
+
+    fn on_outputs_configured(&mut self, id: u32, result: Result<(), String>) {
+        if let Some(configuration_oid) = self.output_configuration_oids.remove(&id) {
+            if result.is_ok() {
+                send!(zwlr_output_configuration_v1::succeeded(&self.socket, configuration_oid));
+            } else {
+                send!(zwlr_output_configuration_v1::failed(&self.socket, configuration_oid));
+            }
+        }
+    }
+
+    fn on_frame_presented(&mut self, output_id: i32, sids: Vec<SurfaceId>, time: PresentationTime) {
+        for sid in sids {
+            let feedback_oids = match self.sid_to_surface_info_dictionary.get_mut(&sid) {
+                Some(info) => info.drawn_feedback_oids.drain(..).collect(),
+                None => continue,
+            };
+            self.present_feedbacks(feedback_oids, output_id, time);
+        }
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -699,3 +2489,44 @@ impl Drop for Proxy {
 }
 
 // -------------------------------------------------------------------------------------------------
+
+/// Translates scale to numerator of fraction with denominator 120 used by
+/// `wp_fractional_scale_v1`.
+fn to_fractional(scale: f32) -> u32 {
+    (scale * 120.0).round() as u32
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Translates transform to value of Wayland `wl_output.transform` enumeration.
+pub fn translate_transform(transform: Transform) -> u32 {
+    match transform {
+        Transform::Normal => 0,
+        Transform::Rotated90 => 1,
+        Transform::Rotated180 => 2,
+        Transform::Rotated270 => 3,
+        Transform::Flipped => 4,
+        Transform::Flipped90 => 5,
+        Transform::Flipped180 => 6,
+        Transform::Flipped270 => 7,
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Translates transform from value of Wayland `wl_output.transform` enumeration.
+pub fn translate_wl_transform(transform: i32) -> Option<Transform> {
+    match transform as u32 {
+        wl_output::transform::NORMAL => Some(Transform::Normal),
+        wl_output::transform::_90 => Some(Transform::Rotated90),
+        wl_output::transform::_180 => Some(Transform::Rotated180),
+        wl_output::transform::_270 => Some(Transform::Rotated270),
+        wl_output::transform::FLIPPED => Some(Transform::Flipped),
+        wl_output::transform::FLIPPED_90 => Some(Transform::Flipped90),
+        wl_output::transform::FLIPPED_180 => Some(Transform::Flipped180),
+        wl_output::transform::FLIPPED_270 => Some(Transform::Flipped270),
+        _ => None,
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,75 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains security policy deciding which clients may access privileged globals.
+
+// -------------------------------------------------------------------------------------------------
+
+use std::fs;
+use std::path::PathBuf;
+
+use nix::sys::socket;
+
+use skylane as wl;
+
+use qualia::SecurityConfig;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Decides which clients are allowed to bind privileged globals.
+///
+/// Clients are identified by name of the executable of the process on the other side of the
+/// socket. Clients which can not be identified are treated as unprivileged.
+pub struct SecurityPolicy {
+    config: SecurityConfig,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl SecurityPolicy {
+    /// Creates new `SecurityPolicy`.
+    pub fn new(config: SecurityConfig) -> Self {
+        SecurityPolicy { config: config }
+    }
+
+    /// Checks if client connected via given socket is privileged.
+    pub fn is_privileged(&self, socket: &wl::server::ClientSocket) -> bool {
+        if let Some(name) = Self::get_executable_name(socket) {
+            let privileged = self.config.privileged_clients.contains(&name);
+            log_wayl3!("Client '{}' is {}privileged", name, if privileged { "" } else { "not " });
+            privileged
+        } else {
+            false
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Private methods.
+impl SecurityPolicy {
+    /// Returns name of executable of process connected via given socket.
+    fn get_executable_name(socket: &wl::server::ClientSocket) -> Option<String> {
+        let fd = socket.get_fd();
+        let credentials = match socket::getsockopt(fd, socket::sockopt::PeerCredentials) {
+            Ok(credentials) => credentials,
+            Err(err) => {
+                log_warn2!("Failed to get client credentials: {:?}", err);
+                return None;
+            }
+        };
+
+        let mut path = PathBuf::from("/proc");
+        path.push(credentials.pid.to_string());
+        path.push("exe");
+        match fs::read_link(&path) {
+            Ok(exe) => exe.file_name().and_then(|name| name.to_str()).map(|name| name.to_owned()),
+            Err(err) => {
+                log_warn2!("Failed to read executable of client: {:?}", err);
+                None
+            }
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,71 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module gathers knowledge about versions of Wayland objects.
+//!
+//! Globals are advertised in highest version of their interface implemented by `wayland_frontend`.
+//! Client binds global in version not higher than advertised one and objects created from it
+//! inherit its version. Events introduced in later versions of the interface must not be sent to
+//! objects of lower version. `Proxy` remembers version of every object it sends versioned events
+//! to and constants below tell since which version the events are available.
+
+// -------------------------------------------------------------------------------------------------
+
+/// Version of `wl_seat` since which `name` event is sent.
+pub const WL_SEAT_NAME_SINCE: u32 = 2;
+
+/// Version of `wl_pointer` since which `frame`, `axis_source`, `axis_stop` and `axis_discrete`
+/// events are sent.
+pub const WL_POINTER_FRAME_SINCE: u32 = 5;
+
+/// Version of `wl_keyboard` since which key repeat information is sent.
+pub const WL_KEYBOARD_REPEAT_INFO_SINCE: u32 = 4;
+
+/// Version of `wl_output` since which `scale` and `done` events are sent.
+pub const WL_OUTPUT_DONE_SINCE: u32 = 2;
+
+/// Version of `zxdg_output_v1` since which `name` and `description` events are sent.
+pub const ZXDG_OUTPUT_NAME_SINCE: u32 = 2;
+
+/// Version of `zxdg_output_v1` since which `done` event is replaced by `wl_output.done`.
+pub const ZXDG_OUTPUT_WL_OUTPUT_DONE_SINCE: u32 = 3;
+
+/// Version of `wl_surface` since which preferred buffer scale and transform are sent.
+pub const WL_SURFACE_PREFERRED_BUFFER_SINCE: u32 = 6;
+
+/// Version of `wl_data_device` since which drag-and-drop actions are negotiated.
+pub const WL_DATA_DEVICE_ACTIONS_SINCE: u32 = 3;
+
+/// Version of `wl_drm` since which PRIME buffers are supported.
+pub const WL_DRM_PRIME_SINCE: u32 = 2;
+
+/// Version of `zwp_linux_dmabuf_v1` since which `modifier` events are sent.
+pub const ZWP_LINUX_DMABUF_MODIFIER_SINCE: u32 = 3;
+
+/// Version of `zwlr_screencopy_frame_v1` since which `damage` events are sent.
+pub const ZWLR_SCREENCOPY_FRAME_DAMAGE_SINCE: u32 = 2;
+
+/// Version of `zwlr_screencopy_frame_v1` since which `buffer_done` event is sent.
+pub const ZWLR_SCREENCOPY_FRAME_BUFFER_DONE_SINCE: u32 = 3;
+
+/// Version of `zwlr_output_head_v1` since which `make` and `model` events are sent.
+pub const ZWLR_OUTPUT_HEAD_MAKE_SINCE: u32 = 2;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Checks if bind of global advertised in version `advertised` in version `requested` is valid.
+/// Returns description of the problem if it is not.
+pub fn check_bind(interface: &str, advertised: u32, requested: u32) -> Result<(), String> {
+    if requested == 0 {
+        Err(format!("Invalid version for global '{}': 0 is not valid version.", interface))
+    } else if advertised < requested {
+        Err(format!("Invalid version for global '{}': server has: {}, client wanted: {}.",
+                    interface,
+                    advertised,
+                    requested))
+    } else {
+        Ok(())
+    }
+}
+
+// -------------------------------------------------------------------------------------------------